@@ -380,6 +380,7 @@ impl BallistaContext {
                 ref delimiter,
                 ref table_partition_cols,
                 ref if_not_exists,
+                ..
             }) => {
                 let table_exists = ctx.table_exist(name.as_str())?;
 
@@ -589,6 +590,7 @@ mod tests {
                         table_partition_cols: x.table_partition_cols.clone(),
                         collect_stat: x.collect_stat,
                         target_partitions: x.target_partitions,
+                        file_sort_order: x.file_sort_order.clone(),
                     };
 
                     let config = ListingTableConfig::new(
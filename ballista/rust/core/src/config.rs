@@ -34,6 +34,8 @@ pub const BALLISTA_REPARTITION_AGGREGATIONS: &str = "ballista.repartition.aggreg
 pub const BALLISTA_REPARTITION_WINDOWS: &str = "ballista.repartition.windows";
 pub const BALLISTA_PARQUET_PRUNING: &str = "ballista.parquet.pruning";
 pub const BALLISTA_WITH_INFORMATION_SCHEMA: &str = "ballista.with_information_schema";
+/// the 4 comma-separated seeds of the hashes of the shuffles, e.g. "1,2,3,4"
+pub const BALLISTA_HASH_SEEDS: &str = "ballista.hash.seeds";
 /// give a plugin files dir, and then the dynamic library files in this dir will be load when scheduler state init.
 pub const BALLISTA_PLUGIN_DIR: &str = "ballista.plugin_dir";
 
@@ -125,10 +127,22 @@ impl BallistaConfig {
                 )));
             }
         }
+        if let Some(v) = settings.get(BALLISTA_HASH_SEEDS) {
+            let _ = Self::parse_hash_seeds(v).map_err(|e| BallistaError::General(format!("Failed to parse user-supplied value '{}' for configuration setting '{}': {}", BALLISTA_HASH_SEEDS, v, e)))?;
+        }
 
         Ok(Self { settings })
     }
 
+    fn parse_hash_seeds(val: &str) -> ParseResult<[u64; 4]> {
+        let seeds = val
+            .split(',')
+            .map(|seed| seed.trim().parse::<u64>().map_err(|e| format!("{:?}", e)))
+            .collect::<ParseResult<Vec<_>>>()?;
+        <[u64; 4]>::try_from(seeds)
+            .map_err(|seeds| format!("expected 4 seeds, got {}", seeds.len()))
+    }
+
     pub fn parse_value(val: &str, data_type: DataType) -> ParseResult<()> {
         match data_type {
             DataType::UInt16 => {
@@ -176,6 +190,9 @@ impl BallistaConfig {
             ConfigEntry::new(BALLISTA_WITH_INFORMATION_SCHEMA.to_string(),
                 "Sets whether enable information_schema".to_string(),
                 DataType::Boolean,Some("false".to_string())),
+            ConfigEntry::new(BALLISTA_HASH_SEEDS.to_string(),
+                             "Sets the seeds of the hashes of the shuffles".to_string(),
+                             DataType::Utf8,Some("0,0,0,0".to_string())),
             ConfigEntry::new(BALLISTA_PLUGIN_DIR.to_string(),
                              "Sets the plugin dir".to_string(),
                              DataType::Utf8,Some("".to_string())),
@@ -222,6 +239,11 @@ impl BallistaConfig {
         self.get_bool_setting(BALLISTA_WITH_INFORMATION_SCHEMA)
     }
 
+    pub fn hash_seeds(&self) -> [u64; 4] {
+        // infallible because we validate all configs in the constructor
+        Self::parse_hash_seeds(&self.get_string_setting(BALLISTA_HASH_SEEDS)).unwrap()
+    }
+
     fn get_usize_setting(&self, key: &str) -> usize {
         if let Some(v) = self.settings.get(key) {
             // infallible because we validate all configs in the constructor
@@ -290,6 +312,7 @@ mod tests {
         assert_eq!(2, config.default_shuffle_partitions());
         assert!(!config.default_with_information_schema());
         assert_eq!("", config.default_plugin_dir().as_str());
+        assert_eq!([0; 4], config.hash_seeds());
         Ok(())
     }
 
@@ -298,9 +321,11 @@ mod tests {
         let config = BallistaConfig::builder()
             .set(BALLISTA_DEFAULT_SHUFFLE_PARTITIONS, "123")
             .set(BALLISTA_WITH_INFORMATION_SCHEMA, "true")
+            .set(BALLISTA_HASH_SEEDS, "1,2,3,4")
             .build()?;
         assert_eq!(123, config.default_shuffle_partitions());
         assert!(config.default_with_information_schema());
+        assert_eq!([1, 2, 3, 4], config.hash_seeds());
         Ok(())
     }
 
@@ -318,6 +343,11 @@ mod tests {
             .build();
         assert!(config.is_err());
         assert_eq!("General(\"Failed to parse user-supplied value 'ballista.with_information_schema' for configuration setting '123': ParseBoolError\")", format!("{:?}", config.unwrap_err()));
+
+        let config = BallistaConfig::builder()
+            .set(BALLISTA_HASH_SEEDS, "1,2,3")
+            .build();
+        assert!(config.is_err());
         Ok(())
     }
 }
@@ -144,6 +144,7 @@ impl ShuffleWriterExec {
     ) -> Result<Vec<ShuffleWritePartition>> {
         let now = Instant::now();
 
+        let random_state = context.session_config().hash_random_state();
         let mut stream = self.plan.execute(input_partition, context).await?;
 
         let mut path = PathBuf::from(&self.work_dir);
@@ -207,7 +208,8 @@ impl ShuffleWriterExec {
                 let mut partitioner = BatchPartitioner::try_new(
                     Partitioning::Hash(exprs.clone(), *n),
                     write_metrics.repart_time.clone(),
-                )?;
+                )?
+                .with_random_state(random_state);
 
                 while let Some(result) = stream.next().await {
                     let input_batch = result?;
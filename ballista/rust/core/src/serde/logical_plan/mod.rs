@@ -32,9 +32,9 @@ use datafusion::logical_plan::plan::{
     Aggregate, EmptyRelation, Filter, Join, Projection, Sort, SubqueryAlias, Window,
 };
 use datafusion::logical_plan::{
-    source_as_provider, Column, CreateCatalog, CreateCatalogSchema, CreateExternalTable,
-    CrossJoin, Expr, JoinConstraint, Limit, LogicalPlan, LogicalPlanBuilder, Repartition,
-    TableScan, Values,
+    source_as_provider, Column, Constraint, Constraints, CreateCatalog,
+    CreateCatalogSchema, CreateExternalTable, CrossJoin, Expr, JoinConstraint, Limit,
+    LogicalPlan, LogicalPlanBuilder, Repartition, TableScan, Values,
 };
 use datafusion::prelude::SessionContext;
 
@@ -219,12 +219,19 @@ impl AsLogicalPlan for LogicalPlanNode {
                         FileFormatType::Avro(..) => Arc::new(AvroFormat::default()),
                     };
 
+                let file_sort_order = scan
+                    .file_sort_order
+                    .iter()
+                    .map(|expr| parse_expr(expr, ctx))
+                    .collect::<Result<Vec<Expr>, _>>()?;
+
                 let options = ListingOptions {
                     file_extension: scan.file_extension.clone(),
                     format: file_format,
                     table_partition_cols: scan.table_partition_cols.clone(),
                     collect_stat: scan.collect_stat,
                     target_partitions: scan.target_partitions as usize,
+                    file_sort_order,
                 };
 
                 let object_store = ctx
@@ -319,8 +326,23 @@ impl AsLogicalPlan for LogicalPlanNode {
                 let pb_file_type: protobuf::FileType =
                     create_extern_table.file_type.try_into()?;
 
+                let constraints = create_extern_table
+                    .constraints
+                    .iter()
+                    .map(|constraint| {
+                        let indices =
+                            constraint.indices.iter().map(|i| *i as usize).collect();
+                        if constraint.primary_key {
+                            Constraint::PrimaryKey(indices)
+                        } else {
+                            Constraint::Unique(indices)
+                        }
+                    })
+                    .collect();
+
                 Ok(LogicalPlan::CreateExternalTable(CreateExternalTable {
                     schema: pb_schema.try_into()?,
+                    constraints: Constraints::new(constraints),
                     name: create_extern_table.name.clone(),
                     location: create_extern_table.location.clone(),
                     file_type: pb_file_type.into(),
@@ -331,6 +353,11 @@ impl AsLogicalPlan for LogicalPlanNode {
                     table_partition_cols: create_extern_table
                         .table_partition_cols
                         .clone(),
+                    order_exprs: create_extern_table
+                        .order_exprs
+                        .iter()
+                        .map(|expr| parse_expr(expr, ctx))
+                        .collect::<Result<Vec<Expr>, _>>()?,
                     if_not_exists: create_extern_table.if_not_exists,
                 }))
             }
@@ -535,6 +562,14 @@ impl AsLogicalPlan for LogicalPlanNode {
 
                 if let Some(listing_table) = source.downcast_ref::<ListingTable>() {
                     let any = listing_table.options().format.as_any();
+                    let file_sort_order: Vec<
+                        datafusion_proto::protobuf::LogicalExprNode,
+                    > = listing_table
+                        .options()
+                        .file_sort_order
+                        .iter()
+                        .map(|expr| expr.try_into())
+                        .collect::<Result<Vec<_>, _>>()?;
                     let file_format_type = if let Some(parquet) =
                         any.downcast_ref::<ParquetFormat>()
                     {
@@ -576,6 +611,7 @@ impl AsLogicalPlan for LogicalPlanNode {
                                     .options()
                                     .target_partitions
                                     as u32,
+                                file_sort_order,
                             },
                         )),
                     })
@@ -816,11 +852,19 @@ impl AsLogicalPlan for LogicalPlanNode {
                 has_header,
                 delimiter,
                 schema: df_schema,
+                constraints,
                 table_partition_cols,
+                order_exprs,
                 if_not_exists,
             }) => {
                 use datafusion::logical_plan::FileType;
 
+                let order_exprs: Vec<datafusion_proto::protobuf::LogicalExprNode> =
+                    order_exprs
+                        .iter()
+                        .map(|expr| expr.try_into())
+                        .collect::<Result<Vec<_>, _>>()?;
+
                 let pb_file_type: protobuf::FileType = match file_type {
                     FileType::NdJson => protobuf::FileType::NdJson,
                     FileType::Parquet => protobuf::FileType::Parquet,
@@ -839,6 +883,21 @@ impl AsLogicalPlan for LogicalPlanNode {
                             table_partition_cols: table_partition_cols.clone(),
                             if_not_exists: *if_not_exists,
                             delimiter: String::from(*delimiter),
+                            constraints: constraints
+                                .iter()
+                                .map(|constraint| protobuf::ConstraintNode {
+                                    primary_key: matches!(
+                                        constraint,
+                                        Constraint::PrimaryKey(_)
+                                    ),
+                                    indices: constraint
+                                        .columns()
+                                        .iter()
+                                        .map(|i| *i as u64)
+                                        .collect(),
+                                })
+                                .collect(),
+                            order_exprs,
                         },
                     )),
                 })
@@ -960,6 +1019,21 @@ impl AsLogicalPlan for LogicalPlanNode {
             LogicalPlan::DropTable(_) => Err(proto_error(
                 "Error converting DropTable. Not yet supported in Ballista",
             )),
+            LogicalPlan::SetVariable(_) => Err(proto_error(
+                "Error converting SetVariable. Not yet supported in Ballista",
+            )),
+            LogicalPlan::Transaction(_) => Err(proto_error(
+                "Error converting Transaction. Not yet supported in Ballista",
+            )),
+            LogicalPlan::OptimizeTable(_) => Err(proto_error(
+                "Error converting OptimizeTable. Not yet supported in Ballista",
+            )),
+            LogicalPlan::Delete(_) => Err(proto_error(
+                "Error converting Delete. Not yet supported in Ballista",
+            )),
+            LogicalPlan::Update(_) => Err(proto_error(
+                "Error converting Update. Not yet supported in Ballista",
+            )),
         }
     }
 }
@@ -996,8 +1070,8 @@ mod roundtrip_tests {
         },
         datasource::listing::ListingTable,
         logical_plan::{
-            col, CreateExternalTable, Expr, FileType, LogicalPlan, LogicalPlanBuilder,
-            Repartition, ToDFSchema,
+            col, Constraint, Constraints, CreateExternalTable, Expr, FileType,
+            LogicalPlan, LogicalPlanBuilder, Repartition, ToDFSchema,
         },
         prelude::*,
     };
@@ -1182,12 +1256,14 @@ mod roundtrip_tests {
             let create_table_node =
                 LogicalPlan::CreateExternalTable(CreateExternalTable {
                     schema: df_schema_ref.clone(),
+                    constraints: Constraints::new(vec![Constraint::PrimaryKey(vec![0])]),
                     name: String::from("TestName"),
                     location: String::from("employee.csv"),
                     file_type: *file,
                     has_header: true,
                     delimiter: ',',
                     table_partition_cols: vec![],
+                    order_exprs: vec![col("id").sort(true, false)],
                     if_not_exists: false,
                 });
 
@@ -34,7 +34,7 @@ use datafusion::datasource::listing::{FileRange, PartitionedFile};
 use datafusion::execution::context::ExecutionProps;
 use datafusion::logical_plan::FunctionRegistry;
 
-use datafusion::physical_plan::file_format::FileScanConfig;
+use datafusion::physical_plan::file_format::{FileScanConfig, VirtualColumn};
 
 use datafusion::logical_expr::window_function::WindowFunction;
 
@@ -390,7 +390,34 @@ impl TryInto<FileScanConfig> for &protobuf::FileScanExecConf {
             statistics,
             projection,
             limit: self.limit.as_ref().map(|sl| sl.limit as usize),
-            table_partition_cols: vec![],
+            table_partition_cols: parse_table_partition_cols(self)?,
+            output_ordering: None,
         })
     }
 }
+
+/// Returns the partitioning columns of `conf`, of the default type of the
+/// partitioning columns if their types are missing
+pub(crate) fn parse_table_partition_cols(
+    conf: &protobuf::FileScanExecConf,
+) -> Result<Vec<VirtualColumn>, BallistaError> {
+    if conf.table_partition_col_types.is_empty() {
+        return Ok(conf
+            .table_partition_cols
+            .iter()
+            .map(VirtualColumn::partition)
+            .collect());
+    }
+    if conf.table_partition_col_types.len() != conf.table_partition_cols.len() {
+        return Err(proto_error(format!(
+            "Expected the types of {} partitioning columns, got {}",
+            conf.table_partition_cols.len(),
+            conf.table_partition_col_types.len()
+        )));
+    }
+    conf.table_partition_cols
+        .iter()
+        .zip(&conf.table_partition_col_types)
+        .map(|(name, data_type)| Ok(VirtualColumn::new(name, data_type.try_into()?)))
+        .collect()
+}
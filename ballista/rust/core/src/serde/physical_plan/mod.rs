@@ -57,7 +57,7 @@ use crate::execution_plans::{
     ShuffleReaderExec, ShuffleWriterExec, UnresolvedShuffleExec,
 };
 use crate::serde::physical_plan::from_proto::{
-    parse_physical_expr, parse_protobuf_hash_partitioning,
+    parse_physical_expr, parse_protobuf_hash_partitioning, parse_table_partition_cols,
 };
 use crate::serde::protobuf::physical_expr_node::ExprType;
 use crate::serde::protobuf::physical_plan_node::PhysicalPlanType;
@@ -515,7 +515,12 @@ impl AsExecutionPlan for PhysicalPlanNode {
             }
             PhysicalPlanType::Empty(empty) => {
                 let schema = Arc::new(convert_required!(empty.schema)?);
-                Ok(Arc::new(EmptyExec::new(empty.produce_one_row, schema)))
+                // plans serialized without the number of partitions have one
+                let partitions = (empty.partitions as usize).max(1);
+                Ok(Arc::new(
+                    EmptyExec::new(empty.produce_one_row, schema)
+                        .with_partitions(partitions),
+                ))
             }
             PhysicalPlanType::Sort(sort) => {
                 let input: Arc<dyn ExecutionPlan> =
@@ -787,6 +792,7 @@ impl AsExecutionPlan for PhysicalPlanNode {
                     protobuf::EmptyExecNode {
                         produce_one_row: empty.produce_one_row(),
                         schema: Some(schema),
+                        partitions: empty.partitions() as u32,
                     },
                 )),
             })
@@ -1040,7 +1046,8 @@ fn decode_scan_config(
         statistics,
         projection,
         limit: proto.limit.as_ref().map(|sl| sl.limit as usize),
-        table_partition_cols: vec![],
+        table_partition_cols: parse_table_partition_cols(proto)?,
+        output_ordering: None,
     })
 }
 
@@ -1083,7 +1090,7 @@ mod roundtrip_tests {
             empty::EmptyExec,
             expressions::{binary, col, lit, InListExpr, NotExpr},
             expressions::{Avg, Column, PhysicalSortExpr},
-            file_format::{FileScanConfig, ParquetExec},
+            file_format::{FileScanConfig, ParquetExec, VirtualColumn},
             filter::FilterExec,
             hash_aggregate::{AggregateMode, HashAggregateExec},
             hash_join::{HashJoinExec, PartitionMode},
@@ -1156,7 +1163,10 @@ mod roundtrip_tests {
 
     #[test]
     fn roundtrip_empty() -> Result<()> {
-        roundtrip_test(Arc::new(EmptyExec::new(false, Arc::new(Schema::empty()))))
+        roundtrip_test(Arc::new(EmptyExec::new(false, Arc::new(Schema::empty()))))?;
+        roundtrip_test(Arc::new(
+            EmptyExec::new(false, Arc::new(Schema::empty())).with_partitions(4),
+        ))
     }
 
     #[test]
@@ -1321,12 +1331,39 @@ mod roundtrip_tests {
             projection: None,
             limit: None,
             table_partition_cols: vec![],
+            output_ordering: None,
         };
 
         let predicate = datafusion::prelude::col("col").eq(datafusion::prelude::lit("1"));
         roundtrip_test(Arc::new(ParquetExec::new(scan_config, Some(predicate))))
     }
 
+    #[test]
+    fn roundtrip_parquet_exec_with_partition_columns() -> Result<()> {
+        let scan_config = FileScanConfig {
+            object_store: Arc::new(LocalFileSystem {}),
+            file_schema: Arc::new(Schema::new(vec![Field::new(
+                "col",
+                DataType::Utf8,
+                false,
+            )])),
+            file_groups: vec![vec![PartitionedFile::new(
+                "/path/to/year=2022/month=1/file.parquet".to_string(),
+                1024,
+            )]],
+            statistics: Statistics::default(),
+            projection: None,
+            limit: None,
+            table_partition_cols: vec![
+                VirtualColumn::new("year", DataType::Int32),
+                VirtualColumn::partition("month"),
+            ],
+            output_ordering: None,
+        };
+
+        roundtrip_test(Arc::new(ParquetExec::new(scan_config, None)))
+    }
+
     #[test]
     fn roundtrip_builtin_scalar_function() -> Result<()> {
         let field_a = Field::new("a", DataType::Int64, false);
@@ -136,6 +136,36 @@ impl TryInto<protobuf::PhysicalExprNode> for Arc<dyn AggregateExpr> {
             .is_some()
         {
             Ok(AggregateFunction::ApproxMedian.into())
+        } else if self
+            .as_any()
+            .downcast_ref::<expressions::HllSketch>()
+            .is_some()
+        {
+            Ok(AggregateFunction::HllSketch.into())
+        } else if self
+            .as_any()
+            .downcast_ref::<expressions::HllUnion>()
+            .is_some()
+        {
+            Ok(AggregateFunction::HllUnion.into())
+        } else if self
+            .as_any()
+            .downcast_ref::<expressions::HllCount>()
+            .is_some()
+        {
+            Ok(AggregateFunction::HllCount.into())
+        } else if self
+            .as_any()
+            .downcast_ref::<expressions::ApproxTopK>()
+            .is_some()
+        {
+            Ok(AggregateFunction::ApproxTopK.into())
+        } else if self
+            .as_any()
+            .downcast_ref::<expressions::Histogram>()
+            .is_some()
+        {
+            Ok(AggregateFunction::Histogram.into())
         } else {
             Err(BallistaError::NotImplemented(format!(
                 "Aggregate function not supported: {:?}",
@@ -440,7 +470,16 @@ impl TryFrom<&FileScanConfig> for protobuf::FileScanExecConf {
                 .map(|n| *n as u32)
                 .collect(),
             schema: Some(conf.file_schema.as_ref().into()),
-            table_partition_cols: conf.table_partition_cols.to_vec(),
+            table_partition_cols: conf
+                .table_partition_cols
+                .iter()
+                .map(|column| column.name().to_owned())
+                .collect(),
+            table_partition_col_types: conf
+                .table_partition_cols
+                .iter()
+                .map(|column| column.data_type().into())
+                .collect(),
         })
     }
 }
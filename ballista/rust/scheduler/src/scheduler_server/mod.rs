@@ -206,7 +206,8 @@ pub fn create_datafusion_context(
         .with_repartition_joins(config.repartition_joins())
         .with_repartition_aggregations(config.repartition_aggregations())
         .with_repartition_windows(config.repartition_windows())
-        .with_parquet_pruning(config.parquet_pruning());
+        .with_parquet_pruning(config.parquet_pruning())
+        .with_hash_seeds(config.hash_seeds());
     let session_state = session_builder(config);
     Arc::new(SessionContext::with_state(session_state))
 }
@@ -224,6 +225,10 @@ pub fn update_datafusion_context(
         mut_state.config.repartition_aggregations = config.repartition_aggregations();
         mut_state.config.repartition_windows = config.repartition_windows();
         mut_state.config.parquet_pruning = config.parquet_pruning();
+        mut_state.config = mut_state
+            .config
+            .clone()
+            .with_hash_seeds(config.hash_seeds());
     }
     session_ctx
 }
@@ -746,6 +746,7 @@ fn get_table(
         target_partitions,
         collect_stat: true,
         table_partition_cols: vec![],
+        file_sort_order: vec![],
     };
 
     let config = ListingTableConfig::new(Arc::new(LocalFileSystem {}), path)
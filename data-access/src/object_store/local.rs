@@ -31,7 +31,7 @@ use super::{
     FileMetaStream, ListEntryStream, ObjectReader, ObjectReaderStream, ObjectStore,
 };
 
-pub static LOCAL_SCHEME: &str = "file";
+pub use super::LOCAL_SCHEME;
 
 #[derive(Debug)]
 /// Local File System as Object Store.
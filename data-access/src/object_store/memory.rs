@@ -0,0 +1,268 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Object store whose files are in-memory byte buffers, e.g. the data piped
+//! to the standard input or the files of tests.
+
+use std::collections::BTreeMap;
+use std::io::{self, Read};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::{io::AllowStdIo, stream, AsyncRead};
+use parking_lot::RwLock;
+
+use crate::{FileMeta, ListEntry, Result, SizedFile};
+
+use super::{FileMetaStream, ListEntryStream, ObjectReader, ObjectStore};
+
+pub static MEMORY_SCHEME: &str = "memory";
+
+/// Object store holding its files in memory, by path. The paths are
+/// relative to the store, e.g. `data/a.csv` for `memory://data/a.csv`, and
+/// the directories are the prefixes of the paths ending with a `/`.
+#[derive(Debug, Default)]
+pub struct InMemoryObjectStore {
+    files: RwLock<BTreeMap<String, Arc<Vec<u8>>>>,
+}
+
+impl InMemoryObjectStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the file `path` with the content `data`, replacing the file of
+    /// the same path if any
+    pub fn put(&self, path: impl Into<String>, data: impl Into<Vec<u8>>) {
+        self.files
+            .write()
+            .insert(strip_scheme(&path.into()).to_owned(), Arc::new(data.into()));
+    }
+
+    /// Adds the file `path` with the content read from `reader` until its
+    /// end, e.g. the standard input
+    pub fn put_reader(
+        &self,
+        path: impl Into<String>,
+        mut reader: impl Read,
+    ) -> Result<()> {
+        let mut data = vec![];
+        reader.read_to_end(&mut data)?;
+        self.put(path, data);
+        Ok(())
+    }
+
+    /// Removes the file `path`, returning whether it existed
+    pub fn remove(&self, path: &str) -> bool {
+        self.files.write().remove(strip_scheme(path)).is_some()
+    }
+
+    /// Returns the files in `prefix`, the file `prefix` itself or the files
+    /// of the directory `prefix`
+    fn files_in(&self, prefix: &str) -> Vec<FileMeta> {
+        let prefix = strip_scheme(prefix);
+        self.files
+            .read()
+            .iter()
+            .filter(|(path, _)| is_in(path, prefix))
+            .map(|(path, data)| file_meta(path, data))
+            .collect()
+    }
+}
+
+/// Returns `uri` without its scheme
+fn strip_scheme(uri: &str) -> &str {
+    match uri.split_once("://") {
+        Some((_scheme, path)) => path,
+        None => uri,
+    }
+}
+
+/// Whether the file `path` is the file `prefix` or in the directory `prefix`
+fn is_in(path: &str, prefix: &str) -> bool {
+    prefix.is_empty()
+        || path == prefix
+        || (path.starts_with(prefix)
+            && (prefix.ends_with('/') || path[prefix.len()..].starts_with('/')))
+}
+
+fn file_meta(path: &str, data: &Arc<Vec<u8>>) -> FileMeta {
+    FileMeta {
+        sized_file: SizedFile {
+            path: path.to_owned(),
+            size: data.len() as u64,
+        },
+        last_modified: None,
+    }
+}
+
+#[async_trait]
+impl ObjectStore for InMemoryObjectStore {
+    async fn list_file(&self, prefix: &str) -> Result<FileMetaStream> {
+        let files = self.files_in(prefix);
+        Ok(Box::pin(stream::iter(files.into_iter().map(Ok))))
+    }
+
+    async fn list_dir(
+        &self,
+        prefix: &str,
+        delimiter: Option<String>,
+    ) -> Result<ListEntryStream> {
+        let prefix = strip_scheme(prefix);
+        let mut entries = vec![];
+        for file in self.files_in(prefix) {
+            let rest = &file.path()[prefix.len()..];
+            let rest_start = rest.len() - rest.trim_start_matches('/').len();
+            // the files below a delimiter are listed as the prefix before it
+            match delimiter
+                .as_deref()
+                .and_then(|delimiter| rest[rest_start..].find(delimiter))
+            {
+                Some(end) => {
+                    let dir = file.path()[..prefix.len() + rest_start + end].to_owned();
+                    if !entries
+                        .iter()
+                        .any(|entry| matches!(entry, ListEntry::Prefix(p) if *p == dir))
+                    {
+                        entries.push(ListEntry::Prefix(dir));
+                    }
+                }
+                None => entries.push(ListEntry::FileMeta(file)),
+            }
+        }
+        Ok(Box::pin(stream::iter(entries.into_iter().map(Ok))))
+    }
+
+    fn file_reader(&self, file: SizedFile) -> Result<Arc<dyn ObjectReader>> {
+        let data = self
+            .files
+            .read()
+            .get(strip_scheme(&file.path))
+            .cloned()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("No file {} in the memory object store", file.path),
+                )
+            })?;
+        Ok(Arc::new(InMemoryFileReader { data }))
+    }
+}
+
+/// Reader of a file of an [`InMemoryObjectStore`]
+struct InMemoryFileReader {
+    data: Arc<Vec<u8>>,
+}
+
+#[async_trait]
+impl ObjectReader for InMemoryFileReader {
+    async fn chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> Result<Box<dyn AsyncRead>> {
+        let reader = self.sync_chunk_reader(start, length)?;
+        Ok(Box::new(AllowStdIo::new(reader)))
+    }
+
+    fn sync_chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> Result<Box<dyn Read + Send + Sync>> {
+        // the chunk is read from the shared buffer, without copying it
+        let start = (start as usize).min(self.data.len());
+        let end = start.saturating_add(length).min(self.data.len());
+        Ok(Box::new(ChunkReader {
+            data: self.data.clone(),
+            position: start,
+            end,
+        }))
+    }
+
+    fn length(&self) -> u64 {
+        self.data.len() as u64
+    }
+}
+
+/// Reader of the bytes `position..end` of a buffer
+struct ChunkReader {
+    data: Arc<Vec<u8>>,
+    position: usize,
+    end: usize,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.end - self.position);
+        buf[..len].copy_from_slice(&self.data[self.position..self.position + len]);
+        self.position += len;
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn list_and_read_files() -> Result<()> {
+        let store = InMemoryObjectStore::new();
+        store.put("data/a.csv", "a,b\n1,2\n");
+        store.put("memory://data/x/b.csv", b"a,b\n3,4\n".to_vec());
+        store.put_reader("database.csv", &b"c\n5\n"[..])?;
+
+        let mut files = store.list_file("memory://data").await?;
+        let mut paths = vec![];
+        while let Some(file) = files.next().await {
+            paths.push(file?.path().to_owned());
+        }
+        assert_eq!(paths, vec!["data/a.csv", "data/x/b.csv"]);
+
+        let mut entries = store.list_dir("data", Some("/".to_owned())).await?;
+        let mut listed = vec![];
+        while let Some(entry) = entries.next().await {
+            listed.push(match entry? {
+                ListEntry::FileMeta(file) => file.path().to_owned(),
+                ListEntry::Prefix(prefix) => format!("{}/", prefix),
+            });
+        }
+        assert_eq!(listed, vec!["data/a.csv", "data/x/"]);
+
+        let reader = store.file_reader(SizedFile {
+            path: "data/x/b.csv".to_owned(),
+            size: 8,
+        })?;
+        assert_eq!(reader.length(), 8);
+        let mut chunk = String::new();
+        reader
+            .sync_chunk_reader(4, 100)?
+            .read_to_string(&mut chunk)?;
+        assert_eq!(chunk, "3,4\n");
+
+        assert!(store.remove("memory://data/a.csv"));
+        assert!(store
+            .file_reader(SizedFile {
+                path: "data/a.csv".to_owned(),
+                size: 8,
+            })
+            .is_err());
+        Ok(())
+    }
+}
@@ -17,7 +17,9 @@
 
 //! Object Store abstracts access to an underlying file/object storage.
 
+#[cfg(not(target_arch = "wasm32"))]
 pub mod local;
+pub mod memory;
 
 use std::fmt::Debug;
 use std::io::Read;
@@ -29,6 +31,10 @@ use futures::{AsyncRead, Stream, StreamExt};
 
 use crate::{FileMeta, ListEntry, Result, SizedFile};
 
+/// Scheme of the local file system, served by [`local::LocalFileSystem`]
+/// where it is available
+pub static LOCAL_SCHEME: &str = "file";
+
 /// Stream of files listed from object store
 pub type FileMetaStream =
     Pin<Box<dyn Stream<Item = Result<FileMeta>> + Send + Sync + 'static>>;
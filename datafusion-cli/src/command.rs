@@ -41,6 +41,7 @@ pub enum Command {
     SearchFunctions(String),
     QuietMode(Option<bool>),
     OutputFormat(Option<String>),
+    CopyFromStdin(String, Option<String>),
 }
 
 pub enum OutputFormat {
@@ -104,6 +105,15 @@ impl Command {
             Self::OutputFormat(_) => Err(DataFusionError::Execution(
                 "Unexpected change output format, this should be handled outside".into(),
             )),
+            Self::CopyFromStdin(name, format) => {
+                let format = format.as_deref().unwrap_or("csv");
+                ctx.register_reader(name, format, std::io::stdin().lock())
+                    .await?;
+                if !print_options.quiet {
+                    println!("Table {} copied from stdin", name);
+                }
+                Ok(())
+            }
         }
     }
 
@@ -119,11 +129,15 @@ impl Command {
             Self::OutputFormat(_) => {
                 ("\\pset [NAME [VALUE]]", "set table output option\n(format)")
             }
+            Self::CopyFromStdin(_, _) => (
+                "\\copy name from stdin [csv|json|parquet]",
+                "register table name with the data\nread from stdin until its end",
+            ),
         }
     }
 }
 
-const ALL_COMMANDS: [Command; 8] = [
+const ALL_COMMANDS: [Command; 9] = [
     Command::ListTables,
     Command::DescribeTable(String::new()),
     Command::Quit,
@@ -132,6 +146,7 @@ const ALL_COMMANDS: [Command; 8] = [
     Command::SearchFunctions(String::new()),
     Command::QuietMode(None),
     Command::OutputFormat(None),
+    Command::CopyFromStdin(String::new(), None),
 ];
 
 fn all_commands_info() -> RecordBatch {
@@ -180,6 +195,14 @@ impl FromStr for Command {
                 Self::OutputFormat(Some(subcommand.to_string()))
             }
             ("pset", None) => Self::OutputFormat(None),
+            ("copy", Some(args)) => match args.split_whitespace().collect::<Vec<_>>()[..]
+            {
+                [name, "from", "stdin"] => Self::CopyFromStdin(name.into(), None),
+                [name, "from", "stdin", format] => {
+                    Self::CopyFromStdin(name.into(), Some(format.to_lowercase()))
+                }
+                _ => return Err(()),
+            },
             _ => return Err(()),
         })
     }
@@ -18,14 +18,20 @@
 //! Context (remote or local)
 
 use datafusion::dataframe::DataFrame;
+use datafusion::datafusion_data_access::object_store::memory::{
+    InMemoryObjectStore, MEMORY_SCHEME,
+};
 use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::{SessionConfig, SessionContext};
+use datafusion::prelude::{CsvReadOptions, NdJsonReadOptions, ParquetReadOptions};
+use std::io::Read;
 use std::sync::Arc;
 
 /// The CLI supports using a local DataFusion context or a distributed BallistaContext
 pub enum Context {
-    /// In-process execution with DataFusion
-    Local(SessionContext),
+    /// In-process execution with DataFusion, with the store of the data
+    /// copied from stdin
+    Local(SessionContext, Arc<InMemoryObjectStore>),
     /// Distributed execution with Ballista (if available)
     Remote(BallistaContext),
 }
@@ -38,16 +44,62 @@ impl Context {
 
     /// create a local context using the given config
     pub fn new_local(config: &SessionConfig) -> Context {
-        Context::Local(SessionContext::with_config(config.clone()))
+        let ctx = SessionContext::with_config(config.clone());
+        let memory_store = Arc::new(InMemoryObjectStore::new());
+        ctx.runtime_env()
+            .register_object_store(MEMORY_SCHEME, memory_store.clone());
+        Context::Local(ctx, memory_store)
     }
 
     /// execute an SQL statement against the context
     pub async fn sql(&mut self, sql: &str) -> Result<Arc<DataFrame>> {
         match self {
-            Context::Local(datafusion) => datafusion.sql(sql).await,
+            Context::Local(datafusion, _) => datafusion.sql(sql).await,
             Context::Remote(ballista) => ballista.sql(sql).await,
         }
     }
+
+    /// register the table `name` with the data of `reader` until its end, e.g.
+    /// stdin, in the given format: csv, json or parquet
+    pub async fn register_reader(
+        &mut self,
+        name: &str,
+        format: &str,
+        reader: impl Read,
+    ) -> Result<()> {
+        match self {
+            Context::Local(datafusion, memory_store) => {
+                if !matches!(format, "csv" | "json" | "parquet") {
+                    return Err(DataFusionError::Plan(format!(
+                        "Unsupported format {}, expected csv, json or parquet",
+                        format
+                    )));
+                }
+                let uri = format!("{}://stdin/{}.{}", MEMORY_SCHEME, name, format);
+                memory_store.put_reader(&uri, reader)?;
+                match format {
+                    "csv" => {
+                        datafusion
+                            .register_csv(name, &uri, CsvReadOptions::new())
+                            .await
+                    }
+                    "json" => {
+                        datafusion
+                            .register_json(name, &uri, NdJsonReadOptions::default())
+                            .await
+                    }
+                    _ => {
+                        datafusion
+                            .register_parquet(name, &uri, ParquetReadOptions::default())
+                            .await
+                    }
+                }
+            }
+            Context::Remote(_) => Err(DataFusionError::NotImplemented(
+                "Copying from stdin is not supported with remote execution".to_string(),
+            )),
+        }
+    }
 }
 
 // implement wrappers around the BallistaContext to support running without ballista
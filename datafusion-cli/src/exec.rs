@@ -44,6 +44,21 @@ pub async fn exec_from_lines(
             Ok(line) if line.starts_with("--") => {
                 continue;
             }
+            // commands, e.g. `\copy t from stdin` to load the data piped to stdin
+            Ok(line) if query.is_empty() && line.starts_with('\\') => {
+                let command = line.split_whitespace().collect::<Vec<_>>().join(" ");
+                match command[1..].parse::<Command>() {
+                    Ok(Command::Quit) => break,
+                    Ok(cmd) => {
+                        if let Err(err) =
+                            cmd.execute(ctx, &mut print_options.clone()).await
+                        {
+                            println!("{:?}", err)
+                        }
+                    }
+                    Err(_) => println!("'\\{}' is not a valid command", &command[1..]),
+                }
+            }
             Ok(line) => {
                 let line = line.trim_end();
                 query.push_str(line);
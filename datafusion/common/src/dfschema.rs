@@ -23,7 +23,7 @@ use std::convert::TryFrom;
 use std::sync::Arc;
 
 use crate::error::{DataFusionError, Result};
-use crate::Column;
+use crate::{Column, FunctionalDependencies};
 
 use arrow::compute::can_cast_types;
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
@@ -33,20 +33,33 @@ use std::fmt::{Display, Formatter};
 pub type DFSchemaRef = Arc<DFSchema>;
 
 /// DFSchema wraps an Arrow schema and adds relation names
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct DFSchema {
     /// Fields
     fields: Vec<DFField>,
     /// Additional metadata in form of key value pairs
     metadata: HashMap<String, String>,
+    /// Known functional dependencies between the fields
+    functional_dependencies: FunctionalDependencies,
+}
+
+// the functional dependencies are derived knowledge about the rows, which the
+// rewrites of the plans may lose, so they do not take part in the equality
+impl PartialEq for DFSchema {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields == other.fields && self.metadata == other.metadata
+    }
 }
 
+impl Eq for DFSchema {}
+
 impl DFSchema {
     /// Creates an empty `DFSchema`
     pub fn empty() -> Self {
         Self {
             fields: vec![],
             metadata: HashMap::new(),
+            functional_dependencies: FunctionalDependencies::empty(),
         }
     }
 
@@ -81,48 +94,86 @@ impl DFSchema {
         }
 
         // check for mix of qualified and unqualified field with same unqualified name
-        // note that we need to sort the contents of the HashSet first so that errors are
-        // deterministic
-        let mut qualified_names = qualified_names
-            .iter()
-            .map(|(l, r)| (l.to_owned(), r.to_owned()))
+        // note that we need to sort the ambiguous names of the HashSet first so that
+        // errors are deterministic
+        let mut ambiguous_names = qualified_names
+            .into_iter()
+            .filter(|(_, name)| unqualified_names.contains(name))
             .collect::<Vec<(&String, &String)>>();
-        qualified_names.sort_by(|a, b| {
-            let a = format!("{}.{}", a.0, a.1);
-            let b = format!("{}.{}", b.0, b.1);
-            a.cmp(&b)
-        });
-        for (qualifier, name) in &qualified_names {
-            if unqualified_names.contains(name) {
-                return Err(DataFusionError::Plan(format!(
-                    "Schema contains qualified field name '{}.{}' \
-                    and unqualified field name '{}' which would be ambiguous",
-                    qualifier, name, name
-                )));
-            }
+        ambiguous_names
+            .sort_by_cached_key(|(qualifier, name)| format!("{}.{}", qualifier, name));
+        if let Some((qualifier, name)) = ambiguous_names.first() {
+            return Err(DataFusionError::Plan(format!(
+                "Schema contains qualified field name '{}.{}' \
+                and unqualified field name '{}' which would be ambiguous",
+                qualifier, name, name
+            )));
         }
-        Ok(Self { fields, metadata })
+        Ok(Self {
+            fields,
+            metadata,
+            functional_dependencies: FunctionalDependencies::empty(),
+        })
     }
 
     /// Create a `DFSchema` from an Arrow schema
     pub fn try_from_qualified_schema(qualifier: &str, schema: &Schema) -> Result<Self> {
+        let qualifier = Arc::new(qualifier.to_owned());
         Self::new_with_metadata(
             schema
                 .fields()
                 .iter()
-                .map(|f| DFField::from_qualified(qualifier, f.clone()))
+                .map(|f| DFField::from_shared_qualifier(&qualifier, f.clone()))
                 .collect(),
             schema.metadata().clone(),
         )
     }
 
+    /// Create a `DFSchema` with the fields of `self` qualified by `qualifier`,
+    /// like [`DFSchema::try_from_qualified_schema`] with the Arrow schema of
+    /// `self`, without converting it
+    pub fn try_with_qualifier(&self, qualifier: &str) -> Result<Self> {
+        let qualifier = Arc::new(qualifier.to_owned());
+        Self::new_with_metadata(
+            self.fields
+                .iter()
+                .map(|f| DFField::from_shared_qualifier(&qualifier, f.field.clone()))
+                .collect(),
+            self.metadata.clone(),
+        )
+    }
+
     /// Combine two schemas
     pub fn join(&self, schema: &DFSchema) -> Result<Self> {
         let mut fields = self.fields.clone();
         let mut metadata = self.metadata.clone();
         fields.extend_from_slice(schema.fields().as_slice());
         metadata.extend(schema.metadata.clone());
-        Self::new_with_metadata(fields, metadata)
+        // each row of a side can be combined with several rows of the other
+        let mut functional_dependencies =
+            self.functional_dependencies.without_uniqueness();
+        functional_dependencies.extend(
+            schema
+                .functional_dependencies
+                .with_offset(self.fields.len())
+                .without_uniqueness(),
+        );
+        Ok(Self::new_with_metadata(fields, metadata)?
+            .with_functional_dependencies(functional_dependencies))
+    }
+
+    /// Sets the known functional dependencies between the fields
+    pub fn with_functional_dependencies(
+        mut self,
+        functional_dependencies: FunctionalDependencies,
+    ) -> Self {
+        self.functional_dependencies = functional_dependencies;
+        self
+    }
+
+    /// Returns the known functional dependencies between the fields
+    pub fn functional_dependencies(&self) -> &FunctionalDependencies {
+        &self.functional_dependencies
     }
 
     /// Merge a schema into self
@@ -179,7 +230,7 @@ impl DFSchema {
                 // field to lookup is qualified.
                 // current field is qualified and not shared between relations, compare both
                 // qualifier and name.
-                (Some(q), Some(field_q)) => q == field_q && field.name() == name,
+                (Some(q), Some(field_q)) => q == field_q.as_str() && field.name() == name,
                 // field to lookup is qualified but current field is unqualified.
                 (Some(_), None) => false,
                 // field to lookup is unqualified, no need to compare qualifier
@@ -241,15 +292,15 @@ impl DFSchema {
 
     /// Find the field with the given name
     pub fn field_with_unqualified_name(&self, name: &str) -> Result<&DFField> {
-        let matches = self.fields_with_unqualified_name(name);
-        match matches.len() {
-            0 => Err(DataFusionError::Plan(format!(
+        let mut matches = self.fields.iter().filter(|field| field.name() == name);
+        match (matches.next(), matches.next()) {
+            (None, _) => Err(DataFusionError::Plan(format!(
                 "No field with unqualified name '{}'. Valid fields are {}.",
                 name,
                 self.get_field_names()
             ))),
-            1 => Ok(matches[0]),
-            _ => Err(DataFusionError::Plan(format!(
+            (Some(field), None) => Ok(field),
+            (Some(_), Some(_)) => Err(DataFusionError::Plan(format!(
                 "Ambiguous reference to field named '{}'",
                 name
             ))),
@@ -320,11 +371,12 @@ impl DFSchema {
 
     /// Replace all field qualifier with new value in schema
     pub fn replace_qualifier(self, qualifier: &str) -> Self {
+        let qualifier = Arc::new(qualifier.to_owned());
         DFSchema {
             fields: self
                 .fields
                 .into_iter()
-                .map(|f| DFField::from_qualified(qualifier, f.field))
+                .map(|f| DFField::from_shared_qualifier(&qualifier, f.field))
                 .collect(),
             ..self
         }
@@ -496,8 +548,10 @@ impl ExprSchema for DFSchema {
 /// DFField wraps an Arrow field and adds an optional qualifier
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DFField {
-    /// Optional qualifier (usually a table or relation name)
-    qualifier: Option<String>,
+    /// Optional qualifier (usually a table or relation name), shared by the
+    /// fields of the same relation so that cloning the fields of wide schemas
+    /// does not copy it
+    qualifier: Option<Arc<String>>,
     /// Arrow field definition
     field: Field,
 }
@@ -511,7 +565,7 @@ impl DFField {
         nullable: bool,
     ) -> Self {
         DFField {
-            qualifier: qualifier.map(|s| s.to_owned()),
+            qualifier: qualifier.map(|s| Arc::new(s.to_owned())),
             field: Field::new(name, data_type, nullable),
         }
     }
@@ -526,8 +580,14 @@ impl DFField {
 
     /// Create a qualified field from an existing Arrow field
     pub fn from_qualified(qualifier: &str, field: Field) -> Self {
+        Self::from_shared_qualifier(&Arc::new(qualifier.to_owned()), field)
+    }
+
+    /// Create a qualified field from an existing Arrow field, sharing the
+    /// qualifier with the other fields of the relation
+    fn from_shared_qualifier(qualifier: &Arc<String>, field: Field) -> Self {
         Self {
-            qualifier: Some(qualifier.to_owned()),
+            qualifier: Some(qualifier.clone()),
             field,
         }
     }
@@ -559,7 +619,7 @@ impl DFField {
     /// Builds a qualified column based on self
     pub fn qualified_column(&self) -> Column {
         Column {
-            relation: self.qualifier.clone(),
+            relation: self.qualifier().cloned(),
             name: self.field.name().to_string(),
         }
     }
@@ -574,7 +634,7 @@ impl DFField {
 
     /// Get the optional qualifier
     pub fn qualifier(&self) -> Option<&String> {
-        self.qualifier.as_ref()
+        self.qualifier.as_deref()
     }
 
     /// Get the arrow field
@@ -624,6 +684,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn from_qualified_schema_shares_qualifier() -> Result<()> {
+        let schema = DFSchema::try_from_qualified_schema("t1", &test_schema_1())?;
+        let qualifiers = schema
+            .fields()
+            .iter()
+            .map(|f| f.qualifier.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert!(Arc::ptr_eq(&qualifiers[0], &qualifiers[1]));
+        assert_eq!(
+            schema.field(0).qualified_column(),
+            Column::from_qualified_name("t1.c0")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn with_qualifier() -> Result<()> {
+        let schema = DFSchema::try_from_qualified_schema("t1", &test_schema_1())?;
+        let schema = schema.try_with_qualifier("t2")?;
+        assert_eq!("fields:[t2.c0, t2.c1], metadata:{}", schema.to_string());
+        assert_eq!(
+            schema,
+            DFSchema::try_from_qualified_schema("t2", &test_schema_1())?
+        );
+
+        // the fields of different relations may have the same names
+        let left = DFSchema::try_from_qualified_schema("t1", &test_schema_1())?;
+        let right = DFSchema::try_from_qualified_schema("t2", &test_schema_1())?;
+        let err = left.join(&right)?.try_with_qualifier("t3").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Schema contains duplicate qualified field name 't3.c0'"));
+        Ok(())
+    }
+
     #[test]
     fn from_qualified_schema_into_arrow_schema() -> Result<()> {
         let schema = DFSchema::try_from_qualified_schema("t1", &test_schema_1())?;
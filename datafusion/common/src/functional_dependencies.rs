@@ -0,0 +1,359 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Functional dependencies between the columns of a [`DFSchema`](crate::DFSchema)
+//!
+//! A functional dependency states that the values of some source columns
+//! determine the values of some target columns: two rows with the same source
+//! values have the same target values. They are declared by the table
+//! providers (their primary key) and derived through the operators of the
+//! logical plans, e.g. the grouping columns of an aggregation determine all its
+//! output columns.
+
+use crate::{DataFusionError, Result};
+use arrow::datatypes::Schema;
+
+/// A constraint declared on the columns of a table, given by their index in
+/// the schema of the table. The constraints are not enforced by DataFusion:
+/// the data of the table is trusted to satisfy them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constraint {
+    /// The columns identify the rows, and are not null
+    PrimaryKey(Vec<usize>),
+    /// The non-null values of the columns identify the rows
+    Unique(Vec<usize>),
+}
+
+impl Constraint {
+    /// Returns the indices of the constrained columns
+    pub fn columns(&self) -> &[usize] {
+        match self {
+            Self::PrimaryKey(columns) | Self::Unique(columns) => columns,
+        }
+    }
+}
+
+/// The constraints declared on the columns of a table
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Constraints {
+    constraints: Vec<Constraint>,
+}
+
+impl Constraints {
+    /// No constraint
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Creates the given constraints
+    pub fn new(constraints: Vec<Constraint>) -> Self {
+        Self { constraints }
+    }
+
+    /// Returns the constraints
+    pub fn iter(&self) -> impl Iterator<Item = &Constraint> {
+        self.constraints.iter()
+    }
+
+    /// Whether there is no constraint
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// Checks that the constrained columns are columns of the `schema`
+    pub fn validate(&self, schema: &Schema) -> Result<()> {
+        let num_fields = schema.fields().len();
+        match self
+            .iter()
+            .flat_map(|constraint| constraint.columns())
+            .find(|column| **column >= num_fields)
+        {
+            Some(column) => Err(DataFusionError::Plan(format!(
+                "Constraint column {} is out of bounds of the {} columns of the table",
+                column, num_fields
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the columns of the primary key, if any
+    pub fn primary_key(&self) -> Option<&[usize]> {
+        self.constraints
+            .iter()
+            .find_map(|constraint| match constraint {
+                Constraint::PrimaryKey(columns) => Some(columns.as_slice()),
+                Constraint::Unique(_) => None,
+            })
+    }
+}
+
+/// The values of the `source` columns determine the values of the `target`
+/// columns, both given by their index in the schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionalDependence {
+    /// Indices of the determining columns
+    pub source: Vec<usize>,
+    /// Indices of the determined columns
+    pub target: Vec<usize>,
+    /// Whether at most one row has each value of the source columns, e.g.
+    /// for a primary key
+    pub unique: bool,
+}
+
+impl FunctionalDependence {
+    /// Creates a dependency of the `target` columns on the `source` columns
+    pub fn new(source: Vec<usize>, target: Vec<usize>, unique: bool) -> Self {
+        Self {
+            source,
+            target,
+            unique,
+        }
+    }
+}
+
+/// The known functional dependencies between the columns of a schema
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionalDependencies {
+    dependencies: Vec<FunctionalDependence>,
+}
+
+impl FunctionalDependencies {
+    /// No known dependency
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Creates the given dependencies
+    pub fn new(dependencies: Vec<FunctionalDependence>) -> Self {
+        Self { dependencies }
+    }
+
+    /// The dependencies of the `num_fields` columns of a relation whose
+    /// `key` columns identify the rows, e.g. a primary key
+    pub fn new_from_key(key: Vec<usize>, num_fields: usize) -> Self {
+        Self::new(vec![FunctionalDependence::new(
+            key,
+            (0..num_fields).collect(),
+            true,
+        )])
+    }
+
+    /// The dependencies of the columns of a table with the given
+    /// `constraints`. Several rows can have NULL values in the columns of a
+    /// unique constraint, so that these columns only identify the rows when
+    /// they are not nullable in the `schema`.
+    pub fn new_from_constraints(constraints: &Constraints, schema: &Schema) -> Self {
+        let num_fields = schema.fields().len();
+        let dependencies = constraints
+            .iter()
+            .filter(|constraint| match constraint {
+                Constraint::PrimaryKey(_) => true,
+                Constraint::Unique(columns) => columns
+                    .iter()
+                    .all(|column| !schema.field(*column).is_nullable()),
+            })
+            .map(|constraint| {
+                FunctionalDependence::new(
+                    constraint.columns().to_vec(),
+                    (0..num_fields).collect(),
+                    true,
+                )
+            })
+            .collect();
+        Self::new(dependencies)
+    }
+
+    /// Returns the dependencies
+    pub fn iter(&self) -> impl Iterator<Item = &FunctionalDependence> {
+        self.dependencies.iter()
+    }
+
+    /// Whether no dependency is known
+    pub fn is_empty(&self) -> bool {
+        self.dependencies.is_empty()
+    }
+
+    /// Adds the dependencies of `other`
+    pub fn extend(&mut self, other: FunctionalDependencies) {
+        self.dependencies.extend(other.dependencies)
+    }
+
+    /// Returns the dependencies between the columns of a relation whose
+    /// column `i` is the column `indices[i]` of this one, if any. The
+    /// dependencies whose source columns are not all kept are dropped.
+    pub fn project(&self, indices: &[Option<usize>]) -> Self {
+        let dependencies = self
+            .dependencies
+            .iter()
+            .filter_map(|dependence| {
+                let source = dependence
+                    .source
+                    .iter()
+                    .map(|source| indices.iter().position(|i| *i == Some(*source)))
+                    .collect::<Option<Vec<_>>>()?;
+                let target = indices
+                    .iter()
+                    .enumerate()
+                    .filter(
+                        |(_, i)| matches!(i, Some(i) if dependence.target.contains(i)),
+                    )
+                    .map(|(position, _)| position)
+                    .collect();
+                Some(FunctionalDependence::new(source, target, dependence.unique))
+            })
+            .collect();
+        Self::new(dependencies)
+    }
+
+    /// Returns the dependencies with all the indices shifted by `offset`,
+    /// e.g. for the columns of the right side of a join
+    pub fn with_offset(&self, offset: usize) -> Self {
+        let shift = |indices: &[usize]| -> Vec<usize> {
+            indices.iter().map(|i| i + offset).collect()
+        };
+        Self::new(
+            self.dependencies
+                .iter()
+                .map(|dependence| {
+                    FunctionalDependence::new(
+                        shift(&dependence.source),
+                        shift(&dependence.target),
+                        dependence.unique,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the dependencies, without their uniqueness, e.g. for the
+    /// columns of a join whose rows can match several rows
+    pub fn without_uniqueness(&self) -> Self {
+        Self::new(
+            self.dependencies
+                .iter()
+                .map(|dependence| FunctionalDependence {
+                    unique: false,
+                    ..dependence.clone()
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the sorted indices of all the columns determined by the
+    /// `columns`, including themselves
+    pub fn determined_by(&self, columns: &[usize]) -> Vec<usize> {
+        let mut determined = columns.to_vec();
+        loop {
+            let mut changed = false;
+            for dependence in &self.dependencies {
+                if dependence.source.iter().all(|c| determined.contains(c)) {
+                    for target in &dependence.target {
+                        if !determined.contains(target) {
+                            determined.push(*target);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        determined.sort_unstable();
+        determined
+    }
+
+    /// Whether at most one row has each value of the `columns`
+    pub fn is_unique(&self, columns: &[usize]) -> bool {
+        self.dependencies.iter().any(|dependence| {
+            dependence.unique && dependence.source.iter().all(|c| columns.contains(c))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::{DataType, Field};
+
+    #[test]
+    fn determined_by() {
+        // 0 is the key of 0..3, 2 determines 3
+        let mut dependencies = FunctionalDependencies::new_from_key(vec![0], 3);
+        dependencies.extend(FunctionalDependencies::new(vec![
+            FunctionalDependence::new(vec![2], vec![3], false),
+        ]));
+        assert_eq!(dependencies.determined_by(&[0]), vec![0, 1, 2, 3]);
+        assert_eq!(dependencies.determined_by(&[2]), vec![2, 3]);
+        assert_eq!(dependencies.determined_by(&[1]), vec![1]);
+        assert!(dependencies.is_unique(&[0, 1]));
+        assert!(!dependencies.is_unique(&[2]));
+    }
+
+    #[test]
+    fn project() {
+        let dependencies = FunctionalDependencies::new_from_key(vec![1], 3);
+        // keeps the key
+        let projected = dependencies.project(&[Some(2), None, Some(1)]);
+        assert_eq!(
+            projected,
+            FunctionalDependencies::new(vec![FunctionalDependence::new(
+                vec![2],
+                vec![0, 2],
+                true
+            )])
+        );
+        // drops the key
+        assert!(dependencies.project(&[Some(0), Some(2)]).is_empty());
+
+        let shifted = dependencies.with_offset(2).without_uniqueness();
+        assert_eq!(
+            shifted,
+            FunctionalDependencies::new(vec![FunctionalDependence::new(
+                vec![3],
+                vec![2, 3, 4],
+                false
+            )])
+        );
+    }
+
+    #[test]
+    fn from_constraints() {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, true),
+            Field::new("c", DataType::Int32, false),
+        ]);
+        let constraints = Constraints::new(vec![
+            Constraint::PrimaryKey(vec![0]),
+            Constraint::Unique(vec![1]),
+            Constraint::Unique(vec![2]),
+        ]);
+        assert_eq!(constraints.primary_key(), Some([0].as_slice()));
+
+        let dependencies =
+            FunctionalDependencies::new_from_constraints(&constraints, &schema);
+        assert!(dependencies.is_unique(&[0]));
+        // the nullable column does not identify the rows
+        assert!(!dependencies.is_unique(&[1]));
+        assert!(dependencies.is_unique(&[2]));
+
+        assert!(constraints.validate(&schema).is_ok());
+        let out_of_bounds = Constraints::new(vec![Constraint::Unique(vec![3])]);
+        assert!(out_of_bounds.validate(&schema).is_err());
+    }
+}
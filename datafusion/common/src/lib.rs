@@ -18,6 +18,7 @@
 mod column;
 mod dfschema;
 mod error;
+mod functional_dependencies;
 #[cfg(feature = "pyarrow")]
 mod pyarrow;
 mod scalar;
@@ -25,4 +26,7 @@ mod scalar;
 pub use column::Column;
 pub use dfschema::{DFField, DFSchema, DFSchemaRef, ExprSchema, ToDFSchema};
 pub use error::{DataFusionError, Result};
+pub use functional_dependencies::{
+    Constraint, Constraints, FunctionalDependence, FunctionalDependencies,
+};
 pub use scalar::{ScalarType, ScalarValue};
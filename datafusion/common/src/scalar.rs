@@ -92,6 +92,14 @@ pub enum ScalarValue {
     IntervalDayTime(Option<i64>),
     /// Interval with MonthDayNano unit
     IntervalMonthDayNano(Option<i128>),
+    /// Duration in seconds
+    DurationSecond(Option<i64>),
+    /// Duration in milliseconds
+    DurationMillisecond(Option<i64>),
+    /// Duration in microseconds
+    DurationMicrosecond(Option<i64>),
+    /// Duration in nanoseconds
+    DurationNanosecond(Option<i64>),
     /// struct of nested ScalarValue (boxed to reduce size_of(ScalarValue))
     #[allow(clippy::box_collection)]
     Struct(Option<Box<Vec<ScalarValue>>>, Box<Vec<Field>>),
@@ -168,6 +176,14 @@ impl PartialEq for ScalarValue {
             (IntervalDayTime(_), _) => false,
             (IntervalMonthDayNano(v1), IntervalMonthDayNano(v2)) => v1.eq(v2),
             (IntervalMonthDayNano(_), _) => false,
+            (DurationSecond(v1), DurationSecond(v2)) => v1.eq(v2),
+            (DurationSecond(_), _) => false,
+            (DurationMillisecond(v1), DurationMillisecond(v2)) => v1.eq(v2),
+            (DurationMillisecond(_), _) => false,
+            (DurationMicrosecond(v1), DurationMicrosecond(v2)) => v1.eq(v2),
+            (DurationMicrosecond(_), _) => false,
+            (DurationNanosecond(v1), DurationNanosecond(v2)) => v1.eq(v2),
+            (DurationNanosecond(_), _) => false,
             (Struct(v1, t1), Struct(v2, t2)) => v1.eq(v2) && t1.eq(t2),
             (Struct(_, _), _) => false,
         }
@@ -262,6 +278,14 @@ impl PartialOrd for ScalarValue {
             (IntervalDayTime(_), _) => None,
             (IntervalMonthDayNano(v1), IntervalMonthDayNano(v2)) => v1.partial_cmp(v2),
             (IntervalMonthDayNano(_), _) => None,
+            (DurationSecond(v1), DurationSecond(v2)) => v1.partial_cmp(v2),
+            (DurationSecond(_), _) => None,
+            (DurationMillisecond(v1), DurationMillisecond(v2)) => v1.partial_cmp(v2),
+            (DurationMillisecond(_), _) => None,
+            (DurationMicrosecond(v1), DurationMicrosecond(v2)) => v1.partial_cmp(v2),
+            (DurationMicrosecond(_), _) => None,
+            (DurationNanosecond(v1), DurationNanosecond(v2)) => v1.partial_cmp(v2),
+            (DurationNanosecond(_), _) => None,
             (Struct(v1, t1), Struct(v2, t2)) => {
                 if t1.eq(t2) {
                     v1.partial_cmp(v2)
@@ -321,6 +345,10 @@ impl std::hash::Hash for ScalarValue {
             IntervalYearMonth(v) => v.hash(state),
             IntervalDayTime(v) => v.hash(state),
             IntervalMonthDayNano(v) => v.hash(state),
+            DurationSecond(v) => v.hash(state),
+            DurationMillisecond(v) => v.hash(state),
+            DurationMicrosecond(v) => v.hash(state),
+            DurationNanosecond(v) => v.hash(state),
             Struct(v, t) => {
                 v.hash(state);
                 t.hash(state);
@@ -593,6 +621,16 @@ impl ScalarValue {
             ScalarValue::IntervalMonthDayNano(_) => {
                 DataType::Interval(IntervalUnit::MonthDayNano)
             }
+            ScalarValue::DurationSecond(_) => DataType::Duration(TimeUnit::Second),
+            ScalarValue::DurationMillisecond(_) => {
+                DataType::Duration(TimeUnit::Millisecond)
+            }
+            ScalarValue::DurationMicrosecond(_) => {
+                DataType::Duration(TimeUnit::Microsecond)
+            }
+            ScalarValue::DurationNanosecond(_) => {
+                DataType::Duration(TimeUnit::Nanosecond)
+            }
             ScalarValue::Struct(_, fields) => DataType::Struct(fields.as_ref().clone()),
         }
     }
@@ -615,10 +653,58 @@ impl ScalarValue {
             ScalarValue::Decimal128(Some(v), precision, scale) => {
                 ScalarValue::Decimal128(Some(-v), *precision, *scale)
             }
+            ScalarValue::DurationSecond(v) => ScalarValue::DurationSecond(v.map(|v| -v)),
+            ScalarValue::DurationMillisecond(v) => {
+                ScalarValue::DurationMillisecond(v.map(|v| -v))
+            }
+            ScalarValue::DurationMicrosecond(v) => {
+                ScalarValue::DurationMicrosecond(v.map(|v| -v))
+            }
+            ScalarValue::DurationNanosecond(v) => {
+                ScalarValue::DurationNanosecond(v.map(|v| -v))
+            }
             _ => panic!("Cannot run arithmetic negate on scalar value: {:?}", self),
         }
     }
 
+    /// Returns the number of bytes allocated by this value, including its
+    /// heap allocated content (e.g. the bytes of a string or the elements of
+    /// a list)
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + match self {
+                ScalarValue::Utf8(Some(v)) | ScalarValue::LargeUtf8(Some(v)) => {
+                    v.capacity()
+                }
+                ScalarValue::Binary(Some(v)) | ScalarValue::LargeBinary(Some(v)) => {
+                    v.capacity()
+                }
+                ScalarValue::TimestampSecond(_, Some(tz))
+                | ScalarValue::TimestampMillisecond(_, Some(tz))
+                | ScalarValue::TimestampMicrosecond(_, Some(tz))
+                | ScalarValue::TimestampNanosecond(_, Some(tz)) => tz.capacity(),
+                ScalarValue::List(values, data_type) => {
+                    std::mem::size_of_val(data_type.as_ref())
+                        + values
+                            .as_ref()
+                            .map_or(0, |values| Self::size_of_slice(values))
+                }
+                ScalarValue::Struct(values, fields) => {
+                    fields.capacity() * std::mem::size_of::<Field>()
+                        + values
+                            .as_ref()
+                            .map_or(0, |values| Self::size_of_slice(values))
+                }
+                _ => 0,
+            }
+    }
+
+    /// Returns the number of bytes allocated by `values`, including their
+    /// heap allocated content
+    pub fn size_of_slice(values: &[ScalarValue]) -> usize {
+        values.iter().map(|value| value.size()).sum()
+    }
+
     /// whether this value is null or not.
     pub fn is_null(&self) -> bool {
         matches!(
@@ -643,6 +729,10 @@ impl ScalarValue {
                 | ScalarValue::TimestampMillisecond(None, _)
                 | ScalarValue::TimestampMicrosecond(None, _)
                 | ScalarValue::TimestampNanosecond(None, _)
+                | ScalarValue::DurationSecond(None)
+                | ScalarValue::DurationMillisecond(None)
+                | ScalarValue::DurationMicrosecond(None)
+                | ScalarValue::DurationNanosecond(None)
                 | ScalarValue::Struct(None, _)
                 | ScalarValue::Decimal128(None, _, _) // For decimal type, the value is null means ScalarValue::Decimal128 is null.
         )
@@ -879,6 +969,18 @@ impl ScalarValue {
             DataType::Interval(IntervalUnit::YearMonth) => {
                 build_array_primitive!(IntervalYearMonthArray, IntervalYearMonth)
             }
+            DataType::Duration(TimeUnit::Second) => {
+                build_array_primitive!(DurationSecondArray, DurationSecond)
+            }
+            DataType::Duration(TimeUnit::Millisecond) => {
+                build_array_primitive!(DurationMillisecondArray, DurationMillisecond)
+            }
+            DataType::Duration(TimeUnit::Microsecond) => {
+                build_array_primitive!(DurationMicrosecondArray, DurationMicrosecond)
+            }
+            DataType::Duration(TimeUnit::Nanosecond) => {
+                build_array_primitive!(DurationNanosecondArray, DurationNanosecond)
+            }
             DataType::List(fields) if fields.data_type() == &DataType::Int8 => {
                 build_array_list_primitive!(Int8Type, Int8, i8)
             }
@@ -1001,12 +1103,31 @@ impl ScalarValue {
             return Err(DataFusionError::ArrowError(err));
         }
 
+        let element_type = match data_type {
+            DataType::List(field) => field.data_type(),
+            other => {
+                return Err(DataFusionError::Internal(format!(
+                    "Expected List data type. Received {:?}",
+                    other
+                )))
+            }
+        };
+
         let mut elements: Vec<ArrayRef> = Vec::new();
         let mut valid = BooleanBufferBuilder::new(0);
         let mut flat_len = 0i32;
         for scalar in scalars {
             if let ScalarValue::List(values, _) = scalar {
                 match values {
+                    Some(values) if values.is_empty() => {
+                        // Repeat previous offset index
+                        if let Err(err) = offsets.append_value(flat_len) {
+                            return Err(DataFusionError::ArrowError(err));
+                        }
+
+                        // Element is valid
+                        valid.append(true);
+                    }
                     Some(values) => {
                         let element_array = ScalarValue::iter_to_array(*values)?;
 
@@ -1042,9 +1163,13 @@ impl ScalarValue {
         // Concatenate element arrays to create single flat array
         let element_arrays: Vec<&dyn Array> =
             elements.iter().map(|a| a.as_ref()).collect();
-        let flat_array = match arrow::compute::concat(&element_arrays) {
-            Ok(flat_array) => flat_array,
-            Err(err) => return Err(DataFusionError::ArrowError(err)),
+        let flat_array = if element_arrays.is_empty() {
+            new_empty_array(element_type)
+        } else {
+            match arrow::compute::concat(&element_arrays) {
+                Ok(flat_array) => flat_array,
+                Err(err) => return Err(DataFusionError::ArrowError(err)),
+            }
         };
 
         // Build ListArray using ArrayData so we can specify a flat inner array, and offset indices
@@ -1224,6 +1349,34 @@ impl ScalarValue {
                 e,
                 size
             ),
+            ScalarValue::DurationSecond(e) => build_array_from_option!(
+                Duration,
+                TimeUnit::Second,
+                DurationSecondArray,
+                e,
+                size
+            ),
+            ScalarValue::DurationMillisecond(e) => build_array_from_option!(
+                Duration,
+                TimeUnit::Millisecond,
+                DurationMillisecondArray,
+                e,
+                size
+            ),
+            ScalarValue::DurationMicrosecond(e) => build_array_from_option!(
+                Duration,
+                TimeUnit::Microsecond,
+                DurationMicrosecondArray,
+                e,
+                size
+            ),
+            ScalarValue::DurationNanosecond(e) => build_array_from_option!(
+                Duration,
+                TimeUnit::Nanosecond,
+                DurationNanosecondArray,
+                e,
+                size
+            ),
             ScalarValue::Struct(values, fields) => match values {
                 Some(values) => {
                     let field_values: Vec<_> = fields
@@ -1357,6 +1510,18 @@ impl ScalarValue {
                     tz_opt
                 )
             }
+            DataType::Duration(TimeUnit::Second) => {
+                typed_cast!(array, index, DurationSecondArray, DurationSecond)
+            }
+            DataType::Duration(TimeUnit::Millisecond) => {
+                typed_cast!(array, index, DurationMillisecondArray, DurationMillisecond)
+            }
+            DataType::Duration(TimeUnit::Microsecond) => {
+                typed_cast!(array, index, DurationMicrosecondArray, DurationMicrosecond)
+            }
+            DataType::Duration(TimeUnit::Nanosecond) => {
+                typed_cast!(array, index, DurationNanosecondArray, DurationNanosecond)
+            }
             DataType::Dictionary(index_type, _) => {
                 let (values, values_index) = match **index_type {
                     DataType::Int8 => get_dict_value::<Int8Type>(array, index)?,
@@ -1512,6 +1677,18 @@ impl ScalarValue {
             ScalarValue::IntervalMonthDayNano(val) => {
                 eq_array_primitive!(array, index, IntervalMonthDayNanoArray, val)
             }
+            ScalarValue::DurationSecond(val) => {
+                eq_array_primitive!(array, index, DurationSecondArray, val)
+            }
+            ScalarValue::DurationMillisecond(val) => {
+                eq_array_primitive!(array, index, DurationMillisecondArray, val)
+            }
+            ScalarValue::DurationMicrosecond(val) => {
+                eq_array_primitive!(array, index, DurationMicrosecondArray, val)
+            }
+            ScalarValue::DurationNanosecond(val) => {
+                eq_array_primitive!(array, index, DurationNanosecondArray, val)
+            }
             ScalarValue::Struct(_, _) => unimplemented!(),
         }
     }
@@ -1644,7 +1821,7 @@ impl TryFrom<ScalarValue> for i32 {
     }
 }
 
-// special implementation for i64 because of TimeNanosecond
+// special implementation for i64 because of TimeNanosecond and Duration
 impl TryFrom<ScalarValue> for i64 {
     type Error = DataFusionError;
 
@@ -1655,7 +1832,11 @@ impl TryFrom<ScalarValue> for i64 {
             | ScalarValue::TimestampNanosecond(Some(inner_value), _)
             | ScalarValue::TimestampMicrosecond(Some(inner_value), _)
             | ScalarValue::TimestampMillisecond(Some(inner_value), _)
-            | ScalarValue::TimestampSecond(Some(inner_value), _) => Ok(inner_value),
+            | ScalarValue::TimestampSecond(Some(inner_value), _)
+            | ScalarValue::DurationSecond(Some(inner_value))
+            | ScalarValue::DurationMillisecond(Some(inner_value))
+            | ScalarValue::DurationMicrosecond(Some(inner_value))
+            | ScalarValue::DurationNanosecond(Some(inner_value)) => Ok(inner_value),
             _ => Err(DataFusionError::Internal(format!(
                 "Cannot convert {:?} to {}",
                 value,
@@ -1725,6 +1906,16 @@ impl TryFrom<&DataType> for ScalarValue {
             DataType::Timestamp(TimeUnit::Nanosecond, tz_opt) => {
                 ScalarValue::TimestampNanosecond(None, tz_opt.clone())
             }
+            DataType::Duration(TimeUnit::Second) => ScalarValue::DurationSecond(None),
+            DataType::Duration(TimeUnit::Millisecond) => {
+                ScalarValue::DurationMillisecond(None)
+            }
+            DataType::Duration(TimeUnit::Microsecond) => {
+                ScalarValue::DurationMicrosecond(None)
+            }
+            DataType::Duration(TimeUnit::Nanosecond) => {
+                ScalarValue::DurationNanosecond(None)
+            }
             DataType::Dictionary(_index_type, value_type) => {
                 value_type.as_ref().try_into()?
             }
@@ -1814,6 +2005,10 @@ impl fmt::Display for ScalarValue {
             ScalarValue::IntervalDayTime(e) => format_option!(f, e)?,
             ScalarValue::IntervalYearMonth(e) => format_option!(f, e)?,
             ScalarValue::IntervalMonthDayNano(e) => format_option!(f, e)?,
+            ScalarValue::DurationSecond(e) => format_option!(f, e)?,
+            ScalarValue::DurationMillisecond(e) => format_option!(f, e)?,
+            ScalarValue::DurationMicrosecond(e) => format_option!(f, e)?,
+            ScalarValue::DurationNanosecond(e) => format_option!(f, e)?,
             ScalarValue::Struct(e, fields) => match e {
                 Some(l) => write!(
                     f,
@@ -1878,6 +2073,16 @@ impl fmt::Debug for ScalarValue {
             ScalarValue::IntervalMonthDayNano(_) => {
                 write!(f, "IntervalMonthDayNano(\"{}\")", self)
             }
+            ScalarValue::DurationSecond(_) => write!(f, "DurationSecond(\"{}\")", self),
+            ScalarValue::DurationMillisecond(_) => {
+                write!(f, "DurationMillisecond(\"{}\")", self)
+            }
+            ScalarValue::DurationMicrosecond(_) => {
+                write!(f, "DurationMicrosecond(\"{}\")", self)
+            }
+            ScalarValue::DurationNanosecond(_) => {
+                write!(f, "DurationNanosecond(\"{}\")", self)
+            }
             ScalarValue::Struct(e, fields) => {
                 // Use Debug representation of field values
                 match e {
@@ -0,0 +1,137 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::{
+    array::{DictionaryArray, Int64Array},
+    datatypes::{DataType, Field, Int32Type, Schema},
+    record_batch::RecordBatch,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use datafusion::from_slice::FromSlice;
+use datafusion::prelude::{SessionConfig, SessionContext};
+use datafusion::{datasource::MemTable, error::Result};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+fn query(ctx: &SessionContext, rt: &Runtime, sql: &str) {
+    let df = rt.block_on(ctx.sql(sql)).unwrap();
+    criterion::black_box(rt.block_on(df.collect()).unwrap());
+}
+
+/// Registers the table `name` holding the keys `key(0..array_len)`, with a
+/// payload and a dictionary encoded column
+fn register_table(
+    ctx: &SessionContext,
+    name: &str,
+    array_len: usize,
+    batch_size: usize,
+    key: impl Fn(usize) -> i64,
+) -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("k", DataType::Int64, false),
+        Field::new("v", DataType::Int64, false),
+        Field::new(
+            "d",
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            false,
+        ),
+    ]));
+
+    let batches = (0..array_len / batch_size)
+        .map(|i| {
+            let keys = (i * batch_size..(i + 1) * batch_size)
+                .map(&key)
+                .collect::<Vec<_>>();
+            let dictionary: DictionaryArray<Int32Type> = keys
+                .iter()
+                .map(|k| ["red", "green", "blue"][*k as usize % 3])
+                .collect();
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int64Array::from_slice(&keys)),
+                    Arc::new(Int64Array::from_slice(&keys)),
+                    Arc::new(dictionary),
+                ],
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let provider = MemTable::try_new(schema, vec![batches])?;
+    ctx.register_table(name, Arc::new(provider))?;
+    Ok(())
+}
+
+fn create_context(array_len: usize, batch_size: usize) -> Result<SessionContext> {
+    let ctx = SessionContext::with_config(
+        SessionConfig::new()
+            .with_target_partitions(1)
+            .with_batch_size(batch_size),
+    );
+    register_table(&ctx, "l", array_len, batch_size, |k| k as i64)?;
+    register_table(&ctx, "r", array_len, batch_size, |k| k as i64)?;
+    // the same keys, shuffled
+    register_table(&ctx, "s", array_len, batch_size, |k| {
+        (k * 7919 % array_len) as i64
+    })?;
+    Ok(ctx)
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let array_len = 524_288; // 2^19
+    let batch_size = 4096; // 2^12
+    let rt = Runtime::new().unwrap();
+    let ctx = create_context(array_len, batch_size).unwrap();
+
+    // every probed row matches one row: the rows of both sides are gathered
+    // as slices
+    c.bench_function("join_unique_keys", |b| {
+        b.iter(|| {
+            query(
+                &ctx,
+                &rt,
+                "SELECT l.v, l.d, r.v, r.d FROM l JOIN r ON l.k = r.k",
+            )
+        })
+    });
+
+    // runs of probed rows match
+    c.bench_function("join_runs_of_keys", |b| {
+        b.iter(|| {
+            query(
+                &ctx,
+                &rt,
+                "SELECT l.v, l.d, r.v, r.d FROM l JOIN r ON l.k = r.k WHERE r.k % 64 < 32",
+            )
+        })
+    });
+
+    // the probed rows match scattered rows: the rows are gathered with take
+    c.bench_function("join_scattered_keys", |b| {
+        b.iter(|| {
+            query(
+                &ctx,
+                &rt,
+                "SELECT l.v, l.d, s.v, s.d FROM l JOIN s ON l.k = s.k",
+            )
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,103 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+#[macro_use]
+extern crate criterion;
+use criterion::Criterion;
+
+use std::sync::Arc;
+
+use tokio::runtime::Runtime;
+
+extern crate arrow;
+extern crate datafusion;
+
+use arrow::datatypes::{DataType, Field, Schema};
+use datafusion::datasource::MemTable;
+use datafusion::execution::context::SessionContext;
+
+/// Plans the query `sql`, from the SQL to the optimized logical plan
+fn logical_plan(ctx: &SessionContext, sql: &str) {
+    let plan = ctx.create_logical_plan(sql).unwrap();
+    criterion::black_box(ctx.optimize(&plan).unwrap());
+}
+
+/// Plans the query `sql`, from the SQL to the physical plan
+fn physical_plan(ctx: &SessionContext, rt: &Runtime, sql: &str) {
+    let plan = ctx.create_logical_plan(sql).unwrap();
+    let plan = ctx.optimize(&plan).unwrap();
+    criterion::black_box(rt.block_on(ctx.create_physical_plan(&plan)).unwrap());
+}
+
+/// Creates a schema of `column_count` columns named after `prefix`
+fn create_schema(prefix: &str, column_count: usize) -> Schema {
+    Schema::new(
+        (0..column_count)
+            .map(|i| Field::new(&format!("{}{}", prefix, i), DataType::Int32, true))
+            .collect(),
+    )
+}
+
+/// Registers the empty tables `t1` and `t2` of `column_count` columns, the
+/// planning time of their queries being mostly spent on their schemas
+fn register_wide_tables(ctx: &SessionContext, column_count: usize) {
+    for (table, prefix) in [("t1", "a"), ("t2", "b")] {
+        let schema = Arc::new(create_schema(prefix, column_count));
+        let provider = MemTable::try_new(schema, vec![vec![]]).unwrap();
+        ctx.register_table(table, Arc::new(provider)).unwrap();
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let ctx = SessionContext::new();
+    register_wide_tables(&ctx, 1000);
+    let rt = Runtime::new().unwrap();
+
+    c.bench_function("logical_select_all_from_1000", |b| {
+        b.iter(|| logical_plan(&ctx, "SELECT * FROM t1"))
+    });
+
+    c.bench_function("logical_select_filter_alias_from_1000", |b| {
+        b.iter(|| {
+            logical_plan(
+                &ctx,
+                "SELECT * FROM (SELECT * FROM t1 WHERE a0 > 10) AS s WHERE s.a1 < 5",
+            )
+        })
+    });
+
+    c.bench_function("logical_join_1000_with_1000", |b| {
+        b.iter(|| {
+            logical_plan(
+                &ctx,
+                "SELECT t1.*, t2.* FROM t1 JOIN t2 ON t1.a0 = t2.b0 WHERE t1.a1 > t2.b1",
+            )
+        })
+    });
+
+    c.bench_function("physical_join_1000_with_1000", |b| {
+        b.iter(|| {
+            physical_plan(
+                &ctx,
+                &rt,
+                "SELECT t1.*, t2.* FROM t1 JOIN t2 ON t1.a0 = t2.b0 WHERE t1.a1 > t2.b1",
+            )
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
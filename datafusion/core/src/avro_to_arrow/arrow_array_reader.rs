@@ -43,20 +43,25 @@ use arrow::error::Result as ArrowResult;
 use avro_rs::{
     schema::{Schema as AvroSchema, SchemaKind},
     types::Value,
-    AvroResult, Error as AvroError, Reader as AvroReader,
+    AvroResult, Error as AvroError,
 };
 use num_traits::NumCast;
 use std::collections::HashMap;
 use std::io::Read;
+use std::marker::PhantomData;
 use std::sync::Arc;
 
+use super::decoder::RecordDecoder;
+
 type RecordSlice<'a> = &'a [&'a Vec<(String, Value)>];
 
 pub struct AvroArrowArrayReader<'a, R: Read> {
-    reader: AvroReader<'a, R>,
-    schema: SchemaRef,
-    projection: Option<Vec<String>>,
+    /// Decodes the projected fields of the records
+    reader: RecordDecoder<R>,
+    /// The schema of the produced batches, after projection
+    projected_schema: SchemaRef,
     schema_lookup: HashMap<String, usize>,
+    lifetime: PhantomData<&'a ()>,
 }
 
 impl<'a, R: Read> AvroArrowArrayReader<'a, R> {
@@ -65,14 +70,33 @@ impl<'a, R: Read> AvroArrowArrayReader<'a, R> {
         schema: SchemaRef,
         projection: Option<Vec<String>>,
     ) -> Result<Self> {
-        let reader = AvroReader::new(reader)?;
+        let reader = RecordDecoder::try_new(reader)?;
         let writer_schema = reader.writer_schema().clone();
         let schema_lookup = Self::schema_lookup(writer_schema)?;
+
+        let (projected_schema, projected_fields_mask) = match &projection {
+            Some(projection) if !projection.is_empty() => {
+                let projected_fields: Vec<Field> = projection
+                    .iter()
+                    .filter_map(|name| schema.column_with_name(name))
+                    .map(|(_, field)| field.clone())
+                    .collect();
+                let mut mask = vec![false; schema_lookup.len()];
+                for (name, idx) in &schema_lookup {
+                    if let Some(projected) = mask.get_mut(*idx) {
+                        *projected = projection.contains(name);
+                    }
+                }
+                (Arc::new(Schema::new(projected_fields)), Some(mask))
+            }
+            _ => (schema, None),
+        };
+
         Ok(Self {
-            reader,
-            schema,
-            projection,
+            reader: reader.with_projected_fields_mask(projected_fields_mask),
+            projected_schema,
             schema_lookup,
+            lifetime: PhantomData,
         })
     }
 
@@ -91,44 +115,24 @@ impl<'a, R: Read> AvroArrowArrayReader<'a, R> {
     /// Read the next batch of records
     #[allow(clippy::should_implement_trait)]
     pub fn next_batch(&mut self, batch_size: usize) -> ArrowResult<Option<RecordBatch>> {
+        // the fields that are not projected are skipped by the decoder and
+        // left null, so that positional lookups keep working
         let rows = self
             .reader
             .by_ref()
             .take(batch_size)
-            .map(|value| match value {
-                Ok(Value::Record(v)) => Ok(v),
-                Err(e) => Err(ArrowError::ParseError(format!(
-                    "Failed to parse avro value: {:?}",
-                    e
-                ))),
-                other => {
-                    return Err(ArrowError::ParseError(format!(
-                        "Row needs to be of type object, got: {:?}",
-                        other
-                    )))
-                }
-            })
             .collect::<ArrowResult<Vec<Vec<(String, Value)>>>>()?;
         if rows.is_empty() {
             // reached end of file
             return Ok(None);
         }
         let rows = rows.iter().collect::<Vec<&Vec<(String, Value)>>>();
-        let projection = self.projection.clone().unwrap_or_else(Vec::new);
+        // build the arrays in the order of the projection
         let arrays =
-            self.build_struct_array(rows.as_slice(), self.schema.fields(), &projection);
-        let projected_fields: Vec<Field> = if projection.is_empty() {
-            self.schema.fields().to_vec()
-        } else {
-            projection
-                .iter()
-                .map(|name| self.schema.column_with_name(name))
-                .flatten()
-                .map(|(_, field)| field.clone())
-                .collect()
-        };
-        let projected_schema = Arc::new(Schema::new(projected_fields));
-        arrays.and_then(|arr| RecordBatch::try_new(projected_schema, arr).map(Some))
+            self.build_struct_array(rows.as_slice(), self.projected_schema.fields(), &[]);
+        arrays.and_then(|arr| {
+            RecordBatch::try_new(self.projected_schema.clone(), arr).map(Some)
+        })
     }
 
     fn build_boolean_array(
@@ -1101,4 +1105,28 @@ mod test {
         assert_eq!(2, num_batches);
         assert_eq!(28, sum_id);
     }
+
+    #[test]
+    fn test_avro_read_projection() {
+        let testdata = crate::test_util::arrow_test_data();
+        let filename = format!("{}/avro/alltypes_plain.avro", testdata);
+        let builder = ReaderBuilder::new()
+            .read_schema()
+            .with_batch_size(10)
+            .with_projection(vec!["string_col".to_owned(), "id".to_owned()]);
+        let mut reader = builder.build(File::open(filename).unwrap()).unwrap();
+        let batch = reader.next().unwrap().unwrap();
+
+        assert_eq!(2, batch.num_columns());
+        assert_eq!(8, batch.num_rows());
+        assert_eq!("string_col", batch.schema().field(0).name());
+        assert_eq!("id", batch.schema().field(1).name());
+
+        let ids = batch
+            .column(1)
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(28, ids.iter().flatten().sum::<i32>());
+    }
 }
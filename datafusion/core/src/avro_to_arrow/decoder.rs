@@ -0,0 +1,432 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Decoding of the records of Avro object container files, skipping the
+//! encoded values of the fields that are not projected instead of decoding
+//! them.
+
+use crate::arrow::error::{ArrowError, Result as ArrowResult};
+use avro_rs::{from_avro_datum, schema::Schema as AvroSchema, types::Value, Codec};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+
+const MAGIC: &[u8; 4] = b"Obj\x01";
+const SYNC_MARKER_SIZE: usize = 16;
+/// The maximum size of the data of a block, bounding the memory allocated
+/// for a corrupted block header
+const MAX_BLOCK_SIZE: u64 = 1 << 30;
+
+/// Iterator over the records of an Avro object container file, each record
+/// being the values of the fields of the writer schema by position. The
+/// fields that are not projected are skipped when decoding and set to
+/// [`Value::Null`].
+pub struct RecordDecoder<R: Read> {
+    reader: R,
+    writer_schema: AvroSchema,
+    codec: Codec,
+    sync_marker: [u8; SYNC_MARKER_SIZE],
+    /// Whether each field of the writer schema, by position, is projected.
+    /// `None` if all fields are projected.
+    projected_fields_mask: Option<Vec<bool>>,
+    /// The decompressed data of the current block
+    block: Cursor<Vec<u8>>,
+    /// The number of records of the current block not decoded yet
+    block_remaining: i64,
+    finished: bool,
+}
+
+impl<R: Read> RecordDecoder<R> {
+    /// Reads the header of the object container file of `reader`
+    pub fn try_new(mut reader: R) -> ArrowResult<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(parse_error("Not an Avro object container file"));
+        }
+        let metadata = read_metadata(&mut reader)?;
+        let schema = metadata
+            .get("avro.schema")
+            .ok_or_else(|| parse_error("No schema in the Avro file header"))?;
+        let writer_schema =
+            AvroSchema::parse_str(std::str::from_utf8(schema).map_err(|e| {
+                parse_error(format!("Invalid schema in the Avro file header: {}", e))
+            })?)
+            .map_err(avro_error)?;
+        let codec = match metadata.get("avro.codec").map(|codec| codec.as_slice()) {
+            None | Some(b"null") => Codec::Null,
+            Some(b"deflate") => Codec::Deflate,
+            Some(b"snappy") => Codec::Snappy,
+            Some(other) => {
+                return Err(parse_error(format!(
+                    "Unsupported Avro codec {}",
+                    String::from_utf8_lossy(other)
+                )))
+            }
+        };
+        let mut sync_marker = [0u8; SYNC_MARKER_SIZE];
+        reader.read_exact(&mut sync_marker)?;
+
+        Ok(Self {
+            reader,
+            writer_schema,
+            codec,
+            sync_marker,
+            projected_fields_mask: None,
+            block: Cursor::new(vec![]),
+            block_remaining: 0,
+            finished: false,
+        })
+    }
+
+    /// The schema the records were written with
+    pub fn writer_schema(&self) -> &AvroSchema {
+        &self.writer_schema
+    }
+
+    /// Only decodes the fields of the writer schema whose position is `true`
+    /// in `mask`
+    pub fn with_projected_fields_mask(mut self, mask: Option<Vec<bool>>) -> Self {
+        self.projected_fields_mask = mask;
+        self
+    }
+
+    /// Reads the next block, returning `false` at the end of the file
+    fn read_block(&mut self) -> ArrowResult<bool> {
+        let count = match read_long_or_eof(&mut self.reader)? {
+            Some(count) => count,
+            None => return Ok(false),
+        };
+        let size = read_long(&mut self.reader)?;
+        if count < 0 || size < 0 {
+            return Err(parse_error("Invalid Avro block header"));
+        }
+        if size as u64 > MAX_BLOCK_SIZE {
+            return Err(parse_error(format!(
+                "Avro block of {} bytes exceeds the maximum of {} bytes",
+                size, MAX_BLOCK_SIZE
+            )));
+        }
+        let mut data = read_exact_len(&mut self.reader, size as u64)?;
+        let mut sync_marker = [0u8; SYNC_MARKER_SIZE];
+        self.reader.read_exact(&mut sync_marker)?;
+        if sync_marker != self.sync_marker {
+            return Err(parse_error("Invalid sync marker of an Avro block"));
+        }
+        self.codec.decompress(&mut data).map_err(avro_error)?;
+        self.block = Cursor::new(data);
+        self.block_remaining = count;
+        Ok(true)
+    }
+
+    fn decode_record(&mut self) -> ArrowResult<Vec<(String, Value)>> {
+        let fields = match &self.writer_schema {
+            AvroSchema::Record { fields, .. } => fields,
+            _ => return Err(parse_error("expected avro schema to be a record")),
+        };
+        let mut record = Vec::with_capacity(fields.len());
+        for (index, field) in fields.iter().enumerate() {
+            let projected = self
+                .projected_fields_mask
+                .as_ref()
+                .map_or(true, |mask| mask.get(index).copied().unwrap_or(false));
+            let value = if projected {
+                from_avro_datum(&field.schema, &mut self.block, None)
+                    .map_err(avro_error)?
+            } else {
+                skip_value(&field.schema, &mut self.block)?;
+                Value::Null
+            };
+            record.push((field.name.clone(), value));
+        }
+        Ok(record)
+    }
+}
+
+impl<R: Read> Iterator for RecordDecoder<R> {
+    type Item = ArrowResult<Vec<(String, Value)>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished && self.block_remaining == 0 {
+            match self.read_block() {
+                Ok(true) => {}
+                Ok(false) => self.finished = true,
+                Err(e) => {
+                    self.finished = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        if self.finished {
+            return None;
+        }
+        self.block_remaining -= 1;
+        let record = self.decode_record();
+        if record.is_err() {
+            self.finished = true;
+        }
+        Some(record)
+    }
+}
+
+/// Reads the metadata map of the file header
+fn read_metadata<R: Read>(reader: &mut R) -> ArrowResult<HashMap<String, Vec<u8>>> {
+    let mut metadata = HashMap::new();
+    loop {
+        let count = read_long(reader)?;
+        if count == 0 {
+            return Ok(metadata);
+        }
+        if count < 0 {
+            // the byte size of the block follows its negated count
+            read_long(reader)?;
+        }
+        for _ in 0..count.unsigned_abs() {
+            let key = String::from_utf8(read_bytes(reader)?).map_err(|e| {
+                parse_error(format!("Invalid key in the Avro file header: {}", e))
+            })?;
+            metadata.insert(key, read_bytes(reader)?);
+        }
+    }
+}
+
+/// Advances `reader` past the encoded value of type `schema`
+fn skip_value(schema: &AvroSchema, reader: &mut Cursor<Vec<u8>>) -> ArrowResult<()> {
+    match schema {
+        AvroSchema::Null => Ok(()),
+        AvroSchema::Boolean => skip_bytes(reader, 1),
+        AvroSchema::Int
+        | AvroSchema::Long
+        | AvroSchema::Date
+        | AvroSchema::TimeMillis
+        | AvroSchema::TimeMicros
+        | AvroSchema::TimestampMillis
+        | AvroSchema::TimestampMicros
+        | AvroSchema::Enum { .. } => read_long(reader).map(|_| ()),
+        AvroSchema::Float => skip_bytes(reader, 4),
+        AvroSchema::Double => skip_bytes(reader, 8),
+        AvroSchema::Bytes | AvroSchema::String | AvroSchema::Uuid => {
+            let len = read_len(reader)?;
+            skip_bytes(reader, len)
+        }
+        AvroSchema::Fixed { size, .. } => skip_bytes(reader, *size),
+        AvroSchema::Duration => skip_bytes(reader, 12),
+        AvroSchema::Decimal { inner, .. } => skip_value(inner, reader),
+        AvroSchema::Array(items) => {
+            skip_blocks(reader, |reader| skip_value(items, reader))
+        }
+        AvroSchema::Map(values) => skip_blocks(reader, |reader| {
+            let len = read_len(reader)?;
+            skip_bytes(reader, len)?;
+            skip_value(values, reader)
+        }),
+        AvroSchema::Union(union) => {
+            let index = read_len(reader)?;
+            let variant = union.variants().get(index).ok_or_else(|| {
+                parse_error(format!("Invalid Avro union variant {}", index))
+            })?;
+            skip_value(variant, reader)
+        }
+        AvroSchema::Record { fields, .. } => fields
+            .iter()
+            .try_for_each(|field| skip_value(&field.schema, reader)),
+    }
+}
+
+/// Advances `reader` past the blocks of an array or a map, calling
+/// `skip_item` for each item of the blocks whose byte size isn't known
+fn skip_blocks<F>(reader: &mut Cursor<Vec<u8>>, skip_item: F) -> ArrowResult<()>
+where
+    F: Fn(&mut Cursor<Vec<u8>>) -> ArrowResult<()>,
+{
+    loop {
+        let count = read_long(reader)?;
+        if count == 0 {
+            return Ok(());
+        }
+        if count < 0 {
+            let size = read_len(reader)?;
+            skip_bytes(reader, size)?;
+        } else {
+            for _ in 0..count {
+                skip_item(reader)?;
+            }
+        }
+    }
+}
+
+fn skip_bytes(reader: &mut Cursor<Vec<u8>>, len: usize) -> ArrowResult<()> {
+    let position = reader.position() as usize;
+    match position.checked_add(len) {
+        Some(end) if end <= reader.get_ref().len() => {
+            reader.set_position(end as u64);
+            Ok(())
+        }
+        _ => Err(parse_error("Unexpected end of an Avro block")),
+    }
+}
+
+fn read_bytes<R: Read>(reader: &mut R) -> ArrowResult<Vec<u8>> {
+    let len = read_len(reader)?;
+    read_exact_len(reader, len as u64)
+}
+
+/// Reads exactly `len` bytes, growing the buffer as they are read so that
+/// a corrupted length fails at the end of `reader` rather than allocating
+/// `len` bytes upfront
+fn read_exact_len<R: Read>(reader: &mut R, len: u64) -> ArrowResult<Vec<u8>> {
+    let mut bytes = vec![];
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(parse_error("Unexpected end of Avro data"));
+    }
+    Ok(bytes)
+}
+
+/// Reads a non-negative length or index
+fn read_len<R: Read>(reader: &mut R) -> ArrowResult<usize> {
+    let len = read_long(reader)?;
+    usize::try_from(len).map_err(|_| parse_error(format!("Invalid Avro length {}", len)))
+}
+
+fn read_long<R: Read>(reader: &mut R) -> ArrowResult<i64> {
+    read_long_or_eof(reader)?.ok_or_else(|| parse_error("Unexpected end of Avro data"))
+}
+
+/// Reads a zigzag encoded long, returning `None` if `reader` is at its end
+fn read_long_or_eof<R: Read>(reader: &mut R) -> ArrowResult<Option<i64>> {
+    let mut value: u64 = 0;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        if reader.read(&mut byte)? == 0 {
+            if shift == 0 {
+                return Ok(None);
+            }
+            return Err(parse_error("Unexpected end of Avro data"));
+        }
+        if shift > 63 {
+            return Err(parse_error("Invalid Avro variable length integer"));
+        }
+        value |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(((value >> 1) as i64) ^ -((value & 1) as i64)));
+        }
+        shift += 7;
+    }
+}
+
+fn parse_error(message: impl Into<String>) -> ArrowError {
+    ArrowError::ParseError(message.into())
+}
+
+fn avro_error(e: avro_rs::Error) -> ArrowError {
+    ArrowError::ParseError(format!("Failed to parse avro value: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn decoder(mask: Option<Vec<bool>>) -> RecordDecoder<File> {
+        let testdata = crate::test_util::arrow_test_data();
+        let filename = format!("{}/avro/alltypes_plain.avro", testdata);
+        RecordDecoder::try_new(File::open(filename).unwrap())
+            .unwrap()
+            .with_projected_fields_mask(mask)
+    }
+
+    #[test]
+    fn decode_projected_fields() -> ArrowResult<()> {
+        // the same records as the avro-rs reader
+        let testdata = crate::test_util::arrow_test_data();
+        let filename = format!("{}/avro/alltypes_plain.avro", testdata);
+        let expected = avro_rs::Reader::new(File::open(filename).unwrap())
+            .unwrap()
+            .map(|value| match value.unwrap() {
+                Value::Record(fields) => fields,
+                other => panic!("unexpected value {:?}", other),
+            })
+            .collect::<Vec<_>>();
+        let records = decoder(None).collect::<ArrowResult<Vec<_>>>()?;
+        assert_eq!(records, expected);
+
+        // the fields that are not projected are skipped
+        let mask = expected[0]
+            .iter()
+            .map(|(name, _)| name == "id" || name == "string_col")
+            .collect::<Vec<_>>();
+        let records = decoder(Some(mask.clone())).collect::<ArrowResult<Vec<_>>>()?;
+        assert_eq!(records.len(), expected.len());
+        for (record, expected) in records.iter().zip(&expected) {
+            for ((field, projected), expected) in record.iter().zip(&mask).zip(expected) {
+                assert_eq!(field.0, expected.0);
+                if *projected {
+                    assert_eq!(field.1, expected.1);
+                } else {
+                    assert_eq!(field.1, Value::Null);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_long(value: i64, bytes: &mut Vec<u8>) {
+        let mut value = ((value << 1) ^ (value >> 63)) as u64;
+        while value >= 0x80 {
+            bytes.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+    }
+
+    /// An Avro file whose single block has the header `count` and `size`,
+    /// followed by `data`
+    fn file_with_block(count: i64, size: i64, data: &[u8]) -> Vec<u8> {
+        let schema = br#"{"type": "record", "name": "r", "fields": []}"#;
+        let mut bytes = MAGIC.to_vec();
+        encode_long(1, &mut bytes);
+        encode_long(11, &mut bytes);
+        bytes.extend_from_slice(b"avro.schema");
+        encode_long(schema.len() as i64, &mut bytes);
+        bytes.extend_from_slice(schema);
+        encode_long(0, &mut bytes);
+        bytes.extend_from_slice(&[7; SYNC_MARKER_SIZE]);
+        encode_long(count, &mut bytes);
+        encode_long(size, &mut bytes);
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    #[test]
+    fn corrupted_block_sizes() -> ArrowResult<()> {
+        let decode = |bytes: Vec<u8>| {
+            let decoder = RecordDecoder::try_new(Cursor::new(bytes))?;
+            decoder.collect::<ArrowResult<Vec<_>>>()
+        };
+
+        let err = decode(file_with_block(1, 1 << 40, &[])).unwrap_err();
+        assert!(err.to_string().contains("exceeds the maximum"), "{}", err);
+        let err = decode(file_with_block(1, 1 << 20, &[0; 16])).unwrap_err();
+        assert!(err.to_string().contains("Unexpected end"), "{}", err);
+
+        let mut data = vec![];
+        data.extend_from_slice(&[7; SYNC_MARKER_SIZE]);
+        assert_eq!(decode(file_with_block(1, 0, &data))?, vec![vec![]]);
+        Ok(())
+    }
+}
@@ -20,6 +20,8 @@
 #[cfg(feature = "avro")]
 mod arrow_array_reader;
 #[cfg(feature = "avro")]
+mod decoder;
+#[cfg(feature = "avro")]
 mod reader;
 #[cfg(feature = "avro")]
 mod schema;
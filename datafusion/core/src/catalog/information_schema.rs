@@ -39,7 +39,7 @@ use super::{
     schema::SchemaProvider,
 };
 
-const INFORMATION_SCHEMA: &str = "information_schema";
+pub(crate) const INFORMATION_SCHEMA: &str = "information_schema";
 const TABLES: &str = "tables";
 const COLUMNS: &str = "columns";
 
@@ -22,6 +22,7 @@
 pub mod catalog;
 pub mod information_schema;
 pub mod schema;
+pub mod snapshot;
 
 use crate::error::DataFusionError;
 use std::convert::TryFrom;
@@ -67,18 +67,41 @@ pub trait SchemaProvider: Sync + Send {
     /// If no matched table in the schema provider, return false.
     /// Otherwise, return true.
     fn table_exist(&self, name: &str) -> bool;
+
+    /// Returns the tables of the schema by name as they are now, captured by
+    /// the snapshots of the catalogs, see
+    /// [`CatalogSnapshot`](crate::catalog::snapshot::CatalogSnapshot).
+    ///
+    /// The default implementation looks up every table. The implementations
+    /// whose tables are expensive to look up, e.g. in a remote catalog, should
+    /// return a versioned or copy-on-write map instead.
+    fn table_snapshot(&self) -> Arc<HashMap<String, Arc<dyn TableProvider>>> {
+        let tables = self
+            .table_names()
+            .into_iter()
+            .filter_map(|name| {
+                let table = self.table(&name)?;
+                Some((name, table))
+            })
+            .collect();
+        Arc::new(tables)
+    }
 }
 
 /// Simple in-memory implementation of a schema.
+///
+/// The tables are kept in a copy-on-write map, so that capturing them in a
+/// snapshot only copies a pointer, the map being copied by the next change
+/// of the tables while the snapshot holds it.
 pub struct MemorySchemaProvider {
-    tables: RwLock<HashMap<String, Arc<dyn TableProvider>>>,
+    tables: RwLock<Arc<HashMap<String, Arc<dyn TableProvider>>>>,
 }
 
 impl MemorySchemaProvider {
     /// Instantiates a new MemorySchemaProvider with an empty collection of tables.
     pub fn new() -> Self {
         Self {
-            tables: RwLock::new(HashMap::new()),
+            tables: RwLock::new(Arc::new(HashMap::new())),
         }
     }
 }
@@ -116,18 +139,25 @@ impl SchemaProvider for MemorySchemaProvider {
             )));
         }
         let mut tables = self.tables.write();
-        Ok(tables.insert(name, table))
+        Ok(Arc::make_mut(&mut *tables).insert(name, table))
     }
 
     fn deregister_table(&self, name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
         let mut tables = self.tables.write();
-        Ok(tables.remove(name))
+        if !tables.contains_key(name) {
+            return Ok(None);
+        }
+        Ok(Arc::make_mut(&mut *tables).remove(name))
     }
 
     fn table_exist(&self, name: &str) -> bool {
         let tables = self.tables.read();
         tables.contains_key(name)
     }
+
+    fn table_snapshot(&self) -> Arc<HashMap<String, Arc<dyn TableProvider>>> {
+        self.tables.read().clone()
+    }
 }
 
 /// `ObjectStore` implementation of `SchemaProvider` to enable registering a `ListingTable`
@@ -0,0 +1,264 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Snapshots of catalog lists, giving the statements of a transaction or of
+//! a script a consistent view of the tables.
+
+use parking_lot::RwLock;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::catalog::{CatalogList, CatalogProvider};
+use super::information_schema::INFORMATION_SCHEMA;
+use super::schema::SchemaProvider;
+use crate::datasource::TableProvider;
+use crate::error::Result;
+
+/// A [`CatalogList`] resolving the catalogs, schemas and tables as they
+/// were in its `source` when the snapshot was captured: the tables
+/// registered, replaced or deregistered in the source afterwards are not
+/// seen through the snapshot.
+///
+/// The catalogs, schemas and tables registered or deregistered through the
+/// snapshot are registered or deregistered in the source as well, so the
+/// snapshot sees the changes made through it.
+///
+/// The `information_schema` schemas are not captured, they always describe
+/// the current catalogs.
+///
+/// The catalogs, schemas and tables are all captured by
+/// [`CatalogSnapshot::capture`], the tables of each schema with
+/// [`SchemaProvider::table_snapshot`], which only copies a pointer for the
+/// [`MemorySchemaProvider`](super::schema::MemorySchemaProvider)s.
+pub struct CatalogSnapshot {
+    source: Arc<dyn CatalogList>,
+    catalogs: RwLock<HashMap<String, Arc<dyn CatalogProvider>>>,
+}
+
+impl CatalogSnapshot {
+    /// Captures the catalogs of `source`
+    pub fn capture(source: Arc<dyn CatalogList>) -> Self {
+        let catalogs = source
+            .catalog_names()
+            .into_iter()
+            .filter_map(|name| {
+                let catalog = source.catalog(&name)?;
+                Some((name, capture_catalog(catalog)))
+            })
+            .collect();
+        Self {
+            source,
+            catalogs: RwLock::new(catalogs),
+        }
+    }
+
+    /// The catalog list the snapshot was captured from
+    pub fn source(&self) -> &Arc<dyn CatalogList> {
+        &self.source
+    }
+}
+
+impl CatalogList for CatalogSnapshot {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn register_catalog(
+        &self,
+        name: String,
+        catalog: Arc<dyn CatalogProvider>,
+    ) -> Option<Arc<dyn CatalogProvider>> {
+        self.source.register_catalog(name.clone(), catalog.clone());
+        self.catalogs.write().insert(name, capture_catalog(catalog))
+    }
+
+    fn catalog_names(&self) -> Vec<String> {
+        self.catalogs.read().keys().cloned().collect()
+    }
+
+    fn catalog(&self, name: &str) -> Option<Arc<dyn CatalogProvider>> {
+        self.catalogs.read().get(name).cloned()
+    }
+}
+
+fn capture_catalog(source: Arc<dyn CatalogProvider>) -> Arc<dyn CatalogProvider> {
+    let schemas = source
+        .schema_names()
+        .into_iter()
+        .filter_map(|name| {
+            let schema = source.schema(&name)?;
+            Some((name, capture_schema(&name, schema)))
+        })
+        .collect();
+    Arc::new(CatalogProviderSnapshot {
+        source,
+        schemas: RwLock::new(schemas),
+    })
+}
+
+fn capture_schema(
+    name: &str,
+    source: Arc<dyn SchemaProvider>,
+) -> Arc<dyn SchemaProvider> {
+    if name.eq_ignore_ascii_case(INFORMATION_SCHEMA) {
+        return source;
+    }
+    let tables = source.table_snapshot();
+    Arc::new(SchemaProviderSnapshot {
+        source,
+        tables: RwLock::new(tables),
+    })
+}
+
+/// The snapshot of a catalog of a [`CatalogSnapshot`]
+struct CatalogProviderSnapshot {
+    source: Arc<dyn CatalogProvider>,
+    schemas: RwLock<HashMap<String, Arc<dyn SchemaProvider>>>,
+}
+
+impl CatalogProvider for CatalogProviderSnapshot {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema_names(&self) -> Vec<String> {
+        self.schemas.read().keys().cloned().collect()
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        self.schemas.read().get(name).cloned()
+    }
+
+    fn register_schema(
+        &self,
+        name: &str,
+        schema: Arc<dyn SchemaProvider>,
+    ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+        self.source.register_schema(name, schema.clone())?;
+        Ok(self
+            .schemas
+            .write()
+            .insert(name.to_owned(), capture_schema(name, schema)))
+    }
+}
+
+/// The snapshot of a schema of a [`CatalogSnapshot`], whose tables are
+/// copied on the first change made through the snapshot if the source
+/// shares them
+struct SchemaProviderSnapshot {
+    source: Arc<dyn SchemaProvider>,
+    tables: RwLock<Arc<HashMap<String, Arc<dyn TableProvider>>>>,
+}
+
+impl SchemaProvider for SchemaProviderSnapshot {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        self.tables.read().keys().cloned().collect()
+    }
+
+    fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+        self.tables.read().get(name).cloned()
+    }
+
+    fn register_table(
+        &self,
+        name: String,
+        table: Arc<dyn TableProvider>,
+    ) -> Result<Option<Arc<dyn TableProvider>>> {
+        let previous = self.source.register_table(name.clone(), table.clone())?;
+        Arc::make_mut(&mut *self.tables.write()).insert(name, table);
+        Ok(previous)
+    }
+
+    fn deregister_table(&self, name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        self.source.deregister_table(name)?;
+        let mut tables = self.tables.write();
+        if !tables.contains_key(name) {
+            return Ok(None);
+        }
+        Ok(Arc::make_mut(&mut *tables).remove(name))
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        self.tables.read().contains_key(name)
+    }
+
+    fn table_snapshot(&self) -> Arc<HashMap<String, Arc<dyn TableProvider>>> {
+        self.tables.read().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::catalog::{MemoryCatalogList, MemoryCatalogProvider};
+    use crate::catalog::schema::MemorySchemaProvider;
+    use crate::datasource::empty::EmptyTable;
+    use arrow::datatypes::Schema;
+
+    fn table() -> Arc<dyn TableProvider> {
+        Arc::new(EmptyTable::new(Arc::new(Schema::empty())))
+    }
+
+    #[test]
+    fn capture_catalog_list() -> Result<()> {
+        let source = Arc::new(MemoryCatalogList::new());
+        let catalog = Arc::new(MemoryCatalogProvider::new());
+        let schema = Arc::new(MemorySchemaProvider::new());
+        schema.register_table("t".to_owned(), table())?;
+        catalog.register_schema("s", schema.clone())?;
+        source.register_catalog("c".to_owned(), catalog.clone());
+
+        let snapshot = CatalogSnapshot::capture(source.clone());
+        let t = schema.table("t").unwrap();
+
+        // the changes of the source are not seen, even before the first
+        // access to the snapshot
+        schema.deregister_table("t")?;
+        schema.register_table("t".to_owned(), table())?;
+        let snapshot_schema = snapshot.catalog("c").unwrap().schema("s").unwrap();
+        schema.register_table("u".to_owned(), table())?;
+        catalog.register_schema("s2", Arc::new(MemorySchemaProvider::new()))?;
+        assert!(Arc::ptr_eq(&snapshot_schema.table("t").unwrap(), &t));
+        assert!(!snapshot_schema.table_exist("u"));
+        assert!(snapshot.catalog("c").unwrap().schema("s2").is_none());
+
+        // the changes of the snapshot are made to the source as well
+        snapshot_schema.register_table("v".to_owned(), table())?;
+        assert!(snapshot_schema.table_exist("v"));
+        assert!(schema.table_exist("v"));
+        assert!(snapshot_schema.deregister_table("t")?.is_some());
+        assert!(!snapshot_schema.table_exist("t"));
+        assert!(!schema.table_exist("t"));
+        // including the conflicting ones
+        assert!(snapshot_schema
+            .register_table("u".to_owned(), table())
+            .is_err());
+
+        snapshot
+            .register_catalog("c2".to_owned(), Arc::new(MemoryCatalogProvider::new()));
+        assert!(source.catalog("c2").is_some());
+        let mut names = snapshot.catalog_names();
+        names.sort();
+        assert_eq!(names, vec!["c", "c2"]);
+        Ok(())
+    }
+}
@@ -18,10 +18,12 @@
 //! DataFrame API for building and executing query plans.
 
 use crate::arrow::record_batch::RecordBatch;
-use crate::error::Result;
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::plan::Extension;
 use crate::logical_plan::{
-    col, DFSchema, Expr, FunctionRegistry, JoinType, LogicalPlan, LogicalPlanBuilder,
-    Partitioning,
+    col, normalize_col, normalize_cols, DFSchema, DFSchemaRef, DedupNode, DedupStrategy,
+    Expr, FunctionRegistry, JoinType, LogicalPlan, LogicalPlanBuilder, MapBatchesNode,
+    Partitioning, ToDFSchema, VerifyNode,
 };
 use parquet::file::properties::WriterProperties;
 use std::sync::Arc;
@@ -32,16 +34,26 @@ use async_trait::async_trait;
 use crate::arrow::datatypes::Schema;
 use crate::arrow::datatypes::SchemaRef;
 use crate::arrow::util::pretty;
+use crate::datasource::checkpoint::Checkpoint;
 use crate::datasource::TableProvider;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::execution::blocking::block_on;
+use crate::execution::compaction::plan_to_sized_parquet;
 use crate::execution::context::{SessionState, TaskContext};
 use crate::logical_expr::TableType;
-use crate::physical_plan::file_format::{plan_to_csv, plan_to_json, plan_to_parquet};
+use crate::physical_plan::file_format::{
+    plan_to_csv, plan_to_csv_with_checkpoint, plan_to_json, plan_to_json_with_checkpoint,
+    plan_to_parquet, plan_to_parquet_with_checkpoint,
+};
 use crate::physical_plan::{collect, collect_partitioned};
 use crate::physical_plan::{execute_stream, execute_stream_partitioned, ExecutionPlan};
 use crate::scalar::ScalarValue;
 use crate::sql::utils::find_window_exprs;
+use crate::util::pretty::{self as display, DisplayOptions, TruncatePolicy};
 use parking_lot::RwLock;
 use std::any::Any;
+use std::fs;
+use std::path::Path;
 
 /// DataFrame represents a logical set of rows with the same named columns.
 /// Similar to a [Pandas DataFrame](https://pandas.pydata.org/pandas-docs/stable/reference/api/pandas.DataFrame.html) or
@@ -161,6 +173,141 @@ impl DataFrame {
         Ok(Arc::new(DataFrame::new(self.session_state.clone(), &plan)))
     }
 
+    /// Check that `predicate` holds for every row of the DataFrame. The rows are
+    /// returned unchanged, but executing the DataFrame fails with a sample of the
+    /// offending rows as soon as one of them doesn't satisfy the predicate
+    /// (a `NULL` result doesn't satisfy it).
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new()).await?;
+    /// let df = df.verify(col("a").is_not_null())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify(&self, predicate: Expr) -> Result<Arc<DataFrame>> {
+        let input = self.to_logical_plan();
+        let predicate = normalize_col(predicate, &input)?;
+        let plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(VerifyNode::try_new(input, predicate)?),
+        });
+        Ok(Arc::new(DataFrame::new(self.session_state.clone(), &plan)))
+    }
+
+    /// Keep only the latest row of each key of the DataFrame, the latest being
+    /// the row with the greatest `sequence`, e.g. to read the current state of
+    /// a change data capture (CDC) dataset holding several versions of each
+    /// row. The latest rows are found with a hash table, see
+    /// [`DataFrame::dedup_with_strategy`] to sort the rows instead.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new()).await?;
+    /// let df = df.dedup(vec![col("a")], col("c"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dedup(&self, keys: Vec<Expr>, sequence: Expr) -> Result<Arc<DataFrame>> {
+        self.dedup_with_strategy(keys, sequence, DedupStrategy::Hash)
+    }
+
+    /// Keep only the latest row of each key of the DataFrame, finding them
+    /// with `strategy`. See [`DataFrame::dedup`].
+    pub fn dedup_with_strategy(
+        &self,
+        keys: Vec<Expr>,
+        sequence: Expr,
+        strategy: DedupStrategy,
+    ) -> Result<Arc<DataFrame>> {
+        let input = self.to_logical_plan();
+        let keys = normalize_cols(keys, &input)?;
+        let sequence = normalize_col(sequence, &input)?;
+        let plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(DedupNode::try_new(input, keys, sequence, strategy)?),
+        });
+        Ok(Arc::new(DataFrame::new(self.session_state.clone(), &plan)))
+    }
+
+    /// Transform each batch of the DataFrame with the function `f`, which must
+    /// return batches of the same schema as its input, e.g. to call a library
+    /// working on whole Arrow arrays.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new()).await?;
+    /// let df = df.map_batches(|batch| Ok(batch.slice(0, batch.num_rows().min(1))))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_batches<F>(&self, f: F) -> Result<Arc<DataFrame>>
+    where
+        F: Fn(RecordBatch) -> Result<RecordBatch> + Send + Sync + 'static,
+    {
+        let input = self.to_logical_plan();
+        let schema = input.schema().clone();
+        self.map_batches_plan(input, schema, f)
+    }
+
+    /// Transform each batch of the DataFrame with the function `f`, which must
+    /// return batches of the given `schema`.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::arrow::datatypes::{DataType, Field, Schema};
+    /// # use datafusion::arrow::record_batch::RecordBatch;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new()).await?;
+    /// let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+    /// let output_schema = schema.clone();
+    /// let df = df.map_batches_with_schema(schema, move |batch| {
+    ///     let a = batch.column(0).clone();
+    ///     Ok(RecordBatch::try_new(output_schema.clone(), vec![a])?)
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_batches_with_schema<F>(
+        &self,
+        schema: SchemaRef,
+        f: F,
+    ) -> Result<Arc<DataFrame>>
+    where
+        F: Fn(RecordBatch) -> Result<RecordBatch> + Send + Sync + 'static,
+    {
+        self.map_batches_plan(self.to_logical_plan(), schema.to_dfschema_ref()?, f)
+    }
+
+    fn map_batches_plan<F>(
+        &self,
+        input: LogicalPlan,
+        schema: DFSchemaRef,
+        f: F,
+    ) -> Result<Arc<DataFrame>>
+    where
+        F: Fn(RecordBatch) -> Result<RecordBatch> + Send + Sync + 'static,
+    {
+        let plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(MapBatchesNode::new(input, schema, Arc::new(f))),
+        });
+        Ok(Arc::new(DataFrame::new(self.session_state.clone(), &plan)))
+    }
+
     /// Perform an aggregate query with optional grouping expressions.
     ///
     /// ```
@@ -230,6 +377,29 @@ impl DataFrame {
         Ok(Arc::new(DataFrame::new(self.session_state.clone(), &plan)))
     }
 
+    /// Calculate the union of two [`DataFrame`]s, matching their columns by name
+    /// rather than by position. The output has the columns of this [`DataFrame`]
+    /// followed by the columns only found in the other one, and the columns
+    /// missing from a [`DataFrame`] are filled with nulls.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new()).await?;
+    /// let df = df.union_by_name(df.select_columns(&["c", "a"])?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn union_by_name(&self, dataframe: Arc<DataFrame>) -> Result<Arc<DataFrame>> {
+        let plan = LogicalPlanBuilder::from(self.to_logical_plan())
+            .union_by_name(dataframe.to_logical_plan())?
+            .build()?;
+        Ok(Arc::new(DataFrame::new(self.session_state.clone(), &plan)))
+    }
+
     /// Calculate the union distinct two [`DataFrame`]s.  The two [`DataFrame`]s must have exactly the same schema
     ///
     /// ```
@@ -355,6 +525,28 @@ impl DataFrame {
         collect(plan, task_ctx).await
     }
 
+    /// Convert the logical plan represented by this DataFrame into a physical
+    /// plan and execute it, blocking the calling thread until all the results
+    /// are collected, without a tokio runtime. See
+    /// [`blocking`](crate::execution::blocking) for the runtime running the
+    /// plan.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.sql_blocking("SELECT 1 AS a UNION ALL SELECT 2")?;
+    /// let batches = df.collect_blocking()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn collect_blocking(&self) -> Result<Vec<RecordBatch>> {
+        let df = DataFrame::new(self.session_state.clone(), &self.plan);
+        block_on(move || async move { df.collect().await })
+    }
+
     /// Print results.
     ///
     /// ```
@@ -391,6 +583,36 @@ impl DataFrame {
         Ok(pretty::print_batches(&results)?)
     }
 
+    /// Print results with the given display options, e.g. as a Markdown or
+    /// HTML table with a limited number of rows.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use datafusion::util::pretty::{DisplayFormat, DisplayOptions};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/example.csv", CsvReadOptions::new()).await?;
+    /// let options = DisplayOptions::new()
+    ///     .with_format(DisplayFormat::Markdown)
+    ///     .with_max_rows(10)
+    ///     .with_max_width(20);
+    /// df.show_opts(&options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn show_opts(&self, options: &DisplayOptions) -> Result<()> {
+        let results = match (options.max_rows(), options.truncate()) {
+            // one more row tells whether rows are omitted
+            (Some(max_rows), TruncatePolicy::Head) => {
+                self.limit(max_rows + 1)?.collect().await?
+            }
+            _ => self.collect().await?,
+        };
+        display::print_batches(&results, options)
+    }
+
     /// Executes this DataFrame and returns a stream over a single partition
     ///
     /// ```
@@ -578,12 +800,92 @@ impl DataFrame {
         plan_to_parquet(&state, plan, path, writer_properties).await
     }
 
+    /// Write a `DataFrame` to the Parquet files `part-0.parquet`,
+    /// `part-1.parquet`, .. of the new directory `path`, starting a new file
+    /// once the batches written to the current one exceed `target_file_size`
+    /// bytes in memory, whatever the number of partitions of the `DataFrame`.
+    /// The rows of a sorted `DataFrame` stay sorted across the files.
+    pub async fn write_parquet_with_file_size(
+        &self,
+        path: &str,
+        target_file_size: usize,
+        writer_properties: Option<WriterProperties>,
+    ) -> Result<()> {
+        let plan = self.create_physical_plan().await?;
+        let state = self.session_state.read().clone();
+        let dir = Path::new(path);
+        fs::create_dir(dir).map_err(|e| {
+            DataFusionError::Execution(format!(
+                "Could not create directory {}: {:?}",
+                path, e
+            ))
+        })?;
+        let file_path = |i: usize| dir.join(format!("part-{}.parquet", i));
+        plan_to_sized_parquet(
+            &state,
+            plan,
+            &file_path,
+            target_file_size,
+            writer_properties,
+        )
+        .await?;
+        Ok(())
+    }
+
     /// Executes a query and writes the results to a partitioned JSON file.
     pub async fn write_json(&self, path: impl AsRef<str>) -> Result<()> {
         let plan = self.create_physical_plan().await?;
         let state = self.session_state.read().clone();
         plan_to_json(&state, plan, path).await
     }
+
+    /// Write a `DataFrame` to CSV files, one per group of input files,
+    /// recording the written files in `checkpoint`.
+    ///
+    /// If the write is interrupted, running it again with the same
+    /// checkpoint over a [`ListingTable`] scanned with that checkpoint (see
+    /// [`ListingTable::with_checkpoint`]) only writes the input files that
+    /// were not written yet. See [`checkpoint`](crate::datasource::checkpoint).
+    ///
+    /// [`ListingTable`]: crate::datasource::listing::ListingTable
+    /// [`ListingTable::with_checkpoint`]: crate::datasource::listing::ListingTable::with_checkpoint
+    pub async fn write_csv_with_checkpoint(
+        &self,
+        path: &str,
+        checkpoint: Arc<dyn Checkpoint>,
+    ) -> Result<()> {
+        let plan = self.create_physical_plan().await?;
+        let state = self.session_state.read().clone();
+        plan_to_csv_with_checkpoint(&state, plan, path, checkpoint).await
+    }
+
+    /// Write a `DataFrame` to Parquet files, one per group of input files,
+    /// recording the written files in `checkpoint`.
+    /// See [`DataFrame::write_csv_with_checkpoint`].
+    pub async fn write_parquet_with_checkpoint(
+        &self,
+        path: &str,
+        writer_properties: Option<WriterProperties>,
+        checkpoint: Arc<dyn Checkpoint>,
+    ) -> Result<()> {
+        let plan = self.create_physical_plan().await?;
+        let state = self.session_state.read().clone();
+        plan_to_parquet_with_checkpoint(&state, plan, path, writer_properties, checkpoint)
+            .await
+    }
+
+    /// Write a `DataFrame` to JSON files, one per group of input files,
+    /// recording the written files in `checkpoint`.
+    /// See [`DataFrame::write_csv_with_checkpoint`].
+    pub async fn write_json_with_checkpoint(
+        &self,
+        path: impl AsRef<str>,
+        checkpoint: Arc<dyn Checkpoint>,
+    ) -> Result<()> {
+        let plan = self.create_physical_plan().await?;
+        let state = self.session_state.read().clone();
+        plan_to_json_with_checkpoint(&state, plan, path, checkpoint).await
+    }
 }
 
 #[async_trait]
@@ -647,11 +949,13 @@ mod tests {
     use std::vec;
 
     use super::*;
-    use crate::execution::options::CsvReadOptions;
+    use crate::execution::options::{CsvReadOptions, ParquetReadOptions};
     use crate::physical_plan::ColumnarValue;
-    use crate::{assert_batches_sorted_eq, execution::context::SessionContext};
+    use crate::{
+        assert_batches_eq, assert_batches_sorted_eq, execution::context::SessionContext,
+    };
     use crate::{logical_plan::*, test_util};
-    use arrow::datatypes::DataType;
+    use arrow::datatypes::{DataType, Field};
     use datafusion_expr::Volatility;
     use datafusion_expr::{
         BuiltInWindowFunction, ScalarFunctionImplementation, WindowFunction,
@@ -780,6 +1084,104 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn verify() -> Result<()> {
+        let t = test_table().await?;
+
+        // c2 is within [1, 5]
+        let df = t.verify(col("c2").gt_eq(lit(1u32)).and(col("c2").lt_eq(lit(5u32))))?;
+        let results = df.collect().await?;
+        let rows: usize = results.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 100);
+
+        let df = t
+            .select_columns(&["c1", "c2"])?
+            .verify(col("c2").lt(lit(5u32)))?;
+        let err = df.collect().await.unwrap_err().to_string();
+        assert!(err.contains("Verification of c2@1 < 5 failed"), "{}", err);
+        assert!(err.contains("| c1 | c2 |"), "{}", err);
+
+        // the predicate must be boolean
+        let err = t.verify(col("c2")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Verify predicate must return boolean values, not UInt32"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn map_batches() -> Result<()> {
+        let t = test_table().await?;
+
+        // keep the first row of each batch
+        let df = t
+            .select_columns(&["c1", "c2"])?
+            .map_batches(|batch| Ok(batch.slice(0, batch.num_rows().min(1))))?
+            .filter(col("c2").gt(lit(0u32)))?;
+        let results = df.collect().await?;
+        let rows: usize = results.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, 1);
+        let names: Vec<_> = df
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.qualified_name())
+            .collect();
+        assert_eq!(names, ["aggregate_test_100.c1", "aggregate_test_100.c2"]);
+
+        // the function must return batches of the declared schema
+        let schema = Arc::new(Schema::new(vec![Field::new("c1", DataType::Utf8, false)]));
+        let df = t.map_batches_with_schema(schema, Ok)?;
+        let err = df.collect().await.unwrap_err().to_string();
+        assert!(
+            err.contains("MapBatches function returned a batch of schema"),
+            "{}",
+            err
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn write_parquet_with_file_size() -> Result<()> {
+        let tmp_dir = tempfile::TempDir::new()?;
+        let path = tmp_dir.path().join("out");
+        let path = path.to_str().unwrap();
+        let df = test_table()
+            .await?
+            .select_columns(&["c1", "c2"])?
+            .sort(vec![col("c2").sort(true, false)])?;
+        df.write_parquet_with_file_size(path, 500, None).await?;
+        let files = std::fs::read_dir(path)?.count();
+        assert!(files > 1, "{} files", files);
+
+        let ctx = SessionContext::new();
+        ctx.register_parquet("out", path, ParquetReadOptions::default())
+            .await?;
+        let batches = ctx
+            .sql("SELECT count(*), min(c2) FROM out")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+-----------------+-------------+",
+            "| COUNT(UInt8(1)) | MIN(out.c2) |",
+            "+-----------------+-------------+",
+            "| 100             | 1           |",
+            "+-----------------+-------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        let err = df
+            .write_parquet_with_file_size(path, 500, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Could not create directory"));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn explain() -> Result<()> {
         // build query using Table API
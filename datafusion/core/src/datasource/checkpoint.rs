@@ -0,0 +1,223 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Checkpoints recording the progress of long running writes, so that a
+//! restarted query only processes the input files it did not write yet.
+//!
+//! A checkpointed write (e.g. [`DataFrame::write_csv_with_checkpoint`]) writes
+//! one output file per group of input files, and commits the output file
+//! along with its input files to the [`Checkpoint`] once the output file is
+//! complete. A [`ListingTable`] scanned with the same checkpoint (see
+//! [`ListingTable::with_checkpoint`]) skips the committed input files, and
+//! the write removes the output files that were not committed, so that every
+//! input file ends up written exactly once.
+//!
+//! [`DataFrame::write_csv_with_checkpoint`]: crate::dataframe::DataFrame::write_csv_with_checkpoint
+//! [`ListingTable`]: crate::datasource::listing::ListingTable
+//! [`ListingTable::with_checkpoint`]: crate::datasource::listing::ListingTable::with_checkpoint
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use parking_lot::Mutex;
+
+use crate::error::{DataFusionError, Result};
+
+/// An output file fully written from a group of input files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckpointEntry {
+    /// Name of the output file, relative to the output directory
+    pub output: String,
+    /// Paths of the input files consumed to write the output file
+    pub inputs: Vec<String>,
+}
+
+/// Durable record of the output files written so far
+pub trait Checkpoint: Debug + Send + Sync {
+    /// All the entries committed so far
+    fn entries(&self) -> Result<Vec<CheckpointEntry>>;
+
+    /// Records that the output file of `entry` is complete. The entry must
+    /// be durable once this returns.
+    fn commit(&self, entry: CheckpointEntry) -> Result<()>;
+
+    /// Paths of the input files consumed by the committed entries
+    fn consumed_files(&self) -> Result<HashSet<String>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .flat_map(|entry| entry.inputs)
+            .collect())
+    }
+}
+
+/// A [`Checkpoint`] kept in memory, mostly useful for tests
+#[derive(Debug, Default)]
+pub struct MemoryCheckpoint {
+    entries: Mutex<Vec<CheckpointEntry>>,
+}
+
+impl MemoryCheckpoint {
+    /// Create an empty checkpoint
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Checkpoint for MemoryCheckpoint {
+    fn entries(&self) -> Result<Vec<CheckpointEntry>> {
+        Ok(self.entries.lock().clone())
+    }
+
+    fn commit(&self, entry: CheckpointEntry) -> Result<()> {
+        self.entries.lock().push(entry);
+        Ok(())
+    }
+}
+
+/// A [`Checkpoint`] stored in a local file, one line per entry holding the
+/// output file followed by its input files, separated by tabs.
+///
+/// Each commit appends a line and syncs the file. A line interrupted by a
+/// crash is discarded when the checkpoint is opened again.
+#[derive(Debug)]
+pub struct LocalFileCheckpoint {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl LocalFileCheckpoint {
+    /// Open the checkpoint stored at `path`, creating it if it does not exist
+    pub fn try_new(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+
+        // drop the last line if it was not completely written
+        let content = fs::read_to_string(&path)?;
+        let complete_len = content.rfind('\n').map(|pos| pos + 1).unwrap_or(0);
+        if complete_len < content.len() {
+            file.set_len(complete_len as u64)?;
+            file.sync_all()?;
+        }
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Path of the file holding the checkpoint
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Checkpoint for LocalFileCheckpoint {
+    fn entries(&self) -> Result<Vec<CheckpointEntry>> {
+        // hold the lock so that no commit is read half written
+        let _file = self.file.lock();
+        let content = fs::read_to_string(&self.path)?;
+        Ok(content
+            .split_terminator('\n')
+            .map(|line| {
+                let mut fields = line.split('\t').map(|field| field.to_owned());
+                CheckpointEntry {
+                    output: fields.next().unwrap_or_default(),
+                    inputs: fields.collect(),
+                }
+            })
+            .collect())
+    }
+
+    fn commit(&self, entry: CheckpointEntry) -> Result<()> {
+        let fields = std::iter::once(&entry.output).chain(entry.inputs.iter());
+        let mut line = String::new();
+        for (i, field) in fields.enumerate() {
+            if field.contains(|c| c == '\t' || c == '\n') {
+                return Err(DataFusionError::Execution(format!(
+                    "Cannot record path {:?} in checkpoint {}",
+                    field,
+                    self.path.display()
+                )));
+            }
+            if i > 0 {
+                line.push('\t');
+            }
+            line.push_str(field);
+        }
+        line.push('\n');
+
+        let mut file = self.file.lock();
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(output: &str, inputs: &[&str]) -> CheckpointEntry {
+        CheckpointEntry {
+            output: output.to_owned(),
+            inputs: inputs.iter().map(|input| input.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn local_file_checkpoint() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("checkpoint");
+
+        let checkpoint = LocalFileCheckpoint::try_new(&path)?;
+        assert!(checkpoint.entries()?.is_empty());
+        checkpoint.commit(entry("part-1.csv", &["a.csv", "b.csv"]))?;
+        checkpoint.commit(entry("part-2.csv", &["c.csv"]))?;
+        assert!(checkpoint
+            .commit(entry("part-3.csv", &["d\te.csv"]))
+            .is_err());
+
+        // simulate a crash in the middle of a commit
+        let mut file = OpenOptions::new().append(true).open(&path)?;
+        file.write_all(b"part-3.csv\td.c")?;
+        drop(checkpoint);
+
+        let checkpoint = LocalFileCheckpoint::try_new(&path)?;
+        assert_eq!(
+            checkpoint.entries()?,
+            vec![
+                entry("part-1.csv", &["a.csv", "b.csv"]),
+                entry("part-2.csv", &["c.csv"])
+            ]
+        );
+        checkpoint.commit(entry("part-3.csv", &["d.csv"]))?;
+
+        let mut consumed = checkpoint.consumed_files()?.into_iter().collect::<Vec<_>>();
+        consumed.sort();
+        assert_eq!(consumed, vec!["a.csv", "b.csv", "c.csv", "d.csv"]);
+        Ok(())
+    }
+}
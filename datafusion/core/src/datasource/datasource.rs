@@ -24,7 +24,9 @@ use async_trait::async_trait;
 use datafusion_expr::{TableProviderFilterPushDown, TableType};
 
 use crate::arrow::datatypes::SchemaRef;
-use crate::error::Result;
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::SessionState;
+use crate::logical_plan::Constraints;
 use crate::logical_plan::Expr;
 use crate::physical_plan::ExecutionPlan;
 
@@ -66,4 +68,42 @@ pub trait TableProvider: Sync + Send {
     ) -> Result<TableProviderFilterPushDown> {
         Ok(TableProviderFilterPushDown::Unsupported)
     }
+
+    /// Get the constraints declared on the columns of this table, if any,
+    /// e.g. its primary key. The rows of the table are identified by the
+    /// values of the constrained columns, which lets the planner relax the
+    /// validation of the aggregations (e.g. select any column of the table
+    /// when grouping by its key) and optimize the plans.
+    fn constraints(&self) -> Option<&Constraints> {
+        None
+    }
+
+    /// Deletes the rows of the table for which `predicate` is true, or all
+    /// of them, returning their number. This is the implementation of
+    /// `DELETE`. The predicate is over the unqualified columns of the table.
+    async fn delete(
+        &self,
+        _state: &SessionState,
+        _predicate: Option<&Expr>,
+    ) -> Result<u64> {
+        Err(DataFusionError::NotImplemented(
+            "DELETE is not supported by this table".to_string(),
+        ))
+    }
+
+    /// Sets the columns of `assignments` to their values in the rows of the
+    /// table for which `predicate` is true, or all of them, returning their
+    /// number. This is the implementation of `UPDATE`. The values and the
+    /// predicate are over the unqualified columns of the table, before the
+    /// update.
+    async fn update(
+        &self,
+        _state: &SessionState,
+        _assignments: &[(String, Expr)],
+        _predicate: Option<&Expr>,
+    ) -> Result<u64> {
+        Err(DataFusionError::NotImplemented(
+            "UPDATE is not supported by this table".to_string(),
+        ))
+    }
 }
@@ -0,0 +1,1134 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Normalization of the arrays of the layouts newer Arrow producers emit but
+//! this version of Arrow can not import, at the boundary of the imported
+//! `ArrowArrayStream`s:
+//!
+//! * the string and binary view arrays (formats `vu` and `vz`) are copied
+//!   into string and binary arrays (formats `u` and `z`),
+//! * the run end encoded arrays (format `+r`) are decoded into arrays of
+//!   their values, when their values are primitive, boolean, string or
+//!   binary arrays.
+//!
+//! The arrays of the other layouts are passed through without copying their
+//! buffers. See [`normalize_stream`].
+
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+
+use arrow::buffer::{Buffer, MutableBuffer};
+use arrow::ffi_stream::FFI_ArrowArrayStream;
+
+/// The `ArrowSchema` of the C data interface
+#[repr(C)]
+struct ArrowSchema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut ArrowSchema,
+    dictionary: *mut ArrowSchema,
+    release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    private_data: *mut c_void,
+}
+
+/// The `ArrowArray` of the C data interface
+#[repr(C)]
+struct ArrowArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut ArrowArray,
+    dictionary: *mut ArrowArray,
+    release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    private_data: *mut c_void,
+}
+
+/// The `ArrowArrayStream` of the C stream interface, the layout of
+/// [`FFI_ArrowArrayStream`]
+#[repr(C)]
+struct ArrowArrayStream {
+    get_schema:
+        Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowSchema) -> c_int>,
+    get_next:
+        Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowArray) -> c_int>,
+    get_last_error: Option<unsafe extern "C" fn(*mut ArrowArrayStream) -> *const c_char>,
+    release: Option<unsafe extern "C" fn(*mut ArrowArrayStream)>,
+    private_data: *mut c_void,
+}
+
+impl ArrowSchema {
+    fn empty() -> Self {
+        Self {
+            format: ptr::null(),
+            name: ptr::null(),
+            metadata: ptr::null(),
+            flags: 0,
+            n_children: 0,
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: None,
+            private_data: ptr::null_mut(),
+        }
+    }
+
+    unsafe fn format(&self) -> Result<&str, String> {
+        if self.format.is_null() {
+            return Err("The format of an imported schema is null".to_string());
+        }
+        CStr::from_ptr(self.format)
+            .to_str()
+            .map_err(|e| format!("Invalid format of an imported schema: {}", e))
+    }
+
+    unsafe fn child(&self, i: usize) -> &ArrowSchema {
+        &**self.children.add(i)
+    }
+}
+
+impl ArrowArray {
+    fn empty() -> Self {
+        Self {
+            length: 0,
+            null_count: 0,
+            offset: 0,
+            n_buffers: 0,
+            n_children: 0,
+            buffers: ptr::null_mut(),
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: None,
+            private_data: ptr::null_mut(),
+        }
+    }
+
+    unsafe fn buffer(&self, i: usize) -> *const u8 {
+        *self.buffers.add(i) as *const u8
+    }
+
+    unsafe fn child(&self, i: usize) -> &ArrowArray {
+        &**self.children.add(i)
+    }
+
+    /// Whether the `i`th value of the array, counting its offset, is valid
+    unsafe fn is_valid(&self, i: usize) -> bool {
+        let validity = self.buffer(0);
+        validity.is_null() || (*validity.add(i / 8) >> (i % 8)) & 1 == 1
+    }
+}
+
+/// Wraps `stream` into a stream normalizing the arrays of the layouts this
+/// version of Arrow can not import, see the [module documentation](self).
+/// The batches of the other layouts are passed through as they are.
+pub fn normalize_stream(stream: FFI_ArrowArrayStream) -> FFI_ArrowArrayStream {
+    // SAFETY: FFI_ArrowArrayStream is the ArrowArrayStream of the C stream
+    // interface, whose release is now owned by the wrapping stream
+    let inner: ArrowArrayStream = unsafe { mem::transmute(stream) };
+    let private = Box::new(NormalizingStream {
+        inner,
+        plan: None,
+        last_error: None,
+    });
+    let stream = ArrowArrayStream {
+        get_schema: Some(get_schema),
+        get_next: Some(get_next),
+        get_last_error: Some(get_last_error),
+        release: Some(release_stream),
+        private_data: Box::into_raw(private) as *mut c_void,
+    };
+    // SAFETY: as above
+    unsafe { mem::transmute(stream) }
+}
+
+/// How the arrays of a schema node are normalized
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Plan {
+    /// The arrays are passed through as they are
+    Keep,
+    /// The arrays are kept, with some of their descendants normalized
+    Children {
+        children: Vec<Plan>,
+        dictionary: Option<Box<Plan>>,
+    },
+    /// The view arrays are copied into string or binary arrays
+    View { binary: bool },
+    /// The run end encoded arrays are decoded into arrays of their values
+    RunEnd {
+        /// The width of the run ends, in bytes
+        run_end_width: usize,
+        values: Values,
+    },
+}
+
+/// The layout of the values of a run end encoded array
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Values {
+    Boolean,
+    /// Values of the given width, in bytes
+    FixedWidth(usize),
+    /// Strings or binaries with 32 or 64 bits offsets
+    Bytes {
+        large: bool,
+    },
+    /// String or binary views
+    View {
+        binary: bool,
+    },
+}
+
+/// Returns how the arrays of `schema` are normalized
+unsafe fn plan_schema(schema: &ArrowSchema) -> Result<Plan, String> {
+    match schema.format()? {
+        "vu" => Ok(Plan::View { binary: false }),
+        "vz" => Ok(Plan::View { binary: true }),
+        "+r" => {
+            if schema.n_children != 2 {
+                return Err(format!(
+                    "A run end encoded schema has {} children, expected 2",
+                    schema.n_children
+                ));
+            }
+            let run_end_width = match schema.child(0).format()? {
+                "s" => 2,
+                "i" => 4,
+                "l" => 8,
+                other => {
+                    return Err(format!(
+                        "Invalid format {} of the run ends of a run end encoded array",
+                        other
+                    ))
+                }
+            };
+            Ok(Plan::RunEnd {
+                run_end_width,
+                values: plan_values(schema.child(1))?,
+            })
+        }
+        _ => {
+            let children = (0..schema.n_children as usize)
+                .map(|i| plan_schema(schema.child(i)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let dictionary = if schema.dictionary.is_null() {
+                None
+            } else {
+                Some(Box::new(plan_schema(&*schema.dictionary)?))
+            };
+            let keep = |plan: &Plan| *plan == Plan::Keep;
+            if children.iter().all(keep) && dictionary.as_deref().map_or(true, keep) {
+                Ok(Plan::Keep)
+            } else {
+                Ok(Plan::Children {
+                    children,
+                    dictionary,
+                })
+            }
+        }
+    }
+}
+
+/// Returns the layout of the values `schema` of a run end encoded array
+unsafe fn plan_values(schema: &ArrowSchema) -> Result<Values, String> {
+    let format = schema.format()?;
+    let values = match format {
+        _ if !schema.dictionary.is_null() => None,
+        "b" => Some(Values::Boolean),
+        "u" | "z" => Some(Values::Bytes { large: false }),
+        "U" | "Z" => Some(Values::Bytes { large: true }),
+        "vu" => Some(Values::View { binary: false }),
+        "vz" => Some(Values::View { binary: true }),
+        _ => fixed_width(format).map(Values::FixedWidth),
+    };
+    values.ok_or_else(|| {
+        format!(
+            "Run end encoded arrays of values of format {} are not supported",
+            format
+        )
+    })
+}
+
+/// Returns the width in bytes of the values of the primitive `format`
+fn fixed_width(format: &str) -> Option<usize> {
+    match format {
+        "c" | "C" => Some(1),
+        "s" | "S" | "e" => Some(2),
+        "i" | "I" | "f" | "tdD" | "tts" | "ttm" | "tiM" => Some(4),
+        "l" | "L" | "g" | "tdm" | "ttu" | "ttn" | "tiD" => Some(8),
+        "tin" => Some(16),
+        _ if format.starts_with("ts") || format.starts_with("tD") => Some(8),
+        _ if format.starts_with("d:") => {
+            // d:precision,scale[,bit width]
+            match format[2..].split(',').nth(2) {
+                None | Some("128") => Some(16),
+                Some("256") => Some(32),
+                Some(_) => None,
+            }
+        }
+        _ if format.starts_with("w:") => format[2..].parse().ok(),
+        _ => None,
+    }
+}
+
+/// The format of the normalized arrays of view arrays
+fn view_format(binary: bool) -> *const c_char {
+    let format: &'static [u8] = if binary { b"z\0" } else { b"u\0" };
+    format.as_ptr() as *const c_char
+}
+
+/// The private data of the schemas created here
+struct SchemaNode {
+    /// The children of the schema, in the schema of the producer or created
+    /// here
+    children: Vec<*mut ArrowSchema>,
+    /// The children and the dictionary created here, released with the node
+    owned: Vec<*mut ArrowSchema>,
+    /// The schema of the producer the node borrows from, released with the
+    /// node
+    source: Option<ArrowSchema>,
+}
+
+/// Creates a schema node with the given `children`, which are released
+/// with it if `owned`
+fn new_schema(
+    template: &ArrowSchema,
+    children: Vec<(*mut ArrowSchema, bool)>,
+    dictionary: Option<(*mut ArrowSchema, bool)>,
+) -> ArrowSchema {
+    let owned = children
+        .iter()
+        .chain(dictionary.iter())
+        .filter(|(_, owned)| *owned)
+        .map(|(child, _)| *child)
+        .collect();
+    let mut node = Box::new(SchemaNode {
+        children: children.into_iter().map(|(child, _)| child).collect(),
+        owned,
+        source: None,
+    });
+    ArrowSchema {
+        format: template.format,
+        name: template.name,
+        metadata: template.metadata,
+        flags: template.flags,
+        n_children: node.children.len() as i64,
+        children: if node.children.is_empty() {
+            ptr::null_mut()
+        } else {
+            node.children.as_mut_ptr()
+        },
+        dictionary: dictionary.map_or(ptr::null_mut(), |(dictionary, _)| dictionary),
+        release: Some(release_schema),
+        private_data: Box::into_raw(node) as *mut c_void,
+    }
+}
+
+unsafe extern "C" fn release_schema(schema: *mut ArrowSchema) {
+    if schema.is_null() || (*schema).release.is_none() {
+        return;
+    }
+    let node = Box::from_raw((*schema).private_data as *mut SchemaNode);
+    for child in &node.owned {
+        if let Some(release) = (**child).release {
+            release(*child);
+        }
+        drop(Box::from_raw(*child));
+    }
+    if let Some(mut source) = node.source {
+        if let Some(release) = source.release {
+            release(&mut source);
+        }
+    }
+    (*schema).release = None;
+}
+
+/// Returns the normalized schema of `source`, borrowing from it
+unsafe fn normalize_schema(source: &ArrowSchema, plan: &Plan) -> ArrowSchema {
+    match plan {
+        Plan::Keep => unreachable!("Kept schemas are not normalized"),
+        Plan::Children {
+            children,
+            dictionary,
+        } => {
+            let children = children
+                .iter()
+                .enumerate()
+                .map(|(i, plan)| normalize_child_schema(*source.children.add(i), plan))
+                .collect();
+            let dictionary = match dictionary {
+                Some(plan) => Some(normalize_child_schema(source.dictionary, plan)),
+                _ => None,
+            };
+            new_schema(source, children, dictionary)
+        }
+        Plan::View { binary } => {
+            let mut schema = new_schema(source, vec![], None);
+            schema.format = view_format(*binary);
+            schema
+        }
+        Plan::RunEnd { values, .. } => {
+            let mut schema = new_schema(source, vec![], None);
+            schema.format = match values {
+                Values::View { binary } => view_format(*binary),
+                _ => source.child(1).format,
+            };
+            schema
+        }
+    }
+}
+
+/// Returns the normalized `child`, and whether it was created here
+unsafe fn normalize_child_schema(
+    child: *mut ArrowSchema,
+    plan: &Plan,
+) -> (*mut ArrowSchema, bool) {
+    match plan {
+        Plan::Keep => (child, false),
+        plan => {
+            let normalized = normalize_schema(&*child, plan);
+            (Box::into_raw(Box::new(normalized)), true)
+        }
+    }
+}
+
+/// The private data of the arrays created here
+struct ArrayNode {
+    /// The buffers of the array, of the producer or created here
+    buffers: Vec<*const c_void>,
+    /// The buffers created here
+    _owned_buffers: Vec<Buffer>,
+    /// The children of the array, of the producer or created here
+    children: Vec<*mut ArrowArray>,
+    /// The children and the dictionary created here, released with the node
+    owned: Vec<*mut ArrowArray>,
+    /// The array of the producer the node borrows from, released with the
+    /// node
+    source: Option<ArrowArray>,
+}
+
+/// The layout of an array created here
+struct NewArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    buffers: Vec<*const c_void>,
+    owned_buffers: Vec<Buffer>,
+    children: Vec<(*mut ArrowArray, bool)>,
+    dictionary: Option<(*mut ArrowArray, bool)>,
+}
+
+impl NewArray {
+    fn into_array(self) -> ArrowArray {
+        let owned = self
+            .children
+            .iter()
+            .chain(self.dictionary.iter())
+            .filter(|(_, owned)| *owned)
+            .map(|(child, _)| *child)
+            .collect();
+        let mut node = Box::new(ArrayNode {
+            buffers: self.buffers,
+            _owned_buffers: self.owned_buffers,
+            children: self.children.into_iter().map(|(child, _)| child).collect(),
+            owned,
+            source: None,
+        });
+        ArrowArray {
+            length: self.length,
+            null_count: self.null_count,
+            offset: self.offset,
+            n_buffers: node.buffers.len() as i64,
+            n_children: node.children.len() as i64,
+            buffers: if node.buffers.is_empty() {
+                ptr::null_mut()
+            } else {
+                node.buffers.as_mut_ptr()
+            },
+            children: if node.children.is_empty() {
+                ptr::null_mut()
+            } else {
+                node.children.as_mut_ptr()
+            },
+            dictionary: self
+                .dictionary
+                .map_or(ptr::null_mut(), |(dictionary, _)| dictionary),
+            release: Some(release_array),
+            private_data: Box::into_raw(node) as *mut c_void,
+        }
+    }
+}
+
+unsafe extern "C" fn release_array(array: *mut ArrowArray) {
+    if array.is_null() || (*array).release.is_none() {
+        return;
+    }
+    let node = Box::from_raw((*array).private_data as *mut ArrayNode);
+    for child in &node.owned {
+        if let Some(release) = (**child).release {
+            release(*child);
+        }
+        drop(Box::from_raw(*child));
+    }
+    if let Some(mut source) = node.source {
+        if let Some(release) = source.release {
+            release(&mut source);
+        }
+    }
+    (*array).release = None;
+}
+
+/// Returns the normalized array of `source`, borrowing from it
+unsafe fn normalize_array(
+    source: &ArrowArray,
+    plan: &Plan,
+) -> Result<ArrowArray, String> {
+    let array = match plan {
+        Plan::Keep => unreachable!("Kept arrays are not normalized"),
+        Plan::Children {
+            children,
+            dictionary,
+        } => {
+            if source.n_children as usize != children.len() {
+                return Err(format!(
+                    "An imported array has {} children, its schema {}",
+                    source.n_children,
+                    children.len()
+                ));
+            }
+            let mut new_children = Vec::with_capacity(children.len());
+            for (i, plan) in children.iter().enumerate() {
+                new_children.push(normalize_child_array(*source.children.add(i), plan)?);
+            }
+            let dictionary = match dictionary {
+                Some(plan) if !source.dictionary.is_null() => {
+                    Some(normalize_child_array(source.dictionary, plan)?)
+                }
+                _ => None,
+            };
+            NewArray {
+                length: source.length,
+                null_count: source.null_count,
+                offset: source.offset,
+                buffers: (0..source.n_buffers as usize)
+                    .map(|i| *source.buffers.add(i))
+                    .collect(),
+                owned_buffers: vec![],
+                children: new_children,
+                dictionary,
+            }
+        }
+        Plan::View { .. } => {
+            if source.n_buffers < 3 {
+                return Err(format!(
+                    "A view array has {} buffers, expected at least 3",
+                    source.n_buffers
+                ));
+            }
+            // the offsets before the offset of the array are empty, so that
+            // its validity buffer is kept
+            let offset = source.offset as usize;
+            let len = offset + source.length as usize;
+            let mut offsets = MutableBuffer::new((len + 1) * mem::size_of::<i32>());
+            let mut data = MutableBuffer::new(0);
+            offsets.push(0i32);
+            for i in 0..len {
+                if i >= offset && source.is_valid(i) {
+                    data.extend_from_slice(view_value(source, i)?);
+                }
+                offsets.push(offset_i32(data.len())?);
+            }
+            let (offsets, data): (Buffer, Buffer) = (offsets.into(), data.into());
+            NewArray {
+                length: source.length,
+                null_count: source.null_count,
+                offset: source.offset,
+                buffers: vec![
+                    *source.buffers,
+                    offsets.as_ptr() as *const c_void,
+                    data.as_ptr() as *const c_void,
+                ],
+                owned_buffers: vec![offsets, data],
+                children: vec![],
+                dictionary: None,
+            }
+        }
+        Plan::RunEnd {
+            run_end_width,
+            values,
+        } => decode_run_ends(source, *run_end_width, *values)?,
+    };
+    Ok(array.into_array())
+}
+
+/// Returns the normalized `child`, and whether it was created here
+unsafe fn normalize_child_array(
+    child: *mut ArrowArray,
+    plan: &Plan,
+) -> Result<(*mut ArrowArray, bool), String> {
+    match plan {
+        Plan::Keep => Ok((child, false)),
+        plan => {
+            let normalized = normalize_array(&*child, plan)?;
+            Ok((Box::into_raw(Box::new(normalized)), true))
+        }
+    }
+}
+
+fn offset_i32(offset: usize) -> Result<i32, String> {
+    if offset > i32::MAX as usize {
+        Err("The values of a normalized array exceed 2 GiB".to_string())
+    } else {
+        Ok(offset as i32)
+    }
+}
+
+/// Returns the bytes of the `i`th view of `array`, counting its offset
+unsafe fn view_value(array: &ArrowArray, i: usize) -> Result<&[u8], String> {
+    // a view is the length of the value, followed by the value if it is at
+    // most 12 bytes long, and otherwise by a prefix of the value, the index
+    // of its data buffer and its offset in the data buffer
+    let view = array.buffer(1).add(i * 16);
+    let length = ptr::read_unaligned(view as *const i32) as usize;
+    if length <= 12 {
+        return Ok(std::slice::from_raw_parts(view.add(4), length));
+    }
+    let index = ptr::read_unaligned(view.add(8) as *const i32) as usize;
+    let offset = ptr::read_unaligned(view.add(12) as *const i32) as usize;
+    // the data buffers are followed by the buffer of their sizes
+    if index + 3 >= array.n_buffers as usize {
+        return Err(format!(
+            "A view refers to the data buffer {} of an array with {} buffers",
+            index, array.n_buffers
+        ));
+    }
+    Ok(std::slice::from_raw_parts(
+        array.buffer(2 + index).add(offset),
+        length,
+    ))
+}
+
+/// Returns the decoded array of the run end encoded array `source`
+unsafe fn decode_run_ends(
+    source: &ArrowArray,
+    run_end_width: usize,
+    values_layout: Values,
+) -> Result<NewArray, String> {
+    if source.n_children != 2 {
+        return Err(format!(
+            "A run end encoded array has {} children, expected 2",
+            source.n_children
+        ));
+    }
+    let run_ends = source.child(0);
+    let values = source.child(1);
+    let run_end = |j: usize| -> i64 {
+        let j = run_ends.offset as usize + j;
+        let run_ends = run_ends.buffer(1);
+        match run_end_width {
+            2 => ptr::read_unaligned((run_ends as *const i16).add(j)) as i64,
+            4 => ptr::read_unaligned((run_ends as *const i32).add(j)) as i64,
+            _ => ptr::read_unaligned((run_ends as *const i64).add(j)),
+        }
+    };
+
+    // the index of the value of each row, in the values array
+    let offset = source.offset;
+    let length = source.length as usize;
+    let num_runs = run_ends.length as usize;
+    let mut indices = Vec::with_capacity(length);
+    let mut run = {
+        // the first run ending after the offset
+        let (mut low, mut high) = (0, num_runs);
+        while low < high {
+            let mid = (low + high) / 2;
+            if run_end(mid) <= offset {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    };
+    for row in offset..offset + length as i64 {
+        while run < num_runs && run_end(run) <= row {
+            run += 1;
+        }
+        if run >= num_runs {
+            return Err(
+                "The run ends of a run end encoded array are too short".to_string()
+            );
+        }
+        indices.push(values.offset as usize + run);
+    }
+
+    let mut validity = MutableBuffer::new_null(length);
+    let mut null_count = 0;
+    for (row, index) in indices.iter().enumerate() {
+        if values.is_valid(*index) {
+            arrow::util::bit_util::set_bit(validity.as_slice_mut(), row);
+        } else {
+            null_count += 1;
+        }
+    }
+    let mut buffers: Vec<Buffer> = vec![validity.into()];
+
+    match values_layout {
+        Values::Boolean => {
+            let mut bits = MutableBuffer::new_null(length);
+            for (row, index) in indices.iter().enumerate() {
+                if (*values.buffer(1).add(index / 8) >> (index % 8)) & 1 == 1 {
+                    arrow::util::bit_util::set_bit(bits.as_slice_mut(), row);
+                }
+            }
+            buffers.push(bits.into());
+        }
+        Values::FixedWidth(width) => {
+            let mut data = MutableBuffer::new(length * width);
+            for index in &indices {
+                let value = values.buffer(1).add(index * width);
+                data.extend_from_slice(std::slice::from_raw_parts(value, width));
+            }
+            buffers.push(data.into());
+        }
+        Values::Bytes { large } => {
+            let value_offset = |i: usize| -> usize {
+                if large {
+                    ptr::read_unaligned((values.buffer(1) as *const i64).add(i)) as usize
+                } else {
+                    ptr::read_unaligned((values.buffer(1) as *const i32).add(i)) as usize
+                }
+            };
+            let offset_width = if large { 8 } else { 4 };
+            let mut offsets = MutableBuffer::new((length + 1) * offset_width);
+            let mut data = MutableBuffer::new(0);
+            let push_offset = |offsets: &mut MutableBuffer, offset: usize| {
+                if large {
+                    offsets.push(offset as i64);
+                    Ok(())
+                } else {
+                    offset_i32(offset).map(|offset| offsets.push(offset))
+                }
+            };
+            push_offset(&mut offsets, 0)?;
+            for index in &indices {
+                let (start, end) = (value_offset(*index), value_offset(index + 1));
+                data.extend_from_slice(std::slice::from_raw_parts(
+                    values.buffer(2).add(start),
+                    end - start,
+                ));
+                push_offset(&mut offsets, data.len())?;
+            }
+            buffers.push(offsets.into());
+            buffers.push(data.into());
+        }
+        Values::View { .. } => {
+            let mut offsets = MutableBuffer::new((length + 1) * mem::size_of::<i32>());
+            let mut data = MutableBuffer::new(0);
+            offsets.push(0i32);
+            for index in &indices {
+                if values.is_valid(*index) {
+                    data.extend_from_slice(view_value(values, *index)?);
+                }
+                offsets.push(offset_i32(data.len())?);
+            }
+            buffers.push(offsets.into());
+            buffers.push(data.into());
+        }
+    }
+
+    Ok(NewArray {
+        length: length as i64,
+        null_count,
+        offset: 0,
+        buffers: buffers
+            .iter()
+            .enumerate()
+            .map(|(i, buffer)| {
+                if i == 0 && null_count == 0 {
+                    ptr::null()
+                } else {
+                    buffer.as_ptr() as *const c_void
+                }
+            })
+            .collect(),
+        owned_buffers: buffers,
+        children: vec![],
+        dictionary: None,
+    })
+}
+
+/// The private data of the normalizing streams
+struct NormalizingStream {
+    /// The stream of the producer
+    inner: ArrowArrayStream,
+    /// How the arrays of the stream are normalized, once its schema is read
+    plan: Option<Plan>,
+    /// The error of the last call, if it failed while normalizing
+    last_error: Option<CString>,
+}
+
+/// The failure of a call of a normalizing stream
+enum StreamError {
+    /// The stream of the producer failed with the given error code
+    Inner(c_int),
+    /// The arrays of the stream could not be normalized
+    Normalize(String),
+}
+
+impl From<String> for StreamError {
+    fn from(message: String) -> Self {
+        Self::Normalize(message)
+    }
+}
+
+/// The error code of the failures to normalize arrays, `EINVAL`
+const EINVAL: c_int = 22;
+
+impl NormalizingStream {
+    unsafe fn record(&mut self, result: Result<(), StreamError>) -> c_int {
+        self.last_error = None;
+        match result {
+            Ok(()) => 0,
+            Err(StreamError::Inner(code)) => code,
+            Err(StreamError::Normalize(message)) => {
+                self.last_error = CString::new(message.replace('\0', " ")).ok();
+                EINVAL
+            }
+        }
+    }
+
+    /// Reads the schema of the stream of the producer
+    unsafe fn inner_schema(&mut self) -> Result<ArrowSchema, StreamError> {
+        let get_schema = self.inner.get_schema.ok_or_else(|| {
+            StreamError::Normalize("The imported stream was released".to_string())
+        })?;
+        let mut schema = ArrowSchema::empty();
+        match get_schema(&mut self.inner, &mut schema) {
+            0 => Ok(schema),
+            code => Err(StreamError::Inner(code)),
+        }
+    }
+
+    /// Returns how the arrays of the stream are normalized, reading its
+    /// schema if it was not read yet
+    unsafe fn plan(&mut self) -> Result<&Plan, StreamError> {
+        if self.plan.is_none() {
+            let mut schema = self.inner_schema()?;
+            let plan = plan_schema(&schema);
+            if let Some(release) = schema.release {
+                release(&mut schema);
+            }
+            self.plan = Some(plan?);
+        }
+        Ok(self.plan.as_ref().unwrap())
+    }
+
+    unsafe fn get_schema(&mut self, out: *mut ArrowSchema) -> Result<(), StreamError> {
+        let mut schema = self.inner_schema()?;
+        let plan = match plan_schema(&schema) {
+            Ok(plan) => plan,
+            Err(message) => {
+                if let Some(release) = schema.release {
+                    release(&mut schema);
+                }
+                return Err(StreamError::Normalize(message));
+            }
+        };
+        if plan == Plan::Keep {
+            ptr::write(out, schema);
+        } else {
+            let mut normalized = normalize_schema(&schema, &plan);
+            (*(normalized.private_data as *mut SchemaNode)).source = Some(schema);
+            ptr::write(out, normalized);
+        }
+        self.plan = Some(plan);
+        Ok(())
+    }
+
+    unsafe fn get_next(&mut self, out: *mut ArrowArray) -> Result<(), StreamError> {
+        let plan = self.plan()?.clone();
+        let get_next = self.inner.get_next.ok_or_else(|| {
+            StreamError::Normalize("The imported stream was released".to_string())
+        })?;
+        let mut array = ArrowArray::empty();
+        match get_next(&mut self.inner, &mut array) {
+            0 => {}
+            code => return Err(StreamError::Inner(code)),
+        }
+        // a released array marks the end of the stream
+        if array.release.is_none() || plan == Plan::Keep {
+            ptr::write(out, array);
+            return Ok(());
+        }
+        match normalize_array(&array, &plan) {
+            Ok(mut normalized) => {
+                (*(normalized.private_data as *mut ArrayNode)).source = Some(array);
+                ptr::write(out, normalized);
+                Ok(())
+            }
+            Err(message) => {
+                if let Some(release) = array.release {
+                    release(&mut array);
+                }
+                Err(StreamError::Normalize(message))
+            }
+        }
+    }
+}
+
+unsafe fn private_stream<'a>(stream: *mut ArrowArrayStream) -> &'a mut NormalizingStream {
+    &mut *((*stream).private_data as *mut NormalizingStream)
+}
+
+unsafe extern "C" fn get_schema(
+    stream: *mut ArrowArrayStream,
+    out: *mut ArrowSchema,
+) -> c_int {
+    let private = private_stream(stream);
+    let result = private.get_schema(out);
+    private.record(result)
+}
+
+unsafe extern "C" fn get_next(
+    stream: *mut ArrowArrayStream,
+    out: *mut ArrowArray,
+) -> c_int {
+    let private = private_stream(stream);
+    let result = private.get_next(out);
+    private.record(result)
+}
+
+unsafe extern "C" fn get_last_error(stream: *mut ArrowArrayStream) -> *const c_char {
+    let private = private_stream(stream);
+    match (&private.last_error, private.inner.get_last_error) {
+        (Some(message), _) => message.as_ptr(),
+        (None, Some(get_last_error)) => get_last_error(&mut private.inner),
+        (None, None) => ptr::null(),
+    }
+}
+
+unsafe extern "C" fn release_stream(stream: *mut ArrowArrayStream) {
+    if stream.is_null() || (*stream).release.is_none() {
+        return;
+    }
+    let mut private = Box::from_raw((*stream).private_data as *mut NormalizingStream);
+    if let Some(release) = private.inner.release {
+        release(&mut private.inner);
+    }
+    (*stream).release = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::datasource::ffi_stream::ArrowArrayStreamTable;
+    use crate::error::Result;
+    use crate::prelude::{SessionConfig, SessionContext};
+    use std::sync::Arc;
+
+    fn format(format: &'static [u8]) -> *const c_char {
+        format.as_ptr() as *const c_char
+    }
+
+    /// A schema node of the test producer, created as the normalized nodes
+    fn schema(
+        format: *const c_char,
+        name: &'static [u8],
+        children: Vec<ArrowSchema>,
+    ) -> ArrowSchema {
+        let template = ArrowSchema {
+            format,
+            name: name.as_ptr() as *const c_char,
+            flags: 2, // nullable
+            ..ArrowSchema::empty()
+        };
+        let children = children
+            .into_iter()
+            .map(|child| (Box::into_raw(Box::new(child)), true))
+            .collect();
+        new_schema(&template, children, None)
+    }
+
+    /// An array of the test producer
+    fn array(
+        length: i64,
+        null_count: i64,
+        buffers: Vec<Option<Buffer>>,
+        children: Vec<ArrowArray>,
+    ) -> ArrowArray {
+        NewArray {
+            length,
+            null_count,
+            offset: 0,
+            buffers: buffers
+                .iter()
+                .map(|buffer| {
+                    buffer
+                        .as_ref()
+                        .map_or(ptr::null(), |b| b.as_ptr() as *const c_void)
+                })
+                .collect(),
+            owned_buffers: buffers.into_iter().flatten().collect(),
+            children: children
+                .into_iter()
+                .map(|child| (Box::into_raw(Box::new(child)), true))
+                .collect(),
+            dictionary: None,
+        }
+        .into_array()
+    }
+
+    fn buffer<T: arrow::datatypes::ToByteSlice>(values: &[T]) -> Option<Buffer> {
+        Some(Buffer::from_slice_ref(&values))
+    }
+
+    /// A view of `value`, in the first data buffer at `offset` if long
+    fn view(value: &str, offset: i32) -> [u8; 16] {
+        let mut view = [0u8; 16];
+        view[..4].copy_from_slice(&(value.len() as i32).to_le_bytes());
+        if value.len() <= 12 {
+            view[4..4 + value.len()].copy_from_slice(value.as_bytes());
+        } else {
+            view[4..8].copy_from_slice(&value.as_bytes()[..4]);
+            view[12..].copy_from_slice(&offset.to_le_bytes());
+        }
+        view
+    }
+
+    /// The producer of a batch with a string view column `a` and a run end
+    /// encoded column `b`
+    struct Producer {
+        batches: usize,
+    }
+
+    unsafe extern "C" fn producer_schema(
+        _stream: *mut ArrowArrayStream,
+        out: *mut ArrowSchema,
+    ) -> c_int {
+        let ree = schema(
+            format(b"+r\0"),
+            b"b\0",
+            vec![
+                schema(format(b"i\0"), b"run_ends\0", vec![]),
+                schema(format(b"l\0"), b"values\0", vec![]),
+            ],
+        );
+        let root = schema(
+            format(b"+s\0"),
+            b"\0",
+            vec![schema(format(b"vu\0"), b"a\0", vec![]), ree],
+        );
+        ptr::write(out, root);
+        0
+    }
+
+    unsafe extern "C" fn producer_next(
+        stream: *mut ArrowArrayStream,
+        out: *mut ArrowArray,
+    ) -> c_int {
+        let producer = &mut *((*stream).private_data as *mut Producer);
+        if producer.batches == 0 {
+            ptr::write(out, ArrowArray::empty());
+            return 0;
+        }
+        producer.batches -= 1;
+
+        let long = "a string longer than 12 bytes";
+        let mut views = vec![];
+        views.extend_from_slice(&view("short", 0));
+        views.extend_from_slice(&[0u8; 16]);
+        views.extend_from_slice(&view(long, 0));
+        let a = array(
+            3,
+            1,
+            vec![
+                buffer(&[0b101u8]),
+                buffer(&views),
+                buffer(long.as_bytes()),
+                buffer(&[long.len() as i64]),
+            ],
+            vec![],
+        );
+        let b = array(
+            3,
+            0,
+            vec![],
+            vec![
+                array(2, 0, vec![None, buffer(&[2i32, 3])], vec![]),
+                array(2, 0, vec![None, buffer(&[7i64, 8])], vec![]),
+            ],
+        );
+        ptr::write(out, array(3, 0, vec![None], vec![a, b]));
+        0
+    }
+
+    unsafe extern "C" fn producer_release(stream: *mut ArrowArrayStream) {
+        drop(Box::from_raw((*stream).private_data as *mut Producer));
+        (*stream).release = None;
+    }
+
+    fn producer() -> FFI_ArrowArrayStream {
+        let producer = ArrowArrayStream {
+            get_schema: Some(producer_schema),
+            get_next: Some(producer_next),
+            get_last_error: None,
+            release: Some(producer_release),
+            private_data: Box::into_raw(Box::new(Producer { batches: 1 })) as *mut c_void,
+        };
+        unsafe { mem::transmute(producer) }
+    }
+
+    #[test]
+    fn normalize_views_and_run_ends() -> Result<()> {
+        // the arrays are normalized unless disabled in the config
+        let config = SessionConfig::new().with_normalize_foreign_arrays(false);
+        assert!(ArrowArrayStreamTable::try_new_with_config(producer(), &config).is_err());
+
+        let ctx = SessionContext::new();
+        ctx.register_table("t", Arc::new(ArrowArrayStreamTable::try_new(producer())?))?;
+        let runtime = tokio::runtime::Runtime::new()?;
+        let batches = runtime
+            .block_on(async { ctx.sql("SELECT a, b FROM t").await?.collect().await })?;
+        let expected = vec![
+            "+-------------------------------+---+",
+            "| a                             | b |",
+            "+-------------------------------+---+",
+            "| short                         | 7 |",
+            "|                               | 7 |",
+            "| a string longer than 12 bytes | 8 |",
+            "+-------------------------------+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_widths() {
+        assert_eq!(fixed_width("i"), Some(4));
+        assert_eq!(fixed_width("tsu:UTC"), Some(8));
+        assert_eq!(fixed_width("d:38,10"), Some(16));
+        assert_eq!(fixed_width("d:76,10,256"), Some(32));
+        assert_eq!(fixed_width("w:5"), Some(5));
+        assert_eq!(fixed_width("+l"), None);
+    }
+}
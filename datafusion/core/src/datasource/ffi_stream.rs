@@ -0,0 +1,268 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A table reading the batches of an `ArrowArrayStream` imported through the
+//! [Arrow C Stream interface](https://arrow.apache.org/docs/format/CStreamInterface.html).
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::ffi_stream::{ArrowArrayStreamReader, FFI_ArrowArrayStream};
+use arrow::record_batch::RecordBatchReader;
+use async_trait::async_trait;
+use log::error;
+use parking_lot::Mutex;
+use tokio::task;
+
+use crate::datasource::ffi_compat::normalize_stream;
+use crate::datasource::TableProvider;
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::{SessionConfig, TaskContext};
+use crate::logical_plan::Expr;
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::stream::RecordBatchReceiverStream;
+use crate::physical_plan::{
+    project_schema, DisplayFormatType, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+
+/// The reader of an imported stream
+struct StreamReader(ArrowArrayStreamReader);
+
+// SAFETY: the Arrow C Stream interface allows a stream to be consumed from
+// any thread, as long as it is not consumed concurrently, which the mutex
+// of `ArrowArrayStreamTable` guarantees
+unsafe impl Send for StreamReader {}
+
+impl StreamReader {
+    fn into_inner(self) -> ArrowArrayStreamReader {
+        self.0
+    }
+}
+
+/// A table holding an imported `ArrowArrayStream`.
+///
+/// The batches are imported without copying their buffers. As the stream
+/// can only be consumed once, the table can only be scanned once.
+pub struct ArrowArrayStreamTable {
+    schema: SchemaRef,
+    reader: Arc<Mutex<Option<StreamReader>>>,
+}
+
+impl fmt::Debug for ArrowArrayStreamTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArrowArrayStreamTable")
+            .field("schema", &self.schema)
+            .field("consumed", &self.reader.lock().is_none())
+            .finish()
+    }
+}
+
+impl ArrowArrayStreamTable {
+    /// Create a table reading the batches of `stream`, taking ownership of it,
+    /// and normalizing the arrays of the layouts this version of Arrow can
+    /// not import, as with the default [`SessionConfig`]
+    pub fn try_new(stream: FFI_ArrowArrayStream) -> Result<Self> {
+        Self::try_new_with_config(stream, &SessionConfig::default())
+    }
+
+    /// Create a table reading the batches of `stream`, taking ownership of it,
+    /// and normalizing the arrays of the layouts this version of Arrow can
+    /// not import if [`SessionConfig::normalize_foreign_arrays`] is enabled
+    pub fn try_new_with_config(
+        stream: FFI_ArrowArrayStream,
+        config: &SessionConfig,
+    ) -> Result<Self> {
+        let stream = if config.normalize_foreign_arrays {
+            normalize_stream(stream)
+        } else {
+            stream
+        };
+        let reader = ArrowArrayStreamReader::try_new(stream)?;
+        Ok(Self {
+            schema: reader.schema(),
+            reader: Arc::new(Mutex::new(Some(StreamReader(reader)))),
+        })
+    }
+
+    /// Create a table reading the batches of the stream `raw_stream` points
+    /// to, taking ownership of it: the stream is moved out of `raw_stream`,
+    /// which is left released. The arrays are normalized as with the default
+    /// [`SessionConfig`].
+    ///
+    /// # Safety
+    /// `raw_stream` must point to a valid `ArrowArrayStream`.
+    pub unsafe fn try_from_raw(raw_stream: *mut FFI_ArrowArrayStream) -> Result<Self> {
+        Self::try_from_raw_with_config(raw_stream, &SessionConfig::default())
+    }
+
+    /// Create a table reading the batches of the stream `raw_stream` points
+    /// to, taking ownership of it as [`Self::try_from_raw`] does, and
+    /// normalizing the arrays if [`SessionConfig::normalize_foreign_arrays`]
+    /// is enabled
+    ///
+    /// # Safety
+    /// `raw_stream` must point to a valid `ArrowArrayStream`.
+    pub unsafe fn try_from_raw_with_config(
+        raw_stream: *mut FFI_ArrowArrayStream,
+        config: &SessionConfig,
+    ) -> Result<Self> {
+        let stream = std::ptr::replace(raw_stream, FFI_ArrowArrayStream::empty());
+        Self::try_new_with_config(stream, config)
+    }
+
+    /// Returns true if the stream was already consumed by a scan
+    pub fn is_consumed(&self) -> bool {
+        self.reader.lock().is_none()
+    }
+}
+
+#[async_trait]
+impl TableProvider for ArrowArrayStreamTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(ArrowArrayStreamExec {
+            reader: self.reader.clone(),
+            projected_schema: project_schema(&self.schema, projection.as_ref())?,
+            projection: projection.clone(),
+        }))
+    }
+}
+
+/// Execution plan reading the batches of an [`ArrowArrayStreamTable`]
+struct ArrowArrayStreamExec {
+    reader: Arc<Mutex<Option<StreamReader>>>,
+    projected_schema: SchemaRef,
+    projection: Option<Vec<usize>>,
+}
+
+impl fmt::Debug for ArrowArrayStreamExec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArrowArrayStreamExec")
+            .field("schema", &self.projected_schema)
+            .field("projection", &self.projection)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for ArrowArrayStreamExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "ArrowArrayStreamExec invalid partition {} (expected 0)",
+                partition
+            )));
+        }
+        let reader = self.reader.lock().take().ok_or_else(|| {
+            DataFusionError::Execution(
+                "The ArrowArrayStream of the table was already consumed, \
+                it can only be scanned once"
+                    .to_string(),
+            )
+        })?;
+
+        // reading the stream calls into foreign code that may block
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let projection = self.projection.clone();
+        let join_handle = task::spawn_blocking(move || {
+            for batch in reader.into_inner() {
+                let batch = match (batch, &projection) {
+                    (Ok(batch), Some(columns)) => batch.project(columns),
+                    (batch, _) => batch,
+                };
+                let is_err = batch.is_err();
+                if let Err(e) = &batch {
+                    error!("Failure while reading an ArrowArrayStream: {}", e);
+                }
+                if sender.blocking_send(batch).is_err() || is_err {
+                    break;
+                }
+            }
+        });
+        Ok(RecordBatchReceiverStream::create(
+            &self.projected_schema,
+            receiver,
+            join_handle,
+        ))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(f, "ArrowArrayStreamExec: projection={:?}", self.projection)
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
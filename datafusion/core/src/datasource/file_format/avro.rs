@@ -29,7 +29,7 @@ use super::FileFormat;
 use crate::avro_to_arrow::read_avro_schema_from_reader;
 use crate::error::Result;
 use crate::logical_plan::Expr;
-use crate::physical_plan::file_format::{AvroExec, FileScanConfig};
+use crate::physical_plan::file_format::{AvroExec, FileScanConfig, ScanErrorPolicy};
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::Statistics;
 use datafusion_data_access::object_store::{ObjectReader, ObjectReaderStream};
@@ -38,7 +38,23 @@ use datafusion_data_access::object_store::{ObjectReader, ObjectReaderStream};
 pub const DEFAULT_AVRO_EXTENSION: &str = ".avro";
 /// Avro `FileFormat` implementation.
 #[derive(Default, Debug)]
-pub struct AvroFormat;
+pub struct AvroFormat {
+    error_policy: ScanErrorPolicy,
+}
+
+impl AvroFormat {
+    /// Set the policy applied when a file cannot be read
+    /// - defaults to `ScanErrorPolicy::Fail`
+    pub fn with_error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// The policy applied when a file cannot be read.
+    pub fn error_policy(&self) -> ScanErrorPolicy {
+        self.error_policy
+    }
+}
 
 #[async_trait]
 impl FileFormat for AvroFormat {
@@ -70,7 +86,7 @@ impl FileFormat for AvroFormat {
         conf: FileScanConfig,
         _filters: &[Expr],
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let exec = AvroExec::new(conf);
+        let exec = AvroExec::new(conf).with_error_policy(self.error_policy);
         Ok(Arc::new(exec))
     }
 }
@@ -365,7 +381,7 @@ mod tests {
     ) -> Result<Arc<dyn ExecutionPlan>> {
         let testdata = crate::test_util::arrow_test_data();
         let filename = format!("{}/avro/{}", testdata, file_name);
-        let format = AvroFormat {};
+        let format = AvroFormat::default();
         let file_schema = format
             .infer_schema(local_object_reader_stream(vec![filename.clone()]))
             .await
@@ -385,6 +401,7 @@ mod tests {
                     projection: projection.clone(),
                     limit,
                     table_partition_cols: vec![],
+                    output_ordering: None,
                 },
                 &[],
             )
@@ -405,7 +422,7 @@ mod tests {
     async fn test() -> Result<()> {
         let testdata = crate::test_util::arrow_test_data();
         let filename = format!("{}/avro/alltypes_plain.avro", testdata);
-        let schema_result = AvroFormat {}
+        let schema_result = AvroFormat::default()
             .infer_schema(local_object_reader_stream(vec![filename]))
             .await;
         assert!(matches!(
@@ -18,18 +18,19 @@
 //! CSV format abstractions
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use arrow::datatypes::Schema;
+use arrow::datatypes::{DataType, Schema};
 use arrow::{self, datatypes::SchemaRef};
 use async_trait::async_trait;
 use futures::StreamExt;
 
-use super::FileFormat;
+use super::{apply_column_type_overrides, merge_inferred_schemas, FileFormat};
 use crate::datasource::file_format::DEFAULT_SCHEMA_INFER_MAX_RECORD;
 use crate::error::Result;
 use crate::logical_plan::Expr;
-use crate::physical_plan::file_format::{CsvExec, FileScanConfig};
+use crate::physical_plan::file_format::{CsvExec, FileScanConfig, ScanErrorPolicy};
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::Statistics;
 use datafusion_data_access::object_store::{ObjectReader, ObjectReaderStream};
@@ -42,6 +43,9 @@ pub struct CsvFormat {
     has_header: bool,
     delimiter: u8,
     schema_infer_max_rec: Option<usize>,
+    type_widening: bool,
+    column_type_overrides: HashMap<String, DataType>,
+    error_policy: ScanErrorPolicy,
 }
 
 impl Default for CsvFormat {
@@ -50,6 +54,9 @@ impl Default for CsvFormat {
             schema_infer_max_rec: Some(DEFAULT_SCHEMA_INFER_MAX_RECORD),
             has_header: true,
             delimiter: b',',
+            type_widening: false,
+            column_type_overrides: HashMap::new(),
+            error_policy: ScanErrorPolicy::default(),
         }
     }
 }
@@ -62,6 +69,36 @@ impl CsvFormat {
         self
     }
 
+    /// Set true to widen the types of columns whose inferred type differs
+    /// between files (e.g. `Int64` and `Float64` become `Float64`) instead of
+    /// failing schema inference. See [`merge_inferred_schemas`].
+    /// - defaults to false
+    pub fn with_type_widening(mut self, type_widening: bool) -> Self {
+        self.type_widening = type_widening;
+        self
+    }
+
+    /// True if conflicting inferred types are widened.
+    pub fn type_widening(&self) -> bool {
+        self.type_widening
+    }
+
+    /// Force the type of some columns, keyed by column name, instead of
+    /// using the inferred types.
+    /// - defaults to no override
+    pub fn with_column_type_overrides(
+        mut self,
+        overrides: HashMap<String, DataType>,
+    ) -> Self {
+        self.column_type_overrides = overrides;
+        self
+    }
+
+    /// The column types forced during schema inference.
+    pub fn column_type_overrides(&self) -> &HashMap<String, DataType> {
+        &self.column_type_overrides
+    }
+
     /// Set true to indicate that the first line is a header.
     /// - default to true
     pub fn with_has_header(mut self, has_header: bool) -> Self {
@@ -85,6 +122,18 @@ impl CsvFormat {
     pub fn delimiter(&self) -> u8 {
         self.delimiter
     }
+
+    /// Set the policy applied when a file or a record cannot be read
+    /// - default to `ScanErrorPolicy::Fail`
+    pub fn with_error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// The policy applied when a file or a record cannot be read.
+    pub fn error_policy(&self) -> ScanErrorPolicy {
+        self.error_policy
+    }
 }
 
 #[async_trait]
@@ -116,8 +165,10 @@ impl FileFormat for CsvFormat {
             }
         }
 
-        let merged_schema = Schema::try_merge(schemas)?;
-        Ok(Arc::new(merged_schema))
+        let merged_schema = merge_inferred_schemas(schemas, self.type_widening)?;
+        let schema =
+            apply_column_type_overrides(merged_schema, &self.column_type_overrides)?;
+        Ok(Arc::new(schema))
     }
 
     async fn infer_stats(
@@ -133,7 +184,8 @@ impl FileFormat for CsvFormat {
         conf: FileScanConfig,
         _filters: &[Expr],
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let exec = CsvExec::new(conf, self.has_header, self.delimiter);
+        let exec = CsvExec::new(conf, self.has_header, self.delimiter)
+            .with_error_policy(self.error_policy);
         Ok(Arc::new(exec))
     }
 }
@@ -283,10 +335,47 @@ mod tests {
                     projection: projection.clone(),
                     limit,
                     table_partition_cols: vec![],
+                    output_ordering: None,
                 },
                 &[],
             )
             .await?;
         Ok(exec)
     }
+
+    #[tokio::test]
+    async fn infer_schema_with_type_widening() -> Result<()> {
+        let tmp_dir = tempfile::TempDir::new()?;
+        let file1 = tmp_dir.path().join("1.csv");
+        let file2 = tmp_dir.path().join("2.csv");
+        std::fs::write(&file1, "a,b\n1,x\n2,y\n")?;
+        std::fs::write(&file2, "a,b\n1.5,3\n2.5,4\n")?;
+        let files = vec![
+            file1.to_str().unwrap().to_owned(),
+            file2.to_str().unwrap().to_owned(),
+        ];
+
+        // the types conflict between the two files
+        let err = CsvFormat::default()
+            .infer_schema(local_object_reader_stream(files.clone()))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Fail to merge schema"), "{}", err);
+
+        let schema = CsvFormat::default()
+            .with_type_widening(true)
+            .infer_schema(local_object_reader_stream(files.clone()))
+            .await?;
+        assert_eq!(schema.field(0).data_type(), &DataType::Float64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Utf8);
+
+        let schema = CsvFormat::default()
+            .with_type_widening(true)
+            .with_column_type_overrides(HashMap::from([("a".to_owned(), DataType::Utf8)]))
+            .infer_schema(local_object_reader_stream(files))
+            .await?;
+        assert_eq!(schema.field(0).data_type(), &DataType::Utf8);
+
+        Ok(())
+    }
 }
@@ -18,22 +18,23 @@
 //! Line delimited JSON format abstractions
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::io::BufReader;
 use std::sync::Arc;
 
-use arrow::datatypes::Schema;
+use arrow::datatypes::DataType;
 use arrow::datatypes::SchemaRef;
 use arrow::json::reader::infer_json_schema_from_iterator;
 use arrow::json::reader::ValueIter;
 use async_trait::async_trait;
 use futures::StreamExt;
 
-use super::FileFormat;
 use super::FileScanConfig;
+use super::{apply_column_type_overrides, merge_inferred_schemas, FileFormat};
 use crate::datasource::file_format::DEFAULT_SCHEMA_INFER_MAX_RECORD;
 use crate::error::Result;
 use crate::logical_plan::Expr;
-use crate::physical_plan::file_format::NdJsonExec;
+use crate::physical_plan::file_format::{NdJsonExec, ScanErrorPolicy};
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::Statistics;
 use datafusion_data_access::object_store::{ObjectReader, ObjectReaderStream};
@@ -44,12 +45,18 @@ pub const DEFAULT_JSON_EXTENSION: &str = ".json";
 #[derive(Debug)]
 pub struct JsonFormat {
     schema_infer_max_rec: Option<usize>,
+    type_widening: bool,
+    column_type_overrides: HashMap<String, DataType>,
+    error_policy: ScanErrorPolicy,
 }
 
 impl Default for JsonFormat {
     fn default() -> Self {
         Self {
             schema_infer_max_rec: Some(DEFAULT_SCHEMA_INFER_MAX_RECORD),
+            type_widening: false,
+            column_type_overrides: HashMap::new(),
+            error_policy: ScanErrorPolicy::default(),
         }
     }
 }
@@ -61,6 +68,48 @@ impl JsonFormat {
         self.schema_infer_max_rec = max_rec;
         self
     }
+
+    /// Set true to widen the types of columns whose inferred type differs
+    /// between files (e.g. `Int64` and `Float64` become `Float64`) instead of
+    /// failing schema inference. See [`merge_inferred_schemas`].
+    /// - defaults to false
+    pub fn with_type_widening(mut self, type_widening: bool) -> Self {
+        self.type_widening = type_widening;
+        self
+    }
+
+    /// True if conflicting inferred types are widened.
+    pub fn type_widening(&self) -> bool {
+        self.type_widening
+    }
+
+    /// Force the type of some columns, keyed by column name, instead of
+    /// using the inferred types.
+    /// - defaults to no override
+    pub fn with_column_type_overrides(
+        mut self,
+        overrides: HashMap<String, DataType>,
+    ) -> Self {
+        self.column_type_overrides = overrides;
+        self
+    }
+
+    /// The column types forced during schema inference.
+    pub fn column_type_overrides(&self) -> &HashMap<String, DataType> {
+        &self.column_type_overrides
+    }
+
+    /// Set the policy applied when a file or a record cannot be read
+    /// - defaults to `ScanErrorPolicy::Fail`
+    pub fn with_error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// The policy applied when a file or a record cannot be read.
+    pub fn error_policy(&self) -> ScanErrorPolicy {
+        self.error_policy
+    }
 }
 
 #[async_trait]
@@ -88,7 +137,9 @@ impl FileFormat for JsonFormat {
             }
         }
 
-        let schema = Schema::try_merge(schemas)?;
+        let merged_schema = merge_inferred_schemas(schemas, self.type_widening)?;
+        let schema =
+            apply_column_type_overrides(merged_schema, &self.column_type_overrides)?;
         Ok(Arc::new(schema))
     }
 
@@ -105,7 +156,7 @@ impl FileFormat for JsonFormat {
         conf: FileScanConfig,
         _filters: &[Expr],
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let exec = NdJsonExec::new(conf);
+        let exec = NdJsonExec::new(conf).with_error_policy(self.error_policy);
         Ok(Arc::new(exec))
     }
 }
@@ -240,6 +291,7 @@ mod tests {
                     projection: projection.clone(),
                     limit,
                     table_partition_cols: vec![],
+                    output_ordering: None,
                 },
                 &[],
             )
@@ -262,4 +314,40 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(vec!["a: Int64", "b: Float64", "c: Boolean"], fields);
     }
+
+    #[tokio::test]
+    async fn infer_schema_with_column_type_overrides() -> Result<()> {
+        let filename = "tests/jsons/2.json";
+        let format = JsonFormat::default().with_column_type_overrides(HashMap::from([(
+            "a".to_owned(),
+            DataType::Float64,
+        )]));
+        let file_schema = format
+            .infer_schema(local_object_reader_stream(vec![filename.to_owned()]))
+            .await?;
+        let fields = file_schema
+            .fields()
+            .iter()
+            .map(|f| format!("{}: {:?}", f.name(), f.data_type()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec!["a: Float64", "b: Float64", "c: Boolean", "d: Utf8"],
+            fields
+        );
+
+        let format = JsonFormat::default().with_column_type_overrides(HashMap::from([(
+            "missing".to_owned(),
+            DataType::Float64,
+        )]));
+        let err = format
+            .infer_schema(local_object_reader_stream(vec![filename.to_owned()]))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Type override for unknown column 'missing'"
+        );
+
+        Ok(())
+    }
 }
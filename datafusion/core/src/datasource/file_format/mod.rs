@@ -26,11 +26,13 @@ pub mod json;
 pub mod parquet;
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 
-use crate::arrow::datatypes::SchemaRef;
-use crate::error::Result;
+use crate::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use crate::error::{DataFusionError, Result};
+use crate::logical_expr::binary_rule::is_numeric;
 use crate::logical_plan::Expr;
 use crate::physical_plan::file_format::FileScanConfig;
 use crate::physical_plan::{ExecutionPlan, Statistics};
@@ -75,3 +77,118 @@ pub trait FileFormat: Send + Sync + fmt::Debug {
         filters: &[Expr],
     ) -> Result<Arc<dyn ExecutionPlan>>;
 }
+
+/// Merge the schemas inferred from the different files of a table.
+///
+/// If `type_widening` is false, a field that appears in several files must
+/// have the same type in all of them. Otherwise conflicting types are widened
+/// to a type that can hold the values of all files:
+/// - integers of different widths become `Int64`, or `UInt64` if they are
+///   all unsigned
+/// - `UInt64` and signed integers become `Float64`, as `Int64` can't hold
+///   the values above `i64::MAX`
+/// - a mix of integers and floating points becomes `Float64`
+/// - a mix of dates becomes `Date64`
+/// - anything else becomes `Utf8`
+pub fn merge_inferred_schemas(
+    schemas: Vec<Schema>,
+    type_widening: bool,
+) -> Result<Schema> {
+    if !type_widening {
+        return Ok(Schema::try_merge(schemas)?);
+    }
+
+    let mut fields: Vec<Field> = vec![];
+    for schema in schemas {
+        for field in schema.fields() {
+            match fields.iter_mut().find(|f| f.name() == field.name()) {
+                Some(merged) => {
+                    *merged = Field::new(
+                        field.name(),
+                        widen_data_type(merged.data_type(), field.data_type()),
+                        merged.is_nullable() || field.is_nullable(),
+                    );
+                }
+                None => fields.push(field.clone()),
+            }
+        }
+    }
+    Ok(Schema::new(fields))
+}
+
+/// Returns the narrowest type that can represent values of both `a` and `b`
+fn widen_data_type(a: &DataType, b: &DataType) -> DataType {
+    use DataType::*;
+    let is_signed = |t: &DataType| matches!(t, Int8 | Int16 | Int32 | Int64);
+    let is_unsigned = |t: &DataType| matches!(t, UInt8 | UInt16 | UInt32 | UInt64);
+    let is_integer = |t: &DataType| is_signed(t) || is_unsigned(t);
+    match (a, b) {
+        (a, b) if a == b => a.clone(),
+        (Null, other) | (other, Null) => other.clone(),
+        (a, b) if is_unsigned(a) && is_unsigned(b) => UInt64,
+        (UInt64, other) | (other, UInt64) if is_signed(other) => Float64,
+        (a, b) if is_integer(a) && is_integer(b) => Int64,
+        (a, b) if is_numeric(a) && is_numeric(b) => Float64,
+        (Date32 | Date64, Date32 | Date64) => Date64,
+        _ => Utf8,
+    }
+}
+
+/// Replace the types of the fields of an inferred schema with the types given
+/// in `overrides`, keyed by field name. Fails if an override refers to a field
+/// that is not in the schema.
+pub fn apply_column_type_overrides(
+    schema: Schema,
+    overrides: &HashMap<String, DataType>,
+) -> Result<Schema> {
+    if overrides.is_empty() {
+        return Ok(schema);
+    }
+
+    if let Some(unknown) = overrides
+        .keys()
+        .find(|name| schema.field_with_name(name).is_err())
+    {
+        return Err(DataFusionError::Plan(format!(
+            "Type override for unknown column '{}'",
+            unknown
+        )));
+    }
+
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| match overrides.get(field.name()) {
+            Some(data_type) => {
+                Field::new(field.name(), data_type.clone(), field.is_nullable())
+            }
+            None => field.clone(),
+        })
+        .collect();
+    Ok(Schema::new_with_metadata(fields, schema.metadata().clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widen_integers() {
+        use DataType::*;
+        let widened = |a: DataType, b: DataType| {
+            let schemas = vec![
+                Schema::new(vec![Field::new("c", a, false)]),
+                Schema::new(vec![Field::new("c", b, false)]),
+            ];
+            let schema = merge_inferred_schemas(schemas, true).unwrap();
+            schema.field(0).data_type().clone()
+        };
+        assert_eq!(widened(Int8, Int32), Int64);
+        assert_eq!(widened(UInt32, Int16), Int64);
+        assert_eq!(widened(UInt8, UInt64), UInt64);
+        // the values of UInt64 above i64::MAX don't wrap
+        assert_eq!(widened(UInt64, Int8), Float64);
+        assert_eq!(widened(Int64, UInt64), Float64);
+        assert_eq!(widened(Int64, Float32), Float64);
+    }
+}
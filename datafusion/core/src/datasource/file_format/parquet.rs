@@ -32,13 +32,16 @@ use parquet::errors::ParquetError;
 use parquet::errors::Result as ParquetResult;
 use parquet::file::reader::ChunkReader;
 use parquet::file::reader::Length;
-use parquet::file::serialized_reader::SerializedFileReader;
+use parquet::file::serialized_reader::{ReadOptions, SerializedFileReader};
 use parquet::file::statistics::Statistics as ParquetStatistics;
+use parquet::schema::types::SchemaDescriptor;
+use parquet_format::FileMetaData as ThriftFileMetaData;
+use thrift::protocol::TCompactInputProtocol;
 
 use super::FileFormat;
 use super::FileScanConfig;
 use crate::arrow::array::{
-    BooleanArray, Float32Array, Float64Array, Int32Array, Int64Array,
+    BooleanArray, Float32Array, Float64Array, Int32Array, Int64Array, StringArray,
 };
 use crate::arrow::datatypes::{DataType, Field};
 use crate::datasource::{create_max_min_accs, get_col_stats};
@@ -47,7 +50,10 @@ use crate::error::Result;
 use crate::logical_plan::combine_filters;
 use crate::logical_plan::Expr;
 use crate::physical_plan::expressions::{MaxAccumulator, MinAccumulator};
-use crate::physical_plan::file_format::{ParquetExec, SchemaAdapter};
+use crate::physical_plan::file_format::{
+    FileStatisticsCache, ParquetExec, ScanErrorPolicy, SchemaAdapter,
+    SORT_ORDER_METADATA_KEY,
+};
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::{Accumulator, Statistics};
 use datafusion_data_access::object_store::{ObjectReader, ObjectReaderStream};
@@ -55,16 +61,39 @@ use datafusion_data_access::object_store::{ObjectReader, ObjectReaderStream};
 /// The default file exetension of parquet files
 pub const DEFAULT_PARQUET_EXTENSION: &str = ".parquet";
 
+/// The key of the metadata of the Arrow fields holding their Parquet field ID
+pub const PARQUET_FIELD_ID_META_KEY: &str = "PARQUET:field_id";
+
+/// The magic bytes ending the Parquet files written with an encrypted footer
+const ENCRYPTED_FOOTER_MAGIC: &[u8; 4] = b"PARE";
+
+/// The magic bytes ending the other Parquet files
+const FOOTER_MAGIC: &[u8; 4] = b"PAR1";
+
 /// The Apache Parquet `FileFormat` implementation
+///
+/// The files written with Parquet modular encryption can not be decrypted
+/// and are reported as not supported.
 #[derive(Debug)]
 pub struct ParquetFormat {
     enable_pruning: bool,
+    error_policy: ScanErrorPolicy,
+    match_field_ids: bool,
+    statistics_truncate_length: Option<usize>,
+    decode_threads: usize,
+    /// The statistics fetched from the footers of the files by the scans
+    statistics_cache: Arc<FileStatisticsCache>,
 }
 
 impl Default for ParquetFormat {
     fn default() -> Self {
         Self {
             enable_pruning: true,
+            error_policy: ScanErrorPolicy::default(),
+            match_field_ids: false,
+            statistics_truncate_length: None,
+            decode_threads: 0,
+            statistics_cache: Arc::new(FileStatisticsCache::default()),
         }
     }
 }
@@ -80,6 +109,71 @@ impl ParquetFormat {
     pub fn enable_pruning(&self) -> bool {
         self.enable_pruning
     }
+
+    /// Set the policy applied when a file cannot be read
+    /// - defaults to `ScanErrorPolicy::Fail`
+    pub fn with_error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// The policy applied when a file cannot be read
+    pub fn error_policy(&self) -> ScanErrorPolicy {
+        self.error_policy
+    }
+
+    /// Resolve the columns of the files by their Parquet field ID rather
+    /// than by their name, as needed to read the files written by systems
+    /// tracking the columns by ID (e.g. Iceberg), where columns can be
+    /// renamed or reordered.
+    ///
+    /// The field ID of a table column is stored in the metadata of its field
+    /// under [`PARQUET_FIELD_ID_META_KEY`], as in the inferred schemas. The
+    /// columns without a field ID are still resolved by name.
+    /// - defaults to false
+    pub fn with_field_id_matching(mut self, enable: bool) -> Self {
+        self.match_field_ids = enable;
+        self
+    }
+
+    /// Return true if the columns are resolved by field ID
+    pub fn field_id_matching(&self) -> bool {
+        self.match_field_ids
+    }
+
+    /// Declare that the min and max statistics of the string columns of the
+    /// files may be truncated to `length` bytes, as done by some writers
+    /// (e.g. parquet-mr) for long values. A statistic of at least `length`
+    /// bytes is then only used as a bound of the values of its column: the
+    /// max is widened to the smallest string greater than all the strings
+    /// starting with it, and the statistics of the file are inexact.
+    ///
+    /// The statistics ending in the middle of a UTF-8 sequence are always
+    /// handled as truncated.
+    /// - defaults to None, the statistics being exact
+    pub fn with_statistics_truncate_length(mut self, length: Option<usize>) -> Self {
+        self.statistics_truncate_length = length;
+        self
+    }
+
+    /// The length the string statistics of the files may be truncated to
+    pub fn statistics_truncate_length(&self) -> Option<usize> {
+        self.statistics_truncate_length
+    }
+
+    /// Decode the columns of the files with the help of up to `threads`
+    /// threads per scan, for the scans of fewer files than the threads of the
+    /// runtime. See [`ParquetExec::with_decode_threads`]
+    /// - defaults to 0
+    pub fn with_decode_threads(mut self, threads: usize) -> Self {
+        self.decode_threads = threads;
+        self
+    }
+
+    /// The maximum number of threads helping a scan decode its files
+    pub fn decode_threads(&self) -> usize {
+        self.decode_threads
+    }
 }
 
 #[async_trait]
@@ -89,14 +183,35 @@ impl FileFormat for ParquetFormat {
     }
 
     async fn infer_schema(&self, readers: ObjectReaderStream) -> Result<SchemaRef> {
-        let merged_schema = readers
+        let match_field_ids = self.match_field_ids;
+        // the sort order of the files if they all have the same, None before
+        // the first file
+        let (mut merged_schema, sort_order) = readers
             .map_err(DataFusionError::IoError)
-            .try_fold(Schema::empty(), |acc, reader| async {
-                let next_schema = fetch_schema(reader);
-                Schema::try_merge([acc, next_schema?])
-                    .map_err(DataFusionError::ArrowError)
-            })
+            .try_fold(
+                (Schema::empty(), None),
+                |(acc, acc_sort_order), reader| async move {
+                    let mut next_schema = fetch_schema(reader, match_field_ids)?;
+                    let next_sort_order =
+                        next_schema.metadata.remove(SORT_ORDER_METADATA_KEY);
+                    let sort_order = match acc_sort_order {
+                        None => next_sort_order,
+                        Some(acc_sort_order) if acc_sort_order == next_sort_order => {
+                            acc_sort_order
+                        }
+                        Some(_) => None,
+                    };
+                    let merged_schema = Schema::try_merge([acc, next_schema])
+                        .map_err(DataFusionError::ArrowError)?;
+                    Ok((merged_schema, Some(sort_order)))
+                },
+            )
             .await?;
+        if let Some(Some(sort_order)) = sort_order {
+            merged_schema
+                .metadata
+                .insert(SORT_ORDER_METADATA_KEY.to_owned(), sort_order);
+        }
         Ok(Arc::new(merged_schema))
     }
 
@@ -105,7 +220,12 @@ impl FileFormat for ParquetFormat {
         reader: Arc<dyn ObjectReader>,
         table_schema: SchemaRef,
     ) -> Result<Statistics> {
-        let stats = fetch_statistics(reader, table_schema)?;
+        let stats = fetch_statistics(
+            reader,
+            table_schema,
+            self.match_field_ids,
+            self.statistics_truncate_length,
+        )?;
         Ok(stats)
     }
 
@@ -123,17 +243,28 @@ impl FileFormat for ParquetFormat {
             None
         };
 
-        Ok(Arc::new(ParquetExec::new(conf, predicate)))
+        Ok(Arc::new(
+            ParquetExec::new(conf, predicate)
+                .with_error_policy(self.error_policy)
+                .with_field_id_matching(self.match_field_ids)
+                .with_statistics_truncate_length(self.statistics_truncate_length)
+                .with_decode_threads(self.decode_threads)
+                .with_statistics_cache(self.statistics_cache.clone()),
+        ))
     }
 }
 
+/// Updates the min and max of the `i`th field with the statistics `stat` of a
+/// row group, returning false if the string statistics may be truncated, and
+/// thus are not the exact min and max of the row group
 fn summarize_min_max(
     max_values: &mut [Option<MaxAccumulator>],
     min_values: &mut [Option<MinAccumulator>],
     fields: &[Field],
     i: usize,
     stat: &ParquetStatistics,
-) {
+    truncate_length: Option<usize>,
+) -> bool {
     match stat {
         ParquetStatistics::Boolean(s) => {
             if let DataType::Boolean = fields[i].data_type() {
@@ -269,39 +400,253 @@ fn summarize_min_max(
                 }
             }
         }
+        ParquetStatistics::ByteArray(s) => {
+            if let DataType::Utf8 = fields[i].data_type() {
+                if s.has_min_max_set() {
+                    let (max, max_exact) =
+                        string_max_bound(s.max_bytes(), truncate_length);
+                    let (min, min_exact) =
+                        string_min_bound(s.min_bytes(), truncate_length);
+                    match (&mut max_values[i], max) {
+                        (Some(max_value), Some(max)) => {
+                            if max_value
+                                .update_batch(&[Arc::new(StringArray::from(vec![
+                                    max.as_str()
+                                ]))])
+                                .is_err()
+                            {
+                                max_values[i] = None;
+                            }
+                        }
+                        // the values of the row group are not bounded
+                        (_, None) => max_values[i] = None,
+                        (None, _) => {}
+                    }
+                    if let Some(min_value) = &mut min_values[i] {
+                        if min_value
+                            .update_batch(&[Arc::new(StringArray::from(vec![
+                                min.as_str()
+                            ]))])
+                            .is_err()
+                        {
+                            min_values[i] = None;
+                        }
+                    }
+                    return max_exact && min_exact;
+                }
+            }
+        }
         _ => {}
     }
+    true
+}
+
+/// Returns the longest prefix of `bytes` that is valid UTF-8
+fn utf8_prefix(bytes: &[u8]) -> &str {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s,
+        // the prefix before the error is valid
+        Err(e) => std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap(),
+    }
+}
+
+/// Returns true if the string statistic `bytes` may have been truncated by
+/// the writer
+fn is_truncated(bytes: &[u8], truncate_length: Option<usize>) -> bool {
+    std::str::from_utf8(bytes).is_err()
+        || truncate_length.map_or(false, |length| bytes.len() >= length)
+}
+
+/// Returns a lower bound of the values of a string column from its min
+/// statistic `bytes`, and whether it is the exact min. A truncated min is a
+/// prefix of the actual min, and thus still a lower bound of the values.
+pub(crate) fn string_min_bound(
+    bytes: &[u8],
+    truncate_length: Option<usize>,
+) -> (String, bool) {
+    (
+        utf8_prefix(bytes).to_owned(),
+        !is_truncated(bytes, truncate_length),
+    )
+}
+
+/// Returns an upper bound of the values of a string column from its max
+/// statistic `bytes`, if any, and whether it is the exact max.
+///
+/// A truncated max is a prefix of the actual max, which can be greater than
+/// it, so the bound is the smallest string greater than all the strings
+/// starting with the prefix: the prefix up to its last character that can
+/// be incremented, incremented. There is no such string when no character
+/// can be incremented, e.g. for an empty prefix.
+pub(crate) fn string_max_bound(
+    bytes: &[u8],
+    truncate_length: Option<usize>,
+) -> (Option<String>, bool) {
+    let prefix = utf8_prefix(bytes);
+    if !is_truncated(bytes, truncate_length) {
+        return (Some(prefix.to_owned()), true);
+    }
+    let mut chars = prefix.chars().collect::<Vec<_>>();
+    while let Some(last) = chars.pop() {
+        // the code points of the surrogates are not characters
+        let next = (last as u32 + 1..=char::MAX as u32).find_map(char::from_u32);
+        if let Some(next) = next {
+            chars.push(next);
+            return (Some(chars.into_iter().collect()), false);
+        }
+    }
+    (None, false)
+}
+
+/// Opens the Parquet file of `object_reader` with `options`, reporting the
+/// files written with modular encryption as not supported rather than as
+/// corrupted, see [`encryption_error`].
+pub(crate) fn open_file_reader(
+    object_reader: Arc<dyn ObjectReader>,
+    options: Option<ReadOptions>,
+) -> Result<SerializedFileReader<ChunkObjectReader>> {
+    let chunk_reader = ChunkObjectReader(object_reader.clone());
+    let result = match options {
+        Some(options) => SerializedFileReader::new_with_options(chunk_reader, options),
+        None => SerializedFileReader::new(chunk_reader),
+    };
+    result
+        .map_err(|e| encryption_error(object_reader.as_ref()).unwrap_or_else(|| e.into()))
+}
+
+/// Returns the error reporting the Parquet file of `object_reader` as not
+/// supported if it was written with modular encryption.
+///
+/// Decryption is not supported by the parquet reader. The files with an
+/// encrypted footer fail to be opened, while the files with a plaintext
+/// footer are opened but fail once their encrypted columns are decoded. The
+/// footer is only read again once the file failed to be read, so that the
+/// other files are not read twice.
+pub(crate) fn encryption_error(
+    object_reader: &dyn ObjectReader,
+) -> Option<DataFusionError> {
+    let length = object_reader.length();
+    // the footer length and the magic bytes
+    let mut tail = [0_u8; 8];
+    if length < tail.len() as u64 {
+        return None;
+    }
+    object_reader
+        .sync_chunk_reader(length - tail.len() as u64, tail.len())
+        .and_then(|mut reader| reader.read_exact(&mut tail))
+        .ok()?;
+
+    if &tail[4..] == ENCRYPTED_FOOTER_MAGIC {
+        return Some(DataFusionError::NotImplemented(
+            "Reading Parquet files with an encrypted footer is not supported".to_owned(),
+        ));
+    }
+    if &tail[4..] != FOOTER_MAGIC {
+        return None;
+    }
+
+    let footer_length = u32::from_le_bytes(tail[..4].try_into().unwrap()) as u64;
+    if footer_length + tail.len() as u64 > length {
+        return None;
+    }
+    let footer = object_reader
+        .sync_chunk_reader(
+            length - tail.len() as u64 - footer_length,
+            footer_length as usize,
+        )
+        .ok()?;
+    // the signature following the metadata of a plaintext footer is ignored
+    let metadata = ThriftFileMetaData::read_from_in_protocol(
+        &mut TCompactInputProtocol::new(footer),
+    )
+    .ok()?;
+    metadata.encryption_algorithm.map(|_| {
+        DataFusionError::NotImplemented(
+            "Reading encrypted Parquet files with a plaintext footer is not supported"
+                .to_owned(),
+        )
+    })
 }
 
-/// Read and parse the schema of the Parquet file at location `path`
-fn fetch_schema(object_reader: Arc<dyn ObjectReader>) -> Result<Schema> {
-    let obj_reader = ChunkObjectReader(object_reader);
-    let file_reader = Arc::new(SerializedFileReader::new(obj_reader)?);
+/// Read and parse the schema of the Parquet file at location `path`, with
+/// the field IDs of its columns if `with_field_ids` is true
+fn fetch_schema(
+    object_reader: Arc<dyn ObjectReader>,
+    with_field_ids: bool,
+) -> Result<Schema> {
+    let file_reader = Arc::new(open_file_reader(object_reader, None)?);
     let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
     let schema = arrow_reader.get_schema()?;
 
-    Ok(schema)
+    if with_field_ids {
+        let metadata = arrow_reader.get_metadata();
+        Ok(schema_with_field_ids(
+            schema,
+            metadata.file_metadata().schema_descr(),
+        ))
+    } else {
+        Ok(schema)
+    }
+}
+
+/// Stores the field IDs of the top level columns of `parquet_schema` in the
+/// metadata of the matching fields of `schema`, under
+/// [`PARQUET_FIELD_ID_META_KEY`]
+pub(crate) fn schema_with_field_ids(
+    schema: Schema,
+    parquet_schema: &SchemaDescriptor,
+) -> Schema {
+    let columns = parquet_schema.root_schema().get_fields();
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let info = columns
+                .iter()
+                .find(|column| column.name() == field.name())
+                .map(|column| column.get_basic_info());
+            match info {
+                Some(info) if info.has_id() => {
+                    let mut metadata = field.metadata().clone().unwrap_or_default();
+                    metadata.insert(
+                        PARQUET_FIELD_ID_META_KEY.to_owned(),
+                        info.id().to_string(),
+                    );
+                    let mut field = field.clone();
+                    field.set_metadata(Some(metadata));
+                    field
+                }
+                _ => field.clone(),
+            }
+        })
+        .collect();
+    Schema::new_with_metadata(fields, schema.metadata().clone())
 }
 
 /// Read and parse the statistics of the Parquet file at location `path`
-fn fetch_statistics(
+pub(crate) fn fetch_statistics(
     object_reader: Arc<dyn ObjectReader>,
     table_schema: SchemaRef,
+    match_field_ids: bool,
+    truncate_length: Option<usize>,
 ) -> Result<Statistics> {
-    let obj_reader = ChunkObjectReader(object_reader);
-    let file_reader = Arc::new(SerializedFileReader::new(obj_reader)?);
+    let file_reader = Arc::new(open_file_reader(object_reader, None)?);
     let mut arrow_reader = ParquetFileArrowReader::new(file_reader);
     let file_schema = arrow_reader.get_schema()?;
+    let meta_data = arrow_reader.get_metadata();
+    let file_schema =
+        schema_with_field_ids(file_schema, meta_data.file_metadata().schema_descr());
     let num_fields = table_schema.fields().len();
     let fields = table_schema.fields().to_vec();
-    let meta_data = arrow_reader.get_metadata();
 
     let mut num_rows = 0;
     let mut total_byte_size = 0;
     let mut null_counts = vec![0; num_fields];
     let mut has_statistics = false;
+    let mut is_exact = true;
 
-    let schema_adapter = SchemaAdapter::new(table_schema.clone());
+    let schema_adapter =
+        SchemaAdapter::new(table_schema.clone()).with_field_id_matching(match_field_ids);
 
     let (mut max_values, mut min_values) = create_max_min_accs(&table_schema);
 
@@ -325,12 +670,13 @@ fn fetch_statistics(
                 {
                     if let Some((null_count, stats)) = column_stats.get(&file_idx) {
                         *null_cnt += *null_count as usize;
-                        summarize_min_max(
+                        is_exact &= summarize_min_max(
                             &mut max_values,
                             &mut min_values,
                             &fields,
                             table_idx,
                             stats,
+                            truncate_length,
                         )
                     }
                 } else {
@@ -355,7 +701,7 @@ fn fetch_statistics(
         num_rows: Some(num_rows as usize),
         total_byte_size: Some(total_byte_size as usize),
         column_statistics: column_stats,
-        is_exact: true,
+        is_exact,
     };
 
     Ok(statistics)
@@ -401,6 +747,7 @@ mod tests {
     use futures::StreamExt;
     use parquet::arrow::ArrowWriter;
     use parquet::file::properties::WriterProperties;
+    use std::io::Write;
     use tempfile::NamedTempFile;
 
     // Add a new column with the specified field name to the RecordBatch
@@ -452,6 +799,100 @@ mod tests {
         Ok((files, merged_schema))
     }
 
+    #[tokio::test]
+    async fn read_encrypted_footer() -> Result<()> {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(b"PAR1 encrypted column chunks and footer PARE")?;
+        let reader = local_object_reader(file.path().to_string_lossy().to_string());
+
+        let err = fetch_schema(reader, false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "This feature is not implemented: Reading Parquet files with an encrypted footer is not supported"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn read_encrypted_plaintext_footer() -> Result<()> {
+        use parquet_format::{AesGcmV1, EncryptionAlgorithm};
+        use thrift::protocol::TCompactOutputProtocol;
+
+        let metadata = ThriftFileMetaData {
+            version: 1,
+            schema: vec![],
+            num_rows: 0,
+            row_groups: vec![],
+            key_value_metadata: None,
+            created_by: None,
+            column_orders: None,
+            encryption_algorithm: Some(EncryptionAlgorithm::AESGCMV1(AesGcmV1 {
+                aad_prefix: None,
+                aad_file_unique: None,
+                supply_aad_prefix: None,
+            })),
+            footer_signing_key_metadata: None,
+        };
+        let mut footer = vec![];
+        metadata
+            .write_to_out_protocol(&mut TCompactOutputProtocol::new(&mut footer))
+            .unwrap();
+        // the nonce and the tag of the footer signature
+        footer.extend_from_slice(&[0; 28]);
+
+        let mut file = NamedTempFile::new()?;
+        file.write_all(b"PAR1 encrypted column chunks")?;
+        file.write_all(&footer)?;
+        file.write_all(&(footer.len() as u32).to_le_bytes())?;
+        file.write_all(b"PAR1")?;
+        let reader = local_object_reader(file.path().to_string_lossy().to_string());
+
+        let err = encryption_error(reader.as_ref()).unwrap();
+        assert_eq!(
+            err.to_string(),
+            "This feature is not implemented: Reading encrypted Parquet files with a plaintext footer is not supported"
+        );
+
+        // the files that are not encrypted are not reported
+        let testdata = crate::test_util::parquet_test_data();
+        let filename = format!("{}/alltypes_plain.parquet", testdata);
+        assert!(encryption_error(local_object_reader(filename).as_ref()).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn field_ids_from_parquet_schema() -> Result<()> {
+        use parquet::basic::{Repetition, Type as PhysicalType};
+        use parquet::schema::types::Type;
+
+        let column = |name: &str, id: Option<i32>| {
+            let builder = Type::primitive_type_builder(name, PhysicalType::INT64)
+                .with_repetition(Repetition::OPTIONAL);
+            let builder = match id {
+                Some(id) => builder.with_id(id),
+                None => builder,
+            };
+            Arc::new(builder.build().unwrap())
+        };
+        let mut columns = vec![column("a", Some(5)), column("b", None)];
+        let root = Type::group_type_builder("schema")
+            .with_fields(&mut columns)
+            .build()?;
+        let parquet_schema = SchemaDescriptor::new(Arc::new(root));
+
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Int64, true),
+        ]);
+        let schema = schema_with_field_ids(schema, &parquet_schema);
+        assert_eq!(
+            schema.field(0).metadata().as_ref().unwrap()[PARQUET_FIELD_ID_META_KEY],
+            "5"
+        );
+        assert_eq!(schema.field(1).metadata(), &None);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn read_merged_batches() -> Result<()> {
         let c1: ArrayRef =
@@ -468,7 +909,7 @@ mod tests {
 
         let reader = local_object_reader(files[0].path().to_string_lossy().to_string());
 
-        let stats = fetch_statistics(reader, table_schema.clone())?;
+        let stats = fetch_statistics(reader, table_schema.clone(), false, None)?;
 
         assert_eq!(stats.num_rows, Some(3));
         let c1_stats = &stats.column_statistics.as_ref().expect("missing c1 stats")[0];
@@ -478,7 +919,7 @@ mod tests {
 
         let reader = local_object_reader(files[1].path().to_string_lossy().to_string());
 
-        let stats = fetch_statistics(reader, table_schema)?;
+        let stats = fetch_statistics(reader, table_schema, false, None)?;
         assert_eq!(stats.num_rows, Some(3));
         let c1_stats = &stats.column_statistics.as_ref().expect("missing c1 stats")[0];
         let c2_stats = &stats.column_statistics.as_ref().expect("missing c2 stats")[1];
@@ -490,6 +931,73 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn read_truncated_string_statistics() -> Result<()> {
+        let c1: ArrayRef =
+            Arc::new(StringArray::from(vec![Some("banana"), None, Some("apple")]));
+        let (files, schema) = create_table(vec![create_batch(vec![("c1", c1)])]).await?;
+        let table_schema = Arc::new(schema);
+        let path = files[0].path().to_string_lossy().to_string();
+
+        let stats = fetch_statistics(
+            local_object_reader(path.clone()),
+            table_schema.clone(),
+            false,
+            None,
+        )?;
+        let c1_stats = &stats.column_statistics.as_ref().expect("missing c1 stats")[0];
+        assert_eq!(c1_stats.min_value, Some(ScalarValue::from("apple")));
+        assert_eq!(c1_stats.max_value, Some(ScalarValue::from("banana")));
+        assert!(stats.is_exact);
+
+        // the statistics of 5 bytes or more may be truncated prefixes
+        let stats =
+            fetch_statistics(local_object_reader(path), table_schema, false, Some(5))?;
+        let c1_stats = &stats.column_statistics.as_ref().expect("missing c1 stats")[0];
+        assert_eq!(c1_stats.min_value, Some(ScalarValue::from("apple")));
+        assert_eq!(c1_stats.max_value, Some(ScalarValue::from("banano")));
+        assert!(!stats.is_exact);
+
+        Ok(())
+    }
+
+    #[test]
+    fn string_statistics_bounds() {
+        assert_eq!(string_min_bound(b"abc", None), ("abc".to_owned(), true));
+        assert_eq!(string_min_bound(b"abc", Some(3)), ("abc".to_owned(), false));
+        assert_eq!(string_min_bound(b"abc", Some(4)), ("abc".to_owned(), true));
+        // truncated in the middle of the 2 bytes of 'é'
+        assert_eq!(string_min_bound(b"a\xC3", None), ("a".to_owned(), false));
+
+        assert_eq!(
+            string_max_bound(b"abc", None),
+            (Some("abc".to_owned()), true)
+        );
+        assert_eq!(
+            string_max_bound(b"abc", Some(3)),
+            (Some("abd".to_owned()), false)
+        );
+        assert_eq!(
+            string_max_bound(b"a\xC3", None),
+            (Some("b".to_owned()), false)
+        );
+        assert_eq!(
+            string_max_bound("a\u{10FFFF}".as_bytes(), Some(1)),
+            (Some("b".to_owned()), false)
+        );
+        // the surrogates are skipped
+        assert_eq!(
+            string_max_bound("\u{D7FF}".as_bytes(), Some(1)),
+            (Some("\u{E000}".to_owned()), false)
+        );
+        // no string starting with the greatest character is greater
+        assert_eq!(
+            string_max_bound("\u{10FFFF}".as_bytes(), Some(1)),
+            (None, false)
+        );
+        assert_eq!(string_max_bound(b"", Some(0)), (None, false));
+    }
+
     #[tokio::test]
     async fn read_small_batches() -> Result<()> {
         let config = SessionConfig::new().with_batch_size(2);
@@ -775,6 +1283,7 @@ mod tests {
                     projection: projection.clone(),
                     limit,
                     table_partition_cols: vec![],
+                    output_ordering: None,
                 },
                 &[],
             )
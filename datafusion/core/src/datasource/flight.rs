@@ -0,0 +1,350 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! A table streaming the batches returned by a remote
+//! [Arrow Flight](https://arrow.apache.org/docs/format/Flight.html) endpoint,
+//! so that Arrow-native sources can be joined with the local tables.
+
+use std::any::Any;
+use std::convert::TryFrom;
+use std::sync::Arc;
+
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use arrow_flight::flight_service_client::FlightServiceClient;
+use arrow_flight::utils::flight_data_to_arrow_batch;
+use arrow_flight::{FlightData, Ticket};
+use async_trait::async_trait;
+use log::error;
+use tokio::sync::mpsc::Sender;
+use tonic::Streaming;
+
+use crate::datasource::TableProvider;
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+use crate::logical_plan::Expr;
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::stream::RecordBatchReceiverStream;
+use crate::physical_plan::{
+    project_schema, DisplayFormatType, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+
+/// The request sent to the `DoGet` method of a Flight endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlightSource {
+    /// An opaque ticket. The whole stream is fetched and projected locally.
+    Ticket(Vec<u8>),
+    /// A SQL query run by the server, sent as the ticket, as by the
+    /// `flight_server` example. The projection and the limit of the scans
+    /// are pushed down to the server by wrapping the query.
+    Sql(String),
+}
+
+/// A table holding the results of a remote Flight endpoint, fetched by each
+/// scan
+#[derive(Debug, Clone)]
+pub struct FlightTable {
+    endpoint: String,
+    source: FlightSource,
+    schema: SchemaRef,
+}
+
+impl FlightTable {
+    /// Create the table of the results of `source` at `endpoint`, e.g.
+    /// `http://localhost:50051`, fetching their schema from the server
+    pub async fn try_new(
+        endpoint: impl Into<String>,
+        source: FlightSource,
+    ) -> Result<Self> {
+        let endpoint = endpoint.into();
+        let ticket = match &source {
+            FlightSource::Ticket(ticket) => ticket.clone(),
+            FlightSource::Sql(sql) => projected_query(sql, None, Some(0)).into_bytes(),
+        };
+        let (schema, _) = do_get(endpoint.clone(), ticket).await?;
+        Ok(Self::new_with_schema(endpoint, source, schema))
+    }
+
+    /// Create the table of the results of `source` at `endpoint`, whose
+    /// schema is known
+    pub fn new_with_schema(
+        endpoint: impl Into<String>,
+        source: FlightSource,
+        schema: SchemaRef,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            source,
+            schema,
+        }
+    }
+
+    /// The endpoint of the server
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// The request sent to the server
+    pub fn source(&self) -> &FlightSource {
+        &self.source
+    }
+}
+
+#[async_trait]
+impl TableProvider for FlightTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let (ticket, local_projection) = match &self.source {
+            FlightSource::Ticket(ticket) => (ticket.clone(), projection.clone()),
+            FlightSource::Sql(sql) => {
+                let columns = projection.as_ref().map(|projection| {
+                    projection
+                        .iter()
+                        .map(|i| self.schema.field(*i).name().as_str())
+                        .collect::<Vec<_>>()
+                });
+                let query = projected_query(sql, columns.as_deref(), limit);
+                (query.into_bytes(), None)
+            }
+        };
+        Ok(Arc::new(FlightExec {
+            endpoint: self.endpoint.clone(),
+            ticket,
+            projection: local_projection,
+            projected_schema: project_schema(&self.schema, projection.as_ref())?,
+        }))
+    }
+}
+
+/// Returns the query selecting the `columns` of the results of `sql`, or
+/// all of them, and up to `limit` rows
+fn projected_query(sql: &str, columns: Option<&[&str]>, limit: Option<usize>) -> String {
+    let columns = match columns {
+        Some(columns) => columns
+            .iter()
+            .map(|c| format!("\"{}\"", c.replace('"', "\"\"")))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "*".to_string(),
+    };
+    let mut query = format!("SELECT {} FROM ({}) AS flight_source", columns, sql);
+    if let Some(limit) = limit {
+        query.push_str(&format!(" LIMIT {}", limit));
+    }
+    query
+}
+
+/// Sends `ticket` to the `DoGet` method of `endpoint`, returning the schema
+/// of the results, which is the first message of the stream, and the stream
+/// of the remaining messages
+async fn do_get(
+    endpoint: String,
+    ticket: Vec<u8>,
+) -> Result<(SchemaRef, Streaming<FlightData>)> {
+    let mut client = FlightServiceClient::connect(endpoint)
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?;
+    let mut stream = client
+        .do_get(tonic::Request::new(Ticket { ticket }))
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?
+        .into_inner();
+    let flight_data = stream
+        .message()
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?
+        .ok_or_else(|| {
+            DataFusionError::Execution(
+                "The Flight stream ended before its schema".to_string(),
+            )
+        })?;
+    let schema = Arc::new(Schema::try_from(&flight_data)?);
+    Ok((schema, stream))
+}
+
+/// Execution plan fetching the results of a Flight endpoint
+#[derive(Debug)]
+struct FlightExec {
+    endpoint: String,
+    ticket: Vec<u8>,
+    /// The columns projected locally, if the server doesn't project them
+    projection: Option<Vec<usize>>,
+    projected_schema: SchemaRef,
+}
+
+#[async_trait]
+impl ExecutionPlan for FlightExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projected_schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "FlightExec invalid partition {} (expected 0)",
+                partition
+            )));
+        }
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let endpoint = self.endpoint.clone();
+        let ticket = self.ticket.clone();
+        let projection = self.projection.clone();
+        let schema = self.projected_schema.clone();
+        let join_handle = tokio::task::spawn(async move {
+            let result =
+                fetch_batches(endpoint, ticket, projection, schema, &sender).await;
+            if let Err(e) = result {
+                error!("Failure while reading a Flight stream: {}", e);
+                // the receiver may be dropped
+                sender.send(Err(e.into())).await.ok();
+            }
+        });
+        Ok(RecordBatchReceiverStream::create(
+            &self.projected_schema,
+            receiver,
+            join_handle,
+        ))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "FlightExec: endpoint={}, projection={:?}",
+                    self.endpoint, self.projection
+                )
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Sends the batches returned by `endpoint` for `ticket` to `sender`, with
+/// the columns `projection` of each batch if any, until the receiver is
+/// dropped
+async fn fetch_batches(
+    endpoint: String,
+    ticket: Vec<u8>,
+    projection: Option<Vec<usize>>,
+    schema: SchemaRef,
+    sender: &Sender<ArrowResult<RecordBatch>>,
+) -> Result<()> {
+    let (flight_schema, mut stream) = do_get(endpoint, ticket).await?;
+    let dictionaries_by_field = vec![None; flight_schema.fields().len()];
+    while let Some(flight_data) = stream
+        .message()
+        .await
+        .map_err(|e| DataFusionError::External(Box::new(e)))?
+    {
+        let batch = flight_data_to_arrow_batch(
+            &flight_data,
+            flight_schema.clone(),
+            &dictionaries_by_field,
+        )?;
+        let batch = match &projection {
+            Some(columns) => batch.project(columns)?,
+            None => batch,
+        };
+        if batch.schema().fields() != schema.fields() {
+            return Err(DataFusionError::Execution(format!(
+                "The Flight stream returned a batch of schema {:?}, expected {:?}",
+                batch.schema(),
+                schema
+            )));
+        }
+        if sender.send(Ok(batch)).await.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pushed_down_query() {
+        let sql = "SELECT a, \"b\" FROM t";
+        assert_eq!(
+            projected_query(sql, None, Some(0)),
+            "SELECT * FROM (SELECT a, \"b\" FROM t) AS flight_source LIMIT 0"
+        );
+        assert_eq!(
+            projected_query(sql, Some(&["b", "a"]), None),
+            "SELECT \"b\", \"a\" FROM (SELECT a, \"b\" FROM t) AS flight_source"
+        );
+        assert_eq!(
+            projected_query("SELECT 1 AS \"x\"\"y\"", Some(&["x\"y"]), Some(10)),
+            "SELECT \"x\"\"y\" FROM (SELECT 1 AS \"x\"\"y\") AS flight_source LIMIT 10"
+        );
+    }
+}
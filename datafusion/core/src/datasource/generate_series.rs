@@ -0,0 +1,312 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The `generate_series(start, stop [, step])` table function, producing the
+//! integers from `start` to `stop` inclusive, `step` apart.
+
+use std::any::Any;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::{ArrayRef, Int64Array};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::Stream;
+
+use crate::datasource::TableProvider;
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+use crate::logical_plan::Expr;
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::{
+    project_schema, DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+
+/// Name of the table function, and of the column it produces
+pub const GENERATE_SERIES: &str = "generate_series";
+
+/// A table made of a single Int64 column holding an arithmetic series
+#[derive(Debug, Clone)]
+pub struct GenerateSeriesTable {
+    schema: SchemaRef,
+    start: i64,
+    stop: i64,
+    step: i64,
+    len: usize,
+}
+
+impl GenerateSeriesTable {
+    /// Create the series from `start` to `stop` inclusive, `step` apart
+    pub fn try_new(start: i64, stop: i64, step: i64) -> Result<Self> {
+        if step == 0 {
+            return Err(DataFusionError::Plan(
+                "step size of generate_series cannot equal zero".to_string(),
+            ));
+        }
+        let len = series_len(start, stop, step).ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "generate_series({}, {}, {}) has too many values",
+                start, stop, step
+            ))
+        })?;
+        Ok(Self {
+            schema: Arc::new(Schema::new(vec![Field::new(
+                GENERATE_SERIES,
+                DataType::Int64,
+                false,
+            )])),
+            start,
+            stop,
+            step,
+            len,
+        })
+    }
+
+    /// Number of values in the series
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the series has no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Number of values of the series from `start` to `stop` inclusive, `step`
+/// apart, or `None` if it does not fit in a `usize`
+fn series_len(start: i64, stop: i64, step: i64) -> Option<usize> {
+    if (step > 0 && start > stop) || (step < 0 && start < stop) {
+        return Some(0);
+    }
+    let span = (i128::from(stop) - i128::from(start)).unsigned_abs();
+    let len = (span / u128::from(step.unsigned_abs())).checked_add(1)?;
+    usize::try_from(len).ok()
+}
+
+#[async_trait]
+impl TableProvider for GenerateSeriesTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let len = match limit {
+            Some(limit) => self.len().min(limit),
+            None => self.len(),
+        };
+        Ok(Arc::new(GenerateSeriesExec {
+            series: self.clone(),
+            schema: project_schema(&self.schema, projection.as_ref())?,
+            len,
+        }))
+    }
+}
+
+/// Execution plan producing the values of a [`GenerateSeriesTable`]
+#[derive(Debug)]
+struct GenerateSeriesExec {
+    series: GenerateSeriesTable,
+    /// The projected schema
+    schema: SchemaRef,
+    /// Number of values to produce
+    len: usize,
+}
+
+#[async_trait]
+impl ExecutionPlan for GenerateSeriesExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "GenerateSeriesExec invalid partition {} (expected 0)",
+                partition
+            )));
+        }
+        Ok(Box::pin(GenerateSeriesStream {
+            schema: self.schema.clone(),
+            next: self.series.start,
+            step: self.series.step,
+            remaining: self.len,
+            batch_size: context.session_config().batch_size,
+        }))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => write!(
+                f,
+                "GenerateSeriesExec: start={}, stop={}, step={}, len={}",
+                self.series.start, self.series.stop, self.series.step, self.len
+            ),
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics {
+            num_rows: Some(self.len),
+            total_byte_size: self.len.checked_mul(std::mem::size_of::<i64>()),
+            column_statistics: None,
+            is_exact: true,
+        }
+    }
+}
+
+/// Produces the values of the series, `batch_size` at a time
+struct GenerateSeriesStream {
+    schema: SchemaRef,
+    next: i64,
+    step: i64,
+    remaining: usize,
+    batch_size: usize,
+}
+
+impl Stream for GenerateSeriesStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        if self.remaining == 0 {
+            return Poll::Ready(None);
+        }
+        let len = self.remaining.min(self.batch_size);
+        let (start, step) = (self.next, self.step);
+        // the values fit in an i64 even when the span of the series does not
+        let values: ArrayRef = Arc::new(Int64Array::from_iter_values(
+            (0..len as i64).map(|i| start.wrapping_add(i.wrapping_mul(step))),
+        ));
+        self.remaining -= len;
+        self.next = start.wrapping_add((len as i64).wrapping_mul(step));
+
+        let columns: Vec<ArrayRef> = self
+            .schema
+            .fields()
+            .iter()
+            .map(|_| values.clone())
+            .collect();
+        Poll::Ready(Some(RecordBatch::try_new(self.schema.clone(), columns)))
+    }
+}
+
+impl RecordBatchStream for GenerateSeriesStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::common;
+    use crate::prelude::{SessionConfig, SessionContext};
+
+    async fn collect_series(table: GenerateSeriesTable) -> Result<Vec<i64>> {
+        let session_ctx =
+            SessionContext::with_config(SessionConfig::new().with_batch_size(2));
+        let exec = table.scan(&None, &[], None).await?;
+        let batches =
+            common::collect(exec.execute(0, session_ctx.task_ctx()).await?).await?;
+        Ok(batches
+            .iter()
+            .flat_map(|batch| {
+                let array = batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap();
+                array.values().to_vec()
+            })
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn generate_series() -> Result<()> {
+        let series = collect_series(GenerateSeriesTable::try_new(1, 5, 1)?).await?;
+        assert_eq!(series, vec![1, 2, 3, 4, 5]);
+
+        let series = collect_series(GenerateSeriesTable::try_new(10, 1, -4)?).await?;
+        assert_eq!(series, vec![10, 6, 2]);
+
+        let empty = GenerateSeriesTable::try_new(5, 1, 1)?;
+        assert!(empty.is_empty());
+        assert!(collect_series(empty).await?.is_empty());
+
+        assert!(GenerateSeriesTable::try_new(1, 5, 0).is_err());
+
+        // the number of values must fit in a usize
+        let half = GenerateSeriesTable::try_new(i64::MIN, i64::MAX, 2)?;
+        assert_eq!(half.len() as u64, 1 << 63);
+        let err = GenerateSeriesTable::try_new(i64::MIN, i64::MAX, 1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Error during planning: generate_series({}, {}, 1) has too many values",
+                i64::MIN,
+                i64::MAX
+            )
+        );
+        Ok(())
+    }
+}
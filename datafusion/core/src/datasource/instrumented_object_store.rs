@@ -0,0 +1,485 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An [`ObjectStore`] decorator retrying the failed requests of the wrapped
+//! store with an exponential backoff, bounding the duration of its requests
+//! and recording request metrics.
+
+use std::fmt;
+use std::future::Future;
+use std::io::{self, ErrorKind, Read};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use datafusion_data_access::object_store::{
+    FileMetaStream, ListEntryStream, ObjectReader, ObjectStore,
+};
+use datafusion_data_access::{Result, SizedFile};
+use futures::AsyncRead;
+use log::debug;
+
+/// Configuration of the requests sent by an [`InstrumentedObjectStore`]
+#[derive(Debug, Clone)]
+pub struct ObjectStoreRequestConfig {
+    max_retries: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    timeout: Option<Duration>,
+}
+
+impl Default for ObjectStoreRequestConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            timeout: Some(Duration::from_secs(30)),
+        }
+    }
+}
+
+impl ObjectStoreRequestConfig {
+    /// Create a configuration with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of times a failed request is retried - defaults to 3
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the first retry, doubled for every following retry up
+    /// to `max_backoff` - defaults to 100ms and 10s
+    pub fn with_backoff(
+        mut self,
+        initial_backoff: Duration,
+        max_backoff: Duration,
+    ) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Maximum duration of a request, or `None` to wait for requests
+    /// indefinitely - defaults to 30s.
+    ///
+    /// Only the asynchronous requests can be interrupted: the synchronous
+    /// ones are retried but not bounded in time.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Maximum number of times a failed request is retried
+    pub fn max_retries(&self) -> usize {
+        self.max_retries
+    }
+
+    /// Maximum duration of a request
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
+/// Metrics of the requests sent by [`InstrumentedObjectStore`]s
+#[derive(Debug, Default)]
+pub struct ObjectStoreMetrics {
+    requests: AtomicUsize,
+    retries: AtomicUsize,
+    timeouts: AtomicUsize,
+    failures: AtomicUsize,
+    latency_nanos: AtomicU64,
+}
+
+impl ObjectStoreMetrics {
+    /// Create empty metrics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of requests sent, including the retries
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Number of requests retried after a failure
+    pub fn retries(&self) -> usize {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    /// Number of requests that timed out
+    pub fn timeouts(&self) -> usize {
+        self.timeouts.load(Ordering::Relaxed)
+    }
+
+    /// Number of requests that failed after all their retries
+    pub fn failures(&self) -> usize {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Total time spent waiting for the requests
+    pub fn total_latency(&self) -> Duration {
+        Duration::from_nanos(self.latency_nanos.load(Ordering::Relaxed))
+    }
+
+    fn record_request(&self, latency: Duration) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.latency_nanos
+            .fetch_add(latency.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Returns true if the request failing with `error` may succeed when retried
+fn is_retryable(error: &io::Error) -> bool {
+    !matches!(
+        error.kind(),
+        ErrorKind::NotFound
+            | ErrorKind::PermissionDenied
+            | ErrorKind::InvalidInput
+            | ErrorKind::InvalidData
+            | ErrorKind::Unsupported
+    )
+}
+
+/// Applies the retry policy and records the metrics of the requests
+#[derive(Debug, Clone)]
+struct RequestPolicy {
+    config: ObjectStoreRequestConfig,
+    metrics: Arc<ObjectStoreMetrics>,
+}
+
+impl RequestPolicy {
+    /// Records the outcome of an attempt of `request`, returning the delay
+    /// before the next attempt or the error to report
+    fn on_error(
+        &self,
+        request: &str,
+        attempt: usize,
+        error: io::Error,
+    ) -> Result<Duration> {
+        if attempt >= self.config.max_retries || !is_retryable(&error) {
+            self.metrics.failures.fetch_add(1, Ordering::Relaxed);
+            return Err(error);
+        }
+        self.metrics.retries.fetch_add(1, Ordering::Relaxed);
+        let backoff = self
+            .config
+            .initial_backoff
+            .saturating_mul(1 << attempt.min(31) as u32)
+            .min(self.config.max_backoff);
+        debug!(
+            "Object store request {} failed (attempt {}), retrying in {:?}: {}",
+            request,
+            attempt + 1,
+            backoff,
+            error
+        );
+        Ok(backoff)
+    }
+
+    async fn run<T, F, Fut>(&self, request: &str, op: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            let start = Instant::now();
+            let result = match self.config.timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, op()).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        self.metrics.timeouts.fetch_add(1, Ordering::Relaxed);
+                        Err(io::Error::new(
+                            ErrorKind::TimedOut,
+                            format!(
+                                "Object store request {} timed out after {:?}",
+                                request, timeout
+                            ),
+                        ))
+                    }
+                },
+                None => op().await,
+            };
+            self.metrics.record_request(start.elapsed());
+            match result {
+                Ok(value) => return Ok(value),
+                Err(error) => {
+                    let backoff = self.on_error(request, attempt, error)?;
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn run_sync<T>(&self, request: &str, op: impl Fn() -> Result<T>) -> Result<T> {
+        let mut attempt = 0;
+        loop {
+            let start = Instant::now();
+            let result = op();
+            self.metrics.record_request(start.elapsed());
+            match result {
+                Ok(value) => return Ok(value),
+                Err(error) => {
+                    let backoff = self.on_error(request, attempt, error)?;
+                    std::thread::sleep(backoff);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Wraps an [`ObjectStore`], retrying its failed requests with an
+/// exponential backoff, interrupting the requests that take longer than the
+/// configured timeout and recording [`ObjectStoreMetrics`].
+///
+/// The readers returned by the store are wrapped as well, so opening a chunk
+/// of a file is retried, while errors happening once the returned streams
+/// and readers are consumed are reported as is.
+pub struct InstrumentedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    policy: RequestPolicy,
+}
+
+impl fmt::Debug for InstrumentedObjectStore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InstrumentedObjectStore")
+            .field("inner", &self.inner)
+            .field("config", &self.policy.config)
+            .finish()
+    }
+}
+
+impl InstrumentedObjectStore {
+    /// Wrap `inner`, recording the requests in `metrics`
+    pub fn new(
+        inner: Arc<dyn ObjectStore>,
+        config: ObjectStoreRequestConfig,
+        metrics: Arc<ObjectStoreMetrics>,
+    ) -> Self {
+        Self {
+            inner,
+            policy: RequestPolicy { config, metrics },
+        }
+    }
+
+    /// The wrapped store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+
+    /// The metrics of the requests sent to the wrapped store
+    pub fn metrics(&self) -> &Arc<ObjectStoreMetrics> {
+        &self.policy.metrics
+    }
+}
+
+#[async_trait]
+impl ObjectStore for InstrumentedObjectStore {
+    async fn list_file(&self, prefix: &str) -> Result<FileMetaStream> {
+        self.policy
+            .run("list_file", || self.inner.list_file(prefix))
+            .await
+    }
+
+    async fn list_dir(
+        &self,
+        prefix: &str,
+        delimiter: Option<String>,
+    ) -> Result<ListEntryStream> {
+        self.policy
+            .run("list_dir", || {
+                self.inner.list_dir(prefix, delimiter.clone())
+            })
+            .await
+    }
+
+    fn file_reader(&self, file: SizedFile) -> Result<Arc<dyn ObjectReader>> {
+        let inner = self
+            .policy
+            .run_sync("file_reader", || self.inner.file_reader(file.clone()))?;
+        Ok(Arc::new(InstrumentedObjectReader {
+            inner,
+            policy: self.policy.clone(),
+        }))
+    }
+}
+
+/// Wraps the [`ObjectReader`]s of an [`InstrumentedObjectStore`]
+struct InstrumentedObjectReader {
+    inner: Arc<dyn ObjectReader>,
+    policy: RequestPolicy,
+}
+
+#[async_trait]
+impl ObjectReader for InstrumentedObjectReader {
+    async fn chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> Result<Box<dyn AsyncRead>> {
+        self.policy
+            .run("chunk_reader", || self.inner.chunk_reader(start, length))
+            .await
+    }
+
+    fn sync_chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> Result<Box<dyn Read + Send + Sync>> {
+        self.policy.run_sync("sync_chunk_reader", || {
+            self.inner.sync_chunk_reader(start, length)
+        })
+    }
+
+    fn length(&self) -> u64 {
+        self.inner.length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion_data_access::object_store::local::LocalFileSystem;
+    use datafusion_data_access::FileMeta;
+    use futures::StreamExt;
+    use parking_lot::Mutex;
+
+    /// Fails the listing requests with `errors` before listing nothing
+    #[derive(Debug)]
+    struct FlakyObjectStore {
+        errors: Mutex<Vec<ErrorKind>>,
+        delay: Duration,
+    }
+
+    impl FlakyObjectStore {
+        fn new(errors: Vec<ErrorKind>) -> Self {
+            Self {
+                errors: Mutex::new(errors),
+                delay: Duration::ZERO,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FlakyObjectStore {
+        async fn list_file(&self, _prefix: &str) -> Result<FileMetaStream> {
+            tokio::time::sleep(self.delay).await;
+            match self.errors.lock().pop() {
+                Some(kind) => Err(io::Error::new(kind, "flaky")),
+                None => Ok(Box::pin(futures::stream::empty::<Result<FileMeta>>())),
+            }
+        }
+
+        async fn list_dir(
+            &self,
+            _prefix: &str,
+            _delimiter: Option<String>,
+        ) -> Result<ListEntryStream> {
+            unimplemented!()
+        }
+
+        fn file_reader(&self, _file: SizedFile) -> Result<Arc<dyn ObjectReader>> {
+            match self.errors.lock().pop() {
+                Some(kind) => Err(io::Error::new(kind, "flaky")),
+                None => LocalFileSystem.file_reader(SizedFile {
+                    path: "Cargo.toml".to_owned(),
+                    size: 0,
+                }),
+            }
+        }
+    }
+
+    fn instrumented(
+        store: FlakyObjectStore,
+        config: ObjectStoreRequestConfig,
+    ) -> InstrumentedObjectStore {
+        InstrumentedObjectStore::new(
+            Arc::new(store),
+            config.with_backoff(Duration::from_millis(1), Duration::from_millis(2)),
+            Arc::new(ObjectStoreMetrics::new()),
+        )
+    }
+
+    #[tokio::test]
+    async fn retry_transient_errors() -> Result<()> {
+        let store = instrumented(
+            FlakyObjectStore::new(vec![ErrorKind::ConnectionReset, ErrorKind::Other]),
+            ObjectStoreRequestConfig::new(),
+        );
+        let files = store.list_file("").await?.collect::<Vec<_>>().await;
+        assert!(files.is_empty());
+
+        let metrics = store.metrics();
+        assert_eq!(metrics.requests(), 3);
+        assert_eq!(metrics.retries(), 2);
+        assert_eq!(metrics.failures(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn give_up_after_max_retries() {
+        let store = instrumented(
+            FlakyObjectStore::new(vec![ErrorKind::Other; 3]),
+            ObjectStoreRequestConfig::new().with_max_retries(1),
+        );
+        let err = store.list_file("").await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::Other);
+        assert_eq!(store.metrics().requests(), 2);
+        assert_eq!(store.metrics().failures(), 1);
+    }
+
+    #[test]
+    fn do_not_retry_permanent_errors() {
+        let store = instrumented(
+            FlakyObjectStore::new(vec![ErrorKind::NotFound]),
+            ObjectStoreRequestConfig::new(),
+        );
+        let err = store
+            .file_reader(SizedFile {
+                path: "missing".to_owned(),
+                size: 0,
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+        assert_eq!(store.metrics().requests(), 1);
+        assert_eq!(store.metrics().retries(), 0);
+    }
+
+    #[tokio::test]
+    async fn timeout_slow_requests() {
+        let mut flaky = FlakyObjectStore::new(vec![]);
+        flaky.delay = Duration::from_secs(10);
+        let store = instrumented(
+            flaky,
+            ObjectStoreRequestConfig::new()
+                .with_max_retries(0)
+                .with_timeout(Some(Duration::from_millis(10))),
+        );
+        let err = store.list_file("").await.err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+        assert_eq!(store.metrics().timeouts(), 1);
+    }
+}
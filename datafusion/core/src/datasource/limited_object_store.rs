@@ -0,0 +1,384 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An [`ObjectStore`] decorator capping the number of concurrent requests
+//! and the request rate of the wrapped store.
+
+use std::fmt;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use datafusion_data_access::object_store::{
+    FileMetaStream, ListEntryStream, ObjectReader, ObjectStore,
+};
+use datafusion_data_access::{Result, SizedFile};
+use futures::AsyncRead;
+use parking_lot::Mutex;
+use tokio::runtime::Handle;
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::runtime::RuntimeFlavor;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+#[cfg(not(target_arch = "wasm32"))]
+use tokio::task;
+
+/// Limits of the requests sent to object stores
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectStoreRequestLimits {
+    max_concurrent_requests: Option<usize>,
+    max_requests_per_second: Option<u32>,
+}
+
+impl ObjectStoreRequestLimits {
+    /// No limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of requests in flight at the same time, at least 1
+    pub fn with_max_concurrent_requests(
+        mut self,
+        max_concurrent_requests: usize,
+    ) -> Self {
+        self.max_concurrent_requests = Some(max_concurrent_requests.max(1));
+        self
+    }
+
+    /// Maximum number of requests started per second, at least 1. The rate
+    /// is not limited on wasm32, which has no clock to pace the requests.
+    pub fn with_max_requests_per_second(mut self, max_requests_per_second: u32) -> Self {
+        self.max_requests_per_second = Some(max_requests_per_second.max(1));
+        self
+    }
+
+    /// Maximum number of requests in flight at the same time
+    pub fn max_concurrent_requests(&self) -> Option<usize> {
+        self.max_concurrent_requests
+    }
+
+    /// Maximum number of requests started per second
+    pub fn max_requests_per_second(&self) -> Option<u32> {
+        self.max_requests_per_second
+    }
+}
+
+/// Enforces [`ObjectStoreRequestLimits`] over all the stores sharing it.
+///
+/// A request is the call opening a listing or a reader: the permit is
+/// released once the call returns, before the returned stream or reader is
+/// consumed, so that a slow consumer can never starve the other requests.
+#[derive(Debug)]
+pub struct RequestLimiter {
+    limits: ObjectStoreRequestLimits,
+    semaphore: Option<Arc<Semaphore>>,
+    /// Earliest start of the next request when the rate is limited, unset
+    /// until the first request
+    next_start: Mutex<Option<Instant>>,
+}
+
+impl RequestLimiter {
+    /// Create a limiter enforcing `limits`
+    pub fn new(limits: ObjectStoreRequestLimits) -> Self {
+        Self {
+            semaphore: limits
+                .max_concurrent_requests
+                .map(|permits| Arc::new(Semaphore::new(permits))),
+            limits,
+            next_start: Mutex::new(None),
+        }
+    }
+
+    /// The limits enforced by this limiter
+    pub fn limits(&self) -> &ObjectStoreRequestLimits {
+        &self.limits
+    }
+
+    /// Number of requests that can start right now without exceeding the
+    /// concurrency limit, if any
+    pub fn available_permits(&self) -> Option<usize> {
+        self.semaphore
+            .as_ref()
+            .map(|semaphore| semaphore.available_permits())
+    }
+
+    /// Returns how long to wait before starting the next request to respect
+    /// the rate limit, reserving its start time
+    fn reserve_start(&self) -> Duration {
+        match self.limits.max_requests_per_second {
+            // `Instant::now` panics on wasm32
+            Some(_) if cfg!(target_arch = "wasm32") => Duration::ZERO,
+            Some(rate) => {
+                let interval = Duration::from_secs(1) / rate;
+                let now = Instant::now();
+                let mut next_start = self.next_start.lock();
+                let start = next_start.map_or(now, |next_start| next_start.max(now));
+                *next_start = Some(start + interval);
+                start - now
+            }
+            None => Duration::ZERO,
+        }
+    }
+
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        let permit = match &self.semaphore {
+            Some(semaphore) => Some(
+                semaphore
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("request semaphore is never closed"),
+            ),
+            None => None,
+        };
+        let wait = self.reserve_start();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        permit
+    }
+
+    /// Waits for the limits in a synchronous call, e.g. when the CSV and
+    /// JSON scans open their files from the worker threads of the runtime.
+    ///
+    /// The worker threads of a multi-threaded runtime are handed over to
+    /// the other tasks while waiting, so that the tasks holding the permits
+    /// keep running. The thread of a current thread runtime can't be handed
+    /// over: its synchronous requests only take a free permit and don't wait
+    /// for the rate limit, so the limits are only enforced by its
+    /// asynchronous requests.
+    fn acquire_blocking(&self) -> Option<OwnedSemaphorePermit> {
+        match Handle::try_current() {
+            #[cfg(not(target_arch = "wasm32"))]
+            Ok(handle)
+                if matches!(handle.runtime_flavor(), RuntimeFlavor::MultiThread) =>
+            {
+                task::block_in_place(|| handle.block_on(self.acquire()))
+            }
+            Ok(_) => {
+                self.reserve_start();
+                self.semaphore
+                    .as_ref()
+                    .and_then(|semaphore| semaphore.clone().try_acquire_owned().ok())
+            }
+            Err(_) => {
+                let permit = self.semaphore.as_ref().map(|semaphore| {
+                    futures::executor::block_on(semaphore.clone().acquire_owned())
+                        .expect("request semaphore is never closed")
+                });
+                let wait = self.reserve_start();
+                if !wait.is_zero() {
+                    std::thread::sleep(wait);
+                }
+                permit
+            }
+        }
+    }
+}
+
+/// Wraps an [`ObjectStore`] so that its requests, and the requests of the
+/// readers it returns, respect the limits of a [`RequestLimiter`].
+pub struct LimitedObjectStore {
+    inner: Arc<dyn ObjectStore>,
+    limiter: Arc<RequestLimiter>,
+}
+
+impl fmt::Debug for LimitedObjectStore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LimitedObjectStore")
+            .field("inner", &self.inner)
+            .field("limits", self.limiter.limits())
+            .finish()
+    }
+}
+
+impl LimitedObjectStore {
+    /// Wrap `inner`, limiting its requests with `limiter`
+    pub fn new(inner: Arc<dyn ObjectStore>, limiter: Arc<RequestLimiter>) -> Self {
+        Self { inner, limiter }
+    }
+
+    /// The wrapped store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl ObjectStore for LimitedObjectStore {
+    async fn list_file(&self, prefix: &str) -> Result<FileMetaStream> {
+        let _permit = self.limiter.acquire().await;
+        self.inner.list_file(prefix).await
+    }
+
+    async fn list_dir(
+        &self,
+        prefix: &str,
+        delimiter: Option<String>,
+    ) -> Result<ListEntryStream> {
+        let _permit = self.limiter.acquire().await;
+        self.inner.list_dir(prefix, delimiter).await
+    }
+
+    fn file_reader(&self, file: SizedFile) -> Result<Arc<dyn ObjectReader>> {
+        let inner = {
+            let _permit = self.limiter.acquire_blocking();
+            self.inner.file_reader(file)?
+        };
+        Ok(Arc::new(LimitedObjectReader {
+            inner,
+            limiter: self.limiter.clone(),
+        }))
+    }
+}
+
+/// Wraps the [`ObjectReader`]s of a [`LimitedObjectStore`]
+struct LimitedObjectReader {
+    inner: Arc<dyn ObjectReader>,
+    limiter: Arc<RequestLimiter>,
+}
+
+#[async_trait]
+impl ObjectReader for LimitedObjectReader {
+    async fn chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> Result<Box<dyn AsyncRead>> {
+        let _permit = self.limiter.acquire().await;
+        self.inner.chunk_reader(start, length).await
+    }
+
+    fn sync_chunk_reader(
+        &self,
+        start: u64,
+        length: usize,
+    ) -> Result<Box<dyn Read + Send + Sync>> {
+        let _permit = self.limiter.acquire_blocking();
+        self.inner.sync_chunk_reader(start, length)
+    }
+
+    fn length(&self) -> u64 {
+        self.inner.length()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datafusion_data_access::object_store::local::LocalFileSystem;
+    use futures::StreamExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Records the maximum number of concurrent listing requests
+    #[derive(Debug, Default)]
+    struct ConcurrencyTracker {
+        current: AtomicUsize,
+        max: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ObjectStore for ConcurrencyTracker {
+        async fn list_file(&self, prefix: &str) -> Result<FileMetaStream> {
+            let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            self.current.fetch_sub(1, Ordering::SeqCst);
+            LocalFileSystem.list_file(prefix).await
+        }
+
+        async fn list_dir(
+            &self,
+            _prefix: &str,
+            _delimiter: Option<String>,
+        ) -> Result<ListEntryStream> {
+            unimplemented!()
+        }
+
+        fn file_reader(&self, file: SizedFile) -> Result<Arc<dyn ObjectReader>> {
+            LocalFileSystem.file_reader(file)
+        }
+    }
+
+    #[tokio::test]
+    async fn cap_concurrent_requests() -> Result<()> {
+        let tracker = Arc::new(ConcurrencyTracker::default());
+        let limiter = Arc::new(RequestLimiter::new(
+            ObjectStoreRequestLimits::new().with_max_concurrent_requests(2),
+        ));
+        let store = LimitedObjectStore::new(tracker.clone(), limiter.clone());
+
+        let listings = futures::future::join_all(
+            (0..8).map(|_| store.list_file("tests/example.csv")),
+        )
+        .await;
+        for listing in listings {
+            assert_eq!(listing?.count().await, 1);
+        }
+        assert_eq!(tracker.max.load(Ordering::SeqCst), 2);
+        assert_eq!(limiter.available_permits(), Some(2));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn open_readers_from_worker_threads() -> Result<()> {
+        let limiter = Arc::new(RequestLimiter::new(
+            ObjectStoreRequestLimits::new().with_max_concurrent_requests(0),
+        ));
+        assert_eq!(limiter.available_permits(), Some(1));
+        let store = Arc::new(LimitedObjectStore::new(
+            Arc::new(LocalFileSystem),
+            limiter.clone(),
+        ));
+
+        let files = futures::future::join_all((0..4).map(|_| {
+            let store = store.clone();
+            tokio::spawn(async move {
+                store
+                    .file_reader(SizedFile {
+                        path: "tests/example.csv".to_owned(),
+                        size: 0,
+                    })?
+                    .sync_chunk_reader(0, 0)
+                    .map(|_| ())
+            })
+        }))
+        .await;
+        for file in files {
+            file.unwrap()?;
+        }
+        assert_eq!(limiter.available_permits(), Some(1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn limit_request_rate() -> Result<()> {
+        let limiter = Arc::new(RequestLimiter::new(
+            ObjectStoreRequestLimits::new().with_max_requests_per_second(100),
+        ));
+        let store = LimitedObjectStore::new(Arc::new(LocalFileSystem), limiter.clone());
+
+        let start = Instant::now();
+        for _ in 0..5 {
+            store.list_file("tests/example.csv").await?;
+        }
+        // the requests start at least 10ms apart
+        assert!(start.elapsed() >= Duration::from_millis(40));
+        assert_eq!(limiter.available_permits(), None);
+        Ok(())
+    }
+}
@@ -41,10 +41,11 @@ use crate::{
     error::Result,
     execution::context::SessionContext,
     logical_plan::{self, Expr, ExprVisitable, ExpressionVisitor, Recursion},
+    physical_plan::file_format::VirtualColumn,
     scalar::ScalarValue,
 };
 
-use super::{PartitionedFile, PartitionedFileStream};
+use super::{PartitionValueExtractor, PartitionedFile, PartitionedFileStream};
 use datafusion_data_access::{object_store::ObjectStore, FileMeta, SizedFile};
 use datafusion_expr::Volatility;
 
@@ -162,7 +163,8 @@ pub async fn pruned_partition_list(
     table_path: &str,
     filters: &[Expr],
     file_extension: &str,
-    table_partition_cols: &[String],
+    table_partition_cols: &[VirtualColumn],
+    partitioning: Arc<dyn PartitionValueExtractor>,
 ) -> Result<PartitionedFileStream> {
     // if no partition col => simply list all the files
     if table_partition_cols.is_empty() {
@@ -180,9 +182,13 @@ pub async fn pruned_partition_list(
         ));
     }
 
+    let partition_names = table_partition_cols
+        .iter()
+        .map(|col| col.name().to_owned())
+        .collect::<Vec<_>>();
     let applicable_filters: Vec<_> = filters
         .iter()
-        .filter(|f| expr_applicable_for_cols(table_partition_cols, f))
+        .filter(|f| expr_applicable_for_cols(&partition_names, f))
         .collect();
     let stream_path = table_path.to_owned();
     if applicable_filters.is_empty() {
@@ -190,7 +196,7 @@ pub async fn pruned_partition_list(
         // Note: We might avoid parsing the partition values if they are not used in any projection,
         // but the cost of parsing will likely be far dominated by the time to fetch the listing from
         // the object store.
-        let table_partition_cols_stream = table_partition_cols.to_vec();
+        let table_partition_cols_stream = partition_names;
         Ok(Box::pin(
             store
                 .list_file_with_suffix(table_path, file_extension)
@@ -198,19 +204,21 @@ pub async fn pruned_partition_list(
                 .filter_map(move |f| {
                     let stream_path = stream_path.clone();
                     let table_partition_cols_stream = table_partition_cols_stream.clone();
+                    let partitioning = partitioning.clone();
                     async move {
                         let file_meta = match f {
                             Ok(fm) => fm,
                             Err(err) => return Some(Err(err)),
                         };
-                        let parsed_path = parse_partitions_for_path(
+                        let parsed_path = partition_values_for_path(
+                            partitioning.as_ref(),
                             &stream_path,
                             file_meta.path(),
                             &table_partition_cols_stream,
                         )
                         .map(|p| {
-                            p.iter()
-                                .map(|&pn| ScalarValue::Utf8(Some(pn.to_owned())))
+                            p.into_iter()
+                                .map(|pn| ScalarValue::Utf8(Some(pn)))
                                 .collect()
                         });
 
@@ -240,7 +248,14 @@ pub async fn pruned_partition_list(
                     .collect::<datafusion_data_access::Result<Vec<_>>>()
             })
             .map_err(DataFusionError::IoError)
-            .map(move |metas| paths_to_batch(table_partition_cols, &stream_path, &metas?))
+            .map(move |metas| {
+                paths_to_batch(
+                    table_partition_cols,
+                    partitioning.as_ref(),
+                    &stream_path,
+                    &metas?,
+                )
+            })
             .try_collect()
             .await?;
 
@@ -266,11 +281,12 @@ pub async fn pruned_partition_list(
 /// - one column for the file size named `_df_part_file_size_`
 /// - one column for with the original path named `_df_part_file_path_`
 /// - one column for with the last modified date named `_df_part_file_modified_`
-/// - ... one column by partition ...
+/// - ... one column by partition, of the type of its values ...
 ///
 /// Note: For the last modified date, this looses precisions higher than millisecond.
 fn paths_to_batch(
-    table_partition_cols: &[String],
+    table_partition_cols: &[VirtualColumn],
+    partitioning: &dyn PartitionValueExtractor,
     table_path: &str,
     metas: &[FileMeta],
 ) -> Result<RecordBatch> {
@@ -281,10 +297,17 @@ fn paths_to_batch(
         .iter()
         .map(|_| StringBuilder::new(metas.len()))
         .collect::<Vec<_>>();
+    let partition_names = table_partition_cols
+        .iter()
+        .map(|col| col.name().to_owned())
+        .collect::<Vec<_>>();
     for file_meta in metas {
-        if let Some(partition_values) =
-            parse_partitions_for_path(table_path, file_meta.path(), table_partition_cols)
-        {
+        if let Some(partition_values) = partition_values_for_path(
+            partitioning,
+            table_path,
+            file_meta.path(),
+            &partition_names,
+        ) {
             key_builder.append_value(file_meta.path())?;
             length_builder.append_value(file_meta.size())?;
             match file_meta.last_modified {
@@ -305,8 +328,10 @@ fn paths_to_batch(
         ArrayBuilder::finish(&mut length_builder),
         ArrayBuilder::finish(&mut modified_builder),
     ];
-    for mut partition_builder in partition_builders {
-        col_arrays.push(ArrayBuilder::finish(&mut partition_builder));
+    for (col, mut partition_builder) in
+        table_partition_cols.iter().zip(partition_builders)
+    {
+        col_arrays.push(col.cast_values(&ArrayBuilder::finish(&mut partition_builder))?);
     }
 
     // put the schema together
@@ -315,8 +340,8 @@ fn paths_to_batch(
         Field::new(FILE_SIZE_COLUMN_NAME, DataType::UInt64, false),
         Field::new(FILE_MODIFIED_COLUMN_NAME, DataType::Date64, false),
     ];
-    for pn in table_partition_cols {
-        fields.push(Field::new(pn, DataType::Utf8, false));
+    for col in table_partition_cols {
+        fields.push(Field::new(col.name(), col.value_type().clone(), false));
     }
 
     let batch = RecordBatch::try_new(Arc::new(Schema::new(fields)), col_arrays)?;
@@ -366,9 +391,22 @@ fn batches_to_paths(batches: &[RecordBatch]) -> Vec<PartitionedFile> {
         .collect()
 }
 
-/// Extract the partition values for the given `file_path` (in the given `table_path`)
-/// associated to the partitions defined by `table_partition_cols`
-fn parse_partitions_for_path<'a>(
+/// Extract the partition values of the given `file_path` (in the given `table_path`)
+/// with `partitioning`, if it extracts a value for each of the `table_partition_cols`
+fn partition_values_for_path(
+    partitioning: &dyn PartitionValueExtractor,
+    table_path: &str,
+    file_path: &str,
+    table_partition_cols: &[String],
+) -> Option<Vec<String>> {
+    partitioning
+        .partition_values(table_path, file_path, table_partition_cols)
+        .filter(|values| values.len() == table_partition_cols.len())
+}
+
+/// Extract the hive style partition values for the given `file_path` (in the given
+/// `table_path`) associated to the partitions defined by `table_partition_cols`
+pub(super) fn parse_partitions_for_path<'a>(
     table_path: &str,
     file_path: &'a str,
     table_partition_cols: &[String],
@@ -394,6 +432,7 @@ fn parse_partitions_for_path<'a>(
 #[cfg(test)]
 mod tests {
     use crate::{
+        datasource::listing::{HivePartitioning, RegexPartitioning},
         logical_plan::{case, col, lit},
         test::object_store::TestObjectStore,
     };
@@ -452,7 +491,8 @@ mod tests {
             "tablepath/",
             &[filter],
             ".parquet",
-            &[String::from("mypartition")],
+            &[VirtualColumn::partition("mypartition")],
+            Arc::new(HivePartitioning),
         )
         .await
         .expect("partition pruning failed")
@@ -475,7 +515,8 @@ mod tests {
             "tablepath/",
             &[filter],
             ".parquet",
-            &[String::from("mypartition")],
+            &[VirtualColumn::partition("mypartition")],
+            Arc::new(HivePartitioning),
         )
         .await
         .expect("partition pruning failed")
@@ -521,7 +562,11 @@ mod tests {
             "tablepath/",
             &[filter1, filter2, filter3],
             ".parquet",
-            &[String::from("part1"), String::from("part2")],
+            &[
+                VirtualColumn::partition("part1"),
+                VirtualColumn::partition("part2"),
+            ],
+            Arc::new(HivePartitioning),
         )
         .await
         .expect("partition pruning failed")
@@ -555,6 +600,111 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_pruned_partition_list_regex() {
+        let store = TestObjectStore::new_arc(&[
+            ("tablepath/data-2021-12-31.parquet", 100),
+            ("tablepath/data-2022-01-01.parquet", 100),
+            ("tablepath/archive/data-2022-01-02.parquet", 100),
+            ("tablepath/data.parquet", 100),
+        ]);
+        let partitioning = RegexPartitioning::try_new(
+            r"data-(?P<year>\d{4})-(?P<month>\d{2})-\d{2}\.parquet$",
+        )
+        .unwrap();
+        let filter = Expr::eq(col("year"), lit("2022"));
+        let pruned = pruned_partition_list(
+            store.as_ref(),
+            "tablepath/",
+            &[filter],
+            ".parquet",
+            &[
+                VirtualColumn::partition("year"),
+                VirtualColumn::partition("month"),
+            ],
+            Arc::new(partitioning),
+        )
+        .await
+        .expect("partition pruning failed")
+        .collect::<Vec<_>>()
+        .await;
+
+        assert_eq!(pruned.len(), 2);
+        let f1 = pruned[0].as_ref().expect("first item not an error");
+        assert_eq!(
+            &f1.file_meta.sized_file.path,
+            "tablepath/data-2022-01-01.parquet"
+        );
+        assert_eq!(
+            &f1.partition_values,
+            &[
+                ScalarValue::Utf8(Some(String::from("2022"))),
+                ScalarValue::Utf8(Some(String::from("01")))
+            ]
+        );
+        let f2 = pruned[1].as_ref().expect("second item not an error");
+        assert_eq!(
+            &f2.file_meta.sized_file.path,
+            "tablepath/archive/data-2022-01-02.parquet"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pruned_partition_list_typed() {
+        let store = TestObjectStore::new_arc(&[
+            ("tablepath/year=2021/file.parquet", 100),
+            ("tablepath/year=2022/file.parquet", 100),
+            ("tablepath/year=10000/file.parquet", 100),
+        ]);
+        // compared as numbers rather than as strings
+        let filter = col("year").gt(lit(2021));
+        let pruned = pruned_partition_list(
+            store.as_ref(),
+            "tablepath/",
+            &[filter.clone()],
+            ".parquet",
+            &[VirtualColumn::new("year", DataType::Int32)],
+            Arc::new(HivePartitioning),
+        )
+        .await
+        .expect("partition pruning failed")
+        .try_collect::<Vec<_>>()
+        .await
+        .expect("partition pruning failed");
+
+        let values = pruned
+            .iter()
+            .map(|f| f.partition_values.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                vec![ScalarValue::Int32(Some(2022))],
+                vec![ScalarValue::Int32(Some(10000))]
+            ]
+        );
+
+        let store =
+            TestObjectStore::new_arc(&[("tablepath/year=last/file.parquet", 100)]);
+        let err = pruned_partition_list(
+            store.as_ref(),
+            "tablepath/",
+            &[filter],
+            ".parquet",
+            &[VirtualColumn::new("year", DataType::Int32)],
+            Arc::new(HivePartitioning),
+        )
+        .await
+        .err()
+        .expect("the invalid partition value was not detected");
+        assert!(
+            err.to_string()
+                .contains("The value 'last' of the column 'year' is not a valid Int32"),
+            "{}",
+            err
+        );
+    }
+
     #[test]
     fn test_parse_partitions_for_path() {
         assert_eq!(
@@ -660,8 +810,9 @@ mod tests {
             },
         ];
 
-        let batches = paths_to_batch(&[], "mybucket/tablepath", &files)
-            .expect("Serialization of file list to batch failed");
+        let batches =
+            paths_to_batch(&[], &HivePartitioning, "mybucket/tablepath", &files)
+                .expect("Serialization of file list to batch failed");
 
         let parsed_files = batches_to_paths(&[batches]);
         assert_eq!(parsed_files.len(), 2);
@@ -694,9 +845,13 @@ mod tests {
             },
         ];
 
-        let batches =
-            paths_to_batch(&[String::from("part1")], "mybucket/tablepath", &files)
-                .expect("Serialization of file list to batch failed");
+        let batches = paths_to_batch(
+            &[VirtualColumn::partition("part1")],
+            &HivePartitioning,
+            "mybucket/tablepath",
+            &files,
+        )
+        .expect("Serialization of file list to batch failed");
 
         let parsed_files = batches_to_paths(&[batches]);
         assert_eq!(parsed_files.len(), 2);
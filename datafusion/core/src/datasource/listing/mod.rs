@@ -18,14 +18,18 @@
 //! A table that uses the `ObjectStore` listing capability
 //! to get the list of files to process.
 
-mod helpers;
+pub(crate) mod helpers;
+mod partitioning;
 mod table;
 
 use datafusion_common::ScalarValue;
-use datafusion_data_access::{object_store::local, FileMeta, Result, SizedFile};
+#[cfg(not(target_arch = "wasm32"))]
+use datafusion_data_access::object_store::local;
+use datafusion_data_access::{FileMeta, Result, SizedFile};
 use futures::Stream;
 use std::pin::Pin;
 
+pub use partitioning::{HivePartitioning, PartitionValueExtractor, RegexPartitioning};
 pub use table::{ListingOptions, ListingTable, ListingTableConfig};
 
 /// Stream of files get listed from object store
@@ -89,6 +93,7 @@ impl std::fmt::Display for PartitionedFile {
 }
 
 /// Helper method to fetch the file size and date at given path and create a `FileMeta`
+#[cfg(not(target_arch = "wasm32"))]
 pub fn local_unpartitioned_file(file: String) -> PartitionedFile {
     PartitionedFile {
         file_meta: local::local_unpartitioned_file(file),
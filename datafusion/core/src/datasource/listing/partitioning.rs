@@ -0,0 +1,245 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Extraction of the values of the partitioning columns of a listing table
+//! from the paths of its files
+
+use regex::Regex;
+
+use crate::error::{DataFusionError, Result};
+
+use super::helpers::parse_partitions_for_path;
+
+/// Extracts the values of the partitioning columns of a
+/// [`ListingTable`](super::ListingTable) from the paths of its files. The
+/// extracted values can be used to prune the files and are appended to the
+/// rows read from the files, like the values of hive style partitions.
+///
+/// Closures taking the path of a file relative to the path of the table,
+/// e.g. `2022/06/data-2022-06-01.csv`, and returning the values of the
+/// partitioning columns, in order, are extractors.
+pub trait PartitionValueExtractor: Send + Sync {
+    /// Returns the values of `table_partition_cols` for the file at
+    /// `file_path` in the table at `table_path`, or `None` if the path does
+    /// not follow the partitioning, in which case the file is not part of the
+    /// table
+    fn partition_values(
+        &self,
+        table_path: &str,
+        file_path: &str,
+        table_partition_cols: &[String],
+    ) -> Option<Vec<String>>;
+
+    /// Checks that the values of all the `table_partition_cols` can be
+    /// extracted
+    fn validate(&self, _table_partition_cols: &[String]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Hive style partitioning, the default: the directories below the table
+/// path are named after the partitioning columns and their values, e.g.
+/// `year=2022/month=06/data.csv`
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HivePartitioning;
+
+impl PartitionValueExtractor for HivePartitioning {
+    fn partition_values(
+        &self,
+        table_path: &str,
+        file_path: &str,
+        table_partition_cols: &[String],
+    ) -> Option<Vec<String>> {
+        parse_partitions_for_path(table_path, file_path, table_partition_cols)
+            .map(|values| values.into_iter().map(str::to_owned).collect())
+    }
+}
+
+/// Partitioning matching the paths of the files relative to the table path
+/// with a regular expression, whose named capture groups are the values of
+/// the partitioning columns of the same names, e.g. the pattern
+/// `data-(?P<year>\d{4})-(?P<month>\d{2})-\d{2}\.csv$` extracts the `year` and
+/// `month` from the dates in the names of the files. The files whose paths do
+/// not match are not part of the table.
+#[derive(Debug, Clone)]
+pub struct RegexPartitioning {
+    regex: Regex,
+}
+
+impl RegexPartitioning {
+    /// Creates a partitioning matching the paths with the regular expression
+    /// `pattern`
+    pub fn try_new(pattern: &str) -> Result<Self> {
+        let regex = Regex::new(pattern).map_err(|e| {
+            DataFusionError::Plan(format!(
+                "Invalid partitioning pattern '{}': {}",
+                pattern, e
+            ))
+        })?;
+        Ok(Self { regex })
+    }
+
+    /// The regular expression matching the paths
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+}
+
+impl PartitionValueExtractor for RegexPartitioning {
+    fn partition_values(
+        &self,
+        table_path: &str,
+        file_path: &str,
+        table_partition_cols: &[String],
+    ) -> Option<Vec<String>> {
+        let captures = self.regex.captures(relative_path(table_path, file_path)?)?;
+        table_partition_cols
+            .iter()
+            .map(|col| captures.name(col).map(|value| value.as_str().to_owned()))
+            .collect()
+    }
+
+    fn validate(&self, table_partition_cols: &[String]) -> Result<()> {
+        match table_partition_cols.iter().find(|col| {
+            !self
+                .regex
+                .capture_names()
+                .any(|name| name == Some(col.as_str()))
+        }) {
+            Some(col) => Err(DataFusionError::Plan(format!(
+                "The partitioning pattern '{}' has no capture group named after the partitioning column {}",
+                self.regex, col
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<F> PartitionValueExtractor for F
+where
+    F: Fn(&str) -> Option<Vec<String>> + Send + Sync,
+{
+    fn partition_values(
+        &self,
+        table_path: &str,
+        file_path: &str,
+        _table_partition_cols: &[String],
+    ) -> Option<Vec<String>> {
+        self(relative_path(table_path, file_path)?)
+    }
+}
+
+/// The path of the file at `file_path` relative to `table_path`, without
+/// leading separator
+fn relative_path<'a>(table_path: &str, file_path: &'a str) -> Option<&'a str> {
+    Some(
+        file_path
+            .strip_prefix(table_path)?
+            .trim_start_matches(std::path::is_separator),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cols(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn hive_partitioning() {
+        assert_eq!(
+            HivePartitioning.partition_values(
+                "bucket/mytable",
+                "bucket/mytable/year=2022/month=06/file.csv",
+                &cols(&["year", "month"])
+            ),
+            Some(cols(&["2022", "06"]))
+        );
+        assert_eq!(
+            HivePartitioning.partition_values(
+                "bucket/mytable",
+                "bucket/mytable/2022/06/file.csv",
+                &cols(&["year", "month"])
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn regex_partitioning() -> Result<()> {
+        let partitioning = RegexPartitioning::try_new(
+            r"data-(?P<year>\d{4})-(?P<month>\d{2})-\d{2}\.csv$",
+        )?;
+        let table_partition_cols = cols(&["year", "month"]);
+        partitioning.validate(&table_partition_cols)?;
+        assert_eq!(
+            partitioning.partition_values(
+                "bucket/mytable/",
+                "bucket/mytable/archive/data-2022-06-01.csv",
+                &table_partition_cols
+            ),
+            Some(cols(&["2022", "06"]))
+        );
+        assert_eq!(
+            partitioning.partition_values(
+                "bucket/mytable/",
+                "bucket/mytable/data.csv",
+                &table_partition_cols
+            ),
+            None
+        );
+        assert_eq!(
+            partitioning.partition_values(
+                "bucket/mytable/",
+                "bucket/othertable/data-2022-06-01.csv",
+                &table_partition_cols
+            ),
+            None
+        );
+
+        let err = partitioning.validate(&cols(&["year", "day"])).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("has no capture group named after the partitioning column day"));
+        assert!(RegexPartitioning::try_new("data-(").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn closure_partitioning() {
+        // the date is the first 10 characters of the name of the file
+        let partitioning = |path: &str| path.get(..10).map(|date| vec![date.to_owned()]);
+        assert_eq!(
+            partitioning.partition_values(
+                "bucket/mytable",
+                "bucket/mytable/2022-06-01T00.csv",
+                &cols(&["date"])
+            ),
+            Some(cols(&["2022-06-01"]))
+        );
+        assert_eq!(
+            partitioning.partition_values(
+                "bucket/mytable",
+                "bucket/mytable/x.csv",
+                &cols(&["date"])
+            ),
+            None
+        );
+    }
+}
@@ -17,31 +17,48 @@
 
 //! The table implementation.
 
-use std::{any::Any, sync::Arc};
-
-use arrow::datatypes::{Field, Schema, SchemaRef};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::Path;
+use std::{any::Any, collections::HashSet, sync::Arc};
+
+use arrow::compute::SortOptions;
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use arrow::{csv, json};
 use async_trait::async_trait;
-use futures::StreamExt;
+use futures::{StreamExt, TryStreamExt};
+use log::debug;
+use parquet::arrow::ArrowWriter;
 
 use crate::datasource::{
+    checkpoint::Checkpoint,
     file_format::{
         avro::AvroFormat, csv::CsvFormat, json::JsonFormat, parquet::ParquetFormat,
         FileFormat,
     },
-    get_statistics_with_limit, TableProvider,
+    get_statistics_with_limit,
+    mutation::RowMutation,
+    zone_map::ZoneMapIndex,
+    TableProvider,
 };
 use crate::logical_expr::TableProviderFilterPushDown;
 use crate::{
     error::{DataFusionError, Result},
-    logical_plan::Expr,
+    execution::context::{default_target_partitions, SessionState, TaskContext},
+    logical_plan::{Constraints, Expr},
     physical_plan::{
+        collect,
         empty::EmptyExec,
-        file_format::{FileScanConfig, DEFAULT_PARTITION_COLUMN_DATATYPE},
+        expressions::{Column, PhysicalSortExpr},
+        file_format::{FileScanConfig, ParquetExec, VirtualColumn},
         project_schema, ExecutionPlan, Statistics,
     },
+    scalar::ScalarValue,
 };
 
-use super::PartitionedFile;
+use super::{HivePartitioning, PartitionValueExtractor, PartitionedFile};
 use datafusion_data_access::object_store::ObjectStore;
 
 use super::helpers::{expr_applicable_for_cols, pruned_partition_list, split_files};
@@ -123,8 +140,9 @@ impl ListingTableConfig {
             format,
             collect_stat: true,
             file_extension: file_type.to_string(),
-            target_partitions: num_cpus::get(),
+            target_partitions: default_target_partitions(),
             table_partition_cols: vec![],
+            file_sort_order: vec![],
         };
 
         Ok(Self {
@@ -175,8 +193,8 @@ pub struct ListingOptions {
     /// partitioning expected should be named "a" and "b":
     /// - If there is a third level of partitioning it will be ignored.
     /// - Files that don't follow this partitioning will be ignored.
-    /// Note that only `DEFAULT_PARTITION_COLUMN_DATATYPE` is currently
-    /// supported for the column type.
+    /// The columns are of type `DEFAULT_PARTITION_COLUMN_DATATYPE`, unless
+    /// declared otherwise with [`ListingTable::with_partition_column_types`].
     pub table_partition_cols: Vec<String>,
     /// Set true to try to guess statistics from the files.
     /// This can add a lot of overhead as it will usually require files
@@ -185,6 +203,14 @@ pub struct ListingOptions {
     /// Group files to avoid that the number of partitions exceeds
     /// this limit
     pub target_partitions: usize,
+    /// The order of the rows of each file, as sort expressions on the
+    /// columns of the table, e.g. `[col("ts").sort(true, false)]`.
+    /// If set, the scans are known to produce their rows in this order: the
+    /// files are grouped into `target_partitions` partitions if the ranges
+    /// of their first sort column, known from their statistics, don't
+    /// overlap, and each file is scanned in its own partition otherwise. The
+    /// files are not checked to be sorted.
+    pub file_sort_order: Vec<Expr>,
 }
 
 impl ListingOptions {
@@ -194,6 +220,7 @@ impl ListingOptions {
     /// - no input partition to discover
     /// - one target partition
     /// - no stat collection
+    /// - no known order of the files
     pub fn new(format: Arc<dyn FileFormat>) -> Self {
         Self {
             file_extension: String::new(),
@@ -201,6 +228,7 @@ impl ListingOptions {
             table_partition_cols: vec![],
             collect_stat: true,
             target_partitions: 1,
+            file_sort_order: vec![],
         }
     }
 
@@ -234,6 +262,13 @@ pub struct ListingTable {
     /// File fields + partition columns
     table_schema: SchemaRef,
     options: ListingOptions,
+    /// The partitioning columns, filled with the values parsed from the
+    /// paths of the files
+    partition_columns: Vec<VirtualColumn>,
+    checkpoint: Option<Arc<dyn Checkpoint>>,
+    zone_maps: Option<Arc<ZoneMapIndex>>,
+    constraints: Constraints,
+    partitioning: Arc<dyn PartitionValueExtractor>,
 }
 
 impl ListingTable {
@@ -254,21 +289,27 @@ impl ListingTable {
         })?;
 
         // Add the partition columns to the file schema
-        let mut table_fields = file_schema.fields().clone();
-        for part in &options.table_partition_cols {
-            table_fields.push(Field::new(
-                part,
-                DEFAULT_PARTITION_COLUMN_DATATYPE.clone(),
-                false,
-            ));
+        let partition_columns = options
+            .table_partition_cols
+            .iter()
+            .map(VirtualColumn::partition)
+            .collect::<Vec<_>>();
+        let table_schema = table_schema(&file_schema, &partition_columns);
+        for sort_expr in &options.file_sort_order {
+            sort_column(sort_expr, &table_schema)?;
         }
 
         let table = Self {
             object_store: config.object_store.clone(),
             table_path: config.table_path.clone(),
             file_schema,
-            table_schema: Arc::new(Schema::new(table_fields)),
+            table_schema: Arc::new(table_schema),
             options,
+            partition_columns,
+            checkpoint: None,
+            zone_maps: None,
+            constraints: Constraints::empty(),
+            partitioning: Arc::new(HivePartitioning),
         };
 
         Ok(table)
@@ -286,6 +327,97 @@ impl ListingTable {
     pub fn options(&self) -> &ListingOptions {
         &self.options
     }
+
+    /// Skip the files consumed by the writes recorded in `checkpoint`, so
+    /// that a restarted checkpointed write only processes the remaining files
+    pub fn with_checkpoint(mut self, checkpoint: Arc<dyn Checkpoint>) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Get the checkpoint of the table, if any
+    pub fn checkpoint(&self) -> Option<&Arc<dyn Checkpoint>> {
+        self.checkpoint.as_ref()
+    }
+
+    /// Skip the files whose zone maps in `zone_maps` show that none of
+    /// their rows match the filters of a scan, without opening them
+    pub fn with_zone_maps(mut self, zone_maps: Arc<ZoneMapIndex>) -> Self {
+        self.zone_maps = Some(zone_maps);
+        self
+    }
+
+    /// Get the zone maps of the files of the table, if any
+    pub fn zone_maps(&self) -> Option<&Arc<ZoneMapIndex>> {
+        self.zone_maps.as_ref()
+    }
+
+    /// Declares the `constraints` on the columns of the table, including its
+    /// partitioning columns. The files are not checked to satisfy them.
+    pub fn with_constraints(mut self, constraints: Constraints) -> Result<Self> {
+        constraints.validate(&self.table_schema)?;
+        self.constraints = constraints;
+        Ok(self)
+    }
+
+    /// Extract the values of the partitioning columns from the paths of the
+    /// files with `partitioning`, rather than from hive style partitions
+    /// (`column=value` directories), e.g. with a
+    /// [`RegexPartitioning`](super::RegexPartitioning)
+    /// matching dates in the names of the files
+    pub fn with_partitioning(
+        mut self,
+        partitioning: Arc<dyn PartitionValueExtractor>,
+    ) -> Result<Self> {
+        partitioning.validate(&self.options.table_partition_cols)?;
+        self.partitioning = partitioning;
+        Ok(self)
+    }
+
+    /// Get the extraction of the values of the partitioning columns
+    pub fn partitioning(&self) -> &Arc<dyn PartitionValueExtractor> {
+        &self.partitioning
+    }
+
+    /// Declares the types of the partitioning columns, in the order of
+    /// [`ListingOptions::table_partition_cols`], e.g. `DataType::Int32` for
+    /// `year=2022` directories. The values parsed from the paths of the files
+    /// are cast to these types, the files with values that can't be cast
+    /// failing the scans. Partitioning columns of `DataType::Utf8` rather
+    /// than `DEFAULT_PARTITION_COLUMN_DATATYPE` are plain strings, like the
+    /// string columns of the files.
+    pub fn with_partition_column_types(mut self, types: Vec<DataType>) -> Result<Self> {
+        if types.len() != self.options.table_partition_cols.len() {
+            return Err(DataFusionError::Plan(format!(
+                "Expected the types of {} partitioning columns, got {}",
+                self.options.table_partition_cols.len(),
+                types.len()
+            )));
+        }
+        self.partition_columns = self
+            .options
+            .table_partition_cols
+            .iter()
+            .zip(types)
+            .map(|(name, data_type)| VirtualColumn::new(name, data_type))
+            .collect();
+        self.table_schema =
+            Arc::new(table_schema(&self.file_schema, &self.partition_columns));
+        Ok(self)
+    }
+
+    /// Get the partitioning columns of the table
+    pub fn partition_columns(&self) -> &[VirtualColumn] {
+        &self.partition_columns
+    }
+}
+
+/// The schema of the table: the fields of the files followed by the
+/// partitioning columns
+fn table_schema(file_schema: &Schema, partition_columns: &[VirtualColumn]) -> Schema {
+    let mut table_fields = file_schema.fields().clone();
+    table_fields.extend(partition_columns.iter().map(VirtualColumn::field));
+    Schema::new(table_fields)
 }
 
 #[async_trait]
@@ -298,13 +430,17 @@ impl TableProvider for ListingTable {
         Arc::clone(&self.table_schema)
     }
 
+    fn constraints(&self) -> Option<&Constraints> {
+        Some(&self.constraints)
+    }
+
     async fn scan(
         &self,
         projection: &Option<Vec<usize>>,
         filters: &[Expr],
         limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let (partitioned_file_lists, statistics) =
+        let (partitioned_file_lists, statistics, listed_files) =
             self.list_files_for_scan(filters, limit).await?;
 
         // if no files need to be read, return an `EmptyExec`
@@ -315,7 +451,8 @@ impl TableProvider for ListingTable {
         }
 
         // create the execution plan
-        self.options
+        let plan = self
+            .options
             .format
             .create_physical_plan(
                 FileScanConfig {
@@ -325,11 +462,16 @@ impl TableProvider for ListingTable {
                     statistics,
                     projection: projection.clone(),
                     limit,
-                    table_partition_cols: self.options.table_partition_cols.clone(),
+                    table_partition_cols: self.partition_columns.clone(),
+                    output_ordering: self.output_ordering(projection)?,
                 },
                 filters,
             )
-            .await
+            .await?;
+        if let Some(parquet_exec) = plan.as_any().downcast_ref::<ParquetExec>() {
+            parquet_exec.record_listed_files(listed_files.listed, listed_files.pruned);
+        }
+        Ok(plan)
     }
 
     fn supports_filter_pushdown(
@@ -345,9 +487,70 @@ impl TableProvider for ListingTable {
             Ok(TableProviderFilterPushDown::Inexact)
         }
     }
+
+    async fn delete(
+        &self,
+        state: &SessionState,
+        predicate: Option<&Expr>,
+    ) -> Result<u64> {
+        let mutation = RowMutation::try_new_delete(state, self.schema(), predicate)?;
+        self.rewrite_files(state, &mutation, predicate).await
+    }
+
+    async fn update(
+        &self,
+        state: &SessionState,
+        assignments: &[(String, Expr)],
+        predicate: Option<&Expr>,
+    ) -> Result<u64> {
+        if let Some((column, _)) = assignments
+            .iter()
+            .find(|(column, _)| self.options.table_partition_cols.contains(column))
+        {
+            return Err(DataFusionError::Plan(format!(
+                "The partitioning column '{}' can't be updated",
+                column
+            )));
+        }
+        // the rewritten files would no longer be sorted
+        for sort_expr in &self.options.file_sort_order {
+            let (name, _) = sort_column(sort_expr, &self.table_schema)?;
+            if assignments.iter().any(|(column, _)| column == name) {
+                return Err(DataFusionError::Plan(format!(
+                    "The column '{}' of the order of the files can't be updated",
+                    name
+                )));
+            }
+        }
+        let mutation =
+            RowMutation::try_new_update(state, self.schema(), assignments, predicate)?;
+        self.rewrite_files(state, &mutation, predicate).await
+    }
 }
 
 impl ListingTable {
+    /// The order of the rows of the files, as sort expressions on the
+    /// projected columns of the table. The order is truncated before its
+    /// first column that is not projected.
+    fn output_ordering(
+        &self,
+        projection: &Option<Vec<usize>>,
+    ) -> Result<Option<Vec<PhysicalSortExpr>>> {
+        let projected_schema = project_schema(&self.table_schema, projection.as_ref())?;
+        let mut ordering = vec![];
+        for sort_expr in &self.options.file_sort_order {
+            let (name, options) = sort_column(sort_expr, &self.table_schema)?;
+            match projected_schema.index_of(name) {
+                Ok(index) => ordering.push(PhysicalSortExpr {
+                    expr: Arc::new(Column::new(name, index)),
+                    options,
+                }),
+                Err(_) => break,
+            }
+        }
+        Ok((!ordering.is_empty()).then(|| ordering))
+    }
+
     /// Get the list of files for a scan as well as the file level statistics.
     /// The list is grouped to let the execution plan know how the files should
     /// be distributed to different threads / executors.
@@ -355,23 +558,49 @@ impl ListingTable {
         &'a self,
         filters: &'a [Expr],
         limit: Option<usize>,
-    ) -> Result<(Vec<Vec<PartitionedFile>>, Statistics)> {
+    ) -> Result<(Vec<Vec<PartitionedFile>>, Statistics, ListedFiles)> {
         // list files (with partitions)
-        let file_list = pruned_partition_list(
+        let mut file_list = pruned_partition_list(
             self.object_store.as_ref(),
             &self.table_path,
             filters,
             &self.options.file_extension,
-            &self.options.table_partition_cols,
+            &self.partition_columns,
+            Arc::clone(&self.partitioning),
         )
+        .await?
+        .try_collect::<Vec<_>>()
         .await?;
+        let files_listed = file_list.len();
+
+        // skip the files already written by a checkpointed write
+        let consumed_files = match &self.checkpoint {
+            Some(checkpoint) => checkpoint.consumed_files()?,
+            None => HashSet::new(),
+        };
+        file_list
+            .retain(|part_file| !consumed_files.contains(part_file.file_meta.path()));
+
+        // skip the files whose zone maps don't match the filters
+        if let Some(zone_maps) = &self.zone_maps {
+            file_list =
+                zone_maps.prune(&self.table_path, file_list, filters, &self.file_schema);
+        }
+        let listed_files = ListedFiles {
+            listed: files_listed,
+            pruned: files_listed - file_list.len(),
+        };
+
+        // the files are read in the order of their paths, whatever the order
+        // of the listing, so that the scans are reproducible
+        file_list.sort_by(|a, b| a.file_meta.path().cmp(b.file_meta.path()));
+        let file_list = futures::stream::iter(file_list);
 
         // collect the statistics if required by the config
         let object_store = Arc::clone(&self.object_store);
         let files = file_list.then(move |part_file| {
             let object_store = object_store.clone();
             async move {
-                let part_file = part_file?;
                 let statistics = if self.options.collect_stat {
                     let object_reader = object_store
                         .file_reader(part_file.file_meta.sized_file.clone())?;
@@ -386,13 +615,286 @@ impl ListingTable {
             }
         });
 
+        // the ranges of the values of the first sort column of the files, to
+        // read several sorted files in a partition
+        let sort_key = match self.options.file_sort_order.first() {
+            Some(sort_expr) => {
+                let (name, options) = sort_column(sort_expr, &self.table_schema)?;
+                self.file_schema
+                    .index_of(name)
+                    .ok()
+                    .map(|index| (index, options))
+            }
+            None => None,
+        };
+        let mut file_ranges = HashMap::new();
+        let files = files.inspect_ok(|(part_file, statistics)| {
+            if let Some(range) =
+                sort_key.and_then(|(index, _)| file_range(statistics, index))
+            {
+                file_ranges.insert(part_file.file_meta.path().to_owned(), range);
+            }
+        });
+
         let (files, statistics) =
             get_statistics_with_limit(files, self.schema(), limit).await?;
 
-        Ok((
-            split_files(files, self.options.target_partitions),
-            statistics,
-        ))
+        let target_partitions = self.options.target_partitions;
+        let file_groups = match sort_key {
+            None if self.options.file_sort_order.is_empty() => {
+                split_files(files, target_partitions)
+            }
+            // the rows of a partition are only sorted when it reads a single
+            // file, or files whose values follow each other
+            Some((_, options)) if files.len() > target_partitions => {
+                split_sorted_files(&files, &file_ranges, options, target_partitions)
+                    .unwrap_or_else(|| files.into_iter().map(|file| vec![file]).collect())
+            }
+            _ => files.into_iter().map(|file| vec![file]).collect(),
+        };
+
+        Ok((file_groups, statistics, listed_files))
+    }
+
+    /// Applies `mutation` to the files of the table which may have rows
+    /// matching `predicate`, and returns the number of rows it deleted or
+    /// updated.
+    ///
+    /// The files are copied on write: each file with matching rows is
+    /// rewritten next to the original, and the rewritten files replace the
+    /// originals once all of them are written. A file whose rows are all
+    /// deleted is removed. Only the CSV, JSON and Parquet files of the local
+    /// file system can be rewritten, and only when the table has no zone
+    /// maps, which would otherwise describe the original files.
+    async fn rewrite_files(
+        &self,
+        state: &SessionState,
+        mutation: &RowMutation,
+        predicate: Option<&Expr>,
+    ) -> Result<u64> {
+        let format = self.options.format.as_any();
+        if !(format.is::<CsvFormat>()
+            || format.is::<JsonFormat>()
+            || format.is::<ParquetFormat>())
+        {
+            return Err(DataFusionError::NotImplemented(format!(
+                "Only the CSV, JSON and Parquet files of {} can be rewritten",
+                self.table_path
+            )));
+        }
+        if self.zone_maps.is_some() {
+            return Err(DataFusionError::NotImplemented(format!(
+                "The files of {} can't be rewritten while they are pruned with zone maps",
+                self.table_path
+            )));
+        }
+
+        let filters = predicate.cloned().into_iter().collect::<Vec<_>>();
+        let (file_groups, _, _) = self.list_files_for_scan(&filters, None).await?;
+        let task_ctx = Arc::new(TaskContext::from(state));
+        let num_file_columns = self.file_schema.fields().len();
+        let mut num_mutated = 0;
+        // the rewritten files, or None for the removed ones, by original path
+        let mut rewrites = vec![];
+        for file in file_groups.into_iter().flatten() {
+            let path = file.file_meta.path().to_string();
+            if !Path::new(&path).is_file() {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "Only the files of the local file system can be rewritten, {} is not one",
+                    path
+                )));
+            }
+            // the pushed down filters are not applied, e.g. to the row
+            // groups of Parquet files, so that all the rows are rewritten
+            let plan = self
+                .options
+                .format
+                .create_physical_plan(
+                    FileScanConfig {
+                        object_store: Arc::clone(&self.object_store),
+                        file_schema: Arc::clone(&self.file_schema),
+                        file_groups: vec![vec![file]],
+                        statistics: Statistics::default(),
+                        projection: None,
+                        limit: None,
+                        table_partition_cols: self.partition_columns.clone(),
+                        output_ordering: None,
+                    },
+                    &[],
+                )
+                .await?;
+
+            let mut num_matched = 0;
+            let mut batches = vec![];
+            for batch in collect(plan, task_ctx.clone()).await? {
+                let (batch, matched) = mutation.apply(&batch)?;
+                num_matched += matched;
+                if batch.num_rows() > 0 {
+                    // the partitioning columns are not written
+                    let file_columns = (0..num_file_columns).collect::<Vec<_>>();
+                    batches.push(batch.project(&file_columns)?);
+                }
+            }
+            if num_matched == 0 {
+                continue;
+            }
+            num_mutated += num_matched as u64;
+            if batches.is_empty() {
+                rewrites.push((path, None));
+            } else {
+                let temp_path = format!("{}.tmp", path);
+                if let Err(e) = self.write_file(&temp_path, &batches) {
+                    fs::remove_file(&temp_path).ok();
+                    for temp_path in rewrites.into_iter().filter_map(|(_, t)| t) {
+                        fs::remove_file(temp_path).ok();
+                    }
+                    return Err(e);
+                }
+                rewrites.push((path, Some(temp_path)));
+            }
+        }
+
+        for (path, temp_path) in &rewrites {
+            match temp_path {
+                Some(temp_path) => fs::rename(temp_path, path)?,
+                None => fs::remove_file(path)?,
+            }
+        }
+        debug!(
+            "Rewrote {} files of {} mutating {} rows",
+            rewrites.len(),
+            self.table_path,
+            num_mutated
+        );
+        Ok(num_mutated)
+    }
+
+    /// Writes the `batches` of the columns of the files of the table to
+    /// `path`, in the format of the table
+    fn write_file(&self, path: &str, batches: &[RecordBatch]) -> Result<()> {
+        let file = File::create(path)?;
+        let format = self.options.format.as_any();
+        if let Some(csv_format) = format.downcast_ref::<CsvFormat>() {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(csv_format.has_header())
+                .with_delimiter(csv_format.delimiter())
+                .build(file);
+            for batch in batches {
+                writer.write(batch)?;
+            }
+        } else if format.is::<JsonFormat>() {
+            let mut writer = json::LineDelimitedWriter::new(file);
+            writer.write_batches(batches)?;
+            writer.finish()?;
+        } else {
+            let mut writer =
+                ArrowWriter::try_new(file, Arc::clone(&self.file_schema), None)?;
+            for batch in batches {
+                writer.write(batch)?;
+            }
+            writer.close()?;
+        }
+        Ok(())
+    }
+}
+
+/// The number of files listed for a scan, and of the ones pruned before the
+/// scan, e.g. by the zone maps of the table or because they were already
+/// written by a checkpointed write. The files of the partitions pruned by the
+/// filters are not listed.
+#[derive(Debug, Clone, Copy)]
+struct ListedFiles {
+    listed: usize,
+    pruned: usize,
+}
+
+/// Returns the range of the values of the column `index` of a file, if it is
+/// known and the column has no nulls.
+///
+/// The range may be wider than the values when the statistics are inexact,
+/// e.g. the bounds of the truncated string statistics of Parquet files, so
+/// that the files whose ranges don't overlap never have overlapping values.
+fn file_range(
+    statistics: &Statistics,
+    index: usize,
+) -> Option<(ScalarValue, ScalarValue)> {
+    let column = statistics.column_statistics.as_ref()?.get(index)?;
+    if column.null_count != Some(0) {
+        return None;
+    }
+    Some((column.min_value.clone()?, column.max_value.clone()?))
+}
+
+/// Groups the sorted `files` into at most `n` partitions, each reading its
+/// files one after the other in the order of their values, given the
+/// `ranges` of the values of their first sort column, sorted as `options`.
+/// Returns `None` if the range of a file is unknown or if the ranges of two
+/// files overlap, as the files could then only be read in order by merging
+/// them.
+fn split_sorted_files(
+    files: &[PartitionedFile],
+    ranges: &HashMap<String, (ScalarValue, ScalarValue)>,
+    options: SortOptions,
+    n: usize,
+) -> Option<Vec<Vec<PartitionedFile>>> {
+    let mut sorted_files = files
+        .iter()
+        .map(|file| Some((ranges.get(file.file_meta.path())?, file)))
+        .collect::<Option<Vec<_>>>()?;
+    let mut comparable = true;
+    sorted_files.sort_by(|((a, _), _), ((b, _), _)| {
+        a.partial_cmp(b).unwrap_or_else(|| {
+            comparable = false;
+            Ordering::Equal
+        })
+    });
+    let disjoint = sorted_files.windows(2).all(|pair| {
+        let ((_, max), _) = pair[0];
+        let ((min, _), _) = pair[1];
+        max.partial_cmp(min) == Some(Ordering::Less)
+    });
+    if !comparable || !disjoint {
+        return None;
+    }
+
+    let mut sorted_files = sorted_files
+        .into_iter()
+        .map(|(_, file)| file.clone())
+        .collect::<Vec<_>>();
+    if options.descending {
+        sorted_files.reverse();
+    }
+    Some(split_files(sorted_files, n))
+}
+
+/// Returns the name of the column of `schema` sorted by `sort_expr`, an
+/// expression of the sort order of the files, and how it is sorted
+fn sort_column<'a>(
+    sort_expr: &'a Expr,
+    schema: &Schema,
+) -> Result<(&'a str, SortOptions)> {
+    match sort_expr {
+        Expr::Sort {
+            expr,
+            asc,
+            nulls_first,
+        } => match expr.as_ref() {
+            Expr::Column(column) if schema.index_of(&column.name).is_ok() => Ok((
+                &column.name,
+                SortOptions {
+                    descending: !*asc,
+                    nulls_first: *nulls_first,
+                },
+            )),
+            _ => Err(DataFusionError::Plan(format!(
+                "The sort order of the files can only sort the columns of the table, found {:?}",
+                expr
+            ))),
+        },
+        _ => Err(DataFusionError::Plan(format!(
+            "The sort order of the files must consist of sort expressions, found {:?}",
+            sort_expr
+        ))),
     }
 }
 
@@ -405,9 +907,11 @@ mod tests {
         logical_plan::{col, lit},
         test::{columns, object_store::TestObjectStore},
     };
-    use arrow::datatypes::DataType;
+    use arrow::array::{ArrayRef, Int32Array};
+    use arrow::datatypes::{DataType, Field};
 
     use super::*;
+    use crate::datasource::MemTable;
 
     #[tokio::test]
     async fn read_single_file() -> Result<()> {
@@ -454,10 +958,11 @@ mod tests {
 
         let opt = ListingOptions {
             file_extension: DEFAULT_AVRO_EXTENSION.to_owned(),
-            format: Arc::new(AvroFormat {}),
+            format: Arc::new(AvroFormat::default()),
             table_partition_cols: vec![String::from("p1")],
             target_partitions: 4,
             collect_stat: true,
+            file_sort_order: vec![],
         };
 
         let file_schema =
@@ -553,6 +1058,310 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn scan_sorted_files() -> Result<()> {
+        let store = TestObjectStore::new_arc(&[
+            ("table/file0.avro", 10),
+            ("table/file1.avro", 10),
+            ("table/file2.avro", 10),
+        ]);
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let table_with_order = |file_sort_order| {
+            let opt = ListingOptions {
+                file_extension: DEFAULT_AVRO_EXTENSION.to_owned(),
+                format: Arc::new(AvroFormat::default()),
+                table_partition_cols: vec![],
+                target_partitions: 2,
+                collect_stat: false,
+                file_sort_order,
+            };
+            let config = ListingTableConfig::new(store.clone(), "table/")
+                .with_listing_options(opt)
+                .with_schema(file_schema.clone());
+            ListingTable::try_new(config)
+        };
+        let table = table_with_order(vec![
+            col("a").sort(true, false),
+            col("b").sort(false, true),
+        ])?;
+
+        // each file is scanned in its own partition
+        let scan = table.scan(&None, &[], None).await?;
+        assert_eq!(scan.output_partitioning().partition_count(), 3);
+        let ordering = scan.output_ordering().expect("the files are sorted");
+        assert_eq!(ordering.len(), 2);
+        assert!(!ordering[0].options.descending);
+        assert!(ordering[1].options.descending && ordering[1].options.nulls_first);
+
+        // the order is truncated before its first column that is not projected
+        let scan = table.scan(&Some(vec![1, 0]), &[], None).await?;
+        let ordering = scan.output_ordering().expect("the files are sorted");
+        let column = ordering[0].expr.as_any().downcast_ref::<Column>().unwrap();
+        assert_eq!((column.name(), column.index()), ("a", 1));
+        assert_eq!(ordering.len(), 2);
+        let scan = table.scan(&Some(vec![1]), &[], None).await?;
+        assert!(scan.output_ordering().is_none());
+
+        let err = table_with_order(vec![col("c").sort(true, false)])
+            .err()
+            .expect("c is not a column of the table");
+        assert!(err
+            .to_string()
+            .contains("The sort order of the files can only sort the columns"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_files_with_zone_maps() -> Result<()> {
+        let store = TestObjectStore::new_arc(&[
+            ("table/file0.avro", 10),
+            ("table/file1.avro", 10),
+            ("table/file2.avro", 10),
+        ]);
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let zone_maps = ZoneMapIndex::try_from_json(
+            r#"{
+                "file0.avro": {"columns": {"a": {"min": 1, "max": 10}}},
+                "file1.avro": {"columns": {"a": {"min": 11, "max": 20}}}
+            }"#,
+            &schema,
+        )?;
+        let opt = ListingOptions {
+            file_extension: DEFAULT_AVRO_EXTENSION.to_owned(),
+            format: Arc::new(AvroFormat::default()),
+            table_partition_cols: vec![],
+            target_partitions: 1,
+            collect_stat: false,
+            file_sort_order: vec![],
+        };
+        let config = ListingTableConfig::new(store, "table/")
+            .with_listing_options(opt)
+            .with_schema(schema);
+        let table = ListingTable::try_new(config)?.with_zone_maps(Arc::new(zone_maps));
+
+        let (file_list, _, listed_files) = table
+            .list_files_for_scan(&[col("a").gt(lit(15))], None)
+            .await?;
+        let paths = file_list
+            .iter()
+            .flatten()
+            .map(|f| f.file_meta.path())
+            .collect::<Vec<_>>();
+        // file2.avro has no zone map
+        assert_eq!(paths, vec!["table/file1.avro", "table/file2.avro"]);
+        assert_eq!((listed_files.listed, listed_files.pruned), (3, 1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_and_update_csv_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("file0.csv"), "a,b\n1,x\n2,y\n")?;
+        fs::write(dir.path().join("file1.csv"), "a,b\n3,z\n")?;
+        fs::write(dir.path().join("file2.csv"), "a,b\n4,w\n")?;
+        let table_path = dir.path().to_str().unwrap();
+        let config = ListingTableConfig::new(Arc::new(LocalFileSystem {}), table_path)
+            .infer()
+            .await?;
+        let table = ListingTable::try_new(config)?;
+        let ctx = crate::prelude::SessionContext::new();
+        let state = ctx.state.read().clone();
+
+        let deleted = table
+            .delete(
+                &state,
+                Some(&col("a").gt_eq(lit(2)).and(col("a").lt(lit(4)))),
+            )
+            .await?;
+        assert_eq!(deleted, 2);
+        // the files whose rows are all deleted are removed
+        assert!(!dir.path().join("file1.csv").exists());
+
+        let assignments = vec![("b".to_string(), lit("v"))];
+        let updated = table
+            .update(&state, &assignments, Some(&col("a").eq(lit(4))))
+            .await?;
+        assert_eq!(updated, 1);
+        // the rows not deleted are kept
+        assert_eq!(
+            fs::read_to_string(dir.path().join("file0.csv"))?,
+            "a,b\n1,x\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("file2.csv"))?,
+            "a,b\n4,v\n"
+        );
+        let files = fs::read_dir(dir.path())?.count();
+        assert_eq!(files, 2);
+
+        // the files pruned with zone maps are not rewritten
+        let table = table.with_zone_maps(Arc::new(ZoneMapIndex::new()));
+        let err = table.delete(&state, None).await.unwrap_err();
+        assert!(err.to_string().contains("pruned with zone maps"), "{}", err);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn typed_partition_columns() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        for (year, rows) in [
+            ("2021", "a\n1\n2\n"),
+            ("2022", "a\n3\n"),
+            ("2023", "a\n4\n"),
+        ] {
+            let partition = dir.path().join(format!("year={}", year));
+            fs::create_dir(&partition)?;
+            fs::write(partition.join("file.csv"), rows)?;
+        }
+        let table_path = dir.path().to_str().unwrap();
+        let new_table = move || async move {
+            let mut options = ListingOptions::new(Arc::new(CsvFormat::default()));
+            options.file_extension = ".csv".to_owned();
+            options.table_partition_cols = vec!["year".to_owned()];
+            let config =
+                ListingTableConfig::new(Arc::new(LocalFileSystem {}), table_path)
+                    .with_listing_options(options)
+                    .infer_schema()
+                    .await?;
+            ListingTable::try_new(config)
+        };
+        let err = new_table()
+            .await?
+            .with_partition_column_types(vec![])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Expected the types of 1 partitioning columns, got 0"
+        );
+        let table = new_table().await?;
+        let table = table.with_partition_column_types(vec![DataType::Int32])?;
+        assert_eq!(table.schema().field(1).data_type(), &DataType::Int32);
+
+        let ctx = crate::prelude::SessionContext::new();
+        ctx.register_table("t", Arc::new(table))?;
+        let years = RecordBatch::try_from_iter(vec![(
+            "y",
+            Arc::new(Int32Array::from(vec![2022, 2023, 2024])) as ArrayRef,
+        )])?;
+        ctx.register_table(
+            "years",
+            Arc::new(MemTable::try_new(years.schema(), vec![vec![years]])?),
+        )?;
+
+        // the partitioning column is compared, joined and grouped as an integer
+        let sql = "SELECT year, count(*), max(a) FROM t JOIN years ON t.year = years.y \
+                   WHERE year > 2021 GROUP BY year ORDER BY year";
+        let batches = ctx.sql(sql).await?.collect().await?;
+        let expected = vec![
+            "+------+-----------------+----------+",
+            "| year | COUNT(UInt8(1)) | MAX(t.a) |",
+            "+------+-----------------+----------+",
+            "| 2022 | 1               | 3        |",
+            "| 2023 | 1               | 4        |",
+            "+------+-----------------+----------+",
+        ];
+        crate::assert_batches_eq!(expected, &batches);
+
+        let batches = ctx
+            .sql("SELECT year + 1 AS next FROM t WHERE a = 1")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec!["+------+", "| next |", "+------+", "| 2022 |", "+------+"];
+        crate::assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[test]
+    fn split_files_keeping_their_order() {
+        let files = (0..4)
+            .map(|i| PartitionedFile::new(format!("file{}", i), 10))
+            .collect::<Vec<_>>();
+        let range = |min: i32, max: i32| {
+            (ScalarValue::Int32(Some(min)), ScalarValue::Int32(Some(max)))
+        };
+        let mut ranges = HashMap::from([
+            ("file0".to_owned(), range(20, 29)),
+            ("file1".to_owned(), range(0, 9)),
+            ("file2".to_owned(), range(30, 39)),
+            ("file3".to_owned(), range(10, 19)),
+        ]);
+        let paths = |groups: Vec<Vec<PartitionedFile>>| {
+            groups
+                .iter()
+                .map(|files| {
+                    files
+                        .iter()
+                        .map(|file| file.file_meta.path().to_owned())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // the files are read in the order of their values
+        let ascending = SortOptions {
+            descending: false,
+            nulls_first: false,
+        };
+        let groups = split_sorted_files(&files, &ranges, ascending, 2).unwrap();
+        assert_eq!(
+            paths(groups),
+            vec![vec!["file1", "file3"], vec!["file0", "file2"]]
+        );
+        let descending = SortOptions {
+            descending: true,
+            nulls_first: false,
+        };
+        let groups = split_sorted_files(&files, &ranges, descending, 2).unwrap();
+        assert_eq!(
+            paths(groups),
+            vec![vec!["file2", "file0"], vec!["file3", "file1"]]
+        );
+
+        // overlapping or unknown ranges can't be read one after the other
+        ranges.insert("file3".to_owned(), range(9, 19));
+        assert!(split_sorted_files(&files, &ranges, ascending, 2).is_none());
+        ranges.remove("file3");
+        assert!(split_sorted_files(&files, &ranges, ascending, 2).is_none());
+    }
+
+    #[test]
+    fn split_files_with_truncated_statistics() {
+        use crate::datasource::file_format::parquet::{
+            string_max_bound, string_min_bound,
+        };
+
+        let files = (0..2)
+            .map(|i| PartitionedFile::new(format!("file{}", i), 10))
+            .collect::<Vec<_>>();
+        let range = |min: &[u8], max: &[u8]| {
+            let (min, _) = string_min_bound(min, None);
+            let (max, _) = string_max_bound(max, None);
+            (ScalarValue::Utf8(Some(min)), ScalarValue::Utf8(max))
+        };
+        let ascending = SortOptions {
+            descending: false,
+            nulls_first: false,
+        };
+
+        // the max "aé" of file0 is truncated in the middle of "é", and
+        // overlaps the min "ab" of file1
+        let mut ranges = HashMap::from([
+            ("file0".to_owned(), range(b"a", b"a\xc3")),
+            ("file1".to_owned(), range(b"ab", b"c")),
+        ]);
+        assert!(split_sorted_files(&files, &ranges, ascending, 1).is_none());
+
+        // but not the min "ba"
+        ranges.insert("file1".to_owned(), range(b"ba", b"c"));
+        let groups = split_sorted_files(&files, &ranges, ascending, 1).unwrap();
+        assert_eq!(groups[0].len(), 2);
+    }
+
     async fn load_table(name: &str) -> Result<Arc<dyn TableProvider>> {
         let testdata = crate::test_util::parquet_test_data();
         let filename = format!("{}/{}", testdata, name);
@@ -574,7 +1383,7 @@ mod tests {
         let mock_store =
             TestObjectStore::new_arc(&files.iter().map(|f| (*f, 10)).collect::<Vec<_>>());
 
-        let format = AvroFormat {};
+        let format = AvroFormat::default();
 
         let opt = ListingOptions {
             file_extension: "".to_owned(),
@@ -582,6 +1391,7 @@ mod tests {
             table_partition_cols: vec![],
             target_partitions,
             collect_stat: true,
+            file_sort_order: vec![],
         };
 
         let schema = Schema::new(vec![Field::new("a", DataType::Boolean, false)]);
@@ -592,7 +1402,7 @@ mod tests {
 
         let table = ListingTable::try_new(config)?;
 
-        let (file_list, _) = table.list_files_for_scan(&[], None).await?;
+        let (file_list, _, _) = table.list_files_for_scan(&[], None).await?;
 
         assert_eq!(file_list.len(), output_partitioning);
 
@@ -26,20 +26,24 @@ use std::sync::Arc;
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
+use parking_lot::RwLock;
 
+use crate::datasource::mutation::RowMutation;
 use crate::datasource::TableProvider;
 use crate::error::{DataFusionError, Result};
-use crate::execution::context::TaskContext;
-use crate::logical_plan::Expr;
+use crate::execution::context::{SessionState, TaskContext};
+use crate::execution::executor;
+use crate::logical_plan::{Constraint, Constraints, Expr};
 use crate::physical_plan::common;
 use crate::physical_plan::memory::MemoryExec;
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::{repartition::RepartitionExec, Partitioning};
 
-/// In-memory table
+/// In-memory table. Its rows may be deleted or updated in place.
 pub struct MemTable {
     schema: SchemaRef,
-    batches: Vec<Vec<RecordBatch>>,
+    batches: RwLock<Vec<Vec<RecordBatch>>>,
+    constraints: Constraints,
 }
 
 impl MemTable {
@@ -52,7 +56,8 @@ impl MemTable {
         {
             Ok(Self {
                 schema,
-                batches: partitions,
+                batches: RwLock::new(partitions),
+                constraints: Constraints::empty(),
             })
         } else {
             Err(DataFusionError::Plan(
@@ -61,6 +66,20 @@ impl MemTable {
         }
     }
 
+    /// Declares the columns at `indices` as the primary key of the table.
+    /// The values of the key are not checked to be unique.
+    pub fn with_primary_key(self, indices: Vec<usize>) -> Result<Self> {
+        self.with_constraints(Constraints::new(vec![Constraint::PrimaryKey(indices)]))
+    }
+
+    /// Declares the `constraints` on the columns of the table. The data of
+    /// the table is not checked to satisfy them.
+    pub fn with_constraints(mut self, constraints: Constraints) -> Result<Self> {
+        constraints.validate(&self.schema)?;
+        self.constraints = constraints;
+        Ok(self)
+    }
+
     /// Create a mem table by reading from another data source
     pub async fn load(
         t: Arc<dyn TableProvider>,
@@ -71,11 +90,12 @@ impl MemTable {
         let exec = t.scan(&None, &[], None).await?;
         let partition_count = exec.output_partitioning().partition_count();
 
+        let runtime = context.runtime_env();
         let tasks = (0..partition_count)
             .map(|part_i| {
                 let context1 = context.clone();
                 let exec = exec.clone();
-                tokio::spawn(async move {
+                executor::spawn(runtime.executor.as_ref(), async move {
                     let stream = exec.execute(part_i, context1.clone()).await?;
                     common::collect(stream).await
                 })
@@ -87,7 +107,7 @@ impl MemTable {
         let mut data: Vec<Vec<RecordBatch>> =
             Vec::with_capacity(exec.output_partitioning().partition_count());
         for task in tasks {
-            let result = task.await.expect("MemTable::load could not join task")?;
+            let result = task.await??;
             data.push(result);
         }
 
@@ -115,6 +135,30 @@ impl MemTable {
         }
         MemTable::try_new(schema.clone(), data)
     }
+
+    /// Applies `mutation` to the batches of the table, replacing them
+    /// once all of them are mutated, and returns the number of rows it
+    /// deleted or updated
+    fn mutate(&self, mutation: &RowMutation) -> Result<u64> {
+        let mut batches = self.batches.write();
+        let mut num_mutated = 0;
+        let mutated = batches
+            .iter()
+            .map(|partition| {
+                partition
+                    .iter()
+                    .map(|batch| {
+                        let (batch, num_matched) = mutation.apply(batch)?;
+                        num_mutated += num_matched as u64;
+                        Ok(batch)
+                    })
+                    .filter(|batch| !matches!(batch, Ok(batch) if batch.num_rows() == 0))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        *batches = mutated;
+        Ok(num_mutated)
+    }
 }
 
 #[async_trait]
@@ -127,6 +171,10 @@ impl TableProvider for MemTable {
         self.schema.clone()
     }
 
+    fn constraints(&self) -> Option<&Constraints> {
+        Some(&self.constraints)
+    }
+
     async fn scan(
         &self,
         projection: &Option<Vec<usize>>,
@@ -134,17 +182,38 @@ impl TableProvider for MemTable {
         _limit: Option<usize>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
         Ok(Arc::new(MemoryExec::try_new(
-            &self.batches.clone(),
+            &self.batches.read().clone(),
             self.schema(),
             projection.clone(),
         )?))
     }
+
+    async fn delete(
+        &self,
+        state: &SessionState,
+        predicate: Option<&Expr>,
+    ) -> Result<u64> {
+        let mutation = RowMutation::try_new_delete(state, self.schema(), predicate)?;
+        self.mutate(&mutation)
+    }
+
+    async fn update(
+        &self,
+        state: &SessionState,
+        assignments: &[(String, Expr)],
+        predicate: Option<&Expr>,
+    ) -> Result<u64> {
+        let mutation =
+            RowMutation::try_new_update(state, self.schema(), assignments, predicate)?;
+        self.mutate(&mutation)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::from_slice::FromSlice;
+    use crate::logical_plan::{col, lit};
     use crate::prelude::SessionContext;
     use arrow::array::Int32Array;
     use arrow::datatypes::{DataType, Field, Schema};
@@ -372,4 +441,55 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn delete_and_update() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let state = session_ctx.state.read().clone();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, true),
+        ]));
+        let batch = |a: &[i32], b: Vec<Option<i32>>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from_slice(a)),
+                    Arc::new(Int32Array::from(b)),
+                ],
+            )
+        };
+        let provider = MemTable::try_new(
+            schema.clone(),
+            vec![
+                vec![batch(&[1, 2], vec![Some(10), None])?],
+                vec![batch(&[3], vec![Some(30)])?],
+            ],
+        )?;
+
+        let predicate = col("a").gt_eq(lit(2));
+        let assignments = vec![("b".to_string(), col("a") + lit(100))];
+        let updated = provider
+            .update(&state, &assignments, Some(&predicate))
+            .await?;
+        assert_eq!(updated, 2);
+        let deleted = provider.delete(&state, Some(&col("a").eq(lit(3)))).await?;
+        assert_eq!(deleted, 1);
+
+        let exec = provider.scan(&None, &[], None).await?;
+        let batches = crate::physical_plan::collect(exec, session_ctx.task_ctx()).await?;
+        let expected = vec![
+            "+---+-----+",
+            "| a | b   |",
+            "+---+-----+",
+            "| 1 | 10  |",
+            "| 2 | 102 |",
+            "+---+-----+",
+        ];
+        crate::assert_batches_eq!(expected, &batches);
+
+        assert_eq!(provider.delete(&state, None).await?, 2);
+        assert_eq!(provider.batches.read().iter().flatten().count(), 0);
+        Ok(())
+    }
 }
@@ -18,12 +18,23 @@
 //! DataFusion data sources
 
 #![allow(clippy::module_inception)]
+pub mod checkpoint;
 pub mod datasource;
 pub mod empty;
+pub mod ffi_compat;
+pub mod ffi_stream;
 pub mod file_format;
+#[cfg(feature = "flight")]
+pub mod flight;
+pub mod generate_series;
+pub mod instrumented_object_store;
+pub mod limited_object_store;
 pub mod listing;
 pub mod memory;
+pub(crate) mod mutation;
 pub mod object_store_registry;
+pub mod parquet_metadata;
+pub mod zone_map;
 
 use futures::Stream;
 
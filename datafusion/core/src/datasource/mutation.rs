@@ -0,0 +1,291 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The rows deleted or updated by `DELETE` and `UPDATE`, applied to the
+//! batches of the tables supporting them
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, BooleanArray};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::SessionState;
+use crate::logical_plan::{when, Column, Expr, ToDFSchema};
+use crate::physical_plan::planner::create_physical_expr;
+use crate::physical_plan::PhysicalExpr;
+
+/// How the rows of a batch are changed
+#[derive(Debug)]
+enum MutationKind {
+    /// The matched rows are removed
+    Delete,
+    /// Each column of the batch is replaced by its expression, selecting
+    /// the new value in the matched rows
+    Update(Vec<Arc<dyn PhysicalExpr>>),
+}
+
+/// The deletion or the update of the rows of a table matching a predicate
+#[derive(Debug)]
+pub(crate) struct RowMutation {
+    schema: SchemaRef,
+    predicate: Option<Arc<dyn PhysicalExpr>>,
+    kind: MutationKind,
+}
+
+impl RowMutation {
+    /// Deletes the rows of the batches of `schema` for which `predicate` is
+    /// true, or all of them
+    pub(crate) fn try_new_delete(
+        state: &SessionState,
+        schema: SchemaRef,
+        predicate: Option<&Expr>,
+    ) -> Result<Self> {
+        let predicate = physical_predicate(state, &schema, predicate)?;
+        Ok(Self {
+            schema,
+            predicate,
+            kind: MutationKind::Delete,
+        })
+    }
+
+    /// Sets the columns of `assignments` to their values in the rows of the
+    /// batches of `schema` for which `predicate` is true, or all of them
+    pub(crate) fn try_new_update(
+        state: &SessionState,
+        schema: SchemaRef,
+        assignments: &[(String, Expr)],
+        predicate: Option<&Expr>,
+    ) -> Result<Self> {
+        if let Some((name, _)) = assignments
+            .iter()
+            .find(|(name, _)| schema.field_with_name(name).is_err())
+        {
+            return Err(DataFusionError::Plan(format!(
+                "The table has no column named '{}'",
+                name
+            )));
+        }
+        let df_schema = schema.clone().to_dfschema()?;
+        let columns = schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let column = Expr::Column(Column::from_name(field.name()));
+                let expr = match assignments.iter().find(|(name, _)| name == field.name())
+                {
+                    Some((_, value)) => {
+                        let value = Expr::Cast {
+                            expr: Box::new(value.clone()),
+                            data_type: field.data_type().clone(),
+                        };
+                        match predicate {
+                            Some(predicate) => {
+                                when(predicate.clone(), value).otherwise(column)?
+                            }
+                            None => value,
+                        }
+                    }
+                    None => column,
+                };
+                create_physical_expr(&expr, &df_schema, &schema, &state.execution_props)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let predicate = physical_predicate(state, &schema, predicate)?;
+        Ok(Self {
+            schema,
+            predicate,
+            kind: MutationKind::Update(columns),
+        })
+    }
+
+    /// Returns `batch` with the mutation applied, and the number of rows it
+    /// deleted or updated
+    pub(crate) fn apply(&self, batch: &RecordBatch) -> Result<(RecordBatch, usize)> {
+        let matched = match &self.predicate {
+            Some(predicate) => {
+                let mask = predicate.evaluate(batch)?.into_array(batch.num_rows());
+                let mask = mask
+                    .as_any()
+                    .downcast_ref::<BooleanArray>()
+                    .ok_or_else(|| {
+                        DataFusionError::Internal(
+                            "The predicate of the mutation isn't boolean".to_string(),
+                        )
+                    })?
+                    .iter()
+                    .map(|matched| matched.unwrap_or(false))
+                    .collect::<Vec<_>>();
+                Some(mask)
+            }
+            None => None,
+        };
+        let num_matched = match &matched {
+            Some(mask) => mask.iter().filter(|matched| **matched).count(),
+            None => batch.num_rows(),
+        };
+        if num_matched == 0 {
+            return Ok((batch.clone(), 0));
+        }
+
+        let batch = match &self.kind {
+            MutationKind::Delete => match matched {
+                Some(mask) => {
+                    let kept = mask.into_iter().map(|matched| Some(!matched));
+                    filter_record_batch(batch, &kept.collect::<BooleanArray>())?
+                }
+                None => RecordBatch::new_empty(self.schema.clone()),
+            },
+            MutationKind::Update(columns) => {
+                let arrays = columns
+                    .iter()
+                    .zip(self.schema.fields())
+                    .map(|(expr, field)| {
+                        let array = expr.evaluate(batch)?.into_array(batch.num_rows());
+                        if !field.is_nullable() && array.null_count() > 0 {
+                            return Err(DataFusionError::Execution(format!(
+                                "Column '{}' can't be set to NULL",
+                                field.name()
+                            )));
+                        }
+                        Ok(array)
+                    })
+                    .collect::<Result<Vec<ArrayRef>>>()?;
+                RecordBatch::try_new(self.schema.clone(), arrays)?
+            }
+        };
+        Ok((batch, num_matched))
+    }
+}
+
+/// Returns the physical expression of `predicate` over the batches of
+/// `schema`, checking that it is boolean
+fn physical_predicate(
+    state: &SessionState,
+    schema: &SchemaRef,
+    predicate: Option<&Expr>,
+) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+    predicate
+        .map(|predicate| {
+            let expr = create_physical_expr(
+                predicate,
+                &schema.clone().to_dfschema()?,
+                schema,
+                &state.execution_props,
+            )?;
+            if expr.data_type(schema)? != DataType::Boolean {
+                return Err(DataFusionError::Plan(format!(
+                    "The predicate {:?} isn't boolean",
+                    predicate
+                )));
+            }
+            Ok(expr)
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::from_slice::FromSlice;
+    use crate::logical_plan::{col, lit};
+    use crate::prelude::SessionContext;
+    use crate::scalar::ScalarValue;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+
+    fn batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int32Array::from_slice(&[1, 2, 3])),
+                Arc::new(StringArray::from(vec![Some("x"), None, Some("z")])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn delete_rows() -> Result<()> {
+        let state = SessionContext::new().state.read().clone();
+        let batch = batch();
+        // the NULL comparison doesn't match the second row
+        let predicate = col("b").not_eq(lit("z"));
+        let mutation =
+            RowMutation::try_new_delete(&state, batch.schema(), Some(&predicate))?;
+        let (deleted, num_matched) = mutation.apply(&batch)?;
+        assert_eq!(num_matched, 1);
+        let expected = vec![
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 2 |   |",
+            "| 3 | z |",
+            "+---+---+",
+        ];
+        assert_batches_eq!(expected, &[deleted]);
+
+        let mutation = RowMutation::try_new_delete(&state, batch.schema(), None)?;
+        let (deleted, num_matched) = mutation.apply(&batch)?;
+        assert_eq!((deleted.num_rows(), num_matched), (0, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn update_rows() -> Result<()> {
+        let state = SessionContext::new().state.read().clone();
+        let batch = batch();
+        let assignments = vec![
+            ("a".to_string(), col("a") * lit(10i64)),
+            ("b".to_string(), lit("y")),
+        ];
+        let predicate = col("a").gt_eq(lit(2));
+        let mutation = RowMutation::try_new_update(
+            &state,
+            batch.schema(),
+            &assignments,
+            Some(&predicate),
+        )?;
+        let (updated, num_matched) = mutation.apply(&batch)?;
+        assert_eq!(num_matched, 2);
+        let expected = vec![
+            "+----+---+",
+            "| a  | b |",
+            "+----+---+",
+            "| 1  | x |",
+            "| 20 | y |",
+            "| 30 | y |",
+            "+----+---+",
+        ];
+        assert_batches_eq!(expected, &[updated]);
+
+        let assignments = vec![("a".to_string(), lit(ScalarValue::Null))];
+        let err =
+            RowMutation::try_new_update(&state, batch.schema(), &assignments, None)?
+                .apply(&batch)
+                .unwrap_err();
+        assert!(err.to_string().contains("Column 'a' can't be set to NULL"));
+        Ok(())
+    }
+}
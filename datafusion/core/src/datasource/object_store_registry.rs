@@ -20,8 +20,9 @@
 //! and query data inside these systems.
 
 use datafusion_common::{DataFusionError, Result};
-use datafusion_data_access::object_store::local::{LocalFileSystem, LOCAL_SCHEME};
-use datafusion_data_access::object_store::ObjectStore;
+#[cfg(not(target_arch = "wasm32"))]
+use datafusion_data_access::object_store::local::LocalFileSystem;
+use datafusion_data_access::object_store::{ObjectStore, LOCAL_SCHEME};
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::fmt;
@@ -52,10 +53,13 @@ impl Default for ObjectStoreRegistry {
 
 impl ObjectStoreRegistry {
     /// Create the registry that object stores can registered into.
-    /// ['LocalFileSystem'] store is registered in by default to support read local files natively.
+    /// ['LocalFileSystem'] store is registered in by default to support read local files natively,
+    /// except on wasm32 where there is no local file system.
     pub fn new() -> Self {
         let mut map: HashMap<String, Arc<dyn ObjectStore>> = HashMap::new();
-        map.insert(LOCAL_SCHEME.to_string(), Arc::new(LocalFileSystem));
+        if let Ok(local) = local_file_system() {
+            map.insert(LOCAL_SCHEME.to_string(), local);
+        }
 
         Self {
             object_stores: RwLock::new(map),
@@ -100,7 +104,26 @@ impl ObjectStoreRegistry {
                 })?;
             Ok((store, uri))
         } else {
-            Ok((Arc::new(LocalFileSystem), uri))
+            let store = match self.get(LOCAL_SCHEME) {
+                Some(store) => store,
+                None => local_file_system()?,
+            };
+            Ok((store, uri))
         }
     }
 }
+
+/// The store of the local files used when none is registered
+#[cfg(not(target_arch = "wasm32"))]
+fn local_file_system() -> Result<Arc<dyn ObjectStore>> {
+    Ok(Arc::new(LocalFileSystem))
+}
+
+/// The store of the local files used when none is registered
+#[cfg(target_arch = "wasm32")]
+fn local_file_system() -> Result<Arc<dyn ObjectStore>> {
+    Err(DataFusionError::NotImplemented(format!(
+        "There is no local file system on wasm32, register an object store for the '{}' scheme",
+        LOCAL_SCHEME
+    )))
+}
@@ -0,0 +1,413 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The `parquet_metadata(path)` table function, producing a row for each
+//! column chunk of the Parquet files at `path`, with its encodings, its
+//! compressed and uncompressed sizes and its statistics, e.g. to diagnose
+//! why the row groups of a query are not pruned.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use parquet::file::reader::FileReader;
+use parquet::file::statistics::Statistics as ParquetStatistics;
+
+use crate::datasource::file_format::parquet::{
+    open_file_reader, DEFAULT_PARQUET_EXTENSION,
+};
+use crate::datasource::TableProvider;
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+use crate::logical_plan::Expr;
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::memory::MemoryStream;
+use crate::physical_plan::{
+    project_schema, DisplayFormatType, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+
+/// Name of the table function
+pub const PARQUET_METADATA: &str = "parquet_metadata";
+
+/// A table of the metadata of the column chunks of Parquet files, one row
+/// per column chunk of each row group
+#[derive(Debug, Clone)]
+pub struct ParquetMetadataTable {
+    schema: SchemaRef,
+    path: String,
+}
+
+impl ParquetMetadataTable {
+    /// Create the table of the metadata of the Parquet file `path`, or of
+    /// the Parquet files of the directory `path`
+    pub fn new(path: impl Into<String>) -> Self {
+        let nullable_int = |name| Field::new(name, DataType::Int64, true);
+        let nullable_str = |name| Field::new(name, DataType::Utf8, true);
+        Self {
+            schema: Arc::new(Schema::new(vec![
+                Field::new("file_name", DataType::Utf8, false),
+                Field::new("row_group", DataType::Int64, false),
+                Field::new("row_group_num_rows", DataType::Int64, false),
+                Field::new("row_group_bytes", DataType::Int64, false),
+                Field::new("column_id", DataType::Int64, false),
+                Field::new("path_in_schema", DataType::Utf8, false),
+                Field::new("type", DataType::Utf8, false),
+                Field::new("encodings", DataType::Utf8, false),
+                Field::new("compression", DataType::Utf8, false),
+                Field::new("num_values", DataType::Int64, false),
+                Field::new("compressed_size", DataType::Int64, false),
+                Field::new("uncompressed_size", DataType::Int64, false),
+                nullable_int("null_count"),
+                nullable_int("distinct_count"),
+                nullable_str("min"),
+                nullable_str("max"),
+            ])),
+            path: path.into(),
+        }
+    }
+
+    /// The path of the Parquet files
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl TableProvider for ParquetMetadataTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(ParquetMetadataExec {
+            table: self.clone(),
+            schema: project_schema(&self.schema, projection.as_ref())?,
+            projection: projection.clone(),
+        }))
+    }
+}
+
+/// Execution plan reading the metadata of the files of a
+/// [`ParquetMetadataTable`]
+#[derive(Debug)]
+struct ParquetMetadataExec {
+    table: ParquetMetadataTable,
+    /// The projected schema
+    schema: SchemaRef,
+    projection: Option<Vec<usize>>,
+}
+
+#[async_trait]
+impl ExecutionPlan for ParquetMetadataExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "ParquetMetadataExec invalid partition {} (expected 0)",
+                partition
+            )));
+        }
+        let (object_store, path) =
+            context.runtime_env().object_store(&self.table.path)?;
+        let object_store = context.limit_object_store(object_store);
+        let mut files: Vec<_> = object_store.list_file(path).await?.try_collect().await?;
+        // `path` is a prefix when it lists other files than itself: the files
+        // of a directory are its Parquet files, a file is read whatever its
+        // extension
+        let is_directory = files.iter().any(|file| file.path() != path);
+        if is_directory {
+            files.retain(|file| file.path().ends_with(DEFAULT_PARQUET_EXTENSION));
+        }
+        files.sort_by(|a, b| a.path().cmp(b.path()));
+
+        let mut rows = ColumnChunkRows::default();
+        for file in files {
+            let path = file.path().to_owned();
+            let object_reader = object_store.file_reader(file.sized_file)?;
+            let file_reader = open_file_reader(object_reader, None)?;
+            rows.push_file(&path, &file_reader);
+        }
+        Ok(Box::pin(MemoryStream::try_new(
+            vec![rows.into_batch(self.table.schema.clone())?],
+            self.table.schema.clone(),
+            self.projection.clone(),
+        )?))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(f, "ParquetMetadataExec: path={}", self.table.path)
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// The columns of the rows of the column chunks
+#[derive(Default)]
+struct ColumnChunkRows {
+    file_name: Vec<String>,
+    row_group: Vec<i64>,
+    row_group_num_rows: Vec<i64>,
+    row_group_bytes: Vec<i64>,
+    column_id: Vec<i64>,
+    path_in_schema: Vec<String>,
+    column_type: Vec<String>,
+    encodings: Vec<String>,
+    compression: Vec<String>,
+    num_values: Vec<i64>,
+    compressed_size: Vec<i64>,
+    uncompressed_size: Vec<i64>,
+    null_count: Vec<Option<i64>>,
+    distinct_count: Vec<Option<i64>>,
+    min: Vec<Option<String>>,
+    max: Vec<Option<String>>,
+}
+
+impl ColumnChunkRows {
+    /// Adds the rows of the column chunks of the file `path`
+    fn push_file(&mut self, path: &str, reader: &dyn FileReader) {
+        for (i, row_group) in reader.metadata().row_groups().iter().enumerate() {
+            for (column_id, column) in row_group.columns().iter().enumerate() {
+                self.file_name.push(path.to_owned());
+                self.row_group.push(i as i64);
+                self.row_group_num_rows.push(row_group.num_rows());
+                self.row_group_bytes.push(row_group.total_byte_size());
+                self.column_id.push(column_id as i64);
+                self.path_in_schema.push(column.column_path().string());
+                self.column_type.push(column.column_type().to_string());
+                self.encodings.push(
+                    column
+                        .encodings()
+                        .iter()
+                        .map(|encoding| encoding.to_string())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+                self.compression.push(column.compression().to_string());
+                self.num_values.push(column.num_values());
+                self.compressed_size.push(column.compressed_size());
+                self.uncompressed_size.push(column.uncompressed_size());
+
+                let statistics = column.statistics();
+                self.null_count
+                    .push(statistics.map(|stats| stats.null_count() as i64));
+                self.distinct_count.push(
+                    statistics
+                        .and_then(|stats| stats.distinct_count())
+                        .map(|count| count as i64),
+                );
+                let (min, max) = statistics.map(min_max_strings).unwrap_or_default();
+                self.min.push(min);
+                self.max.push(max);
+            }
+        }
+    }
+
+    fn into_batch(self, schema: SchemaRef) -> Result<RecordBatch> {
+        let strings =
+            |values: Vec<String>| -> ArrayRef { Arc::new(StringArray::from(values)) };
+        let ints = |values: Vec<i64>| -> ArrayRef { Arc::new(Int64Array::from(values)) };
+        let columns = vec![
+            strings(self.file_name),
+            ints(self.row_group),
+            ints(self.row_group_num_rows),
+            ints(self.row_group_bytes),
+            ints(self.column_id),
+            strings(self.path_in_schema),
+            strings(self.column_type),
+            strings(self.encodings),
+            strings(self.compression),
+            ints(self.num_values),
+            ints(self.compressed_size),
+            ints(self.uncompressed_size),
+            Arc::new(Int64Array::from(self.null_count)),
+            Arc::new(Int64Array::from(self.distinct_count)),
+            Arc::new(self.min.into_iter().collect::<StringArray>()),
+            Arc::new(self.max.into_iter().collect::<StringArray>()),
+        ];
+        Ok(RecordBatch::try_new(schema, columns)?)
+    }
+}
+
+/// Returns the min and the max of `statistics` as strings, if set. The
+/// binary values are displayed as UTF-8, replacing their invalid sequences.
+fn min_max_strings(statistics: &ParquetStatistics) -> (Option<String>, Option<String>) {
+    if !statistics.has_min_max_set() {
+        return (None, None);
+    }
+    let (min, max) = match statistics {
+        ParquetStatistics::Boolean(s) => (s.min().to_string(), s.max().to_string()),
+        ParquetStatistics::Int32(s) => (s.min().to_string(), s.max().to_string()),
+        ParquetStatistics::Int64(s) => (s.min().to_string(), s.max().to_string()),
+        ParquetStatistics::Int96(s) => (
+            format!("{:?}", s.min().data()),
+            format!("{:?}", s.max().data()),
+        ),
+        ParquetStatistics::Float(s) => (s.min().to_string(), s.max().to_string()),
+        ParquetStatistics::Double(s) => (s.min().to_string(), s.max().to_string()),
+        ParquetStatistics::ByteArray(_) | ParquetStatistics::FixedLenByteArray(_) => (
+            String::from_utf8_lossy(statistics.min_bytes()).into_owned(),
+            String::from_utf8_lossy(statistics.max_bytes()).into_owned(),
+        ),
+    };
+    (Some(min), Some(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::prelude::SessionContext;
+    use arrow::array::Int32Array;
+    use parquet::arrow::ArrowWriter;
+    use parquet::file::properties::WriterProperties;
+    use std::fs::File;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn parquet_metadata() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![Some(3), None, Some(1), Some(2)])),
+                Arc::new(StringArray::from(vec!["x", "y", "w", "z"])),
+            ],
+        )?;
+        let path = tmp_dir.path().join("t.parquet");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer = ArrowWriter::try_new(File::create(&path)?, schema, Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let ctx = SessionContext::new();
+        let sql = format!(
+            "SELECT row_group, row_group_num_rows, path_in_schema, type, null_count, min, max \
+             FROM parquet_metadata('{}') ORDER BY row_group, path_in_schema",
+            tmp_dir.path().to_str().unwrap()
+        );
+        let results = ctx.sql(&sql).await?.collect().await?;
+        let expected = vec![
+            "+-----------+--------------------+----------------+------------+------------+-----+-----+",
+            "| row_group | row_group_num_rows | path_in_schema | type       | null_count | min | max |",
+            "+-----------+--------------------+----------------+------------+------------+-----+-----+",
+            "| 0         | 2                  | a              | INT32      | 1          | 3   | 3   |",
+            "| 0         | 2                  | b              | BYTE_ARRAY | 0          | x   | y   |",
+            "| 1         | 2                  | a              | INT32      | 0          | 1   | 2   |",
+            "| 1         | 2                  | b              | BYTE_ARRAY | 0          | w   | z   |",
+            "+-----------+--------------------+----------------+------------+------------+-----+-----+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let sql = format!(
+            "SELECT count(*) AS chunks, sum(compressed_size) > 0 AS sized \
+             FROM parquet_metadata('{}')",
+            path.to_str().unwrap()
+        );
+        let results = ctx.sql(&sql).await?.collect().await?;
+        let expected = vec![
+            "+--------+-------+",
+            "| chunks | sized |",
+            "+--------+-------+",
+            "| 4      | true  |",
+            "+--------+-------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the other files of a directory are skipped, even if it holds a
+        // single Parquet file, while a file is read whatever its extension
+        std::fs::write(tmp_dir.path().join("notes.txt"), "not parquet")?;
+        let other_dir = TempDir::new()?;
+        let other_path = other_dir.path().join("t.data");
+        std::fs::copy(&path, &other_path)?;
+        for path in [tmp_dir.path(), other_path.as_path()] {
+            let sql = format!(
+                "SELECT count(*) AS chunks FROM parquet_metadata('{}')",
+                path.to_str().unwrap()
+            );
+            let results = ctx.sql(&sql).await?.collect().await?;
+            let expected = vec![
+                "+--------+",
+                "| chunks |",
+                "+--------+",
+                "| 4      |",
+                "+--------+",
+            ];
+            assert_batches_eq!(expected, &results);
+        }
+        Ok(())
+    }
+}
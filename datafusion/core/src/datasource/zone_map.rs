@@ -0,0 +1,485 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Zone maps, the minimum and maximum values of the columns of each file of
+//! a table, stored in an index outside of the files.
+//!
+//! A [`ListingTable`] scanned with a [`ZoneMapIndex`] (see
+//! [`ListingTable::with_zone_maps`]) skips the files whose zone maps show
+//! that none of their rows match the filters of the scan, without opening
+//! them. This is useful for the formats without embedded statistics, such as
+//! CSV and JSON, and for tables of many files.
+//!
+//! The index can be read from a JSON file mapping the path of each file,
+//! relative to the path of the table, to its zone map:
+//!
+//! ```json
+//! {
+//!   "part-0.csv": {
+//!     "num_rows": 100,
+//!     "columns": {
+//!       "a": {"min": 1, "max": 10, "null_count": 0},
+//!       "b": {"min": "apple", "max": "pear"}
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! The files and columns missing from the index are never skipped.
+//!
+//! [`ListingTable`]: crate::datasource::listing::ListingTable
+//! [`ListingTable::with_zone_maps`]: crate::datasource::listing::ListingTable::with_zone_maps
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, StringArray, UInt64Array};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Schema, SchemaRef};
+use log::debug;
+use serde_json::Value;
+
+use crate::datasource::listing::helpers::expr_applicable_for_cols;
+use crate::datasource::listing::PartitionedFile;
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::{combine_filters, Column, Expr};
+use crate::physical_optimizer::pruning::{PruningPredicate, PruningStatistics};
+use crate::scalar::ScalarValue;
+
+/// The range of the values of a column in a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnZone {
+    /// The minimum value of the column, null if unknown
+    pub min: ScalarValue,
+    /// The maximum value of the column, null if unknown
+    pub max: ScalarValue,
+    /// The number of nulls of the column, if known
+    pub null_count: Option<usize>,
+}
+
+impl ColumnZone {
+    /// Create the zone of the values `min..=max`, with an unknown number of
+    /// nulls
+    pub fn new(min: ScalarValue, max: ScalarValue) -> Self {
+        Self {
+            min,
+            max,
+            null_count: None,
+        }
+    }
+
+    /// Set the number of nulls of the column
+    pub fn with_null_count(mut self, null_count: usize) -> Self {
+        self.null_count = Some(null_count);
+        self
+    }
+}
+
+/// The zone map of a file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileZoneMap {
+    /// The number of rows of the file, if known
+    pub num_rows: Option<usize>,
+    /// The zones of the columns of the file, by name
+    pub columns: HashMap<String, ColumnZone>,
+}
+
+impl FileZoneMap {
+    /// Create a zone map of no columns
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of rows of the file
+    pub fn with_num_rows(mut self, num_rows: usize) -> Self {
+        self.num_rows = Some(num_rows);
+        self
+    }
+
+    /// Set the zone of the column `name`
+    pub fn with_column(mut self, name: impl Into<String>, zone: ColumnZone) -> Self {
+        self.columns.insert(name.into(), zone);
+        self
+    }
+}
+
+/// The zone maps of the files of a table, by path
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ZoneMapIndex {
+    files: HashMap<String, FileZoneMap>,
+}
+
+impl ZoneMapIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the zone map of the file at `path`, either relative to the path
+    /// of the table or the full path listed by the table
+    pub fn with_file(mut self, path: impl Into<String>, zone_map: FileZoneMap) -> Self {
+        self.files.insert(path.into(), zone_map);
+        self
+    }
+
+    /// The zone map of the file at `path`, if any
+    pub fn file(&self, path: &str) -> Option<&FileZoneMap> {
+        self.files.get(path)
+    }
+
+    /// Number of the files of the index
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns true if the index has no files
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Reads the index from `json`, in the format described in the
+    /// [module documentation](self). The values are converted to the types
+    /// of the columns of `schema`.
+    pub fn try_from_json(json: &str, schema: &Schema) -> Result<Self> {
+        let value: Value = serde_json::from_str(json).map_err(|e| {
+            DataFusionError::Plan(format!("Invalid zone map index: {}", e))
+        })?;
+        let files = as_object(&value, "the zone map index")?;
+        let mut index = Self::new();
+        for (path, file) in files {
+            let file = as_object(file, &format!("the zone map of {}", path))?;
+            let mut zone_map = FileZoneMap::new();
+            if let Some(num_rows) = file.get("num_rows") {
+                zone_map.num_rows = Some(as_usize(num_rows, "num_rows", path)?);
+            }
+            let columns = match file.get("columns") {
+                Some(columns) => as_object(
+                    columns,
+                    &format!("the columns of the zone map of {}", path),
+                )?,
+                None => {
+                    index = index.with_file(path.clone(), zone_map);
+                    continue;
+                }
+            };
+            for (name, zone) in columns {
+                let data_type = schema
+                    .field_with_name(name)
+                    .map_err(|_| {
+                        DataFusionError::Plan(format!(
+                            "The zone map of {} has the column {}, which is not a column of the table",
+                            path, name
+                        ))
+                    })?
+                    .data_type();
+                let zone = as_object(zone, &format!("the zone of {} in {}", name, path))?;
+                let mut column_zone = ColumnZone::new(
+                    to_scalar(zone.get("min"), data_type)?,
+                    to_scalar(zone.get("max"), data_type)?,
+                );
+                if let Some(null_count) = zone.get("null_count") {
+                    column_zone.null_count =
+                        Some(as_usize(null_count, "null_count", path)?);
+                }
+                zone_map.columns.insert(name.clone(), column_zone);
+            }
+            index = index.with_file(path.clone(), zone_map);
+        }
+        Ok(index)
+    }
+
+    /// Reads the index from the JSON file at `path`
+    pub fn try_from_file(path: impl AsRef<Path>, schema: &Schema) -> Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        Self::try_from_json(&json, schema)
+    }
+
+    /// Returns the `files` of the table at `table_path` that may have rows
+    /// matching `filters`, i.e. all but the files whose zone maps show that
+    /// none of their rows match. The filters over other columns than the
+    /// ones of `file_schema` are ignored.
+    pub fn prune(
+        &self,
+        table_path: &str,
+        files: Vec<PartitionedFile>,
+        filters: &[Expr],
+        file_schema: &SchemaRef,
+    ) -> Vec<PartitionedFile> {
+        let columns = file_schema
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        let filters = filters
+            .iter()
+            .filter(|expr| expr_applicable_for_cols(&columns, expr))
+            .cloned()
+            .collect::<Vec<_>>();
+        let predicate = match combine_filters(&filters) {
+            Some(predicate) => predicate,
+            None => return files,
+        };
+        let pruning_predicate =
+            match PruningPredicate::try_new(predicate, file_schema.clone()) {
+                Ok(pruning_predicate) => pruning_predicate,
+                Err(e) => {
+                    debug!("Could not prune the files with their zone maps: {}", e);
+                    return files;
+                }
+            };
+
+        let zone_maps = files
+            .iter()
+            .map(|file| self.lookup(table_path, file.file_meta.path()))
+            .collect::<Vec<_>>();
+        let statistics = ZoneMapStatistics {
+            zone_maps,
+            schema: file_schema,
+        };
+        match pruning_predicate.prune(&statistics) {
+            Ok(keep) => {
+                let pruned = keep.iter().filter(|keep| !**keep).count();
+                debug!("Skipped {} files with their zone maps", pruned);
+                files
+                    .into_iter()
+                    .zip(keep)
+                    .filter_map(|(file, keep)| if keep { Some(file) } else { None })
+                    .collect()
+            }
+            Err(e) => {
+                debug!("Could not prune the files with their zone maps: {}", e);
+                files
+            }
+        }
+    }
+
+    /// The zone map of the file at `path` listed by the table at
+    /// `table_path`, by full or relative path
+    fn lookup(&self, table_path: &str, path: &str) -> Option<&FileZoneMap> {
+        self.files.get(path).or_else(|| {
+            let relative = path.strip_prefix(table_path)?;
+            self.files.get(relative.trim_start_matches('/'))
+        })
+    }
+}
+
+/// The statistics of the files to prune, one container per file
+struct ZoneMapStatistics<'a> {
+    zone_maps: Vec<Option<&'a FileZoneMap>>,
+    schema: &'a SchemaRef,
+}
+
+impl<'a> ZoneMapStatistics<'a> {
+    fn values(
+        &self,
+        column: &Column,
+        value: impl Fn(&ColumnZone) -> &ScalarValue,
+    ) -> Option<ArrayRef> {
+        let data_type = self.schema.field_with_name(&column.name).ok()?.data_type();
+        let null = ScalarValue::try_from(data_type).ok()?;
+        let values = self.zone_maps.iter().map(|zone_map| {
+            zone_map
+                .and_then(|zone_map| zone_map.columns.get(&column.name))
+                .map(|zone| value(zone).clone())
+                .unwrap_or_else(|| null.clone())
+        });
+        ScalarValue::iter_to_array(values).ok()
+    }
+}
+
+impl<'a> PruningStatistics for ZoneMapStatistics<'a> {
+    fn min_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.values(column, |zone| &zone.min)
+    }
+
+    fn max_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.values(column, |zone| &zone.max)
+    }
+
+    fn num_containers(&self) -> usize {
+        self.zone_maps.len()
+    }
+
+    fn null_counts(&self, column: &Column) -> Option<ArrayRef> {
+        let null_counts = self
+            .zone_maps
+            .iter()
+            .map(|zone_map| {
+                zone_map
+                    .and_then(|zone_map| zone_map.columns.get(&column.name))
+                    .and_then(|zone| zone.null_count)
+                    .map(|null_count| null_count as u64)
+            })
+            .collect::<UInt64Array>();
+        Some(Arc::new(null_counts))
+    }
+}
+
+fn as_object<'a>(
+    value: &'a Value,
+    what: &str,
+) -> Result<&'a serde_json::Map<String, Value>> {
+    value.as_object().ok_or_else(|| {
+        DataFusionError::Plan(format!(
+            "Invalid zone map index: {} is not an object",
+            what
+        ))
+    })
+}
+
+fn as_usize(value: &Value, what: &str, path: &str) -> Result<usize> {
+    value.as_u64().map(|v| v as usize).ok_or_else(|| {
+        DataFusionError::Plan(format!(
+            "Invalid zone map index: the {} of {} is not a positive integer",
+            what, path
+        ))
+    })
+}
+
+/// Converts a JSON value of the index to a value of `data_type`, a missing
+/// value being null
+fn to_scalar(value: Option<&Value>, data_type: &DataType) -> Result<ScalarValue> {
+    let string = match value {
+        None | Some(Value::Null) => return ScalarValue::try_from(data_type),
+        Some(Value::String(string)) => string.clone(),
+        Some(Value::Number(number)) => number.to_string(),
+        Some(Value::Bool(boolean)) => boolean.to_string(),
+        Some(value) => {
+            return Err(DataFusionError::Plan(format!(
+                "Invalid zone map index: {} is not a value of a column",
+                value
+            )))
+        }
+    };
+    let array = cast(&StringArray::from(vec![string.as_str()]), data_type)?;
+    let scalar = ScalarValue::try_from_array(&array, 0)?;
+    if scalar.is_null() {
+        return Err(DataFusionError::Plan(format!(
+            "Invalid zone map index: {} is not a value of type {}",
+            string, data_type
+        )));
+    }
+    Ok(scalar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{col, lit};
+    use arrow::datatypes::Field;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+        ]))
+    }
+
+    fn files() -> Vec<PartitionedFile> {
+        ["table/part-0.csv", "table/part-1.csv", "table/part-2.csv"]
+            .iter()
+            .map(|path| PartitionedFile::new(path.to_string(), 10))
+            .collect()
+    }
+
+    fn paths(files: &[PartitionedFile]) -> Vec<&str> {
+        files.iter().map(|f| f.file_meta.path()).collect()
+    }
+
+    #[test]
+    fn zone_map_index_from_json() -> Result<()> {
+        let json = r#"{
+            "part-0.csv": {
+                "num_rows": 100,
+                "columns": {
+                    "a": {"min": 1, "max": 10, "null_count": 0},
+                    "b": {"min": "apple", "max": "pear"}
+                }
+            },
+            "part-1.csv": {"columns": {"a": {"min": "11", "max": null}}}
+        }"#;
+        let index = ZoneMapIndex::try_from_json(json, &schema())?;
+        assert_eq!(index.len(), 2);
+        let expected = FileZoneMap::new()
+            .with_num_rows(100)
+            .with_column(
+                "a",
+                ColumnZone::new(
+                    ScalarValue::Int64(Some(1)),
+                    ScalarValue::Int64(Some(10)),
+                )
+                .with_null_count(0),
+            )
+            .with_column(
+                "b",
+                ColumnZone::new(
+                    ScalarValue::Utf8(Some("apple".to_string())),
+                    ScalarValue::Utf8(Some("pear".to_string())),
+                ),
+            );
+        assert_eq!(index.file("part-0.csv"), Some(&expected));
+        let zone = &index.file("part-1.csv").unwrap().columns["a"];
+        assert_eq!(zone.min, ScalarValue::Int64(Some(11)));
+        assert_eq!(zone.max, ScalarValue::Int64(None));
+
+        let err = ZoneMapIndex::try_from_json(
+            r#"{"part-0.csv": {"columns": {"c": {"min": 1}}}}"#,
+            &schema(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("not a column of the table"));
+        let err = ZoneMapIndex::try_from_json(
+            r#"{"part-0.csv": {"columns": {"a": {"min": "x"}}}}"#,
+            &schema(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("x is not a value of type Int64"));
+        Ok(())
+    }
+
+    #[test]
+    fn prune_files() {
+        let zone = |min, max| {
+            ColumnZone::new(ScalarValue::Int64(Some(min)), ScalarValue::Int64(Some(max)))
+        };
+        // part-2.csv is missing from the index
+        let index = ZoneMapIndex::new()
+            .with_file(
+                "part-0.csv",
+                FileZoneMap::new().with_column("a", zone(1, 10)),
+            )
+            .with_file(
+                "table/part-1.csv",
+                FileZoneMap::new().with_column("a", zone(11, 20)),
+            );
+        let schema = schema();
+
+        let pruned = index.prune("table", files(), &[col("a").gt(lit(10i64))], &schema);
+        assert_eq!(paths(&pruned), vec!["table/part-1.csv", "table/part-2.csv"]);
+
+        let pruned = index.prune("table/", files(), &[col("a").lt(lit(5i64))], &schema);
+        assert_eq!(paths(&pruned), vec!["table/part-0.csv", "table/part-2.csv"]);
+
+        // the column b has no zones and the column c is not a file column
+        let filters = [col("b").eq(lit("x")), col("c").eq(lit("y"))];
+        let pruned = index.prune("table", files(), &filters, &schema);
+        assert_eq!(pruned.len(), 3);
+        let pruned = index.prune("table", files(), &[], &schema);
+        assert_eq!(pruned.len(), 3);
+    }
+}
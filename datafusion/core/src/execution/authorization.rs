@@ -0,0 +1,232 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Authorization of the access to the tables and columns of a session.
+//!
+//! Multi-tenant services can enforce their access control lists while the
+//! queries are planned, rather than by inspecting the plans afterwards, with
+//! an [`Authorizer`], see [`SessionState::with_authorizer`]. By default,
+//! every table and column can be accessed.
+//!
+//! The rows and columns of a table can also be restricted with
+//! [`SecurityPolicy`]s, see [`SessionContext::register_security_policy`],
+//! whose row filters and column masks are planned above every scan of the
+//! table for the principals they apply to. Their row filters also restrict
+//! the rows deleted or updated by these principals, who can neither filter
+//! on nor update the masked columns.
+//!
+//! [`SessionState::with_authorizer`]: crate::execution::context::SessionState::with_authorizer
+//! [`SessionContext::register_security_policy`]: crate::execution::context::SessionContext::register_security_policy
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use crate::catalog::ResolvedTableReference;
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::builder::project_with_alias;
+use crate::logical_plan::{Expr, LogicalPlan, LogicalPlanBuilder};
+
+/// Checks the access of a session to the tables and columns of its catalogs,
+/// returning an error, which fails the planning of the query, if denied
+pub trait Authorizer: Debug + Send + Sync {
+    /// Checks that the session may access `table`, when the SQL planner
+    /// resolves a reference to it or a dataframe is created from it
+    fn authorize_table(&self, table: &ResolvedTableReference) -> Result<()>;
+
+    /// Checks that the session may read the `columns` of `table`, when a plan
+    /// scanning them is turned into a physical plan. The columns are the ones
+    /// left by the projection push down, so they only include the columns the
+    /// query uses.
+    fn authorize_columns(
+        &self,
+        table: &ResolvedTableReference,
+        columns: &[&str],
+    ) -> Result<()>;
+
+    /// Checks that the session may delete or update the rows of `table`,
+    /// when a `DELETE` or an `UPDATE` of the table is executed, after
+    /// [`Authorizer::authorize_table`].
+    /// - defaults to denying the writes, the authorizers granting them
+    ///   having to allow them explicitly
+    fn authorize_write(&self, table: &ResolvedTableReference) -> Result<()> {
+        Err(DataFusionError::Plan(format!(
+            "Write access denied to table {}.{}.{}",
+            table.catalog, table.schema, table.table
+        )))
+    }
+
+    /// Checks that the session may read the metadata of the files at `path`,
+    /// e.g. with the `parquet_metadata` table function, whose statistics
+    /// expose the ranges of the values of the files.
+    /// - defaults to denying the access, the files at any path being
+    ///   readable otherwise
+    fn authorize_file_metadata(&self, path: &str) -> Result<()> {
+        Err(DataFusionError::Plan(format!(
+            "Access denied to the metadata of the files at {}",
+            path
+        )))
+    }
+
+    /// Checks that the session may read the `columns` of the table scanned
+    /// as `table_name`, which isn't registered in the catalogs, e.g. the
+    /// table of a dataframe reading files or a table provider directly.
+    /// - defaults to denying the access, the scans of the tables the
+    ///   authorizer can't identify failing the planning of the query
+    fn authorize_unregistered_columns(
+        &self,
+        table_name: &str,
+        _columns: &[&str],
+    ) -> Result<()> {
+        Err(DataFusionError::Plan(format!(
+            "Access denied to the unregistered table {}",
+            table_name
+        )))
+    }
+}
+
+/// An [`Authorizer`] allowing the access to every table and column, the
+/// default
+#[derive(Debug, Default)]
+pub struct AllowAllAuthorizer;
+
+impl Authorizer for AllowAllAuthorizer {
+    fn authorize_table(&self, _table: &ResolvedTableReference) -> Result<()> {
+        Ok(())
+    }
+
+    fn authorize_columns(
+        &self,
+        _table: &ResolvedTableReference,
+        _columns: &[&str],
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    fn authorize_write(&self, _table: &ResolvedTableReference) -> Result<()> {
+        Ok(())
+    }
+
+    fn authorize_file_metadata(&self, _path: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn authorize_unregistered_columns(
+        &self,
+        _table_name: &str,
+        _columns: &[&str],
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The row filter and column masks of a table, planned above every scan of
+/// the table for the principals the policy applies to, see
+/// [`SessionConfig::with_principal`].
+///
+/// The row filter keeps the rows for which it is true, and is evaluated on
+/// the values of the table before they are masked. A masked column is
+/// replaced by its mask, which is evaluated on the values of the table, so
+/// that its values are not visible to the queries.
+///
+/// [`SessionConfig::with_principal`]: crate::execution::context::SessionConfig::with_principal
+#[derive(Debug, Clone, Default)]
+pub struct SecurityPolicy {
+    /// The principals the policy applies to, or all if empty
+    principals: Vec<String>,
+    /// Keeps the rows for which it is true
+    row_filter: Option<Expr>,
+    /// The masks of the columns, by their name
+    column_masks: HashMap<String, Expr>,
+}
+
+impl SecurityPolicy {
+    /// Creates a policy applying to every principal, which doesn't restrict
+    /// anything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restricts the policy to the `principals`
+    pub fn with_principals(
+        mut self,
+        principals: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.principals = principals.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Keeps the rows for which `row_filter` is true
+    pub fn with_row_filter(mut self, row_filter: Expr) -> Self {
+        self.row_filter = Some(row_filter);
+        self
+    }
+
+    /// Replaces the values of the column `column` by `mask`
+    pub fn with_column_mask(mut self, column: impl Into<String>, mask: Expr) -> Self {
+        self.column_masks.insert(column.into(), mask);
+        self
+    }
+
+    /// The principals the policy applies to, or all if empty
+    pub fn principals(&self) -> &[String] {
+        &self.principals
+    }
+
+    /// The row filter, if any
+    pub fn row_filter(&self) -> Option<&Expr> {
+        self.row_filter.as_ref()
+    }
+
+    /// The masks of the columns, by their name
+    pub fn column_masks(&self) -> &HashMap<String, Expr> {
+        &self.column_masks
+    }
+
+    /// Whether the policy applies to the session of `principal`
+    pub fn applies_to(&self, principal: Option<&str>) -> bool {
+        self.principals.is_empty()
+            || principal.map_or(false, |principal| {
+                self.principals.iter().any(|p| p == principal)
+            })
+    }
+
+    /// Plans the row filter and the column masks above `scan`, a scan of the
+    /// table of the policy
+    pub fn apply(&self, scan: LogicalPlan) -> Result<LogicalPlan> {
+        let mut builder = LogicalPlanBuilder::from(scan);
+        if let Some(row_filter) = &self.row_filter {
+            builder = builder.filter(row_filter.clone())?;
+        }
+        let plan = builder.build()?;
+        let fields = plan.schema().fields();
+        if self.column_masks.is_empty() || fields.is_empty() {
+            return Ok(plan);
+        }
+
+        // keep the qualifier of the table, so that the masked columns can be
+        // referenced like the ones of the table
+        let qualifier = fields[0].qualifier().cloned();
+        let exprs = fields
+            .iter()
+            .map(|field| match self.column_masks.get(field.name()) {
+                Some(mask) => mask.clone().alias(field.name()),
+                None => Expr::Column(field.qualified_column()),
+            })
+            .collect::<Vec<_>>();
+        project_with_alias(plan, exprs, qualifier)
+    }
+}
@@ -0,0 +1,126 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Blocking facade of the `async` API, for synchronous applications.
+//!
+//! The blocking methods, such as [`SessionContext::sql_blocking`] and
+//! [`DataFrame::collect_blocking`], run the queries on a runtime managed by
+//! DataFusion, created on first use, so that the applications do not need to
+//! set up a tokio runtime. They can be called from any thread, including the
+//! threads of another tokio runtime (e.g. from a synchronous callback of an
+//! `async` application), where they block the calling thread until the query
+//! completes. The other `async` methods can be called in the same way with
+//! [`block_on`].
+//!
+//! [`SessionContext::sql_blocking`]: crate::execution::context::SessionContext::sql_blocking
+//! [`DataFrame::collect_blocking`]: crate::dataframe::DataFrame::collect_blocking
+
+use std::future::Future;
+
+use lazy_static::lazy_static;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+lazy_static! {
+    /// The runtime running the futures of the blocking facade
+    static ref RUNTIME: Runtime = Builder::new_multi_thread()
+        .thread_name("datafusion-blocking")
+        .enable_all()
+        .build()
+        .expect("Failed to create the runtime of the blocking API");
+}
+
+/// Runs the future created by `f` to completion on the runtime of the
+/// blocking facade, blocking the calling thread until it completes. The
+/// future is created and polled on another thread when the calling thread
+/// belongs to a tokio runtime, whose threads cannot block on a future.
+/// A panic of the future is resumed on the calling thread.
+///
+/// ```
+/// use datafusion::execution::blocking::block_on;
+/// use datafusion::prelude::*;
+/// # use datafusion::error::Result;
+/// # fn main() -> Result<()> {
+/// let ctx = SessionContext::new();
+/// let registered = ctx.clone();
+/// block_on(move || async move {
+///     registered
+///         .register_csv("example", "tests/example.csv", CsvReadOptions::new())
+///         .await
+/// })?;
+/// let batches = ctx.sql_blocking("SELECT a FROM example")?.collect_blocking()?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn block_on<F, Fut>(f: F) -> Fut::Output
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future,
+    Fut::Output: Send + 'static,
+{
+    if Handle::try_current().is_err() {
+        return RUNTIME.block_on(f());
+    }
+    match std::thread::Builder::new()
+        .name("datafusion-blocking-caller".to_owned())
+        .spawn(move || RUNTIME.block_on(f()))
+        .expect("Failed to spawn the thread of a blocking call")
+        .join()
+    {
+        Ok(output) => output,
+        Err(panic) => std::panic::resume_unwind(panic),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::error::Result;
+    use crate::prelude::SessionContext;
+
+    fn query_one(ctx: &SessionContext) -> Result<()> {
+        let batches = ctx.sql_blocking("SELECT 1 AS one")?.collect_blocking()?;
+        let expected = vec!["+-----+", "| one |", "+-----+", "| 1   |", "+-----+"];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[test]
+    fn blocking_without_runtime() -> Result<()> {
+        query_one(&SessionContext::new())
+    }
+
+    #[tokio::test]
+    async fn blocking_in_current_thread_runtime() -> Result<()> {
+        query_one(&SessionContext::new())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn blocking_in_multi_thread_runtime() -> Result<()> {
+        let ctx = SessionContext::new();
+        tokio::task::spawn_blocking(move || query_one(&ctx))
+            .await
+            .unwrap()?;
+        query_one(&SessionContext::new())
+    }
+
+    #[test]
+    #[should_panic(expected = "panic in a blocking call")]
+    fn blocking_call_panic() {
+        block_on(|| async { panic!("panic in a blocking call") })
+    }
+}
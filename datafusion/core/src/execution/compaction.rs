@@ -0,0 +1,428 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Compaction of the files of tables, e.g. with `OPTIMIZE TABLE t`.
+//!
+//! Tables written incrementally tend to accumulate many small files, which
+//! slow down their scans. The compaction rewrites the files of a
+//! [`ListingTable`] of Parquet files into fewer, larger files of about a
+//! target size, optionally sorted, and removes the original files.
+//!
+//! The rewrite is not atomic: the new files are written under temporary
+//! names and renamed once they are all complete, then the original files are
+//! removed, so that a scan running at the same time may read the rows twice.
+//!
+//! [`ListingTable`]: crate::datasource::listing::ListingTable
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::UInt64Array;
+use arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use log::debug;
+use parking_lot::RwLock;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use uuid::Uuid;
+
+use crate::dataframe::DataFrame;
+use crate::datasource::file_format::parquet::ParquetFormat;
+use crate::datasource::listing::ListingTable;
+use crate::datasource::TableProvider;
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::{SessionState, TaskContext};
+use crate::logical_plan::{Expr, LogicalPlan, LogicalPlanBuilder};
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::common::batch_byte_size;
+use crate::physical_plan::file_format::parquet::schema_with_sort_order;
+use crate::physical_plan::file_format::ParquetExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::ExecutionPlan;
+
+/// Default target size of the compacted files in bytes
+pub const DEFAULT_TARGET_FILE_SIZE: usize = 128 * 1024 * 1024;
+
+/// Options of the compaction of a table
+#[derive(Debug, Clone)]
+pub struct CompactionOptions {
+    sort_exprs: Vec<Expr>,
+    target_file_size: usize,
+    writer_properties: Option<WriterProperties>,
+}
+
+impl Default for CompactionOptions {
+    fn default() -> Self {
+        Self {
+            sort_exprs: vec![],
+            target_file_size: DEFAULT_TARGET_FILE_SIZE,
+            writer_properties: None,
+        }
+    }
+}
+
+impl CompactionOptions {
+    /// Create the default options: unsorted files of
+    /// [`DEFAULT_TARGET_FILE_SIZE`] bytes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sort the rows of the compacted files by `sort_exprs`
+    pub fn with_sort_exprs(mut self, sort_exprs: Vec<Expr>) -> Self {
+        self.sort_exprs = sort_exprs;
+        self
+    }
+
+    /// Start a new file once the batches written to the current one exceed
+    /// `target_file_size` bytes in memory. The encoded files are usually
+    /// smaller.
+    pub fn with_target_file_size(mut self, target_file_size: usize) -> Self {
+        self.target_file_size = target_file_size;
+        self
+    }
+
+    /// Write the files with `writer_properties`, e.g. their compression
+    pub fn with_writer_properties(mut self, writer_properties: WriterProperties) -> Self {
+        self.writer_properties = Some(writer_properties);
+        self
+    }
+
+    /// The order of the rows of the compacted files
+    pub fn sort_exprs(&self) -> &[Expr] {
+        &self.sort_exprs
+    }
+
+    /// The target size of the compacted files in bytes
+    pub fn target_file_size(&self) -> usize {
+        self.target_file_size
+    }
+}
+
+/// The summary of the compaction of a table
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactionSummary {
+    /// Number of the original files removed
+    pub files_removed: usize,
+    /// Number of the compacted files written
+    pub files_written: usize,
+    /// Number of the rows rewritten
+    pub num_rows: usize,
+}
+
+impl CompactionSummary {
+    /// The summary as a batch of one row, in the schema of
+    /// [`LogicalPlan::optimize_table_schema`]
+    pub fn to_record_batch(&self) -> Result<RecordBatch> {
+        Ok(RecordBatch::try_new(
+            LogicalPlan::optimize_table_schema(),
+            vec![
+                Arc::new(UInt64Array::from(vec![self.files_removed as u64])),
+                Arc::new(UInt64Array::from(vec![self.files_written as u64])),
+                Arc::new(UInt64Array::from(vec![self.num_rows as u64])),
+            ],
+        )?)
+    }
+}
+
+/// Rewrites the files of `provider`, the table `name`, into fewer, larger
+/// files, see [`SessionContext::optimize_table`].
+///
+/// [`SessionContext::optimize_table`]: crate::execution::context::SessionContext::optimize_table
+pub(crate) async fn compact_table(
+    session_state: Arc<RwLock<SessionState>>,
+    name: &str,
+    provider: Arc<dyn TableProvider>,
+    options: CompactionOptions,
+) -> Result<CompactionSummary> {
+    let table = provider
+        .as_any()
+        .downcast_ref::<ListingTable>()
+        .ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "Only the listing tables can be optimized, {} is not one",
+                name
+            ))
+        })?;
+    if !table.options().table_partition_cols.is_empty() {
+        return Err(DataFusionError::NotImplemented(format!(
+            "Optimizing the partitioned table {} is not supported",
+            name
+        )));
+    }
+    if table
+        .options()
+        .format
+        .as_any()
+        .downcast_ref::<ParquetFormat>()
+        .is_none()
+    {
+        return Err(DataFusionError::Plan(format!(
+            "Only the tables of Parquet files can be optimized, {} is not one",
+            name
+        )));
+    }
+    let dir = Path::new(table.table_path());
+    if !dir.is_dir() {
+        return Err(DataFusionError::Plan(format!(
+            "Only the tables of a local directory can be optimized, {} is not one",
+            name
+        )));
+    }
+
+    let mut builder = LogicalPlanBuilder::scan(name, provider.clone(), None)?;
+    if !options.sort_exprs.is_empty() {
+        builder = builder.sort(options.sort_exprs.clone())?;
+    }
+    let dataframe = DataFrame::new(session_state.clone(), &builder.build()?);
+    let plan = dataframe.create_physical_plan().await?;
+
+    // the files removed are the ones read by the plan, whatever files were
+    // added to the table since
+    let mut input_files = vec![];
+    scanned_files(plan.as_ref(), &mut input_files);
+    input_files.sort();
+    input_files.dedup();
+
+    let prefix = format!("compacted-{}", Uuid::new_v4());
+    let extension = &table.options().file_extension;
+    let temp_path = |i: usize| dir.join(format!("{}-{}{}.tmp", prefix, i, extension));
+    let state = session_state.read().clone();
+    let written = plan_to_sized_parquet(
+        &state,
+        plan,
+        &temp_path,
+        options.target_file_size,
+        options.writer_properties,
+    )
+    .await;
+    let (temp_files, num_rows) = match written {
+        Ok(written) => written,
+        Err(e) => {
+            for i in 0.. {
+                if fs::remove_file(temp_path(i)).is_err() {
+                    break;
+                }
+            }
+            return Err(e);
+        }
+    };
+
+    for temp_file in &temp_files {
+        fs::rename(temp_file, temp_file.with_extension(""))?;
+    }
+    for input_file in &input_files {
+        fs::remove_file(input_file)?;
+    }
+    debug!(
+        "Compacted {} files of {} into {} files",
+        input_files.len(),
+        name,
+        temp_files.len()
+    );
+    Ok(CompactionSummary {
+        files_removed: input_files.len(),
+        files_written: temp_files.len(),
+        num_rows,
+    })
+}
+
+/// Adds the paths of the Parquet files read by `plan` to `files`
+fn scanned_files(plan: &dyn ExecutionPlan, files: &mut Vec<String>) {
+    if let Some(exec) = plan.as_any().downcast_ref::<ParquetExec>() {
+        files.extend(
+            exec.base_config()
+                .file_groups
+                .iter()
+                .flatten()
+                .map(|file| file.file_meta.path().to_owned()),
+        );
+    }
+    for child in plan.children() {
+        scanned_files(child.as_ref(), files);
+    }
+}
+
+/// Executes `plan` and writes its rows to the Parquet files at
+/// `file_path(0)`, `file_path(1)`, .., starting a new file once the batches
+/// written to the current one exceed `target_file_size` bytes in memory.
+/// The partitions of `plan` are merged, preserving their order if any.
+///
+/// Returns the paths of the files written and the number of rows.
+pub(crate) async fn plan_to_sized_parquet(
+    state: &SessionState,
+    plan: Arc<dyn ExecutionPlan>,
+    file_path: &dyn Fn(usize) -> PathBuf,
+    target_file_size: usize,
+    writer_properties: Option<WriterProperties>,
+) -> Result<(Vec<PathBuf>, usize)> {
+    let plan: Arc<dyn ExecutionPlan> =
+        if plan.output_partitioning().partition_count() == 1 {
+            plan
+        } else if let Some(ordering) = plan.output_ordering() {
+            Arc::new(SortPreservingMergeExec::new(
+                ordering.to_vec(),
+                plan.clone(),
+            ))
+        } else {
+            Arc::new(CoalescePartitionsExec::new(plan))
+        };
+    let schema = schema_with_sort_order(plan.as_ref());
+    let task_ctx = Arc::new(TaskContext::from(state));
+    let mut stream = plan.execute(0, task_ctx).await?;
+
+    let mut files = vec![];
+    let mut writer: Option<ArrowWriter<File>> = None;
+    let mut file_size = 0;
+    let mut num_rows = 0;
+    while let Some(batch) = stream.next().await {
+        let batch = batch?;
+        if batch.num_rows() == 0 {
+            continue;
+        }
+        // the batches must have the metadata of the writer
+        let batch = RecordBatch::try_new(schema.clone(), batch.columns().to_vec())?;
+        if writer.is_none() {
+            let path = file_path(files.len());
+            let file = File::create(&path)?;
+            files.push(path);
+            file_size = 0;
+            writer = Some(ArrowWriter::try_new(
+                file,
+                schema.clone(),
+                writer_properties.clone(),
+            )?);
+        }
+        if let Some(current) = writer.as_mut() {
+            current.write(&batch)?;
+        }
+        num_rows += batch.num_rows();
+        file_size += batch_byte_size(&batch);
+        if file_size >= target_file_size {
+            if let Some(full) = writer.take() {
+                full.close()?;
+            }
+        }
+    }
+    if let Some(last) = writer {
+        last.close()?;
+    }
+    Ok((files, num_rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::datasource::MemTable;
+    use crate::logical_plan::col;
+    use crate::prelude::{ParquetReadOptions, SessionContext};
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use tempfile::TempDir;
+
+    /// Writes the table `t` as 4 files of 25 rows, whose column `a` is
+    /// decreasing, and registers it
+    async fn small_files_table(ctx: &SessionContext, dir: &Path) -> Result<String> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let partitions = (0..4)
+            .map(|i| {
+                let values = (0..25).map(|j| 99 - (i * 25 + j)).collect::<Vec<_>>();
+                let batch = RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Array::from(values))],
+                )?;
+                Ok(vec![batch])
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let table = MemTable::try_new(schema, partitions)?;
+        let path = dir.join("t").to_str().unwrap().to_string();
+        ctx.read_table(Arc::new(table))?
+            .write_parquet(&path, None)
+            .await?;
+        ctx.register_parquet("t", &path, ParquetReadOptions::default())
+            .await?;
+        Ok(path)
+    }
+
+    fn parquet_files(path: &str) -> Vec<String> {
+        let mut files = fs::read_dir(path)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn optimize_table() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let ctx = SessionContext::new();
+        let path = small_files_table(&ctx, tmp_dir.path()).await?;
+        assert_eq!(parquet_files(&path).len(), 4);
+
+        let options = CompactionOptions::new()
+            .with_sort_exprs(vec![col("a").sort(true, false)])
+            .with_target_file_size(400);
+        let summary = ctx.optimize_table("t", options).await?;
+        assert_eq!(summary.files_removed, 4);
+        assert_eq!(summary.num_rows, 100);
+        assert!(summary.files_written > 1);
+
+        let files = parquet_files(&path);
+        assert_eq!(files.len(), summary.files_written);
+        assert!(files
+            .iter()
+            .all(|f| f.starts_with("compacted-") && f.ends_with(".parquet")));
+
+        let batches = ctx
+            .sql("SELECT count(*), min(a), max(a) FROM t")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+-----------------+----------+----------+",
+            "| COUNT(UInt8(1)) | MIN(t.a) | MAX(t.a) |",
+            "+-----------------+----------+----------+",
+            "| 100             | 0        | 99       |",
+            "+-----------------+----------+----------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn optimize_table_invalid() -> Result<()> {
+        let ctx = SessionContext::new();
+        ctx.sql("CREATE TABLE m AS SELECT 1 AS a").await?;
+        let err = ctx
+            .optimize_table("m", CompactionOptions::new())
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Only the listing tables can be optimized, m is not one"));
+
+        let err = ctx
+            .optimize_table("u", CompactionOptions::new())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No table named 'u'"));
+        Ok(())
+    }
+}
@@ -20,6 +20,7 @@ use crate::{
     catalog::{
         catalog::{CatalogList, MemoryCatalogList},
         information_schema::CatalogWithInformationSchema,
+        snapshot::CatalogSnapshot,
     },
     datasource::listing::{ListingOptions, ListingTable},
     datasource::{
@@ -33,23 +34,33 @@ use crate::{
         MemTable,
     },
     logical_plan::{PlanType, ToStringifiedPlan},
+    optimizer::eliminate_aggregate::EliminateAggregate,
     optimizer::eliminate_filter::EliminateFilter,
     optimizer::eliminate_limit::EliminateLimit,
+    optimizer::eliminate_sort::EliminateSort,
     physical_optimizer::{
         aggregate_statistics::AggregateStatistics,
-        hash_build_probe_order::HashBuildProbeOrder, optimizer::PhysicalOptimizerRule,
+        cost::{CostModel, StatisticsCostModel},
+        empty_result::EmptyResult,
+        fuse_filter_projection::FuseFilterProjection,
+        hash_build_probe_order::HashBuildProbeOrder,
+        join_build_projection::JoinBuildProjection,
+        optimizer::PhysicalOptimizerRule,
     },
 };
-use log::{debug, trace};
+use ahash::RandomState;
+use log::{debug, trace, warn};
 use parking_lot::RwLock;
 use std::string::String;
 use std::sync::Arc;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     fmt::Debug,
 };
 
+use arrow::array::{ArrayRef, UInt64Array};
 use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
 
 use crate::catalog::{
     catalog::{CatalogProvider, MemoryCatalogProvider},
@@ -57,42 +68,64 @@ use crate::catalog::{
     ResolvedTableReference, TableReference,
 };
 use crate::dataframe::DataFrame;
+use crate::datasource::empty::EmptyTable;
+use crate::datasource::limited_object_store::{
+    LimitedObjectStore, ObjectStoreRequestLimits, RequestLimiter,
+};
 use crate::datasource::listing::ListingTableConfig;
+use crate::datasource::parquet_metadata::ParquetMetadataTable;
 use crate::datasource::TableProvider;
 use crate::error::{DataFusionError, Result};
 use crate::logical_plan::{
-    CreateCatalog, CreateCatalogSchema, CreateExternalTable, CreateMemoryTable,
-    DropTable, FileType, FunctionRegistry, LogicalPlan, LogicalPlanBuilder,
-    UNNAMED_TABLE,
+    unnormalize_col, CreateCatalog, CreateCatalogSchema, CreateExternalTable,
+    CreateMemoryTable, Delete, DropTable, Expr, FileType, FunctionRegistry, LogicalPlan,
+    LogicalPlanBuilder, Operator, OptimizeTable, PlanVisitor, SetVariable, TableScan,
+    Transaction, TransactionStatement, Update, UNNAMED_TABLE,
 };
 use crate::optimizer::common_subexpr_eliminate::CommonSubexprEliminate;
 use crate::optimizer::filter_push_down::FilterPushDown;
 use crate::optimizer::limit_push_down::LimitPushDown;
+use crate::optimizer::merge_filters::MergeFilters;
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::projection_push_down::ProjectionPushDown;
+use crate::optimizer::reuse_filter_predicates::ReuseFilterPredicates;
 use crate::optimizer::simplify_expressions::SimplifyExpressions;
 use crate::optimizer::single_distinct_to_groupby::SingleDistinctToGroupBy;
 use crate::optimizer::to_approx_perc::ToApproxPerc;
+use crate::optimizer::union_distinct::UnionDistinct;
+use crate::optimizer::utils;
+use datafusion_data_access::object_store::ObjectStore;
 
 use crate::physical_optimizer::coalesce_batches::CoalesceBatches;
 use crate::physical_optimizer::merge_exec::AddCoalescePartitionsExec;
 use crate::physical_optimizer::repartition::Repartition;
 
+use crate::execution::authorization::{AllowAllAuthorizer, Authorizer, SecurityPolicy};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::execution::blocking::block_on;
+use crate::execution::compaction::{compact_table, CompactionOptions, CompactionSummary};
+use crate::execution::kernels::KernelRegistry;
+use crate::execution::plan_baseline::PlanBaselines;
 use crate::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
-use crate::logical_plan::plan::Explain;
+use crate::execution::transaction::{NoOpTransactionHandler, TransactionHandler};
+use crate::logical_expr::{BuiltinScalarFunction, ScalarFunctionImplementation};
+use crate::logical_plan::plan::{source_as_provider, Explain};
+use crate::physical_plan::async_udf::AsyncScalarUDF;
 use crate::physical_plan::file_format::{plan_to_csv, plan_to_json, plan_to_parquet};
-use crate::physical_plan::planner::DefaultPhysicalPlanner;
+use crate::physical_plan::hash_aggregate::NullGroupKeys;
+use crate::physical_plan::planner::{evaluate_expr_with_props, DefaultPhysicalPlanner};
 use crate::physical_plan::udaf::AggregateUDF;
 use crate::physical_plan::udf::ScalarUDF;
 use crate::physical_plan::ExecutionPlan;
 use crate::physical_plan::PhysicalPlanner;
 use crate::sql::{
-    parser::DFParser,
-    planner::{ContextProvider, SqlToRel},
+    parser::{DFParser, Statement as DFStatement},
+    planner::{ContextProvider, NullOrdering, SqlToRel, StatementPlanner},
 };
 use crate::variable::{VarProvider, VarType};
 use async_trait::async_trait;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, FixedOffset, Utc, Weekday};
+use datafusion_physical_expr::datetime_expressions::{parse_time_zone, TemporalSettings};
 use parquet::file::properties::WriterProperties;
 use uuid::Uuid;
 
@@ -214,72 +247,176 @@ impl SessionContext {
     /// might require the schema to be inferred.
     pub async fn sql(&self, sql: &str) -> Result<Arc<DataFrame>> {
         let plan = self.create_logical_plan(sql)?;
+        self.execute_logical_plan(plan).await
+    }
+
+    /// Creates a dataframe that will execute a SQL query, blocking the
+    /// calling thread, without a tokio runtime. See
+    /// [`blocking`](crate::execution::blocking) for the runtime running the
+    /// query.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let batches = ctx.sql_blocking("SELECT 1")?.collect_blocking()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn sql_blocking(&self, sql: &str) -> Result<Arc<DataFrame>> {
+        let ctx = self.clone();
+        let sql = sql.to_owned();
+        block_on(move || async move { ctx.sql(&sql).await })
+    }
+
+    /// Executes the statements of a SQL script, separated by semicolons, one
+    /// after the other, and returns the results of each statement. The
+    /// statements creating, dropping or configuring objects return no batch.
+    ///
+    /// Each statement is planned once the previous ones are executed, so that
+    /// it can use their tables. The script stops at the first failing
+    /// statement, keeping the effects of the previous ones: use
+    /// [`SessionContext::validate_sql_multi`] to check the whole script
+    /// before running it.
+    ///
+    /// The tables are resolved in a snapshot of the catalogs captured when
+    /// the script starts, or when a transaction of the script begins, see
+    /// [`SessionState::capture_catalog_snapshot`]: the tables registered or
+    /// deregistered elsewhere while it runs are not seen by its statements.
+    pub async fn sql_multi(&self, sql: &str) -> Result<Vec<Vec<RecordBatch>>> {
+        let statements = DFParser::parse_sql(sql)?;
+        let snapshot = {
+            // the snapshot of an open transaction is kept
+            let mut state = self.state.write();
+            (!state.has_catalog_snapshot()).then(|| state.capture_catalog_snapshot())
+        };
+        let results = self.execute_statements(statements).await;
+        if let Some(snapshot) = snapshot {
+            // unless a transaction of the script replaced or released it
+            let mut state = self.state.write();
+            if Arc::as_ptr(&state.catalog_list) as *const u8
+                == Arc::as_ptr(&snapshot) as *const u8
+            {
+                state.release_catalog_snapshot();
+            }
+        }
+        results
+    }
+
+    async fn execute_statements(
+        &self,
+        statements: VecDeque<DFStatement>,
+    ) -> Result<Vec<Vec<RecordBatch>>> {
+        let mut results = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let plan = {
+                let state = self.state.read().clone();
+                SqlToRel::new(&state).statement_to_plan(statement)?
+            };
+            let is_command = matches!(
+                plan,
+                LogicalPlan::CreateExternalTable(_)
+                    | LogicalPlan::CreateMemoryTable(_)
+                    | LogicalPlan::DropTable(_)
+                    | LogicalPlan::SetVariable(_)
+                    | LogicalPlan::Transaction(_)
+                    | LogicalPlan::CreateCatalogSchema(_)
+                    | LogicalPlan::CreateCatalog(_)
+            );
+            let dataframe = self.execute_logical_plan(plan).await?;
+            results.push(if is_command {
+                vec![]
+            } else {
+                dataframe.collect().await?
+            });
+        }
+        Ok(results)
+    }
+
+    /// Plans the statements of a SQL script without executing them, and
+    /// returns their logical plans. The script is valid if this succeeds.
+    ///
+    /// The tables created by the statements are visible to the following
+    /// ones, with the schema they would have, and the dropped tables are not,
+    /// but no table is registered or deregistered in this context. The
+    /// schemas of the external tables declared without columns are inferred
+    /// from their files, which are read but not modified. The `SET`
+    /// statements apply to the following statements of the script, but not
+    /// to this context.
+    pub async fn validate_sql_multi(&self, sql: &str) -> Result<Vec<LogicalPlan>> {
+        let statements = DFParser::parse_sql(sql)?;
+        // the statements are planned with the settings changed by the script
+        let mut state = self.state.read().clone();
+        state.execution_props.start_execution();
+        let mut tables = HashMap::new();
+        let mut plans = Vec::with_capacity(statements.len());
+        for statement in statements {
+            let mut provider = DryRunContextProvider {
+                state: &state,
+                tables: &mut tables,
+            };
+            let plan = SqlToRel::new(&provider).statement_to_plan(statement)?;
+            match &plan {
+                LogicalPlan::CreateExternalTable(cmd) => {
+                    if !provider.check_create(&cmd.name, cmd.if_not_exists)? {
+                        let table = self.external_table(cmd).await?;
+                        provider.register(&cmd.name, Arc::new(table));
+                    }
+                }
+                LogicalPlan::CreateMemoryTable(CreateMemoryTable {
+                    name,
+                    input,
+                    if_not_exists,
+                }) => {
+                    state.create_physical_plan(&state.optimize(input)?).await?;
+                    if !provider.check_create(name, *if_not_exists)? {
+                        let schema = Arc::new(input.schema().as_ref().into());
+                        provider.register(name, Arc::new(EmptyTable::new(schema)));
+                    }
+                }
+                LogicalPlan::DropTable(DropTable {
+                    name, if_exists, ..
+                }) => provider.drop(name, *if_exists)?,
+                LogicalPlan::SetVariable(SetVariable {
+                    variable, values, ..
+                }) => set_variable(&mut state.config, variable, values)?,
+                LogicalPlan::Transaction(_)
+                | LogicalPlan::OptimizeTable(_)
+                | LogicalPlan::Delete(_)
+                | LogicalPlan::Update(_)
+                | LogicalPlan::CreateCatalogSchema(_)
+                | LogicalPlan::CreateCatalog(_) => {}
+                plan => {
+                    state.create_physical_plan(&state.optimize(plan)?).await?;
+                }
+            }
+            plans.push(plan);
+        }
+        Ok(plans)
+    }
+
+    /// Executes the commands of the logical plan, e.g. `CREATE TABLE`, and
+    /// returns a dataframe of its results
+    async fn execute_logical_plan(&self, plan: LogicalPlan) -> Result<Arc<DataFrame>> {
         match plan {
-            LogicalPlan::CreateExternalTable(CreateExternalTable {
-                ref schema,
-                ref name,
-                ref location,
-                ref file_type,
-                ref has_header,
-                ref delimiter,
-                ref table_partition_cols,
-                ref if_not_exists,
-            }) => {
-                let (file_format, file_extension) = match file_type {
-                    FileType::CSV => (
-                        Arc::new(
-                            CsvFormat::default()
-                                .with_has_header(*has_header)
-                                .with_delimiter(*delimiter as u8),
-                        ) as Arc<dyn FileFormat>,
-                        DEFAULT_CSV_EXTENSION,
-                    ),
-                    FileType::Parquet => (
-                        Arc::new(ParquetFormat::default()) as Arc<dyn FileFormat>,
-                        DEFAULT_PARQUET_EXTENSION,
-                    ),
-                    FileType::Avro => (
-                        Arc::new(AvroFormat::default()) as Arc<dyn FileFormat>,
-                        DEFAULT_AVRO_EXTENSION,
-                    ),
-                    FileType::NdJson => (
-                        Arc::new(JsonFormat::default()) as Arc<dyn FileFormat>,
-                        DEFAULT_JSON_EXTENSION,
-                    ),
-                };
-                let table = self.table(name.as_str());
-                match (if_not_exists, table) {
-                    (true, Ok(_)) => {
+            LogicalPlan::CreateExternalTable(ref cmd) => {
+                let table_exists = self.table_exist(cmd.name.as_str())?;
+                match (cmd.if_not_exists, table_exists) {
+                    (true, true) => {
                         let plan = LogicalPlanBuilder::empty(false).build()?;
                         Ok(Arc::new(DataFrame::new(self.state.clone(), &plan)))
                     }
-                    (_, Err(_)) => {
-                        // TODO make schema in CreateExternalTable optional instead of empty
-                        let provided_schema = if schema.fields().is_empty() {
-                            None
-                        } else {
-                            Some(Arc::new(schema.as_ref().to_owned().into()))
-                        };
-                        let options = ListingOptions {
-                            format: file_format,
-                            collect_stat: false,
-                            file_extension: file_extension.to_owned(),
-                            target_partitions: self.copied_config().target_partitions,
-                            table_partition_cols: table_partition_cols.clone(),
-                        };
-                        self.register_listing_table(
-                            name,
-                            location,
-                            options,
-                            provided_schema,
-                        )
-                        .await?;
+                    (_, false) => {
+                        let table = self.external_table(cmd).await?;
+                        self.register_table(cmd.name.as_str(), Arc::new(table))?;
                         let plan = LogicalPlanBuilder::empty(false).build()?;
                         Ok(Arc::new(DataFrame::new(self.state.clone(), &plan)))
                     }
-                    (false, Ok(_)) => Err(DataFusionError::Execution(format!(
+                    (false, true) => Err(DataFusionError::Execution(format!(
                         "Table '{:?}' already exists",
-                        name
+                        cmd.name
                     ))),
                 }
             }
@@ -289,14 +426,13 @@ impl SessionContext {
                 input,
                 if_not_exists,
             }) => {
-                let table = self.table(name.as_str());
-
-                match (if_not_exists, table) {
-                    (true, Ok(_)) => {
+                let table_exists = self.table_exist(name.as_str())?;
+                match (if_not_exists, table_exists) {
+                    (true, true) => {
                         let plan = LogicalPlanBuilder::empty(false).build()?;
                         Ok(Arc::new(DataFrame::new(self.state.clone(), &plan)))
                     }
-                    (_, Err(_)) => {
+                    (_, false) => {
                         let plan = self.optimize(&input)?;
                         let physical =
                             Arc::new(DataFrame::new(self.state.clone(), &plan));
@@ -310,7 +446,7 @@ impl SessionContext {
                         self.register_table(name.as_str(), table)?;
                         Ok(Arc::new(DataFrame::new(self.state.clone(), &plan)))
                     }
-                    (false, Ok(_)) => Err(DataFusionError::Execution(format!(
+                    (false, true) => Err(DataFusionError::Execution(format!(
                         "Table '{:?}' already exists",
                         name
                     ))),
@@ -331,6 +467,76 @@ impl SessionContext {
                     Ok(Arc::new(DataFrame::new(self.state.clone(), &plan)))
                 }
             }
+            LogicalPlan::SetVariable(SetVariable {
+                variable, values, ..
+            }) => {
+                let mut state = self.state.write();
+                set_variable(&mut state.config, &variable, &values)?;
+                // the temporal functions are planned with the new settings
+                state.execution_props.temporal_settings =
+                    state.config.temporal_settings();
+                drop(state);
+                let plan = LogicalPlanBuilder::empty(false).build()?;
+                Ok(Arc::new(DataFrame::new(self.state.clone(), &plan)))
+            }
+            LogicalPlan::Transaction(Transaction { statement, .. }) => {
+                let transaction_handler = self.state.read().transaction_handler.clone();
+                transaction_handler.execute(statement)?;
+                // the statements of a transaction see the tables as they
+                // were when it began
+                match statement {
+                    TransactionStatement::Begin => {
+                        self.state.write().capture_catalog_snapshot();
+                    }
+                    TransactionStatement::Commit | TransactionStatement::Rollback => {
+                        self.state.write().release_catalog_snapshot();
+                    }
+                }
+                let plan = LogicalPlanBuilder::empty(false).build()?;
+                Ok(Arc::new(DataFrame::new(self.state.clone(), &plan)))
+            }
+            LogicalPlan::OptimizeTable(OptimizeTable {
+                name,
+                sort_exprs,
+                target_file_size,
+                ..
+            }) => {
+                let mut options = CompactionOptions::new().with_sort_exprs(sort_exprs);
+                if let Some(target_file_size) = target_file_size {
+                    options = options.with_target_file_size(target_file_size);
+                }
+                let batch = self
+                    .optimize_table(&name, options)
+                    .await?
+                    .to_record_batch()?;
+                let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+                self.read_table(Arc::new(table))
+            }
+            LogicalPlan::Delete(Delete {
+                table_name,
+                predicate,
+                ..
+            }) => {
+                let (provider, predicate) =
+                    self.authorized_dml_table(&table_name, predicate, &[])?;
+                let state = self.state.read().clone();
+                let count = provider.delete(&state, predicate.as_ref()).await?;
+                self.dml_count(count)
+            }
+            LogicalPlan::Update(Update {
+                table_name,
+                assignments,
+                predicate,
+                ..
+            }) => {
+                let (provider, predicate) =
+                    self.authorized_dml_table(&table_name, predicate, &assignments)?;
+                let state = self.state.read().clone();
+                let count = provider
+                    .update(&state, &assignments, predicate.as_ref())
+                    .await?;
+                self.dml_count(count)
+            }
             LogicalPlan::CreateCatalogSchema(CreateCatalogSchema {
                 schema_name,
                 if_not_exists,
@@ -420,10 +626,28 @@ impl SessionContext {
             ));
         }
 
-        // create a query planner
+        self.statement_to_plan(statements.pop_front().unwrap())
+    }
+
+    /// Creates a logical plan from a statement parsed beforehand, e.g. by the
+    /// parser of a project extending the SQL grammar. A sqlparser
+    /// [`Statement`](sqlparser::ast::Statement) converts into a statement
+    /// with `into()`, while the statements of custom kinds are planned by the
+    /// [`StatementPlanner`]s of the session.
+    pub fn statement_to_plan(&self, statement: DFStatement) -> Result<LogicalPlan> {
         let state = self.state.read().clone();
         let query_planner = SqlToRel::new(&state);
-        query_planner.statement_to_plan(statements.pop_front().unwrap())
+        query_planner.statement_to_plan(statement)
+    }
+
+    /// Creates a dataframe that will execute a statement parsed beforehand,
+    /// see [`SessionContext::statement_to_plan`]
+    pub async fn execute_statement(
+        &self,
+        statement: DFStatement,
+    ) -> Result<Arc<DataFrame>> {
+        let plan = self.statement_to_plan(statement)?;
+        self.execute_logical_plan(plan).await
     }
 
     /// Registers a variable provider within this context.
@@ -438,6 +662,57 @@ impl SessionContext {
             .add_var_provider(variable_type, provider);
     }
 
+    /// Registers `kernel` as the implementation of the built-in scalar
+    /// function `fun` in the physical plans of this context, e.g. to use
+    /// another regex engine for `regexp_match`. The kernel receives the
+    /// arguments coerced to the signature of the function and must return
+    /// its type.
+    pub fn register_function_kernel(
+        &mut self,
+        fun: BuiltinScalarFunction,
+        kernel: ScalarFunctionImplementation,
+    ) {
+        let mut state = self.state.write();
+        Arc::make_mut(&mut state.execution_props.kernels).register_function(fun, kernel);
+    }
+
+    /// Registers `kernel` as the implementation of the binary operator `op`
+    /// for the operands of types `left_type` and `right_type` in the physical
+    /// plans of this context, e.g. to compare strings case insensitively.
+    /// The kernel receives the two operands and must return the type of the
+    /// operator.
+    pub fn register_operator_kernel(
+        &mut self,
+        op: Operator,
+        left_type: DataType,
+        right_type: DataType,
+        kernel: ScalarFunctionImplementation,
+    ) {
+        let mut state = self.state.write();
+        Arc::make_mut(&mut state.execution_props.kernels)
+            .register_operator(op, left_type, right_type, kernel);
+    }
+
+    /// Registers the security `policy` of the table `table_ref`, whose row
+    /// filter and column masks are applied to every scan of the table planned
+    /// by the sessions of the principals it applies to. The policies of a
+    /// table are applied in the order of their registration.
+    pub fn register_security_policy<'a>(
+        &self,
+        table_ref: impl Into<TableReference<'a>>,
+        policy: SecurityPolicy,
+    ) {
+        let table_ref = table_ref.into();
+        let mut state = self.state.write();
+        let table = state.resolve_table_ref(table_ref);
+        let key = (
+            table.catalog.to_string(),
+            table.schema.to_string(),
+            table.table.to_string(),
+        );
+        state.security_policies.entry(key).or_default().push(policy);
+    }
+
     /// Registers a scalar UDF within this context.
     ///
     /// Note in SQL queries, function names are looked up using
@@ -452,6 +727,20 @@ impl SessionContext {
             .insert(f.name.clone(), Arc::new(f));
     }
 
+    /// Registers an async scalar UDF within this context, whose calls can only
+    /// be evaluated in the projections, filters, sorts and aggregates, see
+    /// [`async_udf`](crate::physical_plan::async_udf). Function names are
+    /// looked up as in [`register_udf`](Self::register_udf).
+    pub fn register_async_udf(&mut self, f: AsyncScalarUDF) {
+        let mut state = self.state.write();
+        state
+            .scalar_functions
+            .insert(f.name().to_owned(), Arc::new(f.to_scalar_udf()));
+        state
+            .async_scalar_functions
+            .insert(f.name().to_owned(), Arc::new(f));
+    }
+
     /// Registers an aggregate UDF within this context.
     ///
     /// Note in SQL queries, aggregate names are looked up using
@@ -582,6 +871,19 @@ impl SessionContext {
         options: ListingOptions,
         provided_schema: Option<SchemaRef>,
     ) -> Result<()> {
+        let table = self.listing_table(uri, options, provided_schema).await?;
+        self.register_table(name, Arc::new(table))?;
+        Ok(())
+    }
+
+    /// Creates a [`ListingTable`] of the files at `uri`, inferring their
+    /// schema if it is not provided
+    async fn listing_table(
+        &self,
+        uri: &str,
+        options: ListingOptions,
+        provided_schema: Option<SchemaRef>,
+    ) -> Result<ListingTable> {
         let (object_store, path) = self.runtime_env().object_store(uri)?;
         let resolved_schema = match provided_schema {
             None => {
@@ -594,9 +896,51 @@ impl SessionContext {
         let config = ListingTableConfig::new(object_store, path)
             .with_listing_options(options)
             .with_schema(resolved_schema);
-        let table = ListingTable::try_new(config)?;
-        self.register_table(name, Arc::new(table))?;
-        Ok(())
+        ListingTable::try_new(config)
+    }
+
+    /// Creates the [`ListingTable`] declared by a `CREATE EXTERNAL TABLE`
+    /// statement
+    async fn external_table(&self, cmd: &CreateExternalTable) -> Result<ListingTable> {
+        let (file_format, file_extension) = match cmd.file_type {
+            FileType::CSV => (
+                Arc::new(
+                    CsvFormat::default()
+                        .with_has_header(cmd.has_header)
+                        .with_delimiter(cmd.delimiter as u8),
+                ) as Arc<dyn FileFormat>,
+                DEFAULT_CSV_EXTENSION,
+            ),
+            FileType::Parquet => (
+                Arc::new(ParquetFormat::default()) as Arc<dyn FileFormat>,
+                DEFAULT_PARQUET_EXTENSION,
+            ),
+            FileType::Avro => (
+                Arc::new(AvroFormat::default()) as Arc<dyn FileFormat>,
+                DEFAULT_AVRO_EXTENSION,
+            ),
+            FileType::NdJson => (
+                Arc::new(JsonFormat::default()) as Arc<dyn FileFormat>,
+                DEFAULT_JSON_EXTENSION,
+            ),
+        };
+        // TODO make schema in CreateExternalTable optional instead of empty
+        let provided_schema = if cmd.schema.fields().is_empty() {
+            None
+        } else {
+            Some(Arc::new(cmd.schema.as_ref().to_owned().into()))
+        };
+        let options = ListingOptions {
+            format: file_format,
+            collect_stat: false,
+            file_extension: file_extension.to_owned(),
+            target_partitions: self.copied_config().target_partitions,
+            table_partition_cols: cmd.table_partition_cols.clone(),
+            file_sort_order: cmd.order_exprs.clone(),
+        };
+        self.listing_table(&cmd.location, options, provided_schema)
+            .await?
+            .with_constraints(cmd.constraints.clone())
     }
 
     /// Registers a CSV data source so that it can be referenced from SQL statements
@@ -690,7 +1034,7 @@ impl SessionContext {
         let state = self.state.read();
         let catalog = if information_schema {
             Arc::new(CatalogWithInformationSchema::new(
-                Arc::downgrade(&state.catalog_list),
+                Arc::downgrade(state.current_catalog_list()),
                 catalog,
             ))
         } else {
@@ -760,8 +1104,16 @@ impl SessionContext {
         table_ref: impl Into<TableReference<'a>>,
     ) -> Result<Arc<DataFrame>> {
         let table_ref = table_ref.into();
-        let schema = self.state.read().schema_for_ref(table_ref)?;
-        match schema.table(table_ref.table()) {
+        let provider = {
+            let state = self.state.read();
+            // report unknown catalogs and schemas of qualified names
+            if !matches!(table_ref, TableReference::Bare { .. }) {
+                state.schema_for_ref(table_ref)?;
+            }
+            state.authorize_table(table_ref)?;
+            state.find_table_provider(table_ref)
+        };
+        match provider {
             Some(ref provider) => {
                 let plan = LogicalPlanBuilder::scan(
                     table_ref.table(),
@@ -769,6 +1121,7 @@ impl SessionContext {
                     None,
                 )?
                 .build()?;
+                let plan = self.state.read().apply_security_policies(table_ref, plan)?;
                 Ok(Arc::new(DataFrame::new(self.state.clone(), &plan)))
             }
             _ => Err(DataFusionError::Plan(format!(
@@ -778,6 +1131,94 @@ impl SessionContext {
         }
     }
 
+    /// Rewrites the files of the table `name` into fewer, larger Parquet
+    /// files of about the target size of `options`, sorted by its sort
+    /// expressions if any, and removes the original files. This is the
+    /// implementation of `OPTIMIZE TABLE`.
+    ///
+    /// The table must be a [`ListingTable`] of the Parquet files of a local
+    /// directory, without partitioning columns. See
+    /// [`compaction`](crate::execution::compaction).
+    pub async fn optimize_table(
+        &self,
+        name: &str,
+        options: CompactionOptions,
+    ) -> Result<CompactionSummary> {
+        let provider = self.authorized_table(name)?;
+        let table_ref = TableReference::from(name);
+        compact_table(self.state.clone(), table_ref.table(), provider, options).await
+    }
+
+    /// Returns the table `name`, after checking that it may be accessed
+    fn authorized_table(&self, name: &str) -> Result<Arc<dyn TableProvider>> {
+        let table_ref = TableReference::from(name);
+        let state = self.state.read();
+        state.authorize_table(table_ref)?;
+        state.find_table_provider(table_ref).ok_or_else(|| {
+            DataFusionError::Plan(format!("No table named '{}'", table_ref.table()))
+        })
+    }
+
+    /// Returns the table `name` deleted from or updated, after checking that
+    /// it may be written to, and the predicate of the rows that may be
+    /// changed: `predicate` restricted to the rows the security policies of
+    /// the table let the session read. The columns they mask can neither be
+    /// filtered on nor assigned by `assignments`.
+    fn authorized_dml_table(
+        &self,
+        name: &str,
+        predicate: Option<Expr>,
+        assignments: &[(String, Expr)],
+    ) -> Result<(Arc<dyn TableProvider>, Option<Expr>)> {
+        let provider = self.authorized_table(name)?;
+        let table_ref = TableReference::from(name);
+        let state = self.state.read();
+        if let Some((table, _)) = state.find_table(table_ref) {
+            state.authorizer.authorize_write(&table)?;
+        }
+
+        let policies = state.applicable_security_policies(table_ref);
+        let mut columns = HashSet::new();
+        for expr in predicate.iter().chain(assignments.iter().map(|(_, e)| e)) {
+            utils::expr_to_columns(expr, &mut columns)?;
+        }
+        let masked_column = columns
+            .iter()
+            .map(|column| column.name.as_str())
+            .chain(assignments.iter().map(|(name, _)| name.as_str()))
+            .find(|name| {
+                policies
+                    .iter()
+                    .any(|policy| policy.column_masks().contains_key(*name))
+            });
+        if let Some(name) = masked_column {
+            return Err(DataFusionError::Plan(format!(
+                "The masked column '{}' can't be filtered on or updated",
+                name
+            )));
+        }
+        let predicate = predicate
+            .into_iter()
+            .chain(
+                policies
+                    .iter()
+                    .filter_map(|policy| policy.row_filter())
+                    .map(|row_filter| unnormalize_col(row_filter.clone())),
+            )
+            .reduce(Expr::and);
+        Ok((provider, predicate))
+    }
+
+    /// Returns the dataframe of the number of rows deleted or updated
+    fn dml_count(&self, count: u64) -> Result<Arc<DataFrame>> {
+        let batch = RecordBatch::try_new(
+            LogicalPlan::dml_schema(),
+            vec![Arc::new(UInt64Array::from(vec![count]))],
+        )?;
+        let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+        self.read_table(Arc::new(table))
+    }
+
     /// Returns the set of available tables in the default catalog and schema.
     ///
     /// Use [`table`] to get a specific table.
@@ -803,6 +1244,19 @@ impl SessionContext {
         self.state.read().optimize(plan)
     }
 
+    /// Evaluates the logical expression `expr` on the rows of `batch`,
+    /// without planning a query, with the time zone, variables and kernels
+    /// of this session. See [`evaluate_expr`] for the columns and the
+    /// expressions supported.
+    ///
+    /// [`evaluate_expr`]: crate::physical_plan::planner::evaluate_expr
+    pub fn evaluate_expr(&self, expr: &Expr, batch: &RecordBatch) -> Result<ArrayRef> {
+        let state = self.state.read();
+        let mut execution_props = state.execution_props.clone();
+        execution_props.start_execution();
+        evaluate_expr_with_props(expr, batch, &execution_props)
+    }
+
     /// Creates a physical plan from a logical plan.
     pub async fn create_physical_plan(
         &self,
@@ -907,6 +1361,109 @@ impl QueryPlanner for DefaultQueryPlanner {
     }
 }
 
+/// Parses the value of `SET target_partitions`: a positive number of
+/// partitions, or `DEFAULT` for the available parallelism
+fn parse_target_partitions(values: &[String]) -> Result<usize> {
+    match values {
+        [value] if value.eq_ignore_ascii_case("default") => {
+            Ok(default_target_partitions())
+        }
+        [value] => match value.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(DataFusionError::Plan(format!(
+                "Invalid value '{}' for {}, expected a positive number of partitions",
+                value, TARGET_PARTITIONS
+            ))),
+        },
+        _ => Err(DataFusionError::Plan(format!(
+            "{} takes exactly one value",
+            TARGET_PARTITIONS
+        ))),
+    }
+}
+
+/// Applies `SET variable = values` to the `config`
+fn set_variable(
+    config: &mut SessionConfig,
+    variable: &str,
+    values: &[String],
+) -> Result<()> {
+    if variable.eq_ignore_ascii_case("search_path") {
+        config.search_path = values.to_vec();
+    } else if variable.eq_ignore_ascii_case(TARGET_PARTITIONS) {
+        config.target_partitions = parse_target_partitions(values)?;
+    } else if variable.eq_ignore_ascii_case(DEFAULT_NULL_ORDERING) {
+        config.default_null_ordering = match values {
+            [value] if value.eq_ignore_ascii_case("default") => NullOrdering::default(),
+            [value] => value.parse()?,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} takes exactly one value",
+                    DEFAULT_NULL_ORDERING
+                )))
+            }
+        };
+    } else if variable.eq_ignore_ascii_case(NULL_GROUP_KEYS) {
+        config.null_group_keys = match values {
+            [value] if value.eq_ignore_ascii_case("default") => NullGroupKeys::default(),
+            [value] => value.parse()?,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} takes exactly one value",
+                    NULL_GROUP_KEYS
+                )))
+            }
+        };
+    } else if variable.eq_ignore_ascii_case(TIME_ZONE) {
+        config.time_zone = match values {
+            [value] if value.eq_ignore_ascii_case("default") => FixedOffset::east(0),
+            [value] => parse_time_zone(value)?,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} takes exactly one value",
+                    TIME_ZONE
+                )))
+            }
+        };
+    } else if variable.eq_ignore_ascii_case(FIRST_DAY_OF_WEEK) {
+        config.first_day_of_week = match values {
+            [value] if value.eq_ignore_ascii_case("default") => Weekday::Mon,
+            [value] => value.parse().map_err(|_| {
+                DataFusionError::Plan(format!(
+                    "Invalid value '{}' for {}, expected a day of the week",
+                    value, FIRST_DAY_OF_WEEK
+                ))
+            })?,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} takes exactly one value",
+                    FIRST_DAY_OF_WEEK
+                )))
+            }
+        };
+    } else {
+        return Err(DataFusionError::NotImplemented(format!(
+            "SET {} not implemented. Supported syntax: SET search_path = <schema>[, <schema>]*, \
+             SET target_partitions = <n> | DEFAULT, \
+             SET default_null_ordering = <ordering> | DEFAULT, \
+             SET null_group_keys = group | drop | DEFAULT, \
+             SET time_zone = <offset> | DEFAULT, \
+             SET first_day_of_week = <day> | DEFAULT",
+            variable
+        )));
+    }
+    Ok(())
+}
+
+/// The default number of partitions for query execution: the parallelism
+/// available to the process, which accounts for the CPU affinity and the
+/// cgroup quotas of the process where supported
+pub fn default_target_partitions() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or_else(|_| num_cpus::get())
+}
+
 /// Session Configuration entry name for 'BATCH_SIZE'
 pub const BATCH_SIZE: &str = "batch_size";
 /// Session Configuration entry name for 'TARGET_PARTITIONS'
@@ -919,6 +1476,32 @@ pub const REPARTITION_AGGREGATIONS: &str = "repartition_aggregations";
 pub const REPARTITION_WINDOWS: &str = "repartition_windows";
 /// Session Configuration entry name for 'PARQUET_PRUNING'
 pub const PARQUET_PRUNING: &str = "parquet_pruning";
+/// Session Configuration entry name for 'TIME_ZONE'
+pub const TIME_ZONE: &str = "time_zone";
+/// Session Configuration entry name for 'FIRST_DAY_OF_WEEK'
+pub const FIRST_DAY_OF_WEEK: &str = "first_day_of_week";
+/// Session Configuration entry name for 'AGGREGATE_LOAD_FACTOR'
+pub const AGGREGATE_LOAD_FACTOR: &str = "aggregate_load_factor";
+/// Session Configuration entry name for 'SKIP_PARTIAL_AGGREGATION_PROBE_ROWS'
+pub const SKIP_PARTIAL_AGGREGATION_PROBE_ROWS: &str =
+    "skip_partial_aggregation_probe_rows";
+/// Session Configuration entry name for 'SKIP_PARTIAL_AGGREGATION_RATIO'
+pub const SKIP_PARTIAL_AGGREGATION_RATIO: &str = "skip_partial_aggregation_ratio";
+/// Session Configuration entry name for 'ADAPTIVE_JOIN_SAMPLE_BATCHES'
+pub const ADAPTIVE_JOIN_SAMPLE_BATCHES: &str = "adaptive_join_sample_batches";
+/// Session Configuration entry name for 'ADAPTIVE_JOIN_SAMPLE_BYTES'
+pub const ADAPTIVE_JOIN_SAMPLE_BYTES: &str = "adaptive_join_sample_bytes";
+/// Session Configuration entry name for 'DETERMINISTIC_OUTPUT'
+pub const DETERMINISTIC_OUTPUT: &str = "deterministic_output";
+/// Session Configuration entry name for 'DEFAULT_NULL_ORDERING'
+pub const DEFAULT_NULL_ORDERING: &str = "default_null_ordering";
+/// Session Configuration entry name for 'NULL_GROUP_KEYS'
+pub const NULL_GROUP_KEYS: &str = "null_group_keys";
+/// Session Configuration entry name for 'HASH_SEEDS'
+pub const HASH_SEEDS: &str = "hash_seeds";
+
+/// The maximum load factor of the hash tables of hashbrown
+const MAX_AGGREGATE_LOAD_FACTOR: f64 = 0.875;
 
 /// Configuration options for session context
 #[derive(Clone)]
@@ -928,11 +1511,17 @@ pub struct SessionConfig {
     /// in too much metadata memory consumption.
     pub batch_size: usize,
     /// Number of partitions for query execution. Increasing partitions can increase concurrency.
+    /// Defaults to the available parallelism, and can be changed per session
+    /// with `SET target_partitions = <n>`
     pub target_partitions: usize,
     /// Default catalog name for table resolution
     default_catalog: String,
     /// Default schema name for table resolution
     default_schema: String,
+    /// Schemas searched, after the default schema, for unqualified table and
+    /// function names. Entries are either `schema` (in the default catalog)
+    /// or `catalog.schema`
+    search_path: Vec<String>,
     /// Whether the default catalog and schema should be created automatically
     create_default_catalog_and_schema: bool,
     /// Should DataFusion provide access to `information_schema`
@@ -949,21 +1538,95 @@ pub struct SessionConfig {
     pub repartition_windows: bool,
     /// Should DataFusion parquet reader using the predicate to prune data
     pub parquet_pruning: bool,
+    /// Should SQL `UNION` match the columns of its inputs by name rather than by
+    /// position, filling the columns missing from an input with nulls
+    pub union_by_name: bool,
+    /// Should the SQL `SELECT` expressions with the same name as a previous
+    /// expression be aliased `<name>_<n>` rather than fail the query
+    pub alias_duplicate_names: bool,
+    /// Should the indexes of the elements of the lists in SQL `list[index]`
+    /// start at 1 rather than 0
+    pub one_based_list_index: bool,
+    /// Ordering of the nulls of the SQL `ORDER BY` expressions that don't
+    /// specify `NULLS FIRST` or `NULLS LAST`, which can be changed with
+    /// `SET default_null_ordering = <ordering>`
+    pub default_null_ordering: NullOrdering,
+    /// Should the physical optimizer fuse the projections with the filters
+    /// below them, see [`FuseFilterProjection`]
+    pub fuse_filter_projection: bool,
+    /// Should the imported `ArrowArrayStream`s normalize the arrays of the
+    /// layouts this version of Arrow can not import, e.g. the string views
+    /// and the run end encoded arrays, see [`normalize_stream`]
+    ///
+    /// [`normalize_stream`]: crate::datasource::ffi_compat::normalize_stream
+    pub normalize_foreign_arrays: bool,
+    /// Whether the grouped aggregations group the rows with a null group key
+    /// together or drop them, which can be changed with
+    /// `SET null_group_keys = group | drop`
+    pub null_group_keys: NullGroupKeys,
+    /// Time zone in which temporal functions interpret timestamps and strings
+    /// without an explicit offset
+    time_zone: FixedOffset,
+    /// First day of the week for `date_trunc('week', ..)` and `extract(dow ..)`
+    first_day_of_week: Weekday,
+    /// Cost model used by the physical optimizer rules to compare plans
+    cost_model: Arc<dyn CostModel>,
+    /// Limits of the object store requests of each query
+    object_store_limits: Option<ObjectStoreRequestLimits>,
+    /// Maximum number of rows of the in-memory plans executed synchronously
+    push_execution_max_rows: Option<usize>,
+    /// Maximum ratio of the number of groups to the number of buckets of the
+    /// hash tables of the grouped aggregations
+    aggregate_load_factor: f64,
+    /// Number of input rows after which the partial aggregations whose
+    /// ratio of groups to input rows reaches the given ratio stop
+    /// aggregating and pass their input through
+    skip_partial_aggregation: Option<(usize, f64)>,
+    /// Maximum number of batches and bytes of each input of the partitioned
+    /// hash joins read to choose their build side at execution time
+    adaptive_join: Option<(usize, usize)>,
+    /// Whether the operators merging partitions output the rows of their
+    /// input partitions in the order of the partitions
+    deterministic_output: bool,
+    /// The principal the session acts for, selecting the security policies
+    /// applied to the tables
+    principal: Option<String>,
+    /// Seeds of the hashes of the hash repartitioning and the hash joins
+    hash_seeds: [u64; 4],
 }
 
 impl Default for SessionConfig {
     fn default() -> Self {
         Self {
             batch_size: 8192,
-            target_partitions: num_cpus::get(),
+            target_partitions: default_target_partitions(),
             default_catalog: DEFAULT_CATALOG.to_owned(),
             default_schema: DEFAULT_SCHEMA.to_owned(),
+            search_path: vec![],
             create_default_catalog_and_schema: true,
             information_schema: false,
             repartition_joins: true,
             repartition_aggregations: true,
             repartition_windows: true,
             parquet_pruning: true,
+            union_by_name: false,
+            alias_duplicate_names: false,
+            one_based_list_index: false,
+            default_null_ordering: NullOrdering::default(),
+            null_group_keys: NullGroupKeys::default(),
+            fuse_filter_projection: false,
+            normalize_foreign_arrays: true,
+            time_zone: FixedOffset::east(0),
+            first_day_of_week: Weekday::Mon,
+            cost_model: Arc::new(StatisticsCostModel::new()),
+            object_store_limits: None,
+            push_execution_max_rows: None,
+            aggregate_load_factor: MAX_AGGREGATE_LOAD_FACTOR,
+            skip_partial_aggregation: None,
+            adaptive_join: None,
+            deterministic_output: false,
+            principal: None,
+            hash_seeds: [0; 4],
         }
     }
 }
@@ -1001,6 +1664,19 @@ impl SessionConfig {
         self
     }
 
+    /// Selects the schemas searched, in order and after the default schema,
+    /// for unqualified table and function names. Each entry is either
+    /// `schema` (in the default catalog) or `catalog.schema`
+    pub fn with_search_path(mut self, search_path: Vec<String>) -> Self {
+        self.search_path = search_path;
+        self
+    }
+
+    /// The schemas searched for unqualified table and function names
+    pub fn search_path(&self) -> &[String] {
+        &self.search_path
+    }
+
     /// Controls whether the default catalog and schema will be automatically created
     pub fn create_default_catalog_and_schema(mut self, create: bool) -> Self {
         self.create_default_catalog_and_schema = create;
@@ -1037,6 +1713,246 @@ impl SessionConfig {
         self
     }
 
+    /// Enables or disables fusing the projections with the filters below them
+    pub fn with_fuse_filter_projection(mut self, enabled: bool) -> Self {
+        self.fuse_filter_projection = enabled;
+        self
+    }
+
+    /// Enables or disables normalizing the arrays of the layouts this version
+    /// of Arrow can not import, in the imported `ArrowArrayStream`s
+    pub fn with_normalize_foreign_arrays(mut self, enabled: bool) -> Self {
+        self.normalize_foreign_arrays = enabled;
+        self
+    }
+
+    /// Enables or disables matching the columns of the inputs of SQL `UNION` by name
+    pub fn with_union_by_name(mut self, enabled: bool) -> Self {
+        self.union_by_name = enabled;
+        self
+    }
+
+    /// Enables or disables aliasing the SQL `SELECT` expressions with duplicate names
+    pub fn with_alias_duplicate_names(mut self, enabled: bool) -> Self {
+        self.alias_duplicate_names = enabled;
+        self
+    }
+
+    /// Enables or disables starting the indexes of the elements of the lists in
+    /// SQL `list[index]` at 1
+    pub fn with_one_based_list_index(mut self, enabled: bool) -> Self {
+        self.one_based_list_index = enabled;
+        self
+    }
+
+    /// Selects the ordering of the nulls of the SQL `ORDER BY` expressions
+    /// that don't specify it - defaults to [`NullOrdering::NullsMax`]
+    pub fn with_default_null_ordering(mut self, null_ordering: NullOrdering) -> Self {
+        self.default_null_ordering = null_ordering;
+        self
+    }
+
+    /// Selects whether the grouped aggregations group the rows with a null
+    /// group key together or drop them - defaults to [`NullGroupKeys::Group`]
+    pub fn with_null_group_keys(mut self, null_group_keys: NullGroupKeys) -> Self {
+        self.null_group_keys = null_group_keys;
+        self
+    }
+
+    /// Selects the time zone in which temporal functions interpret
+    /// timestamps and strings without an explicit offset, a fixed offset
+    /// from UTC without daylight saving time - defaults to UTC
+    pub fn with_time_zone(mut self, time_zone: FixedOffset) -> Self {
+        self.time_zone = time_zone;
+        self
+    }
+
+    /// Selects the first day of the week - defaults to Monday
+    pub fn with_first_day_of_week(mut self, first_day_of_week: Weekday) -> Self {
+        self.first_day_of_week = first_day_of_week;
+        self
+    }
+
+    /// The time zone of the session
+    pub fn time_zone(&self) -> FixedOffset {
+        self.time_zone
+    }
+
+    /// The first day of the week of the session
+    pub fn first_day_of_week(&self) -> Weekday {
+        self.first_day_of_week
+    }
+
+    /// Selects the cost model used by the physical optimizer rules to compare
+    /// plans - defaults to [`StatisticsCostModel`]
+    pub fn with_cost_model(mut self, cost_model: Arc<dyn CostModel>) -> Self {
+        self.cost_model = cost_model;
+        self
+    }
+
+    /// The cost model used by the physical optimizer rules
+    pub fn cost_model(&self) -> &Arc<dyn CostModel> {
+        &self.cost_model
+    }
+
+    /// Limits the object store requests of each query, e.g. to avoid being
+    /// throttled by remote stores. The requests of the scans of a query are
+    /// also subject to the global limits of the [`RuntimeEnv`]
+    pub fn with_object_store_limits(mut self, limits: ObjectStoreRequestLimits) -> Self {
+        self.object_store_limits = Some(limits);
+        self
+    }
+
+    /// The limits of the object store requests of each query
+    pub fn object_store_limits(&self) -> Option<&ObjectStoreRequestLimits> {
+        self.object_store_limits.as_ref()
+    }
+
+    /// Executes the plans reading at most `max_rows` rows from memory, and
+    /// only using the operators supported by [push execution], synchronously
+    /// when they are collected - disabled by default
+    ///
+    /// [push execution]: crate::physical_plan::push
+    pub fn with_push_execution_max_rows(mut self, max_rows: usize) -> Self {
+        self.push_execution_max_rows = Some(max_rows);
+        self
+    }
+
+    /// The maximum number of rows of the plans executed synchronously, if
+    /// push execution is enabled
+    pub fn push_execution_max_rows(&self) -> Option<usize> {
+        self.push_execution_max_rows
+    }
+
+    /// Selects the maximum load factor of the hash tables of the grouped
+    /// aggregations, i.e. the ratio of the number of groups to the number of
+    /// buckets, clamped to `(0, 0.875]`. Lower values trade memory for fewer
+    /// collisions when probing the tables. Defaults to 0.875, the maximum load
+    /// factor of the underlying hash table
+    pub fn with_aggregate_load_factor(mut self, load_factor: f64) -> Self {
+        // load factor must be positive and cannot exceed the one of hashbrown
+        self.aggregate_load_factor = load_factor
+            .max(f64::MIN_POSITIVE)
+            .min(MAX_AGGREGATE_LOAD_FACTOR);
+        self
+    }
+
+    /// The maximum load factor of the hash tables of the grouped aggregations
+    pub fn aggregate_load_factor(&self) -> f64 {
+        self.aggregate_load_factor
+    }
+
+    /// Lets the partial grouped aggregations switch to pass-through when they
+    /// barely reduce their input: once `probe_rows` input rows have been
+    /// aggregated, if the number of groups is at least `ratio` times the
+    /// number of input rows, the groups are emitted and every following input
+    /// row is converted to a group of its own, leaving the aggregation to the
+    /// final stage. The ratio is clamped to `(0, 1]` - disabled by default
+    pub fn with_skip_partial_aggregation(
+        mut self,
+        probe_rows: usize,
+        ratio: f64,
+    ) -> Self {
+        // the ratio of groups to input rows is positive and at most 1
+        let ratio = ratio.max(f64::MIN_POSITIVE).min(1.0);
+        self.skip_partial_aggregation = Some((probe_rows, ratio));
+        self
+    }
+
+    /// The number of probed input rows and the minimum ratio of groups to
+    /// input rows of the partial aggregations to pass their input through,
+    /// if enabled
+    pub fn skip_partial_aggregation(&self) -> Option<(usize, f64)> {
+        self.skip_partial_aggregation
+    }
+
+    /// Lets the partitioned hash joins choose their build side at execution
+    /// time, for when the statistics of their inputs are missing or wrong:
+    /// the first batches of both inputs of each partition are read, up to
+    /// `sample_batches` batches and `sample_bytes` bytes per input, and the
+    /// right input becomes the build side if it ends within its sample while
+    /// the left input does not, or if both do and it is smaller. The sampled
+    /// batches are registered with the memory manager and kept in memory
+    /// until the join consumes them, the sampling stopping early when the
+    /// memory pool is full. At least one batch is sampled. Semi and anti
+    /// joins keep their build side - disabled by default
+    pub fn with_adaptive_join(
+        mut self,
+        sample_batches: usize,
+        sample_bytes: usize,
+    ) -> Self {
+        // at least one batch must be sampled to compare the inputs
+        self.adaptive_join = Some((sample_batches.max(1), sample_bytes));
+        self
+    }
+
+    /// The maximum number of batches and bytes of each input sampled by the
+    /// partitioned hash joins to choose their build side, if enabled
+    pub fn adaptive_join(&self) -> Option<(usize, usize)> {
+        self.adaptive_join
+    }
+
+    /// Makes the output of the queries reproducible, e.g. for golden file
+    /// tests: the operators merging partitions, such as
+    /// [`CoalescePartitionsExec`] and [`RepartitionExec`], output all the rows
+    /// of an input partition before the ones of the next partition rather
+    /// than in the order they are produced, which delays or buffers the rows
+    /// of the partitions produced ahead of their turn - disabled by default. The
+    /// files of the listing tables are always read in the order of their
+    /// paths.
+    ///
+    /// [`CoalescePartitionsExec`]: crate::physical_plan::coalesce_partitions::CoalescePartitionsExec
+    /// [`RepartitionExec`]: crate::physical_plan::repartition::RepartitionExec
+    pub fn with_deterministic_output(mut self, enabled: bool) -> Self {
+        self.deterministic_output = enabled;
+        self
+    }
+
+    /// Whether the output of the queries is reproducible
+    pub fn deterministic_output(&self) -> bool {
+        self.deterministic_output
+    }
+
+    /// Selects the principal, e.g. user or tenant, the session acts for,
+    /// whose [`SecurityPolicy`]s are applied to the tables
+    pub fn with_principal(mut self, principal: impl Into<String>) -> Self {
+        self.principal = Some(principal.into());
+        self
+    }
+
+    /// The principal the session acts for, if any
+    pub fn principal(&self) -> Option<&str> {
+        self.principal.as_deref()
+    }
+
+    /// Customize the seeds of the hashes of the hash repartitioning and the
+    /// hash joins. The processes of a distributed query must use the same
+    /// seeds, and the same build of DataFusion, to assign the rows to the
+    /// same partitions.
+    pub fn with_hash_seeds(mut self, seeds: [u64; 4]) -> Self {
+        self.hash_seeds = seeds;
+        self
+    }
+
+    /// The seeds of the hashes of the hash repartitioning and the hash joins
+    pub fn hash_seeds(&self) -> [u64; 4] {
+        self.hash_seeds
+    }
+
+    /// The state of the hashes of the hash repartitioning and the hash joins
+    pub fn hash_random_state(&self) -> RandomState {
+        let [k0, k1, k2, k3] = self.hash_seeds;
+        RandomState::with_seeds(k0, k1, k2, k3)
+    }
+
+    /// The settings of the temporal functions
+    pub fn temporal_settings(&self) -> TemporalSettings {
+        TemporalSettings {
+            time_zone: self.time_zone,
+            first_day_of_week: self.first_day_of_week,
+        }
+    }
+
     /// Convert configuration to name-value pairs
     pub fn to_props(&self) -> HashMap<String, String> {
         let mut map = HashMap::new();
@@ -1061,6 +1977,51 @@ impl SessionConfig {
             PARQUET_PRUNING.to_owned(),
             format!("{}", self.parquet_pruning),
         );
+        map.insert(TIME_ZONE.to_owned(), format!("{}", self.time_zone));
+        map.insert(
+            FIRST_DAY_OF_WEEK.to_owned(),
+            format!("{:?}", self.first_day_of_week),
+        );
+        map.insert(
+            AGGREGATE_LOAD_FACTOR.to_owned(),
+            format!("{}", self.aggregate_load_factor),
+        );
+        map.insert(
+            DETERMINISTIC_OUTPUT.to_owned(),
+            format!("{}", self.deterministic_output),
+        );
+        map.insert(
+            NULL_GROUP_KEYS.to_owned(),
+            format!("{:?}", self.null_group_keys).to_lowercase(),
+        );
+        map.insert(
+            HASH_SEEDS.to_owned(),
+            self.hash_seeds
+                .iter()
+                .map(|seed| seed.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        if let Some((probe_rows, ratio)) = self.skip_partial_aggregation {
+            map.insert(
+                SKIP_PARTIAL_AGGREGATION_PROBE_ROWS.to_owned(),
+                format!("{}", probe_rows),
+            );
+            map.insert(
+                SKIP_PARTIAL_AGGREGATION_RATIO.to_owned(),
+                format!("{}", ratio),
+            );
+        }
+        if let Some((sample_batches, sample_bytes)) = self.adaptive_join {
+            map.insert(
+                ADAPTIVE_JOIN_SAMPLE_BATCHES.to_owned(),
+                format!("{}", sample_batches),
+            );
+            map.insert(
+                ADAPTIVE_JOIN_SAMPLE_BYTES.to_owned(),
+                format!("{}", sample_bytes),
+            );
+        }
         map
     }
 }
@@ -1077,6 +2038,10 @@ pub struct ExecutionProps {
     pub(crate) query_execution_start_time: DateTime<Utc>,
     /// providers for scalar variables
     pub var_providers: Option<HashMap<VarType, Arc<dyn VarProvider + Send + Sync>>>,
+    /// session time zone and first day of the week used by temporal functions
+    pub temporal_settings: TemporalSettings,
+    /// kernels overriding the built-in functions and operators
+    pub kernels: Arc<KernelRegistry>,
 }
 
 impl Default for ExecutionProps {
@@ -1091,9 +2056,17 @@ impl ExecutionProps {
         ExecutionProps {
             query_execution_start_time: chrono::Utc::now(),
             var_providers: None,
+            temporal_settings: TemporalSettings::default(),
+            kernels: Arc::new(KernelRegistry::new()),
         }
     }
 
+    /// Sets the settings of the temporal functions
+    pub fn with_temporal_settings(mut self, temporal_settings: TemporalSettings) -> Self {
+        self.temporal_settings = temporal_settings;
+        self
+    }
+
     /// Marks the execution of query started timestamp
     pub fn start_execution(&mut self) -> &Self {
         self.query_execution_start_time = chrono::Utc::now();
@@ -1142,6 +2115,9 @@ pub struct SessionState {
     pub catalog_list: Arc<dyn CatalogList>,
     /// Scalar functions that are registered with the context
     pub scalar_functions: HashMap<String, Arc<ScalarUDF>>,
+    /// Async scalar functions that are registered with the context, also
+    /// registered as scalar functions
+    pub async_scalar_functions: HashMap<String, Arc<AsyncScalarUDF>>,
     /// Aggregate functions registered in the context
     pub aggregate_functions: HashMap<String, Arc<AggregateUDF>>,
     /// Session configuration
@@ -1150,6 +2126,17 @@ pub struct SessionState {
     pub execution_props: ExecutionProps,
     /// Runtime environment
     pub runtime_env: Arc<RuntimeEnv>,
+    /// Executes the transaction control statements, e.g. `BEGIN`
+    pub transaction_handler: Arc<dyn TransactionHandler>,
+    /// Plan the statements of custom kinds, tried in order
+    pub statement_planners: Vec<Arc<dyn StatementPlanner>>,
+    /// Checks the access to the tables and columns of the session
+    pub authorizer: Arc<dyn Authorizer>,
+    /// The security policies of the tables, by their resolved
+    /// `(catalog, schema, table)` name
+    pub security_policies: HashMap<(String, String, String), Vec<SecurityPolicy>>,
+    /// The baselines the optimized plans are compared with, if any
+    pub plan_baselines: Option<Arc<PlanBaselines>>,
 }
 
 impl Debug for SessionState {
@@ -1203,9 +2190,14 @@ impl SessionState {
                 // Simplify expressions first to maximize the chance
                 // of applying other optimizations
                 Arc::new(SimplifyExpressions::new()),
+                Arc::new(MergeFilters::new()),
+                Arc::new(ReuseFilterPredicates::new()),
                 Arc::new(EliminateFilter::new()),
                 Arc::new(CommonSubexprEliminate::new()),
                 Arc::new(EliminateLimit::new()),
+                Arc::new(EliminateSort::new()),
+                Arc::new(EliminateAggregate::new()),
+                Arc::new(UnionDistinct::new()),
                 Arc::new(ProjectionPushDown::new()),
                 Arc::new(FilterPushDown::new()),
                 Arc::new(LimitPushDown::new()),
@@ -1216,8 +2208,11 @@ impl SessionState {
                 Arc::new(ToApproxPerc::new()),
             ],
             physical_optimizers: vec![
+                Arc::new(EmptyResult::new()),
                 Arc::new(AggregateStatistics::new()),
                 Arc::new(HashBuildProbeOrder::new()),
+                Arc::new(JoinBuildProjection::new()),
+                Arc::new(FuseFilterProjection::new()),
                 Arc::new(CoalesceBatches::new()),
                 Arc::new(Repartition::new()),
                 Arc::new(AddCoalescePartitionsExec::new()),
@@ -1225,10 +2220,17 @@ impl SessionState {
             query_planner: Arc::new(DefaultQueryPlanner {}),
             catalog_list,
             scalar_functions: HashMap::new(),
+            async_scalar_functions: HashMap::new(),
             aggregate_functions: HashMap::new(),
+            execution_props: ExecutionProps::new()
+                .with_temporal_settings(config.temporal_settings()),
             config,
-            execution_props: ExecutionProps::new(),
             runtime_env: runtime,
+            transaction_handler: Arc::new(NoOpTransactionHandler),
+            statement_planners: vec![],
+            authorizer: Arc::new(AllowAllAuthorizer),
+            security_policies: HashMap::new(),
+            plan_baselines: None,
         }
     }
 
@@ -1241,6 +2243,177 @@ impl SessionState {
             .resolve(&self.config.default_catalog, &self.config.default_schema)
     }
 
+    /// The `(catalog, schema)` pairs of the session search path
+    fn search_path_schemas(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.config.search_path.iter().map(|entry| {
+            entry
+                .split_once('.')
+                .unwrap_or((self.config.default_catalog.as_str(), entry.as_str()))
+        })
+    }
+
+    /// Finds the provider of a table. Unqualified table names that are not
+    /// found in the default schema are looked up in the schemas of the
+    /// search path
+    fn find_table_provider(
+        &self,
+        table_ref: TableReference,
+    ) -> Option<Arc<dyn TableProvider>> {
+        self.find_table(table_ref).map(|(_, provider)| provider)
+    }
+
+    /// The security policies of the table `name` applying to the principal
+    /// of the session, in the order of their registration
+    fn applicable_security_policies(&self, name: TableReference) -> Vec<&SecurityPolicy> {
+        if self.security_policies.is_empty() {
+            return vec![];
+        }
+        let table = match self.find_table(name) {
+            Some((table, _)) => table,
+            None => self.resolve_table_ref(name),
+        };
+        let key = (
+            table.catalog.to_string(),
+            table.schema.to_string(),
+            table.table.to_string(),
+        );
+        let principal = self.config.principal();
+        self.security_policies
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .filter(|policy| policy.applies_to(principal))
+            .collect()
+    }
+
+    /// Finds the provider of a table like [`Self::find_table_provider`],
+    /// along with the name of the table it was found by
+    fn find_table<'a>(
+        &'a self,
+        table_ref: TableReference<'a>,
+    ) -> Option<(ResolvedTableReference<'a>, Arc<dyn TableProvider>)> {
+        let resolved_ref = self.resolve_table_ref(table_ref);
+        let provider = self
+            .schema_for_ref(resolved_ref)
+            .ok()
+            .and_then(|schema| schema.table(resolved_ref.table));
+        match (provider, table_ref) {
+            (Some(provider), _) => Some((resolved_ref, provider)),
+            (None, TableReference::Bare { table }) => {
+                self.search_path_schemas().find_map(|(catalog, schema)| {
+                    let provider = self
+                        .catalog_list
+                        .catalog(catalog)?
+                        .schema(schema)?
+                        .table(table)?;
+                    let resolved_ref = ResolvedTableReference {
+                        catalog,
+                        schema,
+                        table,
+                    };
+                    Some((resolved_ref, provider))
+                })
+            }
+            (None, _) => None,
+        }
+    }
+
+    /// Checks with the authorizer the columns read by the table scans of
+    /// `plan`, including the columns of the filters pushed down to the scans
+    fn authorize_scans(&self, plan: &LogicalPlan) -> Result<()> {
+        struct ScanAuthorizer<'a> {
+            state: &'a SessionState,
+        }
+
+        impl PlanVisitor for ScanAuthorizer<'_> {
+            type Error = DataFusionError;
+
+            fn pre_visit(&mut self, plan: &LogicalPlan) -> Result<bool> {
+                if let LogicalPlan::TableScan(TableScan {
+                    table_name,
+                    source,
+                    projected_schema,
+                    filters,
+                    ..
+                }) = plan
+                {
+                    let mut filter_columns = HashSet::new();
+                    for filter in filters {
+                        utils::expr_to_columns(filter, &mut filter_columns)?;
+                    }
+                    let mut filter_columns = filter_columns
+                        .iter()
+                        .map(|column| column.name.as_str())
+                        .collect::<Vec<_>>();
+                    filter_columns.sort_unstable();
+                    let mut columns = projected_schema
+                        .fields()
+                        .iter()
+                        .map(|field| field.name().as_str())
+                        .collect::<Vec<_>>();
+                    for column in filter_columns {
+                        if !columns.contains(&column) {
+                            columns.push(column);
+                        }
+                    }
+
+                    // the metadata of the files is authorized by their path
+                    let metadata_path =
+                        source_as_provider(source).ok().and_then(|table| {
+                            let table =
+                                table.as_any().downcast_ref::<ParquetMetadataTable>()?;
+                            Some(table.path().to_owned())
+                        });
+
+                    let table_ref = TableReference::from(table_name.as_str());
+                    match (self.state.find_table(table_ref), metadata_path) {
+                        (Some((table, _)), _) => {
+                            self.state.authorizer.authorize_columns(&table, &columns)?
+                        }
+                        (None, Some(path)) => {
+                            self.state.authorizer.authorize_file_metadata(&path)?
+                        }
+                        (None, None) => self
+                            .state
+                            .authorizer
+                            .authorize_unregistered_columns(table_name, &columns)?,
+                    }
+                }
+                Ok(true)
+            }
+        }
+
+        plan.accept(&mut ScanAuthorizer { state: self })?;
+        Ok(())
+    }
+
+    /// Finds a registered function, looking up unqualified names qualified
+    /// with each schema of the search path when not registered as is. The
+    /// functions of the schemas of the default catalog may be registered with
+    /// or without the catalog, e.g. `schema.name` or `catalog.schema.name`.
+    fn find_function<T: Clone>(
+        &self,
+        functions: &HashMap<String, T>,
+        name: &str,
+    ) -> Option<T> {
+        functions
+            .get(name)
+            .or_else(|| {
+                self.search_path_schemas().find_map(|(catalog, schema)| {
+                    functions
+                        .get(&format!("{}.{}.{}", catalog, schema, name))
+                        .or_else(|| {
+                            if catalog == self.config.default_catalog {
+                                functions.get(&format!("{}.{}", schema, name))
+                            } else {
+                                None
+                            }
+                        })
+                })
+            })
+            .cloned()
+    }
+
     fn schema_for_ref<'a>(
         &'a self,
         table_ref: impl Into<TableReference<'a>>,
@@ -1273,6 +2446,54 @@ impl SessionState {
         self
     }
 
+    /// Resolves the tables in a snapshot of the current catalogs until
+    /// [`Self::release_catalog_snapshot`] is called, so that the statements
+    /// planned meanwhile see the same tables even if they are registered or
+    /// deregistered elsewhere, e.g. by another session sharing the catalogs.
+    /// The tables registered or deregistered through the session are still
+    /// seen, see [`CatalogSnapshot`].
+    ///
+    /// The previous snapshot of the session, if any, is replaced. Returns
+    /// the snapshot captured.
+    pub fn capture_catalog_snapshot(&mut self) -> Arc<dyn CatalogList> {
+        let snapshot: Arc<dyn CatalogList> = Arc::new(CatalogSnapshot::capture(
+            self.current_catalog_list().clone(),
+        ));
+        self.catalog_list = snapshot.clone();
+        snapshot
+    }
+
+    /// Whether the tables are resolved in a snapshot of the catalogs, see
+    /// [`Self::capture_catalog_snapshot`]
+    pub fn has_catalog_snapshot(&self) -> bool {
+        self.catalog_list.as_any().is::<CatalogSnapshot>()
+    }
+
+    /// Resolves the tables in the current catalogs again, after
+    /// [`Self::capture_catalog_snapshot`]
+    pub fn release_catalog_snapshot(&mut self) {
+        self.catalog_list = self.current_catalog_list().clone();
+    }
+
+    /// The catalog list holding the current catalogs, the source of the
+    /// catalog snapshot if any
+    fn current_catalog_list(&self) -> &Arc<dyn CatalogList> {
+        match self.catalog_list.as_any().downcast_ref::<CatalogSnapshot>() {
+            Some(snapshot) => snapshot.source(),
+            None => &self.catalog_list,
+        }
+    }
+
+    /// Replace the handler of the transaction control statements, which
+    /// accepts them without doing anything by default
+    pub fn with_transaction_handler(
+        mut self,
+        transaction_handler: Arc<dyn TransactionHandler>,
+    ) -> Self {
+        self.transaction_handler = transaction_handler;
+        self
+    }
+
     /// Replace the optimizer rules
     pub fn with_optimizer_rules(
         mut self,
@@ -1282,6 +2503,30 @@ impl SessionState {
         self
     }
 
+    /// Replace the authorizer checking the access to the tables and columns,
+    /// which allows everything by default
+    pub fn with_authorizer(mut self, authorizer: Arc<dyn Authorizer>) -> Self {
+        self.authorizer = authorizer;
+        self
+    }
+
+    /// Compare the optimized plans of the queries with the baselines of
+    /// their fingerprints, see [`PlanBaselines`]
+    pub fn with_plan_baselines(mut self, plan_baselines: Arc<PlanBaselines>) -> Self {
+        self.plan_baselines = Some(plan_baselines);
+        self
+    }
+
+    /// Adds a new [`StatementPlanner`], planning the statements of custom
+    /// kinds it supports
+    pub fn add_statement_planner(
+        mut self,
+        statement_planner: Arc<dyn StatementPlanner>,
+    ) -> Self {
+        self.statement_planners.push(statement_planner);
+        self
+    }
+
     /// Replace the physical optimizer rules
     pub fn with_physical_optimizer_rules(
         mut self,
@@ -1329,7 +2574,14 @@ impl SessionState {
                 schema: e.schema.clone(),
             }))
         } else {
-            self.optimize_internal(plan, |_, _| {})
+            let optimized = self.optimize_internal(plan, |_, _| {})?;
+            if let Some(plan_baselines) = &self.plan_baselines {
+                // the baselines never fail the planning of the query
+                if let Err(e) = plan_baselines.check(plan, &optimized) {
+                    warn!("Could not compare the plan with its baseline: {}", e);
+                }
+            }
+            Ok(optimized)
         }
     }
 
@@ -1365,6 +2617,7 @@ impl SessionState {
         &self,
         logical_plan: &LogicalPlan,
     ) -> Result<Arc<dyn ExecutionPlan>> {
+        self.authorize_scans(logical_plan)?;
         let planner = self.query_planner.clone();
         planner.create_physical_plan(logical_plan, self).await
     }
@@ -1372,17 +2625,36 @@ impl SessionState {
 
 impl ContextProvider for SessionState {
     fn get_table_provider(&self, name: TableReference) -> Option<Arc<dyn TableProvider>> {
-        let resolved_ref = self.resolve_table_ref(name);
-        let schema = self.schema_for_ref(resolved_ref).ok()?;
-        schema.table(resolved_ref.table)
+        self.find_table_provider(name)
+    }
+
+    fn authorize_table(&self, name: TableReference) -> Result<()> {
+        match self.find_table(name) {
+            Some((table, _)) => self.authorizer.authorize_table(&table),
+            None => Ok(()),
+        }
+    }
+
+    fn authorize_file_metadata(&self, path: &str) -> Result<()> {
+        self.authorizer.authorize_file_metadata(path)
+    }
+
+    fn apply_security_policies(
+        &self,
+        name: TableReference,
+        scan: LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        self.applicable_security_policies(name)
+            .into_iter()
+            .try_fold(scan, |plan, policy| policy.apply(plan))
     }
 
     fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
-        self.scalar_functions.get(name).cloned()
+        self.find_function(&self.scalar_functions, name)
     }
 
     fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
-        self.aggregate_functions.get(name).cloned()
+        self.find_function(&self.aggregate_functions, name)
     }
 
     fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType> {
@@ -1401,6 +2673,145 @@ impl ContextProvider for SessionState {
             .as_ref()
             .and_then(|provider| provider.get(&provider_type)?.get_type(variable_names))
     }
+
+    fn union_by_name(&self) -> bool {
+        self.config.union_by_name
+    }
+
+    fn alias_duplicate_names(&self) -> bool {
+        self.config.alias_duplicate_names
+    }
+
+    fn one_based_list_index(&self) -> bool {
+        self.config.one_based_list_index
+    }
+
+    fn default_null_ordering(&self) -> NullOrdering {
+        self.config.default_null_ordering
+    }
+
+    fn statement_planners(&self) -> Vec<Arc<dyn StatementPlanner>> {
+        self.statement_planners.clone()
+    }
+}
+
+/// Resolves the tables of the statements of a script validated by
+/// [`SessionContext::validate_sql_multi`]: the tables created or dropped by the
+/// previous statements of the script hide the ones of the session
+struct DryRunContextProvider<'a> {
+    state: &'a SessionState,
+    /// The tables created (or dropped, if None) by the script, by their
+    /// resolved `(catalog, schema, table)` name
+    tables: &'a mut HashMap<(String, String, String), Option<Arc<dyn TableProvider>>>,
+}
+
+impl<'a> DryRunContextProvider<'a> {
+    fn key(&self, table_ref: TableReference) -> (String, String, String) {
+        let resolved_ref = table_ref.resolve(
+            &self.state.config.default_catalog,
+            &self.state.config.default_schema,
+        );
+        (
+            resolved_ref.catalog.to_owned(),
+            resolved_ref.schema.to_owned(),
+            resolved_ref.table.to_owned(),
+        )
+    }
+
+    fn table_exists(&self, name: &str) -> bool {
+        match self.tables.get(&self.key(name.into())) {
+            Some(table) => table.is_some(),
+            None => self.state.get_table_provider(name.into()).is_some(),
+        }
+    }
+
+    /// Checks that the table `name` can be created, returns whether it
+    /// already exists
+    fn check_create(&self, name: &str, if_not_exists: bool) -> Result<bool> {
+        match (self.table_exists(name), if_not_exists) {
+            (true, false) => Err(DataFusionError::Execution(format!(
+                "Table '{:?}' already exists",
+                name
+            ))),
+            (exists, _) => Ok(exists),
+        }
+    }
+
+    fn register(&mut self, name: &str, table: Arc<dyn TableProvider>) {
+        self.tables.insert(self.key(name.into()), Some(table));
+    }
+
+    fn drop(&mut self, name: &str, if_exists: bool) -> Result<()> {
+        if !if_exists && !self.table_exists(name) {
+            return Err(DataFusionError::Execution(format!(
+                "Memory table {:?} doesn't exist.",
+                name
+            )));
+        }
+        self.tables.insert(self.key(name.into()), None);
+        Ok(())
+    }
+}
+
+impl<'a> ContextProvider for DryRunContextProvider<'a> {
+    fn get_table_provider(&self, name: TableReference) -> Option<Arc<dyn TableProvider>> {
+        match self.tables.get(&self.key(name)) {
+            Some(table) => table.clone(),
+            None => self.state.get_table_provider(name),
+        }
+    }
+
+    fn authorize_table(&self, name: TableReference) -> Result<()> {
+        // the tables created by the script are checked once it runs
+        match self.tables.get(&self.key(name)) {
+            Some(_) => Ok(()),
+            None => self.state.authorize_table(name),
+        }
+    }
+
+    fn authorize_file_metadata(&self, path: &str) -> Result<()> {
+        self.state.authorize_file_metadata(path)
+    }
+
+    fn apply_security_policies(
+        &self,
+        name: TableReference,
+        scan: LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        self.state.apply_security_policies(name, scan)
+    }
+
+    fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+        self.state.get_function_meta(name)
+    }
+
+    fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>> {
+        self.state.get_aggregate_meta(name)
+    }
+
+    fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType> {
+        self.state.get_variable_type(variable_names)
+    }
+
+    fn union_by_name(&self) -> bool {
+        self.state.union_by_name()
+    }
+
+    fn alias_duplicate_names(&self) -> bool {
+        self.state.alias_duplicate_names()
+    }
+
+    fn one_based_list_index(&self) -> bool {
+        self.state.one_based_list_index()
+    }
+
+    fn default_null_ordering(&self) -> NullOrdering {
+        self.state.default_null_ordering()
+    }
+
+    fn statement_planners(&self) -> Vec<Arc<dyn StatementPlanner>> {
+        self.state.statement_planners()
+    }
 }
 
 impl FunctionRegistry for SessionState {
@@ -1453,6 +2864,8 @@ pub struct TaskContext {
     aggregate_functions: HashMap<String, Arc<AggregateUDF>>,
     /// Runtime environment associated with this task context
     runtime: Arc<RuntimeEnv>,
+    /// Limiter of the object store requests of the task
+    object_store_limiter: Option<Arc<RequestLimiter>>,
 }
 
 impl TaskContext {
@@ -1472,6 +2885,7 @@ impl TaskContext {
             scalar_functions,
             aggregate_functions,
             runtime,
+            object_store_limiter: None,
         }
     }
 
@@ -1480,7 +2894,70 @@ impl TaskContext {
         let task_props = &self.properties;
         match task_props {
             TaskProperties::KVPairs(props) => {
-                let session_config = SessionConfig::new();
+                let mut session_config = SessionConfig::new();
+                // the temporal settings are optional for compatibility
+                if let Some(time_zone) = props
+                    .get(TIME_ZONE)
+                    .and_then(|time_zone| parse_time_zone(time_zone).ok())
+                {
+                    session_config = session_config.with_time_zone(time_zone);
+                }
+                if let Some(first_day_of_week) = props
+                    .get(FIRST_DAY_OF_WEEK)
+                    .and_then(|first_day_of_week| first_day_of_week.parse().ok())
+                {
+                    session_config =
+                        session_config.with_first_day_of_week(first_day_of_week);
+                }
+                if let Some(load_factor) = props
+                    .get(AGGREGATE_LOAD_FACTOR)
+                    .and_then(|load_factor| load_factor.parse::<f64>().ok())
+                    .filter(|load_factor| {
+                        *load_factor > 0.0 && *load_factor <= MAX_AGGREGATE_LOAD_FACTOR
+                    })
+                {
+                    session_config =
+                        session_config.with_aggregate_load_factor(load_factor);
+                }
+                if let (Some(probe_rows), Some(ratio)) = (
+                    props
+                        .get(SKIP_PARTIAL_AGGREGATION_PROBE_ROWS)
+                        .and_then(|probe_rows| probe_rows.parse().ok()),
+                    props
+                        .get(SKIP_PARTIAL_AGGREGATION_RATIO)
+                        .and_then(|ratio| ratio.parse::<f64>().ok())
+                        .filter(|ratio| *ratio > 0.0 && *ratio <= 1.0),
+                ) {
+                    session_config =
+                        session_config.with_skip_partial_aggregation(probe_rows, ratio);
+                }
+                if let (Some(sample_batches), Some(sample_bytes)) = (
+                    props
+                        .get(ADAPTIVE_JOIN_SAMPLE_BATCHES)
+                        .and_then(|sample_batches| sample_batches.parse().ok())
+                        .filter(|sample_batches| *sample_batches > 0),
+                    props
+                        .get(ADAPTIVE_JOIN_SAMPLE_BYTES)
+                        .and_then(|sample_bytes| sample_bytes.parse().ok()),
+                ) {
+                    session_config =
+                        session_config.with_adaptive_join(sample_batches, sample_bytes);
+                }
+                if let Some(enabled) = props
+                    .get(DETERMINISTIC_OUTPUT)
+                    .and_then(|enabled| enabled.parse().ok())
+                {
+                    session_config = session_config.with_deterministic_output(enabled);
+                }
+                if let Some(seeds) = props.get(HASH_SEEDS).and_then(|seeds| {
+                    let seeds = seeds
+                        .split(',')
+                        .map(|seed| seed.parse().ok())
+                        .collect::<Option<Vec<u64>>>()?;
+                    <[u64; 4]>::try_from(seeds).ok()
+                }) {
+                    session_config = session_config.with_hash_seeds(seeds);
+                }
                 if props.is_empty() {
                     session_config
                 } else {
@@ -1525,6 +3002,27 @@ impl TaskContext {
     pub fn runtime_env(&self) -> Arc<RuntimeEnv> {
         self.runtime.clone()
     }
+
+    /// Wraps `object_store` so that its requests respect the object store
+    /// limits of the session, shared by all the scans of the task
+    pub fn limit_object_store(
+        &self,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> Arc<dyn ObjectStore> {
+        match &self.object_store_limiter {
+            Some(limiter) => {
+                Arc::new(LimitedObjectStore::new(object_store, limiter.clone()))
+            }
+            None => object_store,
+        }
+    }
+}
+
+/// Creates the limiter of the object store requests of a task
+fn object_store_limiter(config: &SessionConfig) -> Option<Arc<RequestLimiter>> {
+    config
+        .object_store_limits()
+        .map(|limits| Arc::new(RequestLimiter::new(limits.clone())))
 }
 
 /// Create a new task context instance from SessionContext
@@ -1543,6 +3041,7 @@ impl From<&SessionContext> for TaskContext {
         Self {
             task_id: None,
             session_id,
+            object_store_limiter: object_store_limiter(&config),
             properties: TaskProperties::SessionConfig(config),
             scalar_functions,
             aggregate_functions,
@@ -1562,6 +3061,7 @@ impl From<&SessionState> for TaskContext {
         Self {
             task_id: None,
             session_id,
+            object_store_limiter: object_store_limiter(&config),
             properties: TaskProperties::SessionConfig(config),
             scalar_functions,
             aggregate_functions,
@@ -1603,7 +3103,7 @@ mod tests {
     use super::*;
     use crate::execution::context::QueryPlanner;
     use crate::from_slice::FromSlice;
-    use crate::logical_plan::{binary_expr, lit, Operator};
+    use crate::logical_plan::{binary_expr, lit, Operator, TransactionStatement};
     use crate::physical_plan::functions::make_scalar_function;
     use crate::test;
     use crate::variable::VarType;
@@ -1616,13 +3116,17 @@ mod tests {
         physical_plan::expressions::AvgAccumulator,
     };
     use arrow::array::{
-        Array, ArrayRef, DictionaryArray, Float32Array, Float64Array, Int16Array,
-        Int32Array, Int64Array, Int8Array, LargeStringArray, UInt16Array, UInt32Array,
-        UInt64Array, UInt8Array,
+        Array, ArrayRef, BooleanArray, DictionaryArray, Float32Array, Float64Array,
+        Int16Array, Int32Array, Int64Array, Int8Array, LargeStringArray, StringArray,
+        UInt16Array, UInt32Array, UInt64Array, UInt8Array,
     };
     use arrow::datatypes::*;
     use arrow::record_batch::RecordBatch;
     use async_trait::async_trait;
+    use datafusion_data_access::object_store::local::LocalFileSystem;
+    use datafusion_data_access::object_store::memory::{
+        InMemoryObjectStore, MEMORY_SCHEME,
+    };
     use datafusion_expr::Volatility;
     use std::fs::File;
     use std::sync::Weak;
@@ -1692,6 +3196,72 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn function_and_operator_kernels() -> Result<()> {
+        let mut ctx = SessionContext::new();
+
+        let upper = make_scalar_function(|args: &[ArrayRef]| {
+            let strings = args[0].as_any().downcast_ref::<StringArray>().unwrap();
+            let upper: StringArray = strings
+                .iter()
+                .map(|s| s.map(|s| s.to_uppercase()))
+                .collect();
+            Ok(Arc::new(upper) as ArrayRef)
+        });
+        ctx.register_function_kernel(BuiltinScalarFunction::Lower, upper);
+
+        let eq_ignore_case = make_scalar_function(|args: &[ArrayRef]| {
+            let left = args[0].as_any().downcast_ref::<StringArray>().unwrap();
+            let right = args[1].as_any().downcast_ref::<StringArray>().unwrap();
+            let eq: BooleanArray = left
+                .iter()
+                .zip(right.iter())
+                .map(|(l, r)| Some(l?.eq_ignore_ascii_case(r?)))
+                .collect();
+            Ok(Arc::new(eq) as ArrayRef)
+        });
+        ctx.register_operator_kernel(
+            Operator::Eq,
+            DataType::Utf8,
+            DataType::Utf8,
+            eq_ignore_case,
+        );
+
+        let results = plan_and_collect(
+            &ctx,
+            "SELECT lower(c) AS l, c = 'ABC' AS eq, length(c) = 3 AS len \
+             FROM (SELECT 'aBc' AS c) t",
+        )
+        .await?;
+
+        let expected = vec![
+            "+-----+------+------+",
+            "| l   | eq   | len  |",
+            "+-----+------+------+",
+            "| ABC | true | true |",
+            "+-----+------+------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        // the kernels are not used by the other contexts
+        let results = plan_and_collect(
+            &SessionContext::new(),
+            "SELECT lower(c) AS l, c = 'ABC' AS eq FROM (SELECT 'aBc' AS c) t",
+        )
+        .await?;
+
+        let expected = vec![
+            "+-----+-------+",
+            "| l   | eq    |",
+            "+-----+-------+",
+            "| abc | false |",
+            "+-----+-------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn register_deregister() -> Result<()> {
         let tmp_dir = TempDir::new()?;
@@ -1707,6 +3277,66 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn scan_with_object_store_limits() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let schema = populate_csv_partitions(&tmp_dir, 4, ".csv")?;
+        let limits = ObjectStoreRequestLimits::new()
+            .with_max_concurrent_requests(1)
+            .with_max_requests_per_second(1000);
+        let ctx = SessionContext::with_config(
+            SessionConfig::new()
+                .with_target_partitions(4)
+                .with_object_store_limits(limits.clone()),
+        );
+        ctx.register_csv(
+            "test",
+            tmp_dir.path().to_str().unwrap(),
+            CsvReadOptions::new().schema(&schema),
+        )
+        .await?;
+
+        let task_ctx = ctx.task_ctx();
+        let store = task_ctx.limit_object_store(Arc::new(LocalFileSystem));
+        assert_eq!(
+            format!("{:?}", store),
+            format!(
+                "LimitedObjectStore {{ inner: LocalFileSystem, limits: {:?} }}",
+                limits
+            )
+        );
+
+        let results = plan_and_collect(&ctx, "SELECT count(*) AS cnt FROM test").await?;
+        let expected = vec!["+-----+", "| cnt |", "+-----+", "| 44  |", "+-----+"];
+        assert_batches_eq!(expected, &results);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn scan_in_memory_buffers() -> Result<()> {
+        let store = Arc::new(InMemoryObjectStore::new());
+        store.put("data/t.csv", "a,b\n1,x\n2,y\n");
+        store.put("data/t.json", "{\"a\":3}\n{\"a\":4}\n");
+        let ctx = SessionContext::new();
+        ctx.runtime_env()
+            .register_object_store(MEMORY_SCHEME, store.clone());
+        ctx.register_csv("c", "memory://data/t.csv", CsvReadOptions::new())
+            .await?;
+        ctx.register_json("j", "memory://data/", NdJsonReadOptions::default())
+            .await?;
+
+        let results = plan_and_collect(
+            &ctx,
+            "SELECT a FROM c WHERE b = 'y' UNION ALL SELECT a FROM j ORDER BY a",
+        )
+        .await?;
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 2 |", "| 3 |", "| 4 |", "+---+",
+        ];
+        assert_batches_eq!(expected, &results);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn left_join_using() -> Result<()> {
         let results = execute(
@@ -3336,6 +4966,680 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn sql_set_search_path() -> Result<()> {
+        let ctx = SessionContext::new();
+
+        ctx.sql("CREATE DATABASE test").await?.collect().await?;
+        ctx.sql("CREATE SCHEMA abc").await?.collect().await?;
+        ctx.sql("CREATE SCHEMA test.def").await?.collect().await?;
+        ctx.sql("CREATE TABLE abc.x AS VALUES (1)")
+            .await?
+            .collect()
+            .await?;
+        ctx.sql("CREATE TABLE test.def.y AS VALUES (2)")
+            .await?
+            .collect()
+            .await?;
+        assert!(ctx.sql("SELECT * FROM x").await.is_err());
+
+        ctx.sql("SET search_path = abc, 'test.def'")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(ctx.state.read().config.search_path(), ["abc", "test.def"]);
+
+        let results = plan_and_collect(&ctx, "SELECT * FROM x CROSS JOIN y").await?;
+        let expected = vec![
+            "+---------+---------+",
+            "| column1 | column1 |",
+            "+---------+---------+",
+            "| 1       | 2       |",
+            "+---------+---------+",
+        ];
+        assert_batches_eq!(expected, &results);
+        assert!(ctx.table("y").is_ok());
+
+        // tables of the default schema take precedence
+        ctx.sql("CREATE TABLE y AS VALUES (3)")
+            .await?
+            .collect()
+            .await?;
+        let results = plan_and_collect(&ctx, "SELECT * FROM y").await?;
+        let expected = vec![
+            "+---------+",
+            "| column1 |",
+            "+---------+",
+            "| 3       |",
+            "+---------+",
+        ];
+        assert_batches_eq!(expected, &results);
+
+        let err = ctx.sql("SET foo = 1").await.unwrap_err();
+        assert!(err.to_string().contains("SET foo not implemented"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sql_set_target_partitions() -> Result<()> {
+        let ctx =
+            SessionContext::with_config(SessionConfig::new().with_target_partitions(2));
+        let sql = "SELECT a, count(*) FROM (VALUES (1), (2)) AS t (a) GROUP BY a";
+
+        ctx.sql("SET target_partitions = 3")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(ctx.copied_config().target_partitions, 3);
+        let plan = ctx.sql(sql).await?.create_physical_plan().await?;
+        assert_eq!(plan.output_partitioning().partition_count(), 3);
+
+        ctx.sql("SET target_partitions TO DEFAULT")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(
+            ctx.copied_config().target_partitions,
+            default_target_partitions()
+        );
+
+        let err = ctx.sql("SET target_partitions = 0").await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid value '0' for target_partitions"),
+            "{}",
+            err
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sql_set_default_null_ordering() -> Result<()> {
+        let ctx = SessionContext::new();
+        assert_eq!(
+            ctx.copied_config().default_null_ordering,
+            NullOrdering::NullsMax
+        );
+
+        ctx.sql("SET default_null_ordering = spark")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(
+            ctx.copied_config().default_null_ordering,
+            NullOrdering::NullsMin
+        );
+
+        ctx.sql("SET default_null_ordering TO DEFAULT")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(
+            ctx.copied_config().default_null_ordering,
+            NullOrdering::NullsMax
+        );
+
+        let err = ctx
+            .sql("SET default_null_ordering = sideways")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Unknown null ordering 'sideways'"),
+            "{}",
+            err
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sql_set_null_group_keys() -> Result<()> {
+        let ctx = SessionContext::new();
+        assert_eq!(ctx.copied_config().null_group_keys, NullGroupKeys::Group);
+
+        ctx.sql("SET null_group_keys = drop")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(ctx.copied_config().null_group_keys, NullGroupKeys::Drop);
+        assert_eq!(
+            ctx.copied_config().to_props()[NULL_GROUP_KEYS],
+            "drop".to_string()
+        );
+
+        ctx.sql("SET null_group_keys TO DEFAULT")
+            .await?
+            .collect()
+            .await?;
+        assert_eq!(ctx.copied_config().null_group_keys, NullGroupKeys::Group);
+
+        let err = ctx.sql("SET null_group_keys = keep").await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Unknown null group key handling 'keep'"),
+            "{}",
+            err
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sql_multi_statements() -> Result<()> {
+        let ctx = SessionContext::new();
+        let results = ctx
+            .sql_multi(
+                "CREATE TABLE t AS SELECT * FROM (VALUES (1), (2)) AS v (a); \
+                 SET target_partitions = 3; \
+                 SELECT sum(a) AS total FROM t; \
+                 DROP TABLE t",
+            )
+            .await?;
+        assert_eq!(results.len(), 4);
+        assert!(results[0].is_empty() && results[1].is_empty() && results[3].is_empty());
+        let expected = vec![
+            "+-------+",
+            "| total |",
+            "+-------+",
+            "| 3     |",
+            "+-------+",
+        ];
+        assert_batches_eq!(expected, &results[2]);
+        assert_eq!(ctx.copied_config().target_partitions, 3);
+        assert!(!ctx.table_exist("t")?);
+
+        // the statements before the failing one are executed
+        assert!(ctx
+            .sql_multi("CREATE TABLE u AS SELECT 1 AS a; SELECT b FROM u")
+            .await
+            .is_err());
+        assert!(ctx.table_exist("u")?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn validate_sql_multi_statements() -> Result<()> {
+        let ctx = SessionContext::new();
+        ctx.sql("CREATE TABLE existing AS SELECT 1 AS a").await?;
+
+        let plans = ctx
+            .validate_sql_multi(
+                "CREATE TABLE t AS SELECT a, a + 1 AS b FROM existing; \
+                 SELECT b FROM t; \
+                 DROP TABLE existing; \
+                 SET target_partitions = 5",
+            )
+            .await?;
+        assert_eq!(plans.len(), 4);
+        assert!(matches!(plans[1], LogicalPlan::Projection(_)));
+        // nothing was executed
+        assert!(!ctx.table_exist("t")?);
+        assert!(ctx.table_exist("existing")?);
+        assert_ne!(ctx.copied_config().target_partitions, 5);
+
+        // a table dropped by the script can not be used
+        let err = ctx
+            .validate_sql_multi("DROP TABLE existing; SELECT a FROM existing")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Table or CTE with name 'existing' not found"),
+            "{}",
+            err
+        );
+        assert!(ctx
+            .validate_sql_multi("CREATE TABLE existing AS SELECT 1 AS a")
+            .await
+            .is_err());
+        assert!(ctx
+            .validate_sql_multi("SET target_partitions = 0")
+            .await
+            .is_err());
+        assert!(ctx.table_exist("existing")?);
+
+        // the settings of the script apply to its following statements
+        let plans = ctx
+            .validate_sql_multi(
+                "SET default_null_ordering = spark; \
+                 SELECT a FROM existing ORDER BY a",
+            )
+            .await?;
+        let plan = format!("{:?}", plans[1]);
+        assert!(plan.contains("#existing.a ASC NULLS FIRST"), "{}", plan);
+        assert_eq!(
+            ctx.copied_config().default_null_ordering,
+            NullOrdering::NullsMax
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sql_transaction_statements() -> Result<()> {
+        /// Records the statements, failing to commit
+        #[derive(Debug, Default)]
+        struct RecordingHandler(parking_lot::Mutex<Vec<TransactionStatement>>);
+
+        impl TransactionHandler for RecordingHandler {
+            fn begin(&self) -> Result<()> {
+                self.0.lock().push(TransactionStatement::Begin);
+                Ok(())
+            }
+
+            fn commit(&self) -> Result<()> {
+                Err(DataFusionError::Execution("commit failed".to_string()))
+            }
+
+            fn rollback(&self) -> Result<()> {
+                self.0.lock().push(TransactionStatement::Rollback);
+                Ok(())
+            }
+        }
+
+        // accepted without doing anything by default
+        let ctx = SessionContext::new();
+        for sql in ["BEGIN", "START TRANSACTION", "COMMIT", "ROLLBACK"] {
+            assert!(ctx.sql(sql).await?.collect().await?.is_empty());
+        }
+
+        let handler = Arc::new(RecordingHandler::default());
+        let state = SessionState::with_config_rt(
+            SessionConfig::new(),
+            Arc::new(RuntimeEnv::new(RuntimeConfig::default())?),
+        )
+        .with_transaction_handler(handler.clone());
+        let ctx = SessionContext::with_state(state);
+        ctx.sql_multi("BEGIN; SELECT 1; ROLLBACK").await?;
+        assert_eq!(
+            *handler.0.lock(),
+            vec![TransactionStatement::Begin, TransactionStatement::Rollback]
+        );
+        let err = match ctx.sql("COMMIT").await {
+            Ok(_) => panic!("the commit should fail"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("commit failed"), "{}", err);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn catalog_snapshot() -> Result<()> {
+        let ctx = SessionContext::new();
+        ctx.sql("CREATE TABLE t AS SELECT 1 AS a").await?;
+        // the catalogs as seen by another session sharing them
+        let catalog_list = ctx.state.read().catalog_list.clone();
+        let schema = catalog_list
+            .catalog("datafusion")
+            .unwrap()
+            .schema("public")
+            .unwrap();
+        let replace_t = |value: i64| -> Result<()> {
+            let batch = RecordBatch::try_from_iter(vec![(
+                "a",
+                Arc::new(Int64Array::from(vec![value])) as ArrayRef,
+            )])?;
+            let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+            schema.deregister_table("t")?;
+            schema.register_table("t".to_owned(), Arc::new(table))?;
+            Ok(())
+        };
+
+        // a transaction sees the tables as they were when it began, and the
+        // tables it creates
+        ctx.sql("BEGIN").await?;
+        replace_t(2)?;
+        ctx.sql("CREATE TABLE u AS SELECT a + 10 AS b FROM t")
+            .await?;
+        let results = ctx.sql("SELECT a, b FROM t, u").await?.collect().await?;
+        let expected = vec![
+            "+---+----+",
+            "| a | b  |",
+            "+---+----+",
+            "| 1 | 11 |",
+            "+---+----+",
+        ];
+        assert_batches_eq!(expected, &results);
+        assert!(schema.table_exist("u"));
+
+        // the current tables are seen once it ends
+        ctx.sql("COMMIT").await?;
+        let results = ctx.sql("SELECT a FROM t").await?.collect().await?;
+        let expected = vec!["+---+", "| a |", "+---+", "| 2 |", "+---+"];
+        assert_batches_eq!(expected, &results);
+
+        // a script releases its snapshot, unless it leaves a transaction open
+        let in_snapshot = |ctx: &SessionContext| ctx.state.read().has_catalog_snapshot();
+        ctx.sql_multi("SELECT 1; BEGIN; COMMIT; SELECT 2").await?;
+        assert!(!in_snapshot(&ctx));
+        ctx.sql_multi("SELECT 1; BEGIN").await?;
+        assert!(in_snapshot(&ctx));
+        replace_t(3)?;
+        let results = ctx.sql("SELECT a FROM t").await?.collect().await?;
+        assert_batches_eq!(expected, &results);
+        ctx.sql("ROLLBACK").await?;
+        assert!(!in_snapshot(&ctx));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn execute_parsed_statements() -> Result<()> {
+        use crate::sql::parser::UserDefinedStatement;
+        use sqlparser::ast::{Query, Statement as SQLStatement};
+        use std::any::Any;
+        use std::fmt;
+
+        /// `CREATE SNAPSHOT name AS query`, parsed by an extended grammar
+        #[derive(Debug)]
+        struct CreateSnapshot {
+            name: String,
+            query: Query,
+        }
+
+        impl fmt::Display for CreateSnapshot {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "CREATE SNAPSHOT {} AS {}", self.name, self.query)
+            }
+        }
+
+        impl UserDefinedStatement for CreateSnapshot {
+            fn as_any(&self) -> &dyn Any {
+                self
+            }
+        }
+
+        struct SnapshotPlanner;
+
+        impl StatementPlanner for SnapshotPlanner {
+            fn plan_statement(
+                &self,
+                statement: &dyn UserDefinedStatement,
+                plan_statement: &dyn Fn(DFStatement) -> Result<LogicalPlan>,
+            ) -> Result<Option<LogicalPlan>> {
+                let snapshot = match statement.as_any().downcast_ref::<CreateSnapshot>() {
+                    Some(snapshot) => snapshot,
+                    None => return Ok(None),
+                };
+                let query = SQLStatement::Query(Box::new(snapshot.query.clone()));
+                let input = plan_statement(query.into())?;
+                Ok(Some(LogicalPlan::CreateMemoryTable(CreateMemoryTable {
+                    name: snapshot.name.clone(),
+                    input: Arc::new(input),
+                    if_not_exists: false,
+                })))
+            }
+        }
+
+        let parse_query = |sql: &str| match DFParser::parse_sql(sql).unwrap().pop_front()
+        {
+            Some(DFStatement::Statement(statement)) => match *statement {
+                SQLStatement::Query(query) => *query,
+                other => panic!("Expected a query, got {:?}", other),
+            },
+            other => panic!("Expected a statement, got {:?}", other),
+        };
+        let snapshot = DFStatement::Extension(Arc::new(CreateSnapshot {
+            name: "s".to_string(),
+            query: parse_query("SELECT * FROM (VALUES (1), (2)) AS t (a)"),
+        }));
+
+        // not supported without a planner
+        let err = SessionContext::new()
+            .statement_to_plan(snapshot.clone())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "This feature is not implemented: No statement planner supports the statement: \
+             CREATE SNAPSHOT s AS SELECT * FROM (VALUES (1), (2)) AS t (a)"
+        );
+
+        let state = SessionState::with_config_rt(
+            SessionConfig::new(),
+            Arc::new(RuntimeEnv::new(RuntimeConfig::default())?),
+        )
+        .add_statement_planner(Arc::new(SnapshotPlanner));
+        let ctx = SessionContext::with_state(state);
+        ctx.execute_statement(snapshot).await?;
+
+        let query =
+            SQLStatement::Query(Box::new(parse_query("SELECT sum(a) AS s FROM s")));
+        let batches = ctx.execute_statement(query.into()).await?.collect().await?;
+        let expected = vec!["+---+", "| s |", "+---+", "| 3 |", "+---+"];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn authorize_tables_and_columns() -> Result<()> {
+        /// Denies the table `secret` and the column `salary`
+        #[derive(Debug)]
+        struct TestAuthorizer;
+
+        impl Authorizer for TestAuthorizer {
+            fn authorize_table(&self, table: &ResolvedTableReference) -> Result<()> {
+                match table.table {
+                    "secret" => Err(DataFusionError::Plan(format!(
+                        "Access denied to table {}.{}.{}",
+                        table.catalog, table.schema, table.table
+                    ))),
+                    _ => Ok(()),
+                }
+            }
+
+            fn authorize_columns(
+                &self,
+                table: &ResolvedTableReference,
+                columns: &[&str],
+            ) -> Result<()> {
+                match columns.iter().find(|column| **column == "salary") {
+                    Some(column) => Err(DataFusionError::Plan(format!(
+                        "Access denied to column {} of table {}",
+                        column, table.table
+                    ))),
+                    None => Ok(()),
+                }
+            }
+        }
+
+        let state = SessionState::with_config_rt(
+            SessionConfig::new(),
+            Arc::new(RuntimeEnv::new(RuntimeConfig::default())?),
+        )
+        .with_authorizer(Arc::new(TestAuthorizer));
+        let ctx = SessionContext::with_state(state);
+        ctx.sql_multi(
+            "CREATE TABLE employees AS \
+             SELECT * FROM (VALUES ('alice', 10), ('bob', 20)) AS t (name, salary); \
+             CREATE TABLE secret AS SELECT 1 AS a",
+        )
+        .await?;
+
+        let batches = ctx
+            .sql("SELECT name FROM employees ORDER BY name")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+-------+",
+            "| name  |",
+            "+-------+",
+            "| alice |",
+            "| bob   |",
+            "+-------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        for sql in [
+            "SELECT * FROM employees",
+            "SELECT sum(salary) FROM employees",
+        ] {
+            let err = ctx.sql(sql).await?.collect().await.unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Error during planning: Access denied to column salary of table employees"
+            );
+        }
+
+        let err = ctx.sql("SELECT * FROM secret").await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Access denied to table datafusion.public.secret"
+        );
+        let err = ctx.table("secret").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Access denied to table datafusion.public.secret"
+        );
+
+        // the writes must be allowed explicitly
+        let err = ctx.sql("DELETE FROM employees").await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Write access denied to table datafusion.public.employees"
+        );
+
+        // the metadata of the files must be allowed explicitly
+        let err = ctx
+            .sql("SELECT * FROM parquet_metadata('/tmp')")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Access denied to the metadata of the files at /tmp"
+        );
+
+        // the columns of the filters pushed down to the scans are authorized
+        let employees = ctx.state.read().find_table_provider("employees".into());
+        let plan = LogicalPlanBuilder::scan_with_filters(
+            "employees",
+            employees.unwrap(),
+            Some(vec![0]),
+            vec![col("salary").gt(lit(15))],
+        )?
+        .build()?;
+        let err = ctx.create_physical_plan(&plan).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Access denied to column salary of table employees"
+        );
+
+        // the scans of the unregistered tables are denied
+        let plan = LogicalPlanBuilder::scan(
+            "other",
+            Arc::new(EmptyTable::new(Arc::new(Schema::empty()))),
+            None,
+        )?
+        .build()?;
+        let err = ctx.create_physical_plan(&plan).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Access denied to the unregistered table other"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn security_policies() -> Result<()> {
+        async fn query(principal: &str, sql: &str) -> Result<Vec<RecordBatch>> {
+            let ctx = SessionContext::with_config(
+                SessionConfig::new().with_principal(principal),
+            );
+            ctx.sql(
+                "CREATE TABLE employees AS SELECT * FROM (VALUES \
+                 ('alice', 'EU', '123'), ('bob', 'US', '456')) AS t (name, region, ssn)",
+            )
+            .await?;
+            ctx.register_security_policy(
+                "employees",
+                SecurityPolicy::new()
+                    .with_principals(["analyst"])
+                    .with_row_filter(col("region").eq(lit("EU")))
+                    .with_column_mask("ssn", lit("***")),
+            );
+            ctx.sql(sql).await?.collect().await
+        }
+
+        let sql = "SELECT e.name, e.ssn FROM employees AS e ORDER BY name";
+        let expected = vec![
+            "+-------+-----+",
+            "| name  | ssn |",
+            "+-------+-----+",
+            "| alice | *** |",
+            "+-------+-----+",
+        ];
+        assert_batches_eq!(expected, &query("analyst", sql).await?);
+
+        let expected = vec![
+            "+-------+-----+",
+            "| name  | ssn |",
+            "+-------+-----+",
+            "| alice | 123 |",
+            "| bob   | 456 |",
+            "+-------+-----+",
+        ];
+        assert_batches_eq!(expected, &query("admin", sql).await?);
+
+        // the masked values can't be filtered on
+        let sql = "SELECT employees.name FROM employees WHERE ssn = '123'";
+        assert!(query("analyst", sql)
+            .await?
+            .iter()
+            .all(|b| b.num_rows() == 0));
+
+        // nor deleted or updated, and only the filtered rows are deleted
+        for sql in [
+            "DELETE FROM employees WHERE ssn = '123'",
+            "UPDATE employees SET ssn = '789'",
+        ] {
+            let err = query("analyst", sql).await.unwrap_err();
+            assert!(err.to_string().contains("masked column 'ssn'"), "{}", err);
+        }
+        let sql = "DELETE FROM employees WHERE name <> 'carol'";
+        let expected = vec![
+            "+-------+",
+            "| count |",
+            "+-------+",
+            "| 1     |",
+            "+-------+",
+        ];
+        assert_batches_eq!(expected, &query("analyst", sql).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn search_path_udf() -> Result<()> {
+        let mut ctx = SessionContext::with_config(
+            SessionConfig::new().with_search_path(vec!["abc".to_string()]),
+        );
+        let myfunc = |args: &[ArrayRef]| Ok(Arc::clone(&args[0]));
+        ctx.register_udf(create_udf(
+            "abc.my_func",
+            vec![DataType::Int64],
+            Arc::new(DataType::Int64),
+            Volatility::Immutable,
+            make_scalar_function(myfunc),
+        ));
+
+        let results = plan_and_collect(&ctx, "SELECT my_func(3) AS x").await?;
+        let expected = vec!["+---+", "| x |", "+---+", "| 3 |", "+---+"];
+        assert_batches_eq!(expected, &results);
+
+        // the entries qualified with a catalog find the functions registered
+        // with or without it
+        ctx.register_udf(create_udf(
+            "other.def.other_func",
+            vec![DataType::Int64],
+            Arc::new(DataType::Int64),
+            Volatility::Immutable,
+            make_scalar_function(myfunc),
+        ));
+        ctx.sql("SET search_path = 'datafusion.abc', 'other.def'")
+            .await?
+            .collect()
+            .await?;
+        let results =
+            plan_and_collect(&ctx, "SELECT my_func(other_func(3)) AS x").await?;
+        assert_batches_eq!(expected, &results);
+        Ok(())
+    }
+
     struct MyPhysicalPlanner {}
 
     #[async_trait]
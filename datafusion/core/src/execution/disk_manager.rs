@@ -64,6 +64,24 @@ impl DiskManagerConfig {
     }
 }
 
+/// Compression codec of the files spilled to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpillCompression {
+    /// Spill files are written uncompressed
+    Uncompressed,
+    /// Spill files are compressed with Zstandard, which trades more CPU for
+    /// smaller files than LZ4
+    Zstd,
+    /// Spill files are compressed with the LZ4 frame format
+    Lz4,
+}
+
+impl Default for SpillCompression {
+    fn default() -> Self {
+        Self::Uncompressed
+    }
+}
+
 /// Manages files generated during query execution, e.g. spill files generated
 /// while processing dataset larger than available memory.
 #[derive(Debug)]
@@ -0,0 +1,329 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Pluggable executor of the tasks spawned by the operators processing
+//! in-memory data (e.g. to merge or repartition their inputs).
+//!
+//! Queries run on tokio by default. Environments without a tokio runtime,
+//! such as `wasm32-unknown-unknown` where tasks are spawned with
+//! `wasm_bindgen_futures::spawn_local`, can provide their own [`Executor`]
+//! with [`RuntimeConfig::with_executor`]. The file based sources and the
+//! spilling operators still use tokio to run their blocking I/O. On wasm32,
+//! the blocking API and the Zstd compression of the spill files are not
+//! available.
+//!
+//! With the `pinned_executor` feature, [`PinnedExecutor`] runs the tasks on
+//! threads pinned to cores, optionally restricted to a NUMA node.
+//!
+//! [`RuntimeConfig::with_executor`]: crate::execution::runtime_env::RuntimeConfig::with_executor
+
+use std::any::Any;
+use std::fmt::Debug;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::future::{BoxFuture, RemoteHandle};
+use futures::FutureExt;
+
+use crate::error::{DataFusionError, Result};
+
+/// Runs the tasks spawned during the execution of queries
+pub trait Executor: Debug + Send + Sync {
+    /// Runs `task` in the background, concurrently with the caller
+    fn spawn(&self, task: BoxFuture<'static, ()>);
+}
+
+/// An [`Executor`] spawning the tasks on the current tokio runtime
+#[derive(Debug, Default)]
+pub struct TokioExecutor;
+
+impl Executor for TokioExecutor {
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        tokio::spawn(task);
+    }
+}
+
+/// An [`Executor`] running the tasks on worker threads pinned to cores, each
+/// with its own single threaded tokio runtime.
+///
+/// The tasks are assigned to the workers in a round robin fashion, so that
+/// the partitions of the operators (e.g. the inputs of a repartition) run on
+/// distinct cores, without migrating between cores. Restricting the cores to
+/// the ones of a NUMA node, see [`numa_node_cores`], keeps the execution of
+/// the queries close to their memory on large machines.
+#[cfg(feature = "pinned_executor")]
+pub struct PinnedExecutor {
+    cores: Vec<usize>,
+    workers: Vec<tokio::sync::mpsc::UnboundedSender<BoxFuture<'static, ()>>>,
+    next: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(feature = "pinned_executor")]
+impl PinnedExecutor {
+    /// Creates an executor with a worker pinned to each core of the machine
+    pub fn try_new() -> Result<Self> {
+        let cores = core_affinity::get_core_ids().ok_or_else(|| {
+            DataFusionError::NotImplemented(
+                "Cannot list the cores of the machine".to_string(),
+            )
+        })?;
+        Self::try_new_with_cores(cores.into_iter().map(|core| core.id).collect())
+    }
+
+    /// Creates an executor with a worker pinned to each of `cores`
+    pub fn try_new_with_cores(cores: Vec<usize>) -> Result<Self> {
+        if cores.is_empty() {
+            return Err(DataFusionError::Plan(
+                "PinnedExecutor requires at least one core".to_string(),
+            ));
+        }
+        let workers = cores
+            .iter()
+            .map(|core| spawn_pinned_worker(*core))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            cores,
+            workers,
+            next: std::sync::atomic::AtomicUsize::new(0),
+        })
+    }
+
+    /// The cores the workers are pinned to
+    pub fn cores(&self) -> &[usize] {
+        &self.cores
+    }
+}
+
+#[cfg(feature = "pinned_executor")]
+impl Debug for PinnedExecutor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PinnedExecutor")
+            .field("cores", &self.cores)
+            .finish()
+    }
+}
+
+#[cfg(feature = "pinned_executor")]
+impl Executor for PinnedExecutor {
+    fn spawn(&self, task: BoxFuture<'static, ()>) {
+        let next = self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        // the workers only stop once the executor is dropped
+        let _ = self.workers[next % self.workers.len()].send(task);
+    }
+}
+
+/// Starts a worker thread pinned to `core`, running the tasks it receives
+/// until the sender is dropped.
+#[cfg(feature = "pinned_executor")]
+fn spawn_pinned_worker(
+    core: usize,
+) -> Result<tokio::sync::mpsc::UnboundedSender<BoxFuture<'static, ()>>> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let (sender, mut receiver) =
+        tokio::sync::mpsc::unbounded_channel::<BoxFuture<'static, ()>>();
+    std::thread::Builder::new()
+        .name(format!("datafusion-core-{}", core))
+        .spawn(move || {
+            core_affinity::set_for_current(core_affinity::CoreId { id: core });
+            runtime.block_on(async move {
+                while let Some(task) = receiver.recv().await {
+                    tokio::spawn(task);
+                }
+            })
+        })?;
+    Ok(sender)
+}
+
+/// Returns the cores of the NUMA node `node`, as listed by the kernel.
+#[cfg(all(feature = "pinned_executor", target_os = "linux"))]
+pub fn numa_node_cores(node: usize) -> Result<Vec<usize>> {
+    let path = format!("/sys/devices/system/node/node{}/cpulist", node);
+    parse_cpu_list(std::fs::read_to_string(&path)?.trim())
+}
+
+/// Parses a list of cores such as `0-3,8,10-11`
+#[cfg(all(feature = "pinned_executor", target_os = "linux"))]
+fn parse_cpu_list(list: &str) -> Result<Vec<usize>> {
+    let invalid = || DataFusionError::Execution(format!("Invalid cpu list '{}'", list));
+    let mut cores = vec![];
+    for range in list.split(',').filter(|range| !range.is_empty()) {
+        match range.split_once('-') {
+            Some((first, last)) => {
+                let first = first.parse::<usize>().map_err(|_| invalid())?;
+                let last = last.parse::<usize>().map_err(|_| invalid())?;
+                cores.extend(first..=last);
+            }
+            None => cores.push(range.parse().map_err(|_| invalid())?),
+        }
+    }
+    Ok(cores)
+}
+
+/// Spawns `task` on `executor`, returning a handle to its output.
+pub fn spawn<T, F>(executor: &dyn Executor, task: F) -> SpawnedTask<T>
+where
+    T: Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+{
+    let (remote, handle) = AssertUnwindSafe(task).catch_unwind().remote_handle();
+    executor.spawn(Box::pin(remote));
+    SpawnedTask { handle }
+}
+
+/// Handle of a task spawned with [`spawn`].
+///
+/// The task is cancelled when its handle is dropped. Awaiting the handle
+/// returns the output of the task, or an error if the task panicked.
+#[derive(Debug)]
+pub struct SpawnedTask<T> {
+    handle: RemoteHandle<std::thread::Result<T>>,
+}
+
+impl<T: Send + 'static> Future for SpawnedTask<T> {
+    type Output = Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.handle.poll_unpin(cx).map(|result| {
+            result.map_err(|panic| {
+                DataFusionError::Execution(format!(
+                    "Spawned task panicked: {}",
+                    panic_message(panic.as_ref())
+                ))
+            })
+        })
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown cause"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_sorted_eq;
+    use crate::datasource::MemTable;
+    use crate::execution::context::{SessionConfig, SessionContext};
+    use crate::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use crate::test::create_vec_batches;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Runs the tasks on tokio, counting them
+    #[derive(Debug, Default)]
+    struct CountingExecutor(AtomicUsize);
+
+    impl Executor for CountingExecutor {
+        fn spawn(&self, task: BoxFuture<'static, ()>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(task);
+        }
+    }
+
+    #[tokio::test]
+    async fn spawned_task() -> Result<()> {
+        let executor = Arc::new(CountingExecutor::default());
+        assert_eq!(spawn(executor.as_ref(), async { 1 + 1 }).await?, 2);
+
+        let panicked = spawn(executor.as_ref(), async { panic!("boom") }).await;
+        let message = panicked.unwrap_err().to_string();
+        assert!(
+            message.contains("Spawned task panicked: boom"),
+            "{}",
+            message
+        );
+        assert_eq!(executor.0.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn query_with_custom_executor() -> Result<()> {
+        let executor = Arc::new(CountingExecutor::default());
+        let runtime = RuntimeConfig::new().with_executor(executor.clone());
+        let ctx = SessionContext::with_config_rt(
+            SessionConfig::new().with_target_partitions(4),
+            Arc::new(RuntimeEnv::new(runtime)?),
+        );
+
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("c0", DataType::UInt32, false)]));
+        let partitions = vec![create_vec_batches(&schema, 2); 2];
+        ctx.register_table("t", Arc::new(MemTable::try_new(schema, partitions)?))?;
+
+        let batches = ctx
+            .sql("SELECT c0, count(*) AS n FROM t GROUP BY c0")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+----+---+",
+            "| c0 | n |",
+            "+----+---+",
+            "| 1  | 4 |",
+            "| 2  | 4 |",
+            "| 3  | 4 |",
+            "| 4  | 4 |",
+            "| 5  | 4 |",
+            "| 6  | 4 |",
+            "| 7  | 4 |",
+            "| 8  | 4 |",
+            "+----+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        // the repartitions and the merge of the partitions spawned their tasks
+        assert!(executor.0.load(Ordering::SeqCst) > 0);
+        Ok(())
+    }
+
+    #[cfg(feature = "pinned_executor")]
+    #[tokio::test]
+    async fn pinned_executor() -> Result<()> {
+        let executor = PinnedExecutor::try_new_with_cores(vec![0, 0])?;
+        let threads = futures::future::try_join_all((0..4).map(|_| {
+            spawn(&executor, async {
+                std::thread::current().name().map(str::to_owned)
+            })
+        }))
+        .await?;
+        assert!(threads
+            .iter()
+            .all(|thread| thread.as_deref() == Some("datafusion-core-0")));
+
+        assert!(PinnedExecutor::try_new_with_cores(vec![]).is_err());
+        Ok(())
+    }
+
+    #[cfg(all(feature = "pinned_executor", target_os = "linux"))]
+    #[test]
+    fn cpu_list() -> Result<()> {
+        assert_eq!(parse_cpu_list("0-3,8,10-11")?, vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5")?, vec![5]);
+        assert!(parse_cpu_list("a-b").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Registry of the kernels overriding the implementations of the built-in
+//! scalar functions and binary operators, e.g. to evaluate `regexp_match` with
+//! another regex engine or to compare strings with other semantics, without
+//! changing the plans.
+
+use std::collections::HashMap;
+
+use arrow::datatypes::DataType;
+
+use crate::logical_expr::{BuiltinScalarFunction, ScalarFunctionImplementation};
+use crate::logical_plan::Operator;
+
+/// The kernels overriding the implementations of the built-in scalar
+/// functions and binary operators in the physical plans of a session, see
+/// [`SessionContext::register_function_kernel`] and
+/// [`SessionContext::register_operator_kernel`].
+///
+/// The kernels receive the same arguments and must return the same type as
+/// the implementations they override: the arguments of the functions are
+/// coerced to their signature, while the operands of the operators are passed
+/// as they are, and the kernels of the operators are only used for the types
+/// of the operands they are registered for.
+///
+/// [`SessionContext::register_function_kernel`]: crate::execution::context::SessionContext::register_function_kernel
+/// [`SessionContext::register_operator_kernel`]: crate::execution::context::SessionContext::register_operator_kernel
+#[derive(Clone, Default)]
+pub struct KernelRegistry {
+    functions: HashMap<BuiltinScalarFunction, ScalarFunctionImplementation>,
+    operators: HashMap<(Operator, DataType, DataType), ScalarFunctionImplementation>,
+}
+
+impl KernelRegistry {
+    /// Creates an empty registry, leaving the built-in implementations
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `kernel` as the implementation of the function `fun`,
+    /// returning the kernel it replaces, if any
+    pub fn register_function(
+        &mut self,
+        fun: BuiltinScalarFunction,
+        kernel: ScalarFunctionImplementation,
+    ) -> Option<ScalarFunctionImplementation> {
+        self.functions.insert(fun, kernel)
+    }
+
+    /// Registers `kernel` as the implementation of the operator `op` for the
+    /// operands of types `left_type` and `right_type`, returning the kernel
+    /// it replaces, if any
+    pub fn register_operator(
+        &mut self,
+        op: Operator,
+        left_type: DataType,
+        right_type: DataType,
+        kernel: ScalarFunctionImplementation,
+    ) -> Option<ScalarFunctionImplementation> {
+        self.operators.insert((op, left_type, right_type), kernel)
+    }
+
+    /// The kernel registered for the function `fun`, if any
+    pub fn function(
+        &self,
+        fun: &BuiltinScalarFunction,
+    ) -> Option<ScalarFunctionImplementation> {
+        self.functions.get(fun).cloned()
+    }
+
+    /// The kernel registered for the operator `op` and the operands of types
+    /// `left_type` and `right_type`, if any
+    pub fn operator(
+        &self,
+        op: Operator,
+        left_type: &DataType,
+        right_type: &DataType,
+    ) -> Option<ScalarFunctionImplementation> {
+        if self.operators.is_empty() {
+            return None;
+        }
+        self.operators
+            .get(&(op, left_type.clone(), right_type.clone()))
+            .cloned()
+    }
+}
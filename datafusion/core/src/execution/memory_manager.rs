@@ -19,7 +19,7 @@
 
 use crate::error::{DataFusionError, Result};
 use async_trait::async_trait;
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
 use log::{debug, warn};
 use parking_lot::{Condvar, Mutex};
 use std::fmt;
@@ -30,6 +30,9 @@ use std::time::{Duration, Instant};
 
 static CONSUMER_ID: AtomicUsize = AtomicUsize::new(0);
 
+/// Number of consumers listed when the memory pool is exhausted
+const TOP_CONSUMERS: usize = 5;
+
 #[derive(Debug, Clone)]
 /// Configuration information for memory management
 pub enum MemoryManagerConfig {
@@ -257,6 +260,9 @@ pub struct MemoryManager {
     pool_size: usize,
     requesters_total: Arc<Mutex<usize>>,
     trackers_total: AtomicUsize,
+    /// name and memory used of the tracking consumers reporting their usage
+    /// by id, to report the top consumers when the pool is exhausted
+    trackers: Mutex<HashMap<MemoryConsumerId, (String, usize)>>,
     cv: Condvar,
 }
 
@@ -279,6 +285,7 @@ impl MemoryManager {
                     pool_size,
                     requesters_total: Arc::new(Mutex::new(0)),
                     trackers_total: AtomicUsize::new(0),
+                    trackers: Mutex::new(HashMap::new()),
                     cv: Condvar::new(),
                 })
             }
@@ -309,6 +316,93 @@ impl MemoryManager {
         ));
     }
 
+    /// Sets the memory used by the tracking consumer `id`, named `name`, to
+    /// `size`
+    pub(crate) fn set_tracker_usage(
+        &self,
+        id: &MemoryConsumerId,
+        name: &str,
+        size: usize,
+    ) {
+        let mut trackers = self.trackers.lock();
+        let previous = trackers.get(id).map_or(0, |(_, used)| *used);
+        self.update_tracker(&mut trackers, id, name, previous, size);
+    }
+
+    /// Sets the memory used by the tracking consumer `id`, named `name`, to
+    /// `size`, failing with [`DataFusionError::ResourcesExhausted`], listing
+    /// the top consumers, when it would exceed the memory pool
+    pub(crate) fn try_set_tracker_usage(
+        &self,
+        id: &MemoryConsumerId,
+        name: &str,
+        size: usize,
+    ) -> Result<()> {
+        let mut trackers = self.trackers.lock();
+        let previous = trackers.get(id).map_or(0, |(_, used)| *used);
+        if size > previous {
+            let used = self.get_tracker_total() + self.get_requester_total();
+            if used.saturating_add(size - previous) > self.pool_size {
+                let consumers = top_consumers(&trackers, TOP_CONSUMERS)
+                    .into_iter()
+                    .map(|(name, used)| format!("{} {}", name, human_readable_size(used)))
+                    .chain(self.requesters_summary())
+                    .collect::<Vec<_>>();
+                return Err(DataFusionError::ResourcesExhausted(format!(
+                    "Failed to allocate additional {} for {}[{}] with {} already allocated \
+                     - the memory pool of {} has {} in use. Top memory consumers: {}",
+                    human_readable_size(size - previous),
+                    name,
+                    id,
+                    human_readable_size(previous),
+                    human_readable_size(self.pool_size),
+                    human_readable_size(used),
+                    consumers.join(", "),
+                )));
+            }
+        }
+        self.update_tracker(&mut trackers, id, name, previous, size);
+        Ok(())
+    }
+
+    fn update_tracker(
+        &self,
+        trackers: &mut HashMap<MemoryConsumerId, (String, usize)>,
+        id: &MemoryConsumerId,
+        name: &str,
+        previous: usize,
+        size: usize,
+    ) {
+        if size > previous {
+            self.grow_tracker_usage(size - previous);
+        } else if size < previous {
+            self.shrink_tracker_usage(previous - size);
+        }
+        if size == 0 {
+            trackers.remove(id);
+        } else {
+            trackers.insert(id.clone(), (name.to_owned(), size));
+        }
+    }
+
+    /// Returns the tracking consumers reporting their usage by id that use
+    /// the most memory, as their name and id with their memory used, in
+    /// descending order of memory used
+    pub fn top_consumers(&self, n: usize) -> Vec<(String, usize)> {
+        top_consumers(&self.trackers.lock(), n)
+    }
+
+    fn requesters_summary(&self) -> Option<String> {
+        let num_requesters = self.requesters.lock().len();
+        (num_requesters > 0).then(|| {
+            format!(
+                "{} spilling consumers {}",
+                num_requesters,
+                human_readable_size(self.get_requester_total())
+            )
+        })
+    }
+
     /// Return the total memory usage for all requesters
     pub fn get_requester_total(&self) -> usize {
         *self.requesters_total.lock()
@@ -407,11 +501,29 @@ impl MemoryManager {
                 return;
             }
         }
-        self.shrink_tracker_usage(mem_used);
+        // the consumers reporting their usage by id are accounted for by the
+        // manager itself
+        match self.trackers.lock().remove(id) {
+            Some((_, used)) => self.shrink_tracker_usage(used),
+            None => self.shrink_tracker_usage(mem_used),
+        }
         self.cv.notify_all();
     }
 }
 
+fn top_consumers(
+    trackers: &HashMap<MemoryConsumerId, (String, usize)>,
+    n: usize,
+) -> Vec<(String, usize)> {
+    let mut consumers = trackers
+        .iter()
+        .map(|(id, (name, used))| (format!("{}[{}]", name, id), *used))
+        .collect::<Vec<_>>();
+    consumers.sort_by(|(_, a), (_, b)| b.cmp(a));
+    consumers.truncate(n);
+    consumers
+}
+
 impl Display for MemoryManager {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f,
@@ -612,6 +724,49 @@ mod tests {
         assert_eq!(*runtime.memory_manager.requesters_total.lock(), 40);
     }
 
+    #[test]
+    fn exhausted_pool_lists_top_consumers() {
+        let config = RuntimeConfig::new()
+            .with_memory_manager(MemoryManagerConfig::try_new_limit(100, 1.0).unwrap());
+        let runtime = Arc::new(RuntimeEnv::new(config).unwrap());
+
+        let ms = ExecutionPlanMetricsSet::new();
+        let small =
+            MemTrackingMetrics::new_with_rt(&ms, 0, runtime.clone()).with_name("small");
+        let large =
+            MemTrackingMetrics::new_with_rt(&ms, 1, runtime.clone()).with_name("large");
+        small.try_set_mem_used(20).unwrap();
+        large.try_set_mem_used(70).unwrap();
+        assert_eq!(runtime.memory_manager.get_tracker_total(), 90);
+
+        let consumers = runtime.memory_manager.top_consumers(5);
+        assert_eq!(consumers.len(), 2);
+        assert!(consumers[0].0.starts_with("large["));
+        assert_eq!(consumers[0].1, 70);
+
+        let err = small.try_set_mem_used(40).unwrap_err().to_string();
+        assert!(
+            err.starts_with(
+                "Resources exhausted: Failed to allocate additional 20.0 B for small["
+            ),
+            "{}",
+            err
+        );
+        assert!(err.contains("Top memory consumers: large["), "{}", err);
+        // the usage is unchanged on failure
+        assert_eq!(small.mem_used(), 20);
+        assert_eq!(runtime.memory_manager.get_tracker_total(), 90);
+
+        // shrinking always succeeds
+        large.try_set_mem_used(10).unwrap();
+        small.try_set_mem_used(40).unwrap();
+        assert_eq!(runtime.memory_manager.get_tracker_total(), 50);
+
+        drop(large);
+        assert_eq!(runtime.memory_manager.get_tracker_total(), 40);
+        assert_eq!(runtime.memory_manager.top_consumers(5).len(), 1);
+    }
+
     #[tokio::test]
     #[should_panic(expected = "invalid max_memory. Expected greater than 0, got 0")]
     async fn test_try_new_with_limit_0() {
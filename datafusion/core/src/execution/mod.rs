@@ -17,11 +17,20 @@
 
 //! DataFusion query execution
 
+pub mod authorization;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod blocking;
+pub mod compaction;
 pub mod context;
 pub mod disk_manager;
+pub mod executor;
+pub mod kernels;
 pub mod memory_manager;
 pub mod options;
+pub mod pagination;
+pub mod plan_baseline;
 pub mod runtime_env;
+pub mod transaction;
 
 pub use disk_manager::DiskManager;
 pub use memory_manager::{
@@ -17,9 +17,10 @@
 
 //! User facing options for the file formats readers
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use arrow::datatypes::{Schema, SchemaRef};
+use arrow::datatypes::{DataType, Schema, SchemaRef};
 
 use crate::datasource::file_format::DEFAULT_SCHEMA_INFER_MAX_RECORD;
 use crate::datasource::{
@@ -31,6 +32,7 @@ use crate::datasource::{
     },
     listing::ListingOptions,
 };
+use crate::physical_plan::file_format::ScanErrorPolicy;
 
 /// CSV file read option
 #[derive(Clone)]
@@ -47,11 +49,20 @@ pub struct CsvReadOptions<'a> {
     pub schema: Option<&'a Schema>,
     /// Max number of rows to read from CSV files for schema inference if needed. Defaults to `DEFAULT_SCHEMA_INFER_MAX_RECORD`.
     pub schema_infer_max_records: usize,
+    /// Widen the types of columns whose inferred type differs between files
+    /// instead of failing schema inference. Defaults to false.
+    pub type_widening: bool,
+    /// Types forced for some columns during schema inference, keyed by
+    /// column name. Defaults to no override.
+    pub column_type_overrides: HashMap<String, DataType>,
     /// File extension; only files with this extension are selected for data input.
     /// Defaults to DEFAULT_CSV_EXTENSION.
     pub file_extension: &'a str,
     /// Partition Columns
     pub table_partition_cols: Vec<String>,
+    /// What to do when a file or a record cannot be read. Defaults to
+    /// `ScanErrorPolicy::Fail`.
+    pub error_policy: ScanErrorPolicy,
 }
 
 impl<'a> Default for CsvReadOptions<'a> {
@@ -67,9 +78,12 @@ impl<'a> CsvReadOptions<'a> {
             has_header: true,
             schema: None,
             schema_infer_max_records: DEFAULT_SCHEMA_INFER_MAX_RECORD,
+            type_widening: false,
+            column_type_overrides: HashMap::new(),
             delimiter: b',',
             file_extension: DEFAULT_CSV_EXTENSION,
             table_partition_cols: vec![],
+            error_policy: ScanErrorPolicy::default(),
         }
     }
 
@@ -117,12 +131,37 @@ impl<'a> CsvReadOptions<'a> {
         self
     }
 
+    /// Configure whether conflicting inferred types are widened
+    pub fn type_widening(mut self, type_widening: bool) -> Self {
+        self.type_widening = type_widening;
+        self
+    }
+
+    /// Force the type of a column during schema inference
+    pub fn column_type_override(
+        mut self,
+        column: impl Into<String>,
+        data_type: DataType,
+    ) -> Self {
+        self.column_type_overrides.insert(column.into(), data_type);
+        self
+    }
+
+    /// Configure the policy applied when a file or a record cannot be read
+    pub fn error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
     /// Helper to convert these user facing options to `ListingTable` options
     pub fn to_listing_options(&self, target_partitions: usize) -> ListingOptions {
         let file_format = CsvFormat::default()
             .with_has_header(self.has_header)
             .with_delimiter(self.delimiter)
-            .with_schema_infer_max_rec(Some(self.schema_infer_max_records));
+            .with_schema_infer_max_rec(Some(self.schema_infer_max_records))
+            .with_type_widening(self.type_widening)
+            .with_column_type_overrides(self.column_type_overrides.clone())
+            .with_error_policy(self.error_policy);
 
         ListingOptions {
             format: Arc::new(file_format),
@@ -130,6 +169,7 @@ impl<'a> CsvReadOptions<'a> {
             file_extension: self.file_extension.to_owned(),
             target_partitions,
             table_partition_cols: self.table_partition_cols.clone(),
+            file_sort_order: vec![],
         }
     }
 }
@@ -145,6 +185,12 @@ pub struct ParquetReadOptions<'a> {
     /// Should DataFusion parquet reader using the predicate to prune data,
     /// overridden by value on execution::context::SessionConfig
     pub parquet_pruning: bool,
+    /// What to do when a file cannot be read. Defaults to
+    /// `ScanErrorPolicy::Fail`.
+    pub error_policy: ScanErrorPolicy,
+    /// Resolve the columns of the files by their Parquet field ID rather than
+    /// by their name. Defaults to false.
+    pub field_id_matching: bool,
 }
 
 impl<'a> Default for ParquetReadOptions<'a> {
@@ -153,6 +199,8 @@ impl<'a> Default for ParquetReadOptions<'a> {
             file_extension: DEFAULT_PARQUET_EXTENSION,
             table_partition_cols: vec![],
             parquet_pruning: ParquetFormat::default().enable_pruning(),
+            error_policy: ScanErrorPolicy::default(),
+            field_id_matching: ParquetFormat::default().field_id_matching(),
         }
     }
 }
@@ -170,10 +218,25 @@ impl<'a> ParquetReadOptions<'a> {
         self
     }
 
+    /// Configure the policy applied when a file cannot be read
+    pub fn error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// Resolve the columns of the files by their Parquet field ID, see
+    /// [`ParquetFormat::with_field_id_matching`]
+    pub fn field_id_matching(mut self, field_id_matching: bool) -> Self {
+        self.field_id_matching = field_id_matching;
+        self
+    }
+
     /// Helper to convert these user facing options to `ListingTable` options
     pub fn to_listing_options(&self, target_partitions: usize) -> ListingOptions {
-        let file_format =
-            ParquetFormat::default().with_enable_pruning(self.parquet_pruning);
+        let file_format = ParquetFormat::default()
+            .with_enable_pruning(self.parquet_pruning)
+            .with_error_policy(self.error_policy)
+            .with_field_id_matching(self.field_id_matching);
 
         ListingOptions {
             format: Arc::new(file_format),
@@ -181,6 +244,7 @@ impl<'a> ParquetReadOptions<'a> {
             file_extension: self.file_extension.to_owned(),
             target_partitions,
             table_partition_cols: self.table_partition_cols.clone(),
+            file_sort_order: vec![],
         }
     }
 }
@@ -196,6 +260,9 @@ pub struct AvroReadOptions<'a> {
     pub file_extension: &'a str,
     /// Partition Columns
     pub table_partition_cols: Vec<String>,
+    /// What to do when a file cannot be read. Defaults to
+    /// `ScanErrorPolicy::Fail`.
+    pub error_policy: ScanErrorPolicy,
 }
 
 impl<'a> Default for AvroReadOptions<'a> {
@@ -204,6 +271,7 @@ impl<'a> Default for AvroReadOptions<'a> {
             schema: None,
             file_extension: DEFAULT_AVRO_EXTENSION,
             table_partition_cols: vec![],
+            error_policy: ScanErrorPolicy::default(),
         }
     }
 }
@@ -215,9 +283,15 @@ impl<'a> AvroReadOptions<'a> {
         self
     }
 
+    /// Configure the policy applied when a file cannot be read
+    pub fn error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
     /// Helper to convert these user facing options to `ListingTable` options
     pub fn to_listing_options(&self, target_partitions: usize) -> ListingOptions {
-        let file_format = AvroFormat::default();
+        let file_format = AvroFormat::default().with_error_policy(self.error_policy);
 
         ListingOptions {
             format: Arc::new(file_format),
@@ -225,6 +299,7 @@ impl<'a> AvroReadOptions<'a> {
             file_extension: self.file_extension.to_owned(),
             target_partitions,
             table_partition_cols: self.table_partition_cols.clone(),
+            file_sort_order: vec![],
         }
     }
 }
@@ -237,12 +312,21 @@ pub struct NdJsonReadOptions<'a> {
 
     /// Max number of rows to read from JSON files for schema inference if needed. Defaults to `DEFAULT_SCHEMA_INFER_MAX_RECORD`.
     pub schema_infer_max_records: usize,
+    /// Widen the types of columns whose inferred type differs between files
+    /// instead of failing schema inference. Defaults to false.
+    pub type_widening: bool,
+    /// Types forced for some columns during schema inference, keyed by
+    /// column name. Defaults to no override.
+    pub column_type_overrides: HashMap<String, DataType>,
 
     /// File extension; only files with this extension are selected for data input.
     /// Defaults to DEFAULT_JSON_EXTENSION.
     pub file_extension: &'a str,
     /// Partition Columns
     pub table_partition_cols: Vec<String>,
+    /// What to do when a file or a record cannot be read. Defaults to
+    /// `ScanErrorPolicy::Fail`.
+    pub error_policy: ScanErrorPolicy,
 }
 
 impl<'a> Default for NdJsonReadOptions<'a> {
@@ -250,8 +334,11 @@ impl<'a> Default for NdJsonReadOptions<'a> {
         Self {
             schema: None,
             schema_infer_max_records: DEFAULT_SCHEMA_INFER_MAX_RECORD,
+            type_widening: false,
+            column_type_overrides: HashMap::new(),
             file_extension: DEFAULT_JSON_EXTENSION,
             table_partition_cols: vec![],
+            error_policy: ScanErrorPolicy::default(),
         }
     }
 }
@@ -263,15 +350,48 @@ impl<'a> NdJsonReadOptions<'a> {
         self
     }
 
+    /// Configure number of max records to read for schema inference
+    pub fn schema_infer_max_records(mut self, max_records: usize) -> Self {
+        self.schema_infer_max_records = max_records;
+        self
+    }
+
+    /// Configure whether conflicting inferred types are widened
+    pub fn type_widening(mut self, type_widening: bool) -> Self {
+        self.type_widening = type_widening;
+        self
+    }
+
+    /// Force the type of a column during schema inference
+    pub fn column_type_override(
+        mut self,
+        column: impl Into<String>,
+        data_type: DataType,
+    ) -> Self {
+        self.column_type_overrides.insert(column.into(), data_type);
+        self
+    }
+
+    /// Configure the policy applied when a file or a record cannot be read
+    pub fn error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
     /// Helper to convert these user facing options to `ListingTable` options
     pub fn to_listing_options(&self, target_partitions: usize) -> ListingOptions {
-        let file_format = JsonFormat::default();
+        let file_format = JsonFormat::default()
+            .with_schema_infer_max_rec(Some(self.schema_infer_max_records))
+            .with_type_widening(self.type_widening)
+            .with_column_type_overrides(self.column_type_overrides.clone())
+            .with_error_policy(self.error_policy);
         ListingOptions {
             format: Arc::new(file_format),
             collect_stat: false,
             file_extension: self.file_extension.to_owned(),
             target_partitions,
             table_partition_cols: self.table_partition_cols.clone(),
+            file_sort_order: vec![],
         }
     }
 }
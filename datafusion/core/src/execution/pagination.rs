@@ -0,0 +1,413 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Pagination of the results of queries, e.g. to display them in a UI.
+//!
+//! A [`ResultStore`] executes a query once and buffers its results, spilling
+//! them to disk beyond its memory limit or once the memory pool of the
+//! runtime is exhausted. The pages of the results are then
+//! served by cursor, offset and page size without executing the query again,
+//! until the results are removed or expire.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use futures::StreamExt;
+use log::debug;
+use parking_lot::Mutex;
+use tempfile::NamedTempFile;
+use uuid::Uuid;
+
+use crate::dataframe::DataFrame;
+use crate::error::{DataFusionError, Result};
+use crate::execution::runtime_env::RuntimeEnv;
+use crate::physical_plan::common::{
+    batch_byte_size, read_spill_file, write_spill_file, SpillCompression,
+};
+use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MemTrackingMetrics};
+
+/// Default time the results are kept after their last access
+pub const DEFAULT_RESULT_TTL: Duration = Duration::from_secs(300);
+
+/// Default number of bytes of the results of a query kept in memory
+pub const DEFAULT_RESULT_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// A page of the results of a query
+#[derive(Debug, Clone)]
+pub struct ResultPage {
+    /// The cursor of the results
+    pub cursor: String,
+    /// Index of the first row of the page in the results
+    pub offset: usize,
+    /// The rows of the page
+    pub batches: Vec<RecordBatch>,
+    /// Number of rows of the results
+    pub total_rows: usize,
+}
+
+impl ResultPage {
+    /// Number of rows of the page
+    pub fn num_rows(&self) -> usize {
+        self.batches.iter().map(|b| b.num_rows()).sum()
+    }
+
+    /// Offset of the next page, if the results have more rows
+    pub fn next_offset(&self) -> Option<usize> {
+        let next = self.offset + self.num_rows();
+        if next < self.total_rows {
+            Some(next)
+        } else {
+            None
+        }
+    }
+}
+
+/// Consecutive batches of the results, in memory or spilled to disk
+#[derive(Debug)]
+enum ResultChunk {
+    Memory(Vec<RecordBatch>),
+    Spilled(NamedTempFile),
+}
+
+/// The buffered results of a query
+#[derive(Debug)]
+struct BufferedResult {
+    schema: SchemaRef,
+    /// The chunks, with the index of their first row
+    chunks: Vec<(usize, ResultChunk)>,
+    total_rows: usize,
+    compression: SpillCompression,
+    last_access: Mutex<Instant>,
+    /// The memory of the chunks in memory, registered with the memory
+    /// manager of the runtime until the results are dropped
+    tracking_metrics: MemTrackingMetrics,
+}
+
+impl BufferedResult {
+    /// The rows `offset..offset + len` of the results, reading the spilled
+    /// chunks they overlap
+    fn rows(&self, offset: usize, len: usize) -> Result<Vec<RecordBatch>> {
+        let end = offset.saturating_add(len).min(self.total_rows);
+        let mut batches = vec![];
+        for (i, (first_row, chunk)) in self.chunks.iter().enumerate() {
+            let chunk_end = self
+                .chunks
+                .get(i + 1)
+                .map(|(next, _)| *next)
+                .unwrap_or(self.total_rows);
+            if chunk_end <= offset || *first_row >= end {
+                continue;
+            }
+            let mut batch_offset = *first_row;
+            let mut slice = |batch: &RecordBatch| {
+                let batch_end = batch_offset + batch.num_rows();
+                if batch_end > offset && batch_offset < end {
+                    let start = offset.max(batch_offset);
+                    let len = end.min(batch_end) - start;
+                    batches.push(batch.slice(start - batch_offset, len));
+                }
+                batch_offset = batch_end;
+            };
+            match chunk {
+                ResultChunk::Memory(chunk_batches) => {
+                    chunk_batches.iter().for_each(slice)
+                }
+                ResultChunk::Spilled(file) => {
+                    for batch in read_spill_file(file.path(), self.compression)? {
+                        slice(&batch?);
+                    }
+                }
+            }
+        }
+        Ok(batches)
+    }
+}
+
+/// Buffers the results of queries and serves their pages by cursor.
+///
+/// The results expire once they haven't been accessed for the TTL of the
+/// store. The expired results are removed by the following calls, or by
+/// [`ResultStore::remove_expired`].
+#[derive(Debug)]
+pub struct ResultStore {
+    runtime: Arc<RuntimeEnv>,
+    ttl: Duration,
+    memory_limit: usize,
+    results: Mutex<HashMap<String, Arc<BufferedResult>>>,
+}
+
+impl ResultStore {
+    /// Create an empty store, spilling the results to the disk of `runtime`
+    pub fn new(runtime: Arc<RuntimeEnv>) -> Self {
+        Self {
+            runtime,
+            ttl: DEFAULT_RESULT_TTL,
+            memory_limit: DEFAULT_RESULT_MEMORY_LIMIT,
+            results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Keep the results for `ttl` after their last access
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Spill the results of a query to disk once their batches in memory
+    /// exceed `memory_limit` bytes. The batches in memory are registered
+    /// with the memory manager of the runtime, and spilled as well once its
+    /// memory pool is exhausted.
+    pub fn with_memory_limit(mut self, memory_limit: usize) -> Self {
+        self.memory_limit = memory_limit;
+        self
+    }
+
+    /// The time the results are kept after their last access
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The number of bytes of the results of a query kept in memory
+    pub fn memory_limit(&self) -> usize {
+        self.memory_limit
+    }
+
+    /// Executes `df` and buffers its results, returning their first page of
+    /// `page_size` rows. The following pages are returned by [`Self::page`]
+    /// with the cursor of the page.
+    pub async fn execute(&self, df: &DataFrame, page_size: usize) -> Result<ResultPage> {
+        self.remove_expired();
+        let mut stream = df.execute_stream().await?;
+        let schema = stream.schema();
+        let compression = self.runtime.spill_compression;
+        let tracking_metrics = MemTrackingMetrics::new_with_rt(
+            &ExecutionPlanMetricsSet::new(),
+            0,
+            self.runtime.clone(),
+        )
+        .with_name("ResultStore");
+
+        let mut chunks = vec![];
+        let mut chunk_first_row = 0;
+        let mut in_memory = vec![];
+        let mut in_memory_size = 0;
+        let mut total_rows = 0;
+        while let Some(batch) = stream.next().await {
+            let batch = batch?;
+            total_rows += batch.num_rows();
+            in_memory_size += batch_byte_size(&batch);
+            in_memory.push(batch);
+            let in_memory_kept = in_memory_size <= self.memory_limit
+                && tracking_metrics.try_set_mem_used(in_memory_size).is_ok();
+            if !in_memory_kept {
+                let file = self.runtime.disk_manager.create_tmp_file()?;
+                let batches = std::mem::take(&mut in_memory);
+                write_spill_file(
+                    file.path(),
+                    &schema,
+                    compression,
+                    batches.into_iter().map(Ok),
+                )?;
+                debug!(
+                    "Spilled {} bytes of results to {:?}",
+                    in_memory_size,
+                    file.path()
+                );
+                chunks.push((chunk_first_row, ResultChunk::Spilled(file)));
+                chunk_first_row = total_rows;
+                in_memory_size = 0;
+                tracking_metrics.set_mem_used(0);
+            }
+        }
+        if !in_memory.is_empty() {
+            chunks.push((chunk_first_row, ResultChunk::Memory(in_memory)));
+        }
+
+        let result = Arc::new(BufferedResult {
+            schema,
+            chunks,
+            total_rows,
+            compression,
+            last_access: Mutex::new(Instant::now()),
+            tracking_metrics,
+        });
+        let cursor = Uuid::new_v4().to_string();
+        debug!(
+            "Buffered {} rows of results as {}, {} bytes in memory",
+            result.total_rows,
+            cursor,
+            result.tracking_metrics.mem_used()
+        );
+        self.results.lock().insert(cursor.clone(), result.clone());
+        Self::page_of(cursor, &result, 0, page_size)
+    }
+
+    /// The page of `page_size` rows starting at the row `offset` of the
+    /// results of `cursor`, which is empty past their end
+    pub fn page(
+        &self,
+        cursor: &str,
+        offset: usize,
+        page_size: usize,
+    ) -> Result<ResultPage> {
+        self.remove_expired();
+        let result = self.results.lock().get(cursor).cloned().ok_or_else(|| {
+            DataFusionError::Execution(format!(
+                "Unknown or expired result cursor {}",
+                cursor
+            ))
+        })?;
+        *result.last_access.lock() = Instant::now();
+        Self::page_of(cursor.to_string(), &result, offset, page_size)
+    }
+
+    fn page_of(
+        cursor: String,
+        result: &BufferedResult,
+        offset: usize,
+        page_size: usize,
+    ) -> Result<ResultPage> {
+        Ok(ResultPage {
+            cursor,
+            offset,
+            batches: result.rows(offset, page_size)?,
+            total_rows: result.total_rows,
+        })
+    }
+
+    /// The schema of the results of `cursor`, if they are buffered
+    pub fn schema(&self, cursor: &str) -> Option<SchemaRef> {
+        self.results.lock().get(cursor).map(|r| r.schema.clone())
+    }
+
+    /// Removes the results of `cursor`, returning whether they were buffered
+    pub fn remove(&self, cursor: &str) -> bool {
+        self.results.lock().remove(cursor).is_some()
+    }
+
+    /// Removes the expired results, returning their number
+    pub fn remove_expired(&self) -> usize {
+        let mut results = self.results.lock();
+        let len = results.len();
+        results.retain(|_, result| result.last_access.lock().elapsed() < self.ttl);
+        len - results.len()
+    }
+
+    /// Number of the results buffered, including the expired ones not
+    /// removed yet
+    pub fn len(&self) -> usize {
+        self.results.lock().len()
+    }
+
+    /// Returns true if no results are buffered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::execution::runtime_env::RuntimeConfig;
+    use crate::prelude::SessionContext;
+
+    async fn paginated_results(store: &ResultStore) -> Result<ResultPage> {
+        let ctx = SessionContext::new();
+        let df = ctx
+            .sql("SELECT column1 AS a FROM (VALUES (1), (2), (3), (4), (5), (6), (7))")
+            .await?;
+        store.execute(&df, 3).await
+    }
+
+    #[tokio::test]
+    async fn pages_by_cursor() -> Result<()> {
+        let ctx = SessionContext::new();
+        // spill every batch
+        let store = ResultStore::new(ctx.runtime_env()).with_memory_limit(0);
+        let page = paginated_results(&store).await?;
+        assert_eq!(page.total_rows, 7);
+        assert_eq!(page.num_rows(), 3);
+        assert_eq!(page.next_offset(), Some(3));
+
+        let page = store.page(&page.cursor, 3, 3)?;
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 4 |", "| 5 |", "| 6 |", "+---+",
+        ];
+        assert_batches_eq!(expected, &page.batches);
+        assert_eq!(page.next_offset(), Some(6));
+
+        let page = store.page(&page.cursor, 6, 3)?;
+        assert_eq!(page.num_rows(), 1);
+        assert_eq!(page.next_offset(), None);
+        let page = store.page(&page.cursor, 10, 3)?;
+        assert_eq!(page.num_rows(), 0);
+
+        assert!(store.remove(&page.cursor));
+        let err = store.page(&page.cursor, 0, 3).unwrap_err();
+        assert!(err.to_string().contains("Unknown or expired result cursor"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn expired_results() -> Result<()> {
+        let ctx = SessionContext::new();
+        let store = ResultStore::new(ctx.runtime_env()).with_ttl(Duration::ZERO);
+        let page = paginated_results(&store).await?;
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+",
+        ];
+        assert_batches_eq!(expected, &page.batches);
+        assert_eq!(store.len(), 1);
+
+        assert_eq!(store.remove_expired(), 1);
+        assert!(store.is_empty());
+        assert!(store.page(&page.cursor, 0, 3).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn results_in_memory_pool() -> Result<()> {
+        // the results in memory are registered with the memory manager
+        let ctx = SessionContext::new();
+        let runtime = ctx.runtime_env();
+        let store = ResultStore::new(runtime.clone());
+        let page = paginated_results(&store).await?;
+        let consumers = runtime.memory_manager.top_consumers(1);
+        assert!(consumers[0].0.starts_with("ResultStore"), "{:?}", consumers);
+        assert_eq!(store.page(&page.cursor, 2, usize::MAX)?.num_rows(), 5);
+        assert!(store.remove(&page.cursor));
+        assert!(runtime.memory_manager.top_consumers(1).is_empty());
+
+        // and spilled once the memory pool is exhausted
+        let runtime = Arc::new(RuntimeEnv::new(
+            RuntimeConfig::new().with_memory_limit(1, 1.0),
+        )?);
+        let store = ResultStore::new(runtime.clone());
+        let page = paginated_results(&store).await?;
+        assert!(runtime.memory_manager.top_consumers(1).is_empty());
+        let page = store.page(&page.cursor, 3, usize::MAX)?;
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 4 |", "| 5 |", "| 6 |", "| 7 |", "+---+",
+        ];
+        assert_batches_eq!(expected, &page.batches);
+        Ok(())
+    }
+}
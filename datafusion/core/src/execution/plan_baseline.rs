@@ -0,0 +1,234 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Baselines of the optimized plans of queries, to detect the changes of
+//! their plans, e.g. a different join order after the statistics of a table
+//! changed.
+//!
+//! With [`PlanBaselines`] registered with
+//! [`SessionState::with_plan_baselines`], the first optimized plan of each
+//! query fingerprint is captured as its baseline, and the
+//! [`PlanChangeListener`] is notified whenever a later optimized plan of the
+//! same fingerprint differs from it. The fingerprint of a query is the
+//! display of its plan before optimization, with its literals redacted (see
+//! [`sanitize_plan`]), so that the queries differing only by their literals
+//! share their baseline.
+//!
+//! [`SessionState::with_plan_baselines`]: crate::execution::context::SessionState::with_plan_baselines
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use log::warn;
+use parking_lot::RwLock;
+
+use crate::error::Result;
+use crate::logical_plan::{sanitize_plan, LogicalPlan};
+
+/// The optimized plan of a query differing from the baseline of its
+/// fingerprint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanChange {
+    /// The fingerprint of the query
+    pub fingerprint: String,
+    /// The indented display of the baseline plan
+    pub baseline: String,
+    /// The indented display of the new plan
+    pub plan: String,
+}
+
+/// Notified of the plans differing from their baseline, e.g. to raise an
+/// alert or to record the plan regressions
+pub trait PlanChangeListener: Debug + Send + Sync {
+    /// Called when the optimized plan of a query differs from the baseline
+    /// of its fingerprint, while the query is planned
+    fn plan_changed(&self, change: &PlanChange);
+}
+
+/// A [`PlanChangeListener`] logging a warning for each plan change, the
+/// default
+#[derive(Debug, Default)]
+pub struct LogPlanChangeListener;
+
+impl PlanChangeListener for LogPlanChangeListener {
+    fn plan_changed(&self, change: &PlanChange) {
+        warn!(
+            "The plan of the query\n{}\nchanged from\n{}\nto\n{}",
+            change.fingerprint, change.baseline, change.plan
+        );
+    }
+}
+
+/// The baseline optimized plans of the query fingerprints, see the
+/// [module documentation](self)
+#[derive(Debug)]
+pub struct PlanBaselines {
+    /// The indented display of the baseline plan of each fingerprint
+    baselines: RwLock<HashMap<String, String>>,
+    /// Notified of the plan changes
+    listener: Arc<dyn PlanChangeListener>,
+}
+
+impl Default for PlanBaselines {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PlanBaselines {
+    /// Create empty baselines, logging the plan changes
+    pub fn new() -> Self {
+        Self {
+            baselines: RwLock::new(HashMap::new()),
+            listener: Arc::new(LogPlanChangeListener),
+        }
+    }
+
+    /// Replace the listener notified of the plan changes
+    pub fn with_listener(mut self, listener: Arc<dyn PlanChangeListener>) -> Self {
+        self.listener = listener;
+        self
+    }
+
+    /// Returns the baseline plan of `fingerprint`, if captured
+    pub fn baseline(&self, fingerprint: &str) -> Option<String> {
+        self.baselines.read().get(fingerprint).cloned()
+    }
+
+    /// Returns the baseline plans by fingerprint, e.g. to persist them
+    pub fn baselines(&self) -> HashMap<String, String> {
+        self.baselines.read().clone()
+    }
+
+    /// Sets the baseline plan of `fingerprint`, e.g. to restore persisted
+    /// baselines, replacing the captured one
+    pub fn set_baseline(&self, fingerprint: impl Into<String>, plan: impl Into<String>) {
+        self.baselines
+            .write()
+            .insert(fingerprint.into(), plan.into());
+    }
+
+    /// Removes the baseline plan of `fingerprint`, so that its next plan is
+    /// captured as its baseline, e.g. to accept a plan change
+    pub fn remove_baseline(&self, fingerprint: &str) -> Option<String> {
+        self.baselines.write().remove(fingerprint)
+    }
+
+    /// Compares the plan `optimized`, optimized from `query`, with the
+    /// baseline of the fingerprint of `query`, capturing it as the baseline
+    /// if there is none. Returns the change notified to the listener if the
+    /// plan differs from the baseline, which is kept.
+    pub fn check(
+        &self,
+        query: &LogicalPlan,
+        optimized: &LogicalPlan,
+    ) -> Result<Option<PlanChange>> {
+        let fingerprint = sanitize_plan(query)?.fingerprint;
+        // the literals of the optimized plan are redacted too, the queries of
+        // a fingerprint differing by their literals
+        let plan = sanitize_plan(optimized)?.fingerprint;
+
+        let change = {
+            let mut baselines = self.baselines.write();
+            match baselines.get(&fingerprint) {
+                Some(baseline) if *baseline != plan => Some(PlanChange {
+                    fingerprint,
+                    baseline: baseline.clone(),
+                    plan,
+                }),
+                Some(_) => None,
+                None => {
+                    baselines.insert(fingerprint, plan);
+                    None
+                }
+            }
+        };
+        if let Some(change) = &change {
+            self.listener.plan_changed(change);
+        }
+        Ok(change)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::MemTable;
+    use crate::prelude::SessionContext;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use parking_lot::Mutex;
+
+    /// Records the plan changes
+    #[derive(Debug, Default)]
+    struct RecordingListener {
+        changes: Mutex<Vec<PlanChange>>,
+    }
+
+    impl PlanChangeListener for RecordingListener {
+        fn plan_changed(&self, change: &PlanChange) {
+            self.changes.lock().push(change.clone());
+        }
+    }
+
+    #[test]
+    fn plan_change_detected() -> Result<()> {
+        let listener = Arc::new(RecordingListener::default());
+        let baselines = Arc::new(PlanBaselines::new().with_listener(listener.clone()));
+        let ctx = SessionContext::new();
+        {
+            let mut state = ctx.state.write();
+            *state = state.clone().with_plan_baselines(baselines.clone());
+        }
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        ctx.register_table("t", Arc::new(MemTable::try_new(schema, vec![vec![]])?))?;
+
+        // the first plan is captured, the queries differing by their
+        // literals share it
+        let plan = ctx.create_logical_plan("SELECT a FROM t WHERE b > 1")?;
+        ctx.optimize(&plan)?;
+        assert_eq!(baselines.baselines().len(), 1);
+        let plan = ctx.create_logical_plan("SELECT a FROM t WHERE b > 2")?;
+        ctx.optimize(&plan)?;
+        assert!(listener.changes.lock().is_empty());
+
+        // without the optimizer rules, the plan changes
+        ctx.state.write().optimizers.clear();
+        ctx.optimize(&plan)?;
+        let changes = listener.changes.lock().clone();
+        assert_eq!(changes.len(), 1);
+        let change = &changes[0];
+        assert_eq!(
+            baselines.baseline(&change.fingerprint),
+            Some(change.baseline.clone())
+        );
+        assert_ne!(change.plan, change.baseline);
+
+        // once removed, the new plan is the baseline
+        baselines.remove_baseline(&change.fingerprint);
+        ctx.optimize(&plan)?;
+        assert_eq!(
+            baselines.baseline(&change.fingerprint),
+            Some(change.plan.clone())
+        );
+        assert_eq!(listener.changes.lock().len(), 1);
+        Ok(())
+    }
+}
@@ -21,14 +21,21 @@
 use crate::{
     error::Result,
     execution::{
-        disk_manager::{DiskManager, DiskManagerConfig},
+        disk_manager::{DiskManager, DiskManagerConfig, SpillCompression},
+        executor::{Executor, TokioExecutor},
         memory_manager::{MemoryConsumerId, MemoryManager, MemoryManagerConfig},
     },
 };
 
+use crate::datasource::instrumented_object_store::{
+    InstrumentedObjectStore, ObjectStoreMetrics, ObjectStoreRequestConfig,
+};
+use crate::datasource::limited_object_store::{
+    LimitedObjectStore, ObjectStoreRequestLimits, RequestLimiter,
+};
 use crate::datasource::object_store_registry::ObjectStoreRegistry;
 use datafusion_common::DataFusionError;
-use datafusion_data_access::object_store::ObjectStore;
+use datafusion_data_access::object_store::{ObjectStore, LOCAL_SCHEME};
 use std::fmt::{Debug, Formatter};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -42,6 +49,17 @@ pub struct RuntimeEnv {
     pub disk_manager: Arc<DiskManager>,
     /// Object Store Registry
     pub object_store_registry: Arc<ObjectStoreRegistry>,
+    /// Compression codec of the spill files
+    pub spill_compression: SpillCompression,
+    /// Retries, timeouts and metrics of the requests to the registered object
+    /// stores, if enabled
+    pub object_store_requests: Option<ObjectStoreRequestConfig>,
+    /// Metrics of the requests to the registered object stores
+    pub object_store_metrics: Arc<ObjectStoreMetrics>,
+    /// Limiter of the requests to all the registered object stores, if any
+    pub object_store_limiter: Option<Arc<RequestLimiter>>,
+    /// Executor of the tasks spawned by the operators
+    pub executor: Arc<dyn Executor>,
 }
 
 impl Debug for RuntimeEnv {
@@ -56,13 +74,29 @@ impl RuntimeEnv {
         let RuntimeConfig {
             memory_manager,
             disk_manager,
+            spill_compression,
+            object_store_requests,
+            object_store_limits,
+            executor,
         } = config;
 
-        Ok(Self {
+        let env = Self {
             memory_manager: MemoryManager::new(memory_manager),
             disk_manager: DiskManager::try_new(disk_manager)?,
             object_store_registry: Arc::new(ObjectStoreRegistry::new()),
-        })
+            spill_compression,
+            object_store_requests,
+            object_store_metrics: Arc::new(ObjectStoreMetrics::new()),
+            object_store_limiter: object_store_limits
+                .map(|limits| Arc::new(RequestLimiter::new(limits))),
+            executor: executor.unwrap_or_else(|| Arc::new(TokioExecutor)),
+        };
+        // the local file system, registered by default, is instrumented and
+        // limited like the stores registered later
+        if let Some(local) = env.object_store_registry.get(LOCAL_SCHEME) {
+            env.register_object_store(LOCAL_SCHEME, local);
+        }
+        Ok(env)
     }
 
     /// Register the consumer to get it tracked
@@ -88,6 +122,11 @@ impl RuntimeEnv {
     /// Registers a object store with scheme using a custom `ObjectStore` so that
     /// an external file system or object storage system could be used against this context.
     ///
+    /// If [`RuntimeConfig::with_object_store_requests`] was set, the store is
+    /// wrapped in an [`InstrumentedObjectStore`] recording its requests in
+    /// `object_store_metrics`. If [`RuntimeConfig::with_object_store_limits`]
+    /// was set, the requests of the store count towards these global limits.
+    ///
     /// Returns the `ObjectStore` previously registered for this scheme, if any
     pub fn register_object_store(
         &self,
@@ -95,6 +134,20 @@ impl RuntimeEnv {
         object_store: Arc<dyn ObjectStore>,
     ) -> Option<Arc<dyn ObjectStore>> {
         let scheme = scheme.into();
+        let object_store: Arc<dyn ObjectStore> = match &self.object_store_limiter {
+            Some(limiter) => {
+                Arc::new(LimitedObjectStore::new(object_store, limiter.clone()))
+            }
+            None => object_store,
+        };
+        let object_store: Arc<dyn ObjectStore> = match &self.object_store_requests {
+            Some(config) => Arc::new(InstrumentedObjectStore::new(
+                object_store,
+                config.clone(),
+                self.object_store_metrics.clone(),
+            )),
+            None => object_store,
+        };
         self.object_store_registry
             .register_store(scheme, object_store)
     }
@@ -123,6 +176,16 @@ pub struct RuntimeConfig {
     pub disk_manager: DiskManagerConfig,
     /// MemoryManager to limit access to memory
     pub memory_manager: MemoryManagerConfig,
+    /// Compression codec of the spill files
+    pub spill_compression: SpillCompression,
+    /// Retries, timeouts and metrics of the requests to the registered object
+    /// stores, disabled by default
+    pub object_store_requests: Option<ObjectStoreRequestConfig>,
+    /// Limits of the requests to all the registered object stores, shared by
+    /// all the queries of the runtime. Unlimited by default
+    pub object_store_limits: Option<ObjectStoreRequestLimits>,
+    /// Executor of the tasks spawned by the operators, tokio by default
+    pub executor: Option<Arc<dyn Executor>>,
 }
 
 impl RuntimeConfig {
@@ -153,8 +216,57 @@ impl RuntimeConfig {
         )
     }
 
+    /// Customize the compression codec of the spill files
+    pub fn with_spill_compression(mut self, spill_compression: SpillCompression) -> Self {
+        self.spill_compression = spill_compression;
+        self
+    }
+
+    /// Retry the failed requests to the registered object stores, bound
+    /// their duration and record their metrics as configured by `config`
+    pub fn with_object_store_requests(
+        mut self,
+        config: ObjectStoreRequestConfig,
+    ) -> Self {
+        self.object_store_requests = Some(config);
+        self
+    }
+
+    /// Limit the requests to all the registered object stores. See
+    /// [`SessionConfig::with_object_store_limits`] for per query limits
+    ///
+    /// [`SessionConfig::with_object_store_limits`]: crate::execution::context::SessionConfig::with_object_store_limits
+    pub fn with_object_store_limits(mut self, limits: ObjectStoreRequestLimits) -> Self {
+        self.object_store_limits = Some(limits);
+        self
+    }
+
+    /// Spawn the tasks of the operators on `executor` rather than on tokio
+    pub fn with_executor(mut self, executor: Arc<dyn Executor>) -> Self {
+        self.executor = Some(executor);
+        self
+    }
+
     /// Use the specified path to create any needed temporary files
     pub fn with_temp_file_path(self, path: impl Into<PathBuf>) -> Self {
         self.with_disk_manager(DiskManagerConfig::new_specified(vec![path.into()]))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn instrument_default_local_store() -> Result<()> {
+        let config = RuntimeConfig::new()
+            .with_object_store_requests(ObjectStoreRequestConfig::new());
+        let env = RuntimeEnv::new(config)?;
+        let (store, path) = env.object_store("Cargo.toml")?;
+        let files = store.list_file(path).await?.collect::<Vec<_>>().await;
+        assert_eq!(files.len(), 1);
+        assert_eq!(env.object_store_metrics.requests(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Handling of the transaction control statements (`BEGIN`, `COMMIT` and
+//! `ROLLBACK`).
+//!
+//! DataFusion has no transactions of its own: by default, these statements
+//! are accepted and do nothing, so that the SQL clients sending them (e.g.
+//! around each query) can be used. Table providers with write transactions
+//! can take part in them with a [`TransactionHandler`], see
+//! [`SessionState::with_transaction_handler`].
+//!
+//! The statements of a transaction resolve the tables in a snapshot of the
+//! catalogs captured when it begins, see
+//! [`SessionState::capture_catalog_snapshot`].
+//!
+//! [`SessionState::with_transaction_handler`]: crate::execution::context::SessionState::with_transaction_handler
+//! [`SessionState::capture_catalog_snapshot`]: crate::execution::context::SessionState::capture_catalog_snapshot
+
+use std::fmt::Debug;
+
+use crate::error::Result;
+use crate::logical_plan::TransactionStatement;
+
+/// Executes the transaction control statements of a session
+pub trait TransactionHandler: Debug + Send + Sync {
+    /// Starts a transaction, on `BEGIN` or `START TRANSACTION`
+    fn begin(&self) -> Result<()>;
+
+    /// Commits the current transaction, on `COMMIT`
+    fn commit(&self) -> Result<()>;
+
+    /// Rolls back the current transaction, on `ROLLBACK`
+    fn rollback(&self) -> Result<()>;
+
+    /// Executes the transaction control `statement`
+    fn execute(&self, statement: TransactionStatement) -> Result<()> {
+        match statement {
+            TransactionStatement::Begin => self.begin(),
+            TransactionStatement::Commit => self.commit(),
+            TransactionStatement::Rollback => self.rollback(),
+        }
+    }
+}
+
+/// A [`TransactionHandler`] accepting all the statements without doing
+/// anything, the default
+#[derive(Debug, Default)]
+pub struct NoOpTransactionHandler;
+
+impl TransactionHandler for NoOpTransactionHandler {
+    fn begin(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn commit(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn rollback(&self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Exchange of query results with non-Rust applications through the
+//! [Arrow C Stream interface](https://arrow.apache.org/docs/format/CStreamInterface.html),
+//! without copying the batches.
+//!
+//! Results are exported with [`export_stream`], and imported streams are
+//! queried through an [`ArrowArrayStreamTable`]. With the `c_api` feature,
+//! the [`c_api`] module also exposes a session to C callers.
+//!
+//! The arrays of the layouts of newer Arrow producers this version of Arrow
+//! can not import, e.g. the string views, are normalized by the streams
+//! wrapped with [`normalize_stream`].
+//!
+//! [`ArrowArrayStreamTable`]: crate::datasource::ffi_stream::ArrowArrayStreamTable
+//! [`normalize_stream`]: crate::datasource::ffi_compat::normalize_stream
+
+use arrow::datatypes::SchemaRef;
+use arrow::error::Result as ArrowResult;
+use arrow::ffi_stream::FFI_ArrowArrayStream;
+use arrow::record_batch::{RecordBatch, RecordBatchReader};
+use futures::StreamExt;
+use tokio::runtime::Handle;
+
+use crate::physical_plan::SendableRecordBatchStream;
+
+/// Exports `stream` as an `ArrowArrayStream`.
+///
+/// The consumer of the exported stream pulls the batches synchronously, by
+/// blocking on `runtime` until the next batch is ready: the stream must not
+/// be consumed from a thread running asynchronous tasks of `runtime`.
+pub fn export_stream(
+    stream: SendableRecordBatchStream,
+    runtime: Handle,
+) -> FFI_ArrowArrayStream {
+    FFI_ArrowArrayStream::new(Box::new(BlockingStreamReader { stream, runtime }))
+}
+
+/// Reads a [`SendableRecordBatchStream`] synchronously
+struct BlockingStreamReader {
+    stream: SendableRecordBatchStream,
+    runtime: Handle,
+}
+
+impl Iterator for BlockingStreamReader {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+impl RecordBatchReader for BlockingStreamReader {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+}
+
+/// A C API running SQL queries over `ArrowArrayStream`s.
+///
+/// ```c
+/// typedef struct DataFusionContext DataFusionContext;
+///
+/// DataFusionContext* datafusion_context_new(void);
+/// void datafusion_context_free(DataFusionContext* ctx);
+/// int datafusion_register_stream(DataFusionContext* ctx, const char* name,
+///                                struct ArrowArrayStream* stream);
+/// int datafusion_sql(DataFusionContext* ctx, const char* sql,
+///                    struct ArrowArrayStream* out);
+/// const char* datafusion_last_error(const DataFusionContext* ctx);
+/// ```
+///
+/// The functions returning an `int` return 0 on success, and a non zero
+/// value on failure, with the message of the error available from
+/// `datafusion_last_error` until the next call using the context.
+#[cfg(feature = "c_api")]
+pub mod c_api {
+    use std::ffi::{CStr, CString};
+    use std::os::raw::{c_char, c_int};
+    use std::ptr;
+    use std::sync::Arc;
+
+    use arrow::ffi_stream::FFI_ArrowArrayStream;
+    use tokio::runtime::Runtime;
+
+    use super::export_stream;
+    use crate::datasource::ffi_stream::ArrowArrayStreamTable;
+    use crate::error::{DataFusionError, Result};
+    use crate::execution::context::SessionContext;
+
+    /// A session driven from C, with the runtime executing its queries
+    pub struct DataFusionContext {
+        ctx: SessionContext,
+        runtime: Runtime,
+        last_error: Option<CString>,
+    }
+
+    impl DataFusionContext {
+        fn record<T>(&mut self, result: Result<T>) -> c_int {
+            match result {
+                Ok(_) => {
+                    self.last_error = None;
+                    0
+                }
+                Err(e) => {
+                    let message = e.to_string().replace('\0', " ");
+                    self.last_error = CString::new(message).ok();
+                    -1
+                }
+            }
+        }
+    }
+
+    unsafe fn to_str<'a>(s: *const c_char, what: &str) -> Result<&'a str> {
+        if s.is_null() {
+            return Err(DataFusionError::Execution(format!("{} is null", what)));
+        }
+        CStr::from_ptr(s).to_str().map_err(|e| {
+            DataFusionError::Execution(format!("{} is not valid UTF-8: {}", what, e))
+        })
+    }
+
+    /// Creates a context, or returns null if its runtime cannot be started.
+    /// The context must be freed with [`datafusion_context_free`].
+    #[no_mangle]
+    pub extern "C" fn datafusion_context_new() -> *mut DataFusionContext {
+        match Runtime::new() {
+            Ok(runtime) => Box::into_raw(Box::new(DataFusionContext {
+                ctx: SessionContext::new(),
+                runtime,
+                last_error: None,
+            })),
+            Err(_) => ptr::null_mut(),
+        }
+    }
+
+    /// Frees a context created by [`datafusion_context_new`]
+    ///
+    /// # Safety
+    /// `ctx` must be null or a context that was not freed yet.
+    #[no_mangle]
+    pub unsafe extern "C" fn datafusion_context_free(ctx: *mut DataFusionContext) {
+        if !ctx.is_null() {
+            drop(Box::from_raw(ctx));
+        }
+    }
+
+    /// Registers the stream `stream` points to as the table `name`, taking
+    /// ownership of the stream
+    ///
+    /// # Safety
+    /// `ctx` must be a live context, `name` a null terminated string and
+    /// `stream` must point to a valid `ArrowArrayStream`.
+    #[no_mangle]
+    pub unsafe extern "C" fn datafusion_register_stream(
+        ctx: *mut DataFusionContext,
+        name: *const c_char,
+        stream: *mut FFI_ArrowArrayStream,
+    ) -> c_int {
+        let ctx = &mut *ctx;
+        let result = to_str(name, "table name").and_then(|name| {
+            let table = ArrowArrayStreamTable::try_from_raw_with_config(
+                stream,
+                &ctx.ctx.copied_config(),
+            )?;
+            ctx.ctx.register_table(name, Arc::new(table))
+        });
+        ctx.record(result)
+    }
+
+    /// Runs the query `sql` and exports its results to `out`. The exported
+    /// stream must be released by the caller, before the context is freed.
+    ///
+    /// # Safety
+    /// `ctx` must be a live context, `sql` a null terminated string and
+    /// `out` must point to memory where an `ArrowArrayStream` can be written.
+    #[no_mangle]
+    pub unsafe extern "C" fn datafusion_sql(
+        ctx: *mut DataFusionContext,
+        sql: *const c_char,
+        out: *mut FFI_ArrowArrayStream,
+    ) -> c_int {
+        let ctx = &mut *ctx;
+        let result = to_str(sql, "query").and_then(|sql| {
+            let session = &ctx.ctx;
+            ctx.runtime.block_on(async {
+                let df = session.sql(sql).await?;
+                df.execute_stream().await
+            })
+        });
+        let result = result.map(|stream| {
+            let exported = export_stream(stream, ctx.runtime.handle().clone());
+            ptr::write(out, exported)
+        });
+        ctx.record(result)
+    }
+
+    /// The message of the error of the last failed call using `ctx`, or null
+    /// if the last call succeeded
+    ///
+    /// # Safety
+    /// `ctx` must be a live context.
+    #[no_mangle]
+    pub unsafe extern "C" fn datafusion_last_error(
+        ctx: *const DataFusionContext,
+    ) -> *const c_char {
+        match &(*ctx).last_error {
+            Some(message) => message.as_ptr(),
+            None => ptr::null(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::datasource::ffi_stream::ArrowArrayStreamTable;
+    use crate::datasource::MemTable;
+    use crate::error::Result;
+    use crate::prelude::SessionContext;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    #[test]
+    fn export_and_import_stream() -> Result<()> {
+        let runtime = tokio::runtime::Runtime::new()?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3])),
+                Arc::new(Int32Array::from(vec![4, 5, 6])),
+            ],
+        )?;
+
+        // export the results of a query
+        let ctx = SessionContext::new();
+        ctx.register_table("t", Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))?;
+        let stream = runtime.block_on(async {
+            ctx.sql("SELECT a, a + b AS c FROM t WHERE a > 1")
+                .await?
+                .execute_stream()
+                .await
+        })?;
+        let exported = export_stream(stream, runtime.handle().clone());
+
+        // and query them as a table of another context
+        let other_ctx = SessionContext::new();
+        let table = Arc::new(ArrowArrayStreamTable::try_new(exported)?);
+        other_ctx.register_table("exported", table.clone())?;
+        let query = "SELECT c FROM exported ORDER BY c DESC";
+        let batches =
+            runtime.block_on(async { other_ctx.sql(query).await?.collect().await })?;
+        let expected = vec!["+---+", "| c |", "+---+", "| 9 |", "| 7 |", "+---+"];
+        assert_batches_eq!(expected, &batches);
+        assert!(table.is_consumed());
+
+        // the stream can only be consumed once
+        let result =
+            runtime.block_on(async { other_ctx.sql(query).await?.collect().await });
+        assert!(result.is_err());
+        Ok(())
+    }
+}
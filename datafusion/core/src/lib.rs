@@ -212,6 +212,7 @@ pub mod dataframe;
 pub mod datasource;
 pub mod error;
 pub mod execution;
+pub mod ffi;
 pub mod logical_plan;
 pub mod optimizer;
 pub mod physical_optimizer;
@@ -219,6 +220,7 @@ pub mod physical_plan;
 pub mod prelude;
 pub mod scalar;
 pub mod sql;
+pub mod util;
 pub mod variable;
 
 // re-export dependencies from arrow-rs to minimise version maintenance for crate users
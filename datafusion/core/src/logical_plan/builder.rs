@@ -47,10 +47,12 @@ use super::dfschema::ToDFSchema;
 use super::{exprlist_to_fields, Expr, JoinConstraint, JoinType, LogicalPlan, PlanType};
 use crate::logical_plan::{
     columnize_expr, normalize_col, normalize_cols, provider_as_source,
-    rewrite_sort_cols_by_aggs, Column, CrossJoin, DFField, DFSchema, DFSchemaRef, Limit,
-    Partitioning, Repartition, Values,
+    rewrite_sort_cols_by_aggs, Column, Constraints, CrossJoin, DFField, DFSchema,
+    DFSchemaRef, FunctionalDependencies, Limit, Partitioning, Repartition, Values,
+};
+use crate::sql::utils::{
+    find_aggregate_exprs, find_nested_aggregate_exprs, group_window_expr_by_sort_keys,
 };
-use crate::sql::utils::group_window_expr_by_sort_keys;
 
 /// Default table name for unnamed table
 pub const UNNAMED_TABLE: &str = "?table?";
@@ -431,6 +433,11 @@ impl LogicalPlanBuilder {
         }
 
         let schema = provider.schema();
+        let functional_dependencies = scan_functional_dependencies(
+            provider.constraints(),
+            &schema,
+            projection.as_deref(),
+        );
 
         let projected_schema = projection
             .as_ref()
@@ -444,9 +451,8 @@ impl LogicalPlanBuilder {
                     schema.metadata().clone(),
                 )
             })
-            .unwrap_or_else(|| {
-                DFSchema::try_from_qualified_schema(&table_name, &schema)
-            })?;
+            .unwrap_or_else(|| DFSchema::try_from_qualified_schema(&table_name, &schema))?
+            .with_functional_dependencies(functional_dependencies);
 
         let table_scan = LogicalPlan::TableScan(TableScan {
             table_name,
@@ -521,9 +527,7 @@ impl LogicalPlanBuilder {
 
     /// Apply an alias
     pub fn alias(&self, alias: &str) -> Result<Self> {
-        let schema: Schema = self.schema().as_ref().clone().into();
-        let schema =
-            DFSchemaRef::new(DFSchema::try_from_qualified_schema(alias, &schema)?);
+        let schema = DFSchemaRef::new(self.schema().try_with_qualifier(alias)?);
         Ok(Self::from(LogicalPlan::SubqueryAlias(SubqueryAlias {
             input: Arc::new(self.plan.clone()),
             alias: alias.to_string(),
@@ -646,6 +650,16 @@ impl LogicalPlanBuilder {
         Ok(Self::from(union_with_alias(self.plan.clone(), plan, None)?))
     }
 
+    /// Apply a union matching the columns of the inputs by name, filling
+    /// the columns missing from an input with nulls
+    pub fn union_by_name(&self, plan: LogicalPlan) -> Result<Self> {
+        Ok(Self::from(union_by_name_with_alias(
+            self.plan.clone(),
+            plan,
+            None,
+        )?))
+    }
+
     /// Apply deduplication: Only distinct (different) values are returned)
     pub fn distinct(&self) -> Result<Self> {
         let projection_expr = expand_wildcard(self.plan.schema(), &self.plan)?;
@@ -772,7 +786,7 @@ impl LogicalPlanBuilder {
 
         let on: Vec<(_, _)> = left_keys.into_iter().zip(right_keys.into_iter()).collect();
         let join_schema =
-            build_join_schema(self.plan.schema(), right.schema(), &join_type)?;
+            build_join_schema(self.plan.schema(), right.schema(), &join_type, &on)?;
 
         Ok(Self::from(LogicalPlan::Join(Join {
             left: Arc::new(self.plan.clone()),
@@ -804,7 +818,7 @@ impl LogicalPlanBuilder {
 
         let on: Vec<(_, _)> = left_keys.into_iter().zip(right_keys.into_iter()).collect();
         let join_schema =
-            build_join_schema(self.plan.schema(), right.schema(), &join_type)?;
+            build_join_schema(self.plan.schema(), right.schema(), &join_type, &on)?;
 
         Ok(Self::from(LogicalPlan::Join(Join {
             left: Arc::new(self.plan.clone()),
@@ -866,12 +880,24 @@ impl LogicalPlanBuilder {
     ) -> Result<Self> {
         let group_expr = normalize_cols(group_expr, &self.plan)?;
         let aggr_expr = normalize_cols(aggr_expr, &self.plan)?;
+        validate_aggregate_nesting(&group_expr, &aggr_expr)?;
         let all_expr = group_expr.iter().chain(aggr_expr.iter());
         validate_unique_names("Aggregations", all_expr.clone(), self.plan.schema())?;
-        let aggr_schema = DFSchema::new_with_metadata(
-            exprlist_to_fields(all_expr, self.plan.schema())?,
-            self.plan.schema().metadata().clone(),
-        )?;
+        let fields = exprlist_to_fields(all_expr, self.plan.schema())?;
+        // the groups are unique and determine the aggregates, the dependencies
+        // between the grouping columns of the input still hold
+        let mut functional_dependencies = self
+            .plan
+            .schema()
+            .functional_dependencies()
+            .project(&column_indices(&group_expr, self.plan.schema()));
+        functional_dependencies.extend(FunctionalDependencies::new_from_key(
+            (0..group_expr.len()).collect(),
+            fields.len(),
+        ));
+        let aggr_schema =
+            DFSchema::new_with_metadata(fields, self.plan.schema().metadata().clone())?
+                .with_functional_dependencies(functional_dependencies);
         Ok(Self::from(LogicalPlan::Aggregate(Aggregate {
             input: Arc::new(self.plan.clone()),
             group_expr,
@@ -974,12 +1000,13 @@ impl LogicalPlanBuilder {
     }
 }
 
-/// Creates a schema for a join operation.
+/// Creates a schema for a join operation on the pairs of columns `on`.
 /// The fields from the left side are first
 pub fn build_join_schema(
     left: &DFSchema,
     right: &DFSchema,
     join_type: &JoinType,
+    on: &[(Column, Column)],
 ) -> Result<DFSchema> {
     let fields: Vec<DFField> = match join_type {
         JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right => {
@@ -996,7 +1023,103 @@ pub fn build_join_schema(
 
     let mut metadata = left.metadata().clone();
     metadata.extend(right.metadata().clone());
-    DFSchema::new_with_metadata(fields, metadata)
+    let functional_dependencies = match join_type {
+        JoinType::Inner | JoinType::Left | JoinType::Full | JoinType::Right => {
+            // the rows of a side stay unique when they match at most one row
+            // of the other side, which is the case when the keys of the other
+            // side are unique, and are not padded with nulls
+            let (left_keys, right_keys): (Vec<_>, Vec<_>) = on.iter().cloned().unzip();
+            let left_unique = matches!(join_type, JoinType::Inner | JoinType::Left)
+                && is_unique_key(right, &right_keys);
+            let right_unique = matches!(join_type, JoinType::Inner | JoinType::Right)
+                && is_unique_key(left, &left_keys);
+            let side_dependencies = |schema: &DFSchema, unique: bool| {
+                if unique {
+                    schema.functional_dependencies().clone()
+                } else {
+                    schema.functional_dependencies().without_uniqueness()
+                }
+            };
+            let mut functional_dependencies = side_dependencies(left, left_unique);
+            functional_dependencies.extend(
+                side_dependencies(right, right_unique).with_offset(left.fields().len()),
+            );
+            functional_dependencies
+        }
+        // at most the rows of the left side
+        JoinType::Semi | JoinType::Anti => left.functional_dependencies().clone(),
+    };
+    Ok(DFSchema::new_with_metadata(fields, metadata)?
+        .with_functional_dependencies(functional_dependencies))
+}
+
+/// Whether at most one row of a relation with the `schema` has each value of
+/// the `keys`
+pub(crate) fn is_unique_key(schema: &DFSchema, keys: &[Column]) -> bool {
+    keys.iter()
+        .map(|key| schema.index_of_column(key).ok())
+        .collect::<Option<Vec<_>>>()
+        .map_or(false, |indices| {
+            schema.functional_dependencies().is_unique(&indices)
+        })
+}
+
+/// Returns the functional dependencies of the columns of a scan of a table,
+/// given its schema, its constraints and the indices of the scanned columns
+pub(crate) fn scan_functional_dependencies(
+    constraints: Option<&Constraints>,
+    schema: &Schema,
+    projection: Option<&[usize]>,
+) -> FunctionalDependencies {
+    let functional_dependencies = match constraints {
+        Some(constraints) => {
+            FunctionalDependencies::new_from_constraints(constraints, schema)
+        }
+        None => return FunctionalDependencies::empty(),
+    };
+    match projection {
+        Some(projection) => functional_dependencies
+            .project(&projection.iter().map(|i| Some(*i)).collect::<Vec<_>>()),
+        None => functional_dependencies,
+    }
+}
+
+/// Returns the index in `schema` of each expression that is a (possibly
+/// aliased) column
+fn column_indices(exprs: &[Expr], schema: &DFSchema) -> Vec<Option<usize>> {
+    exprs
+        .iter()
+        .map(|expr| match expr {
+            Expr::Column(column) => schema.index_of_column(column).ok(),
+            Expr::Alias(expr, _) => match expr.as_ref() {
+                Expr::Column(column) => schema.index_of_column(column).ok(),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Errors if the grouping expressions contain aggregate functions or the
+/// aggregate expressions contain nested aggregate functions, which cannot be
+/// computed by a single aggregation
+fn validate_aggregate_nesting(group_expr: &[Expr], aggr_expr: &[Expr]) -> Result<()> {
+    if let Some(expr) = find_aggregate_exprs(group_expr).first() {
+        return Err(DataFusionError::Plan(format!(
+            "Aggregate function {:?} is not allowed in the grouping expressions",
+            expr
+        )));
+    }
+    for expr in find_aggregate_exprs(aggr_expr) {
+        if let Some(nested) = find_nested_aggregate_exprs(&[expr.clone()]).first() {
+            return Err(DataFusionError::Plan(format!(
+                "Aggregate function {:?} cannot contain the aggregate function {:?}, \
+                 aggregate the result of a grouped subquery instead",
+                expr, nested
+            )));
+        }
+    }
+    Ok(())
 }
 
 /// Errors if one or more expressions have equal names.
@@ -1075,7 +1198,10 @@ pub fn union_with_alias(
         return Err(DataFusionError::Plan("Empty UNION".to_string()));
     }
 
-    let union_schema = (**inputs[0].schema()).clone();
+    // the rows of the inputs do not depend on each other
+    let union_schema = (**inputs[0].schema())
+        .clone()
+        .with_functional_dependencies(FunctionalDependencies::empty());
     let union_schema = Arc::new(match alias {
         Some(ref alias) => union_schema.replace_qualifier(alias.as_str()),
         None => union_schema.strip_qualifiers(),
@@ -1085,10 +1211,14 @@ pub fn union_with_alias(
         .iter()
         .skip(1)
         .try_for_each(|input_plan| -> Result<()> {
-            union_schema.check_arrow_schema_type_compatible(
-                &((**input_plan.schema()).clone().into()),
-            )
+            union_schema
+                .check_arrow_schema_type_compatible(&input_plan.schema().as_ref().into())
         })?;
+    // the inputs produce the types of the union, e.g. for its distinct
+    let inputs = inputs
+        .into_iter()
+        .map(|input| cast_to_schema_types(input, &union_schema))
+        .collect::<Result<Vec<_>>>()?;
 
     Ok(LogicalPlan::Union(Union {
         inputs,
@@ -1097,6 +1227,81 @@ pub fn union_with_alias(
     }))
 }
 
+/// Returns `plan` with its columns cast to the types of the columns of
+/// `schema` at the same position, including the nested types differing
+/// only by the nullability or the names of their fields, if any differs
+fn cast_to_schema_types(plan: LogicalPlan, schema: &DFSchema) -> Result<LogicalPlan> {
+    let fields = plan.schema().fields();
+    if fields
+        .iter()
+        .zip(schema.fields())
+        .all(|(field, union_field)| field.data_type() == union_field.data_type())
+    {
+        return Ok(plan);
+    }
+    let expr = fields
+        .iter()
+        .zip(schema.fields())
+        .map(|(field, union_field)| {
+            let column = Expr::Column(field.qualified_column());
+            if field.data_type() == union_field.data_type() {
+                column
+            } else {
+                Expr::Cast {
+                    expr: Box::new(column),
+                    data_type: union_field.data_type().clone(),
+                }
+                .alias(field.name())
+            }
+        })
+        .collect::<Vec<_>>();
+    project_with_alias(plan, expr, None)
+}
+
+/// Union two logical plans with an optional alias, matching their columns by
+/// name rather than by position.
+///
+/// The output has the columns of `left_plan` followed by the columns only found
+/// in `right_plan`; the columns missing from an input are filled with nulls.
+pub fn union_by_name_with_alias(
+    left_plan: LogicalPlan,
+    right_plan: LogicalPlan,
+    alias: Option<String>,
+) -> Result<LogicalPlan> {
+    let mut fields: Vec<&DFField> = vec![];
+    for field in left_plan
+        .schema()
+        .fields()
+        .iter()
+        .chain(right_plan.schema().fields())
+    {
+        if !fields.iter().any(|f| f.name() == field.name()) {
+            fields.push(field);
+        }
+    }
+
+    let align = |plan: &LogicalPlan| -> Result<LogicalPlan> {
+        let schema = plan.schema();
+        let expr = fields
+            .iter()
+            .map(|field| {
+                match schema.fields_with_unqualified_name(field.name()).as_slice() {
+                    [] => Ok(lit(ScalarValue::try_from(field.data_type())?)
+                        .alias(field.name())),
+                    [input_field] => Ok(Expr::Column(input_field.qualified_column())),
+                    _ => Err(DataFusionError::Plan(format!(
+                        "Ambiguous reference to field named '{}' in union by name",
+                        field.name()
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        project_with_alias(plan.clone(), expr, None)
+    };
+
+    union_with_alias(align(&left_plan)?, align(&right_plan)?, alias)
+}
+
 /// Project with optional alias
 /// # Errors
 /// This function errors under any of the following conditions:
@@ -1122,10 +1327,14 @@ pub fn project_with_alias(
         }
     }
     validate_unique_names("Projections", projected_expr.iter(), input_schema)?;
+    let functional_dependencies = input_schema
+        .functional_dependencies()
+        .project(&column_indices(&projected_expr, input_schema));
     let input_schema = DFSchema::new_with_metadata(
         exprlist_to_fields(&projected_expr, input_schema)?,
         plan.schema().metadata().clone(),
-    )?;
+    )?
+    .with_functional_dependencies(functional_dependencies);
     let schema = match alias {
         Some(ref alias) => input_schema.replace_qualifier(alias.as_str()),
         None => input_schema,
@@ -1339,6 +1548,78 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn plan_builder_union_cast_types() -> Result<()> {
+        let list = |nullable| {
+            DataType::List(Box::new(Field::new("item", DataType::Int32, nullable)))
+        };
+        let left = LogicalPlanBuilder::scan_empty(
+            Some("t1"),
+            &Schema::new(vec![
+                Field::new("a", DataType::Int64, false),
+                Field::new("b", list(true), false),
+            ]),
+            None,
+        )?
+        .build()?;
+        let right = LogicalPlanBuilder::scan_empty(
+            Some("t2"),
+            &Schema::new(vec![
+                Field::new("a", DataType::Int64, false),
+                Field::new("b", list(false), false),
+            ]),
+            None,
+        )?
+        .build()?;
+
+        let plan = LogicalPlanBuilder::from(left).union(right)?.build()?;
+
+        // the right input is cast to the types of the left one
+        let inputs = plan.inputs();
+        assert!(matches!(inputs[0], LogicalPlan::TableScan(_)));
+        match inputs[1] {
+            LogicalPlan::Projection(Projection { expr, .. }) => {
+                assert_eq!(expr[0], col("t2.a"));
+                assert!(matches!(expr[1], Expr::Alias(..)));
+            }
+            other => panic!("expected a projection, got {:?}", other),
+        }
+        assert_eq!(inputs[1].schema().field(1).data_type(), &list(true));
+        assert_eq!(inputs[1].schema().field(1).name(), "b");
+
+        Ok(())
+    }
+
+    #[test]
+    fn plan_builder_union_by_name() -> Result<()> {
+        let left = LogicalPlanBuilder::scan_empty(
+            Some("employee_csv"),
+            &employee_schema(),
+            Some(vec![0, 3]),
+        )?
+        .build()?;
+        let right = LogicalPlanBuilder::scan_empty(
+            Some("employee_csv"),
+            &employee_schema(),
+            Some(vec![4, 0]),
+        )?
+        .build()?;
+
+        let plan = LogicalPlanBuilder::from(left)
+            .union_by_name(right)?
+            .build()?;
+
+        let expected = "Union\
+        \n  Projection: #employee_csv.id, #employee_csv.state, Int32(NULL) AS salary\
+        \n    TableScan: employee_csv projection=Some([0, 3])\
+        \n  Projection: #employee_csv.id, Utf8(NULL) AS state, #employee_csv.salary\
+        \n    TableScan: employee_csv projection=Some([4, 0])";
+
+        assert_eq!(expected, format!("{:?}", plan));
+
+        Ok(())
+    }
+
     #[test]
     fn projection_non_unique_names() -> Result<()> {
         let plan = LogicalPlanBuilder::scan_empty(
@@ -0,0 +1,344 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Coercion of the rows written to a table to the schema of the table
+
+use super::plan::Extension;
+use super::{Column, DFField, Expr, LogicalPlan, LogicalPlanBuilder, VerifyNode};
+use crate::error::{DataFusionError, Result};
+use crate::scalar::ScalarValue;
+use arrow::compute::{can_cast_types, cast};
+use arrow::datatypes::{DataType, Field, Schema};
+use std::sync::Arc;
+
+/// Coerces the output of `input`, the rows written to a table, to the
+/// `target` schema of the table, matching the columns by position.
+///
+/// This is the write-side counterpart of the type coercion of expressions,
+/// shared by the statements writing to tables such as
+/// `CREATE TABLE ... AS SELECT`:
+///
+/// * the columns are renamed to the columns of the table
+/// * the columns are cast to the types of the table when no value can be
+///   lost, e.g. from `Int32` to `Int64` or from `Utf8` to `LargeUtf8`. Other
+///   casts, e.g. from `Int64` to `Int32`, must be written explicitly in the
+///   query, unless the column is a literal whose value is kept by the cast
+/// * the nullable columns written to non-nullable columns are checked for
+///   NULLs when executing the plan, failing the query if one is found
+pub fn coerce_to_table_schema(
+    input: LogicalPlan,
+    target: &Schema,
+) -> Result<LogicalPlan> {
+    let fields = input.schema().fields().clone();
+    if fields.len() != target.fields().len() {
+        return Err(DataFusionError::Plan(format!(
+            "Cannot write {} column(s) to a table of {} column(s)",
+            fields.len(),
+            target.fields().len()
+        )));
+    }
+
+    let mut exprs = Vec::with_capacity(fields.len());
+    let mut checked_columns = vec![];
+    let mut changed = false;
+    for (index, (field, target_field)) in fields.iter().zip(target.fields()).enumerate() {
+        let literal = output_literal(&input, index);
+        if !target_field.is_nullable() && field.is_nullable() {
+            if matches!(literal, Some(value) if value.is_null()) {
+                return Err(DataFusionError::Plan(format!(
+                    "Cannot write NULL to the non-nullable column {}",
+                    target_field.name()
+                )));
+            }
+            if literal.is_none() {
+                checked_columns.push(target_field.name());
+            }
+        }
+
+        let expr = coerce_column(field, target_field, literal)?;
+        changed |= field.data_type() != target_field.data_type()
+            || field.qualifier().is_some()
+            || field.name() != target_field.name();
+        exprs.push(expr.alias(target_field.name()));
+    }
+
+    let mut plan = if changed {
+        LogicalPlanBuilder::from(input).project(exprs)?.build()?
+    } else {
+        input
+    };
+    // the NULLs of the nullable columns are only found when executing
+    let predicate = checked_columns
+        .into_iter()
+        .map(|name| Expr::Column(Column::from_name(name)).is_not_null())
+        .reduce(Expr::and);
+    if let Some(predicate) = predicate {
+        plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(VerifyNode::try_new(plan, predicate)?),
+        });
+    }
+    Ok(plan)
+}
+
+/// Returns the expression writing the column `field` to `target_field`
+fn coerce_column(
+    field: &DFField,
+    target_field: &Field,
+    literal: Option<&ScalarValue>,
+) -> Result<Expr> {
+    let column = Expr::Column(field.qualified_column());
+    let (from, to) = (field.data_type(), target_field.data_type());
+    if from == to {
+        return Ok(column);
+    }
+    if !can_cast_types(from, to) {
+        return Err(DataFusionError::Plan(format!(
+            "Cannot write column {} of type {:?} to column {} of type {:?}",
+            field.name(),
+            from,
+            target_field.name(),
+            to
+        )));
+    }
+    if *from == DataType::Null || is_lossless_cast(from, to) {
+        return Ok(Expr::Cast {
+            expr: Box::new(column),
+            data_type: to.clone(),
+        });
+    }
+    match literal.map(|value| cast_literal(value, to)).transpose()? {
+        Some(Some(value)) => Ok(Expr::Literal(value)),
+        _ => Err(DataFusionError::Plan(format!(
+            "Cannot write column {} of type {:?} to column {} of type {:?} \
+             without losing data, cast it explicitly",
+            field.name(),
+            from,
+            target_field.name(),
+            to
+        ))),
+    }
+}
+
+/// Returns the literal computing the column `index` of `plan`, if any
+fn output_literal(plan: &LogicalPlan, index: usize) -> Option<&ScalarValue> {
+    let mut expr = match plan {
+        LogicalPlan::Projection(projection) => projection.expr.get(index)?,
+        _ => return None,
+    };
+    while let Expr::Alias(inner, _) = expr {
+        expr = inner;
+    }
+    match expr {
+        Expr::Literal(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Casts the literal `value` to `data_type`, returning `None` if its value
+/// isn't kept, i.e. if casting it back doesn't return `value`
+fn cast_literal(
+    value: &ScalarValue,
+    data_type: &DataType,
+) -> Result<Option<ScalarValue>> {
+    let array = value.to_array();
+    let cast_array = cast(&array, data_type)?;
+    let round_trip = cast(&cast_array, array.data_type())?;
+    if cast_array.null_count() != array.null_count()
+        || ScalarValue::try_from_array(&round_trip, 0)? != *value
+    {
+        return Ok(None);
+    }
+    Ok(Some(ScalarValue::try_from_array(&cast_array, 0)?))
+}
+
+/// Whether all the values of type `from` can be cast to `to` without losing
+/// data
+fn is_lossless_cast(from: &DataType, to: &DataType) -> bool {
+    use DataType::*;
+    match (from, to) {
+        (Dictionary(_, value_type), to) => {
+            value_type.as_ref() == to || is_lossless_cast(value_type, to)
+        }
+        (from, Dictionary(_, value_type)) => {
+            from == value_type.as_ref() || is_lossless_cast(from, value_type)
+        }
+        (Decimal(from_precision, from_scale), Decimal(precision, scale)) => {
+            scale >= from_scale && precision - scale >= from_precision - from_scale
+        }
+        (from, Decimal(precision, scale)) => {
+            integer_digits(from).map_or(false, |digits| digits <= precision - scale)
+        }
+        (Int8, Int16 | Int32 | Int64 | Float32 | Float64)
+        | (Int16, Int32 | Int64 | Float32 | Float64)
+        | (Int32, Int64 | Float64)
+        | (UInt8, UInt16 | UInt32 | UInt64 | Int16 | Int32 | Int64 | Float32 | Float64)
+        | (UInt16, UInt32 | UInt64 | Int32 | Int64 | Float32 | Float64)
+        | (UInt32, UInt64 | Int64 | Float64)
+        | (Float32, Float64)
+        | (Utf8, LargeUtf8)
+        | (Binary, LargeBinary)
+        | (Date32, Date64) => true,
+        _ => false,
+    }
+}
+
+/// The number of decimal digits of the values of the integer type
+/// `data_type`
+fn integer_digits(data_type: &DataType) -> Option<usize> {
+    match data_type {
+        DataType::Int8 | DataType::UInt8 => Some(3),
+        DataType::Int16 | DataType::UInt16 => Some(5),
+        DataType::Int32 | DataType::UInt32 => Some(10),
+        DataType::Int64 => Some(19),
+        DataType::UInt64 => Some(20),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{col, lit};
+    use crate::test::test_table_scan;
+
+    fn target(fields: Vec<(&str, DataType, bool)>) -> Schema {
+        Schema::new(
+            fields
+                .into_iter()
+                .map(|(name, data_type, nullable)| Field::new(name, data_type, nullable))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn coerce_to_table_schema() -> Result<()> {
+        // the columns are renamed and widened
+        let input = test_table_scan()?;
+        let plan = super::coerce_to_table_schema(
+            input.clone(),
+            &target(vec![
+                ("x", DataType::UInt32, false),
+                ("y", DataType::Int64, true),
+                ("z", DataType::Float64, false),
+            ]),
+        )?;
+        let expected = "Projection: #test.a AS x, CAST(#test.b AS Int64) AS y, \
+                        CAST(#test.c AS Float64) AS z\
+                        \n  TableScan: test projection=None";
+        assert_eq!(format!("{:?}", plan), expected);
+
+        // narrowing a column must be explicit
+        let err = super::coerce_to_table_schema(
+            input.clone(),
+            &target(vec![
+                ("a", DataType::UInt32, false),
+                ("b", DataType::UInt8, false),
+                ("c", DataType::UInt32, false),
+            ]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Cannot write column b of type UInt32 to column b \
+             of type UInt8 without losing data, cast it explicitly"
+        );
+
+        let err = super::coerce_to_table_schema(
+            input,
+            &target(vec![("a", DataType::UInt32, false)]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Cannot write 3 column(s) to a table of 1 column(s)"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn coerce_literals_and_nulls() -> Result<()> {
+        let input = LogicalPlanBuilder::from(test_table_scan()?)
+            .project(vec![
+                lit(1i64).alias("one"),
+                lit(1.5f64).alias("half"),
+                lit(ScalarValue::Int32(None)).alias("nothing"),
+            ])?
+            .build()?;
+
+        // the literals kept by the casts are written, the nullable columns
+        // are verified
+        let target_schema = target(vec![
+            ("one", DataType::Int8, false),
+            ("half", DataType::Float32, false),
+            ("nothing", DataType::Int32, true),
+        ]);
+        let plan = super::coerce_to_table_schema(input.clone(), &target_schema)?;
+        let expected = "Projection: Int8(1) AS one, Float32(1.5) AS half, \
+                        #nothing AS nothing\
+                        \n  Projection: Int64(1) AS one, Float64(1.5) AS half, \
+                        Int32(NULL) AS nothing\
+                        \n    TableScan: test projection=None";
+        assert_eq!(format!("{:?}", plan), expected);
+
+        // a literal changed by the cast isn't written
+        let err = super::coerce_to_table_schema(
+            input.clone(),
+            &target(vec![
+                ("one", DataType::Int8, false),
+                ("half", DataType::Int32, false),
+                ("nothing", DataType::Int32, true),
+            ]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("without losing data"), "{}", err);
+
+        // a NULL literal is never written to a non-nullable column
+        let err = super::coerce_to_table_schema(
+            input,
+            &target(vec![
+                ("one", DataType::Int8, false),
+                ("half", DataType::Float32, false),
+                ("nothing", DataType::Int32, false),
+            ]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Cannot write NULL to the non-nullable column nothing"
+        );
+
+        // the other nullable columns are checked when executing
+        let input = LogicalPlanBuilder::from(test_table_scan()?)
+            .project(vec![col("a"), lit(ScalarValue::UInt32(None)).alias("b")])?
+            .project(vec![col("a"), col("b")])?
+            .build()?;
+        let plan = super::coerce_to_table_schema(
+            input,
+            &target(vec![
+                ("a", DataType::UInt32, false),
+                ("b", DataType::UInt32, false),
+            ]),
+        )?;
+        let expected = "Verify: #b IS NOT NULL\
+                        \n  Projection: #test.a AS a, #b AS b\
+                        \n    Projection: #test.a, #b\
+                        \n      Projection: #test.a, UInt32(NULL) AS b\
+                        \n        TableScan: test projection=None";
+        assert_eq!(format!("{:?}", plan), expected);
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Logical node keeping the latest row of each key of its input
+
+use super::{DFSchemaRef, Expr, ExprSchemable, LogicalPlan, UserDefinedLogicalNode};
+use crate::error::{DataFusionError, Result};
+use std::{any::Any, fmt, sync::Arc};
+
+/// How the latest row of each key is found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupStrategy {
+    /// The latest row of each key is kept in a hash table, holding the whole
+    /// input in memory
+    Hash,
+    /// The input is sorted by key and sequence, and the first row of each key
+    /// is kept
+    Sort,
+}
+
+impl Default for DedupStrategy {
+    fn default() -> Self {
+        Self::Hash
+    }
+}
+
+/// Keeps only the latest version of the rows of its input with the same
+/// key, e.g. the rows of a change data capture (CDC) dataset, the latest
+/// being the row with the greatest `sequence` (e.g. a timestamp or a log
+/// sequence number). The rows with a NULL sequence are older than the other
+/// rows of their key. Of the rows with the same key and sequence, which one
+/// is kept is unspecified.
+///
+/// This node is planned into a
+/// [`DedupExec`](crate::physical_plan::dedup::DedupExec) by the default
+/// physical planner. Filters are never pushed below it, as that would
+/// change which rows are the latest.
+#[derive(Clone)]
+pub struct DedupNode {
+    input: LogicalPlan,
+    keys: Vec<Expr>,
+    sequence: Expr,
+    strategy: DedupStrategy,
+}
+
+impl DedupNode {
+    /// Create a new DedupNode, checking that `keys` and `sequence` are
+    /// expressions over the schema of `input`
+    pub fn try_new(
+        input: LogicalPlan,
+        keys: Vec<Expr>,
+        sequence: Expr,
+        strategy: DedupStrategy,
+    ) -> Result<Self> {
+        if keys.is_empty() {
+            return Err(DataFusionError::Plan(
+                "Dedup requires at least one key".to_string(),
+            ));
+        }
+        for expr in keys.iter().chain(std::iter::once(&sequence)) {
+            expr.get_type(input.schema())?;
+        }
+        Ok(Self {
+            input,
+            keys,
+            sequence,
+            strategy,
+        })
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &LogicalPlan {
+        &self.input
+    }
+
+    /// The key of the rows
+    pub fn keys(&self) -> &[Expr] {
+        &self.keys
+    }
+
+    /// The version of the rows, the greatest being the latest
+    pub fn sequence(&self) -> &Expr {
+        &self.sequence
+    }
+
+    /// How the latest row of each key is found
+    pub fn strategy(&self) -> DedupStrategy {
+        self.strategy
+    }
+}
+
+impl fmt::Debug for DedupNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNode for DedupNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        self.input.schema()
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        let mut exprs = self.keys.clone();
+        exprs.push(self.sequence.clone());
+        exprs
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Dedup: keys={:?}, sequence={:?}, strategy={:?}",
+            self.keys, self.sequence, self.strategy
+        )
+    }
+
+    fn from_template(
+        &self,
+        exprs: &[Expr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode + Send + Sync> {
+        assert_eq!(inputs.len(), 1, "input size inconsistent");
+        assert_eq!(
+            exprs.len(),
+            self.keys.len() + 1,
+            "expression size inconsistent"
+        );
+        let (sequence, keys) = exprs.split_last().unwrap();
+        Arc::new(Self {
+            input: inputs[0].clone(),
+            keys: keys.to_vec(),
+            sequence: sequence.clone(),
+            strategy: self.strategy,
+        })
+    }
+}
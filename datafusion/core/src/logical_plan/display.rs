@@ -58,7 +58,7 @@ impl<'a, 'b> PlanVisitor for IndentVisitor<'a, 'b> {
             write!(
                 self.f,
                 " {}",
-                display_schema(&plan.schema().as_ref().to_owned().into())
+                display_schema(&plan.schema().as_ref().into())
             )?;
         }
 
@@ -199,7 +199,7 @@ impl<'a, 'b> PlanVisitor for GraphvizVisitor<'a, 'b> {
             format!(
                 r"{}\nSchema: {}",
                 plan.display(),
-                display_schema(&plan.schema().as_ref().to_owned().into())
+                display_schema(&plan.schema().as_ref().into())
             )
         } else {
             format!("{}", plan.display())
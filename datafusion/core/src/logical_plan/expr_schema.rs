@@ -187,7 +187,9 @@ impl ExprSchemable for Expr {
             )),
             Expr::GetIndexedField { ref expr, key } => {
                 let data_type = expr.get_type(input_schema)?;
-                get_indexed_field(&data_type, key).map(|x| x.is_nullable())
+                // the fields of a null list, struct or map are null
+                Ok(get_indexed_field(&data_type, key)?.is_nullable()
+                    || expr.nullable(input_schema)?)
             }
         }
     }
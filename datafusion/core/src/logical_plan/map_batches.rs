@@ -0,0 +1,113 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Logical node transforming the batches of its input with a Rust function
+
+use super::{DFSchemaRef, Expr, LogicalPlan, UserDefinedLogicalNode};
+use crate::error::Result;
+use arrow::record_batch::RecordBatch;
+use std::{any::Any, fmt, sync::Arc};
+
+/// A function transforming a batch into a batch of the output schema of a
+/// [`MapBatchesNode`]
+pub type BatchFunction = Arc<dyn Fn(RecordBatch) -> Result<RecordBatch> + Send + Sync>;
+
+/// Replaces each batch of its input by the batch returned by a Rust function,
+/// e.g. calling an external library, whose rows and columns can differ from
+/// the ones of its input.
+///
+/// This node is planned into a
+/// [`MapBatchesExec`](crate::physical_plan::map_batches::MapBatchesExec) by
+/// the default physical planner. The function can read every column of the
+/// input, so the projections are not pushed below it.
+#[derive(Clone)]
+pub struct MapBatchesNode {
+    input: LogicalPlan,
+    schema: DFSchemaRef,
+    fun: BatchFunction,
+}
+
+impl MapBatchesNode {
+    /// Create a new MapBatchesNode, transforming the batches of `input` with
+    /// `fun` into batches of the given `schema`
+    pub fn new(input: LogicalPlan, schema: DFSchemaRef, fun: BatchFunction) -> Self {
+        Self { input, schema, fun }
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &LogicalPlan {
+        &self.input
+    }
+
+    /// The function transforming the batches
+    pub fn fun(&self) -> &BatchFunction {
+        &self.fun
+    }
+}
+
+impl fmt::Debug for MapBatchesNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNode for MapBatchesNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    /// All the columns of the input, which the function can read
+    fn expressions(&self) -> Vec<Expr> {
+        self.input
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| Expr::Column(field.qualified_column()))
+            .collect()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|field| field.name().as_str())
+            .collect::<Vec<_>>();
+        write!(f, "MapBatches: output=[{}]", columns.join(", "))
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[Expr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode + Send + Sync> {
+        assert_eq!(inputs.len(), 1, "input size inconsistent");
+        Arc::new(Self {
+            input: inputs[0].clone(),
+            schema: self.schema.clone(),
+            fun: self.fun.clone(),
+        })
+    }
+}
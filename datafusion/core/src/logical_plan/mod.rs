@@ -22,6 +22,8 @@
 //! physical query plans and executed.
 
 pub(crate) mod builder;
+mod coerce_write;
+mod dedup;
 mod dfschema;
 mod display;
 mod expr;
@@ -30,28 +32,39 @@ mod expr_schema;
 mod expr_simplier;
 mod expr_visitor;
 mod extension;
+mod map_batches;
 mod operators;
 pub mod plan;
 mod registry;
+mod sanitize;
+mod verify;
 pub mod window_frames;
 pub use builder::{
-    build_join_schema, union_with_alias, LogicalPlanBuilder, UNNAMED_TABLE,
+    build_join_schema, union_by_name_with_alias, union_with_alias, LogicalPlanBuilder,
+    UNNAMED_TABLE,
 };
+pub use coerce_write::coerce_to_table_schema;
 pub use datafusion_expr::expr_fn::binary_expr;
-pub use dfschema::{DFField, DFSchema, DFSchemaRef, ToDFSchema};
+pub use dedup::{DedupNode, DedupStrategy};
+pub use dfschema::{
+    Constraint, Constraints, DFField, DFSchema, DFSchemaRef, FunctionalDependence,
+    FunctionalDependencies, ToDFSchema,
+};
 pub use display::display_schema;
 pub use expr::{
-    abs, acos, and, approx_distinct, approx_percentile_cont, array, ascii, asin, atan,
-    avg, bit_length, btrim, call_fn, case, ceil, character_length, chr, coalesce, col,
-    columnize_expr, combine_filters, concat, concat_expr, concat_ws, concat_ws_expr, cos,
-    count, count_distinct, create_udaf, create_udf, date_part, date_trunc, digest, exp,
-    exprlist_to_fields, floor, in_list, initcap, left, length, lit, lit_timestamp_nano,
-    ln, log10, log2, lower, lpad, ltrim, max, md5, min, now, now_expr, nullif,
-    octet_length, or, random, regexp_match, regexp_replace, repeat, replace, reverse,
-    right, round, rpad, rtrim, sha224, sha256, sha384, sha512, signum, sin, split_part,
-    sqrt, starts_with, strpos, substr, sum, tan, to_hex, to_timestamp_micros,
-    to_timestamp_millis, to_timestamp_seconds, translate, trim, trunc, unalias, upper,
-    when, Column, Expr, ExprSchema, Literal,
+    abs, acos, and, approx_distinct, approx_percentile_cont, approx_top_k, array,
+    array_contains, ascii, asin, atan, avg, bit_length, btrim, call_fn, case, ceil,
+    character_length, chr, coalesce, col, collate, columnize_expr, combine_filters,
+    concat, concat_expr, concat_ws, concat_ws_expr, cos, count, count_distinct,
+    create_udaf, create_udf, date_part, date_trunc, digest, exp, exprlist_to_fields,
+    floor, histogram, hll_count, hll_sketch, hll_union, in_list, initcap, left, length,
+    lit, lit_timestamp_nano, ln, log10, log2, lower, lpad, ltrim, max, md5, min, nfc,
+    nfd, now, now_expr, nullif, octet_length, or, random, regexp_match, regexp_replace,
+    repeat, replace, reverse, right, round, rpad, rtrim, sha224, sha256, sha384, sha512,
+    signum, sin, split_part, sqrt, starts_with, strpos, substr, sum, tan, to_hex,
+    to_timestamp_micros, to_timestamp_millis, to_timestamp_seconds, translate, trim,
+    trunc, unaccent, unalias, upper, when, width_bucket, Column, Expr, ExprSchema,
+    Literal,
 };
 pub use expr_rewriter::{
     normalize_col, normalize_cols, replace_col, rewrite_sort_cols_by_aggs,
@@ -61,13 +74,16 @@ pub use expr_schema::ExprSchemable;
 pub use expr_simplier::{ExprSimplifiable, SimplifyInfo};
 pub use expr_visitor::{ExprVisitable, ExpressionVisitor, Recursion};
 pub use extension::UserDefinedLogicalNode;
+pub use map_batches::{BatchFunction, MapBatchesNode};
 pub use operators::Operator;
 pub use plan::{provider_as_source, source_as_provider};
 pub use plan::{
     CreateCatalog, CreateCatalogSchema, CreateExternalTable, CreateMemoryTable,
-    CrossJoin, DropTable, EmptyRelation, FileType, JoinConstraint, JoinType, Limit,
-    LogicalPlan, Partitioning, PlanType, PlanVisitor, Repartition, TableScan, Union,
-    Values,
+    CrossJoin, Delete, DropTable, EmptyRelation, FileType, JoinConstraint, JoinType,
+    Limit, LogicalPlan, OptimizeTable, Partitioning, PlanType, PlanVisitor, Repartition,
+    SetVariable, TableScan, Transaction, TransactionStatement, Union, Update, Values,
 };
 pub(crate) use plan::{StringifiedPlan, ToStringifiedPlan};
 pub use registry::FunctionRegistry;
+pub use sanitize::{sanitize_expr, sanitize_plan, SanitizedPlan};
+pub use verify::VerifyNode;
@@ -23,7 +23,7 @@ use super::extension::UserDefinedLogicalNode;
 use crate::datasource::TableProvider;
 use crate::error::DataFusionError;
 use crate::logical_expr::TableProviderFilterPushDown;
-use crate::logical_plan::dfschema::DFSchemaRef;
+use crate::logical_plan::dfschema::{Constraints, DFSchemaRef};
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion_expr::TableSource;
 use std::any::Any;
@@ -162,6 +162,11 @@ impl TableSource for DefaultTableSource {
     ) -> datafusion_common::Result<TableProviderFilterPushDown> {
         self.table_provider.supports_filter_pushdown(filter)
     }
+
+    /// Get the constraints declared on the columns of this table
+    fn constraints(&self) -> Option<&Constraints> {
+        self.table_provider.constraints()
+    }
 }
 
 /// Wrap TableProvider in TableSource
@@ -265,6 +270,8 @@ pub enum FileType {
 pub struct CreateExternalTable {
     /// The table schema
     pub schema: DFSchemaRef,
+    /// The constraints declared on the columns of the table
+    pub constraints: Constraints,
     /// The table name
     pub name: String,
     /// The physical location
@@ -277,6 +284,8 @@ pub struct CreateExternalTable {
     pub delimiter: char,
     /// Partition Columns
     pub table_partition_cols: Vec<String>,
+    /// The order of the rows of the files, as sort expressions
+    pub order_exprs: Vec<Expr>,
     /// Option to not error if table already exists
     pub if_not_exists: bool,
 }
@@ -314,6 +323,76 @@ pub struct DropTable {
     pub schema: DFSchemaRef,
 }
 
+/// Sets a session variable, e.g. `SET search_path = s1, s2`.
+#[derive(Clone)]
+pub struct SetVariable {
+    /// The variable name
+    pub variable: String,
+    /// The values assigned to the variable
+    pub values: Vec<String>,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+/// A transaction control statement, e.g. `BEGIN`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionStatement {
+    /// Starts a transaction, e.g. `BEGIN` or `START TRANSACTION`
+    Begin,
+    /// Commits the current transaction
+    Commit,
+    /// Rolls back the current transaction
+    Rollback,
+}
+
+/// Controls the transaction of the session, see [`TransactionStatement`]
+#[derive(Clone)]
+pub struct Transaction {
+    /// The transaction control statement
+    pub statement: TransactionStatement,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
+
+/// Rewrites the files of a table into fewer, larger Parquet files, e.g.
+/// `OPTIMIZE TABLE t`
+#[derive(Clone)]
+pub struct OptimizeTable {
+    /// The table name
+    pub name: String,
+    /// The order of the rows of the rewritten files, if any
+    pub sort_exprs: Vec<Expr>,
+    /// The target size of the rewritten files in bytes, if not the default
+    pub target_file_size: Option<usize>,
+    /// The schema of the summary of the rewrite
+    pub schema: DFSchemaRef,
+}
+
+/// Deletes the rows of a table, e.g. `DELETE FROM t WHERE c1 > 1`
+#[derive(Clone)]
+pub struct Delete {
+    /// The table name
+    pub table_name: String,
+    /// The rows deleted, or all, over the unqualified columns of the table
+    pub predicate: Option<Expr>,
+    /// The schema of the number of rows deleted
+    pub schema: DFSchemaRef,
+}
+
+/// Updates the rows of a table, e.g. `UPDATE t SET c1 = c1 + 1 WHERE c2 = 'x'`
+#[derive(Clone)]
+pub struct Update {
+    /// The table name
+    pub table_name: String,
+    /// The columns updated, and their new values over the unqualified
+    /// columns of the table
+    pub assignments: Vec<(String, Expr)>,
+    /// The rows updated, or all, over the unqualified columns of the table
+    pub predicate: Option<Expr>,
+    /// The schema of the number of rows updated
+    pub schema: DFSchemaRef,
+}
+
 /// Produces a relation with string representations of
 /// various parts of the plan
 #[derive(Clone)]
@@ -473,6 +552,16 @@ pub enum LogicalPlan {
     CreateCatalog(CreateCatalog),
     /// Drops a table.
     DropTable(DropTable),
+    /// Sets a session variable.
+    SetVariable(SetVariable),
+    /// Controls the transaction of the session.
+    Transaction(Transaction),
+    /// Rewrites the files of a table.
+    OptimizeTable(OptimizeTable),
+    /// Deletes the rows of a table.
+    Delete(Delete),
+    /// Updates the rows of a table.
+    Update(Update),
     /// Values expression. See
     /// [Postgres VALUES](https://www.postgresql.org/docs/current/queries-values.html)
     /// documentation for more details.
@@ -521,6 +610,11 @@ impl LogicalPlan {
             }
             LogicalPlan::CreateCatalog(CreateCatalog { schema, .. }) => schema,
             LogicalPlan::DropTable(DropTable { schema, .. }) => schema,
+            LogicalPlan::SetVariable(SetVariable { schema, .. }) => schema,
+            LogicalPlan::Transaction(Transaction { schema, .. }) => schema,
+            LogicalPlan::OptimizeTable(OptimizeTable { schema, .. }) => schema,
+            LogicalPlan::Delete(Delete { schema, .. }) => schema,
+            LogicalPlan::Update(Update { schema, .. }) => schema,
         }
     }
 
@@ -574,7 +668,12 @@ impl LogicalPlan {
             | LogicalPlan::Sort(Sort { input, .. })
             | LogicalPlan::CreateMemoryTable(CreateMemoryTable { input, .. })
             | LogicalPlan::Filter(Filter { input, .. }) => input.all_schemas(),
-            LogicalPlan::DropTable(_) => vec![],
+            LogicalPlan::DropTable(_)
+            | LogicalPlan::SetVariable(_)
+            | LogicalPlan::Transaction(_)
+            | LogicalPlan::OptimizeTable(_)
+            | LogicalPlan::Delete(_)
+            | LogicalPlan::Update(_) => vec![],
         }
     }
 
@@ -586,6 +685,26 @@ impl LogicalPlan {
         ]))
     }
 
+    /// Returns the (fixed) output schema of the `OptimizeTable` plans, the
+    /// summary of the rewrite
+    pub fn optimize_table_schema() -> SchemaRef {
+        SchemaRef::new(Schema::new(vec![
+            Field::new("files_removed", DataType::UInt64, false),
+            Field::new("files_written", DataType::UInt64, false),
+            Field::new("num_rows", DataType::UInt64, false),
+        ]))
+    }
+
+    /// Returns the (fixed) output schema of the `Delete` and `Update` plans,
+    /// the number of rows deleted or updated
+    pub fn dml_schema() -> SchemaRef {
+        SchemaRef::new(Schema::new(vec![Field::new(
+            "count",
+            DataType::UInt64,
+            false,
+        )]))
+    }
+
     /// returns all expressions (non-recursively) in the current
     /// logical plan node. This does not include expressions in any
     /// children
@@ -625,6 +744,11 @@ impl LogicalPlan {
             | LogicalPlan::CreateCatalogSchema(_)
             | LogicalPlan::CreateCatalog(_)
             | LogicalPlan::DropTable(_)
+            | LogicalPlan::SetVariable(_)
+            | LogicalPlan::Transaction(_)
+            | LogicalPlan::OptimizeTable(_)
+            | LogicalPlan::Delete(_)
+            | LogicalPlan::Update(_)
             | LogicalPlan::CrossJoin(_)
             | LogicalPlan::Analyze { .. }
             | LogicalPlan::Explain { .. }
@@ -662,7 +786,12 @@ impl LogicalPlan {
             | LogicalPlan::CreateExternalTable(_)
             | LogicalPlan::CreateCatalogSchema(_)
             | LogicalPlan::CreateCatalog(_)
-            | LogicalPlan::DropTable(_) => vec![],
+            | LogicalPlan::DropTable(_)
+            | LogicalPlan::SetVariable(_)
+            | LogicalPlan::Transaction(_)
+            | LogicalPlan::OptimizeTable(_)
+            | LogicalPlan::Delete(_)
+            | LogicalPlan::Update(_) => vec![],
         }
     }
 
@@ -819,7 +948,12 @@ impl LogicalPlan {
             | LogicalPlan::CreateExternalTable(_)
             | LogicalPlan::CreateCatalogSchema(_)
             | LogicalPlan::CreateCatalog(_)
-            | LogicalPlan::DropTable(_) => true,
+            | LogicalPlan::DropTable(_)
+            | LogicalPlan::SetVariable(_)
+            | LogicalPlan::Transaction(_)
+            | LogicalPlan::OptimizeTable(_)
+            | LogicalPlan::Delete(_)
+            | LogicalPlan::Update(_) => true,
         };
         if !recurse {
             return Ok(false);
@@ -1200,6 +1334,53 @@ impl LogicalPlan {
                     }) => {
                         write!(f, "DropTable: {:?} if not exist:={}", name, if_exists)
                     }
+                    LogicalPlan::SetVariable(SetVariable {
+                        variable, values, ..
+                    }) => {
+                        write!(f, "SetVariable: {} = {:?}", variable, values)
+                    }
+                    LogicalPlan::Transaction(Transaction { statement, .. }) => {
+                        write!(f, "Transaction: {:?}", statement)
+                    }
+                    LogicalPlan::OptimizeTable(OptimizeTable {
+                        name,
+                        sort_exprs,
+                        ..
+                    }) => {
+                        write!(f, "OptimizeTable: {:?} sort={:?}", name, sort_exprs)
+                    }
+                    LogicalPlan::Delete(Delete {
+                        table_name,
+                        predicate,
+                        ..
+                    }) => {
+                        write!(f, "Delete: {:?}", table_name)?;
+                        if let Some(predicate) = predicate {
+                            write!(f, " predicate={:?}", predicate)?;
+                        }
+                        Ok(())
+                    }
+                    LogicalPlan::Update(Update {
+                        table_name,
+                        assignments,
+                        predicate,
+                        ..
+                    }) => {
+                        let assignments = assignments
+                            .iter()
+                            .map(|(column, value)| format!("{} = {:?}", column, value))
+                            .collect::<Vec<_>>();
+                        write!(
+                            f,
+                            "Update: {:?} set {}",
+                            table_name,
+                            assignments.join(", ")
+                        )?;
+                        if let Some(predicate) = predicate {
+                            write!(f, " predicate={:?}", predicate)?;
+                        }
+                        Ok(())
+                    }
                     LogicalPlan::Explain { .. } => write!(f, "Explain"),
                     LogicalPlan::Analyze { .. } => write!(f, "Analyze"),
                     LogicalPlan::Union(_) => write!(f, "Union"),
@@ -0,0 +1,247 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Redaction of the literals of logical plans, e.g. to log the shapes of the
+//! queries without leaking the personal data of their literals
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::plan::{Explain, TableScan};
+use super::{
+    Column, ExprRewritable, ExprRewriter, LogicalPlan, PlanType, ToStringifiedPlan,
+};
+use crate::error::Result;
+use crate::logical_plan::Expr;
+use crate::optimizer::utils::from_plan;
+use crate::scalar::ScalarValue;
+
+/// A logical plan whose literals are replaced by typed placeholders, see
+/// [`sanitize_plan`]
+#[derive(Debug, Clone)]
+pub struct SanitizedPlan {
+    /// The plan, whose literals are replaced by the variables `$1`, `$2`..
+    /// of their type. It is meant to be displayed rather than executed, and
+    /// its schemas are the ones of the original plan.
+    pub plan: LogicalPlan,
+    /// The indented display of the plan, identical for the plans differing
+    /// only by the values of their literals
+    pub fingerprint: String,
+    /// The values of the placeholders, `$1` first
+    pub parameters: Vec<ScalarValue>,
+}
+
+/// Replaces the literals of the expressions of `plan` by the typed
+/// placeholders `$1`, `$2`.. numbered in the order of the nodes of its
+/// indented display, and returns the sanitized plan with its fingerprint and
+/// the values of the placeholders.
+///
+/// The literals of the statements other than queries, e.g. the location of
+/// `CREATE EXTERNAL TABLE` or the values of `SET`, are not redacted.
+pub fn sanitize_plan(plan: &LogicalPlan) -> Result<SanitizedPlan> {
+    let mut parameters = vec![];
+    let plan = sanitize_plan_internal(plan, &mut parameters, &mut HashMap::new())?;
+    let fingerprint = plan.display_indent().to_string();
+    Ok(SanitizedPlan {
+        plan,
+        fingerprint,
+        parameters,
+    })
+}
+
+/// Replaces the literals of `expr` by the typed placeholders numbered after
+/// the ones already in `parameters`, appending their values to it
+pub fn sanitize_expr(expr: Expr, parameters: &mut Vec<ScalarValue>) -> Result<Expr> {
+    expr.rewrite(&mut LiteralRedactor { parameters })
+}
+
+/// Sanitizes `plan`, renaming the columns named after the expressions of its
+/// inputs containing literals with the sanitized `names` of the expressions
+fn sanitize_plan_internal(
+    plan: &LogicalPlan,
+    parameters: &mut Vec<ScalarValue>,
+    names: &mut HashMap<String, String>,
+) -> Result<LogicalPlan> {
+    match plan {
+        LogicalPlan::TableScan(scan) => {
+            let filters = scan
+                .filters
+                .iter()
+                .map(|filter| sanitize_expr(filter.clone(), parameters))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(LogicalPlan::TableScan(TableScan {
+                filters,
+                ..scan.clone()
+            }))
+        }
+        LogicalPlan::Explain(explain) => {
+            // the plans of the explanation show the literals
+            let inner = sanitize_plan_internal(&explain.plan, parameters, names)?;
+            let stringified_plans =
+                vec![inner.to_stringified(PlanType::InitialLogicalPlan)];
+            Ok(LogicalPlan::Explain(Explain {
+                verbose: explain.verbose,
+                plan: Arc::new(inner),
+                stringified_plans,
+                schema: explain.schema.clone(),
+            }))
+        }
+        _ => {
+            let exprs = plan.expressions();
+            let sanitized = exprs
+                .iter()
+                .map(|expr| sanitize_expr(expr.clone(), parameters))
+                .collect::<Result<Vec<_>>>()?;
+            let inputs = plan
+                .inputs()
+                .into_iter()
+                .map(|input| sanitize_plan_internal(input, parameters, names))
+                .collect::<Result<Vec<_>>>()?;
+            let sanitized = sanitized
+                .into_iter()
+                .map(|expr| expr.rewrite(&mut ColumnRenamer { names }))
+                .collect::<Result<Vec<_>>>()?;
+
+            // the columns derived from the expressions, e.g. by an aggregate,
+            // are named after the expressions
+            if let [input] = plan.inputs().as_slice() {
+                for (expr, sanitized) in exprs.iter().zip(&sanitized) {
+                    let name = expr.name(input.schema());
+                    let sanitized_name = sanitized.name(input.schema());
+                    if let (Ok(name), Ok(sanitized_name)) = (name, sanitized_name) {
+                        if name != sanitized_name {
+                            names.insert(name, sanitized_name);
+                        }
+                    }
+                }
+            }
+            from_plan(plan, &sanitized, &inputs)
+        }
+    }
+}
+
+/// Replaces the literals by placeholders, recording their values
+struct LiteralRedactor<'a> {
+    parameters: &'a mut Vec<ScalarValue>,
+}
+
+impl ExprRewriter for LiteralRedactor<'_> {
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        match expr {
+            Expr::Literal(value) => {
+                let data_type = value.get_datatype();
+                self.parameters.push(value);
+                let name = format!("${}", self.parameters.len());
+                Ok(Expr::ScalarVariable(data_type, vec![name]))
+            }
+            expr => Ok(expr),
+        }
+    }
+}
+
+/// Renames the columns named after the expressions containing literals
+struct ColumnRenamer<'a> {
+    names: &'a HashMap<String, String>,
+}
+
+impl ExprRewriter for ColumnRenamer<'_> {
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        match expr {
+            Expr::Column(column) => match self.names.get(&column.name) {
+                Some(name) => Ok(Expr::Column(Column {
+                    relation: column.relation,
+                    name: name.clone(),
+                })),
+                None => Ok(Expr::Column(column)),
+            },
+            expr => Ok(expr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{col, lit, sum, when, LogicalPlanBuilder};
+    use crate::test::test_table_scan;
+
+    fn plan(min_a: u32, name: &str) -> Result<LogicalPlan> {
+        LogicalPlanBuilder::from(test_table_scan()?)
+            .filter(col("a").gt(lit(min_a)))?
+            .aggregate(vec![col("b")], vec![sum(col("c"))])?
+            .project(vec![col("b"), lit(name).alias("name")])?
+            .build()
+    }
+
+    #[test]
+    fn sanitize_literals() -> Result<()> {
+        let sanitized = sanitize_plan(&plan(10, "alice")?)?;
+        let expected = "Projection: #test.b, $1 AS name\
+                        \n  Aggregate: groupBy=[[#test.b]], aggr=[[SUM(#test.c)]]\
+                        \n    Filter: #test.a > $2\
+                        \n      TableScan: test projection=None";
+        assert_eq!(sanitized.fingerprint, expected);
+        assert_eq!(
+            sanitized.parameters,
+            vec![
+                ScalarValue::Utf8(Some("alice".to_string())),
+                ScalarValue::UInt32(Some(10)),
+            ]
+        );
+
+        // the placeholders have the types of the literals
+        assert!(sanitized.plan.expressions().iter().any(|expr| matches!(
+            expr,
+            Expr::Alias(var, _) if **var == Expr::ScalarVariable(
+                arrow::datatypes::DataType::Utf8,
+                vec!["$1".to_string()]
+            )
+        )));
+
+        // the plans differing only by their literals have the same fingerprint
+        let other = sanitize_plan(&plan(20, "bob")?)?;
+        assert_eq!(other.fingerprint, sanitized.fingerprint);
+        assert_ne!(other.parameters, sanitized.parameters);
+        Ok(())
+    }
+
+    #[test]
+    fn sanitize_derived_column_names() -> Result<()> {
+        let total = sum(when(col("a").eq(lit(10u32)), col("c")).otherwise(lit(0u32))?);
+        let aggregate = LogicalPlanBuilder::from(test_table_scan()?)
+            .aggregate(vec![col("b")], vec![total])?
+            .build()?;
+        let total = Column::from_name(aggregate.schema().field(1).name());
+        let plan = LogicalPlanBuilder::from(aggregate)
+            .project(vec![col("b"), Expr::Column(total)])?
+            .build()?;
+
+        // the literals of the column derived from the aggregate are redacted
+        let sanitized = sanitize_plan(&plan)?;
+        let expected = "Projection: #test.b, \
+                        #SUM(CASE WHEN test.a = $1 THEN test.c ELSE $2 END)\
+                        \n  Aggregate: groupBy=[[#test.b]], \
+                        aggr=[[SUM(CASE WHEN #test.a = $1 THEN #test.c ELSE $2 END)]]\
+                        \n    TableScan: test projection=None";
+        assert_eq!(sanitized.fingerprint, expected);
+        assert_eq!(
+            sanitized.parameters,
+            vec![ScalarValue::UInt32(Some(10)), ScalarValue::UInt32(Some(0))]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Logical node checking that a predicate holds for all rows of its input
+
+use super::{DFSchemaRef, Expr, ExprSchemable, LogicalPlan, UserDefinedLogicalNode};
+use crate::error::{DataFusionError, Result};
+use arrow::datatypes::DataType;
+use std::{any::Any, fmt, sync::Arc};
+
+/// Passes the rows of its input through unchanged, failing the query if
+/// `predicate` doesn't evaluate to `true` for one of them.
+///
+/// This node is planned into a
+/// [`VerifyExec`](crate::physical_plan::verify::VerifyExec) by the default
+/// physical planner. Filters are never pushed below it, as that would hide
+/// rows from the verification.
+#[derive(Clone)]
+pub struct VerifyNode {
+    input: LogicalPlan,
+    predicate: Expr,
+}
+
+impl VerifyNode {
+    /// Create a new VerifyNode, checking that `predicate` is a boolean
+    /// expression over the schema of `input`
+    pub fn try_new(input: LogicalPlan, predicate: Expr) -> Result<Self> {
+        match predicate.get_type(input.schema())? {
+            DataType::Boolean => Ok(Self { input, predicate }),
+            other => Err(DataFusionError::Plan(format!(
+                "Verify predicate must return boolean values, not {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &LogicalPlan {
+        &self.input
+    }
+
+    /// The predicate checked for every row
+    pub fn predicate(&self) -> &Expr {
+        &self.predicate
+    }
+}
+
+impl fmt::Debug for VerifyNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNode for VerifyNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        self.input.schema()
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        vec![self.predicate.clone()]
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Verify: {:?}", self.predicate)
+    }
+
+    fn from_template(
+        &self,
+        exprs: &[Expr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode + Send + Sync> {
+        assert_eq!(inputs.len(), 1, "input size inconsistent");
+        assert_eq!(exprs.len(), 1, "expression size inconsistent");
+        Arc::new(Self {
+            input: inputs[0].clone(),
+            predicate: exprs[0].clone(),
+        })
+    }
+}
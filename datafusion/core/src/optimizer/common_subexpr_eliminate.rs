@@ -111,8 +111,8 @@ fn optimize(plan: &LogicalPlan, execution_props: &ExecutionProps) -> Result<Logi
             }))
         }
         LogicalPlan::Filter(Filter { predicate, input }) => {
-            let schema = plan.schema().as_ref().clone();
-            let data_type = if let Ok(data_type) = predicate.get_type(&schema) {
+            let schema = plan.schema();
+            let data_type = if let Ok(data_type) = predicate.get_type(schema.as_ref()) {
                 data_type
             } else {
                 // predicate type could not be resolved in schema, fall back to all schemas
@@ -225,6 +225,11 @@ fn optimize(plan: &LogicalPlan, execution_props: &ExecutionProps) -> Result<Logi
         | LogicalPlan::CreateCatalogSchema(_)
         | LogicalPlan::CreateCatalog(_)
         | LogicalPlan::DropTable(_)
+        | LogicalPlan::SetVariable(_)
+        | LogicalPlan::Transaction(_)
+        | LogicalPlan::OptimizeTable(_)
+        | LogicalPlan::Delete(_)
+        | LogicalPlan::Update(_)
         | LogicalPlan::Extension { .. } => {
             // apply the optimization to all inputs of the plan
             let expr = plan.expressions();
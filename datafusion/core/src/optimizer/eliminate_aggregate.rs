@@ -0,0 +1,210 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Optimizer rule to replace the aggregations of rows that are already unique
+//! by their groups (e.g. `SELECT DISTINCT` of a primary key, or a `GROUP BY`
+//! of a primary key) by a projection. This saves time in executing the query.
+use crate::error::Result;
+use crate::logical_plan::plan::{Aggregate, Projection};
+use crate::logical_plan::{lit, when, DFSchema, Expr, ExprSchemable, LogicalPlan};
+use crate::optimizer::optimizer::OptimizerRule;
+use crate::physical_plan::aggregates::AggregateFunction;
+use crate::scalar::ScalarValue;
+use std::sync::Arc;
+
+use super::utils;
+use crate::execution::context::ExecutionProps;
+
+/// Optimization rule that replaces a [LogicalPlan::Aggregate] by a
+/// [LogicalPlan::Projection] when its grouping columns contain a unique key of
+/// its input, e.g. a primary key, so that each group has a single row. The
+/// aggregate expressions must then have an equivalent on a single row, e.g.
+/// `MAX(a)` is `a`.
+#[derive(Default)]
+pub struct EliminateAggregate;
+
+impl EliminateAggregate {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for EliminateAggregate {
+    fn optimize(
+        &self,
+        plan: &LogicalPlan,
+        execution_props: &ExecutionProps,
+    ) -> Result<LogicalPlan> {
+        match plan {
+            LogicalPlan::Aggregate(Aggregate {
+                input,
+                group_expr,
+                aggr_expr,
+                schema,
+            }) => {
+                let input_schema = input.schema();
+                let group_indices = group_expr
+                    .iter()
+                    .map(|expr| match expr {
+                        Expr::Column(column) => input_schema.index_of_column(column).ok(),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>();
+                let unique = group_indices.map_or(false, |group_indices| {
+                    input_schema
+                        .functional_dependencies()
+                        .is_unique(&group_indices)
+                });
+                // without groups, an aggregation of no row still produces a row
+                if !unique || group_expr.is_empty() {
+                    return utils::optimize_children(self, plan, execution_props);
+                }
+                let mut expr = group_expr.clone();
+                for aggr_expr in aggr_expr {
+                    match single_row_aggregate(aggr_expr, input_schema)? {
+                        Some(single_row_expr) => expr
+                            .push(single_row_expr.alias(&aggr_expr.name(input_schema)?)),
+                        // the aggregation is required
+                        None => {
+                            return utils::optimize_children(self, plan, execution_props)
+                        }
+                    }
+                }
+                Ok(LogicalPlan::Projection(Projection {
+                    expr,
+                    input: Arc::new(self.optimize(input, execution_props)?),
+                    schema: schema.clone(),
+                    alias: None,
+                }))
+            }
+            _ => utils::optimize_children(self, plan, execution_props),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "eliminate_aggregate"
+    }
+}
+
+/// Returns the value of the aggregate expression `expr` over a single row,
+/// if it can be computed without aggregating
+fn single_row_aggregate(expr: &Expr, schema: &DFSchema) -> Result<Option<Expr>> {
+    let (fun, arg) = match expr {
+        Expr::AggregateFunction { fun, args, .. } if args.len() == 1 => (fun, &args[0]),
+        _ => return Ok(None),
+    };
+    Ok(match fun {
+        AggregateFunction::Min | AggregateFunction::Max => Some(arg.clone()),
+        AggregateFunction::Sum => Some(Expr::Cast {
+            expr: Box::new(arg.clone()),
+            data_type: expr.get_type(schema)?,
+        }),
+        AggregateFunction::Count => match arg {
+            // e.g. COUNT(*)
+            Expr::Literal(value) if !value.is_null() => {
+                Some(lit(ScalarValue::Int64(Some(1))))
+            }
+            arg => Some(
+                when(arg.clone().is_null(), lit(ScalarValue::Int64(Some(0))))
+                    .otherwise(lit(ScalarValue::Int64(Some(1))))?,
+            ),
+        },
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::MemTable;
+    use crate::logical_plan::{avg, col, count, max, sum, LogicalPlanBuilder};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let rule = EliminateAggregate::new();
+        let optimized_plan = rule
+            .optimize(plan, &ExecutionProps::new())
+            .expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+        assert_eq!(plan.schema(), optimized_plan.schema());
+    }
+
+    /// a table whose column `a` is the primary key
+    fn keyed_table_scan() -> Result<LogicalPlan> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::UInt32, false),
+        ]));
+        let table = MemTable::try_new(schema, vec![])?.with_primary_key(vec![0])?;
+        LogicalPlanBuilder::scan("test", Arc::new(table), None)?.build()
+    }
+
+    #[test]
+    fn distinct_key() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(keyed_table_scan()?)
+            .aggregate(vec![col("b"), col("a")], Vec::<Expr>::new())?
+            .build()?;
+
+        let expected = "Projection: #test.b, #test.a\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn distinct_without_key() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(keyed_table_scan()?)
+            .aggregate(vec![col("b")], Vec::<Expr>::new())?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[#test.b]], aggr=[[]]\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_key() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(keyed_table_scan()?)
+            .aggregate(
+                vec![col("a")],
+                vec![max(col("b")), sum(col("b")), count(col("b"))],
+            )?
+            .build()?;
+
+        let expected = "Projection: #test.a, #test.b AS MAX(test.b), \
+        CAST(#test.b AS UInt64) AS SUM(test.b), \
+        CASE WHEN #test.b IS NULL THEN Int64(0) ELSE Int64(1) END AS COUNT(test.b)\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_key_unsupported_aggregate() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(keyed_table_scan()?)
+            .aggregate(vec![col("a")], vec![avg(col("b"))])?
+            .build()?;
+
+        let expected = "Aggregate: groupBy=[[#test.a]], aggr=[[AVG(#test.b)]]\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+}
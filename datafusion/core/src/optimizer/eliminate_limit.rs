@@ -15,7 +15,8 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! Optimizer rule to replace `LIMIT 0` on a plan with an empty relation.
+//! Optimizer rule to replace `LIMIT 0` on a plan with an empty relation,
+//! merge adjacent limits and remove limits that can not reduce the number of rows.
 //! This saves time in planning and executing the query.
 use crate::error::Result;
 use crate::logical_plan::{EmptyRelation, Limit, LogicalPlan};
@@ -24,7 +25,9 @@ use crate::optimizer::optimizer::OptimizerRule;
 use super::utils;
 use crate::execution::context::ExecutionProps;
 
-/// Optimization rule that replaces LIMIT 0 with an [LogicalPlan::EmptyRelation]
+/// Optimization rule that replaces LIMIT 0 with an [LogicalPlan::EmptyRelation],
+/// merges a [LogicalPlan::Limit] on top of another one, and removes a
+/// [LogicalPlan::Limit] whose input is known to produce at most the limit of rows
 #[derive(Default)]
 pub struct EliminateLimit;
 
@@ -48,6 +51,24 @@ impl OptimizerRule for EliminateLimit {
                     schema: input.schema().clone(),
                 }))
             }
+            LogicalPlan::Limit(Limit { n, input }) => match input.as_ref() {
+                // merge the limits, keeping the smallest
+                LogicalPlan::Limit(Limit {
+                    n: input_n,
+                    input: input_input,
+                }) => self.optimize(
+                    &LogicalPlan::Limit(Limit {
+                        n: (*n).min(*input_n),
+                        input: input_input.clone(),
+                    }),
+                    execution_props,
+                ),
+                // the limit can not reduce the number of rows
+                input if matches!(utils::max_rows(input), Some(rows) if rows <= *n) => {
+                    self.optimize(input, execution_props)
+                }
+                _ => utils::optimize_children(self, plan, execution_props),
+            },
             // Rest: recurse and find possible LIMIT 0 nodes
             _ => {
                 let expr = plan.expressions();
@@ -73,7 +94,7 @@ impl OptimizerRule for EliminateLimit {
 mod tests {
     use super::*;
     use crate::logical_plan::LogicalPlanBuilder;
-    use crate::logical_plan::{col, sum};
+    use crate::logical_plan::{col, sum, Expr};
     use crate::test::*;
 
     fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
@@ -102,6 +123,41 @@ mod tests {
         assert_optimized_plan_eq(&plan, expected);
     }
 
+    #[test]
+    fn merge_limits() {
+        let table_scan = test_table_scan().unwrap();
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .limit(10)
+            .unwrap()
+            .limit(1000)
+            .unwrap()
+            .limit(100)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let expected = "Limit: 10\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+    }
+
+    #[test]
+    fn limit_on_fewer_rows() {
+        let table_scan = test_table_scan().unwrap();
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(Vec::<Expr>::new(), vec![sum(col("b"))])
+            .unwrap()
+            .limit(5)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        // the aggregate without grouping produces a single row
+        let expected = "Aggregate: groupBy=[[]], aggr=[[SUM(#test.b)]]\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+    }
+
     #[test]
     fn limit_0_nested() {
         let table_scan = test_table_scan().unwrap();
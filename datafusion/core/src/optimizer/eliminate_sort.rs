@@ -0,0 +1,138 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Optimizer rule to remove sorts that can not change the order of the rows:
+//! sorts of at most one row and sorts immediately re-sorted by another sort.
+//! This saves time in executing the query.
+use crate::error::Result;
+use crate::logical_plan::plan::Sort;
+use crate::logical_plan::LogicalPlan;
+use crate::optimizer::optimizer::OptimizerRule;
+
+use super::utils;
+use crate::execution::context::ExecutionProps;
+
+/// Optimization rule that removes a [LogicalPlan::Sort] whose input is known to
+/// produce at most one row, or whose output is sorted again by another
+/// [LogicalPlan::Sort]
+#[derive(Default)]
+pub struct EliminateSort;
+
+impl EliminateSort {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for EliminateSort {
+    fn optimize(
+        &self,
+        plan: &LogicalPlan,
+        execution_props: &ExecutionProps,
+    ) -> Result<LogicalPlan> {
+        match plan {
+            LogicalPlan::Sort(Sort { input, .. }) if matches!(utils::max_rows(input), Some(rows) if rows <= 1) => {
+                self.optimize(input, execution_props)
+            }
+            // the order of the inner sort is lost by the outer sort
+            LogicalPlan::Sort(Sort { expr, input }) => match input.as_ref() {
+                LogicalPlan::Sort(Sort {
+                    input: input_input, ..
+                }) => self.optimize(
+                    &LogicalPlan::Sort(Sort {
+                        expr: expr.clone(),
+                        input: input_input.clone(),
+                    }),
+                    execution_props,
+                ),
+                _ => utils::optimize_children(self, plan, execution_props),
+            },
+            _ => utils::optimize_children(self, plan, execution_props),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "eliminate_sort"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{col, sum, Expr, LogicalPlanBuilder};
+    use crate::test::*;
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let rule = EliminateSort::new();
+        let optimized_plan = rule
+            .optimize(plan, &ExecutionProps::new())
+            .expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+        assert_eq!(plan.schema(), optimized_plan.schema());
+    }
+
+    #[test]
+    fn sort_single_row() {
+        let table_scan = test_table_scan().unwrap();
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(Vec::<Expr>::new(), vec![sum(col("b")).alias("total")])
+            .unwrap()
+            .sort(vec![col("total").sort(true, false)])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let expected = "Aggregate: groupBy=[[]], aggr=[[SUM(#test.b) AS total]]\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+    }
+
+    #[test]
+    fn sort_of_sort() {
+        let table_scan = test_table_scan().unwrap();
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .sort(vec![col("a").sort(true, false)])
+            .unwrap()
+            .sort(vec![col("b").sort(false, true)])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let expected = "Sort: #test.b DESC NULLS FIRST\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+    }
+
+    #[test]
+    fn sort_of_multiple_rows() {
+        let table_scan = test_table_scan().unwrap();
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .aggregate(vec![col("a")], vec![sum(col("b"))])
+            .unwrap()
+            .sort(vec![col("a").sort(true, false)])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let expected = "Sort: #test.a ASC NULLS LAST\
+        \n  Aggregate: groupBy=[[#test.a]], aggr=[[SUM(#test.b)]]\
+        \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+    }
+}
@@ -14,6 +14,7 @@
 
 //! Filter Push Down optimizer rule ensures that filters are applied as early as possible in the plan
 
+use crate::error::Result;
 use crate::execution::context::ExecutionProps;
 use crate::logical_expr::TableProviderFilterPushDown;
 use crate::logical_plan::plan::{Aggregate, Filter, Join, Projection, Union};
@@ -23,7 +24,6 @@ use crate::logical_plan::{
 use crate::logical_plan::{DFSchema, Expr};
 use crate::optimizer::optimizer::OptimizerRule;
 use crate::optimizer::utils;
-use crate::{error::Result, logical_plan::Operator};
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
@@ -158,24 +158,6 @@ fn issue_filters(
     push_down(&state, &plan)
 }
 
-/// converts "A AND B AND C" => [A, B, C]
-fn split_members<'a>(predicate: &'a Expr, predicates: &mut Vec<&'a Expr>) {
-    match predicate {
-        Expr::BinaryExpr {
-            right,
-            op: Operator::And,
-            left,
-        } => {
-            split_members(left, predicates);
-            split_members(right, predicates);
-        }
-        Expr::Alias(expr, _) => {
-            split_members(expr, predicates);
-        }
-        other => predicates.push(other),
-    }
-}
-
 // For a given JOIN logical plan, determine whether each side of the join is preserved.
 // We say a join side is preserved if the join returns all or a subset of the rows from
 // the relevant side, such that each row of the output table directly maps to a row of
@@ -305,7 +287,7 @@ fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
         LogicalPlan::Analyze { .. } => push_down(&state, plan),
         LogicalPlan::Filter(Filter { input, predicate }) => {
             let mut predicates = vec![];
-            split_members(predicate, &mut predicates);
+            utils::split_conjunction(predicate, &mut predicates);
 
             // Predicates without referencing columns (WHERE FALSE, WHERE 1=1, etc.)
             let mut no_col_predicates = vec![];
@@ -339,6 +321,21 @@ fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
             schema,
             alias: _,
         }) => {
+            // The filters on the volatile expressions of the projection, e.g.
+            // calling random() or an async function, are kept above it, as
+            // pushing them down would evaluate the expressions twice
+            let mut volatile_columns = HashSet::new();
+            for (field, expr) in schema.fields().iter().zip(expr) {
+                if utils::is_volatile(expr)? {
+                    volatile_columns.insert(field.qualified_column());
+                    volatile_columns.insert(field.unqualified_column());
+                }
+            }
+            let (predicates, predicate_columns) =
+                get_predicates(&state, &volatile_columns);
+            let predicates = predicates.into_iter().cloned().collect::<Vec<_>>();
+            state.filters = remove_filters(&state.filters, &predicate_columns);
+
             // A projection is filter-commutable, but re-writes all predicate expressions
             // collect projection.
             let projection = schema
@@ -368,7 +365,12 @@ fn optimize(plan: &LogicalPlan, mut state: State) -> Result<LogicalPlan> {
             // optimize inner
             let new_input = optimize(input, state)?;
 
-            utils::from_plan(plan, expr, &[new_input])
+            let plan = utils::from_plan(plan, expr, &[new_input])?;
+            if predicates.is_empty() {
+                Ok(plan)
+            } else {
+                Ok(add_filter(plan, &predicates.iter().collect::<Vec<_>>()))
+            }
         }
         LogicalPlan::Aggregate(Aggregate {
             aggr_expr, input, ..
@@ -594,6 +596,7 @@ fn rewrite(expr: &Expr, projection: &HashMap<String, Expr>) -> Result<Expr> {
 mod tests {
     use super::*;
     use crate::datasource::TableProvider;
+    use crate::logical_expr::BuiltinScalarFunction;
     use crate::logical_plan::{
         lit, sum, union_with_alias, DFSchema, Expr, LogicalPlanBuilder, Operator,
     };
@@ -635,6 +638,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn filter_on_volatile_projection() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let random = Expr::ScalarFunction {
+            fun: BuiltinScalarFunction::Random,
+            args: vec![],
+        };
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .project(vec![col("a"), random.alias("r")])?
+            .filter(col("r").lt(lit(0.5f64)).and(col("a").eq(lit(1i64))))?
+            .build()?;
+        // the filter on the volatile expression isn't evaluated twice
+        let expected = "\
+            Filter: #r < Float64(0.5)\
+            \n  Projection: #test.a, random() AS r\
+            \n    Filter: #test.a = Int64(1)\
+            \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
     #[test]
     fn filter_after_limit() -> Result<()> {
         let table_scan = test_table_scan()?;
@@ -0,0 +1,122 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Optimizer rule to merge a filter on top of another filter into a single filter.
+//! This saves evaluating the predicates in separate operators.
+use crate::error::Result;
+use crate::logical_plan::plan::Filter;
+use crate::logical_plan::LogicalPlan;
+use crate::optimizer::optimizer::OptimizerRule;
+
+use super::utils;
+use crate::execution::context::ExecutionProps;
+
+/// Optimization rule that merges adjacent [LogicalPlan::Filter]s, combining
+/// their predicates with `AND`
+#[derive(Default)]
+pub struct MergeFilters;
+
+impl MergeFilters {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for MergeFilters {
+    fn optimize(
+        &self,
+        plan: &LogicalPlan,
+        execution_props: &ExecutionProps,
+    ) -> Result<LogicalPlan> {
+        let plan = utils::optimize_children(self, plan, execution_props)?;
+        match plan {
+            LogicalPlan::Filter(Filter { predicate, input }) => match input.as_ref() {
+                // the inner filter is already merged with its own input filters
+                LogicalPlan::Filter(Filter {
+                    predicate: input_predicate,
+                    input: input_input,
+                }) => Ok(LogicalPlan::Filter(Filter {
+                    predicate: input_predicate.clone().and(predicate),
+                    input: input_input.clone(),
+                })),
+                _ => Ok(LogicalPlan::Filter(Filter { predicate, input })),
+            },
+            plan => Ok(plan),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "merge_filters"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{col, lit, sum, LogicalPlanBuilder};
+    use crate::test::*;
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let rule = MergeFilters::new();
+        let optimized_plan = rule
+            .optimize(plan, &ExecutionProps::new())
+            .expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+        assert_eq!(plan.schema(), optimized_plan.schema());
+    }
+
+    #[test]
+    fn merge_stacked_filters() {
+        let table_scan = test_table_scan().unwrap();
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(col("a").gt(lit(1)))
+            .unwrap()
+            .filter(col("b").lt(lit(2)))
+            .unwrap()
+            .filter(col("c").eq(lit(3)))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let expected =
+            "Filter: #test.a > Int32(1) AND #test.b < Int32(2) AND #test.c = Int32(3)\
+        \n  TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+    }
+
+    #[test]
+    fn filters_separated_by_aggregate() {
+        let table_scan = test_table_scan().unwrap();
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(col("a").gt(lit(1)))
+            .unwrap()
+            .aggregate(vec![col("a")], vec![sum(col("b"))])
+            .unwrap()
+            .filter(col("a").lt(lit(10)))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let expected = "Filter: #test.a < Int32(10)\
+        \n  Aggregate: groupBy=[[#test.a]], aggr=[[SUM(#test.b)]]\
+        \n    Filter: #test.a > Int32(1)\
+        \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+    }
+}
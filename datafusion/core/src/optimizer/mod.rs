@@ -20,13 +20,18 @@
 
 #![allow(clippy::module_inception)]
 pub mod common_subexpr_eliminate;
+pub mod eliminate_aggregate;
 pub mod eliminate_filter;
 pub mod eliminate_limit;
+pub mod eliminate_sort;
 pub mod filter_push_down;
 pub mod limit_push_down;
+pub mod merge_filters;
 pub mod optimizer;
 pub mod projection_push_down;
+pub mod reuse_filter_predicates;
 pub mod simplify_expressions;
 pub mod single_distinct_to_groupby;
 pub mod to_approx_perc;
+pub mod union_distinct;
 pub mod utils;
@@ -20,11 +20,12 @@
 
 use crate::error::{DataFusionError, Result};
 use crate::execution::context::ExecutionProps;
+use crate::logical_plan::builder::scan_functional_dependencies;
 use crate::logical_plan::plan::{
     Aggregate, Analyze, Join, Projection, SubqueryAlias, TableScan, Window,
 };
 use crate::logical_plan::{
-    build_join_schema, Column, DFField, DFSchema, DFSchemaRef, LogicalPlan,
+    build_join_schema, Column, Constraints, DFField, DFSchema, DFSchemaRef, LogicalPlan,
     LogicalPlanBuilder, ToDFSchema, Union,
 };
 use crate::optimizer::optimizer::OptimizerRule;
@@ -73,6 +74,7 @@ impl ProjectionPushDown {
 fn get_projected_schema(
     table_name: Option<&String>,
     schema: &Schema,
+    constraints: Option<&Constraints>,
     required_columns: &HashSet<Column>,
     has_projection: bool,
 ) -> Result<(Vec<usize>, DFSchemaRef)> {
@@ -121,7 +123,12 @@ fn get_projected_schema(
     };
 
     let projection = projection.into_iter().collect::<Vec<_>>();
-    Ok((projection, projected_fields.to_dfschema_ref()?))
+    let functional_dependencies =
+        scan_functional_dependencies(constraints, schema, Some(&projection));
+    let projected_schema = projected_fields
+        .to_dfschema()?
+        .with_functional_dependencies(functional_dependencies);
+    Ok((projection, Arc::new(projected_schema)))
 }
 
 /// Recursively transverses the logical plan removing expressions and that are not needed.
@@ -146,6 +153,7 @@ fn optimize_plan(
 
             let mut new_expr = Vec::new();
             let mut new_fields = Vec::new();
+            let mut kept_indices = Vec::new();
 
             // Gather all columns needed for expressions in this Projection
             schema
@@ -156,6 +164,7 @@ fn optimize_plan(
                     if required_columns.contains(&field.qualified_column()) {
                         new_expr.push(expr[i].clone());
                         new_fields.push(field.clone());
+                        kept_indices.push(Some(i));
 
                         // gather the new set of required columns
                         utils::expr_to_columns(&expr[i], &mut new_required_columns)
@@ -189,9 +198,12 @@ fn optimize_plan(
                 Ok(LogicalPlan::Projection(Projection {
                     expr: new_expr,
                     input: Arc::new(new_input),
-                    schema: DFSchemaRef::new(DFSchema::new_with_metadata(
-                        new_fields, metadata,
-                    )?),
+                    schema: DFSchemaRef::new(
+                        DFSchema::new_with_metadata(new_fields, metadata)?
+                            .with_functional_dependencies(
+                                schema.functional_dependencies().project(&kept_indices),
+                            ),
+                    ),
                     alias: alias.clone(),
                 }))
             }
@@ -230,6 +242,7 @@ fn optimize_plan(
                 optimized_left.schema(),
                 optimized_right.schema(),
                 join_type,
+                on,
             )?;
 
             Ok(LogicalPlan::Join(Join {
@@ -310,15 +323,24 @@ fn optimize_plan(
                 }
             })?;
 
+            let kept_indices = schema
+                .fields()
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| new_required_columns.contains(&x.qualified_column()))
+                .map(|(i, _)| Some(i))
+                .collect::<Vec<_>>();
             let new_schema = DFSchema::new_with_metadata(
-                schema
-                    .fields()
+                kept_indices
                     .iter()
-                    .filter(|x| new_required_columns.contains(&x.qualified_column()))
-                    .cloned()
+                    .flatten()
+                    .map(|i| schema.field(*i).clone())
                     .collect(),
                 schema.metadata().clone(),
-            )?;
+            )?
+            .with_functional_dependencies(
+                schema.functional_dependencies().project(&kept_indices),
+            );
 
             Ok(LogicalPlan::Aggregate(Aggregate {
                 group_expr: group_expr.clone(),
@@ -345,6 +367,7 @@ fn optimize_plan(
             let (projection, projected_schema) = get_projected_schema(
                 Some(table_name),
                 &source.schema(),
+                source.constraints(),
                 required_columns,
                 has_projection,
             )?;
@@ -473,6 +496,11 @@ fn optimize_plan(
         | LogicalPlan::CreateCatalogSchema(_)
         | LogicalPlan::CreateCatalog(_)
         | LogicalPlan::DropTable(_)
+        | LogicalPlan::SetVariable(_)
+        | LogicalPlan::Transaction(_)
+        | LogicalPlan::OptimizeTable(_)
+        | LogicalPlan::Delete(_)
+        | LogicalPlan::Update(_)
         | LogicalPlan::CrossJoin(_)
         | LogicalPlan::Extension { .. } => {
             let expr = plan.expressions();
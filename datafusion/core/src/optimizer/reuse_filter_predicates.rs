@@ -0,0 +1,214 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Optimizer rule reusing the outcome of a filter in the projection on top of it.
+//! Every row surviving a filter evaluated its predicate to `true`, so the
+//! projection does not need to evaluate the same predicate again.
+use datafusion_expr::Expr;
+
+use crate::error::Result;
+use crate::logical_plan::plan::{Filter, Projection};
+use crate::logical_plan::{
+    lit, ExprRewritable, ExprRewriter, LogicalPlan, RewriteRecursion,
+};
+use crate::optimizer::optimizer::OptimizerRule;
+
+use super::utils;
+use crate::execution::context::ExecutionProps;
+
+/// Optimization rule that replaces the conjuncts of a [LogicalPlan::Filter]
+/// predicate found in the expressions of the [LogicalPlan::Projection] right
+/// above it with `true`.
+///
+/// For example, in `SELECT CASE WHEN a > 5 THEN b END FROM t WHERE a > 5`,
+/// `a > 5` is only computed once per batch, by the filter.
+#[derive(Default)]
+pub struct ReuseFilterPredicates;
+
+impl ReuseFilterPredicates {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl OptimizerRule for ReuseFilterPredicates {
+    fn optimize(
+        &self,
+        plan: &LogicalPlan,
+        execution_props: &ExecutionProps,
+    ) -> Result<LogicalPlan> {
+        let plan = utils::optimize_children(self, plan, execution_props)?;
+        match plan {
+            LogicalPlan::Projection(Projection {
+                expr,
+                input,
+                schema,
+                alias,
+            }) => {
+                let mut predicates = vec![];
+                collect_filter_predicates(&input, &mut predicates)?;
+                if predicates.is_empty() {
+                    return Ok(LogicalPlan::Projection(Projection {
+                        expr,
+                        input,
+                        schema,
+                        alias,
+                    }));
+                }
+
+                let expr = expr
+                    .into_iter()
+                    .map(|e| {
+                        let new_expr =
+                            e.clone().rewrite(&mut PredicateRewriter(&predicates))?;
+                        match new_expr {
+                            new_expr if new_expr == e => Ok(new_expr),
+                            // aliases already keep the name of the expression
+                            Expr::Alias(..) => Ok(new_expr),
+                            // keep the name of the projected expression
+                            new_expr => Ok(new_expr.alias(&e.name(input.schema())?)),
+                        }
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(LogicalPlan::Projection(Projection {
+                    expr,
+                    input,
+                    schema,
+                    alias,
+                }))
+            }
+            plan => Ok(plan),
+        }
+    }
+
+    fn name(&self) -> &str {
+        "reuse_filter_predicates"
+    }
+}
+
+/// Collects the conjuncts of the predicates of the chain of filters at the
+/// top of `plan` that are worth reusing
+fn collect_filter_predicates(
+    plan: &LogicalPlan,
+    predicates: &mut Vec<Expr>,
+) -> Result<()> {
+    if let LogicalPlan::Filter(Filter { predicate, input }) = plan {
+        let mut conjuncts = vec![];
+        utils::split_conjunction(predicate, &mut conjuncts);
+        for conjunct in conjuncts {
+            // reading a boolean column is as cheap as reading a literal
+            if !matches!(conjunct, Expr::Column(_) | Expr::Literal(_))
+                && !utils::is_volatile(conjunct)?
+            {
+                predicates.push(conjunct.clone());
+            }
+        }
+        collect_filter_predicates(input, predicates)?;
+    }
+    Ok(())
+}
+
+/// Replaces the reused predicates with `true`
+struct PredicateRewriter<'a>(&'a [Expr]);
+
+impl ExprRewriter for PredicateRewriter<'_> {
+    fn pre_visit(&mut self, expr: &Expr) -> Result<RewriteRecursion> {
+        if self.0.contains(expr) {
+            Ok(RewriteRecursion::Mutate)
+        } else {
+            Ok(RewriteRecursion::Continue)
+        }
+    }
+
+    fn mutate(&mut self, expr: Expr) -> Result<Expr> {
+        if self.0.contains(&expr) {
+            Ok(lit(true))
+        } else {
+            Ok(expr)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::{col, random, when, LogicalPlanBuilder};
+    use crate::test::*;
+
+    fn assert_optimized_plan_eq(plan: &LogicalPlan, expected: &str) {
+        let rule = ReuseFilterPredicates::new();
+        let optimized_plan = rule
+            .optimize(plan, &ExecutionProps::new())
+            .expect("failed to optimize plan");
+        let formatted_plan = format!("{:?}", optimized_plan);
+        assert_eq!(formatted_plan, expected);
+        assert_eq!(plan.schema(), optimized_plan.schema());
+    }
+
+    #[test]
+    fn reuse_filter_predicate_in_case() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(col("a").gt(lit(5u32)).and(col("b").lt(lit(2u32))))?
+            .project(vec![
+                when(col("a").gt(lit(5u32)), col("c")).end()?,
+                col("b").lt(lit(2u32)).alias("b_lt_2"),
+                col("a").lt(lit(5u32)),
+            ])?
+            .build()?;
+
+        let expected = "Projection: CASE WHEN Boolean(true) THEN #test.c END AS CASE WHEN test.a > UInt32(5) THEN test.c END, Boolean(true) AS b_lt_2, #test.a < UInt32(5)\
+        \n  Filter: #test.a > UInt32(5) AND #test.b < UInt32(2)\
+        \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn reuse_stacked_filter_predicates() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(col("a").gt(lit(5u32)))?
+            .filter(col("b").lt(lit(2u32)))?
+            .project(vec![col("a").gt(lit(5u32)).and(col("b").lt(lit(2u32)))])?
+            .build()?;
+
+        let expected = "Projection: Boolean(true) AND Boolean(true) AS test.a > UInt32(5) AND test.b < UInt32(2)\
+        \n  Filter: #test.b < UInt32(2)\
+        \n    Filter: #test.a > UInt32(5)\
+        \n      TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn keep_volatile_predicates() -> Result<()> {
+        let table_scan = test_table_scan()?;
+        let plan = LogicalPlanBuilder::from(table_scan)
+            .filter(random().gt(lit(0.5)))?
+            .project(vec![random().gt(lit(0.5))])?
+            .build()?;
+
+        let expected = "Projection: random() > Float64(0.5)\
+        \n  Filter: random() > Float64(0.5)\
+        \n    TableScan: test projection=None";
+        assert_optimized_plan_eq(&plan, expected);
+        Ok(())
+    }
+}
@@ -1176,7 +1176,7 @@ mod tests {
     ) {
         let execution_props = ExecutionProps {
             query_execution_start_time: *date_time,
-            var_providers: None,
+            ..ExecutionProps::new()
         };
 
         let mut const_evaluator = ConstEvaluator::new(&execution_props);
@@ -1734,7 +1734,7 @@ mod tests {
         let rule = SimplifyExpressions::new();
         let execution_props = ExecutionProps {
             query_execution_start_time: *date_time,
-            var_providers: None,
+            ..ExecutionProps::new()
         };
 
         let err = rule
@@ -1751,7 +1751,7 @@ mod tests {
         let rule = SimplifyExpressions::new();
         let execution_props = ExecutionProps {
             query_execution_start_time: *date_time,
-            var_providers: None,
+            ..ExecutionProps::new()
         };
 
         let optimized_plan = rule
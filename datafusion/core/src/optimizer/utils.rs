@@ -20,13 +20,16 @@
 use super::optimizer::OptimizerRule;
 use crate::execution::context::ExecutionProps;
 use crate::logical_plan::plan::{
-    Aggregate, Analyze, Extension, Filter, Join, Projection, Sort, SubqueryAlias, Window,
+    Aggregate, Analyze, CrossJoin, Extension, Filter, Join, Projection, Sort,
+    SubqueryAlias, Window,
 };
 
+use crate::logical_expr::Volatility;
+use crate::logical_plan::builder::is_unique_key;
 use crate::logical_plan::{
-    build_join_schema, Column, CreateMemoryTable, DFSchemaRef, Expr, ExprVisitable,
-    Limit, LogicalPlan, LogicalPlanBuilder, Operator, Partitioning, Recursion,
-    Repartition, Union, Values,
+    build_join_schema, Column, CreateMemoryTable, DFSchemaRef, EmptyRelation, Expr,
+    ExprVisitable, JoinType, Limit, LogicalPlan, LogicalPlanBuilder, Operator,
+    Partitioning, Recursion, Repartition, Union, Values,
 };
 use crate::prelude::lit;
 use crate::scalar::ScalarValue;
@@ -34,7 +37,6 @@ use crate::{
     error::{DataFusionError, Result},
     logical_plan::ExpressionVisitor,
 };
-use datafusion_common::DFSchema;
 use std::{collections::HashSet, sync::Arc};
 
 const CASE_EXPR_MARKER: &str = "__DATAFUSION_CASE_EXPR__";
@@ -99,6 +101,46 @@ pub fn expr_to_columns(expr: &Expr, accum: &mut HashSet<Column>) -> Result<()> {
     Ok(())
 }
 
+/// Returns true if `expr` may produce a different value each time it is evaluated
+pub fn is_volatile(expr: &Expr) -> Result<bool> {
+    struct VolatileVisitor(bool);
+
+    impl ExpressionVisitor for VolatileVisitor {
+        fn pre_visit(mut self, expr: &Expr) -> Result<Recursion<Self>> {
+            let volatility = match expr {
+                Expr::ScalarFunction { fun, .. } => Some(fun.volatility()),
+                Expr::ScalarUDF { fun, .. } => Some(fun.signature.volatility),
+                _ => None,
+            };
+            if volatility == Some(Volatility::Volatile) {
+                self.0 = true;
+                return Ok(Recursion::Stop(self));
+            }
+            Ok(Recursion::Continue(self))
+        }
+    }
+
+    Ok(expr.accept(VolatileVisitor(false))?.0)
+}
+
+/// converts "A AND B AND C" => [A, B, C]
+pub fn split_conjunction<'a>(predicate: &'a Expr, predicates: &mut Vec<&'a Expr>) {
+    match predicate {
+        Expr::BinaryExpr {
+            right,
+            op: Operator::And,
+            left,
+        } => {
+            split_conjunction(left, predicates);
+            split_conjunction(right, predicates);
+        }
+        Expr::Alias(expr, _) => {
+            split_conjunction(expr, predicates);
+        }
+        other => predicates.push(other),
+    }
+}
+
 /// Convenience rule for writing optimizers: recursively invoke
 /// optimize on plan's children and then return a node of the same
 /// type. Useful for optimizer rules which want to leave the type
@@ -119,6 +161,55 @@ pub fn optimize_children(
     from_plan(plan, &new_exprs, &new_inputs)
 }
 
+/// Returns an upper bound of the number of rows produced by `plan`, when it
+/// is known without executing the plan
+pub fn max_rows(plan: &LogicalPlan) -> Option<usize> {
+    match plan {
+        LogicalPlan::EmptyRelation(EmptyRelation {
+            produce_one_row, ..
+        }) => Some(*produce_one_row as usize),
+        LogicalPlan::Values(Values { values, .. }) => Some(values.len()),
+        LogicalPlan::Aggregate(Aggregate { group_expr, .. }) if group_expr.is_empty() => {
+            Some(1)
+        }
+        LogicalPlan::Limit(Limit { n, input }) => {
+            Some(max_rows(input).map_or(*n, |rows| rows.min(*n)))
+        }
+        LogicalPlan::Projection(Projection { input, .. })
+        | LogicalPlan::Filter(Filter { input, .. })
+        | LogicalPlan::Sort(Sort { input, .. })
+        | LogicalPlan::Window(Window { input, .. })
+        | LogicalPlan::SubqueryAlias(SubqueryAlias { input, .. }) => max_rows(input),
+        LogicalPlan::Join(Join {
+            left,
+            right,
+            on,
+            join_type,
+            ..
+        }) => {
+            let (left_keys, right_keys): (Vec<_>, Vec<_>) = on.iter().cloned().unzip();
+            match join_type {
+                // each row of a side matches at most one row of the other side
+                JoinType::Inner | JoinType::Left
+                    if is_unique_key(right.schema(), &right_keys) =>
+                {
+                    max_rows(left)
+                }
+                JoinType::Inner | JoinType::Right
+                    if is_unique_key(left.schema(), &left_keys) =>
+                {
+                    max_rows(right)
+                }
+                JoinType::Semi | JoinType::Anti => max_rows(left),
+                _ => None,
+            }
+        }
+        // all the rows have the same values of no column
+        _ if plan.schema().functional_dependencies().is_unique(&[]) => Some(1),
+        _ => None,
+    }
+}
+
 /// Returns a new logical plan based on the original one with inputs
 /// and expressions replaced.
 ///
@@ -200,33 +291,60 @@ pub fn from_plan(
             input: Arc::new(inputs[0].clone()),
         })),
         LogicalPlan::Join(Join {
+            left,
+            right,
             join_type,
             join_constraint,
             on,
+            schema,
             null_equals_null,
-            ..
         }) => {
-            let schema =
-                build_join_schema(inputs[0].schema(), inputs[1].schema(), join_type)?;
+            let schema = if same_schemas(&[left, right], inputs) {
+                schema.clone()
+            } else {
+                DFSchemaRef::new(build_join_schema(
+                    inputs[0].schema(),
+                    inputs[1].schema(),
+                    join_type,
+                    on,
+                )?)
+            };
             Ok(LogicalPlan::Join(Join {
                 left: Arc::new(inputs[0].clone()),
                 right: Arc::new(inputs[1].clone()),
                 join_type: *join_type,
                 join_constraint: *join_constraint,
                 on: on.clone(),
-                schema: DFSchemaRef::new(schema),
+                schema,
                 null_equals_null: *null_equals_null,
             }))
         }
+        LogicalPlan::CrossJoin(CrossJoin {
+            left,
+            right,
+            schema,
+        }) if same_schemas(&[left, right], inputs) => {
+            Ok(LogicalPlan::CrossJoin(CrossJoin {
+                left: Arc::new(inputs[0].clone()),
+                right: Arc::new(inputs[1].clone()),
+                schema: schema.clone(),
+            }))
+        }
         LogicalPlan::CrossJoin(_) => {
             let left = inputs[0].clone();
             let right = &inputs[1];
             LogicalPlanBuilder::from(left).cross_join(right)?.build()
         }
-        LogicalPlan::SubqueryAlias(SubqueryAlias { alias, .. }) => {
-            let schema = inputs[0].schema().as_ref().clone().into();
-            let schema =
-                DFSchemaRef::new(DFSchema::try_from_qualified_schema(alias, &schema)?);
+        LogicalPlan::SubqueryAlias(SubqueryAlias {
+            input,
+            alias,
+            schema,
+        }) => {
+            let schema = if same_schemas(&[input], inputs) {
+                schema.clone()
+            } else {
+                DFSchemaRef::new(inputs[0].schema().try_with_qualifier(alias)?)
+            };
             Ok(LogicalPlan::SubqueryAlias(SubqueryAlias {
                 alias: alias.clone(),
                 input: Arc::new(inputs[0].clone()),
@@ -283,6 +401,11 @@ pub fn from_plan(
         | LogicalPlan::TableScan { .. }
         | LogicalPlan::CreateExternalTable(_)
         | LogicalPlan::DropTable(_)
+        | LogicalPlan::SetVariable(_)
+        | LogicalPlan::Transaction(_)
+        | LogicalPlan::OptimizeTable(_)
+        | LogicalPlan::Delete(_)
+        | LogicalPlan::Update(_)
         | LogicalPlan::CreateCatalogSchema(_)
         | LogicalPlan::CreateCatalog(_) => {
             // All of these plan types have no inputs / exprs so should not be called
@@ -293,6 +416,17 @@ pub fn from_plan(
     }
 }
 
+/// Returns true if the new `inputs` of a plan have the schemas of its
+/// `old_inputs`, so that its schema derived from them can be reused instead of
+/// being rebuilt
+fn same_schemas(old_inputs: &[&Arc<LogicalPlan>], inputs: &[LogicalPlan]) -> bool {
+    old_inputs.len() == inputs.len()
+        && old_inputs.iter().zip(inputs).all(|(old, new)| {
+            let (old, new) = (old.schema(), new.schema());
+            Arc::ptr_eq(old, new) || old == new
+        })
+}
+
 /// Returns all direct children `Expression`s of `expr`.
 /// E.g. if the expression is "(a + 1) + 1", it returns ["a + 1", "1"] (as Expr objects)
 pub fn expr_sub_expressions(expr: &Expr) -> Result<Vec<Expr>> {
@@ -544,6 +678,7 @@ pub fn rewrite_expression(expr: &Expr, expressions: &[Expr]) -> Result<Expr> {
 mod tests {
     use super::*;
     use crate::logical_plan::col;
+    use crate::test::test_table_scan_with_name;
     use arrow::datatypes::DataType;
     use std::collections::HashSet;
 
@@ -568,4 +703,35 @@ mod tests {
         assert!(accum.contains(&Column::from_name("a")));
         Ok(())
     }
+
+    #[test]
+    fn from_plan_reuses_unchanged_schemas() -> Result<()> {
+        let left = test_table_scan_with_name("t1")?;
+        let right = test_table_scan_with_name("t2")?;
+        let plan = LogicalPlanBuilder::from(left)
+            .join(&right, JoinType::Inner, (vec!["t1.a"], vec!["t2.a"]))?
+            .alias("j")?
+            .build()?;
+
+        // the inputs are rebuilt with the same schemas
+        let join = plan.inputs()[0];
+        let inputs = join
+            .inputs()
+            .into_iter()
+            .map(|input| from_plan(input, &input.expressions(), &[]))
+            .collect::<Result<Vec<_>>>()?;
+        let new_join = from_plan(join, &join.expressions(), &inputs)?;
+        assert!(Arc::ptr_eq(join.schema(), new_join.schema()));
+        let new_plan = from_plan(&plan, &[], &[new_join])?;
+        assert!(Arc::ptr_eq(plan.schema(), new_plan.schema()));
+
+        // the schema is rebuilt when an input has a new schema
+        let new_right = LogicalPlanBuilder::from(test_table_scan_with_name("t2")?)
+            .project(vec![col("a"), col("b"), col("c"), lit(1).alias("d")])?
+            .build()?;
+        let new_join =
+            from_plan(join, &join.expressions(), &[inputs[0].clone(), new_right])?;
+        assert_eq!(new_join.schema().fields().len(), 7);
+        Ok(())
+    }
 }
@@ -30,7 +30,7 @@ use crate::physical_plan::{
 use crate::scalar::ScalarValue;
 
 use super::optimizer::PhysicalOptimizerRule;
-use super::utils::optimize_children;
+use super::utils::{any_node, optimize_children};
 use crate::error::Result;
 
 /// Optimizer that uses available statistics for aggregate functions
@@ -94,6 +94,21 @@ impl PhysicalOptimizerRule for AggregateStatistics {
     fn name(&self) -> &str {
         "aggregate_statistics"
     }
+
+    fn requires_statistics(
+        &self,
+        plan: &dyn ExecutionPlan,
+        _config: &SessionConfig,
+    ) -> bool {
+        any_node(plan, &|node| {
+            node.as_any()
+                .downcast_ref::<HashAggregateExec>()
+                .map_or(false, |agg_exec| {
+                    agg_exec.mode() == &AggregateMode::Final
+                        && agg_exec.group_expr().is_empty()
+                })
+        })
+    }
 }
 
 /// assert if the node passed as argument is a final `HashAggregateExec` node that can be optimized:
@@ -298,6 +313,7 @@ mod tests {
         let session_ctx = SessionContext::new();
         let task_ctx = session_ctx.task_ctx();
         let conf = session_ctx.copied_config();
+        assert!(AggregateStatistics::new().requires_statistics(&plan, &conf));
         let optimized = AggregateStatistics::new().optimize(Arc::new(plan), &conf)?;
 
         let (col, count) = match nulls {
@@ -330,6 +346,25 @@ mod tests {
         Arc::new(Count::new(expr, "my_count_alias", DataType::UInt64))
     }
 
+    #[test]
+    fn test_statistics_not_required() -> Result<()> {
+        // the statistics of the plans without aggregation are not read
+        let source = mock_data()?;
+        let conf = SessionConfig::new();
+        assert!(!AggregateStatistics::new().requires_statistics(&*source, &conf));
+
+        let schema = source.schema();
+        let final_agg = HashAggregateExec::try_new(
+            AggregateMode::Final,
+            vec![(expressions::col("a", &schema)?, "a".to_string())],
+            vec![count_expr(None, None)],
+            source,
+            Arc::clone(&schema),
+        )?;
+        assert!(!AggregateStatistics::new().requires_statistics(&final_agg, &conf));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_count_partial_direct_child() -> Result<()> {
         // basic test case with the aggregation applied on a source with exact statistics
@@ -24,7 +24,8 @@ use crate::{
     error::Result,
     physical_plan::{
         coalesce_batches::CoalesceBatchesExec, filter::FilterExec,
-        hash_join::HashJoinExec, repartition::RepartitionExec,
+        filter_project::FilterProjectExec, hash_join::HashJoinExec,
+        repartition::RepartitionExec,
     },
 };
 use std::sync::Arc;
@@ -59,6 +60,7 @@ impl PhysicalOptimizerRule for CoalesceBatches {
         // need to be wrapped in a coalesce batches operator.
         // See https://issues.apache.org/jira/browse/ARROW-11068
         let wrap_in_coalesce = plan_any.downcast_ref::<FilterExec>().is_some()
+            || plan_any.downcast_ref::<FilterProjectExec>().is_some()
             || plan_any.downcast_ref::<HashJoinExec>().is_some()
             || plan_any.downcast_ref::<RepartitionExec>().is_some();
 
@@ -0,0 +1,181 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Cost model used by the physical optimizer rules to compare alternative plans
+
+use arrow::datatypes::{DataType, Schema};
+
+use crate::physical_plan::ExecutionPlan;
+
+/// Width assumed for the values of variable length types, in bytes
+const DEFAULT_VARIABLE_WIDTH: usize = 32;
+
+/// Estimates the cost of execution plans, so that the physical optimizer rules
+/// can choose between alternative plans computing the same results, such as
+/// the order of the inputs of a join.
+///
+/// Costs are in arbitrary units, only comparable with the costs estimated by
+/// the same model. The default model is [`StatisticsCostModel`]; embedders can
+/// provide a model tuned for their workload with
+/// [`SessionConfig::with_cost_model`](crate::execution::context::SessionConfig::with_cost_model).
+pub trait CostModel: Send + Sync {
+    /// Estimated cost of executing `plan` and all its inputs, or `None` when
+    /// it can not be estimated
+    fn execution_cost(&self, plan: &dyn ExecutionPlan) -> Option<f64>;
+
+    /// Estimated cost of holding all the output of `plan` in memory, such as
+    /// the build side of a hash join, or `None` when it can not be estimated
+    fn memory_cost(&self, plan: &dyn ExecutionPlan) -> Option<f64>;
+
+    /// Whether the costs are estimated from the statistics of the plans, which
+    /// are then fetched before the rules using the model optimize the plans
+    fn requires_statistics(&self) -> bool {
+        true
+    }
+}
+
+/// [`CostModel`] based on the [`Statistics`](crate::physical_plan::Statistics)
+/// of the plans: the memory cost is the size of the output, and the execution
+/// cost is the number of rows produced by the plan and all its inputs.
+#[derive(Debug, Default)]
+pub struct StatisticsCostModel {}
+
+impl StatisticsCostModel {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl CostModel for StatisticsCostModel {
+    fn execution_cost(&self, plan: &dyn ExecutionPlan) -> Option<f64> {
+        let statistics = plan.statistics();
+        let rows = match (statistics.num_rows, statistics.total_byte_size) {
+            (Some(num_rows), _) => num_rows,
+            (None, Some(total_byte_size)) => {
+                total_byte_size / estimated_row_width(&plan.schema()).max(1)
+            }
+            (None, None) => return None,
+        };
+        plan.children().iter().try_fold(rows as f64, |cost, child| {
+            Some(cost + self.execution_cost(child.as_ref())?)
+        })
+    }
+
+    fn memory_cost(&self, plan: &dyn ExecutionPlan) -> Option<f64> {
+        let statistics = plan.statistics();
+        match (statistics.total_byte_size, statistics.num_rows) {
+            (Some(total_byte_size), _) => Some(total_byte_size as f64),
+            (None, Some(num_rows)) => {
+                Some((num_rows * estimated_row_width(&plan.schema())) as f64)
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+/// Estimated size of a row of `schema`, in bytes
+fn estimated_row_width(schema: &Schema) -> usize {
+    schema
+        .fields()
+        .iter()
+        .map(|field| estimated_value_width(field.data_type()))
+        .sum()
+}
+
+fn estimated_value_width(data_type: &DataType) -> usize {
+    match data_type {
+        DataType::Null => 0,
+        DataType::Boolean | DataType::Int8 | DataType::UInt8 => 1,
+        DataType::Int16 | DataType::UInt16 | DataType::Float16 => 2,
+        DataType::Int32
+        | DataType::UInt32
+        | DataType::Float32
+        | DataType::Date32
+        | DataType::Time32(_) => 4,
+        DataType::Int64
+        | DataType::UInt64
+        | DataType::Float64
+        | DataType::Date64
+        | DataType::Time64(_)
+        | DataType::Timestamp(_, _)
+        | DataType::Duration(_)
+        | DataType::Interval(_) => 8,
+        DataType::Decimal(_, _) => 16,
+        DataType::FixedSizeBinary(size) => *size as usize,
+        DataType::Dictionary(key_type, _) => estimated_value_width(key_type),
+        DataType::Struct(fields) => fields
+            .iter()
+            .map(|field| estimated_value_width(field.data_type()))
+            .sum(),
+        _ => DEFAULT_VARIABLE_WIDTH,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::Statistics;
+    use crate::test::exec::StatisticsExec;
+    use arrow::datatypes::Field;
+
+    fn statistics_exec(
+        num_rows: Option<usize>,
+        total_byte_size: Option<usize>,
+    ) -> StatisticsExec {
+        StatisticsExec::new(
+            Statistics {
+                num_rows,
+                total_byte_size,
+                ..Default::default()
+            },
+            Schema::new(vec![
+                Field::new("a", DataType::Int32, false),
+                Field::new("b", DataType::Utf8, false),
+            ]),
+        )
+    }
+
+    #[test]
+    fn statistics_memory_cost() {
+        let model = StatisticsCostModel::new();
+        assert_eq!(
+            model.memory_cost(&statistics_exec(Some(10), Some(1000))),
+            Some(1000.0)
+        );
+        // 4 bytes for the integers and 32 for the strings
+        assert_eq!(
+            model.memory_cost(&statistics_exec(Some(10), None)),
+            Some(360.0)
+        );
+        assert_eq!(model.memory_cost(&statistics_exec(None, None)), None);
+    }
+
+    #[test]
+    fn statistics_execution_cost() {
+        let model = StatisticsCostModel::new();
+        assert_eq!(
+            model.execution_cost(&statistics_exec(Some(10), Some(1000))),
+            Some(10.0)
+        );
+        assert_eq!(
+            model.execution_cost(&statistics_exec(None, Some(3600))),
+            Some(100.0)
+        );
+        assert_eq!(model.execution_cost(&statistics_exec(None, None)), None);
+    }
+}
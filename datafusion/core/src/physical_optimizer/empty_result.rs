@@ -0,0 +1,248 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Utilizing exact statistics to avoid executing the plans that cannot
+//! produce any row
+use std::sync::Arc;
+
+use arrow::array::{ArrayRef, UInt64Array};
+use arrow::datatypes::Schema;
+use log::debug;
+
+use crate::error::Result;
+use crate::execution::context::SessionConfig;
+use crate::logical_plan::Column;
+use crate::physical_plan::empty::EmptyExec;
+use crate::physical_plan::file_format::ParquetExec;
+use crate::physical_plan::{ColumnStatistics, ExecutionPlan, Statistics};
+
+use super::optimizer::PhysicalOptimizerRule;
+use super::pruning::PruningStatistics;
+use super::utils::optimize_children;
+
+/// Optimizer that replaces the plans that exact statistics prove to produce
+/// no rows with an [`EmptyExec`], e.g. scans without any file to read, or
+/// whose filters cannot match the min/max values of the files.
+#[derive(Default)]
+pub struct EmptyResult {}
+
+impl EmptyResult {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for EmptyResult {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &SessionConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if plan.as_any().downcast_ref::<EmptyExec>().is_none() && is_empty(&*plan) {
+            debug!("Replacing plan without any row: {:?}", plan);
+            let partitions = plan.output_partitioning().partition_count();
+            return Ok(Arc::new(
+                EmptyExec::new(false, plan.schema()).with_partitions(partitions),
+            ));
+        }
+        optimize_children(self, plan, config)
+    }
+
+    fn name(&self) -> &str {
+        "empty_result"
+    }
+}
+
+/// Returns true if the statistics of `plan` prove that it produces no rows
+fn is_empty(plan: &dyn ExecutionPlan) -> bool {
+    let stats = plan.statistics();
+    if !stats.is_exact {
+        return false;
+    }
+    if stats.num_rows == Some(0) {
+        return true;
+    }
+
+    // the min/max values of the files cannot match the pruning predicate
+    if let Some(parquet_exec) = plan.as_any().downcast_ref::<ParquetExec>() {
+        let base_config = parquet_exec.base_config();
+        if let (Some(predicate), Some(column_statistics)) = (
+            parquet_exec.pruning_predicate(),
+            &base_config.statistics.column_statistics,
+        ) {
+            let statistics = TableStatistics {
+                schema: &base_config.file_schema,
+                column_statistics,
+            };
+            return matches!(predicate.prune(&statistics).as_deref(), Ok([false]));
+        }
+    }
+    false
+}
+
+/// The statistics of a whole table, seen as a single container
+struct TableStatistics<'a> {
+    schema: &'a Schema,
+    column_statistics: &'a [ColumnStatistics],
+}
+
+impl<'a> TableStatistics<'a> {
+    fn column(&self, column: &Column) -> Option<&ColumnStatistics> {
+        let index = self.schema.index_of(&column.name).ok()?;
+        self.column_statistics.get(index)
+    }
+}
+
+impl<'a> PruningStatistics for TableStatistics<'a> {
+    fn min_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.column(column)?
+            .min_value
+            .as_ref()
+            .map(|value| value.to_array())
+    }
+
+    fn max_values(&self, column: &Column) -> Option<ArrayRef> {
+        self.column(column)?
+            .max_value
+            .as_ref()
+            .map(|value| value.to_array())
+    }
+
+    fn num_containers(&self) -> usize {
+        1
+    }
+
+    fn null_counts(&self, column: &Column) -> Option<ArrayRef> {
+        let null_count = self.column(column)?.null_count? as u64;
+        Some(Arc::new(UInt64Array::from(vec![null_count])))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datafusion_data_access::object_store::local::LocalFileSystem;
+    use crate::datasource::listing::PartitionedFile;
+    use crate::logical_plan::{col, lit, Expr, Operator};
+    use crate::physical_plan::displayable;
+    use crate::physical_plan::expressions;
+    use crate::physical_plan::file_format::FileScanConfig;
+    use crate::physical_plan::filter::FilterExec;
+    use crate::scalar::ScalarValue;
+    use arrow::datatypes::{DataType, Field};
+
+    fn parquet_exec(
+        statistics: Statistics,
+        file_groups: Vec<Vec<PartitionedFile>>,
+        predicate: Option<Expr>,
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+        Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store: Arc::new(LocalFileSystem {}),
+                file_schema: schema,
+                file_groups,
+                statistics,
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: None,
+            },
+            predicate,
+        ))
+    }
+
+    fn int_statistics(min: i32, max: i32, is_exact: bool) -> Statistics {
+        Statistics {
+            num_rows: Some(10),
+            total_byte_size: None,
+            column_statistics: Some(vec![ColumnStatistics {
+                null_count: Some(0),
+                max_value: Some(ScalarValue::Int32(Some(max))),
+                min_value: Some(ScalarValue::Int32(Some(min))),
+                distinct_count: None,
+            }]),
+            is_exact,
+        }
+    }
+
+    fn files() -> Vec<Vec<PartitionedFile>> {
+        vec![
+            vec![PartitionedFile::new("x".to_owned(), 100)],
+            vec![PartitionedFile::new("y".to_owned(), 100)],
+        ]
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Result<String> {
+        let optimized = EmptyResult::new().optimize(plan, &SessionConfig::new())?;
+        Ok(displayable(optimized.as_ref()).indent().to_string())
+    }
+
+    #[test]
+    fn disjoint_min_max() -> Result<()> {
+        let scan = parquet_exec(
+            int_statistics(1, 5, true),
+            files(),
+            Some(col("a").gt(lit(10))),
+        );
+        let filter = Arc::new(FilterExec::try_new(
+            expressions::binary(
+                expressions::col("a", &scan.schema())?,
+                Operator::Gt,
+                expressions::lit(ScalarValue::Int32(Some(10))),
+                &scan.schema(),
+            )?,
+            scan,
+        )?);
+
+        let expected = "FilterExec: a@0 > 10\
+                        \n  EmptyExec: produce_one_row=false, partitions=2\n";
+        assert_eq!(optimize(filter)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn overlapping_or_inexact_min_max() -> Result<()> {
+        let scan = parquet_exec(
+            int_statistics(1, 20, true),
+            files(),
+            Some(col("a").gt(lit(10))),
+        );
+        assert!(optimize(scan)?.starts_with("ParquetExec"));
+
+        let scan = parquet_exec(
+            int_statistics(1, 5, false),
+            files(),
+            Some(col("a").gt(lit(10))),
+        );
+        assert!(optimize(scan)?.starts_with("ParquetExec"));
+        Ok(())
+    }
+
+    #[test]
+    fn no_files() -> Result<()> {
+        let statistics = Statistics {
+            num_rows: Some(0),
+            is_exact: true,
+            ..Default::default()
+        };
+        let scan = parquet_exec(statistics, vec![], None);
+        assert_eq!(optimize(scan)?, "EmptyExec: produce_one_row=false\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Fusing the projections with the filters below them
+use std::sync::Arc;
+
+use crate::execution::context::SessionConfig;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::filter_project::FilterProjectExec;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::ExecutionPlan;
+
+use super::optimizer::PhysicalOptimizerRule;
+use super::utils::optimize_children;
+use crate::error::Result;
+
+/// FuseFilterProjection replaces a [`ProjectionExec`] directly above a
+/// [`FilterExec`] by a [`FilterProjectExec`], filtering and projecting each
+/// batch in a single pass. It only applies when enabled with
+/// [`SessionConfig::with_fuse_filter_projection`], and must run before the
+/// [`CoalesceBatches`](super::coalesce_batches::CoalesceBatches) rule
+/// separates the filters from the projections.
+#[derive(Default)]
+pub struct FuseFilterProjection {}
+
+impl FuseFilterProjection {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl PhysicalOptimizerRule for FuseFilterProjection {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        session_config: &SessionConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !session_config.fuse_filter_projection {
+            return Ok(plan);
+        }
+        let plan = optimize_children(self, plan, session_config)?;
+        if let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() {
+            if let Some(filter) = projection.input().as_any().downcast_ref::<FilterExec>()
+            {
+                return Ok(Arc::new(FilterProjectExec::try_new(
+                    filter.predicate().clone(),
+                    projection.expr().to_vec(),
+                    filter.input().clone(),
+                )?));
+            }
+        }
+        Ok(plan)
+    }
+
+    fn name(&self) -> &str {
+        "fuse_filter_projection"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::displayable;
+    use crate::physical_plan::expressions::{col, lit};
+    use crate::physical_plan::Statistics;
+    use crate::scalar::ScalarValue;
+    use crate::test::exec::StatisticsExec;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    #[test]
+    fn fuse_filter_projection() -> Result<()> {
+        let input = Arc::new(StatisticsExec::new(
+            Statistics::default(),
+            Schema::new(vec![
+                Field::new("a", DataType::Int32, false),
+                Field::new("b", DataType::Boolean, false),
+            ]),
+        ));
+        let schema = input.schema();
+        let filter = Arc::new(FilterExec::try_new(col("b", &schema)?, input)?);
+        let projection: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![(col("a", &schema)?, "a".to_string())],
+            filter,
+        )?);
+
+        // disabled by default
+        let rule = FuseFilterProjection::new();
+        let plan = rule.optimize(projection.clone(), &SessionConfig::new())?;
+        assert!(plan.as_any().downcast_ref::<ProjectionExec>().is_some());
+
+        let config = SessionConfig::new().with_fuse_filter_projection(true);
+        let plan = rule.optimize(projection, &config)?;
+        assert_eq!(
+            displayable(plan.as_ref()).indent().to_string(),
+            "FilterProjectExec: predicate=b@1, expr=[a@0 as a]\
+             \n  StatisticsExec: col_count=2, row_count=None\n"
+        );
+
+        // a projection without a filter below is kept
+        let projection: Arc<dyn ExecutionPlan> = Arc::new(ProjectionExec::try_new(
+            vec![(lit(ScalarValue::Int32(Some(1))), "one".to_string())],
+            plan,
+        )?);
+        let plan = rule.optimize(projection, &config)?;
+        assert!(plan.as_any().downcast_ref::<ProjectionExec>().is_some());
+        Ok(())
+    }
+}
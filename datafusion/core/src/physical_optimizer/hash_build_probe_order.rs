@@ -28,12 +28,14 @@ use crate::physical_plan::hash_join::HashJoinExec;
 use crate::physical_plan::projection::ProjectionExec;
 use crate::physical_plan::{ExecutionPlan, PhysicalExpr};
 
+use super::cost::CostModel;
 use super::optimizer::PhysicalOptimizerRule;
-use super::utils::optimize_children;
+use super::utils::{any_node, optimize_children};
 use crate::error::Result;
 
 /// BuildProbeOrder reorders the build and probe phase of
-/// hash joins. This uses the amount of rows that a datasource has.
+/// hash joins. This uses the memory cost of the inputs estimated by the
+/// [`CostModel`] of the session.
 /// The rule optimizes the order such that the left (build) side of the join
 /// is the smallest.
 /// If the information is not available, the order stays the same,
@@ -48,32 +50,25 @@ impl HashBuildProbeOrder {
     }
 }
 
-fn should_swap_join_order(left: &dyn ExecutionPlan, right: &dyn ExecutionPlan) -> bool {
-    // Get the left and right table's total bytes
-    // If both the left and right tables contain total_byte_size statistics,
-    // use `total_byte_size` to determine `should_swap_join_order`, else use `num_rows`
-    let (left_size, right_size) = match (
-        left.statistics().total_byte_size,
-        right.statistics().total_byte_size,
-    ) {
-        (Some(l), Some(r)) => (Some(l), Some(r)),
-        _ => (left.statistics().num_rows, right.statistics().num_rows),
-    };
-
-    match (left_size, right_size) {
+fn should_swap_join_order(
+    cost_model: &dyn CostModel,
+    left: &dyn ExecutionPlan,
+    right: &dyn ExecutionPlan,
+) -> bool {
+    match (cost_model.memory_cost(left), cost_model.memory_cost(right)) {
         (Some(l), Some(r)) => l > r,
         _ => false,
     }
 }
 
-fn supports_swap(join_type: JoinType) -> bool {
+pub(crate) fn supports_swap(join_type: JoinType) -> bool {
     match join_type {
         JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full => true,
         JoinType::Semi | JoinType::Anti => false,
     }
 }
 
-fn swap_join_type(join_type: JoinType) -> JoinType {
+pub(crate) fn swap_join_type(join_type: JoinType) -> JoinType {
     match join_type {
         JoinType::Inner => JoinType::Inner,
         JoinType::Full => JoinType::Full,
@@ -119,8 +114,11 @@ impl PhysicalOptimizerRule for HashBuildProbeOrder {
         if let Some(hash_join) = plan.as_any().downcast_ref::<HashJoinExec>() {
             let left = hash_join.left();
             let right = hash_join.right();
-            if should_swap_join_order(&**left, &**right)
-                && supports_swap(*hash_join.join_type())
+            if should_swap_join_order(
+                session_config.cost_model().as_ref(),
+                &**left,
+                &**right,
+            ) && supports_swap(*hash_join.join_type())
             {
                 let new_join = HashJoinExec::try_new(
                     Arc::clone(right),
@@ -143,7 +141,11 @@ impl PhysicalOptimizerRule for HashBuildProbeOrder {
         } else if let Some(cross_join) = plan.as_any().downcast_ref::<CrossJoinExec>() {
             let left = cross_join.left();
             let right = cross_join.right();
-            if should_swap_join_order(&**left, &**right) {
+            if should_swap_join_order(
+                session_config.cost_model().as_ref(),
+                &**left,
+                &**right,
+            ) {
                 let new_join =
                     CrossJoinExec::try_new(Arc::clone(right), Arc::clone(left))?;
                 let proj = ProjectionExec::try_new(
@@ -159,6 +161,17 @@ impl PhysicalOptimizerRule for HashBuildProbeOrder {
     fn name(&self) -> &str {
         "hash_build_probe_order"
     }
+
+    fn requires_statistics(
+        &self,
+        plan: &dyn ExecutionPlan,
+        session_config: &SessionConfig,
+    ) -> bool {
+        session_config.cost_model().requires_statistics()
+            && any_node(plan, &|node| {
+                node.as_any().is::<HashJoinExec>() || node.as_any().is::<CrossJoinExec>()
+            })
+    }
 }
 
 #[cfg(test)]
@@ -274,6 +287,53 @@ mod tests {
         );
     }
 
+    /// Cost model preferring the inputs with the most rows on the build side
+    struct MostRowsFirst {}
+
+    impl CostModel for MostRowsFirst {
+        fn execution_cost(&self, _plan: &dyn ExecutionPlan) -> Option<f64> {
+            None
+        }
+
+        fn memory_cost(&self, plan: &dyn ExecutionPlan) -> Option<f64> {
+            plan.statistics().num_rows.map(|rows| -(rows as f64))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_join_swap_with_cost_model() {
+        let (big, small) = create_big_and_small();
+
+        let join = HashJoinExec::try_new(
+            Arc::clone(&big),
+            Arc::clone(&small),
+            vec![(
+                Column::new_with_schema("big_col", &big.schema()).unwrap(),
+                Column::new_with_schema("small_col", &small.schema()).unwrap(),
+            )],
+            &JoinType::Left,
+            PartitionMode::CollectLeft,
+            &false,
+        )
+        .unwrap();
+
+        let config = SessionConfig::new().with_cost_model(Arc::new(MostRowsFirst {}));
+        let optimized_join = HashBuildProbeOrder::new()
+            .optimize(Arc::new(join), &config)
+            .unwrap();
+
+        // "small" has the most rows, the join is reordered to build on it
+        let swapped_join = optimized_join
+            .as_any()
+            .downcast_ref::<ProjectionExec>()
+            .expect("A proj is required to swap columns back to their original order")
+            .input()
+            .as_any()
+            .downcast_ref::<HashJoinExec>()
+            .expect("The type of the plan should not be changed");
+        assert_eq!(swapped_join.left().statistics().num_rows, Some(100000));
+    }
+
     #[tokio::test]
     async fn test_swap_reverting_projection() {
         let left_schema = Schema::new(vec![
@@ -0,0 +1,283 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Narrowing the build side of hash joins to the columns used after the join
+use std::sync::Arc;
+
+use crate::execution::context::SessionConfig;
+use crate::physical_plan::expressions::Column;
+use crate::physical_plan::hash_join::HashJoinExec;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::{ExecutionPlan, PhysicalExpr};
+
+use super::optimizer::PhysicalOptimizerRule;
+use super::utils::optimize_children;
+use crate::error::Result;
+
+/// JoinBuildProjection projects the left (build) side of a hash join on the
+/// columns used by the projection above the join, and on its join keys.
+/// The build side is entirely held in memory, so that the columns only
+/// carried through the join (e.g. the wide columns of a dimension table)
+/// are dropped before it is collected rather than after the join.
+/// The rule only applies when the projection above the join consists of
+/// columns.
+#[derive(Default)]
+pub struct JoinBuildProjection {}
+
+impl JoinBuildProjection {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+/// Returns the indices of the columns of the join output used by the
+/// projection, if all its expressions are columns
+fn projected_columns(projection: &ProjectionExec) -> Option<Vec<usize>> {
+    projection
+        .expr()
+        .iter()
+        .map(|(expr, _)| {
+            expr.as_any()
+                .downcast_ref::<Column>()
+                .map(|column| column.index())
+        })
+        .collect()
+}
+
+/// Returns the plan with the build side of the join below `projection`
+/// narrowed to the required columns, if any column can be dropped
+fn narrow_build_side(
+    projection: &ProjectionExec,
+    hash_join: &HashJoinExec,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let projected = match projected_columns(projection) {
+        Some(projected) => projected,
+        None => return Ok(None),
+    };
+    let left = hash_join.left();
+    let left_schema = left.schema();
+    let left_len = left_schema.fields().len();
+
+    // the columns of the build side used after the join, or as join keys,
+    // in the order of the build side
+    let mut required = projected
+        .iter()
+        .copied()
+        .filter(|index| *index < left_len)
+        .chain(hash_join.on().iter().map(|(l, _)| l.index()))
+        .collect::<Vec<_>>();
+    required.sort_unstable();
+    required.dedup();
+    if required.len() == left_len {
+        return Ok(None);
+    }
+
+    let new_index = |index: usize| {
+        if index < left_len {
+            required.iter().position(|i| *i == index).unwrap()
+        } else {
+            // the columns of the probe side follow the ones of the build side
+            index - left_len + required.len()
+        }
+    };
+
+    let new_left = ProjectionExec::try_new(
+        required
+            .iter()
+            .map(|index| {
+                let name = left_schema.field(*index).name();
+                (
+                    Arc::new(Column::new(name, *index)) as Arc<dyn PhysicalExpr>,
+                    name.to_owned(),
+                )
+            })
+            .collect(),
+        Arc::clone(left),
+    )?;
+    let new_join = HashJoinExec::try_new(
+        Arc::new(new_left),
+        Arc::clone(hash_join.right()),
+        hash_join
+            .on()
+            .iter()
+            .map(|(l, r)| (Column::new(l.name(), new_index(l.index())), r.clone()))
+            .collect(),
+        hash_join.join_type(),
+        *hash_join.partition_mode(),
+        hash_join.null_equals_null(),
+    )?;
+    let new_projection = ProjectionExec::try_new(
+        projection
+            .expr()
+            .iter()
+            .zip(projected)
+            .map(|((expr, name), index)| {
+                let column = expr.as_any().downcast_ref::<Column>().unwrap();
+                (
+                    Arc::new(Column::new(column.name(), new_index(index)))
+                        as Arc<dyn PhysicalExpr>,
+                    name.clone(),
+                )
+            })
+            .collect(),
+        Arc::new(new_join),
+    )?;
+    Ok(Some(Arc::new(new_projection)))
+}
+
+impl PhysicalOptimizerRule for JoinBuildProjection {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        session_config: &SessionConfig,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let plan = optimize_children(self, plan, session_config)?;
+        if let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() {
+            if let Some(hash_join) =
+                projection.input().as_any().downcast_ref::<HashJoinExec>()
+            {
+                if let Some(new_plan) = narrow_build_side(projection, hash_join)? {
+                    return Ok(new_plan);
+                }
+            }
+        }
+        Ok(plan)
+    }
+
+    fn name(&self) -> &str {
+        "join_build_projection"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logical_plan::JoinType;
+    use crate::physical_plan::hash_join::PartitionMode;
+    use crate::physical_plan::Statistics;
+    use crate::test::exec::StatisticsExec;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    /// Joins a dimension table (`id`, `name`, `description`) to a fact
+    /// table (`dim_id`, `value`) on `id = dim_id`, projecting `columns` of the
+    /// join output
+    fn projected_join(
+        join_type: JoinType,
+        columns: &[&str],
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let dim = Arc::new(StatisticsExec::new(
+            Statistics::default(),
+            Schema::new(vec![
+                Field::new("id", DataType::Int32, false),
+                Field::new("name", DataType::Utf8, false),
+                Field::new("description", DataType::Utf8, false),
+            ]),
+        ));
+        let fact = Arc::new(StatisticsExec::new(
+            Statistics::default(),
+            Schema::new(vec![
+                Field::new("dim_id", DataType::Int32, false),
+                Field::new("value", DataType::Int64, false),
+            ]),
+        ));
+        let join = Arc::new(HashJoinExec::try_new(
+            dim,
+            fact,
+            vec![(Column::new("id", 0), Column::new("dim_id", 0))],
+            &join_type,
+            PartitionMode::CollectLeft,
+            &false,
+        )?);
+        let join_schema = join.schema();
+        let expr = columns
+            .iter()
+            .map(|name| {
+                Ok((
+                    Arc::new(Column::new_with_schema(name, &join_schema)?)
+                        as Arc<dyn PhysicalExpr>,
+                    name.to_string(),
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Arc::new(ProjectionExec::try_new(expr, join)?))
+    }
+
+    fn column_indices(exprs: &[(Arc<dyn PhysicalExpr>, String)]) -> Vec<(String, usize)> {
+        exprs
+            .iter()
+            .map(|(expr, _)| {
+                let column = expr.as_any().downcast_ref::<Column>().unwrap();
+                (column.name().to_owned(), column.index())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn narrow_dimension_join() -> Result<()> {
+        let plan = projected_join(JoinType::Inner, &["value", "name"])?;
+        let optimized =
+            JoinBuildProjection::new().optimize(plan.clone(), &SessionConfig::new())?;
+        assert_eq!(optimized.schema(), plan.schema());
+
+        let projection = optimized
+            .as_any()
+            .downcast_ref::<ProjectionExec>()
+            .expect("the projection should be kept");
+        assert_eq!(
+            column_indices(projection.expr()),
+            vec![("value".to_owned(), 3), ("name".to_owned(), 1)]
+        );
+        let join = projection
+            .input()
+            .as_any()
+            .downcast_ref::<HashJoinExec>()
+            .expect("the join should be kept");
+        assert_eq!(join.on()[0].0, Column::new("id", 0));
+        let build = join
+            .left()
+            .as_any()
+            .downcast_ref::<ProjectionExec>()
+            .expect("the build side should be projected");
+        // `description` is dropped, the join key is kept
+        assert_eq!(
+            column_indices(build.expr()),
+            vec![("id".to_owned(), 0), ("name".to_owned(), 1)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn keep_required_build_side() -> Result<()> {
+        let plan =
+            projected_join(JoinType::Semi, &["description", "name", "id", "name"])?;
+        let optimized =
+            JoinBuildProjection::new().optimize(plan.clone(), &SessionConfig::new())?;
+        let projection = optimized.as_any().downcast_ref::<ProjectionExec>().unwrap();
+        let join = projection
+            .input()
+            .as_any()
+            .downcast_ref::<HashJoinExec>()
+            .unwrap();
+        assert!(join
+            .left()
+            .as_any()
+            .downcast_ref::<StatisticsExec>()
+            .is_some());
+        Ok(())
+    }
+}
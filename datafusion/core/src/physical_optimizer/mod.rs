@@ -20,7 +20,11 @@
 
 pub mod aggregate_statistics;
 pub mod coalesce_batches;
+pub mod cost;
+pub mod empty_result;
+pub mod fuse_filter_projection;
 pub mod hash_build_probe_order;
+pub mod join_build_projection;
 pub mod merge_exec;
 pub mod optimizer;
 pub mod pruning;
@@ -36,4 +36,16 @@ pub trait PhysicalOptimizerRule {
 
     /// A human readable name for this optimizer rule
     fn name(&self) -> &str;
+
+    /// Whether the rule reads the statistics of the nodes of `plan` when
+    /// optimizing it. The physical planner only fetches the statistics
+    /// depending on metadata, see [`ExecutionPlan::statistics_async`], when
+    /// a rule requires them.
+    fn requires_statistics(
+        &self,
+        _plan: &dyn ExecutionPlan,
+        _config: &SessionConfig,
+    ) -> bool {
+        false
+    }
 }
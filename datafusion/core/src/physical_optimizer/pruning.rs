@@ -38,13 +38,19 @@ use arrow::{
 };
 
 use crate::execution::context::ExecutionProps;
+use crate::execution::kernels::KernelRegistry;
+use crate::logical_expr::binary_rule::is_numeric;
 use crate::physical_plan::planner::create_physical_expr;
 use crate::prelude::lit;
 use crate::{
     error::{DataFusionError, Result},
-    logical_plan::{Column, DFSchema, Expr, Operator},
+    logical_plan::{
+        Column, DFSchema, Expr, ExprSchemable, ExprVisitable, ExpressionVisitor,
+        Operator, Recursion,
+    },
     optimizer::utils,
     physical_plan::{ColumnarValue, PhysicalExpr},
+    scalar::ScalarValue,
 };
 
 /// Interface to pass statistics information to [`PruningPredicate`]
@@ -122,6 +128,26 @@ impl PruningPredicate {
     /// the pruning predicate
     /// `(column_min / 2) <= 4 && 4 <= (column_max / 2))`
     pub fn try_new(expr: Expr, schema: SchemaRef) -> Result<Self> {
+        Self::try_new_with_props(expr, schema, &ExecutionProps::new())
+    }
+
+    /// Try to create a new instance of [`PruningPredicate`], evaluating the
+    /// functions and operators of `expr` as the queries of the session of
+    /// `execution_props` do, e.g. with the kernels registered in the session
+    pub fn try_new_with_props(
+        expr: Expr,
+        schema: SchemaRef,
+        execution_props: &ExecutionProps,
+    ) -> Result<Self> {
+        // the rewrites assume the semantics of the built-in implementations
+        let dfschema = DFSchema::try_from(schema.as_ref().clone())?;
+        if uses_kernels(&expr, &dfschema, execution_props)? {
+            return Err(DataFusionError::NotImplemented(format!(
+                "Pruning with the kernels of the session: {:?}",
+                expr
+            )));
+        }
+
         // build predicate expression once
         let mut required_columns = RequiredStatColumns::new();
         let logical_predicate_expr =
@@ -133,13 +159,11 @@ impl PruningPredicate {
         let stat_schema = Schema::new(stat_fields);
         let stat_dfschema = DFSchema::try_from(stat_schema.clone())?;
 
-        // TODO allow these properties to be passed in
-        let execution_props = ExecutionProps::new();
         let predicate_expr = create_physical_expr(
             &logical_predicate_expr,
             &stat_dfschema,
             &stat_schema,
-            &execution_props,
+            execution_props,
         )?;
         Ok(Self {
             schema,
@@ -428,11 +452,15 @@ impl<'a> PruningExpressionBuilder<'a> {
                 }
             };
 
-        let (column_expr, correct_operator, scalar_expr) =
-            match rewrite_expr_to_prunable(column_expr, correct_operator, scalar_expr) {
-                Ok(ret) => ret,
-                Err(e) => return Err(e),
-            };
+        let (column_expr, correct_operator, scalar_expr) = match rewrite_expr_to_prunable(
+            column_expr,
+            correct_operator,
+            scalar_expr,
+            schema,
+        ) {
+            Ok(ret) => ret,
+            Err(e) => return Err(e),
+        };
         let column = columns.iter().next().unwrap().clone();
         let field = match schema.column_with_name(&column.flat_name()) {
             Some((_, f)) => f,
@@ -479,13 +507,18 @@ impl<'a> PruningExpressionBuilder<'a> {
 /// 1. `col > 10`
 /// 2. `-col > 10` should be rewritten to `col < -10`
 /// 3. `!col = true` would be rewritten to `col = !true`
-/// 4. `abs(a - 10) > 0` not supported
+/// 4. `col + 1 > 10` should be rewritten to `col > 9`, for integer literals
+///    only and if `10 - 1` doesn't overflow
+/// 5. `cast(col as date) = '2022-01-01'` is kept, as the cast preserves the
+///    order of the values (the min/max statistics are cast the same way)
+/// 6. `abs(a - 10) > 0` not supported
 ///
 /// More rewrite rules are still in progress.
 fn rewrite_expr_to_prunable(
     column_expr: &Expr,
     op: Operator,
     scalar_expr: &Expr,
+    schema: &Schema,
 ) -> Result<(Expr, Operator, Expr)> {
     if !is_compare_op(op) {
         return Err(DataFusionError::Plan(
@@ -497,18 +530,66 @@ fn rewrite_expr_to_prunable(
         // `col > lit()`
         Expr::Column(_) => Ok((column_expr.clone(), op, scalar_expr.clone())),
 
+        // `cast(col) > lit()`
+        Expr::Cast { .. } | Expr::TryCast { .. } => {
+            order_preserving_cast_type(column_expr, schema)?;
+            Ok((column_expr.clone(), op, scalar_expr.clone()))
+        }
+
         // `-col > lit()`  --> `col < -lit()`
-        Expr::Negative(c) => match c.as_ref() {
-            Expr::Column(_) => Ok((
-                c.as_ref().clone(),
-                reverse_operator(op),
-                Expr::Negative(Box::new(scalar_expr.clone())),
-            )),
-            _ => Err(DataFusionError::Plan(format!(
-                "negative with complex expression {:?} is not supported",
-                column_expr
-            ))),
-        },
+        Expr::Negative(c) => rewrite_expr_to_prunable(
+            c,
+            reverse_operator(op),
+            &Expr::Negative(Box::new(scalar_expr.clone())),
+            schema,
+        ),
+
+        Expr::BinaryExpr {
+            left,
+            op: arith_op @ (Operator::Plus | Operator::Minus),
+            right,
+        } => {
+            let mut left_columns = HashSet::<Column>::new();
+            utils::expr_to_columns(left, &mut left_columns)?;
+            let mut right_columns = HashSet::<Column>::new();
+            utils::expr_to_columns(right, &mut right_columns)?;
+            if !left_columns.is_empty() && !right_columns.is_empty() {
+                return Err(DataFusionError::Plan(format!(
+                    "arithmetic between columns {:?} is not supported",
+                    column_expr
+                )));
+            }
+            match (left_columns.is_empty(), arith_op) {
+                // `col + lit() > lit()` --> `col > lit() - lit()`
+                (false, Operator::Plus) => rewrite_expr_to_prunable(
+                    left,
+                    op,
+                    &checked_integer_arithmetic(scalar_expr, Operator::Minus, right)?,
+                    schema,
+                ),
+                // `col - lit() > lit()` --> `col > lit() + lit()`
+                (false, _) => rewrite_expr_to_prunable(
+                    left,
+                    op,
+                    &checked_integer_arithmetic(scalar_expr, Operator::Plus, right)?,
+                    schema,
+                ),
+                // `lit() + col > lit()` --> `col > lit() - lit()`
+                (true, Operator::Plus) => rewrite_expr_to_prunable(
+                    right,
+                    op,
+                    &checked_integer_arithmetic(scalar_expr, Operator::Minus, left)?,
+                    schema,
+                ),
+                // `lit() - col > lit()` --> `col < lit() - lit()`
+                (true, _) => rewrite_expr_to_prunable(
+                    right,
+                    reverse_operator(op),
+                    &checked_integer_arithmetic(left, Operator::Minus, scalar_expr)?,
+                    schema,
+                ),
+            }
+        }
 
         // `!col = true` --> `col = !true`
         Expr::Not(c) => {
@@ -538,7 +619,113 @@ fn rewrite_expr_to_prunable(
             )))
         }
     }
-    // Ok((column_expr.clone(), op, scalar_expr.clone()))
+}
+
+/// Computes `left op right`, `op` being `+` or `-`, as a literal of the type
+/// of `left`. Only integer literals are supported, failing if the result
+/// overflows the type: the floating point values are rounded, so that moving
+/// a term to the other side of a comparison could change its result.
+fn checked_integer_arithmetic(left: &Expr, op: Operator, right: &Expr) -> Result<Expr> {
+    let result = match (integer_literal_value(left), integer_literal_value(right)) {
+        (Some((left_value, data_type)), Some((right_value, _))) => {
+            // the values of 64 bit integers can't overflow an i128
+            let value = match op {
+                Operator::Plus => left_value + right_value,
+                _ => left_value - right_value,
+            };
+            integer_literal(value, &data_type)
+        }
+        _ => None,
+    };
+    result.ok_or_else(|| {
+        DataFusionError::Plan(format!(
+            "arithmetic {:?} {} {:?} is only supported for integer literals \
+             without overflow",
+            left, op, right
+        ))
+    })
+}
+
+/// Returns the value and the type of the integer literal `expr`, possibly
+/// negated
+fn integer_literal_value(expr: &Expr) -> Option<(i128, DataType)> {
+    match expr {
+        Expr::Literal(value) => {
+            let int_value = match value {
+                ScalarValue::Int8(Some(v)) => *v as i128,
+                ScalarValue::Int16(Some(v)) => *v as i128,
+                ScalarValue::Int32(Some(v)) => *v as i128,
+                ScalarValue::Int64(Some(v)) => *v as i128,
+                ScalarValue::UInt8(Some(v)) => *v as i128,
+                ScalarValue::UInt16(Some(v)) => *v as i128,
+                ScalarValue::UInt32(Some(v)) => *v as i128,
+                ScalarValue::UInt64(Some(v)) => *v as i128,
+                _ => return None,
+            };
+            Some((int_value, value.get_datatype()))
+        }
+        Expr::Negative(expr) => {
+            let (value, data_type) = integer_literal_value(expr)?;
+            integer_literal(-value, &data_type)?;
+            Some((-value, data_type))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the literal of type `data_type` of the integer `value`, if the
+/// type can hold it
+fn integer_literal(value: i128, data_type: &DataType) -> Option<Expr> {
+    let value = match data_type {
+        DataType::Int8 => ScalarValue::Int8(Some(i8::try_from(value).ok()?)),
+        DataType::Int16 => ScalarValue::Int16(Some(i16::try_from(value).ok()?)),
+        DataType::Int32 => ScalarValue::Int32(Some(i32::try_from(value).ok()?)),
+        DataType::Int64 => ScalarValue::Int64(Some(i64::try_from(value).ok()?)),
+        DataType::UInt8 => ScalarValue::UInt8(Some(u8::try_from(value).ok()?)),
+        DataType::UInt16 => ScalarValue::UInt16(Some(u16::try_from(value).ok()?)),
+        DataType::UInt32 => ScalarValue::UInt32(Some(u32::try_from(value).ok()?)),
+        DataType::UInt64 => ScalarValue::UInt64(Some(u64::try_from(value).ok()?)),
+        _ => return None,
+    };
+    Some(Expr::Literal(value))
+}
+
+/// Returns the type of `expr`, a column possibly wrapped in casts that
+/// preserve the order of its values, so that the casts can be applied to the
+/// min/max statistics of the column
+fn order_preserving_cast_type(expr: &Expr, schema: &Schema) -> Result<DataType> {
+    match expr {
+        Expr::Column(c) => Ok(schema.field_with_name(&c.name)?.data_type().clone()),
+        Expr::Cast { expr, data_type } | Expr::TryCast { expr, data_type } => {
+            let input_type = order_preserving_cast_type(expr, schema)?;
+            if is_order_preserving_cast(&input_type, data_type) {
+                Ok(data_type.clone())
+            } else {
+                Err(DataFusionError::Plan(format!(
+                    "cast from {:?} to {:?} does not preserve the order of the values",
+                    input_type, data_type
+                )))
+            }
+        }
+        _ => Err(DataFusionError::Plan(format!(
+            "cast of complex expression {:?} is not supported",
+            expr
+        ))),
+    }
+}
+
+/// Returns true if `a <= b` implies `cast(a) <= cast(b)` for all the values
+/// of type `from`
+fn is_order_preserving_cast(from: &DataType, to: &DataType) -> bool {
+    let is_temporal = |t: &DataType| {
+        matches!(
+            t,
+            DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _)
+        )
+    };
+    from == to
+        || (is_numeric(from) && is_numeric(to))
+        || (is_temporal(from) && is_temporal(to))
 }
 
 fn is_compare_op(op: Operator) -> bool {
@@ -648,6 +835,49 @@ fn build_is_null_column_expr(
     }
 }
 
+/// Returns true if `expr` calls a function or an operator overridden by a
+/// kernel of `execution_props`
+fn uses_kernels(
+    expr: &Expr,
+    schema: &DFSchema,
+    execution_props: &ExecutionProps,
+) -> Result<bool> {
+    struct KernelVisitor<'a> {
+        schema: &'a DFSchema,
+        kernels: &'a KernelRegistry,
+        found: bool,
+    }
+
+    impl ExpressionVisitor for KernelVisitor<'_> {
+        fn pre_visit(mut self, expr: &Expr) -> Result<Recursion<Self>> {
+            self.found |= match expr {
+                Expr::BinaryExpr { left, op, right } => self
+                    .kernels
+                    .operator(
+                        *op,
+                        &left.get_type(self.schema)?,
+                        &right.get_type(self.schema)?,
+                    )
+                    .is_some(),
+                Expr::ScalarFunction { fun, .. } => self.kernels.function(fun).is_some(),
+                _ => false,
+            };
+            Ok(if self.found {
+                Recursion::Stop(self)
+            } else {
+                Recursion::Continue(self)
+            })
+        }
+    }
+
+    let visitor = expr.accept(KernelVisitor {
+        schema,
+        kernels: &execution_props.kernels,
+        found: false,
+    })?;
+    Ok(visitor.found)
+}
+
 /// Translate logical filter expression into pruning predicate
 /// expression that will evaluate to FALSE if it can be determined no
 /// rows between the min/max values could pass the predicates.
@@ -780,10 +1010,13 @@ enum StatisticsType {
 mod tests {
     use super::*;
     use crate::from_slice::FromSlice;
+    use crate::logical_expr::ScalarFunctionImplementation;
     use crate::logical_plan::{col, lit};
     use crate::{assert_batches_eq, physical_optimizer::pruning::StatisticsType};
     use arrow::{
-        array::{BinaryArray, Int32Array, Int64Array, StringArray},
+        array::{
+            BinaryArray, Int32Array, Int64Array, StringArray, TimestampMillisecondArray,
+        },
         datatypes::{DataType, TimeUnit},
     };
     use std::collections::HashMap;
@@ -1302,6 +1535,95 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn row_group_predicate_cast() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("c1", DataType::Int32, false),
+            Field::new("c2", DataType::Timestamp(TimeUnit::Nanosecond, None), false),
+        ]);
+
+        let expr = Expr::Cast {
+            expr: Box::new(col("c1")),
+            data_type: DataType::Int64,
+        }
+        .gt(lit(1i64));
+        let predicate_expr =
+            build_predicate_expression(&expr, &schema, &mut RequiredStatColumns::new())?;
+        assert_eq!(
+            format!("{:?}", predicate_expr),
+            "CAST(#c1_max AS Int64) > Int64(1)"
+        );
+
+        let expr = Expr::Cast {
+            expr: Box::new(col("c2")),
+            data_type: DataType::Date32,
+        }
+        .eq(lit(ScalarValue::Date32(Some(19000))));
+        let predicate_expr =
+            build_predicate_expression(&expr, &schema, &mut RequiredStatColumns::new())?;
+        assert_eq!(
+            format!("{:?}", predicate_expr),
+            "CAST(#c2_min AS Date32) <= Date32(\"19000\") \
+             AND Date32(\"19000\") <= CAST(#c2_max AS Date32)"
+        );
+
+        // the order of the values is not preserved by a cast to strings
+        let expr = Expr::Cast {
+            expr: Box::new(col("c1")),
+            data_type: DataType::Utf8,
+        }
+        .gt(lit("1"));
+        let predicate_expr =
+            build_predicate_expression(&expr, &schema, &mut RequiredStatColumns::new())?;
+        assert_eq!(format!("{:?}", predicate_expr), "Boolean(true)");
+
+        Ok(())
+    }
+
+    #[test]
+    fn row_group_predicate_arithmetic() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("c1", DataType::Int32, false),
+            Field::new("c2", DataType::Int32, false),
+        ]);
+
+        let expr = (col("c1") + lit(1)).gt(lit(10));
+        let predicate_expr =
+            build_predicate_expression(&expr, &schema, &mut RequiredStatColumns::new())?;
+        assert_eq!(format!("{:?}", predicate_expr), "#c1_max > Int32(9)");
+
+        let expr = (lit(10) - col("c1")).gt(lit(1));
+        let predicate_expr =
+            build_predicate_expression(&expr, &schema, &mut RequiredStatColumns::new())?;
+        assert_eq!(format!("{:?}", predicate_expr), "#c1_min < Int32(9)");
+
+        let expr = lit(5).lt_eq(Expr::Negative(Box::new(col("c1") - lit(2))));
+        let predicate_expr =
+            build_predicate_expression(&expr, &schema, &mut RequiredStatColumns::new())?;
+        assert_eq!(format!("{:?}", predicate_expr), "#c1_min <= Int32(-3)");
+
+        // the rewrite is skipped if the literals overflow
+        let expr = (col("c1") - lit(1)).gt(lit(i32::MAX));
+        let predicate_expr =
+            build_predicate_expression(&expr, &schema, &mut RequiredStatColumns::new())?;
+        assert_eq!(format!("{:?}", predicate_expr), "Boolean(true)");
+
+        // or if they are floating point values
+        let schema = Schema::new(vec![Field::new("c3", DataType::Float64, false)]);
+        let expr = (col("c3") + lit(0.1)).gt(lit(0.3));
+        let predicate_expr =
+            build_predicate_expression(&expr, &schema, &mut RequiredStatColumns::new())?;
+        assert_eq!(format!("{:?}", predicate_expr), "Boolean(true)");
+
+        // arithmetic between columns is not supported
+        let expr = (col("c1") + col("c2")).gt(lit(10));
+        let predicate_expr =
+            build_predicate_expression(&expr, &schema, &mut RequiredStatColumns::new())?;
+        assert_eq!(format!("{:?}", predicate_expr), "Boolean(true)");
+
+        Ok(())
+    }
+
     #[test]
     fn row_group_predicate_required_columns() -> Result<()> {
         let schema = Schema::new(vec![
@@ -1370,6 +1692,67 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn prune_cast_and_arithmetic() {
+        let schema = Arc::new(Schema::new(vec![Field::new("s2", DataType::Int32, true)]));
+        let statistics = TestStatistics::new().with(
+            "s2",
+            ContainerStats::new_i32(
+                vec![Some(0), Some(4), None, Some(3)], // min
+                vec![Some(5), Some(6), None, None],    // max
+            ),
+        );
+        // same as s2 > 5
+        let expected = vec![false, true, true, true];
+
+        let expr = (col("s2") + lit(1)).gt(lit(6));
+        let p = PruningPredicate::try_new(expr, schema.clone()).unwrap();
+        assert_eq!(p.prune(&statistics).unwrap(), expected);
+
+        let expr = Expr::Cast {
+            expr: Box::new(col("s2")),
+            data_type: DataType::Int64,
+        }
+        .gt(lit(5i64));
+        let p = PruningPredicate::try_new(expr, schema).unwrap();
+        assert_eq!(p.prune(&statistics).unwrap(), expected);
+    }
+
+    #[test]
+    fn prune_timestamp_string_literal() {
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "t",
+            DataType::Timestamp(TimeUnit::Millisecond, None),
+            true,
+        )]));
+        // 2021-01-01T00:00:00, 2021-01-01T00:00:01 and 2021-01-01T00:00:02
+        let statistics = TestStatistics::new().with(
+            "t",
+            ContainerStats {
+                min: Arc::new(TimestampMillisecondArray::from(vec![
+                    Some(1609459200000),
+                    Some(1609459201000),
+                    None,
+                ])),
+                max: Arc::new(TimestampMillisecondArray::from(vec![
+                    Some(1609459201000),
+                    Some(1609459202000),
+                    None,
+                ])),
+            },
+        );
+
+        // 2021-01-01T00:00:01.500 UTC
+        let expr = col("t").gt(lit("2021-01-01T02:00:01.500+02:00"));
+        let p = PruningPredicate::try_new(expr, schema.clone()).unwrap();
+        assert_eq!(p.prune(&statistics).unwrap(), vec![false, true, true]);
+
+        // below the millisecond
+        let expr = col("t").lt_eq(lit("2021-01-01T00:00:00.999999Z"));
+        let p = PruningPredicate::try_new(expr, schema).unwrap();
+        assert_eq!(p.prune(&statistics).unwrap(), vec![true, false, true]);
+    }
+
     #[test]
     fn prune_not_eq_data() {
         let schema = Arc::new(Schema::new(vec![Field::new("s1", DataType::Utf8, true)]));
@@ -1581,4 +1964,46 @@ mod tests {
         let result = p.prune(&statistics).unwrap();
         assert_eq!(result, expected_ret);
     }
+
+    #[test]
+    fn prune_with_kernels() {
+        let (schema, statistics) = int32_setup();
+        let expr = col("i").gt(lit(-1));
+
+        // the operators overridden by the kernels of the session are not
+        // pruned with the min / max rewrites of the built-in operators
+        let kernel: ScalarFunctionImplementation = Arc::new(|args| Ok(args[0].clone()));
+        let mut execution_props = ExecutionProps::new();
+        Arc::make_mut(&mut execution_props.kernels).register_operator(
+            Operator::Gt,
+            DataType::Int32,
+            DataType::Int32,
+            kernel,
+        );
+        let err = PruningPredicate::try_new_with_props(
+            expr.clone(),
+            schema.clone(),
+            &execution_props,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("kernels"), "{}", err);
+
+        // the other operators are pruned
+        let p = PruningPredicate::try_new_with_props(
+            col("i").lt(lit(1)),
+            schema.clone(),
+            &execution_props,
+        )
+        .unwrap();
+        assert_eq!(
+            p.prune(&statistics).unwrap(),
+            vec![true, false, true, true, false]
+        );
+
+        let p = PruningPredicate::try_new(expr, schema).unwrap();
+        assert_eq!(
+            p.prune(&statistics).unwrap(),
+            vec![true, true, false, true, true]
+        );
+    }
 }
@@ -267,6 +267,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             None,
         ))
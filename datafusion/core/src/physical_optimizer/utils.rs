@@ -20,7 +20,7 @@
 use super::optimizer::PhysicalOptimizerRule;
 use crate::execution::context::SessionConfig;
 
-use crate::error::Result;
+use crate::error::{DataFusionError, Result};
 use crate::physical_plan::{with_new_children_if_necessary, ExecutionPlan};
 use std::sync::Arc;
 
@@ -33,15 +33,220 @@ pub fn optimize_children(
     plan: Arc<dyn ExecutionPlan>,
     session_config: &SessionConfig,
 ) -> Result<Arc<dyn ExecutionPlan>> {
+    map_children(plan, |child| optimizer.optimize(child, session_config))
+}
+
+/// Rewrites each child of `plan` with `f`, returning a node of the same type.
+/// The node is only rebuilt if a child changed, so that the unchanged
+/// subtrees keep their nodes, along with their metrics.
+pub fn map_children<F>(
+    plan: Arc<dyn ExecutionPlan>,
+    f: F,
+) -> Result<Arc<dyn ExecutionPlan>>
+where
+    F: FnMut(Arc<dyn ExecutionPlan>) -> Result<Arc<dyn ExecutionPlan>>,
+{
     let children = plan
         .children()
-        .iter()
-        .map(|child| optimizer.optimize(Arc::clone(child), session_config))
+        .into_iter()
+        .map(f)
         .collect::<Result<Vec<_>>>()?;
 
     if children.is_empty() {
-        Ok(Arc::clone(&plan))
+        Ok(plan)
     } else {
         with_new_children_if_necessary(plan, children)
     }
 }
+
+/// Returns true if `f` holds for `plan` or any of its descendants
+pub fn any_node<F>(plan: &dyn ExecutionPlan, f: &F) -> bool
+where
+    F: Fn(&dyn ExecutionPlan) -> bool,
+{
+    f(plan) || plan.children().iter().any(|child| any_node(&**child, f))
+}
+
+/// Replaces the child at `index` of `plan` by `child`
+pub fn replace_child(
+    plan: Arc<dyn ExecutionPlan>,
+    index: usize,
+    child: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let mut children = plan.children();
+    if index >= children.len() {
+        return Err(DataFusionError::Internal(format!(
+            "Cannot replace child {} of a plan with {} children",
+            index,
+            children.len()
+        )));
+    }
+    children[index] = child;
+    with_new_children_if_necessary(plan, children)
+}
+
+/// Rewrites `plan` bottom up: `f` is applied to each node once its children
+/// are rewritten, and returns the node replacing it, or `None` to keep it.
+pub fn transform_up<F>(
+    plan: Arc<dyn ExecutionPlan>,
+    f: &mut F,
+) -> Result<Arc<dyn ExecutionPlan>>
+where
+    F: FnMut(&Arc<dyn ExecutionPlan>) -> Result<Option<Arc<dyn ExecutionPlan>>>,
+{
+    let plan = map_children(plan, |child| transform_up(child, f))?;
+    Ok(f(&plan)?.unwrap_or(plan))
+}
+
+/// Rewrites `plan` top down: `f` is applied to each node before its
+/// children, and returns the node replacing it, or `None` to keep it. The
+/// children of the returned node are rewritten next.
+pub fn transform_down<F>(
+    plan: Arc<dyn ExecutionPlan>,
+    f: &mut F,
+) -> Result<Arc<dyn ExecutionPlan>>
+where
+    F: FnMut(&Arc<dyn ExecutionPlan>) -> Result<Option<Arc<dyn ExecutionPlan>>>,
+{
+    let plan = f(&plan)?.unwrap_or(plan);
+    map_children(plan, |child| transform_down(child, f))
+}
+
+/// Checks that the rule `rule_name` rewrote `before` into a plan producing
+/// the same schema
+pub fn check_same_schema(
+    rule_name: &str,
+    before: &dyn ExecutionPlan,
+    after: &dyn ExecutionPlan,
+) -> Result<()> {
+    if before.schema() != after.schema() {
+        return Err(DataFusionError::Internal(format!(
+            "Physical optimizer rule {} changed the schema of the plan from {:?} to {:?}",
+            rule_name,
+            before.schema(),
+            after.schema()
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that the rule `rule_name` rewrote `before` into a plan producing
+/// the same schema, with the same number of partitions and ordering
+pub fn check_same_properties(
+    rule_name: &str,
+    before: &dyn ExecutionPlan,
+    after: &dyn ExecutionPlan,
+) -> Result<()> {
+    check_same_schema(rule_name, before, after)?;
+    let (before_partitions, after_partitions) = (
+        before.output_partitioning().partition_count(),
+        after.output_partitioning().partition_count(),
+    );
+    if before_partitions != after_partitions {
+        return Err(DataFusionError::Internal(format!(
+            "Physical optimizer rule {} changed the number of partitions of the plan from {} to {}",
+            rule_name, before_partitions, after_partitions
+        )));
+    }
+    let ordering = |plan: &dyn ExecutionPlan| {
+        plan.output_ordering()
+            .map(|ordering| ordering.iter().map(|e| e.to_string()).collect::<Vec<_>>())
+    };
+    let (before_ordering, after_ordering) = (ordering(before), ordering(after));
+    if before_ordering != after_ordering {
+        return Err(DataFusionError::Internal(format!(
+            "Physical optimizer rule {} changed the ordering of the plan from {:?} to {:?}",
+            rule_name, before_ordering, after_ordering
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+    use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+    use crate::physical_plan::displayable;
+    use crate::physical_plan::empty::EmptyExec;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::projection::ProjectionExec;
+    use crate::physical_plan::union::UnionExec;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn empty() -> Arc<dyn ExecutionPlan> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        Arc::new(EmptyExec::new(false, Arc::new(schema)))
+    }
+
+    fn plan_string(plan: &Arc<dyn ExecutionPlan>) -> String {
+        displayable(plan.as_ref()).indent().to_string()
+    }
+
+    #[test]
+    fn transform_plans() -> Result<()> {
+        let plan: Arc<dyn ExecutionPlan> =
+            Arc::new(UnionExec::new(vec![empty(), empty()]));
+
+        // unchanged subtrees keep their nodes
+        let same = transform_up(plan.clone(), &mut |_| Ok(None))?;
+        assert!(Arc::ptr_eq(&plan, &same));
+
+        // wrap the leaves
+        let wrapped = transform_up(plan.clone(), &mut |node| {
+            Ok(node.children().is_empty().then(|| {
+                Arc::new(CoalesceBatchesExec::new(node.clone(), 10))
+                    as Arc<dyn ExecutionPlan>
+            }))
+        })?;
+        assert_eq!(
+            plan_string(&wrapped),
+            "UnionExec\
+            \n  CoalesceBatchesExec: target_batch_size=10\
+            \n    EmptyExec: produce_one_row=false\
+            \n  CoalesceBatchesExec: target_batch_size=10\
+            \n    EmptyExec: produce_one_row=false\n"
+        );
+
+        // the nodes are visited before their children
+        let mut visited = vec![];
+        transform_down(plan, &mut |node| {
+            visited.push(node.children().len());
+            Ok(None)
+        })?;
+        assert_eq!(visited, vec![2, 0, 0]);
+
+        let replaced = replace_child(wrapped, 1, empty())?;
+        assert_eq!(replaced.children()[1].children().len(), 0);
+        let err = replace_child(replaced, 2, empty()).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Cannot replace child 2 of a plan with 2 children"));
+        Ok(())
+    }
+
+    #[test]
+    fn check_properties() -> Result<()> {
+        let plan = empty();
+        let union: Arc<dyn ExecutionPlan> =
+            Arc::new(UnionExec::new(vec![empty(), empty()]));
+        let coalesced = Arc::new(CoalescePartitionsExec::new(union.clone()));
+        check_same_schema("test", union.as_ref(), coalesced.as_ref())?;
+        let err = check_same_properties("test", union.as_ref(), coalesced.as_ref())
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("changed the number of partitions of the plan from 2 to 1"));
+
+        let schema = plan.schema();
+        let projection = ProjectionExec::try_new(
+            vec![(col("a", &schema)?, "b".to_string())],
+            plan.clone(),
+        )?;
+        let err = check_same_schema("test", plan.as_ref(), &projection).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Physical optimizer rule test changed the schema of the plan"));
+        Ok(())
+    }
+}
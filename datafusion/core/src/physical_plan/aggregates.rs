@@ -100,6 +100,19 @@ pub fn create_aggregate_expr(
                 coerced_exprs_types[0].clone(),
             ))
         }
+        (AggregateFunction::HllSketch, _) => Arc::new(expressions::HllSketch::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+            coerced_exprs_types[0].clone(),
+        )),
+        (AggregateFunction::HllUnion, _) => Arc::new(expressions::HllUnion::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+        )),
+        (AggregateFunction::HllCount, _) => Arc::new(expressions::HllCount::new(
+            coerced_phy_exprs[0].clone(),
+            name,
+        )),
         (AggregateFunction::ArrayAgg, false) => Arc::new(expressions::ArrayAgg::new(
             coerced_phy_exprs[0].clone(),
             name,
@@ -239,6 +252,26 @@ pub fn create_aggregate_expr(
                     .to_string(),
             ));
         }
+        (AggregateFunction::ApproxTopK, false) => Arc::new(expressions::ApproxTopK::new(
+            // Pass in the k expr
+            coerced_phy_exprs,
+            name,
+            coerced_exprs_types[0].clone(),
+        )?),
+        (AggregateFunction::ApproxTopK, true) => {
+            return Err(DataFusionError::NotImplemented(
+                "approx_top_k(DISTINCT) aggregations are not available".to_string(),
+            ));
+        }
+        (AggregateFunction::Histogram, false) => {
+            // Pass in the bins expr
+            Arc::new(expressions::Histogram::new(coerced_phy_exprs, name)?)
+        }
+        (AggregateFunction::Histogram, true) => {
+            return Err(DataFusionError::NotImplemented(
+                "histogram(DISTINCT) aggregations are not available".to_string(),
+            ));
+        }
         (AggregateFunction::ApproxMedian, false) => {
             Arc::new(expressions::ApproxMedian::new(
                 coerced_phy_exprs[0].clone(),
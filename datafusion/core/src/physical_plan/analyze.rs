@@ -33,6 +33,7 @@ use futures::StreamExt;
 use super::expressions::PhysicalSortExpr;
 use super::{stream::RecordBatchReceiverStream, Distribution, SendableRecordBatchStream};
 use crate::execution::context::TaskContext;
+use crate::execution::executor;
 use async_trait::async_trait;
 
 /// `EXPLAIN ANALYZE` execution plan operator. This operator runs its input,
@@ -126,13 +127,14 @@ impl ExecutionPlan for AnalyzeExec {
         let (tx, rx) = tokio::sync::mpsc::channel(input_partitions);
 
         let captured_input = self.input.clone();
+        let runtime = context.runtime_env();
         let mut input_stream = captured_input.execute(0, context).await?;
         let captured_schema = self.schema.clone();
         let verbose = self.verbose;
 
         // Task reads batches the input and when complete produce a
         // RecordBatch with a report that is written to `tx` when done
-        let join_handle = tokio::task::spawn(async move {
+        let task = executor::spawn(runtime.executor.as_ref(), async move {
             let start = Instant::now();
             let mut total_rows = 0;
 
@@ -201,10 +203,10 @@ impl ExecutionPlan for AnalyzeExec {
             tx.send(maybe_batch).await.ok();
         });
 
-        Ok(RecordBatchReceiverStream::create(
+        Ok(RecordBatchReceiverStream::create_with_task(
             &self.schema,
             rx,
-            join_handle,
+            task,
         ))
     }
 
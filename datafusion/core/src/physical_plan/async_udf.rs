@@ -0,0 +1,684 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Async scalar UDFs, whose implementation returns a future, e.g. to call an
+//! external service, and AsyncFuncExec evaluating their calls.
+//!
+//! The calls of the async functions registered with
+//! [`SessionContext::register_async_udf`] in the expressions of a projection,
+//! a filter, a sort or an aggregate are evaluated by an [`AsyncFuncExec`]
+//! below it, appending their results to its input. Calling them elsewhere,
+//! e.g. in a join condition, is an error.
+//!
+//! [`SessionContext::register_async_udf`]: crate::execution::context::SessionContext::register_async_udf
+
+use std::any::Any;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::{new_null_array, Array, ArrayRef};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+
+use super::expressions::PhysicalSortExpr;
+use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::type_coercion::coerce;
+use super::{
+    DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+use crate::logical_expr::{
+    ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF, Signature, Volatility,
+};
+
+/// The implementation of an async scalar function, receiving the arguments
+/// of the calls for the rows of a batch and returning the future of their
+/// results, which must have one value per row.
+///
+/// A function without arguments receives a single null array, whose length is
+/// the number of rows of the batch.
+pub type AsyncScalarFunctionImplementation =
+    Arc<dyn Fn(Vec<ArrayRef>) -> BoxFuture<'static, Result<ArrayRef>> + Send + Sync>;
+
+/// Logical representation of an async scalar UDF, with hints on how to
+/// evaluate its calls: the number of batches evaluated concurrently, and the
+/// maximum number of rows passed to each call.
+#[derive(Clone)]
+pub struct AsyncScalarUDF {
+    name: String,
+    signature: Signature,
+    return_type: ReturnTypeFunction,
+    fun: AsyncScalarFunctionImplementation,
+    max_concurrency: usize,
+    batch_size: Option<usize>,
+}
+
+impl AsyncScalarUDF {
+    /// Create a new async UDF, whose calls are evaluated one batch at a time
+    pub fn new(
+        name: &str,
+        signature: &Signature,
+        return_type: &ReturnTypeFunction,
+        fun: &AsyncScalarFunctionImplementation,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            signature: signature.clone(),
+            return_type: return_type.clone(),
+            fun: fun.clone(),
+            max_concurrency: 1,
+            batch_size: None,
+        }
+    }
+
+    /// Evaluate up to `max_concurrency` batches concurrently in each
+    /// partition, preserving their order
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Split the batches so that each call receives at most `batch_size` rows
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size.max(1));
+        self
+    }
+
+    /// The name of the function
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The signature of the function
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// The function computing the return type from the argument types
+    pub fn return_type(&self) -> &ReturnTypeFunction {
+        &self.return_type
+    }
+
+    /// The implementation of the function
+    pub fn fun(&self) -> &AsyncScalarFunctionImplementation {
+        &self.fun
+    }
+
+    /// The maximum number of batches evaluated concurrently in each partition
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// The maximum number of rows passed to each call, if any
+    pub fn batch_size(&self) -> Option<usize> {
+        self.batch_size
+    }
+
+    /// The scalar UDF resolving the calls of this function in the logical
+    /// plans, which can't be evaluated outside of an [`AsyncFuncExec`].
+    ///
+    /// Its calls are volatile whatever the volatility of the function, so
+    /// that the optimizer neither evaluates them when planning, e.g. with
+    /// literal arguments, nor duplicates them, e.g. when pushing down the
+    /// filters on their results.
+    pub fn to_scalar_udf(&self) -> ScalarUDF {
+        let name = self.name.clone();
+        let fun: ScalarFunctionImplementation = Arc::new(move |_| {
+            Err(DataFusionError::NotImplemented(format!(
+                "The async function {} can only be called in a projection, \
+                 a filter, a sort or an aggregate",
+                name
+            )))
+        });
+        let signature =
+            Signature::new(self.signature.type_signature.clone(), Volatility::Volatile);
+        ScalarUDF::new(&self.name, &signature, &self.return_type, &fun)
+    }
+}
+
+impl fmt::Debug for AsyncScalarUDF {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncScalarUDF")
+            .field("name", &self.name)
+            .field("signature", &self.signature)
+            .field("max_concurrency", &self.max_concurrency)
+            .field("batch_size", &self.batch_size)
+            .finish()
+    }
+}
+
+/// Creates a new async UDF with a fixed signature and return type, see
+/// [`create_udf`](crate::logical_plan::create_udf)
+pub fn create_async_udf(
+    name: &str,
+    input_types: Vec<DataType>,
+    return_type: Arc<DataType>,
+    volatility: Volatility,
+    fun: AsyncScalarFunctionImplementation,
+) -> AsyncScalarUDF {
+    let return_type: ReturnTypeFunction = Arc::new(move |_| Ok(return_type.clone()));
+    AsyncScalarUDF::new(
+        name,
+        &Signature::exact(input_types, volatility),
+        &return_type,
+        &fun,
+    )
+}
+
+/// A call of an async UDF evaluated by an [`AsyncFuncExec`]
+#[derive(Debug, Clone)]
+pub struct AsyncFuncExpr {
+    /// The name of the column of the results
+    name: String,
+    fun: Arc<AsyncScalarUDF>,
+    args: Vec<Arc<dyn PhysicalExpr>>,
+    return_type: DataType,
+}
+
+impl AsyncFuncExpr {
+    /// Create the call of `fun` with the arguments `args`, coerced to its
+    /// signature, whose results are named `name`
+    pub fn try_new(
+        name: impl Into<String>,
+        fun: Arc<AsyncScalarUDF>,
+        args: &[Arc<dyn PhysicalExpr>],
+        input_schema: &Schema,
+    ) -> Result<Self> {
+        let args = coerce(args, input_schema, &fun.signature)?;
+        let arg_types = args
+            .iter()
+            .map(|e| e.data_type(input_schema))
+            .collect::<Result<Vec<_>>>()?;
+        let return_type = (fun.return_type)(&arg_types)?.as_ref().clone();
+        Ok(Self {
+            name: name.into(),
+            fun,
+            args,
+            return_type,
+        })
+    }
+
+    /// The name of the column of the results
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The function called
+    pub fn fun(&self) -> &Arc<AsyncScalarUDF> {
+        &self.fun
+    }
+
+    /// The arguments of the call
+    pub fn args(&self) -> &[Arc<dyn PhysicalExpr>] {
+        &self.args
+    }
+
+    /// The field of the results
+    pub fn field(&self) -> Field {
+        Field::new(&self.name, self.return_type.clone(), true)
+    }
+
+    /// Evaluate the call for the rows of `batch`
+    async fn evaluate(&self, batch: &RecordBatch) -> Result<ArrayRef> {
+        let num_rows = batch.num_rows();
+        let mut args = self
+            .args
+            .iter()
+            .map(|arg| Ok(arg.evaluate(batch)?.into_array(num_rows)))
+            .collect::<Result<Vec<_>>>()?;
+        if args.is_empty() {
+            args.push(new_null_array(&DataType::Null, num_rows));
+        }
+        let result = (self.fun.fun)(args).await?;
+        if result.len() != num_rows || result.data_type() != &self.return_type {
+            return Err(DataFusionError::Execution(format!(
+                "The async function {} returned {} values of type {}, expected {} values of type {}",
+                self.fun.name,
+                result.len(),
+                result.data_type(),
+                num_rows,
+                self.return_type
+            )));
+        }
+        Ok(result)
+    }
+}
+
+impl fmt::Display for AsyncFuncExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args = self
+            .args
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}({})", self.fun.name, args.join(", "))
+    }
+}
+
+/// AsyncFuncExec evaluates the calls of async UDFs, appending their results
+/// to the columns of its input. The batches of each partition are split
+/// according to the smallest batch size of the functions, and up to the
+/// smallest maximum concurrency of the functions are evaluated concurrently,
+/// preserving their order.
+#[derive(Debug)]
+pub struct AsyncFuncExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The calls evaluated
+    async_exprs: Vec<AsyncFuncExpr>,
+    /// The schema of the input, followed by the results of the calls
+    schema: SchemaRef,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl AsyncFuncExec {
+    /// Create an AsyncFuncExec evaluating `async_exprs` for the rows of `input`
+    pub fn try_new(
+        async_exprs: Vec<AsyncFuncExpr>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        if async_exprs.is_empty() {
+            return Err(DataFusionError::Internal(
+                "AsyncFuncExec requires at least one async function call".to_string(),
+            ));
+        }
+        let input_schema = input.schema();
+        let mut fields = input_schema.fields().clone();
+        fields.extend(async_exprs.iter().map(|e| e.field()));
+        let schema = Arc::new(Schema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        ));
+        Ok(Self {
+            input,
+            async_exprs,
+            schema,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The calls evaluated
+    pub fn async_exprs(&self) -> &[AsyncFuncExpr] {
+        &self.async_exprs
+    }
+
+    /// The number of batches evaluated concurrently in each partition
+    pub fn max_concurrency(&self) -> usize {
+        self.async_exprs
+            .iter()
+            .map(|e| e.fun.max_concurrency)
+            .min()
+            .unwrap_or(1)
+    }
+
+    /// The maximum number of rows of the batches evaluated, if any
+    pub fn batch_size(&self) -> Option<usize> {
+        self.async_exprs
+            .iter()
+            .filter_map(|e| e.fun.batch_size)
+            .min()
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for AsyncFuncExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> bool {
+        true
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(AsyncFuncExec::try_new(
+            self.async_exprs.clone(),
+            children[0].clone(),
+        )?))
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let input = self.input.execute(partition, context).await?;
+        let batch_size = self.batch_size();
+        let async_exprs = Arc::new(self.async_exprs.clone());
+        let schema = self.schema.clone();
+        let output = input
+            .flat_map(move |batch| stream::iter(split_batch(batch, batch_size)))
+            .map(move |batch| {
+                let async_exprs = async_exprs.clone();
+                let schema = schema.clone();
+                async move {
+                    evaluate_async_exprs(batch?, &async_exprs, schema)
+                        .await
+                        .map_err(DataFusionError::into)
+                }
+            })
+            .buffered(self.max_concurrency())
+            .boxed();
+        Ok(Box::pin(AsyncFuncStream {
+            schema: self.schema.clone(),
+            output,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                let exprs = self
+                    .async_exprs
+                    .iter()
+                    .map(|e| format!("{} as {}", e, e.name))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "AsyncFuncExec: expr=[{}], max_concurrency={}",
+                    exprs.join(", "),
+                    self.max_concurrency()
+                )?;
+                if let Some(batch_size) = self.batch_size() {
+                    write!(f, ", batch_size={}", batch_size)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        let input_stats = self.input.statistics();
+        Statistics {
+            is_exact: input_stats.is_exact,
+            num_rows: input_stats.num_rows,
+            ..Statistics::default()
+        }
+    }
+}
+
+/// Splits `batch` into batches of at most `batch_size` rows
+fn split_batch(
+    batch: ArrowResult<RecordBatch>,
+    batch_size: Option<usize>,
+) -> Vec<ArrowResult<RecordBatch>> {
+    match (batch, batch_size) {
+        (Ok(batch), Some(batch_size)) if batch.num_rows() > batch_size => {
+            let num_rows = batch.num_rows();
+            (0..num_rows)
+                .step_by(batch_size)
+                .map(|offset| Ok(batch.slice(offset, batch_size.min(num_rows - offset))))
+                .collect()
+        }
+        (batch, _) => vec![batch],
+    }
+}
+
+/// Appends the results of `async_exprs` for the rows of `batch` to its columns
+async fn evaluate_async_exprs(
+    batch: RecordBatch,
+    async_exprs: &[AsyncFuncExpr],
+    schema: SchemaRef,
+) -> Result<RecordBatch> {
+    let mut columns = batch.columns().to_vec();
+    for async_expr in async_exprs {
+        columns.push(async_expr.evaluate(&batch).await?);
+    }
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+/// The batches of a partition with the results of the calls
+struct AsyncFuncStream {
+    /// Output schema
+    schema: SchemaRef,
+    /// The batches, in the order of the input
+    output: BoxStream<'static, ArrowResult<RecordBatch>>,
+    /// runtime metrics recording
+    baseline_metrics: BaselineMetrics,
+}
+
+impl Stream for AsyncFuncStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.output.poll_next_unpin(cx);
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for AsyncFuncStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::MemTable;
+    use crate::physical_plan::collect;
+    use crate::prelude::SessionContext;
+    use crate::test::make_partition;
+    use crate::{assert_batches_eq, assert_batches_sorted_eq};
+    use arrow::array::Int32Array;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// `add_one(i)` counting its calls in `calls`
+    fn add_one(calls: Arc<AtomicUsize>, volatility: Volatility) -> AsyncScalarUDF {
+        let fun: AsyncScalarFunctionImplementation = Arc::new(move |args| {
+            let calls = calls.clone();
+            Box::pin(async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let a = args[0].as_any().downcast_ref::<Int32Array>().unwrap();
+                let result: ArrayRef =
+                    Arc::new(a.iter().map(|v| v.map(|v| v + 1)).collect::<Int32Array>());
+                Ok(result)
+            })
+        });
+        create_async_udf(
+            "add_one",
+            vec![DataType::Int32],
+            Arc::new(DataType::Int32),
+            volatility,
+            fun,
+        )
+    }
+
+    #[tokio::test]
+    async fn async_udf_in_projection() -> Result<()> {
+        let mut ctx = SessionContext::new();
+        // make_partition(5) produces the values 0..5 in column "i"
+        let batch = make_partition(5);
+        let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+        ctx.register_table("t", Arc::new(table))?;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        ctx.register_async_udf(
+            add_one(calls.clone(), Volatility::Volatile)
+                .with_batch_size(2)
+                .with_max_concurrency(2),
+        );
+
+        let sql = "SELECT i, add_one(i) * 10 AS x FROM t";
+        let plan = ctx.create_logical_plan(sql)?;
+        let plan = ctx.optimize(&plan)?;
+        let plan = ctx.create_physical_plan(&plan).await?;
+        let async_exec = plan.children()[0].clone();
+        let async_exec = async_exec.as_any().downcast_ref::<AsyncFuncExec>().unwrap();
+        assert_eq!(async_exec.max_concurrency(), 2);
+        assert_eq!(async_exec.batch_size(), Some(2));
+
+        let batches = collect(plan, ctx.task_ctx()).await?;
+        let expected = vec![
+            "+---+----+",
+            "| i | x  |",
+            "+---+----+",
+            "| 0 | 10 |",
+            "| 1 | 20 |",
+            "| 2 | 30 |",
+            "| 3 | 40 |",
+            "| 4 | 50 |",
+            "+---+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        // the 5 rows are passed in batches of at most 2 rows
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn async_udf_in_filter_sort_and_aggregate() -> Result<()> {
+        let mut ctx = SessionContext::new();
+        let batch = make_partition(5);
+        let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+        ctx.register_table("t", Arc::new(table))?;
+        let calls = Arc::new(AtomicUsize::new(0));
+        ctx.register_async_udf(add_one(calls.clone(), Volatility::Immutable));
+
+        // the filter on the result of the call isn't pushed down below it,
+        // which would call the function twice
+        let sql = "SELECT * FROM (SELECT add_one(i) AS e FROM t) WHERE e > 3";
+        let batches = ctx.sql(sql).await?.collect().await?;
+        let expected = vec!["+---+", "| e |", "+---+", "| 4 |", "| 5 |", "+---+"];
+        assert_batches_sorted_eq!(expected, &batches);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // the calls with literal arguments aren't evaluated when planning
+        let batches = ctx.sql("SELECT add_one(1) AS x").await?.collect().await?;
+        let expected = vec!["+---+", "| x |", "+---+", "| 2 |", "+---+"];
+        assert_batches_eq!(expected, &batches);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let sql = "SELECT i FROM t WHERE add_one(i) > 2 ORDER BY add_one(i) DESC";
+        let batches = ctx.sql(sql).await?.collect().await?;
+        let expected = vec![
+            "+---+", "| i |", "+---+", "| 4 |", "| 3 |", "| 2 |", "+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        let sql = "SELECT add_one(i) % 2 AS k, sum(add_one(i)) AS s FROM t \
+                   GROUP BY add_one(i) % 2";
+        let batches = ctx.sql(sql).await?.collect().await?;
+        let expected = vec![
+            "+---+---+",
+            "| k | s |",
+            "+---+---+",
+            "| 0 | 6 |",
+            "| 1 | 9 |",
+            "+---+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // async functions can't be evaluated in the other nodes
+        let err = ctx
+            .sql("SELECT row_number() OVER (ORDER BY add_one(i)) FROM t")
+            .await?
+            .collect()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "The async function add_one can only be called in a projection"
+            ),
+            "{}",
+            err
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn async_udf_invalid_results() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let batch = make_partition(3);
+        let schema = batch.schema();
+        let input = Arc::new(crate::physical_plan::memory::MemoryExec::try_new(
+            &[vec![batch]],
+            schema.clone(),
+            None,
+        )?);
+
+        let fun: AsyncScalarFunctionImplementation = Arc::new(|_| {
+            Box::pin(async {
+                let result: ArrayRef = Arc::new(Int32Array::from(vec![1]));
+                Ok(result)
+            })
+        });
+        let udf = create_async_udf(
+            "one",
+            vec![DataType::Int32],
+            Arc::new(DataType::Int32),
+            Volatility::Volatile,
+            fun,
+        );
+        let args = vec![crate::physical_plan::expressions::col("i", &schema)?];
+        let expr = AsyncFuncExpr::try_new("one(i)", Arc::new(udf), &args, &schema)?;
+        let exec = Arc::new(AsyncFuncExec::try_new(vec![expr], input)?);
+        let err = collect(exec, session_ctx.task_ctx()).await.unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "The async function one returned 1 values of type Int32, expected 3 values of type Int32"
+            ),
+            "{}",
+            err
+        );
+        Ok(())
+    }
+}
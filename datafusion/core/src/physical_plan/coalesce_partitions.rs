@@ -22,15 +22,16 @@ use std::any::Any;
 use std::sync::Arc;
 use std::task::Poll;
 
-use futures::Stream;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
 use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 
 use async_trait::async_trait;
 
 use arrow::record_batch::RecordBatch;
 use arrow::{datatypes::SchemaRef, error::Result as ArrowResult};
 
-use super::common::AbortOnDropMany;
 use super::expressions::PhysicalSortExpr;
 use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
 use super::{RecordBatchStream, Statistics};
@@ -39,10 +40,15 @@ use crate::physical_plan::{DisplayFormatType, ExecutionPlan, Partitioning};
 
 use super::SendableRecordBatchStream;
 use crate::execution::context::TaskContext;
+use crate::execution::executor::SpawnedTask;
 use crate::physical_plan::common::spawn_execution;
 
 /// Merge execution plan executes partitions in parallel and combines them into a single
-/// partition. No guarantees are made about the order of the resulting partition.
+/// partition. No guarantees are made about the order of the resulting partition, unless
+/// the output of the session is [deterministic], in which case the partitions are output
+/// one after the other.
+///
+/// [deterministic]: crate::execution::context::SessionConfig::with_deterministic_output
 #[derive(Debug)]
 pub struct CoalescePartitionsExec {
     /// Input execution plan
@@ -130,29 +136,48 @@ impl ExecutionPlan for CoalescePartitionsExec {
                 let elapsed_compute = baseline_metrics.elapsed_compute().clone();
                 let _timer = elapsed_compute.timer();
 
-                // use a stream that allows each sender to put in at
-                // least one result in an attempt to maximize
-                // parallelism.
-                let (sender, receiver) =
-                    mpsc::channel::<ArrowResult<RecordBatch>>(input_partitions);
-
-                // spawn independent tasks whose resulting streams (of batches)
-                // are sent to the channel for consumption.
                 let mut join_handles = Vec::with_capacity(input_partitions);
-                for part_i in 0..input_partitions {
-                    join_handles.push(spawn_execution(
-                        self.input.clone(),
-                        sender.clone(),
-                        part_i,
-                        context.clone(),
-                    ));
-                }
+                let input = if context.session_config().deterministic_output() {
+                    // the partitions are still executed in parallel, but each
+                    // one sends its batches to its own channel, read in turn
+                    let mut receivers = Vec::with_capacity(input_partitions);
+                    for part_i in 0..input_partitions {
+                        let (sender, receiver) =
+                            mpsc::channel::<ArrowResult<RecordBatch>>(1);
+                        join_handles.push(spawn_execution(
+                            self.input.clone(),
+                            sender,
+                            part_i,
+                            context.clone(),
+                        ));
+                        receivers.push(ReceiverStream::new(receiver));
+                    }
+                    futures::stream::iter(receivers).flatten().boxed()
+                } else {
+                    // use a stream that allows each sender to put in at
+                    // least one result in an attempt to maximize
+                    // parallelism.
+                    let (sender, receiver) =
+                        mpsc::channel::<ArrowResult<RecordBatch>>(input_partitions);
+
+                    // spawn independent tasks whose resulting streams (of batches)
+                    // are sent to the channel for consumption.
+                    for part_i in 0..input_partitions {
+                        join_handles.push(spawn_execution(
+                            self.input.clone(),
+                            sender.clone(),
+                            part_i,
+                            context.clone(),
+                        ));
+                    }
+                    ReceiverStream::new(receiver).boxed()
+                };
 
                 Ok(Box::pin(MergeStream {
-                    input: receiver,
+                    input,
                     schema: self.schema(),
                     baseline_metrics,
-                    drop_helper: AbortOnDropMany(join_handles),
+                    drop_helper: join_handles,
                 }))
             }
         }
@@ -181,10 +206,10 @@ impl ExecutionPlan for CoalescePartitionsExec {
 
 struct MergeStream {
     schema: SchemaRef,
-    input: mpsc::Receiver<ArrowResult<RecordBatch>>,
+    input: BoxStream<'static, ArrowResult<RecordBatch>>,
     baseline_metrics: BaselineMetrics,
     #[allow(unused)]
-    drop_helper: AbortOnDropMany<()>,
+    drop_helper: Vec<SpawnedTask<()>>,
 }
 
 impl Stream for MergeStream {
@@ -194,7 +219,7 @@ impl Stream for MergeStream {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        let poll = self.input.poll_recv(cx);
+        let poll = self.input.poll_next_unpin(cx);
         self.baseline_metrics.record_poll(poll)
     }
 }
@@ -208,14 +233,16 @@ impl RecordBatchStream for MergeStream {
 #[cfg(test)]
 mod tests {
 
+    use arrow::array::UInt32Array;
     use arrow::datatypes::{DataType, Field, Schema};
     use futures::FutureExt;
 
     use super::*;
     use crate::datafusion_data_access::object_store::local::LocalFileSystem;
     use crate::physical_plan::file_format::{CsvExec, FileScanConfig};
+    use crate::physical_plan::memory::MemoryExec;
     use crate::physical_plan::{collect, common};
-    use crate::prelude::SessionContext;
+    use crate::prelude::{SessionConfig, SessionContext};
     use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
     use crate::test::{self, assert_is_pending};
     use crate::test_util;
@@ -238,6 +265,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -263,6 +291,44 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn merge_deterministic() -> Result<()> {
+        let session_ctx = SessionContext::with_config(
+            SessionConfig::new().with_deterministic_output(true),
+        );
+        let task_ctx = session_ctx.task_ctx();
+        let schema =
+            Arc::new(Schema::new(vec![Field::new("a", DataType::UInt32, false)]));
+
+        // 8 partitions of 4 batches of a single row, numbered in order
+        let partitions = (0..8u32)
+            .map(|partition| {
+                (0..4u32)
+                    .map(|i| {
+                        RecordBatch::try_new(
+                            schema.clone(),
+                            vec![Arc::new(UInt32Array::from(vec![partition * 4 + i]))],
+                        )
+                    })
+                    .collect::<ArrowResult<Vec<_>>>()
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        let input = MemoryExec::try_new(&partitions, schema, None)?;
+        let merge = CoalescePartitionsExec::new(Arc::new(input));
+
+        let batches = common::collect(merge.execute(0, task_ctx).await?).await?;
+        let values = batches
+            .iter()
+            .map(|batch| {
+                let array = batch.column(0).as_any().downcast_ref::<UInt32Array>();
+                array.unwrap().value(0)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, (0..32).collect::<Vec<_>>());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_drop_cancel() -> Result<()> {
         let session_ctx = SessionContext::new();
@@ -20,18 +20,23 @@
 use super::{RecordBatchStream, SendableRecordBatchStream};
 use crate::error::{DataFusionError, Result};
 use crate::execution::context::TaskContext;
+use crate::execution::disk_manager::SpillCompression;
+use crate::execution::executor::{self, SpawnedTask};
 use crate::physical_plan::metrics::MemTrackingMetrics;
 use crate::physical_plan::{ColumnStatistics, ExecutionPlan, Statistics};
 use arrow::compute::concat;
 use arrow::datatypes::{Schema, SchemaRef};
 use arrow::error::ArrowError;
 use arrow::error::Result as ArrowResult;
-use arrow::ipc::writer::FileWriter;
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::{FileWriter, StreamWriter};
 use arrow::record_batch::RecordBatch;
 use futures::{Future, Stream, StreamExt, TryStreamExt};
+use lz4_flex::frame::{FrameDecoder, FrameEncoder};
 use pin_project_lite::pin_project;
 use std::fs;
 use std::fs::{metadata, File};
+use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -54,6 +59,7 @@ impl SizedRecordBatchStream {
         metrics: MemTrackingMetrics,
     ) -> Self {
         let size = batches.iter().map(|b| batch_byte_size(b)).sum::<usize>();
+        let metrics = metrics.with_name("SizedRecordBatchStream");
         metrics.init_mem_used(size);
         SizedRecordBatchStream {
             schema,
@@ -171,14 +177,16 @@ fn build_file_list_recurse(
     Ok(())
 }
 
-/// Spawns a task to the tokio threadpool and writes its outputs to the provided mpsc sender
+/// Spawns a task to the executor of the runtime and writes its outputs to the
+/// provided mpsc sender
 pub(crate) fn spawn_execution(
     input: Arc<dyn ExecutionPlan>,
     output: mpsc::Sender<ArrowResult<RecordBatch>>,
     partition: usize,
     context: Arc<TaskContext>,
-) -> JoinHandle<()> {
-    tokio::spawn(async move {
+) -> SpawnedTask<()> {
+    let runtime = context.runtime_env();
+    executor::spawn(runtime.executor.as_ref(), async move {
         let mut stream = match input.execute(partition, context).await {
             Err(e) => {
                 // If send fails, plan being torn
@@ -385,6 +393,43 @@ mod tests {
         assert_eq!(result, expected);
         Ok(())
     }
+
+    #[test]
+    fn test_spill_file_roundtrip() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("f32", DataType::Float32, false),
+            Field::new("f64", DataType::Float64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Float32Array::from_slice(&[1., 2., 3.])),
+                Arc::new(Float64Array::from_slice(&[9., 8., 7.])),
+            ],
+        )?;
+        let dir = tempfile::tempdir()?;
+
+        for compression in [
+            SpillCompression::Uncompressed,
+            SpillCompression::Zstd,
+            SpillCompression::Lz4,
+        ] {
+            let path = dir.path().join(format!("{:?}.arrow", compression));
+            let stats = write_spill_file(
+                &path,
+                &schema,
+                compression,
+                vec![Ok(batch.clone()), Ok(batch.clone())],
+            )?;
+            assert_eq!(stats.num_batches, 2);
+            assert_eq!(stats.num_rows, 6);
+
+            let batches =
+                read_spill_file(&path, compression)?.collect::<ArrowResult<Vec<_>>>()?;
+            assert_eq!(batches, vec![batch.clone(), batch.clone()]);
+        }
+        Ok(())
+    }
 }
 
 /// Write in Arrow IPC format.
@@ -440,6 +485,166 @@ impl IPCWriter {
     }
 }
 
+/// Numbers of batches, rows and in-memory bytes written to a spill file
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SpillStats {
+    /// batches written
+    pub num_batches: u64,
+    /// rows written
+    pub num_rows: u64,
+    /// in-memory size of the batches written
+    pub num_bytes: u64,
+}
+
+/// Compresses the bytes written to a spill file
+enum SpillEncoder {
+    Uncompressed(BufWriter<File>),
+    #[cfg(not(target_arch = "wasm32"))]
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+    Lz4(FrameEncoder<BufWriter<File>>),
+}
+
+impl SpillEncoder {
+    fn try_new(file: File, compression: SpillCompression) -> Result<Self> {
+        let file = BufWriter::new(file);
+        Ok(match compression {
+            SpillCompression::Uncompressed => Self::Uncompressed(file),
+            #[cfg(not(target_arch = "wasm32"))]
+            SpillCompression::Zstd => {
+                Self::Zstd(zstd::Encoder::new(file, zstd::DEFAULT_COMPRESSION_LEVEL)?)
+            }
+            #[cfg(target_arch = "wasm32")]
+            SpillCompression::Zstd => return Err(zstd_not_supported()),
+            SpillCompression::Lz4 => Self::Lz4(FrameEncoder::new(file)),
+        })
+    }
+
+    /// Writes the end of the compressed stream and flushes the file
+    fn finish(self) -> Result<()> {
+        let mut file = match self {
+            Self::Uncompressed(file) => file,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Zstd(encoder) => encoder.finish()?,
+            Self::Lz4(encoder) => encoder.finish().map_err(|e| {
+                DataFusionError::Execution(format!(
+                    "Failed to finish LZ4 spill file: {}",
+                    e
+                ))
+            })?,
+        };
+        file.flush()?;
+        Ok(())
+    }
+}
+
+impl Write for SpillEncoder {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Uncompressed(file) => file.write(buf),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Zstd(encoder) => encoder.write(buf),
+            Self::Lz4(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Uncompressed(file) => file.flush(),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Zstd(encoder) => encoder.flush(),
+            Self::Lz4(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Writes batches one at a time to a spill file in the Arrow IPC streaming
+/// format, compressed with a [`SpillCompression`].
+///
+/// Spill files are read back with [`read_spill_file`].
+pub struct SpillWriter {
+    writer: StreamWriter<SpillEncoder>,
+    stats: SpillStats,
+}
+
+impl SpillWriter {
+    /// Creates the spill file at `path` of the batches of `schema`
+    pub fn try_new(
+        path: &Path,
+        schema: &Schema,
+        compression: SpillCompression,
+    ) -> Result<Self> {
+        let file = File::create(path).map_err(|e| {
+            DataFusionError::Execution(format!(
+                "Failed to create spill file at {:?}: {:?}",
+                path, e
+            ))
+        })?;
+        let encoder = SpillEncoder::try_new(file, compression)?;
+        Ok(Self {
+            writer: StreamWriter::try_new(encoder, schema)?,
+            stats: SpillStats::default(),
+        })
+    }
+
+    /// Writes one batch
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.writer.write(batch)?;
+        self.stats.num_batches += 1;
+        self.stats.num_rows += batch.num_rows() as u64;
+        self.stats.num_bytes += batch_byte_size(batch) as u64;
+        Ok(())
+    }
+
+    /// Finishes the IPC stream and the compressed stream, returning the
+    /// numbers of batches, rows and bytes written
+    pub fn finish(self) -> Result<SpillStats> {
+        self.writer.into_inner()?.finish()?;
+        Ok(self.stats)
+    }
+}
+
+/// Writes `batches` to the spill file at `path` in the Arrow IPC streaming
+/// format, compressed with `compression`.
+///
+/// Spill files are read back with [`read_spill_file`].
+pub fn write_spill_file(
+    path: &Path,
+    schema: &Schema,
+    compression: SpillCompression,
+    batches: impl IntoIterator<Item = ArrowResult<RecordBatch>>,
+) -> Result<SpillStats> {
+    let mut writer = SpillWriter::try_new(path, schema, compression)?;
+    for batch in batches {
+        writer.write(&batch?)?;
+    }
+    writer.finish()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn zstd_not_supported() -> DataFusionError {
+    DataFusionError::NotImplemented(
+        "Zstd compression of the spill files is not supported on wasm32".to_owned(),
+    )
+}
+
+/// Opens the spill file at `path` written by [`write_spill_file`] with the
+/// same `compression`
+pub fn read_spill_file(
+    path: &Path,
+    compression: SpillCompression,
+) -> Result<StreamReader<Box<dyn Read + Send>>> {
+    let file = BufReader::new(File::open(path)?);
+    let reader: Box<dyn Read + Send> = match compression {
+        SpillCompression::Uncompressed => Box::new(file),
+        #[cfg(not(target_arch = "wasm32"))]
+        SpillCompression::Zstd => Box::new(zstd::Decoder::with_buffer(file)?),
+        #[cfg(target_arch = "wasm32")]
+        SpillCompression::Zstd => return Err(zstd_not_supported()),
+        SpillCompression::Lz4 => Box::new(FrameDecoder::new(file)),
+    };
+    Ok(StreamReader::try_new(reader)?)
+}
+
 /// Returns the total number of bytes of memory occupied physically by this batch.
 pub fn batch_byte_size(batch: &RecordBatch) -> usize {
     batch
@@ -30,7 +30,7 @@ use futures::{Stream, TryStreamExt};
 use super::expressions::PhysicalSortExpr;
 use super::{
     coalesce_partitions::CoalescePartitionsExec, join_utils::check_join_is_valid,
-    ColumnStatistics, Statistics,
+    ColumnStatistics, Statistics, StatisticsCache,
 };
 use crate::{error::Result, scalar::ScalarValue};
 use async_trait::async_trait;
@@ -58,6 +58,8 @@ pub struct CrossJoinExec {
     schema: SchemaRef,
     /// Build-side data
     build_side: Arc<Mutex<Option<JoinLeftData>>>,
+    /// The statistics, computed on the first request
+    statistics: StatisticsCache,
 }
 
 impl CrossJoinExec {
@@ -88,6 +90,7 @@ impl CrossJoinExec {
             right,
             schema,
             build_side: Arc::new(Mutex::new(None)),
+            statistics: StatisticsCache::default(),
         })
     }
 
@@ -216,12 +219,14 @@ impl ExecutionPlan for CrossJoinExec {
     }
 
     fn statistics(&self) -> Statistics {
-        stats_cartesian_product(
-            self.left.statistics(),
-            self.left.schema().fields().len(),
-            self.right.statistics(),
-            self.right.schema().fields().len(),
-        )
+        self.statistics.get_or_compute(|| {
+            stats_cartesian_product(
+                self.left.statistics(),
+                self.left.schema().fields().len(),
+                self.right.statistics(),
+                self.right.schema().fields().len(),
+            )
+        })
     }
 }
 
@@ -0,0 +1,471 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! DedupExec keeps the latest row of each key of its input, the latest being
+//! the row with the greatest sequence, with a hash table or by sorting.
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::expressions::PhysicalSortExpr;
+use super::{RecordBatchStream, SendableRecordBatchStream, Statistics};
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::DedupStrategy;
+use crate::physical_plan::{
+    common,
+    memory::MemoryStream,
+    metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, PhysicalExpr,
+};
+use crate::scalar::ScalarValue;
+use arrow::array::{ArrayRef, BooleanArray, UInt32Array};
+use arrow::compute::{filter_record_batch, take};
+use arrow::datatypes::SchemaRef;
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+
+use crate::execution::context::TaskContext;
+use futures::stream::{Stream, StreamExt};
+
+/// DedupExec keeps only the latest row of each key of its input, the latest
+/// being the row with the greatest sequence. The rows of a key must be in a
+/// single partition of the input, e.g. by hash partitioning it by the keys.
+///
+/// With [`DedupStrategy::Hash`], the latest rows are found with a hash table
+/// after reading the whole partition. With [`DedupStrategy::Sort`], the
+/// partition must be sorted by the keys and by the sequence in descending
+/// order, and the first row of each key is streamed. In both cases the order
+/// of the rows kept is preserved.
+#[derive(Debug)]
+pub struct DedupExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The key of the rows
+    keys: Vec<Arc<dyn PhysicalExpr>>,
+    /// The version of the rows, the greatest being the latest
+    sequence: Arc<dyn PhysicalExpr>,
+    /// How the latest rows are found
+    strategy: DedupStrategy,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl DedupExec {
+    /// Create a DedupExec keeping the latest row of each key of `input`
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        keys: Vec<Arc<dyn PhysicalExpr>>,
+        sequence: Arc<dyn PhysicalExpr>,
+        strategy: DedupStrategy,
+    ) -> Result<Self> {
+        if keys.is_empty() {
+            return Err(DataFusionError::Plan(
+                "Dedup requires at least one key".to_string(),
+            ));
+        }
+        Ok(Self {
+            input,
+            keys,
+            sequence,
+            strategy,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The key of the rows
+    pub fn keys(&self) -> &[Arc<dyn PhysicalExpr>] {
+        &self.keys
+    }
+
+    /// The version of the rows, the greatest being the latest
+    pub fn sequence(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.sequence
+    }
+
+    /// How the latest rows are found
+    pub fn strategy(&self) -> DedupStrategy {
+        self.strategy
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for DedupExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::HashPartitioned(self.keys.clone())
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        self.strategy == DedupStrategy::Sort
+    }
+
+    fn maintains_input_order(&self) -> bool {
+        true
+    }
+
+    /// The input is partitioned by the keys when planned, repartitioning it
+    /// otherwise would split the rows of the keys between partitions
+    fn benefits_from_input_partitioning(&self) -> bool {
+        false
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(DedupExec::try_new(
+            children[0].clone(),
+            self.keys.clone(),
+            self.sequence.clone(),
+            self.strategy,
+        )?))
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let input = self.input.execute(partition, context).await?;
+        match self.strategy {
+            DedupStrategy::Hash => {
+                let batches = common::collect(input).await?;
+                let timer = baseline_metrics.elapsed_compute().timer();
+                let batches = latest_rows(&batches, &self.keys, &self.sequence)?;
+                timer.done();
+                let num_rows = batches.iter().map(|b| b.num_rows()).sum();
+                baseline_metrics.record_output(num_rows);
+                baseline_metrics.done();
+                Ok(Box::pin(MemoryStream::try_new(
+                    batches,
+                    self.schema(),
+                    None,
+                )?))
+            }
+            DedupStrategy::Sort => Ok(Box::pin(SortedDedupStream {
+                schema: self.schema(),
+                keys: self.keys.clone(),
+                input,
+                last_key: None,
+                baseline_metrics,
+            })),
+        }
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                let keys = self
+                    .keys
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "DedupExec: keys=[{}], sequence={}, strategy={:?}",
+                    keys.join(", "),
+                    self.sequence,
+                    self.strategy
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    /// The number of keys is unknown
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Evaluates `exprs` on `batch`
+fn evaluate(
+    exprs: &[Arc<dyn PhysicalExpr>],
+    batch: &RecordBatch,
+) -> Result<Vec<ArrayRef>> {
+    exprs
+        .iter()
+        .map(|expr| Ok(expr.evaluate(batch)?.into_array(batch.num_rows())))
+        .collect()
+}
+
+/// Returns the key of the row `row` of the evaluated `keys`
+fn row_key(keys: &[ArrayRef], row: usize) -> Result<Vec<ScalarValue>> {
+    keys.iter()
+        .map(|key| ScalarValue::try_from_array(key, row))
+        .collect()
+}
+
+/// Returns the latest row of each key of `batches`, in their order
+fn latest_rows(
+    batches: &[RecordBatch],
+    keys: &[Arc<dyn PhysicalExpr>],
+    sequence: &Arc<dyn PhysicalExpr>,
+) -> Result<Vec<RecordBatch>> {
+    // the sequence, the batch and the row of the latest row of each key
+    let mut latest: HashMap<Vec<ScalarValue>, (ScalarValue, usize, usize)> =
+        HashMap::new();
+    for (batch_index, batch) in batches.iter().enumerate() {
+        let key_arrays = evaluate(keys, batch)?;
+        let sequences = sequence.evaluate(batch)?.into_array(batch.num_rows());
+        for row in 0..batch.num_rows() {
+            let sequence = ScalarValue::try_from_array(&sequences, row)?;
+            match latest.entry(row_key(&key_arrays, row)?) {
+                Entry::Occupied(mut entry) => {
+                    if sequence.partial_cmp(&entry.get().0) == Some(Ordering::Greater) {
+                        entry.insert((sequence, batch_index, row));
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert((sequence, batch_index, row));
+                }
+            }
+        }
+    }
+
+    let mut rows = vec![vec![]; batches.len()];
+    for (_, batch_index, row) in latest.into_values() {
+        rows[batch_index].push(row as u32);
+    }
+    batches
+        .iter()
+        .zip(rows)
+        .filter(|(_, rows)| !rows.is_empty())
+        .map(|(batch, mut rows)| {
+            rows.sort_unstable();
+            let indices = UInt32Array::from(rows);
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|column| take(column.as_ref(), &indices, None))
+                .collect::<ArrowResult<Vec<_>>>()?;
+            Ok(RecordBatch::try_new(batch.schema(), columns)?)
+        })
+        .collect()
+}
+
+/// Streams the first row of each key of a partition sorted by the keys and
+/// by the sequence in descending order
+struct SortedDedupStream {
+    /// Output schema
+    schema: SchemaRef,
+    /// The key of the rows
+    keys: Vec<Arc<dyn PhysicalExpr>>,
+    /// The input partition
+    input: SendableRecordBatchStream,
+    /// The key of the last row read
+    last_key: Option<Vec<ScalarValue>>,
+    /// runtime metrics recording
+    baseline_metrics: BaselineMetrics,
+}
+
+impl SortedDedupStream {
+    /// Returns the rows of `batch` whose key differs from the previous row
+    fn first_rows(&mut self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let key_arrays = evaluate(&self.keys, batch)?;
+        let mut first = Vec::with_capacity(batch.num_rows());
+        for row in 0..batch.num_rows() {
+            let key = row_key(&key_arrays, row)?;
+            first.push(Some(self.last_key.as_ref() != Some(&key)));
+            self.last_key = Some(key);
+        }
+        Ok(filter_record_batch(batch, &BooleanArray::from(first))?)
+    }
+}
+
+impl Stream for SortedDedupStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx).map(|x| match x {
+            Some(Ok(batch)) => {
+                let timer = self.baseline_metrics.elapsed_compute().timer();
+                let result = self.first_rows(&batch).map_err(DataFusionError::into);
+                timer.done();
+                Some(result)
+            }
+            other => other,
+        });
+        self.baseline_metrics.record_poll(poll)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // same number of record batches
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for SortedDedupStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::prelude::SessionContext;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn versions() -> Result<(SchemaRef, Vec<RecordBatch>)> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("seq", DataType::Int32, true),
+            Field::new("v", DataType::Utf8, false),
+        ]));
+        let batch = |ids: Vec<i32>, seqs: Vec<Option<i32>>, values: Vec<&str>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(ids)),
+                    Arc::new(Int32Array::from(seqs)),
+                    Arc::new(StringArray::from(values)),
+                ],
+            )
+        };
+        let batches = vec![
+            batch(
+                vec![1, 2, 1],
+                vec![Some(1), Some(5), Some(3)],
+                vec!["a", "b", "c"],
+            )?,
+            batch(
+                vec![2, 3, 1],
+                vec![None, None, Some(2)],
+                vec!["d", "e", "f"],
+            )?,
+        ];
+        Ok((schema, batches))
+    }
+
+    #[tokio::test]
+    async fn hash_dedup() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let (schema, batches) = versions()?;
+        let input = Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+        let exec = Arc::new(DedupExec::try_new(
+            input,
+            vec![col("id", &schema)?],
+            col("seq", &schema)?,
+            DedupStrategy::Hash,
+        )?);
+        let batches = collect(exec, session_ctx.task_ctx()).await?;
+        // the rows kept are in the order of the input
+        let expected = vec![
+            "+----+-----+---+",
+            "| id | seq | v |",
+            "+----+-----+---+",
+            "| 2  | 5   | b |",
+            "| 1  | 3   | c |",
+            "| 3  |     | e |",
+            "+----+-----+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sorted_dedup() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let (schema, _) = versions()?;
+        // sorted by id and by seq in descending order, split between batches
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 1, 1, 2, 2, 3])),
+                Arc::new(Int32Array::from(vec![
+                    Some(3),
+                    Some(2),
+                    Some(1),
+                    Some(5),
+                    None,
+                    None,
+                ])),
+                Arc::new(StringArray::from(vec!["c", "f", "a", "b", "d", "e"])),
+            ],
+        )?;
+        let batches = vec![batch.slice(0, 2), batch.slice(2, 2), batch.slice(4, 2)];
+        let input = Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+        let exec = Arc::new(DedupExec::try_new(
+            input,
+            vec![col("id", &schema)?],
+            col("seq", &schema)?,
+            DedupStrategy::Sort,
+        )?);
+        let batches = collect(exec, session_ctx.task_ctx()).await?;
+        let expected = vec![
+            "+----+-----+---+",
+            "| id | seq | v |",
+            "+----+-----+---+",
+            "| 1  | 3   | c |",
+            "| 2  | 5   | b |",
+            "| 3  |     | e |",
+            "+----+-----+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+}
@@ -42,6 +42,8 @@ pub struct EmptyExec {
     produce_one_row: bool,
     /// The schema for the produced row
     schema: SchemaRef,
+    /// Number of partitions
+    partitions: usize,
 }
 
 impl EmptyExec {
@@ -50,15 +52,27 @@ impl EmptyExec {
         EmptyExec {
             produce_one_row,
             schema,
+            partitions: 1,
         }
     }
 
+    /// Create a new EmptyExec with the given number of (empty) partitions
+    pub fn with_partitions(mut self, partitions: usize) -> Self {
+        self.partitions = partitions;
+        self
+    }
+
+    /// Number of partitions of this exec
+    pub fn partitions(&self) -> usize {
+        self.partitions
+    }
+
     /// Specifies whether this exec produces a row or not
     pub fn produce_one_row(&self) -> bool {
         self.produce_one_row
     }
 
-    fn data(&self) -> Result<Vec<RecordBatch>> {
+    pub(crate) fn data(&self) -> Result<Vec<RecordBatch>> {
         let batch = if self.produce_one_row {
             vec![RecordBatch::try_new(
                 Arc::new(Schema::new(vec![Field::new(
@@ -97,7 +111,7 @@ impl ExecutionPlan for EmptyExec {
 
     /// Get the output partitioning of this plan
     fn output_partitioning(&self) -> Partitioning {
-        Partitioning::UnknownPartitioning(1)
+        Partitioning::UnknownPartitioning(self.partitions)
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
@@ -108,10 +122,10 @@ impl ExecutionPlan for EmptyExec {
         self: Arc<Self>,
         _: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(EmptyExec::new(
-            self.produce_one_row,
-            self.schema.clone(),
-        )))
+        Ok(Arc::new(
+            EmptyExec::new(self.produce_one_row, self.schema.clone())
+                .with_partitions(self.partitions),
+        ))
     }
 
     async fn execute(
@@ -120,11 +134,10 @@ impl ExecutionPlan for EmptyExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         debug!("Start EmptyExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
-        // GlobalLimitExec has a single output partition
-        if 0 != partition {
+        if partition >= self.partitions {
             return Err(DataFusionError::Internal(format!(
-                "EmptyExec invalid partition {} (expected 0)",
-                partition
+                "EmptyExec invalid partition {} (expected less than {})",
+                partition, self.partitions
             )));
         }
 
@@ -142,7 +155,11 @@ impl ExecutionPlan for EmptyExec {
     ) -> std::fmt::Result {
         match t {
             DisplayFormatType::Default => {
-                write!(f, "EmptyExec: produce_one_row={}", self.produce_one_row)
+                write!(f, "EmptyExec: produce_one_row={}", self.produce_one_row)?;
+                if self.partitions > 1 {
+                    write!(f, ", partitions={}", self.partitions)?;
+                }
+                Ok(())
             }
         }
     }
@@ -213,6 +230,22 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn multiple_partitions() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let schema = test_util::aggr_test_schema();
+        let empty = EmptyExec::new(false, schema).with_partitions(3);
+        assert_eq!(empty.output_partitioning().partition_count(), 3);
+
+        for partition in 0..3 {
+            let iter = empty.execute(partition, task_ctx.clone()).await?;
+            assert!(common::collect(iter).await?.is_empty());
+        }
+        assert!(empty.execute(3, task_ctx).await.is_err());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn produce_one_row() -> Result<()> {
         let session_ctx = SessionContext::new();
@@ -20,6 +20,7 @@
 use crate::avro_to_arrow;
 use crate::error::{DataFusionError, Result};
 use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
 use crate::physical_plan::{
     DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
 };
@@ -33,8 +34,8 @@ use std::any::Any;
 use std::sync::Arc;
 
 #[cfg(feature = "avro")]
-use super::file_stream::{BatchIter, FileStream};
-use super::FileScanConfig;
+use super::file_stream::{BatchIter, FileStream, FileStreamMetrics};
+use super::{FileScanConfig, ScanErrorPolicy};
 
 /// Execution plan for scanning Avro data source
 #[derive(Debug, Clone)]
@@ -42,6 +43,9 @@ pub struct AvroExec {
     base_config: FileScanConfig,
     projected_statistics: Statistics,
     projected_schema: SchemaRef,
+    error_policy: ScanErrorPolicy,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
 }
 
 impl AvroExec {
@@ -53,12 +57,32 @@ impl AvroExec {
             base_config,
             projected_schema,
             projected_statistics,
+            error_policy: ScanErrorPolicy::default(),
+            metrics: ExecutionPlanMetricsSet::new(),
         }
     }
+
+    /// Set the policy applied when a file cannot be read. Avro blocks
+    /// cannot be resynchronized after a decoding error, so
+    /// `ScanErrorPolicy::SkipBadRecords` skips the whole file.
+    /// - defaults to `ScanErrorPolicy::Fail`
+    pub fn with_error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = match error_policy {
+            ScanErrorPolicy::SkipBadRecords => ScanErrorPolicy::SkipFile,
+            policy => policy,
+        };
+        self
+    }
+
     /// Ref to the base configs
     pub fn base_config(&self) -> &FileScanConfig {
         &self.base_config
     }
+
+    /// The policy applied when a file cannot be read
+    pub fn error_policy(&self) -> ScanErrorPolicy {
+        self.error_policy
+    }
 }
 
 #[async_trait]
@@ -76,7 +100,7 @@ impl ExecutionPlan for AvroExec {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.base_config.output_ordering()
     }
 
     fn relies_on_input_order(&self) -> bool {
@@ -133,12 +157,14 @@ impl ExecutionPlan for AvroExec {
         };
 
         Ok(Box::pin(FileStream::new(
-            Arc::clone(&self.base_config.object_store),
+            context.limit_object_store(Arc::clone(&self.base_config.object_store)),
             self.base_config.file_groups[partition].clone(),
             fun,
             Arc::clone(&self.projected_schema),
             self.base_config.limit,
             self.base_config.table_partition_cols.clone(),
+            self.error_policy,
+            FileStreamMetrics::new(&self.metrics, partition),
         )))
     }
 
@@ -159,6 +185,10 @@ impl ExecutionPlan for AvroExec {
         }
     }
 
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
     fn statistics(&self) -> Statistics {
         self.projected_statistics.clone()
     }
@@ -180,6 +210,7 @@ mod tests {
     use sqlparser::ast::ObjectType::Schema;
 
     use super::*;
+    use crate::physical_plan::file_format::VirtualColumn;
 
     #[tokio::test]
     async fn avro_exec_without_partition() -> Result<()> {
@@ -188,13 +219,14 @@ mod tests {
         let avro_exec = AvroExec::new(FileScanConfig {
             object_store: Arc::new(LocalFileSystem {}),
             file_groups: vec![vec![local_unpartitioned_file(filename.clone())]],
-            file_schema: AvroFormat {}
+            file_schema: AvroFormat::default()
                 .infer_schema(local_object_reader_stream(vec![filename]))
                 .await?,
             statistics: Statistics::default(),
             projection: Some(vec![0, 1, 2]),
             limit: None,
             table_partition_cols: vec![],
+            output_ordering: None,
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
@@ -238,7 +270,7 @@ mod tests {
     async fn avro_exec_missing_column() -> Result<()> {
         let testdata = crate::test_util::arrow_test_data();
         let filename = format!("{}/avro/alltypes_plain.avro", testdata);
-        let actual_schema = AvroFormat {}
+        let actual_schema = AvroFormat::default()
             .infer_schema(local_object_reader_stream(vec![filename]))
             .await?;
 
@@ -256,6 +288,7 @@ mod tests {
             projection: Some(vec![0, 1, 2, file_schema.fields().len()]),
             limit: None,
             table_partition_cols: vec![],
+            output_ordering: None,
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
@@ -302,7 +335,7 @@ mod tests {
         let mut partitioned_file = local_unpartitioned_file(filename.clone());
         partitioned_file.partition_values =
             vec![ScalarValue::Utf8(Some("2021-10-26".to_owned()))];
-        let file_schema = AvroFormat {}
+        let file_schema = AvroFormat::default()
             .infer_schema(local_object_reader_stream(vec![filename]))
             .await?;
 
@@ -315,7 +348,8 @@ mod tests {
             file_schema: file_schema,
             statistics: Statistics::default(),
             limit: None,
-            table_partition_cols: vec!["date".to_owned()],
+            table_partition_cols: vec![VirtualColumn::partition("date")],
+            output_ordering: None,
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
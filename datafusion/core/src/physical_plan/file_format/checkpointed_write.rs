@@ -0,0 +1,289 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Writes of a plan recorded in a [`Checkpoint`], one output file per group
+//! of scanned files.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::fs;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio::task::{self, JoinHandle};
+
+use crate::datasource::checkpoint::{Checkpoint, CheckpointEntry};
+use crate::datasource::listing::PartitionedFile;
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::{SessionState, TaskContext};
+use crate::physical_plan::empty::EmptyExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::{displayable, ExecutionPlan, SendableRecordBatchStream};
+
+use super::{AvroExec, CsvExec, NdJsonExec, ParquetExec};
+
+/// Prefix of the names of the output files
+const OUTPUT_PREFIX: &str = "part-";
+
+/// Executes `plan` and writes each of its partitions to a file of the
+/// directory `path` with `write_partition`, committing the output file along
+/// with the input files it was computed from to `checkpoint`.
+///
+/// Each partition of `plan` must be computed from a single group of files of
+/// a file scan, so that the input of every output file is known. The output
+/// files of previous runs that were not committed are removed, and the plan
+/// must not read the files consumed by the committed output files (see
+/// [`ListingTable::with_checkpoint`]).
+///
+/// [`ListingTable::with_checkpoint`]: crate::datasource::listing::ListingTable::with_checkpoint
+pub(crate) async fn plan_to_files_with_checkpoint<F, Fut>(
+    state: &SessionState,
+    plan: Arc<dyn ExecutionPlan>,
+    path: &str,
+    extension: &str,
+    checkpoint: Arc<dyn Checkpoint>,
+    write_partition: F,
+) -> Result<()>
+where
+    F: Fn(fs::File, SendableRecordBatchStream) -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let file_groups = scanned_file_groups(&plan)?;
+
+    // the directory is kept between the runs of the write
+    let fs_path = Path::new(path);
+    fs::create_dir_all(fs_path).map_err(|e| {
+        DataFusionError::Execution(format!(
+            "Could not create directory {}: {:?}",
+            path, e
+        ))
+    })?;
+
+    let entries = checkpoint.entries()?;
+    let committed_outputs = entries
+        .iter()
+        .map(|entry| entry.output.as_str())
+        .collect::<HashSet<_>>();
+    let consumed_files = entries
+        .iter()
+        .flat_map(|entry| entry.inputs.iter().map(|input| input.as_str()))
+        .collect::<HashSet<_>>();
+
+    // remove the output of the partitions interrupted before their commit
+    for dir_entry in fs::read_dir(fs_path)? {
+        let dir_entry = dir_entry?;
+        let name = dir_entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with(OUTPUT_PREFIX) && !committed_outputs.contains(name.as_str()) {
+            fs::remove_file(dir_entry.path())?;
+        }
+    }
+
+    let mut tasks: Vec<JoinHandle<Result<()>>> = vec![];
+    for (i, files) in file_groups.iter().enumerate() {
+        let inputs = files
+            .iter()
+            .map(|file| {
+                let input = file.file_meta.path().to_owned();
+                if file.range.is_some() {
+                    return Err(DataFusionError::NotImplemented(format!(
+                        "Checkpointed writes cannot read a range of the file {}",
+                        input
+                    )));
+                }
+                if consumed_files.contains(input.as_str()) {
+                    return Err(DataFusionError::Execution(format!(
+                        "File {} was already written according to the checkpoint, \
+                        the table must be scanned with the checkpoint of the write",
+                        input
+                    )));
+                }
+                Ok(input)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // the output only needs a name unique to its input files: the output
+        // of an interrupted run is removed before the next run
+        let mut hasher = DefaultHasher::new();
+        inputs.hash(&mut hasher);
+        let output = format!("{}{:016x}.{}", OUTPUT_PREFIX, hasher.finish(), extension);
+        let output_path = fs_path.join(&output);
+        let in_progress_path = fs_path.join(format!("{}.inprogress", output));
+
+        let file = fs::File::create(&in_progress_path)?;
+        let sync_file = file.try_clone()?;
+        let task_ctx = Arc::new(TaskContext::from(state));
+        let stream = plan.execute(i, task_ctx).await?;
+        let write = write_partition(file, stream);
+        let checkpoint = checkpoint.clone();
+        tasks.push(task::spawn(async move {
+            write.await?;
+            sync_file.sync_all()?;
+            fs::rename(&in_progress_path, &output_path)?;
+            checkpoint.commit(CheckpointEntry { output, inputs })
+        }));
+    }
+
+    for result in futures::future::join_all(tasks).await {
+        result.map_err(|e| DataFusionError::Execution(format!("{:?}", e)))??;
+    }
+    Ok(())
+}
+
+/// Returns the file groups read by the partitions of `plan`, or no groups
+/// if `plan` reads no files
+fn scanned_file_groups(
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Result<Vec<Vec<PartitionedFile>>> {
+    let any = plan.as_any();
+    if let Some(exec) = any.downcast_ref::<CsvExec>() {
+        return Ok(exec.base_config().file_groups.clone());
+    }
+    if let Some(exec) = any.downcast_ref::<NdJsonExec>() {
+        return Ok(exec.base_config().file_groups.clone());
+    }
+    if let Some(exec) = any.downcast_ref::<AvroExec>() {
+        return Ok(exec.base_config().file_groups.clone());
+    }
+    if let Some(exec) = any.downcast_ref::<ParquetExec>() {
+        return Ok(exec.base_config().file_groups.clone());
+    }
+    if any.is::<EmptyExec>() {
+        return Ok(vec![]);
+    }
+    if any.is::<RepartitionExec>() {
+        return Err(checkpoint_not_supported(plan));
+    }
+
+    match plan.children().as_slice() {
+        [child] => {
+            let file_groups = scanned_file_groups(child)?;
+            if !file_groups.is_empty()
+                && file_groups.len() != plan.output_partitioning().partition_count()
+            {
+                return Err(checkpoint_not_supported(plan));
+            }
+            Ok(file_groups)
+        }
+        _ => Err(checkpoint_not_supported(plan)),
+    }
+}
+
+fn checkpoint_not_supported(plan: &Arc<dyn ExecutionPlan>) -> DataFusionError {
+    DataFusionError::NotImplemented(format!(
+        "Checkpointed writes need every partition to be computed from a single \
+        group of scanned files, which {} does not preserve",
+        displayable(plan.as_ref()).one_line()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::datafusion_data_access::object_store::local::LocalFileSystem;
+    use crate::datasource::checkpoint::MemoryCheckpoint;
+    use crate::datasource::file_format::csv::CsvFormat;
+    use crate::datasource::listing::{ListingOptions, ListingTable, ListingTableConfig};
+    use crate::prelude::*;
+    use tempfile::TempDir;
+
+    fn write_input(dir: &Path, name: &str, values: &[i32]) -> Result<()> {
+        let rows = values
+            .iter()
+            .map(|v| format!("{}\n", v))
+            .collect::<String>();
+        fs::write(dir.join(name), format!("c\n{}", rows))?;
+        Ok(())
+    }
+
+    async fn run_write(
+        input: &Path,
+        output: &Path,
+        checkpoint: Arc<dyn Checkpoint>,
+    ) -> Result<()> {
+        let ctx =
+            SessionContext::with_config(SessionConfig::new().with_target_partitions(1));
+        let options = ListingOptions {
+            file_extension: ".csv".to_owned(),
+            target_partitions: 2,
+            ..ListingOptions::new(Arc::new(CsvFormat::default()))
+        };
+        let config =
+            ListingTableConfig::new(Arc::new(LocalFileSystem), input.to_str().unwrap())
+                .with_listing_options(options)
+                .infer_schema()
+                .await?;
+        let table = ListingTable::try_new(config)?.with_checkpoint(checkpoint.clone());
+        ctx.register_table("t", Arc::new(table))?;
+        ctx.sql("SELECT c * 10 AS c FROM t WHERE c > 1")
+            .await?
+            .write_csv_with_checkpoint(output.to_str().unwrap(), checkpoint)
+            .await
+    }
+
+    #[tokio::test]
+    async fn restart_checkpointed_write() -> Result<()> {
+        let input = TempDir::new()?;
+        let output = TempDir::new()?;
+        let checkpoint = Arc::new(MemoryCheckpoint::new());
+        write_input(input.path(), "a.csv", &[1, 2])?;
+        write_input(input.path(), "b.csv", &[3])?;
+        write_input(input.path(), "c.csv", &[4, 5])?;
+        // output of a previous run interrupted before its commit
+        write_input(output.path(), "part-0.csv", &[1000])?;
+
+        run_write(input.path(), output.path(), checkpoint.clone()).await?;
+        assert_eq!(checkpoint.entries()?.len(), 2);
+        assert_eq!(checkpoint.consumed_files()?.len(), 3);
+
+        // only the new file is written by the next run
+        write_input(input.path(), "d.csv", &[6])?;
+        run_write(input.path(), output.path(), checkpoint.clone()).await?;
+        let entries = checkpoint.entries()?;
+        assert_eq!(entries.len(), 3);
+        assert!(entries[2].inputs[0].ends_with("d.csv"));
+
+        // nothing is left to write
+        run_write(input.path(), output.path(), checkpoint.clone()).await?;
+        assert_eq!(checkpoint.entries()?.len(), 3);
+        assert_eq!(fs::read_dir(output.path())?.count(), 3);
+
+        let ctx = SessionContext::new();
+        ctx.register_csv(
+            "written",
+            output.path().to_str().unwrap(),
+            CsvReadOptions::new(),
+        )
+        .await?;
+        let batches = ctx
+            .sql("SELECT count(*), sum(c) FROM written")
+            .await?
+            .collect()
+            .await?;
+        let expected = vec![
+            "+-----------------+----------------+",
+            "| COUNT(UInt8(1)) | SUM(written.c) |",
+            "+-----------------+----------------+",
+            "| 5               | 200            |",
+            "+-----------------+----------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+}
@@ -17,9 +17,11 @@
 
 //! Execution plan for reading CSV files
 
+use crate::datasource::checkpoint::Checkpoint;
 use crate::error::{DataFusionError, Result};
 use crate::execution::context::{SessionState, TaskContext};
 use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
 use crate::physical_plan::{
     DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
 };
@@ -34,8 +36,9 @@ use std::path::Path;
 use std::sync::Arc;
 use tokio::task::{self, JoinHandle};
 
-use super::file_stream::{BatchIter, FileStream};
-use super::FileScanConfig;
+use super::checkpointed_write::plan_to_files_with_checkpoint;
+use super::file_stream::{BatchIter, FileStream, FileStreamMetrics};
+use super::{FileScanConfig, ScanErrorPolicy};
 
 /// Execution plan for scanning a CSV file
 #[derive(Debug, Clone)]
@@ -45,6 +48,9 @@ pub struct CsvExec {
     projected_schema: SchemaRef,
     has_header: bool,
     delimiter: u8,
+    error_policy: ScanErrorPolicy,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
 }
 
 impl CsvExec {
@@ -58,9 +64,18 @@ impl CsvExec {
             projected_statistics,
             has_header,
             delimiter,
+            error_policy: ScanErrorPolicy::default(),
+            metrics: ExecutionPlanMetricsSet::new(),
         }
     }
 
+    /// Set the policy applied when a file or a record cannot be read
+    /// - defaults to `ScanErrorPolicy::Fail`
+    pub fn with_error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
     /// Ref to the base configs
     pub fn base_config(&self) -> &FileScanConfig {
         &self.base_config
@@ -73,6 +88,10 @@ impl CsvExec {
     pub fn delimiter(&self) -> u8 {
         self.delimiter
     }
+    /// The policy applied when a file or a record cannot be read
+    pub fn error_policy(&self) -> ScanErrorPolicy {
+        self.error_policy
+    }
 }
 
 #[async_trait]
@@ -97,7 +116,7 @@ impl ExecutionPlan for CsvExec {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.base_config.output_ordering()
     }
 
     fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
@@ -140,12 +159,14 @@ impl ExecutionPlan for CsvExec {
         };
 
         Ok(Box::pin(FileStream::new(
-            Arc::clone(&self.base_config.object_store),
+            context.limit_object_store(Arc::clone(&self.base_config.object_store)),
             self.base_config.file_groups[partition].clone(),
             fun,
             Arc::clone(&self.projected_schema),
             self.base_config.limit,
             self.base_config.table_partition_cols.clone(),
+            self.error_policy,
+            FileStreamMetrics::new(&self.metrics, partition),
         )))
     }
 
@@ -168,6 +189,10 @@ impl ExecutionPlan for CsvExec {
         }
     }
 
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
     fn statistics(&self) -> Statistics {
         self.projected_statistics.clone()
     }
@@ -211,11 +236,38 @@ pub async fn plan_to_csv(
     }
 }
 
+/// Executes a query and writes the results to a partitioned CSV file,
+/// recording the written files in `checkpoint`
+pub async fn plan_to_csv_with_checkpoint(
+    state: &SessionState,
+    plan: Arc<dyn ExecutionPlan>,
+    path: impl AsRef<str>,
+    checkpoint: Arc<dyn Checkpoint>,
+) -> Result<()> {
+    plan_to_files_with_checkpoint(
+        state,
+        plan,
+        path.as_ref(),
+        "csv",
+        checkpoint,
+        |file, stream| async move {
+            let mut writer = csv::Writer::new(file);
+            stream
+                .map(|batch| writer.write(&batch?))
+                .try_collect()
+                .await
+                .map_err(DataFusionError::from)
+        },
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::datafusion_data_access::object_store::local::LocalFileSystem;
     use crate::datasource::listing::local_unpartitioned_file;
+    use crate::physical_plan::file_format::VirtualColumn;
     use crate::prelude::*;
     use crate::test_util::aggr_test_schema_with_missing_col;
     use crate::{scalar::ScalarValue, test_util::aggr_test_schema};
@@ -242,6 +294,7 @@ mod tests {
                 projection: Some(vec![0, 2, 4]),
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -289,6 +342,7 @@ mod tests {
                 projection: None,
                 limit: Some(5),
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -336,6 +390,7 @@ mod tests {
                 projection: None,
                 limit: Some(5),
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -389,7 +444,8 @@ mod tests {
                 file_groups: vec![vec![partitioned_file]],
                 statistics: Statistics::default(),
                 limit: None,
-                table_partition_cols: vec!["date".to_owned()],
+                table_partition_cols: vec![VirtualColumn::partition("date")],
+                output_ordering: None,
             },
             true,
             b',',
@@ -22,6 +22,7 @@
 //! compliant with the `SendableRecordBatchStream` trait.
 
 use crate::datasource::listing::PartitionedFile;
+use crate::physical_plan::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder};
 use crate::{physical_plan::RecordBatchStream, scalar::ScalarValue};
 use arrow::{
     datatypes::SchemaRef,
@@ -30,6 +31,7 @@ use arrow::{
 };
 use datafusion_data_access::object_store::ObjectStore;
 use futures::Stream;
+use log::warn;
 use std::{
     io::Read,
     iter,
@@ -38,7 +40,7 @@ use std::{
     task::{Context, Poll},
 };
 
-use super::PartitionColumnProjector;
+use super::{PartitionColumnProjector, ScanErrorPolicy, VirtualColumn};
 
 pub type FileIter = Box<dyn Iterator<Item = PartitionedFile> + Send + Sync>;
 pub type BatchIter = Box<dyn Iterator<Item = ArrowResult<RecordBatch>> + Send + Sync>;
@@ -58,6 +60,31 @@ impl<T> FormatReaderOpener for T where
 {
 }
 
+/// Counters of the errors tolerated by a [`FileStream`] according to its
+/// [`ScanErrorPolicy`]
+#[derive(Debug, Clone)]
+pub struct FileStreamMetrics {
+    /// Number of files that were skipped after a read error
+    pub files_skipped: metrics::Count,
+    /// Number of batches that were dropped because they failed to decode
+    pub batches_skipped: metrics::Count,
+}
+
+impl FileStreamMetrics {
+    /// Create new metrics for the given partition
+    pub fn new(metrics: &ExecutionPlanMetricsSet, partition: usize) -> Self {
+        let files_skipped =
+            MetricBuilder::new(metrics).counter("files_skipped", partition);
+        let batches_skipped =
+            MetricBuilder::new(metrics).counter("batches_skipped", partition);
+
+        Self {
+            files_skipped,
+            batches_skipped,
+        }
+    }
+}
+
 /// A stream that iterates record batch by record batch, file over file.
 pub struct FileStream<F: FormatReaderOpener> {
     /// An iterator over record batches of the last file returned by file_iter
@@ -80,6 +107,12 @@ pub struct FileStream<F: FormatReaderOpener> {
     pc_projector: PartitionColumnProjector,
     /// the store from which to source the files.
     object_store: Arc<dyn ObjectStore>,
+    /// What to do when a file cannot be opened or decoded
+    error_policy: ScanErrorPolicy,
+    /// Path of the file currently being read, used to report errors
+    current_file: Option<String>,
+    /// Counters of the tolerated errors
+    metrics: FileStreamMetrics,
 }
 
 impl<F: FormatReaderOpener> FileStream<F> {
@@ -89,7 +122,9 @@ impl<F: FormatReaderOpener> FileStream<F> {
         file_reader: F,
         projected_schema: SchemaRef,
         limit: Option<usize>,
-        table_partition_cols: Vec<String>,
+        table_partition_cols: Vec<VirtualColumn>,
+        error_policy: ScanErrorPolicy,
+        metrics: FileStreamMetrics,
     ) -> Self {
         let pc_projector = PartitionColumnProjector::new(
             Arc::clone(&projected_schema),
@@ -105,32 +140,76 @@ impl<F: FormatReaderOpener> FileStream<F> {
             file_reader,
             pc_projector,
             object_store,
+            error_policy,
+            current_file: None,
+            metrics,
+        }
+    }
+
+    /// Applies the error policy to an error raised while reading the current
+    /// file. Returns the error if it should be propagated, or `None` if the
+    /// stream should carry on.
+    fn handle_error(&mut self, e: ArrowError) -> Option<ArrowError> {
+        let file = self.current_file.as_deref().unwrap_or_default();
+        match self.error_policy {
+            ScanErrorPolicy::Fail => Some(e),
+            ScanErrorPolicy::SkipFile => {
+                warn!("Skipping file {} after read error: {}", file, e);
+                self.metrics.files_skipped.add(1);
+                self.batch_iter = Box::new(iter::empty());
+                None
+            }
+            ScanErrorPolicy::SkipBadRecords => {
+                warn!("Skipping undecodable records in file {}: {}", file, e);
+                self.metrics.batches_skipped.add(1);
+                None
+            }
         }
     }
 
     /// Acts as a flat_map of record batches over files. Adds the partitioning
     /// Columns to the returned record batches.
     fn next_batch(&mut self) -> Option<ArrowResult<RecordBatch>> {
-        match self.batch_iter.next() {
-            Some(Ok(batch)) => {
-                Some(self.pc_projector.project(batch, &self.partition_values))
-            }
-            Some(Err(e)) => Some(Err(e)),
-            None => match self.file_iter.next() {
-                Some(f) => {
-                    self.partition_values = f.partition_values;
-                    self.object_store
-                        .file_reader(f.file_meta.sized_file)
-                        .and_then(|r| r.sync_reader())
-                        .map_err(|e| ArrowError::ExternalError(Box::new(e)))
-                        .and_then(|f| {
-                            self.batch_iter = (self.file_reader)(f, &self.remain);
-                            self.next_batch().transpose()
-                        })
-                        .transpose()
+        loop {
+            match self.batch_iter.next() {
+                Some(Ok(batch)) => {
+                    return Some(self.pc_projector.project(batch, &self.partition_values))
                 }
-                None => None,
-            },
+                Some(Err(e)) => match self.handle_error(e) {
+                    Some(e) => return Some(Err(e)),
+                    None => continue,
+                },
+                None => match self.file_iter.next() {
+                    Some(f) => {
+                        self.partition_values = f.partition_values;
+                        self.current_file = Some(f.file_meta.path().to_owned());
+                        let reader = self
+                            .object_store
+                            .file_reader(f.file_meta.sized_file)
+                            .and_then(|r| r.sync_reader())
+                            .map_err(|e| ArrowError::ExternalError(Box::new(e)));
+                        match reader {
+                            Ok(r) => {
+                                self.batch_iter = (self.file_reader)(r, &self.remain);
+                            }
+                            Err(e) => {
+                                // a file that cannot be opened is always skipped
+                                // as a whole
+                                if self.error_policy == ScanErrorPolicy::Fail {
+                                    return Some(Err(e));
+                                }
+                                warn!(
+                                    "Skipping file {} after open error: {}",
+                                    self.current_file.as_deref().unwrap_or_default(),
+                                    e
+                                );
+                                self.metrics.files_skipped.add(1);
+                            }
+                        }
+                    }
+                    None => return None,
+                },
+            }
         }
     }
 }
@@ -188,6 +267,7 @@ mod tests {
     use super::*;
     use crate::{
         error::Result,
+        physical_plan::metrics::MetricsSet,
         test::{make_partition, object_store::TestObjectStore},
     };
 
@@ -202,6 +282,7 @@ mod tests {
             Box::new(records.clone().into_iter().map(Ok)) as BatchIter
         };
 
+        let metrics = ExecutionPlanMetricsSet::new();
         let file_stream = FileStream::new(
             TestObjectStore::new_arc(&[("mock_file1", 10), ("mock_file2", 20)]),
             vec![
@@ -212,6 +293,8 @@ mod tests {
             source_schema,
             limit,
             vec![],
+            ScanErrorPolicy::Fail,
+            FileStreamMetrics::new(&metrics, 0),
         );
 
         file_stream
@@ -220,6 +303,40 @@ mod tests {
             .await
     }
 
+    /// helper that creates a stream of 2 files where the first batch of
+    /// each file fails to decode, and collects it with the given policy
+    async fn create_and_collect_with_errors(
+        error_policy: ScanErrorPolicy,
+    ) -> (Vec<ArrowResult<RecordBatch>>, MetricsSet) {
+        let records = vec![make_partition(3), make_partition(2)];
+
+        let source_schema = records[0].schema();
+
+        let reader = move |_file, _remain: &Option<usize>| {
+            let error = ArrowError::ParseError("corrupt record".to_owned());
+            Box::new(iter::once(Err(error)).chain(records.clone().into_iter().map(Ok)))
+                as BatchIter
+        };
+
+        let metrics = ExecutionPlanMetricsSet::new();
+        let file_stream = FileStream::new(
+            TestObjectStore::new_arc(&[("mock_file1", 10), ("mock_file2", 20)]),
+            vec![
+                PartitionedFile::new("mock_file1".to_owned(), 10),
+                PartitionedFile::new("mock_file2".to_owned(), 20),
+            ],
+            reader,
+            source_schema,
+            None,
+            vec![],
+            error_policy,
+            FileStreamMetrics::new(&metrics, 0),
+        );
+
+        let results = file_stream.collect::<Vec<_>>().await;
+        (results, metrics.clone_inner())
+    }
+
     #[tokio::test]
     async fn without_limit() -> Result<()> {
         let batches = create_and_collect(None).await;
@@ -283,4 +400,61 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn error_policy_fail() -> Result<()> {
+        let (results, metrics) =
+            create_and_collect_with_errors(ScanErrorPolicy::Fail).await;
+
+        assert!(results[0].is_err());
+        assert_eq!(metrics.sum_by_name("files_skipped").unwrap().as_usize(), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn error_policy_skip_file() -> Result<()> {
+        let (results, metrics) =
+            create_and_collect_with_errors(ScanErrorPolicy::SkipFile).await;
+
+        assert!(results.is_empty());
+        assert_eq!(metrics.sum_by_name("files_skipped").unwrap().as_usize(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn error_policy_skip_bad_records() -> Result<()> {
+        let (results, metrics) =
+            create_and_collect_with_errors(ScanErrorPolicy::SkipBadRecords).await;
+        let batches = results
+            .into_iter()
+            .collect::<ArrowResult<Vec<_>>>()
+            .expect("No error expected in stream");
+
+        #[rustfmt::skip]
+        crate::assert_batches_eq!(&[
+            "+---+",
+            "| i |",
+            "+---+",
+            "| 0 |",
+            "| 1 |",
+            "| 2 |",
+            "| 0 |",
+            "| 1 |",
+            "| 0 |",
+            "| 1 |",
+            "| 2 |",
+            "| 0 |",
+            "| 1 |",
+            "+---+",
+        ], &batches);
+        assert_eq!(
+            metrics.sum_by_name("batches_skipped").unwrap().as_usize(),
+            2
+        );
+        assert_eq!(metrics.sum_by_name("files_skipped").unwrap().as_usize(), 0);
+
+        Ok(())
+    }
 }
@@ -16,26 +16,37 @@
 // under the License.
 
 //! Execution plan for reading line-delimited JSON files
-use arrow::json::reader::DecoderOptions;
+use arrow::error::{ArrowError, Result as ArrowResult};
+use arrow::json::reader::{Decoder, DecoderOptions};
+use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::{Map, Value};
 
+use crate::datasource::checkpoint::Checkpoint;
 use crate::error::{DataFusionError, Result};
 use crate::execution::context::SessionState;
 use crate::execution::context::TaskContext;
 use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
 use crate::physical_plan::{
     DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
 };
 use arrow::{datatypes::SchemaRef, json};
 use futures::{StreamExt, TryStreamExt};
 use std::any::Any;
+use std::collections::HashSet;
+use std::fmt;
 use std::fs;
+use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 use std::sync::Arc;
 use tokio::task::{self, JoinHandle};
 
-use super::file_stream::{BatchIter, FileStream};
-use super::FileScanConfig;
+use super::checkpointed_write::plan_to_files_with_checkpoint;
+use super::file_stream::{BatchIter, FileStream, FileStreamMetrics};
+use super::{FileScanConfig, ScanErrorPolicy};
 
 /// Execution plan for scanning NdJson data source
 #[derive(Debug, Clone)]
@@ -43,6 +54,9 @@ pub struct NdJsonExec {
     base_config: FileScanConfig,
     projected_statistics: Statistics,
     projected_schema: SchemaRef,
+    error_policy: ScanErrorPolicy,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
 }
 
 impl NdJsonExec {
@@ -54,8 +68,27 @@ impl NdJsonExec {
             base_config,
             projected_schema,
             projected_statistics,
+            error_policy: ScanErrorPolicy::default(),
+            metrics: ExecutionPlanMetricsSet::new(),
         }
     }
+
+    /// Set the policy applied when a file or a record cannot be read
+    /// - defaults to `ScanErrorPolicy::Fail`
+    pub fn with_error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// The policy applied when a file or a record cannot be read
+    pub fn error_policy(&self) -> ScanErrorPolicy {
+        self.error_policy
+    }
+
+    /// Ref to the base configs
+    pub fn base_config(&self) -> &FileScanConfig {
+        &self.base_config
+    }
 }
 
 #[async_trait]
@@ -73,7 +106,7 @@ impl ExecutionPlan for NdJsonExec {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.base_config.output_ordering()
     }
 
     fn relies_on_input_order(&self) -> bool {
@@ -97,12 +130,14 @@ impl ExecutionPlan for NdJsonExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         let proj = self.base_config.projected_file_column_names();
+        let proj_keys = proj
+            .as_ref()
+            .map(|names| Arc::new(names.iter().cloned().collect::<HashSet<_>>()));
 
         let batch_size = context.session_config().batch_size;
         let file_schema = Arc::clone(&self.base_config.file_schema);
 
-        // The json reader cannot limit the number of records, so `remaining` is ignored.
-        let fun = move |file, _remaining: &Option<usize>| {
+        let fun = move |file, remaining: &Option<usize>| {
             // TODO: make DecoderOptions implement Clone so we can
             // clone here rather than recreating the options each time
             // https://github.com/apache/arrow-rs/issues/1580
@@ -114,17 +149,23 @@ impl ExecutionPlan for NdJsonExec {
                 options
             };
 
-            Box::new(json::Reader::new(file, Arc::clone(&file_schema), options))
-                as BatchIter
+            Box::new(ProjectedJsonReader::new(
+                file,
+                Decoder::new(Arc::clone(&file_schema), options),
+                proj_keys.clone(),
+                *remaining,
+            )) as BatchIter
         };
 
         Ok(Box::pin(FileStream::new(
-            Arc::clone(&self.base_config.object_store),
+            context.limit_object_store(Arc::clone(&self.base_config.object_store)),
             self.base_config.file_groups[partition].clone(),
             fun,
             Arc::clone(&self.projected_schema),
             self.base_config.limit,
             self.base_config.table_partition_cols.clone(),
+            self.error_policy,
+            FileStreamMetrics::new(&self.metrics, partition),
         )))
     }
 
@@ -145,11 +186,153 @@ impl ExecutionPlan for NdJsonExec {
         }
     }
 
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
     fn statistics(&self) -> Statistics {
         self.projected_statistics.clone()
     }
 }
 
+/// Reads line-delimited JSON into record batches, parsing only the projected
+/// fields of each object. The values of the other fields are skipped by the
+/// parser without being materialized, which is much cheaper for wide records.
+/// Once the limit is reached, the rest of the file is not read.
+struct ProjectedJsonReader<R: Read> {
+    reader: BufReader<R>,
+    decoder: Decoder,
+    /// Names of the fields to parse, `None` to parse all of them
+    projection: Option<Arc<HashSet<String>>>,
+    /// The remaining number of records to parse, `None` if no limit
+    remaining: Option<usize>,
+    /// Buffer for the line being parsed
+    line: String,
+}
+
+impl<R: Read> ProjectedJsonReader<R> {
+    fn new(
+        reader: R,
+        decoder: Decoder,
+        projection: Option<Arc<HashSet<String>>>,
+        remaining: Option<usize>,
+    ) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            decoder,
+            projection,
+            remaining,
+            line: String::new(),
+        }
+    }
+}
+
+impl<R: Read> Iterator for ProjectedJsonReader<R> {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut values = ProjectedValueIter {
+            reader: &mut self.reader,
+            projection: self.projection.as_deref(),
+            remaining: &mut self.remaining,
+            line: &mut self.line,
+        };
+        self.decoder.next_batch(&mut values).transpose()
+    }
+}
+
+/// Iterator over the JSON objects of a line-delimited JSON reader, only
+/// keeping the projected fields of each object, and stopping after the
+/// remaining number of records
+struct ProjectedValueIter<'a, R: BufRead> {
+    reader: &'a mut R,
+    projection: Option<&'a HashSet<String>>,
+    remaining: &'a mut Option<usize>,
+    line: &'a mut String,
+}
+
+impl<'a, R: BufRead> Iterator for ProjectedValueIter<'a, R> {
+    type Item = ArrowResult<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.remaining {
+            Some(0) => return None,
+            Some(remaining) => *remaining -= 1,
+            None => {}
+        }
+        loop {
+            self.line.clear();
+            match self.reader.read_line(self.line) {
+                Ok(0) => return None,
+                Ok(_) => {
+                    let trimmed = self.line.trim();
+                    if trimmed.is_empty() {
+                        // skip empty lines
+                        continue;
+                    }
+
+                    let mut deserializer = serde_json::Deserializer::from_str(trimmed);
+                    let value = match self.projection {
+                        Some(keys) => {
+                            ProjectedObject(keys).deserialize(&mut deserializer)
+                        }
+                        None => Value::deserialize(&mut deserializer),
+                    }
+                    .and_then(|value| deserializer.end().map(|_| value));
+                    return Some(value.map_err(|e| {
+                        ArrowError::JsonError(format!("Not valid JSON: {}", e))
+                    }));
+                }
+                Err(e) => {
+                    return Some(Err(ArrowError::JsonError(format!(
+                        "Failed to read JSON record: {}",
+                        e
+                    ))))
+                }
+            }
+        }
+    }
+}
+
+/// Deserializes a JSON object, keeping only the fields whose name is in the
+/// given set
+struct ProjectedObject<'a>(&'a HashSet<String>);
+
+impl<'de, 'a> DeserializeSeed<'de> for ProjectedObject<'a> {
+    type Value = Value;
+
+    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for ProjectedObject<'a> {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON object")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> std::result::Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut object = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if self.0.contains(&key) {
+                let value = map.next_value::<Value>()?;
+                object.insert(key, value);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(Value::Object(object))
+    }
+}
+
 pub async fn plan_to_json(
     state: &SessionState,
     plan: Arc<dyn ExecutionPlan>,
@@ -188,10 +371,36 @@ pub async fn plan_to_json(
     }
 }
 
+/// Executes a query and writes the results to a partitioned JSON file,
+/// recording the written files in `checkpoint`
+pub async fn plan_to_json_with_checkpoint(
+    state: &SessionState,
+    plan: Arc<dyn ExecutionPlan>,
+    path: impl AsRef<str>,
+    checkpoint: Arc<dyn Checkpoint>,
+) -> Result<()> {
+    plan_to_files_with_checkpoint(
+        state,
+        plan,
+        path.as_ref(),
+        "json",
+        checkpoint,
+        |file, stream| async move {
+            let mut writer = json::LineDelimitedWriter::new(file);
+            stream
+                .map(|batch| writer.write(batch?))
+                .try_collect::<()>()
+                .await?;
+            writer.finish().map_err(DataFusionError::from)
+        },
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use arrow::array::Array;
-    use arrow::datatypes::{Field, Schema};
+    use arrow::datatypes::{DataType, Field, Schema};
     use futures::StreamExt;
 
     use crate::datafusion_data_access::object_store::local::{
@@ -229,6 +438,7 @@ mod tests {
             projection: None,
             limit: Some(3),
             table_partition_cols: vec![],
+            output_ordering: None,
         });
 
         // TODO: this is not where schema inference should be tested
@@ -294,6 +504,7 @@ mod tests {
             projection: None,
             limit: Some(3),
             table_partition_cols: vec![],
+            output_ordering: None,
         });
 
         let mut it = exec.execute(0, task_ctx).await?;
@@ -326,6 +537,7 @@ mod tests {
             projection: Some(vec![0, 2]),
             limit: None,
             table_partition_cols: vec![],
+            output_ordering: None,
         });
         let inferred_schema = exec.schema();
         assert_eq!(inferred_schema.fields().len(), 2);
@@ -397,4 +609,45 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn projected_value_iter_skips_unprojected_fields() -> Result<()> {
+        let data = "{\"a\": 1, \"b\": [2.0, 1.3], \"c\": false, \"d\": \"4\"}\n\n\
+                    {\"d\": \"text\", \"a\": -10, \"b\": {\"nested\": [1]}}\n";
+        let mut reader = BufReader::new(data.as_bytes());
+        let projection = HashSet::from(["a".to_owned(), "d".to_owned()]);
+        let mut line = String::new();
+        let values = ProjectedValueIter {
+            reader: &mut reader,
+            projection: Some(&projection),
+            remaining: &mut None,
+            line: &mut line,
+        }
+        .collect::<ArrowResult<Vec<_>>>()?;
+
+        assert_eq!(
+            values,
+            vec![
+                serde_json::json!({"a": 1, "d": "4"}),
+                serde_json::json!({"a": -10, "d": "text"}),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn projected_json_reader_stops_at_limit() -> Result<()> {
+        // the lines after the limit are not parsed
+        let data = "{\"a\": 1, \"b\": true}\n{\"a\": 2}\n{\"a\": 3}\nnot json\n";
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, true)]));
+        let decoder = Decoder::new(schema, DecoderOptions::new().with_batch_size(2));
+        let projection = Arc::new(HashSet::from(["a".to_owned()]));
+        let batches =
+            ProjectedJsonReader::new(data.as_bytes(), decoder, Some(projection), Some(3))
+                .collect::<ArrowResult<Vec<_>>>()?;
+
+        let num_rows: Vec<_> = batches.iter().map(|b| b.num_rows()).collect();
+        assert_eq!(num_rows, vec![2, 1]);
+        Ok(())
+    }
 }
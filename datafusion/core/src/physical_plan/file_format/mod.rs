@@ -18,13 +18,14 @@
 //! Execution plans that read file formats
 
 mod avro;
+mod checkpointed_write;
 mod csv;
 mod file_stream;
 mod json;
 mod parquet;
 
-pub(crate) use self::parquet::plan_to_parquet;
-pub use self::parquet::ParquetExec;
+pub(crate) use self::parquet::{plan_to_parquet, plan_to_parquet_with_checkpoint};
+pub use self::parquet::{FileStatisticsCache, ParquetExec, SORT_ORDER_METADATA_KEY};
 use arrow::{
     array::{ArrayData, ArrayRef, DictionaryArray},
     buffer::Buffer,
@@ -33,12 +34,14 @@ use arrow::{
     record_batch::RecordBatch,
 };
 pub use avro::AvroExec;
-pub(crate) use csv::plan_to_csv;
 pub use csv::CsvExec;
-pub(crate) use json::plan_to_json;
+pub(crate) use csv::{plan_to_csv, plan_to_csv_with_checkpoint};
 pub use json::NdJsonExec;
+pub(crate) use json::{plan_to_json, plan_to_json_with_checkpoint};
 
+use crate::datasource::file_format::parquet::PARQUET_FIELD_ID_META_KEY;
 use crate::datasource::listing::PartitionedFile;
+use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::{
     error::{DataFusionError, Result},
     scalar::ScalarValue,
@@ -61,6 +64,136 @@ lazy_static! {
     pub static ref DEFAULT_PARTITION_COLUMN_DATATYPE: DataType = DataType::Dictionary(Box::new(DataType::UInt16), Box::new(DataType::Utf8));
 }
 
+/// What a scan should do when a file, or some of the records in a file,
+/// cannot be read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanErrorPolicy {
+    /// Fail the whole query on the first error (default)
+    Fail,
+    /// Log a warning, skip the rest of the offending file and move on to the
+    /// next one. Skipped files are counted in the `files_skipped` metric.
+    SkipFile,
+    /// Drop the records that could not be decoded and keep reading the file.
+    /// The readers decode whole batches at a time, so the rest of the batch
+    /// holding a bad record is dropped with it. Dropped batches are counted
+    /// in the `batches_skipped` metric.
+    ///
+    /// Only record oriented formats (CSV and JSON) can recover from a bad
+    /// record; the other formats treat this policy as `SkipFile`.
+    SkipBadRecords,
+}
+
+impl Default for ScanErrorPolicy {
+    fn default() -> Self {
+        Self::Fail
+    }
+}
+
+/// A column of the table scanned by a [`FileScanConfig`] that isn't read from
+/// the files but filled with a value of each file, such as a partitioning
+/// column parsed from the paths of the files.
+///
+/// The values of the files are cast to the declared type of the column, and
+/// the column is materialized as an array of that type, so that it behaves
+/// like the columns read from the files. The columns of
+/// [`DEFAULT_PARTITION_COLUMN_DATATYPE`] are materialized as dictionaries
+/// sharing the buffer of their keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VirtualColumn {
+    name: String,
+    data_type: DataType,
+}
+
+impl VirtualColumn {
+    /// Create a virtual column of the given type
+    pub fn new(name: impl Into<String>, data_type: DataType) -> Self {
+        Self {
+            name: name.into(),
+            data_type,
+        }
+    }
+
+    /// Create a partitioning column of [`DEFAULT_PARTITION_COLUMN_DATATYPE`]
+    pub fn partition(name: impl Into<String>) -> Self {
+        Self::new(name, DEFAULT_PARTITION_COLUMN_DATATYPE.clone())
+    }
+
+    /// The name of the column
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The type of the column
+    pub fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    /// The field of the column in the schema of the table
+    pub fn field(&self) -> Field {
+        Field::new(&self.name, self.data_type.clone(), false)
+    }
+
+    /// The type of the values of the column: its type, or the type of the
+    /// values of its dictionary
+    pub fn value_type(&self) -> &DataType {
+        match &self.data_type {
+            DataType::Dictionary(_, value_type) => value_type.as_ref(),
+            data_type => data_type,
+        }
+    }
+
+    /// Cast the values of files, e.g. the strings parsed from their paths, to
+    /// the [`value_type`](Self::value_type) of the column
+    pub fn cast_values(&self, values: &ArrayRef) -> Result<ArrayRef> {
+        let value_type = self.value_type();
+        if values.data_type() == value_type {
+            return Ok(Arc::clone(values));
+        }
+        let cast_values = arrow::compute::cast(values, value_type)?;
+        // the values that can't be cast are cast to nulls
+        if let Some(row) = (0..values.len())
+            .find(|row| cast_values.is_null(*row) && !values.is_null(*row))
+        {
+            return Err(DataFusionError::Execution(format!(
+                "The value '{}' of the column '{}' is not a valid {:?}",
+                ScalarValue::try_from_array(values, row)?,
+                self.name,
+                value_type
+            )));
+        }
+        Ok(cast_values)
+    }
+
+    /// Cast the value of a file to the [`value_type`](Self::value_type) of
+    /// the column
+    pub fn cast_value(&self, value: &ScalarValue) -> Result<ScalarValue> {
+        if value.get_datatype() == *self.value_type() {
+            return Ok(value.clone());
+        }
+        ScalarValue::try_from_array(&self.cast_values(&value.to_array())?, 0)
+    }
+
+    /// Materialize the value of a file as an array of `len` rows
+    fn create_array(
+        &self,
+        key_buffer_cache: &mut Option<Buffer>,
+        value: &ScalarValue,
+        len: usize,
+    ) -> Result<ArrayRef> {
+        let value = self.cast_value(value)?;
+        if self.data_type == *DEFAULT_PARTITION_COLUMN_DATATYPE {
+            return Ok(create_dict_array(key_buffer_cache, &value, len));
+        }
+        match &self.data_type {
+            DataType::Dictionary(_, _) => Ok(arrow::compute::cast(
+                &value.to_array_of_size(len),
+                &self.data_type,
+            )?),
+            _ => Ok(value.to_array_of_size(len)),
+        }
+    }
+}
+
 /// The base configurations to provide when creating a physical plan for
 /// any given file format.
 #[derive(Debug, Clone)]
@@ -79,11 +212,22 @@ pub struct FileScanConfig {
     pub projection: Option<Vec<usize>>,
     /// The minimum number of records required from this source plan
     pub limit: Option<usize>,
-    /// The partitioning column names
-    pub table_partition_cols: Vec<String>,
+    /// The virtual columns of the table, e.g. its partitioning columns, whose
+    /// values are the `partition_values` of the files
+    pub table_partition_cols: Vec<VirtualColumn>,
+    /// The order of the rows of each partition, as sort expressions on the
+    /// projected columns, if known. The files of a partition are read one
+    /// after the other: each of them must be sorted, and the values of a file
+    /// must follow the ones of the previous file of its group
+    pub output_ordering: Option<Vec<PhysicalSortExpr>>,
 }
 
 impl FileScanConfig {
+    /// The order of the rows of the partitions of the scan
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.output_ordering.as_deref()
+    }
+
     /// Project the schema and the statistics on the given column indices
     fn project(&self) -> (SchemaRef, Statistics) {
         if self.projection.is_none() && self.table_partition_cols.is_empty() {
@@ -109,11 +253,7 @@ impl FileScanConfig {
                 }
             } else {
                 let partition_idx = idx - self.file_schema.fields().len();
-                table_fields.push(Field::new(
-                    &self.table_partition_cols[partition_idx],
-                    DEFAULT_PARTITION_COLUMN_DATATYPE.clone(),
-                    false,
-                ));
+                table_fields.push(self.table_partition_cols[partition_idx].field());
                 // TODO provide accurate stat for partition column (#1186)
                 table_cols_stats.push(ColumnStatistics::default())
             }
@@ -201,15 +341,45 @@ impl<'a> Display for ProjectSchemaDisplay<'a> {
 /// 2. After reading a record batch we need to map the read columns back to the expected columns
 ///    indexes and insert null-valued columns wherever the file schema was missing a colum present
 ///    in the table schema.
+///
+/// Columns are matched by name, or by Parquet field ID when enabled with
+/// [`SchemaAdapter::with_field_id_matching`].
 #[derive(Clone, Debug)]
 pub(crate) struct SchemaAdapter {
     /// Schema for the table
     table_schema: SchemaRef,
+    /// Match the table fields with the file fields holding the same field ID
+    match_field_ids: bool,
 }
 
 impl SchemaAdapter {
     pub(crate) fn new(table_schema: SchemaRef) -> SchemaAdapter {
-        Self { table_schema }
+        Self {
+            table_schema,
+            match_field_ids: false,
+        }
+    }
+
+    /// Match the fields of the table schema that have a field ID (see
+    /// [`PARQUET_FIELD_ID_META_KEY`]) with the file field holding the same
+    /// field ID, whatever its name and position. The fields without a field
+    /// ID are still matched by name.
+    pub(crate) fn with_field_id_matching(mut self, enable: bool) -> Self {
+        self.match_field_ids = enable;
+        self
+    }
+
+    /// Index of the field of `file_schema` matching `table_field`
+    fn file_index(&self, table_field: &Field, file_schema: &Schema) -> Option<usize> {
+        if self.match_field_ids {
+            if let Some(id) = field_id(table_field) {
+                return file_schema
+                    .fields()
+                    .iter()
+                    .position(|field| field_id(field) == Some(id));
+            }
+        }
+        file_schema.index_of(table_field.name()).ok()
     }
 
     /// Map a column index in the table schema to a column index in a particular
@@ -220,8 +390,7 @@ impl SchemaAdapter {
         index: usize,
         file_schema: &Schema,
     ) -> Option<usize> {
-        let field = self.table_schema.field(index);
-        file_schema.index_of(field.name()).ok()
+        self.file_index(self.table_schema.field(index), file_schema)
     }
 
     /// Map projected column indexes to the file schema. This will fail if the table schema
@@ -234,7 +403,7 @@ impl SchemaAdapter {
         let mut mapped: Vec<usize> = vec![];
         for idx in projections {
             let field = self.table_schema.field(*idx);
-            if let Ok(mapped_idx) = file_schema.index_of(field.name().as_str()) {
+            if let Some(mapped_idx) = self.file_index(field, file_schema) {
                 if file_schema.field(mapped_idx).data_type() == field.data_type() {
                     mapped.push(mapped_idx)
                 } else {
@@ -249,11 +418,12 @@ impl SchemaAdapter {
 
     /// Re-order projected columns by index in record batch to match table schema column ordering. If the record
     /// batch does not contain a column for an expected field, insert a null-valued column at the
-    /// required column index.
+    /// required column index. `file_schema` is the schema of the file the batch was read from.
     pub fn adapt_batch(
         &self,
         batch: RecordBatch,
         projections: &[usize],
+        file_schema: &Schema,
     ) -> Result<RecordBatch> {
         let batch_rows = batch.num_rows();
 
@@ -264,9 +434,11 @@ impl SchemaAdapter {
 
         for field_idx in projections {
             let table_field = &self.table_schema.fields()[*field_idx];
-            if let Some((batch_idx, _name)) =
-                batch_schema.column_with_name(table_field.name().as_str())
-            {
+            let batch_column = self
+                .file_index(table_field, file_schema)
+                .map(|file_idx| file_schema.field(file_idx).name())
+                .and_then(|name| batch_schema.column_with_name(name));
+            if let Some((batch_idx, _name)) = batch_column {
                 cols.push(batch_cols[batch_idx].clone());
             } else {
                 cols.push(new_null_array(table_field.data_type(), batch_rows))
@@ -281,6 +453,16 @@ impl SchemaAdapter {
     }
 }
 
+/// The Parquet field ID of `field`, if any
+pub(crate) fn field_id(field: &Field) -> Option<i32> {
+    field
+        .metadata()
+        .as_ref()?
+        .get(PARQUET_FIELD_ID_META_KEY)?
+        .parse()
+        .ok()
+}
+
 /// A helper that projects partition columns into the file record batches.
 ///
 /// One interesting trick is the usage of a cache for the key buffers of the partition column
@@ -296,6 +478,8 @@ struct PartitionColumnProjector {
     /// schema. Sorted by index in the target schema so that we can iterate on it to
     /// insert the partition columns in the target record batch.
     projected_partition_indexes: Vec<(usize, usize)>,
+    /// All the partition columns
+    table_partition_cols: Vec<VirtualColumn>,
     /// The schema of the table once the projection was applied.
     projected_schema: SchemaRef,
 }
@@ -303,11 +487,11 @@ struct PartitionColumnProjector {
 impl PartitionColumnProjector {
     // Create a projector to insert the partitioning columns into batches read from files
     // - projected_schema: the target schema with both file and partitioning columns
-    // - table_partition_cols: all the partitioning column names
-    fn new(projected_schema: SchemaRef, table_partition_cols: &[String]) -> Self {
+    // - table_partition_cols: all the partitioning columns
+    fn new(projected_schema: SchemaRef, table_partition_cols: &[VirtualColumn]) -> Self {
         let mut idx_map = HashMap::new();
-        for (partition_idx, partition_name) in table_partition_cols.iter().enumerate() {
-            if let Ok(schema_idx) = projected_schema.index_of(partition_name) {
+        for (partition_idx, partition_col) in table_partition_cols.iter().enumerate() {
+            if let Ok(schema_idx) = projected_schema.index_of(partition_col.name()) {
                 idx_map.insert(partition_idx, schema_idx);
             }
         }
@@ -317,6 +501,7 @@ impl PartitionColumnProjector {
 
         Self {
             projected_partition_indexes,
+            table_partition_cols: table_partition_cols.to_vec(),
             key_buffer_cache: None,
             projected_schema,
         }
@@ -342,13 +527,14 @@ impl PartitionColumnProjector {
             .projected_partition_indexes
             .iter()
             .map(|(pidx, _)| {
-                create_dict_array(
+                self.table_partition_cols[*pidx].create_array(
                     &mut self.key_buffer_cache,
                     &partition_values[*pidx],
                     batch_size,
                 )
             })
-            .collect();
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
         RecordBatch::try_new(Arc::clone(&self.projected_schema), cols)
     }
 
@@ -375,11 +561,13 @@ impl PartitionColumnProjector {
         for &(pidx, sidx) in &self.projected_partition_indexes {
             cols.insert(
                 sidx,
-                create_dict_array(
-                    &mut self.key_buffer_cache,
-                    &partition_values[pidx],
-                    file_batch.num_rows(),
-                ),
+                self.table_partition_cols[pidx]
+                    .create_array(
+                        &mut self.key_buffer_cache,
+                        &partition_values[pidx],
+                        file_batch.num_rows(),
+                    )
+                    .map_err(|e| ArrowError::ExternalError(Box::new(e)))?,
             )
         }
         RecordBatch::try_new(Arc::clone(&self.projected_schema), cols)
@@ -422,10 +610,12 @@ fn create_dict_array(
 
 #[cfg(test)]
 mod tests {
+    use crate::from_slice::FromSlice;
     use crate::{
         test::{build_table_i32, columns, object_store::TestObjectStore},
         test_util::aggr_test_schema,
     };
+    use arrow::array::{Int32Array, Int64Array, Int8Array, StringArray};
 
     use super::*;
 
@@ -436,7 +626,7 @@ mod tests {
             Arc::clone(&file_schema),
             None,
             Statistics::default(),
-            vec!["date".to_owned()],
+            vec![VirtualColumn::partition("date")],
         );
 
         let (proj_schema, proj_statistics) = conf.project();
@@ -482,7 +672,7 @@ mod tests {
                 ),
                 ..Default::default()
             },
-            vec!["date".to_owned()],
+            vec![VirtualColumn::partition("date")],
         );
 
         let (proj_schema, proj_statistics) = conf.project();
@@ -512,8 +702,11 @@ mod tests {
             ("b", &vec![-2, -1, 0]),
             ("c", &vec![10, 11, 12]),
         );
-        let partition_cols =
-            vec!["year".to_owned(), "month".to_owned(), "day".to_owned()];
+        let partition_cols = vec![
+            VirtualColumn::partition("year"),
+            VirtualColumn::partition("month"),
+            VirtualColumn::partition("day"),
+        ];
         // create a projected schema
         let conf = config_for_projection(
             file_batch.schema(),
@@ -614,6 +807,73 @@ mod tests {
         crate::assert_batches_eq!(expected, &[projected_batch]);
     }
 
+    #[test]
+    fn typed_partition_column_projector() {
+        let file_batch = RecordBatch::try_from_iter(vec![(
+            "a",
+            Arc::new(Int32Array::from_slice(&[0, 1])) as ArrayRef,
+        )])
+        .unwrap();
+        let partition_cols = vec![
+            VirtualColumn::new("year", DataType::Int32),
+            VirtualColumn::new("city", DataType::Utf8),
+        ];
+        let conf = config_for_projection(
+            file_batch.schema(),
+            None,
+            Statistics::default(),
+            partition_cols.clone(),
+        );
+        let (proj_schema, _) = conf.project();
+        assert_eq!(proj_schema.field(1).data_type(), &DataType::Int32);
+        assert_eq!(proj_schema.field(2).data_type(), &DataType::Utf8);
+        let mut proj = PartitionColumnProjector::new(proj_schema, &partition_cols);
+
+        let projected_batch = proj
+            .project(
+                file_batch,
+                &[
+                    ScalarValue::Utf8(Some("2021".to_owned())),
+                    ScalarValue::Utf8(Some("Paris".to_owned())),
+                ],
+            )
+            .expect("Projection of partition columns into record batch failed");
+        let expected = vec![
+            "+---+------+-------+",
+            "| a | year | city  |",
+            "+---+------+-------+",
+            "| 0 | 2021 | Paris |",
+            "| 1 | 2021 | Paris |",
+            "+---+------+-------+",
+        ];
+        crate::assert_batches_eq!(expected, &[projected_batch]);
+
+        // the values that can't be cast to the type of the column are errors
+        let conf = config_for_projection(
+            conf.file_schema,
+            Some(vec![1]),
+            Statistics::default(),
+            partition_cols.clone(),
+        );
+        let (proj_schema, _) = conf.project();
+        let mut proj = PartitionColumnProjector::new(proj_schema, &partition_cols);
+        let err = proj
+            .project_from_size(
+                2,
+                &[
+                    ScalarValue::Utf8(Some("last".to_owned())),
+                    ScalarValue::Utf8(Some("Paris".to_owned())),
+                ],
+            )
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("The value 'last' of the column 'year' is not a valid Int32"),
+            "{}",
+            err
+        );
+    }
+
     #[test]
     fn schema_adapter_adapt_projections() {
         let table_schema = Arc::new(Schema::new(vec![
@@ -669,12 +929,65 @@ mod tests {
         assert!(mapped.is_err());
     }
 
+    fn field_with_id(name: &str, data_type: DataType, id: i32) -> Field {
+        let mut field = Field::new(name, data_type, true);
+        let metadata = [(PARQUET_FIELD_ID_META_KEY.to_owned(), id.to_string())];
+        field.set_metadata(Some(metadata.into_iter().collect()));
+        field
+    }
+
+    #[test]
+    fn schema_adapter_field_ids() -> Result<()> {
+        let table_schema = Arc::new(Schema::new(vec![
+            field_with_id("c1", DataType::Utf8, 1),
+            field_with_id("c2", DataType::Int64, 2),
+            Field::new("c3", DataType::Int8, true),
+        ]));
+
+        // c1 was renamed to c2, and the former c2 to c1
+        let file_schema = Schema::new(vec![
+            field_with_id("c3", DataType::Int8, 3),
+            field_with_id("c2", DataType::Utf8, 1),
+            field_with_id("c1", DataType::Int64, 2),
+        ]);
+
+        let by_name = SchemaAdapter::new(table_schema.clone());
+        assert!(by_name.map_projections(&file_schema, &[0, 1]).is_err());
+
+        let adapter = SchemaAdapter::new(table_schema).with_field_id_matching(true);
+        assert_eq!(
+            adapter.map_projections(&file_schema, &[0, 1, 2])?,
+            vec![1, 2, 0]
+        );
+        assert_eq!(adapter.map_column_index(0, &file_schema), Some(1));
+
+        // the reader returns the projected columns in file order
+        let batch = RecordBatch::try_new(
+            Arc::new(file_schema.clone()),
+            vec![
+                Arc::new(Int8Array::from(vec![7])),
+                Arc::new(StringArray::from(vec!["x"])),
+                Arc::new(Int64Array::from(vec![42])),
+            ],
+        )?;
+        let adapted = adapter.adapt_batch(batch, &[0, 1, 2], &file_schema)?;
+        let expected = vec![
+            "+----+----+----+",
+            "| c1 | c2 | c3 |",
+            "+----+----+----+",
+            "| x  | 42 | 7  |",
+            "+----+----+----+",
+        ];
+        crate::assert_batches_eq!(expected, &[adapted]);
+        Ok(())
+    }
+
     // sets default for configs that play no role in projections
     fn config_for_projection(
         file_schema: SchemaRef,
         projection: Option<Vec<usize>>,
         statistics: Statistics,
-        table_partition_cols: Vec<String>,
+        table_partition_cols: Vec<VirtualColumn>,
     ) -> FileScanConfig {
         FileScanConfig {
             file_schema,
@@ -684,6 +997,7 @@ mod tests {
             projection,
             statistics,
             table_partition_cols,
+            output_ordering: None,
         }
     }
 }
@@ -18,55 +18,71 @@
 //! Execution plan for reading Parquet files
 
 use fmt::Debug;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
 use std::fs;
 use std::path::Path;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver};
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::{any::Any, convert::TryInto};
 
 use arrow::{
     array::ArrayRef,
+    compute::SortOptions,
     datatypes::{Schema, SchemaRef},
     error::{ArrowError, Result as ArrowResult},
-    record_batch::RecordBatch,
+    record_batch::{RecordBatch, RecordBatchReader},
 };
 use async_trait::async_trait;
 use futures::{Stream, StreamExt, TryStreamExt};
-use log::debug;
+use log::{debug, warn};
+use parking_lot::Mutex;
 use parquet::arrow::{
     arrow_reader::ParquetRecordBatchReader, ArrowReader, ArrowWriter,
     ParquetFileArrowReader,
 };
 use parquet::file::reader::FileReader;
 use parquet::file::{
-    metadata::RowGroupMetaData, properties::WriterProperties,
-    reader::SerializedFileReader, serialized_reader::ReadOptionsBuilder,
+    metadata::{ColumnChunkMetaData, RowGroupMetaData},
+    properties::WriterProperties,
+    reader::SerializedFileReader,
+    serialized_reader::ReadOptionsBuilder,
     statistics::Statistics as ParquetStatistics,
 };
 
 use datafusion_common::Column;
 use datafusion_data_access::object_store::ObjectStore;
+use datafusion_data_access::FileMeta;
 use datafusion_expr::Expr;
 
 use crate::physical_plan::stream::RecordBatchReceiverStream;
 use crate::{
-    datasource::{file_format::parquet::ChunkObjectReader, listing::PartitionedFile},
+    datasource::{
+        checkpoint::Checkpoint,
+        file_format::parquet::{
+            encryption_error, fetch_statistics, open_file_reader, schema_with_field_ids,
+            string_max_bound, string_min_bound, ChunkObjectReader,
+        },
+        get_statistics_with_limit,
+        listing::PartitionedFile,
+    },
     error::{DataFusionError, Result},
-    execution::context::{SessionState, TaskContext},
+    execution::context::{ExecutionProps, SessionState, TaskContext},
     physical_optimizer::pruning::{PruningPredicate, PruningStatistics},
     physical_plan::{
-        expressions::PhysicalSortExpr,
-        file_format::{FileScanConfig, SchemaAdapter},
+        expressions::{self, PhysicalSortExpr},
+        file_format::{field_id, FileScanConfig, ScanErrorPolicy, SchemaAdapter},
         metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
         DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
-        SendableRecordBatchStream, Statistics,
+        SendableRecordBatchStream, Statistics, StatisticsCache,
     },
     scalar::ScalarValue,
 };
 
+use super::checkpointed_write::plan_to_files_with_checkpoint;
 use super::PartitionColumnProjector;
 
 /// Execution plan for scanning one or more Parquet partitions
@@ -79,6 +95,53 @@ pub struct ParquetExec {
     metrics: ExecutionPlanMetricsSet,
     /// Optional predicate for pruning row groups
     pruning_predicate: Option<PruningPredicate>,
+    /// What to do when a file cannot be read
+    error_policy: ScanErrorPolicy,
+    /// Resolve the columns of the files by field ID
+    match_field_ids: bool,
+    /// The length the string statistics of the files may be truncated to
+    statistics_truncate_length: Option<usize>,
+    /// The threads helping the partitions decode the columns of their files
+    decode_helpers: Option<Arc<DecodeHelpers>>,
+    /// The sort order of the rows of each partition, if known
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
+    /// The statistics fetched from the footers of the files, when they were
+    /// not collected when the table was scanned
+    fetched_statistics: StatisticsCache,
+    /// The statistics of the files fetched by the scans of the same table
+    file_statistics: Arc<FileStatisticsCache>,
+}
+
+/// The statistics fetched from the footers of Parquet files, shared by the
+/// scans of a table. The statistics of a file are fetched again when its size
+/// or its last modification time changed.
+#[derive(Debug, Default)]
+pub struct FileStatisticsCache {
+    statistics: Mutex<HashMap<String, (FileMeta, Statistics)>>,
+}
+
+impl FileStatisticsCache {
+    /// Returns the statistics of `file`, if they were fetched since it was
+    /// last modified
+    pub fn get(&self, file: &FileMeta) -> Option<Statistics> {
+        let statistics = self.statistics.lock();
+        match statistics.get(file.path()) {
+            Some((meta, statistics))
+                if meta.size() == file.size()
+                    && meta.last_modified == file.last_modified =>
+            {
+                Some(statistics.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Records the statistics fetched from the footer of `file`
+    pub fn insert(&self, file: &FileMeta, statistics: Statistics) {
+        self.statistics
+            .lock()
+            .insert(file.path().to_owned(), (file.clone(), statistics));
+    }
 }
 
 /// Stores metrics about the parquet execution for a particular parquet file
@@ -88,6 +151,10 @@ struct ParquetFileMetrics {
     pub predicate_evaluation_errors: metrics::Count,
     /// Number of row groups pruned using
     pub row_groups_pruned: metrics::Count,
+    /// Number of row groups not pruned by the pruning predicate, and thus read
+    pub row_groups_matched: metrics::Count,
+    /// Number of rows of the row groups pruned using the pruning predicate
+    pub rows_pruned: metrics::Count,
 }
 
 impl ParquetExec {
@@ -117,15 +184,131 @@ impl ParquetExec {
 
         let (projected_schema, projected_statistics) = base_config.project();
 
+        // the order recorded in the files only holds for the partitions
+        // reading a single file
+        let output_ordering = base_config.output_ordering.clone().or_else(|| {
+            if base_config.file_groups.iter().all(|files| files.len() <= 1) {
+                decode_sort_order(&base_config.file_schema, &projected_schema)
+            } else {
+                None
+            }
+        });
+
         Self {
             base_config,
             projected_schema,
             projected_statistics,
             metrics,
             pruning_predicate,
+            error_policy: ScanErrorPolicy::default(),
+            match_field_ids: false,
+            statistics_truncate_length: None,
+            decode_helpers: None,
+            output_ordering,
+            fetched_statistics: StatisticsCache::default(),
+            file_statistics: Arc::new(FileStatisticsCache::default()),
         }
     }
 
+    /// Set the policy applied when a file cannot be read. Parquet pages
+    /// cannot be skipped individually, so `ScanErrorPolicy::SkipBadRecords`
+    /// skips the whole file.
+    /// - defaults to `ScanErrorPolicy::Fail`
+    pub fn with_error_policy(mut self, error_policy: ScanErrorPolicy) -> Self {
+        self.error_policy = match error_policy {
+            ScanErrorPolicy::SkipBadRecords => ScanErrorPolicy::SkipFile,
+            policy => policy,
+        };
+        self
+    }
+
+    /// The policy applied when a file cannot be read
+    pub fn error_policy(&self) -> ScanErrorPolicy {
+        self.error_policy
+    }
+
+    /// Resolve the columns of the files by their Parquet field ID rather than
+    /// by their name. See [`ParquetFormat::with_field_id_matching`]
+    /// - defaults to false
+    ///
+    /// [`ParquetFormat::with_field_id_matching`]: crate::datasource::file_format::parquet::ParquetFormat::with_field_id_matching
+    pub fn with_field_id_matching(mut self, enable: bool) -> Self {
+        self.match_field_ids = enable;
+        self
+    }
+
+    /// Return true if the columns of the files are resolved by field ID
+    pub fn field_id_matching(&self) -> bool {
+        self.match_field_ids
+    }
+
+    /// Declare that the string statistics of the files may be truncated to
+    /// `length` bytes. See [`ParquetFormat::with_statistics_truncate_length`]
+    /// - defaults to None
+    ///
+    /// [`ParquetFormat::with_statistics_truncate_length`]: crate::datasource::file_format::parquet::ParquetFormat::with_statistics_truncate_length
+    pub fn with_statistics_truncate_length(mut self, length: Option<usize>) -> Self {
+        self.statistics_truncate_length = length;
+        self
+    }
+
+    /// The length the string statistics of the files may be truncated to
+    pub fn statistics_truncate_length(&self) -> Option<usize> {
+        self.statistics_truncate_length
+    }
+
+    /// Share the statistics fetched from the footers of the files with the
+    /// other scans using `cache`. See [`ExecutionPlan::statistics_async`]
+    /// - defaults to a cache used by this scan only
+    pub fn with_statistics_cache(mut self, cache: Arc<FileStatisticsCache>) -> Self {
+        self.file_statistics = cache;
+        self
+    }
+
+    /// Decode the columns of the files with the help of up to `threads`
+    /// threads, shared by the partitions of the scan. The columns of a file
+    /// are split into groups, decoded in parallel by the thread reading the
+    /// partition and by as many helper threads as are available. This speeds
+    /// up the scans of a few large files, which have fewer partitions than
+    /// the threads of the runtime.
+    /// - defaults to 0, each partition decoding its files on its own
+    pub fn with_decode_threads(mut self, threads: usize) -> Self {
+        self.decode_helpers = (threads > 0).then(|| {
+            Arc::new(DecodeHelpers {
+                max_threads: threads,
+                running: AtomicUsize::new(0),
+            })
+        });
+        self
+    }
+
+    /// The maximum number of threads helping the partitions decode their files
+    pub fn decode_threads(&self) -> usize {
+        self.decode_helpers
+            .as_ref()
+            .map_or(0, |helpers| helpers.max_threads)
+    }
+
+    /// Evaluate the pruning predicate with the functions and operators of the
+    /// session of `execution_props`, e.g. with the kernels registered in the
+    /// session, so that the row groups are pruned as the rows are filtered
+    pub fn with_execution_props(mut self, execution_props: &ExecutionProps) -> Self {
+        self.pruning_predicate = self.pruning_predicate.and_then(|predicate| {
+            match PruningPredicate::try_new_with_props(
+                predicate.logical_expr().clone(),
+                predicate.schema().clone(),
+                execution_props,
+            ) {
+                Ok(pruning_predicate) => Some(pruning_predicate),
+                Err(e) => {
+                    debug!("Could not create pruning predicate for: {}", e);
+                    None
+                }
+            }
+        });
+        self
+    }
+
     /// Ref to the base configs
     pub fn base_config(&self) -> &FileScanConfig {
         &self.base_config
@@ -135,6 +318,79 @@ impl ParquetExec {
     pub fn pruning_predicate(&self) -> Option<&PruningPredicate> {
         self.pruning_predicate.as_ref()
     }
+
+    /// Record in the metrics of the scan the number of files listed by the
+    /// table, and of the ones pruned before the scan, e.g. by its zone maps
+    pub fn record_listed_files(&self, files_listed: usize, files_pruned: usize) {
+        MetricBuilder::new(&self.metrics)
+            .global_counter("files_listed")
+            .add(files_listed);
+        MetricBuilder::new(&self.metrics)
+            .global_counter("files_pruned_by_listing")
+            .add(files_pruned);
+    }
+
+    /// Fetches the statistics of the files from their footers, or from the
+    /// statistics cache, and caches the projected statistics of the scan
+    async fn fetch_file_statistics(&self) -> Result<Statistics> {
+        let file_schema = self.base_config.file_schema.clone();
+        let object_store = self.base_config.object_store.clone();
+        let cache = self.file_statistics.clone();
+        let match_field_ids = self.match_field_ids;
+        let truncate_length = self.statistics_truncate_length;
+        let files: Vec<PartitionedFile> = self
+            .base_config
+            .file_groups
+            .iter()
+            .flatten()
+            .cloned()
+            .collect();
+        let fetch = move || {
+            files
+                .into_iter()
+                .map(|file| {
+                    if let Some(statistics) = cache.get(&file.file_meta) {
+                        return Ok((file, statistics));
+                    }
+                    let object_reader =
+                        object_store.file_reader(file.file_meta.sized_file.clone())?;
+                    let statistics = fetch_statistics(
+                        object_reader,
+                        file_schema.clone(),
+                        match_field_ids,
+                        truncate_length,
+                    )?;
+                    cache.insert(&file.file_meta, statistics.clone());
+                    Ok((file, statistics))
+                })
+                .collect::<Vec<Result<_>>>()
+        };
+        // Use spawn_blocking only if running from a tokio context (#2201)
+        let files = match tokio::runtime::Handle::try_current() {
+            Ok(handle) => handle.spawn_blocking(fetch).await.map_err(|e| {
+                DataFusionError::Execution(format!(
+                    "Failed to fetch the statistics of the files: {}",
+                    e
+                ))
+            })?,
+            Err(_) => fetch(),
+        };
+        let file_schema = &self.base_config.file_schema;
+        let (_, statistics) = get_statistics_with_limit(
+            futures::stream::iter(files),
+            file_schema.clone(),
+            None,
+        )
+        .await?;
+        let (_, projected_statistics) = FileScanConfig {
+            statistics,
+            ..self.base_config.clone()
+        }
+        .project();
+        Ok(self
+            .fetched_statistics
+            .get_or_compute(|| projected_statistics))
+    }
 }
 
 impl ParquetFileMetrics {
@@ -152,9 +408,19 @@ impl ParquetFileMetrics {
             .with_new_label("filename", filename.to_string())
             .counter("row_groups_pruned", partition);
 
+        let row_groups_matched = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("row_groups_matched", partition);
+
+        let rows_pruned = MetricBuilder::new(metrics)
+            .with_new_label("filename", filename.to_string())
+            .counter("rows_pruned", partition);
+
         Self {
             predicate_evaluation_errors,
             row_groups_pruned,
+            row_groups_matched,
+            rows_pruned,
         }
     }
 }
@@ -181,7 +447,7 @@ impl ExecutionPlan for ParquetExec {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        None
+        self.output_ordering.as_deref()
     }
 
     fn relies_on_input_order(&self) -> bool {
@@ -209,11 +475,25 @@ impl ExecutionPlan for ParquetExec {
             &self.base_config.table_partition_cols,
         );
 
+        let files_skipped =
+            MetricBuilder::new(&self.metrics).counter("files_skipped", partition_index);
+        let files_scanned =
+            MetricBuilder::new(&self.metrics).counter("files_scanned", partition_index);
+        let files_pruned =
+            MetricBuilder::new(&self.metrics).counter("files_pruned", partition_index);
+        let output_rows = MetricBuilder::new(&self.metrics).output_rows(partition_index);
+
         let stream = ParquetExecStream {
             error: false,
+            error_policy: self.error_policy,
+            files_skipped,
+            files_scanned,
+            files_pruned,
+            output_rows,
             partition_index,
             metrics: self.metrics.clone(),
-            object_store: self.base_config.object_store.clone(),
+            object_store: context
+                .limit_object_store(Arc::clone(&self.base_config.object_store)),
             pruning_predicate: self.pruning_predicate.clone(),
             batch_size: context.session_config().batch_size,
             schema: self.projected_schema.clone(),
@@ -222,7 +502,11 @@ impl ExecutionPlan for ParquetExec {
             reader: None,
             files: self.base_config.file_groups[partition_index].clone().into(),
             projector: partition_col_proj,
-            adapter: SchemaAdapter::new(self.base_config.file_schema.clone()),
+            adapter: SchemaAdapter::new(self.base_config.file_schema.clone())
+                .with_field_id_matching(self.match_field_ids),
+            match_field_ids: self.match_field_ids,
+            statistics_truncate_length: self.statistics_truncate_length,
+            decode_helpers: self.decode_helpers.clone(),
         };
 
         // Use spawn_blocking only if running from a tokio context (#2201)
@@ -281,7 +565,27 @@ impl ExecutionPlan for ParquetExec {
     }
 
     fn statistics(&self) -> Statistics {
-        self.projected_statistics.clone()
+        self.fetched_statistics
+            .get()
+            .unwrap_or_else(|| self.projected_statistics.clone())
+    }
+
+    async fn statistics_async(&self) -> Result<Statistics> {
+        let collected = &self.base_config.statistics;
+        if collected.num_rows.is_some() || collected.column_statistics.is_some() {
+            return Ok(self.statistics());
+        }
+        if let Some(statistics) = self.fetched_statistics.get() {
+            return Ok(statistics);
+        }
+        match self.fetch_file_statistics().await {
+            Ok(statistics) => Ok(statistics),
+            Err(e) => {
+                // the statistics are only used to optimize the plan
+                warn!("Failed to fetch the statistics of the files: {}", e);
+                Ok(Statistics::default())
+            }
+        }
     }
 }
 
@@ -290,7 +594,9 @@ impl ExecutionPlan for ParquetExec {
 /// This is a workaround for https://github.com/apache/arrow-rs/issues/1537
 enum ProjectedReader {
     Reader {
-        reader: ParquetRecordBatchReader,
+        reader: Box<dyn Iterator<Item = ArrowResult<RecordBatch>> + Send>,
+        /// Schema of the file, with the field IDs of its columns
+        file_schema: Schema,
     },
     EmptyProjection {
         remaining_rows: usize,
@@ -305,6 +611,14 @@ enum ProjectedReader {
 /// network IO)
 struct ParquetExecStream {
     error: bool,
+    error_policy: ScanErrorPolicy,
+    files_skipped: metrics::Count,
+    /// Number of files read
+    files_scanned: metrics::Count,
+    /// Number of files whose row groups were all pruned
+    files_pruned: metrics::Count,
+    /// Number of rows read from the row groups that were not pruned
+    output_rows: metrics::Count,
     partition_index: usize,
     metrics: ExecutionPlanMetricsSet,
     object_store: Arc<dyn ObjectStore>,
@@ -317,15 +631,19 @@ struct ParquetExecStream {
     files: VecDeque<PartitionedFile>,
     projector: PartitionColumnProjector,
     adapter: SchemaAdapter,
+    match_field_ids: bool,
+    statistics_truncate_length: Option<usize>,
+    decode_helpers: Option<Arc<DecodeHelpers>>,
 }
 
 impl ParquetExecStream {
-    fn create_reader(&mut self, file: &PartitionedFile) -> Result<ProjectedReader> {
-        let file_metrics = ParquetFileMetrics::new(
-            self.partition_index,
-            file.file_meta.path(),
-            &self.metrics,
-        );
+    /// Opens the reader of `file`, recording the pruning of its row groups in
+    /// `file_metrics`
+    fn open_file(
+        &self,
+        file: &PartitionedFile,
+        file_metrics: ParquetFileMetrics,
+    ) -> Result<SerializedFileReader<ChunkObjectReader>> {
         let object_reader = self
             .object_store
             .file_reader(file.file_meta.sized_file.clone())?;
@@ -335,6 +653,8 @@ impl ParquetExecStream {
             opt = opt.with_predicate(build_row_group_predicate(
                 pruning_predicate,
                 file_metrics,
+                self.match_field_ids,
+                self.statistics_truncate_length,
             ));
         }
         if let Some(range) = &file.range {
@@ -346,10 +666,24 @@ impl ParquetExecStream {
             opt = opt.with_range(range.start, range.end);
         }
 
-        let file_reader = SerializedFileReader::new_with_options(
-            ChunkObjectReader(object_reader),
-            opt.build(),
-        )?;
+        open_file_reader(object_reader, Some(opt.build()))
+    }
+
+    fn create_reader(&mut self, file: &PartitionedFile) -> Result<ProjectedReader> {
+        let file_metrics = ParquetFileMetrics::new(
+            self.partition_index,
+            file.file_meta.path(),
+            &self.metrics,
+        );
+        let file_reader = self.open_file(file, file_metrics.clone())?;
+
+        if file_metrics.row_groups_matched.value() == 0
+            && file_metrics.row_groups_pruned.value() > 0
+        {
+            self.files_pruned.add(1);
+        } else {
+            self.files_scanned.add(1);
+        }
 
         if self.projection.is_empty() {
             let remaining_rows = file_reader
@@ -366,15 +700,166 @@ impl ParquetExecStream {
         }
 
         let mut arrow_reader = ParquetFileArrowReader::new(Arc::new(file_reader));
+        let file_schema = arrow_reader.get_schema()?;
+        let metadata = arrow_reader.get_metadata();
+        let file_schema =
+            schema_with_field_ids(file_schema, metadata.file_metadata().schema_descr());
 
         let adapted_projections = self
             .adapter
-            .map_projections(&arrow_reader.get_schema()?, &self.projection)?;
+            .map_projections(&file_schema, &self.projection)?;
 
+        // the root columns of the projection, which can be decoded separately
+        let schema_descr = metadata.file_metadata().schema_descr();
+        let mut roots = adapted_projections
+            .iter()
+            .map(|leaf| schema_descr.get_column_root_idx(*leaf))
+            .collect::<Vec<_>>();
+        roots.sort_unstable();
+        roots.dedup();
+        let permits = match &self.decode_helpers {
+            Some(helpers) => helpers.reserve(roots.len().saturating_sub(1)),
+            None => vec![],
+        };
+
+        if permits.is_empty() {
+            let reader = arrow_reader
+                .get_record_reader_by_columns(adapted_projections, self.batch_size)?;
+            return Ok(ProjectedReader::Reader {
+                reader: Box::new(reader),
+                file_schema,
+            });
+        }
+
+        // contiguous groups of roots, so that the columns of the groups are
+        // in the order of the columns of the file
+        let group_len = (roots.len() + permits.len()) / (permits.len() + 1);
+        let mut groups = roots.chunks(group_len).map(|group| {
+            adapted_projections
+                .iter()
+                .copied()
+                .filter(|leaf| group.contains(&schema_descr.get_column_root_idx(*leaf)))
+                .collect::<Vec<_>>()
+        });
         let reader = arrow_reader
-            .get_record_reader_by_columns(adapted_projections, self.batch_size)?;
+            .get_record_reader_by_columns(groups.next().unwrap(), self.batch_size)?;
+        let mut fields = reader.schema().fields().clone();
+        let mut helpers = vec![];
+        for (group, permit) in groups.zip(permits) {
+            // the row groups of the file are pruned again, without recording
+            // it twice
+            let file_metrics = ParquetFileMetrics::new(
+                self.partition_index,
+                file.file_meta.path(),
+                &ExecutionPlanMetricsSet::new(),
+            );
+            let file_reader = self.open_file(file, file_metrics)?;
+            let group_reader = ParquetFileArrowReader::new(Arc::new(file_reader))
+                .get_record_reader_by_columns(group, self.batch_size)?;
+            fields.extend(group_reader.schema().fields().iter().cloned());
+            helpers.push(permit.decode(group_reader)?);
+        }
+
+        Ok(ProjectedReader::Reader {
+            reader: Box::new(ParallelReader {
+                schema: Arc::new(Schema::new(fields)),
+                reader,
+                helpers,
+            }),
+            file_schema,
+        })
+    }
+}
+
+/// The threads helping the partitions of a Parquet scan decode the columns
+/// of their files, at most `max_threads` at a time
+#[derive(Debug)]
+struct DecodeHelpers {
+    max_threads: usize,
+    running: AtomicUsize,
+}
 
-        Ok(ProjectedReader::Reader { reader })
+impl DecodeHelpers {
+    /// Reserves up to `n` of the helper threads that are not running
+    fn reserve(self: &Arc<Self>, n: usize) -> Vec<DecodePermit> {
+        let mut permits = vec![];
+        while permits.len() < n
+            && self
+                .running
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+                    (running < self.max_threads).then(|| running + 1)
+                })
+                .is_ok()
+        {
+            permits.push(DecodePermit(self.clone()));
+        }
+        permits
+    }
+}
+
+/// A reserved helper thread, released when dropped
+struct DecodePermit(Arc<DecodeHelpers>);
+
+impl DecodePermit {
+    /// Decodes the batches of `reader` on the helper thread, which decodes
+    /// at most one batch ahead of their consumer
+    fn decode(
+        self,
+        reader: ParquetRecordBatchReader,
+    ) -> Result<Receiver<ArrowResult<RecordBatch>>> {
+        let (sender, receiver) = sync_channel(1);
+        std::thread::Builder::new()
+            .name("datafusion-parquet-decode".to_owned())
+            .spawn(move || {
+                let _permit = self;
+                for batch in reader {
+                    if sender.send(batch).is_err() {
+                        // the consumer is gone
+                        break;
+                    }
+                }
+            })?;
+        Ok(receiver)
+    }
+}
+
+impl Drop for DecodePermit {
+    fn drop(&mut self) {
+        self.0.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Reads the batches of a Parquet file decoded by several readers of groups
+/// of its columns, the first one on the thread reading the partition and the
+/// others on helper threads. The readers read the same row groups in batches
+/// of the same size, whose columns are concatenated.
+struct ParallelReader {
+    /// The schema of the concatenated batches
+    schema: SchemaRef,
+    reader: ParquetRecordBatchReader,
+    helpers: Vec<Receiver<ArrowResult<RecordBatch>>>,
+}
+
+impl Iterator for ParallelReader {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut columns = match self.reader.next()? {
+            Ok(batch) => batch.columns().to_vec(),
+            Err(e) => return Some(Err(e)),
+        };
+        for helper in &self.helpers {
+            match helper.recv() {
+                Ok(Ok(batch)) => columns.extend_from_slice(batch.columns()),
+                Ok(Err(e)) => return Some(Err(e)),
+                Err(_) => {
+                    return Some(Err(ArrowError::ComputeError(
+                        "A thread decoding the Parquet file stopped early".to_owned(),
+                    )))
+                }
+            }
+        }
+        Some(RecordBatch::try_new(self.schema.clone(), columns))
     }
 }
 
@@ -394,6 +879,16 @@ impl Iterator for ParquetExecStream {
                     None => return None,
                     Some(file) => match self.create_reader(&file) {
                         Ok(reader) => self.reader.insert((reader, file)),
+                        Err(e)
+                            if skip_file_on_error(
+                                self.error_policy,
+                                &self.files_skipped,
+                                &file,
+                                &e,
+                            ) =>
+                        {
+                            continue
+                        }
                         Err(e) => {
                             self.error = true;
                             return Some(Err(ArrowError::ExternalError(Box::new(e))));
@@ -403,11 +898,14 @@ impl Iterator for ParquetExecStream {
             };
 
             let result = match reader {
-                ProjectedReader::Reader { reader } => reader.next().map(|result| {
+                ProjectedReader::Reader {
+                    reader,
+                    file_schema,
+                } => reader.next().map(|result| {
                     result
                         .and_then(|batch| {
                             self.adapter
-                                .adapt_batch(batch, &self.projection)
+                                .adapt_batch(batch, &self.projection, file_schema)
                                 .map_err(|e| ArrowError::ExternalError(Box::new(e)))
                         })
                         .and_then(|batch| {
@@ -427,7 +925,21 @@ impl Iterator for ParquetExecStream {
                 }
             };
 
+            let result = result.map(|result| {
+                result.map_err(|e| report_encryption(self.object_store.as_ref(), file, e))
+            });
             let result = match result {
+                Some(Err(e))
+                    if skip_file_on_error(
+                        self.error_policy,
+                        &self.files_skipped,
+                        file,
+                        &e,
+                    ) =>
+                {
+                    self.reader = None;
+                    continue;
+                }
                 Some(result) => result,
                 None => {
                     self.reader = None;
@@ -435,6 +947,9 @@ impl Iterator for ParquetExecStream {
                 }
             };
 
+            if let Ok(batch) = &result {
+                self.output_rows.add(batch.num_rows());
+            }
             match (&result, self.remaining_rows.as_mut()) {
                 (Ok(batch), Some(remaining_rows)) => {
                     *remaining_rows = remaining_rows.saturating_sub(batch.num_rows());
@@ -464,16 +979,95 @@ impl RecordBatchStream for ParquetExecStream {
     }
 }
 
+/// Reports the error raised while decoding `file` as not supported if the
+/// file was written with modular encryption, see [`encryption_error`]
+fn report_encryption(
+    object_store: &dyn ObjectStore,
+    file: &PartitionedFile,
+    e: ArrowError,
+) -> ArrowError {
+    let unsupported = object_store
+        .file_reader(file.file_meta.sized_file.clone())
+        .ok()
+        .and_then(|object_reader| encryption_error(object_reader.as_ref()));
+    match unsupported {
+        Some(unsupported) => ArrowError::ExternalError(Box::new(unsupported)),
+        None => e,
+    }
+}
+
+/// Returns true if the error raised while reading `file` is tolerated by
+/// the error policy, in which case the rest of the file should be skipped
+fn skip_file_on_error(
+    error_policy: ScanErrorPolicy,
+    files_skipped: &metrics::Count,
+    file: &PartitionedFile,
+    e: &dyn fmt::Display,
+) -> bool {
+    match error_policy {
+        ScanErrorPolicy::Fail => false,
+        ScanErrorPolicy::SkipFile | ScanErrorPolicy::SkipBadRecords => {
+            warn!(
+                "Skipping file {} after read error: {}",
+                file.file_meta.path(),
+                e
+            );
+            files_skipped.add(1);
+            true
+        }
+    }
+}
+
 /// Wraps parquet statistics in a way
 /// that implements [`PruningStatistics`]
 struct RowGroupPruningStatistics<'a> {
     row_group_metadata: &'a RowGroupMetaData,
     parquet_schema: &'a Schema,
+    /// Find the columns of the row group by the field ID of the fields of
+    /// `parquet_schema` rather than by name
+    match_field_ids: bool,
+    /// The length the string statistics may be truncated to
+    statistics_truncate_length: Option<usize>,
+}
+
+impl<'a> RowGroupPruningStatistics<'a> {
+    /// The column chunk of the row group holding `column`, if any
+    fn column_chunk(&self, column: &Column) -> Option<&'a ColumnChunkMetaData> {
+        let field_id = self
+            .parquet_schema
+            .field_with_name(&column.name)
+            .ok()
+            .and_then(field_id)
+            .filter(|_| self.match_field_ids);
+        self.row_group_metadata
+            .columns()
+            .iter()
+            .find(|c| match field_id {
+                Some(id) => {
+                    let info = c.column_descr().self_type().get_basic_info();
+                    info.has_id() && info.id() == id
+                }
+                None => c.column_descr().name() == column.name,
+            })
+    }
 }
 
-/// Extract the min/max statistics from a `ParquetStatistics` object
+/// The min of the values of a string column bounded by its min statistic
+fn string_min_value(bytes: &[u8], truncate_length: Option<usize>) -> Option<String> {
+    Some(string_min_bound(bytes, truncate_length).0)
+}
+
+/// The max of the values of a string column bounded by its max statistic,
+/// None if they are not bounded
+fn string_max_value(bytes: &[u8], truncate_length: Option<usize>) -> Option<String> {
+    string_max_bound(bytes, truncate_length).0
+}
+
+/// Extract the min/max statistics from a `ParquetStatistics` object, the
+/// possibly truncated string statistics being turned into bounds of the values
+/// by `string_func`
 macro_rules! get_statistic {
-    ($column_statistics:expr, $func:ident, $bytes_func:ident) => {{
+    ($column_statistics:expr, $func:ident, $bytes_func:ident, $string_func:ident, $truncate_length:expr) => {{
         if !$column_statistics.has_min_max_set() {
             return None;
         }
@@ -485,12 +1079,10 @@ macro_rules! get_statistic {
             ParquetStatistics::Int96(_) => None,
             ParquetStatistics::Float(s) => Some(ScalarValue::Float32(Some(*s.$func()))),
             ParquetStatistics::Double(s) => Some(ScalarValue::Float64(Some(*s.$func()))),
-            ParquetStatistics::ByteArray(s) => {
-                let s = std::str::from_utf8(s.$bytes_func())
-                    .map(|s| s.to_string())
-                    .ok();
-                Some(ScalarValue::Utf8(s))
-            }
+            ParquetStatistics::ByteArray(s) => Some(ScalarValue::Utf8($string_func(
+                s.$bytes_func(),
+                $truncate_length,
+            ))),
             // type not supported yet
             ParquetStatistics::FixedLenByteArray(_) => None,
         }
@@ -499,7 +1091,7 @@ macro_rules! get_statistic {
 
 // Extract the min or max value calling `func` or `bytes_func` on the ParquetStatistics as appropriate
 macro_rules! get_min_max_values {
-    ($self:expr, $column:expr, $func:ident, $bytes_func:ident) => {{
+    ($self:expr, $column:expr, $func:ident, $bytes_func:ident, $string_func:ident) => {{
         let (_column_index, field) =
             if let Some((v, f)) = $self.parquet_schema.column_with_name(&$column.name) {
                 (v, f)
@@ -512,12 +1104,17 @@ macro_rules! get_min_max_values {
         // The result may be None, because DataFusion doesn't have support for ScalarValues of the column type
         let null_scalar: ScalarValue = data_type.try_into().ok()?;
 
-        $self.row_group_metadata
-            .columns()
-            .iter()
-            .find(|c| c.column_descr().name() == &$column.name)
+        $self.column_chunk($column)
             .and_then(|c| c.statistics())
-            .map(|stats| get_statistic!(stats, $func, $bytes_func))
+            .map(|stats| {
+                get_statistic!(
+                    stats,
+                    $func,
+                    $bytes_func,
+                    $string_func,
+                    $self.statistics_truncate_length
+                )
+            })
             .flatten()
             // column either didn't have statistics at all or didn't have min/max values
             .or_else(|| Some(null_scalar.clone()))
@@ -528,18 +1125,11 @@ macro_rules! get_min_max_values {
 // Extract the null count value on the ParquetStatistics
 macro_rules! get_null_count_values {
     ($self:expr, $column:expr) => {{
-        let value = ScalarValue::UInt64(
-            if let Some(col) = $self
-                .row_group_metadata
-                .columns()
-                .iter()
-                .find(|c| c.column_descr().name() == &$column.name)
-            {
-                col.statistics().map(|s| s.null_count())
-            } else {
-                Some($self.row_group_metadata.num_rows() as u64)
-            },
-        );
+        let value = ScalarValue::UInt64(if let Some(col) = $self.column_chunk($column) {
+            col.statistics().map(|s| s.null_count())
+        } else {
+            Some($self.row_group_metadata.num_rows() as u64)
+        });
 
         Some(value.to_array())
     }};
@@ -547,11 +1137,11 @@ macro_rules! get_null_count_values {
 
 impl<'a> PruningStatistics for RowGroupPruningStatistics<'a> {
     fn min_values(&self, column: &Column) -> Option<ArrayRef> {
-        get_min_max_values!(self, column, min, min_bytes)
+        get_min_max_values!(self, column, min, min_bytes, string_min_value)
     }
 
     fn max_values(&self, column: &Column) -> Option<ArrayRef> {
-        get_min_max_values!(self, column, max, max_bytes)
+        get_min_max_values!(self, column, max, max_bytes, string_max_value)
     }
 
     fn num_containers(&self) -> usize {
@@ -566,6 +1156,8 @@ impl<'a> PruningStatistics for RowGroupPruningStatistics<'a> {
 fn build_row_group_predicate(
     pruning_predicate: &PruningPredicate,
     metrics: ParquetFileMetrics,
+    match_field_ids: bool,
+    statistics_truncate_length: Option<usize>,
 ) -> Box<dyn FnMut(&RowGroupMetaData, usize) -> bool> {
     let pruning_predicate = pruning_predicate.clone();
     Box::new(
@@ -574,6 +1166,8 @@ fn build_row_group_predicate(
             let pruning_stats = RowGroupPruningStatistics {
                 row_group_metadata,
                 parquet_schema,
+                match_field_ids,
+                statistics_truncate_length,
             };
             let predicate_values = pruning_predicate.prune(&pruning_stats);
             match predicate_values {
@@ -581,6 +1175,12 @@ fn build_row_group_predicate(
                     // NB: false means don't scan row group
                     let num_pruned = values.iter().filter(|&v| !*v).count();
                     metrics.row_groups_pruned.add(num_pruned);
+                    metrics.row_groups_matched.add(values.len() - num_pruned);
+                    if num_pruned > 0 {
+                        metrics
+                            .rows_pruned
+                            .add(row_group_metadata.num_rows() as usize);
+                    }
                     values[0]
                 }
                 // stats filter array could not be built
@@ -588,6 +1188,7 @@ fn build_row_group_predicate(
                 Err(e) => {
                     debug!("Error evaluating row group predicate values {}", e);
                     metrics.predicate_evaluation_errors.add(1);
+                    metrics.row_groups_matched.add(1);
                     true
                 }
             }
@@ -595,7 +1196,67 @@ fn build_row_group_predicate(
     )
 }
 
+/// Key of the Arrow schema metadata recording the sort order of the rows of
+/// the Parquet files written by `SessionContext::write_parquet` and
+/// `DataFrame::write_parquet`, so that the files read back do not need to be
+/// sorted again. The value is a JSON array of the sort
+/// columns, e.g. `[{"column":"a","descending":false,"nulls_first":true}]`.
+pub const SORT_ORDER_METADATA_KEY: &str = "datafusion.sort_order";
+
+/// Encodes the sort order `ordering` of rows of the given `schema`, if it
+/// only sorts columns of the schema
+fn encode_sort_order(ordering: &[PhysicalSortExpr], schema: &Schema) -> Option<String> {
+    let columns = ordering
+        .iter()
+        .map(|sort_expr| {
+            let column = sort_expr
+                .expr
+                .as_any()
+                .downcast_ref::<expressions::Column>()?;
+            let field = schema.fields().get(column.index())?;
+            if field.name() != column.name() {
+                return None;
+            }
+            Some(serde_json::json!({
+                "column": column.name(),
+                "descending": sort_expr.options.descending,
+                "nulls_first": sort_expr.options.nulls_first,
+            }))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(serde_json::Value::Array(columns).to_string())
+}
+
+/// Decodes the sort order recorded in the metadata of `file_schema`, as sort
+/// expressions on the columns of `projected_schema`. The order is truncated
+/// before its first column that is not projected.
+fn decode_sort_order(
+    file_schema: &Schema,
+    projected_schema: &Schema,
+) -> Option<Vec<PhysicalSortExpr>> {
+    let sort_order = file_schema.metadata().get(SORT_ORDER_METADATA_KEY)?;
+    let columns: serde_json::Value = serde_json::from_str(sort_order).ok()?;
+    let ordering = columns
+        .as_array()?
+        .iter()
+        .map_while(|column| {
+            let name = column.get("column")?.as_str()?;
+            let index = projected_schema.index_of(name).ok()?;
+            Some(PhysicalSortExpr {
+                expr: Arc::new(expressions::Column::new(name, index)),
+                options: SortOptions {
+                    descending: column.get("descending")?.as_bool()?,
+                    nulls_first: column.get("nulls_first")?.as_bool()?,
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+    (!ordering.is_empty()).then(|| ordering)
+}
+
 /// Executes a query and writes the results to a partitioned Parquet file.
+/// The sort order of the partitions of the query, if any, is recorded in
+/// the metadata of the files, see [`SORT_ORDER_METADATA_KEY`].
 pub async fn plan_to_parquet(
     state: &SessionState,
     plan: Arc<dyn ExecutionPlan>,
@@ -603,6 +1264,7 @@ pub async fn plan_to_parquet(
     writer_properties: Option<WriterProperties>,
 ) -> Result<()> {
     let path = path.as_ref();
+    let schema = schema_with_sort_order(plan.as_ref());
     // create directory to contain the Parquet files (one per partition)
     let fs_path = Path::new(path);
     match fs::create_dir(fs_path) {
@@ -615,15 +1277,23 @@ pub async fn plan_to_parquet(
                 let file = fs::File::create(path)?;
                 let mut writer = ArrowWriter::try_new(
                     file.try_clone().unwrap(),
-                    plan.schema(),
+                    schema.clone(),
                     writer_properties.clone(),
                 )?;
                 let task_ctx = Arc::new(TaskContext::from(state));
                 let stream = plan.execute(i, task_ctx).await?;
+                let schema = schema.clone();
                 let handle: tokio::task::JoinHandle<Result<()>> =
                     tokio::task::spawn(async move {
                         stream
-                            .map(|batch| writer.write(&batch?))
+                            .map(|batch| {
+                                // the batches must have the metadata of the writer
+                                let batch = RecordBatch::try_new(
+                                    schema.clone(),
+                                    batch?.columns().to_vec(),
+                                )?;
+                                writer.write(&batch)
+                            })
                             .try_collect()
                             .await
                             .map_err(DataFusionError::from)?;
@@ -641,10 +1311,59 @@ pub async fn plan_to_parquet(
     }
 }
 
+/// The schema of the Parquet files written from `plan`, recording the
+/// ordering of its output in the metadata, if any
+pub(crate) fn schema_with_sort_order(plan: &dyn ExecutionPlan) -> SchemaRef {
+    let schema = plan.schema();
+    match plan
+        .output_ordering()
+        .and_then(|ordering| encode_sort_order(ordering, &schema))
+    {
+        Some(sort_order) => {
+            let mut metadata = schema.metadata().clone();
+            metadata.insert(SORT_ORDER_METADATA_KEY.to_owned(), sort_order);
+            Arc::new(Schema::new_with_metadata(schema.fields().clone(), metadata))
+        }
+        None => schema,
+    }
+}
+
+/// Executes a query and writes the results to a partitioned Parquet file,
+/// recording the written files in `checkpoint`
+pub async fn plan_to_parquet_with_checkpoint(
+    state: &SessionState,
+    plan: Arc<dyn ExecutionPlan>,
+    path: impl AsRef<str>,
+    writer_properties: Option<WriterProperties>,
+    checkpoint: Arc<dyn Checkpoint>,
+) -> Result<()> {
+    let schema = plan.schema();
+    plan_to_files_with_checkpoint(
+        state,
+        plan,
+        path.as_ref(),
+        "parquet",
+        checkpoint,
+        |file, stream| {
+            let writer =
+                ArrowWriter::try_new(file, schema.clone(), writer_properties.clone());
+            async move {
+                let mut writer = writer?;
+                stream
+                    .map(|batch| writer.write(&batch?))
+                    .try_collect::<()>()
+                    .await?;
+                writer.close().map_err(DataFusionError::from).map(|_| ())
+            }
+        },
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        assert_batches_sorted_eq, assert_contains,
+        assert_batches_eq, assert_batches_sorted_eq, assert_contains,
         datafusion_data_access::{
             object_store::local::{local_object_reader_stream, LocalFileSystem},
             FileMeta, SizedFile,
@@ -653,16 +1372,19 @@ mod tests {
             file_format::{parquet::ParquetFormat, FileFormat},
             listing::local_unpartitioned_file,
         },
-        physical_plan::collect,
+        physical_plan::{collect, displayable},
     };
 
     use super::*;
     use crate::datasource::listing::FileRange;
     use crate::execution::options::CsvReadOptions;
+    use crate::physical_plan::file_format::VirtualColumn;
+    use crate::physical_plan::limit::GlobalLimitExec;
     use crate::prelude::{ParquetReadOptions, SessionConfig, SessionContext};
     use arrow::array::Float32Array;
+    use arrow::util::pretty::pretty_format_batches;
     use arrow::{
-        array::{Int64Array, Int8Array, StringArray},
+        array::{Int64Array, Int8Array, StringArray, UInt64Array},
         datatypes::{DataType, Field},
     };
     use datafusion_data_access::object_store::local;
@@ -738,6 +1460,7 @@ mod tests {
                 projection,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             predicate,
         );
@@ -769,6 +1492,82 @@ mod tests {
         )
     }
 
+    #[tokio::test]
+    async fn parquet_exec_decode_threads() -> Result<()> {
+        let c1: ArrayRef = Arc::new(Int64Array::from((0..10).collect::<Vec<i64>>()));
+        let c2: ArrayRef = Arc::new(StringArray::from(
+            (0..10).map(|i| format!("s{}", i)).collect::<Vec<_>>(),
+        ));
+        let c3: ArrayRef = Arc::new(Int8Array::from(
+            (0..10)
+                .map(|i| (i % 3 == 0).then(|| i as i8))
+                .collect::<Vec<_>>(),
+        ));
+        let batch = create_batch(vec![("c1", c1), ("c2", c2), ("c3", c3)]);
+
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("file.parquet");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(3)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path)?, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let path = path.to_string_lossy().to_string();
+        let file_schema = ParquetFormat::default()
+            .infer_schema(local_object_reader_stream(vec![path.clone()]))
+            .await?;
+        // the first row group is pruned
+        let predicate = col("c1").gt_eq(lit(5_i64));
+        let exec = ParquetExec::new(
+            FileScanConfig {
+                object_store: Arc::new(LocalFileSystem {}),
+                file_groups: vec![vec![local_unpartitioned_file(path)]],
+                file_schema,
+                statistics: Statistics::default(),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: None,
+            },
+            Some(predicate),
+        );
+        let config = SessionConfig::new().with_batch_size(4);
+        let task_ctx = SessionContext::with_config(config).task_ctx();
+        let expected = collect(Arc::new(exec.clone()), task_ctx.clone()).await?;
+
+        // a single helper thread decodes the last of the 3 columns
+        let exec = exec.with_decode_threads(1);
+        assert_eq!(exec.decode_threads(), 1);
+        let batches = collect(Arc::new(exec), task_ctx).await?;
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![4, 3]
+        );
+        assert_eq!(
+            pretty_format_batches(&batches)?.to_string(),
+            pretty_format_batches(&expected)?.to_string()
+        );
+        let expected = vec![
+            "+----+----+----+",
+            "| c1 | c2 | c3 |",
+            "+----+----+----+",
+            "| 3  | s3 | 3  |",
+            "| 4  | s4 |    |",
+            "| 5  | s5 |    |",
+            "| 6  | s6 | 6  |",
+            "| 7  | s7 |    |",
+            "| 8  | s8 |    |",
+            "| 9  | s9 | 9  |",
+            "+----+----+----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn evolved_schema() {
         let c1: ArrayRef =
@@ -1081,6 +1880,7 @@ mod tests {
                 projection: Some(vec![0, 1, 2]),
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             None,
         );
@@ -1109,6 +1909,98 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn parquet_exec_fetch_statistics() -> Result<()> {
+        let testdata = crate::test_util::parquet_test_data();
+        let filename = format!("{}/alltypes_plain.parquet", testdata);
+        let parquet_exec = Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store: Arc::new(LocalFileSystem {}),
+                file_groups: vec![vec![local_unpartitioned_file(filename.clone())]],
+                file_schema: ParquetFormat::default()
+                    .infer_schema(local_object_reader_stream(vec![filename.clone()]))
+                    .await?,
+                statistics: Statistics::default(),
+                projection: Some(vec![0, 1, 2]),
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: None,
+            },
+            None,
+        ));
+        // the statistics were not collected
+        assert_eq!(parquet_exec.statistics(), Statistics::default());
+
+        // the statistics of the parents are computed from the fetched ones
+        let limit = GlobalLimitExec::new(parquet_exec.clone(), 5);
+        let statistics = limit.statistics_async().await?;
+        assert_eq!(statistics.num_rows, Some(5));
+        assert_eq!(limit.statistics(), statistics);
+
+        let statistics = parquet_exec.statistics();
+        assert_eq!(statistics.num_rows, Some(8));
+        assert_eq!(
+            statistics.column_statistics.map(|stats| stats.len()),
+            Some(3)
+        );
+        assert_eq!(
+            parquet_exec.statistics_async().await?,
+            parquet_exec.statistics()
+        );
+
+        // the statistics of a file are fetched again once it changed
+        use chrono::{TimeZone, Utc};
+        let cache = parquet_exec.file_statistics.clone();
+        let mut file = local_unpartitioned_file(filename);
+        assert!(cache.get(&file).is_some());
+        file.last_modified = Some(Utc.timestamp(0, 0));
+        assert!(cache.get(&file).is_none());
+        file.sized_file.size += 1;
+        cache.insert(&file, Statistics::default());
+        assert_eq!(cache.get(&file), Some(Statistics::default()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn parquet_exec_fetch_statistics_error() -> Result<()> {
+        let testdata = crate::test_util::parquet_test_data();
+        let filename = format!("{}/alltypes_plain.parquet", testdata);
+        let missing_file = PartitionedFile {
+            file_meta: FileMeta {
+                sized_file: SizedFile {
+                    size: 1337,
+                    path: "missing".into(),
+                },
+                last_modified: None,
+            },
+            partition_values: vec![],
+            range: None,
+        };
+        let parquet_exec = ParquetExec::new(
+            FileScanConfig {
+                object_store: Arc::new(LocalFileSystem {}),
+                file_groups: vec![vec![missing_file]],
+                file_schema: ParquetFormat::default()
+                    .infer_schema(local_object_reader_stream(vec![filename]))
+                    .await?,
+                statistics: Statistics::default(),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: None,
+            },
+            None,
+        );
+
+        // the statistics of the missing file are unknown
+        assert_eq!(
+            parquet_exec.statistics_async().await?,
+            Statistics::default()
+        );
+        assert_eq!(parquet_exec.statistics(), Statistics::default());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn parquet_exec_with_range() -> Result<()> {
         fn file_range(file: String, start: i64, end: i64) -> PartitionedFile {
@@ -1134,6 +2026,7 @@ mod tests {
                     projection: None,
                     limit: None,
                     table_partition_cols: vec![],
+                    output_ordering: None,
                 },
                 None,
             );
@@ -1208,10 +2101,11 @@ mod tests {
                 projection: Some(vec![0, 1, 2, 12]),
                 limit: None,
                 table_partition_cols: vec![
-                    "year".to_owned(),
-                    "month".to_owned(),
-                    "day".to_owned(),
+                    VirtualColumn::partition("year"),
+                    VirtualColumn::partition("month"),
+                    VirtualColumn::partition("day"),
                 ],
+                output_ordering: None,
             },
             None,
         );
@@ -1270,6 +2164,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             None,
         );
@@ -1286,6 +2181,53 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn parquet_exec_skip_file_on_error() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let testdata = crate::test_util::parquet_test_data();
+        let filename = format!("{}/alltypes_plain.parquet", testdata);
+        let invalid_file = PartitionedFile {
+            file_meta: FileMeta {
+                sized_file: SizedFile {
+                    size: 1337,
+                    path: "invalid".into(),
+                },
+                last_modified: None,
+            },
+            partition_values: vec![],
+            range: None,
+        };
+        let valid_file = local_unpartitioned_file(filename.clone());
+
+        let parquet_exec = ParquetExec::new(
+            FileScanConfig {
+                object_store: Arc::new(LocalFileSystem {}),
+                file_groups: vec![vec![invalid_file, valid_file]],
+                file_schema: ParquetFormat::default()
+                    .infer_schema(local_object_reader_stream(vec![filename]))
+                    .await?,
+                statistics: Statistics::default(),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: None,
+            },
+            None,
+        )
+        .with_error_policy(ScanErrorPolicy::SkipFile);
+
+        let batches = collect(Arc::new(parquet_exec.clone()), task_ctx).await?;
+        // only the rows of the valid file are returned
+        let row_count: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(row_count, 8);
+
+        let metrics = parquet_exec.metrics().unwrap();
+        assert_eq!(metrics.sum_by_name("files_skipped").unwrap().as_usize(), 1);
+
+        Ok(())
+    }
+
     fn parquet_file_metrics() -> ParquetFileMetrics {
         let metrics = Arc::new(ExecutionPlanMetricsSet::new());
         ParquetFileMetrics::new(0, "file.parquet", &metrics)
@@ -1309,8 +2251,12 @@ mod tests {
             vec![ParquetStatistics::int32(Some(11), Some(20), None, 0, false)],
         );
         let row_group_metadata = vec![rgm1, rgm2];
-        let mut row_group_predicate =
-            build_row_group_predicate(&pruning_predicate, parquet_file_metrics());
+        let mut row_group_predicate = build_row_group_predicate(
+            &pruning_predicate,
+            parquet_file_metrics(),
+            false,
+            None,
+        );
         let row_group_filter = row_group_metadata
             .iter()
             .enumerate()
@@ -1339,8 +2285,12 @@ mod tests {
             vec![ParquetStatistics::int32(Some(11), Some(20), None, 0, false)],
         );
         let row_group_metadata = vec![rgm1, rgm2];
-        let mut row_group_predicate =
-            build_row_group_predicate(&pruning_predicate, parquet_file_metrics());
+        let mut row_group_predicate = build_row_group_predicate(
+            &pruning_predicate,
+            parquet_file_metrics(),
+            false,
+            None,
+        );
         let row_group_filter = row_group_metadata
             .iter()
             .enumerate()
@@ -1384,8 +2334,12 @@ mod tests {
             ],
         );
         let row_group_metadata = vec![rgm1, rgm2];
-        let mut row_group_predicate =
-            build_row_group_predicate(&pruning_predicate, parquet_file_metrics());
+        let mut row_group_predicate = build_row_group_predicate(
+            &pruning_predicate,
+            parquet_file_metrics(),
+            false,
+            None,
+        );
         let row_group_filter = row_group_metadata
             .iter()
             .enumerate()
@@ -1399,8 +2353,12 @@ mod tests {
         // this bypasses the entire predicate expression and no row groups are filtered out
         let expr = col("c1").gt(lit(15)).or(col("c2").modulus(lit(2)));
         let pruning_predicate = PruningPredicate::try_new(expr, schema)?;
-        let mut row_group_predicate =
-            build_row_group_predicate(&pruning_predicate, parquet_file_metrics());
+        let mut row_group_predicate = build_row_group_predicate(
+            &pruning_predicate,
+            parquet_file_metrics(),
+            false,
+            None,
+        );
         let row_group_filter = row_group_metadata
             .iter()
             .enumerate()
@@ -1411,6 +2369,59 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn row_group_pruning_predicate_truncated_strings() -> Result<()> {
+        use parquet::data_type::ByteArray;
+        let expr = col("c1").eq(lit("apricot"));
+        let schema = Schema::new(vec![Field::new("c1", DataType::Utf8, false)]);
+        let pruning_predicate = PruningPredicate::try_new(expr, Arc::new(schema))?;
+
+        let schema_descr = get_test_schema_descr(vec![("c1", PhysicalType::BYTE_ARRAY)]);
+        let string_statistics = |min: &str, max: &str| {
+            ParquetStatistics::byte_array(
+                Some(ByteArray::from(min)),
+                Some(ByteArray::from(max)),
+                None,
+                0,
+                false,
+            )
+        };
+        let row_group_metadata = vec![
+            get_row_group_meta_data(&schema_descr, vec![string_statistics("aa", "ap")]),
+            get_row_group_meta_data(&schema_descr, vec![string_statistics("b", "c")]),
+        ];
+
+        let mut row_group_predicate = build_row_group_predicate(
+            &pruning_predicate,
+            parquet_file_metrics(),
+            false,
+            None,
+        );
+        let row_group_filter = row_group_metadata
+            .iter()
+            .enumerate()
+            .map(|(i, g)| row_group_predicate(g, i))
+            .collect::<Vec<_>>();
+        assert_eq!(row_group_filter, vec![false, false]);
+
+        // "ap" may be the truncated prefix of a max such as "apricot", so the
+        // values of the first row group are only known to be below "aq"
+        let mut row_group_predicate = build_row_group_predicate(
+            &pruning_predicate,
+            parquet_file_metrics(),
+            false,
+            Some(2),
+        );
+        let row_group_filter = row_group_metadata
+            .iter()
+            .enumerate()
+            .map(|(i, g)| row_group_predicate(g, i))
+            .collect::<Vec<_>>();
+        assert_eq!(row_group_filter, vec![true, false]);
+
+        Ok(())
+    }
+
     fn gen_row_group_meta_data_for_pruning_predicate() -> Vec<RowGroupMetaData> {
         let schema_descr = get_test_schema_descr(vec![
             ("c1", PhysicalType::INT32),
@@ -1445,8 +2456,12 @@ mod tests {
         let pruning_predicate = PruningPredicate::try_new(expr, schema)?;
         let row_group_metadata = gen_row_group_meta_data_for_pruning_predicate();
 
-        let mut row_group_predicate =
-            build_row_group_predicate(&pruning_predicate, parquet_file_metrics());
+        let mut row_group_predicate = build_row_group_predicate(
+            &pruning_predicate,
+            parquet_file_metrics(),
+            false,
+            None,
+        );
         let row_group_filter = row_group_metadata
             .iter()
             .enumerate()
@@ -1474,8 +2489,12 @@ mod tests {
         let pruning_predicate = PruningPredicate::try_new(expr, schema)?;
         let row_group_metadata = gen_row_group_meta_data_for_pruning_predicate();
 
-        let mut row_group_predicate =
-            build_row_group_predicate(&pruning_predicate, parquet_file_metrics());
+        let mut row_group_predicate = build_row_group_predicate(
+            &pruning_predicate,
+            parquet_file_metrics(),
+            false,
+            None,
+        );
         let row_group_filter = row_group_metadata
             .iter()
             .enumerate()
@@ -1621,4 +2640,56 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn write_parquet_sort_order() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let ctx =
+            SessionContext::with_config(SessionConfig::new().with_target_partitions(8));
+        let schema = populate_csv_partitions(&tmp_dir, 4, ".csv")?;
+        ctx.register_csv(
+            "test",
+            tmp_dir.path().to_str().unwrap(),
+            CsvReadOptions::new().schema(&schema),
+        )
+        .await?;
+
+        // the sorted results are written to a single file
+        let out_dir = tmp_dir.as_ref().to_str().unwrap().to_string() + "/out";
+        let df = ctx
+            .sql("SELECT c1, c2 FROM test ORDER BY c2 DESC, c1")
+            .await?;
+        df.write_parquet(&out_dir, None).await?;
+
+        let ctx = SessionContext::new();
+        ctx.register_parquet("sorted", &out_dir, ParquetReadOptions::default())
+            .await?;
+
+        // the files are known to be sorted by c2
+        let df = ctx
+            .sql("SELECT c2, c1 FROM sorted ORDER BY c2 DESC")
+            .await?;
+        let plan = df.create_physical_plan().await?;
+        let formatted = displayable(plan.as_ref()).indent().to_string();
+        assert!(!formatted.contains("SortExec"), "{}", formatted);
+
+        let batches = collect(plan, ctx.task_ctx()).await?;
+        let c2 = batches
+            .iter()
+            .flat_map(|batch| {
+                let column = batch.column(0);
+                let column = column.as_any().downcast_ref::<UInt64Array>().unwrap();
+                column.iter().flatten().collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(c2.len(), 44);
+        assert!(c2.windows(2).all(|pair| pair[0] >= pair[1]));
+
+        // but not by c1
+        let df = ctx.sql("SELECT c1, c2 FROM sorted ORDER BY c1").await?;
+        let plan = df.create_physical_plan().await?;
+        let formatted = displayable(plan.as_ref()).indent().to_string();
+        assert!(formatted.contains("SortExec"), "{}", formatted);
+        Ok(())
+    }
 }
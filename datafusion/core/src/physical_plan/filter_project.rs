@@ -0,0 +1,377 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! FilterProjectExec filters the rows of its input with a predicate and
+//! projects the rows kept, as a [`ProjectionExec`] above a [`FilterExec`]
+//! would, in a single pass over each batch.
+//!
+//! [`FilterExec`]: super::filter::FilterExec
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::expressions::{Column, PhysicalSortExpr};
+use super::projection::ProjectionExec;
+use super::{RecordBatchStream, SendableRecordBatchStream, Statistics};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::{
+    metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+    DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr,
+};
+use arrow::array::BooleanArray;
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+use log::debug;
+
+use crate::execution::context::TaskContext;
+use futures::stream::{Stream, StreamExt};
+
+/// FilterProjectExec filters the rows of its input with a boolean predicate
+/// and evaluates the projection expressions on the rows kept, fusing a
+/// [`ProjectionExec`] and the [`FilterExec`] below it.
+///
+/// When the projection expressions are all columns, the projected columns
+/// are filtered directly rather than all the columns of the input.
+///
+/// [`FilterExec`]: super::filter::FilterExec
+#[derive(Debug)]
+pub struct FilterProjectExec {
+    /// The expression to filter on. This expression must evaluate to a boolean value.
+    predicate: Arc<dyn PhysicalExpr>,
+    /// The projection of the input, giving the schema and the order of the
+    /// output
+    projection: ProjectionExec,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl FilterProjectExec {
+    /// Create a FilterProjectExec filtering `input` with `predicate` and
+    /// projecting the rows kept on `expr`
+    pub fn try_new(
+        predicate: Arc<dyn PhysicalExpr>,
+        expr: Vec<(Arc<dyn PhysicalExpr>, String)>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        match predicate.data_type(input.schema().as_ref())? {
+            DataType::Boolean => Ok(Self {
+                predicate,
+                projection: ProjectionExec::try_new(expr, input)?,
+                metrics: ExecutionPlanMetricsSet::new(),
+            }),
+            other => Err(DataFusionError::Plan(format!(
+                "Filter predicate must return boolean values, not {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// The expression to filter on. This expression must evaluate to a boolean value.
+    pub fn predicate(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.predicate
+    }
+
+    /// The projection expressions stored as tuples of (expression, output column name)
+    pub fn expr(&self) -> &[(Arc<dyn PhysicalExpr>, String)] {
+        self.projection.expr()
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        self.projection.input()
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for FilterProjectExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.projection.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input().clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        self.input().output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.projection.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> bool {
+        // tell optimizer this operator doesn't reorder its input
+        true
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(FilterProjectExec::try_new(
+            self.predicate.clone(),
+            self.expr().to_vec(),
+            children[0].clone(),
+        )?))
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        debug!("Start FilterProjectExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+        let expr = self
+            .expr()
+            .iter()
+            .map(|(e, _)| e.clone())
+            .collect::<Vec<_>>();
+        // the projected columns are filtered directly if the projection
+        // expressions are all columns
+        let projected_columns = expr
+            .iter()
+            .map(|e| e.as_any().downcast_ref::<Column>().map(|c| c.index()))
+            .collect();
+        Ok(Box::pin(FilterProjectStream {
+            schema: self.schema(),
+            predicate: self.predicate.clone(),
+            expr,
+            projected_columns,
+            input: self.input().execute(partition, context).await?,
+            baseline_metrics: BaselineMetrics::new(&self.metrics, partition),
+        }))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                let expr: Vec<String> = self
+                    .expr()
+                    .iter()
+                    .map(|(e, alias)| {
+                        let e = e.to_string();
+                        if &e != alias {
+                            format!("{} as {}", e, alias)
+                        } else {
+                            e
+                        }
+                    })
+                    .collect();
+
+                write!(
+                    f,
+                    "FilterProjectExec: predicate={}, expr=[{}]",
+                    self.predicate,
+                    expr.join(", ")
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    /// The output statistics of a filtering operation are unknown
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Filters and projects the batches of the input
+struct FilterProjectStream {
+    /// Output schema, the one of the projection
+    schema: SchemaRef,
+    /// The expression to filter on. This expression must evaluate to a boolean value.
+    predicate: Arc<dyn PhysicalExpr>,
+    /// The projection expressions
+    expr: Vec<Arc<dyn PhysicalExpr>>,
+    /// The indices of the projected columns, if the projection expressions
+    /// are all columns
+    projected_columns: Option<Vec<usize>>,
+    /// The input partition to filter.
+    input: SendableRecordBatchStream,
+    /// runtime metrics recording
+    baseline_metrics: BaselineMetrics,
+}
+
+impl FilterProjectStream {
+    fn filter_project(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let mask = self.predicate.evaluate(batch)?.into_array(batch.num_rows());
+        let mask = mask
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "Filter predicate evaluated to non-boolean value".to_string(),
+                )
+            })?;
+        match &self.projected_columns {
+            Some(columns) => {
+                let columns = columns.iter().map(|i| batch.column(*i).clone()).collect();
+                let projected = RecordBatch::try_new(self.schema.clone(), columns)?;
+                Ok(filter_record_batch(&projected, mask)?)
+            }
+            None => {
+                let filtered = filter_record_batch(batch, mask)?;
+                let columns = self
+                    .expr
+                    .iter()
+                    .map(|e| Ok(e.evaluate(&filtered)?.into_array(filtered.num_rows())))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(RecordBatch::try_new(self.schema.clone(), columns)?)
+            }
+        }
+    }
+}
+
+impl Stream for FilterProjectStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx).map(|x| match x {
+            Some(Ok(batch)) => {
+                let timer = self.baseline_metrics.elapsed_compute().timer();
+                let result = self.filter_project(&batch).map_err(DataFusionError::into);
+                timer.done();
+                Some(result)
+            }
+            other => other,
+        });
+        self.baseline_metrics.record_poll(poll)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // same number of record batches
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for FilterProjectStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::expressions::{binary, col, lit};
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::prelude::SessionContext;
+    use crate::scalar::ScalarValue;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{Field, Schema};
+    use datafusion_expr::Operator;
+
+    #[tokio::test]
+    async fn filter_project() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(vec![1, 2, 3, 4])),
+                Arc::new(StringArray::from(vec!["w", "x", "y", "z"])),
+            ],
+        )?;
+        let input: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None)?);
+        let predicate = binary(
+            col("a", &schema)?,
+            Operator::Gt,
+            lit(ScalarValue::Int32(Some(1))),
+            &schema,
+        )?;
+
+        // the projected columns are filtered
+        let exec = Arc::new(FilterProjectExec::try_new(
+            predicate.clone(),
+            vec![
+                (col("b", &schema)?, "b".to_string()),
+                (col("a", &schema)?, "c".to_string()),
+            ],
+            input.clone(),
+        )?);
+        let batches = collect(exec, session_ctx.task_ctx()).await?;
+        let expected = vec![
+            "+---+---+",
+            "| b | c |",
+            "+---+---+",
+            "| x | 2 |",
+            "| y | 3 |",
+            "| z | 4 |",
+            "+---+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        // the expressions are evaluated on the rows kept
+        let exec = Arc::new(FilterProjectExec::try_new(
+            predicate,
+            vec![(
+                binary(
+                    lit(ScalarValue::Int32(Some(12))),
+                    Operator::Divide,
+                    binary(
+                        col("a", &schema)?,
+                        Operator::Minus,
+                        lit(ScalarValue::Int32(Some(1))),
+                        &schema,
+                    )?,
+                    &schema,
+                )?,
+                "d".to_string(),
+            )],
+            input,
+        )?);
+        let batches = collect(exec, session_ctx.task_ctx()).await?;
+        let expected = vec![
+            "+----+", "| d  |", "+----+", "| 12 |", "| 6  |", "| 4  |", "+----+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+}
@@ -0,0 +1,330 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Execution of plan fragments, whose inputs are fed by the caller.
+//!
+//! A fragment is an [`ExecutionPlan`] whose leaves include
+//! [`PlaceholderExec`]s, standing for the output of another stage of a
+//! distributed query. [`execute_fragment`] binds every placeholder to the
+//! streams of its partitions, e.g. read from the shuffle files or the network,
+//! and executes the fragment, so that an external scheduler can stitch the
+//! stages of a query together.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+
+use super::expressions::PhysicalSortExpr;
+use super::{
+    execute_stream_partitioned, with_new_children_if_necessary, DisplayFormatType,
+    ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
+};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+
+/// Leaf of a plan fragment standing for an input fed by the caller of
+/// [`execute_fragment`], with the given name, schema and number of partitions.
+/// Each partition of a bound placeholder is the stream provided for it, which
+/// can only be executed once.
+pub struct PlaceholderExec {
+    /// The name the input is bound by
+    name: String,
+    /// The schema of the input
+    schema: SchemaRef,
+    /// Number of partitions of the input
+    partitions: usize,
+    /// The streams of the partitions not executed yet, if bound
+    streams: Option<Mutex<Vec<Option<SendableRecordBatchStream>>>>,
+}
+
+impl PlaceholderExec {
+    /// Create a new placeholder of the input `name`, which is not bound
+    pub fn new(name: impl Into<String>, schema: SchemaRef, partitions: usize) -> Self {
+        Self {
+            name: name.into(),
+            schema,
+            partitions,
+            streams: None,
+        }
+    }
+
+    /// The name of the input
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of partitions of the input
+    pub fn partitions(&self) -> usize {
+        self.partitions
+    }
+
+    /// Binds a copy of this placeholder to the streams of its partitions
+    fn bind(&self, streams: Vec<SendableRecordBatchStream>) -> Result<Self> {
+        if streams.len() != self.partitions {
+            return Err(DataFusionError::Plan(format!(
+                "Input {} of the fragment has {} partitions, but {} streams were provided",
+                self.name,
+                self.partitions,
+                streams.len()
+            )));
+        }
+        if let Some(stream) = streams.iter().find(|s| s.schema() != self.schema) {
+            return Err(DataFusionError::Plan(format!(
+                "Input {} of the fragment has schema {:?}, but a stream of schema {:?} was provided",
+                self.name,
+                self.schema,
+                stream.schema()
+            )));
+        }
+        Ok(Self {
+            name: self.name.clone(),
+            schema: self.schema.clone(),
+            partitions: self.partitions,
+            streams: Some(Mutex::new(streams.into_iter().map(Some).collect())),
+        })
+    }
+}
+
+impl fmt::Debug for PlaceholderExec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PlaceholderExec")
+            .field("name", &self.name)
+            .field("schema", &self.schema)
+            .field("partitions", &self.partitions)
+            .field("bound", &self.streams.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for PlaceholderExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.partitions)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let streams = self.streams.as_ref().ok_or_else(|| {
+            DataFusionError::Execution(format!(
+                "Input {} of the fragment is not bound to streams",
+                self.name
+            ))
+        })?;
+        let mut streams = streams.lock();
+        let stream = streams.get_mut(partition).ok_or_else(|| {
+            DataFusionError::Internal(format!(
+                "PlaceholderExec invalid partition {} (expected less than {})",
+                partition, self.partitions
+            ))
+        })?;
+        stream.take().ok_or_else(|| {
+            DataFusionError::Execution(format!(
+                "Partition {} of input {} of the fragment was already executed",
+                partition, self.name
+            ))
+        })
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "PlaceholderExec: name={}, partitions={}",
+                    self.name, self.partitions
+                )
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Executes the fragment `plan`, binding each of its [`PlaceholderExec`]s to
+/// the streams of the partitions of the input of the same name in `inputs`,
+/// and returns the streams of the partitions of its output.
+///
+/// Errors if an input of the fragment is missing from `inputs`, or doesn't
+/// have the schema and number of partitions of its placeholder, or if
+/// `inputs` has an input the fragment doesn't have. The streams of an input
+/// are bound to a single placeholder.
+pub async fn execute_fragment(
+    plan: Arc<dyn ExecutionPlan>,
+    mut inputs: HashMap<String, Vec<SendableRecordBatchStream>>,
+    context: Arc<TaskContext>,
+) -> Result<Vec<SendableRecordBatchStream>> {
+    let plan = bind_placeholders(plan, &mut inputs)?;
+    if !inputs.is_empty() {
+        let mut names = inputs.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        return Err(DataFusionError::Plan(format!(
+            "The fragment has no input named {}",
+            names.join(", ")
+        )));
+    }
+    execute_stream_partitioned(plan, context).await
+}
+
+/// Replaces the placeholders of `plan` by the ones bound to the streams of
+/// `inputs`, removing the streams bound
+fn bind_placeholders(
+    plan: Arc<dyn ExecutionPlan>,
+    inputs: &mut HashMap<String, Vec<SendableRecordBatchStream>>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if let Some(placeholder) = plan.as_any().downcast_ref::<PlaceholderExec>() {
+        let streams = inputs.remove(&placeholder.name).ok_or_else(|| {
+            DataFusionError::Plan(format!(
+                "No streams were provided for input {} of the fragment",
+                placeholder.name
+            ))
+        })?;
+        return Ok(Arc::new(placeholder.bind(streams)?));
+    }
+    let children = plan
+        .children()
+        .into_iter()
+        .map(|child| bind_placeholders(child, inputs))
+        .collect::<Result<Vec<_>>>()?;
+    with_new_children_if_necessary(plan, children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_sorted_eq;
+    use crate::from_slice::FromSlice;
+    use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+    use crate::physical_plan::common;
+    use crate::physical_plan::memory::MemoryStream;
+    use crate::prelude::SessionContext;
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+
+    fn stream(schema: &SchemaRef, values: &[i32]) -> Result<SendableRecordBatchStream> {
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_slice(values))],
+        )?;
+        Ok(Box::pin(MemoryStream::try_new(
+            vec![batch],
+            schema.clone(),
+            None,
+        )?))
+    }
+
+    #[tokio::test]
+    async fn execute_fragment_with_inputs() -> Result<()> {
+        let task_ctx = SessionContext::new().task_ctx();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let placeholder = Arc::new(PlaceholderExec::new("stage_1", schema.clone(), 2));
+        let plan = Arc::new(CoalescePartitionsExec::new(placeholder.clone()));
+
+        let inputs = HashMap::from([(
+            "stage_1".to_string(),
+            vec![stream(&schema, &[1, 2])?, stream(&schema, &[3])?],
+        )]);
+        let mut streams = execute_fragment(plan, inputs, task_ctx.clone()).await?;
+        assert_eq!(streams.len(), 1);
+        let batches = common::collect(streams.remove(0)).await?;
+
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 1 |", "| 2 |", "| 3 |", "+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // the placeholders of the fragment itself are not bound
+        let err = placeholder.execute(0, task_ctx).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Execution error: Input stage_1 of the fragment is not bound to streams"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn execute_fragment_invalid_inputs() -> Result<()> {
+        let task_ctx = SessionContext::new().task_ctx();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let plan: Arc<dyn ExecutionPlan> =
+            Arc::new(PlaceholderExec::new("stage_1", schema.clone(), 1));
+
+        let err = execute_fragment(plan.clone(), HashMap::new(), task_ctx.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: No streams were provided for input stage_1 of the fragment"
+        );
+
+        let inputs = HashMap::from([(
+            "stage_1".to_string(),
+            vec![stream(&schema, &[1])?, stream(&schema, &[2])?],
+        )]);
+        let err = execute_fragment(plan.clone(), inputs, task_ctx.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Input stage_1 of the fragment has 1 partitions, but 2 streams were provided"
+        );
+
+        let inputs = HashMap::from([
+            ("stage_1".to_string(), vec![stream(&schema, &[1])?]),
+            ("stage_2".to_string(), vec![]),
+        ]);
+        let err = execute_fragment(plan, inputs, task_ctx).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: The fragment has no input named stage_2"
+        );
+        Ok(())
+    }
+}
@@ -46,6 +46,7 @@ use arrow::{
     datatypes::{DataType, Int32Type, Int64Type, Schema},
 };
 use datafusion_physical_expr::array_expressions;
+use datafusion_physical_expr::collation_expressions;
 use datafusion_physical_expr::conditional_expressions;
 use datafusion_physical_expr::datetime_expressions;
 use datafusion_physical_expr::math_expressions;
@@ -70,83 +71,104 @@ pub fn create_physical_expr(
 
     let data_type = function::return_type(fun, &coerced_expr_types)?;
 
+    if let Some(kernel) = execution_props.kernels.function(fun) {
+        return Ok(Arc::new(ScalarFunctionExpr::new(
+            &format!("{}", fun),
+            kernel,
+            coerced_phy_exprs,
+            &data_type,
+        )));
+    }
+
     let fun_expr: ScalarFunctionImplementation = match fun {
         // These functions need args and input schema to pick an implementation
         // Unlike the string functions, which actually figure out the function to use with each array,
         // here we return either a cast fn or string timestamp translation based on the expression data type
         // so we don't have to pay a per-array/batch cost.
         BuiltinScalarFunction::ToTimestamp => {
-            Arc::new(match coerced_phy_exprs[0].data_type(input_schema) {
+            match coerced_phy_exprs[0].data_type(input_schema) {
                 Ok(DataType::Int64) | Ok(DataType::Timestamp(_, None)) => {
-                    |col_values: &[ColumnarValue]| {
+                    Arc::new(|col_values: &[ColumnarValue]| {
                         cast_column(
                             &col_values[0],
                             &DataType::Timestamp(TimeUnit::Nanosecond, None),
                             &DEFAULT_DATAFUSION_CAST_OPTIONS,
                         )
-                    }
+                    })
                 }
-                Ok(DataType::Utf8) => datetime_expressions::to_timestamp,
+                Ok(DataType::Utf8) => Arc::new(datetime_expressions::make_to_timestamp(
+                    TimeUnit::Nanosecond,
+                    execution_props.temporal_settings,
+                )),
                 other => {
                     return Err(DataFusionError::Internal(format!(
                         "Unsupported data type {:?} for function to_timestamp",
                         other,
                     )))
                 }
-            })
+            }
         }
         BuiltinScalarFunction::ToTimestampMillis => {
-            Arc::new(match coerced_phy_exprs[0].data_type(input_schema) {
+            match coerced_phy_exprs[0].data_type(input_schema) {
                 Ok(DataType::Int64) | Ok(DataType::Timestamp(_, None)) => {
-                    |col_values: &[ColumnarValue]| {
+                    Arc::new(|col_values: &[ColumnarValue]| {
                         cast_column(
                             &col_values[0],
                             &DataType::Timestamp(TimeUnit::Millisecond, None),
                             &DEFAULT_DATAFUSION_CAST_OPTIONS,
                         )
-                    }
+                    })
                 }
-                Ok(DataType::Utf8) => datetime_expressions::to_timestamp_millis,
+                Ok(DataType::Utf8) => Arc::new(datetime_expressions::make_to_timestamp(
+                    TimeUnit::Millisecond,
+                    execution_props.temporal_settings,
+                )),
                 other => {
                     return Err(DataFusionError::Internal(format!(
                         "Unsupported data type {:?} for function to_timestamp_millis",
                         other,
                     )))
                 }
-            })
+            }
         }
         BuiltinScalarFunction::ToTimestampMicros => {
-            Arc::new(match coerced_phy_exprs[0].data_type(input_schema) {
+            match coerced_phy_exprs[0].data_type(input_schema) {
                 Ok(DataType::Int64) | Ok(DataType::Timestamp(_, None)) => {
-                    |col_values: &[ColumnarValue]| {
+                    Arc::new(|col_values: &[ColumnarValue]| {
                         cast_column(
                             &col_values[0],
                             &DataType::Timestamp(TimeUnit::Microsecond, None),
                             &DEFAULT_DATAFUSION_CAST_OPTIONS,
                         )
-                    }
+                    })
                 }
-                Ok(DataType::Utf8) => datetime_expressions::to_timestamp_micros,
+                Ok(DataType::Utf8) => Arc::new(datetime_expressions::make_to_timestamp(
+                    TimeUnit::Microsecond,
+                    execution_props.temporal_settings,
+                )),
                 other => {
                     return Err(DataFusionError::Internal(format!(
                         "Unsupported data type {:?} for function to_timestamp_micros",
                         other,
                     )))
                 }
-            })
+            }
         }
-        BuiltinScalarFunction::ToTimestampSeconds => Arc::new({
+        BuiltinScalarFunction::ToTimestampSeconds => {
             match coerced_phy_exprs[0].data_type(input_schema) {
                 Ok(DataType::Int64) | Ok(DataType::Timestamp(_, None)) => {
-                    |col_values: &[ColumnarValue]| {
+                    Arc::new(|col_values: &[ColumnarValue]| {
                         cast_column(
                             &col_values[0],
                             &DataType::Timestamp(TimeUnit::Second, None),
                             &DEFAULT_DATAFUSION_CAST_OPTIONS,
                         )
-                    }
+                    })
                 }
-                Ok(DataType::Utf8) => datetime_expressions::to_timestamp_seconds,
+                Ok(DataType::Utf8) => Arc::new(datetime_expressions::make_to_timestamp(
+                    TimeUnit::Second,
+                    execution_props.temporal_settings,
+                )),
                 other => {
                     return Err(DataFusionError::Internal(format!(
                         "Unsupported data type {:?} for function to_timestamp_seconds",
@@ -154,7 +176,7 @@ pub fn create_physical_expr(
                     )))
                 }
             }
-        }),
+        }
         // These don't need args and input schema
         _ => create_physical_fun(fun, execution_props)?,
     };
@@ -293,8 +315,14 @@ pub fn create_physical_fun(
         BuiltinScalarFunction::Sqrt => Arc::new(math_expressions::sqrt),
         BuiltinScalarFunction::Tan => Arc::new(math_expressions::tan),
         BuiltinScalarFunction::Trunc => Arc::new(math_expressions::trunc),
+        BuiltinScalarFunction::WidthBucket => {
+            make_scalar_function(math_expressions::width_bucket)
+        }
         // string functions
         BuiltinScalarFunction::Array => Arc::new(array_expressions::array),
+        BuiltinScalarFunction::ArrayContains => {
+            make_scalar_function(array_expressions::array_contains)
+        }
         BuiltinScalarFunction::Ascii => Arc::new(|args| match args[0].data_type() {
             DataType::Utf8 => {
                 make_scalar_function(string_expressions::ascii::<i32>)(args)
@@ -359,12 +387,17 @@ pub fn create_physical_fun(
             Arc::new(|args| make_scalar_function(string_expressions::chr)(args))
         }
         BuiltinScalarFunction::Coalesce => Arc::new(conditional_expressions::coalesce),
+        BuiltinScalarFunction::Collate => Arc::new(collation_expressions::collate),
         BuiltinScalarFunction::Concat => Arc::new(string_expressions::concat),
         BuiltinScalarFunction::ConcatWithSeparator => {
             Arc::new(|args| make_scalar_function(string_expressions::concat_ws)(args))
         }
-        BuiltinScalarFunction::DatePart => Arc::new(datetime_expressions::date_part),
-        BuiltinScalarFunction::DateTrunc => Arc::new(datetime_expressions::date_trunc),
+        BuiltinScalarFunction::DatePart => Arc::new(
+            datetime_expressions::make_date_part(execution_props.temporal_settings),
+        ),
+        BuiltinScalarFunction::DateTrunc => Arc::new(
+            datetime_expressions::make_date_trunc(execution_props.temporal_settings),
+        ),
         BuiltinScalarFunction::Now => {
             // bind value for now at plan time
             Arc::new(datetime_expressions::make_now(
@@ -515,6 +548,34 @@ pub fn create_physical_fun(
                 other,
             ))),
         }),
+        BuiltinScalarFunction::Nfc => Arc::new(|args| match args[0].data_type() {
+            DataType::Utf8 => {
+                let func = invoke_if_unicode_expressions_feature_flag!(nfc, i32, "nfc");
+                make_scalar_function(func)(args)
+            }
+            DataType::LargeUtf8 => {
+                let func = invoke_if_unicode_expressions_feature_flag!(nfc, i64, "nfc");
+                make_scalar_function(func)(args)
+            }
+            other => Err(DataFusionError::Internal(format!(
+                "Unsupported data type {:?} for function nfc",
+                other,
+            ))),
+        }),
+        BuiltinScalarFunction::Nfd => Arc::new(|args| match args[0].data_type() {
+            DataType::Utf8 => {
+                let func = invoke_if_unicode_expressions_feature_flag!(nfd, i32, "nfd");
+                make_scalar_function(func)(args)
+            }
+            DataType::LargeUtf8 => {
+                let func = invoke_if_unicode_expressions_feature_flag!(nfd, i64, "nfd");
+                make_scalar_function(func)(args)
+            }
+            other => Err(DataFusionError::Internal(format!(
+                "Unsupported data type {:?} for function nfd",
+                other,
+            ))),
+        }),
         BuiltinScalarFunction::Reverse => Arc::new(|args| match args[0].data_type() {
             DataType::Utf8 => {
                 let func =
@@ -689,6 +750,24 @@ pub fn create_physical_fun(
                 other,
             ))),
         }),
+        BuiltinScalarFunction::Unaccent => Arc::new(|args| match args[0].data_type() {
+            DataType::Utf8 => {
+                let func = invoke_if_unicode_expressions_feature_flag!(
+                    unaccent, i32, "unaccent"
+                );
+                make_scalar_function(func)(args)
+            }
+            DataType::LargeUtf8 => {
+                let func = invoke_if_unicode_expressions_feature_flag!(
+                    unaccent, i64, "unaccent"
+                );
+                make_scalar_function(func)(args)
+            }
+            other => Err(DataFusionError::Internal(format!(
+                "Unsupported data type {:?} for function unaccent",
+                other,
+            ))),
+        }),
         BuiltinScalarFunction::Upper => Arc::new(string_expressions::upper),
         _ => {
             return Err(DataFusionError::Internal(format!(
@@ -1830,6 +1909,62 @@ mod tests {
             Utf8,
             StringArray
         );
+        #[cfg(feature = "unicode_expressions")]
+        test_function!(
+            Nfc,
+            &[lit(ScalarValue::Utf8(Some("Cre\u{300}me".to_string())))],
+            Ok(Some("Crème")),
+            &str,
+            Utf8,
+            StringArray
+        );
+        #[cfg(feature = "unicode_expressions")]
+        test_function!(
+            Nfd,
+            &[lit(ScalarValue::Utf8(Some("Crème".to_string())))],
+            Ok(Some("Cre\u{300}me")),
+            &str,
+            Utf8,
+            StringArray
+        );
+        #[cfg(feature = "unicode_expressions")]
+        test_function!(
+            Nfd,
+            &[lit(ScalarValue::Utf8(None))],
+            Ok(None),
+            &str,
+            Utf8,
+            StringArray
+        );
+        #[cfg(feature = "unicode_expressions")]
+        test_function!(
+            Unaccent,
+            &[lit(ScalarValue::Utf8(Some("Crème Brûlée".to_string())))],
+            Ok(Some("Creme Brulee")),
+            &str,
+            Utf8,
+            StringArray
+        );
+        #[cfg(feature = "unicode_expressions")]
+        test_function!(
+            Unaccent,
+            &[lit(ScalarValue::Utf8(Some("Cre\u{300}me".to_string())))],
+            Ok(Some("Creme")),
+            &str,
+            Utf8,
+            StringArray
+        );
+        #[cfg(not(feature = "unicode_expressions"))]
+        test_function!(
+            Unaccent,
+            &[lit(ScalarValue::Utf8(Some("Crème".to_string())))],
+            Err(DataFusionError::Internal(
+                "function unaccent requires compilation with feature flag: unicode_expressions.".to_string()
+            )),
+            &str,
+            Utf8,
+            StringArray
+        );
         #[cfg(not(feature = "unicode_expressions"))]
         test_function!(
             Reverse,
@@ -18,6 +18,7 @@
 //! Defines the execution plan for the hash aggregate operation
 
 use std::any::Any;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::vec;
@@ -28,7 +29,7 @@ use futures::{
     stream::{Stream, StreamExt},
 };
 
-use crate::error::Result;
+use crate::error::{DataFusionError, Result};
 use crate::physical_plan::hash_utils::create_hashes;
 use crate::physical_plan::{
     Accumulator, AggregateExpr, DisplayFormatType, Distribution, ExecutionPlan,
@@ -38,12 +39,17 @@ use crate::scalar::ScalarValue;
 
 use arrow::{array::ArrayRef, compute, compute::cast};
 use arrow::{
-    array::{Array, UInt32Builder},
-    error::{ArrowError, Result as ArrowResult},
+    array::DictionaryArray,
+    datatypes::{
+        ArrowDictionaryKeyType, ArrowNativeType, DataType, Field, Int16Type, Int32Type,
+        Int64Type, Int8Type, Schema, SchemaRef, UInt16Type, UInt32Type, UInt64Type,
+        UInt8Type,
+    },
+    record_batch::RecordBatch,
 };
 use arrow::{
-    datatypes::{Field, Schema, SchemaRef},
-    record_batch::RecordBatch,
+    array::{Array, UInt32Builder},
+    error::{ArrowError, Result as ArrowResult},
 };
 use hashbrown::raw::RawTable;
 
@@ -52,7 +58,8 @@ use async_trait::async_trait;
 
 use super::expressions::PhysicalSortExpr;
 use super::metrics::{
-    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet, RecordOutput,
+    BaselineMetrics, Count, ExecutionPlanMetricsSet, MemTrackingMetrics, MetricBuilder,
+    MetricsSet, RecordOutput,
 };
 use super::Statistics;
 use super::{expressions::Column, RecordBatchStream, SendableRecordBatchStream};
@@ -73,6 +80,41 @@ pub enum AggregateMode {
     FinalPartitioned,
 }
 
+/// How the grouped aggregations treat the rows with a null group key
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NullGroupKeys {
+    /// The null keys are equal to each other, making the rows whose group
+    /// keys are all equal or null a single group, as in the SQL standard.
+    /// This is the default.
+    Group,
+    /// The rows with a null in any group key are dropped before the
+    /// aggregation, as with `groupby(dropna=True)` in pandas. The aggregates
+    /// without aggregate expressions, which plan `DISTINCT` and `UNION`,
+    /// keep them.
+    Drop,
+}
+
+impl Default for NullGroupKeys {
+    fn default() -> Self {
+        NullGroupKeys::Group
+    }
+}
+
+impl FromStr for NullGroupKeys {
+    type Err = DataFusionError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "group" => Ok(NullGroupKeys::Group),
+            "drop" => Ok(NullGroupKeys::Drop),
+            _ => Err(DataFusionError::Plan(format!(
+                "Unknown null group key handling '{}', expected group or drop",
+                s
+            ))),
+        }
+    }
+}
+
 /// Hash aggregate execution plan
 #[derive(Debug)]
 pub struct HashAggregateExec {
@@ -188,6 +230,27 @@ impl HashAggregateExec {
     pub fn input_schema(&self) -> SchemaRef {
         self.input_schema.clone()
     }
+
+    /// Estimates the number of groups from the statistics of the input: the
+    /// product of the distinct counts of the grouping columns, bounded by
+    /// the number of input rows. Returns `None` when a grouping expression is
+    /// not a column or its distinct count is unknown.
+    pub fn estimated_group_count(&self) -> Option<usize> {
+        let statistics = self.input.statistics();
+        let column_statistics = statistics.column_statistics?;
+        let groups = self
+            .group_expr
+            .iter()
+            .try_fold(1_usize, |groups, (expr, _)| {
+                let column = expr.as_any().downcast_ref::<Column>()?;
+                let distinct = column_statistics.get(column.index())?.distinct_count?;
+                Some(groups.saturating_mul(distinct))
+            })?;
+        Some(match statistics.num_rows {
+            Some(num_rows) => groups.min(num_rows),
+            None => groups,
+        })
+    }
 }
 
 #[async_trait]
@@ -233,12 +296,13 @@ impl ExecutionPlan for HashAggregateExec {
         partition: usize,
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
+        let session_config = context.session_config();
+        let runtime = context.runtime_env();
         let input = self.input.execute(partition, context).await?;
         let group_expr = self.group_expr.iter().map(|x| x.0.clone()).collect();
 
-        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
-
         if self.group_expr.is_empty() {
+            let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
             Ok(Box::pin(HashAggregateStream::new(
                 self.mode,
                 self.schema.clone(),
@@ -253,7 +317,13 @@ impl ExecutionPlan for HashAggregateExec {
                 group_expr,
                 self.aggr_expr.clone(),
                 input,
-                baseline_metrics,
+                MemTrackingMetrics::new_with_rt(&self.metrics, partition, runtime)
+                    .with_name(format!("HashAggregateExec({:?})", self.mode)),
+                self.estimated_group_count(),
+                session_config.aggregate_load_factor(),
+                session_config.skip_partial_aggregation(),
+                MetricBuilder::new(&self.metrics)
+                    .counter("skipped_aggregation_rows", partition),
             )?))
         }
     }
@@ -353,7 +423,18 @@ Example: average
 * The RecordBatch is (sent back / transmitted over network)
 * Once all N record batches arrive, `merge` is performed, which builds a RecordBatch with N rows and 2 columns.
 * Finally, `get_value` returns an array with one entry computed from the state
+
+When the grouping keys are nearly unique, the partial aggregation barely
+reduces its input, so it can be configured to stop aggregating once it has
+probed enough rows: it then emits its groups and converts every following input
+row to the state of a group of its own, leaving all the work to the final stage.
 */
+
+/// Upper bound of the number of groups the hash table of a grouped aggregation
+/// is pre-allocated for, so that overestimated statistics do not reserve an
+/// unreasonable amount of memory upfront
+const MAX_PREALLOCATED_GROUPS: usize = 1 << 20;
+
 struct GroupedHashAggregateStream {
     schema: SchemaRef,
     input: SendableRecordBatchStream,
@@ -364,22 +445,38 @@ struct GroupedHashAggregateStream {
     aggr_expr: Vec<Arc<dyn AggregateExpr>>,
     group_expr: Vec<Arc<dyn PhysicalExpr>>,
 
-    baseline_metrics: BaselineMetrics,
+    tracking_metrics: MemTrackingMetrics,
+    load_factor: f64,
     random_state: RandomState,
     finished: bool,
+
+    /// Number of probed input rows and minimum ratio of groups to input rows
+    /// to stop aggregating, for partial aggregations
+    skip_aggregation_probe: Option<(usize, f64)>,
+    /// Number of rows aggregated so far
+    input_rows: usize,
+    /// Whether the input rows are passed through rather than aggregated
+    skipping_aggregation: bool,
+    /// Number of input rows passed through
+    skipped_aggregation_rows: Count,
 }
 
 impl GroupedHashAggregateStream {
     /// Create a new HashAggregateStream
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         mode: AggregateMode,
         schema: SchemaRef,
         group_expr: Vec<Arc<dyn PhysicalExpr>>,
         aggr_expr: Vec<Arc<dyn AggregateExpr>>,
         input: SendableRecordBatchStream,
-        baseline_metrics: BaselineMetrics,
+        tracking_metrics: MemTrackingMetrics,
+        estimated_groups: Option<usize>,
+        load_factor: f64,
+        skip_partial_aggregation: Option<(usize, f64)>,
+        skipped_aggregation_rows: Count,
     ) -> Result<Self> {
-        let timer = baseline_metrics.elapsed_compute().timer();
+        let timer = tracking_metrics.elapsed_compute().timer();
 
         // The expressions to evaluate the batch, one vec of expressions per aggregation.
         // Assume create_schema() always put group columns in front of aggr columns, we set
@@ -387,6 +484,14 @@ impl GroupedHashAggregateStream {
         let aggregate_expressions =
             aggregate_expressions(&aggr_expr, &mode, group_expr.len())?;
 
+        let accumulators = match estimated_groups {
+            Some(groups) => {
+                Accumulators::with_capacity(groups.min(MAX_PREALLOCATED_GROUPS))
+            }
+            None => Accumulators::default(),
+        };
+        tracking_metrics.init_mem_used(accumulators.allocated_size());
+
         timer.done();
 
         Ok(Self {
@@ -395,13 +500,51 @@ impl GroupedHashAggregateStream {
             input,
             aggr_expr,
             group_expr,
-            baseline_metrics,
+            tracking_metrics,
+            load_factor,
             aggregate_expressions,
-            accumulators: Default::default(),
+            accumulators,
             random_state: Default::default(),
             finished: false,
+            skip_aggregation_probe: match mode {
+                AggregateMode::Partial => skip_partial_aggregation,
+                AggregateMode::Final | AggregateMode::FinalPartitioned => None,
+            },
+            input_rows: 0,
+            skipping_aggregation: false,
+            skipped_aggregation_rows,
         })
     }
+
+    /// Whether the groups aggregated so far reduce the input rows too little
+    /// for the aggregation to be worth continuing
+    fn should_skip_aggregation(&self) -> bool {
+        match self.skip_aggregation_probe {
+            Some((probe_rows, ratio)) => {
+                self.input_rows >= probe_rows
+                    && self.accumulators.group_states.len() as f64
+                        >= ratio * self.input_rows as f64
+            }
+            None => false,
+        }
+    }
+
+    /// Emits the groups aggregated so far and releases their memory
+    fn emit_groups(&mut self) -> ArrowResult<RecordBatch> {
+        let batch = create_batch_from_map(
+            &self.mode,
+            &self.accumulators,
+            self.group_expr.len(),
+            &self.schema,
+        );
+        self.accumulators = Accumulators::default();
+        self.tracking_metrics
+            .set_mem_used(self.accumulators.allocated_size());
+        if let Ok(batch) = &batch {
+            self.tracking_metrics.record_output(batch.num_rows());
+        }
+        batch
+    }
 }
 
 impl Stream for GroupedHashAggregateStream {
@@ -416,12 +559,41 @@ impl Stream for GroupedHashAggregateStream {
             return Poll::Ready(None);
         }
 
-        let elapsed_compute = this.baseline_metrics.elapsed_compute();
+        let elapsed_compute = this.tracking_metrics.elapsed_compute().clone();
+
+        if this.skipping_aggregation {
+            return match ready!(this.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    let timer = elapsed_compute.timer();
+                    let num_rows = batch.num_rows();
+                    let result = aggregate_batch_per_row(
+                        &this.group_expr,
+                        &this.aggr_expr,
+                        &this.aggregate_expressions,
+                        &batch,
+                        &this.schema,
+                    )
+                    .map_err(|e| ArrowError::ExternalError(Box::new(e)));
+                    if result.is_ok() {
+                        this.skipped_aggregation_rows.add(num_rows);
+                        this.tracking_metrics.record_output(num_rows);
+                    }
+                    timer.done();
+                    Poll::Ready(Some(result))
+                }
+                Some(Err(e)) => Poll::Ready(Some(Err(e))),
+                None => {
+                    this.finished = true;
+                    Poll::Ready(None)
+                }
+            };
+        }
 
         loop {
             let result = match ready!(this.input.poll_next_unpin(cx)) {
                 Some(Ok(batch)) => {
                     let timer = elapsed_compute.timer();
+                    this.input_rows += batch.num_rows();
                     let result = group_aggregate_batch(
                         &this.mode,
                         &this.random_state,
@@ -430,26 +602,43 @@ impl Stream for GroupedHashAggregateStream {
                         batch,
                         &mut this.accumulators,
                         &this.aggregate_expressions,
-                    );
-
+                        this.load_factor,
+                    )
+                    .and_then(|_| {
+                        this.tracking_metrics
+                            .try_set_mem_used(this.accumulators.allocated_size())
+                    });
+
+                    let result = match result {
+                        Ok(_) if this.should_skip_aggregation() => {
+                            // emit the groups, the remaining input is passed through
+                            this.skipping_aggregation = true;
+                            Some(this.emit_groups())
+                        }
+                        Ok(_) => None,
+                        Err(e) => Some(Err(ArrowError::ExternalError(Box::new(e)))),
+                    };
                     timer.done();
 
                     match result {
-                        Ok(_) => continue,
-                        Err(e) => Err(ArrowError::ExternalError(Box::new(e))),
+                        Some(Ok(batch)) => return Poll::Ready(Some(Ok(batch))),
+                        Some(Err(e)) => Err(e),
+                        None => continue,
                     }
                 }
                 Some(Err(e)) => Err(e),
                 None => {
                     this.finished = true;
-                    let timer = this.baseline_metrics.elapsed_compute().timer();
+                    let timer = this.tracking_metrics.elapsed_compute().timer();
                     let result = create_batch_from_map(
                         &this.mode,
                         &this.accumulators,
                         this.group_expr.len(),
                         &this.schema,
-                    )
-                    .record_output(&this.baseline_metrics);
+                    );
+                    if let Ok(batch) = &result {
+                        this.tracking_metrics.record_output(batch.num_rows());
+                    }
 
                     timer.done();
                     result
@@ -468,7 +657,53 @@ impl RecordBatchStream for GroupedHashAggregateStream {
     }
 }
 
+/// Converts every row of `batch` to a group of its own, with the state of the
+/// aggregates of that single row, as the partial aggregation would output it.
+fn aggregate_batch_per_row(
+    group_expr: &[Arc<dyn PhysicalExpr>],
+    aggr_expr: &[Arc<dyn AggregateExpr>],
+    aggregate_expressions: &[Vec<Arc<dyn PhysicalExpr>>],
+    batch: &RecordBatch,
+    output_schema: &SchemaRef,
+) -> Result<RecordBatch> {
+    if batch.num_rows() == 0 {
+        return Ok(RecordBatch::new_empty(output_schema.clone()));
+    }
+    let mut columns = evaluate(group_expr, batch)?;
+    let aggr_input_values = evaluate_many(aggregate_expressions, batch)?;
+
+    for (expr, values) in aggr_expr.iter().zip(aggr_input_values.iter()) {
+        if let Some(states) = expr.row_states(values)? {
+            columns.extend(states);
+            continue;
+        }
+        // the states of the other aggregations are built by their accumulators
+        let accumulators = (0..batch.num_rows())
+            .map(|row| {
+                let mut accumulator = expr.create_accumulator()?;
+                let values = values
+                    .iter()
+                    .map(|array| array.slice(row, 1))
+                    .collect::<Vec<_>>();
+                accumulator.update_batch(&values)?;
+                Ok(accumulator)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        columns.extend(state_arrays(accumulators.iter().map(|a| a.as_ref()))?);
+    }
+
+    // cast output if needed, as in `create_batch_from_map`
+    let columns = columns
+        .iter()
+        .zip(output_schema.fields().iter())
+        .map(|(col, desired_field)| cast(col, desired_field.data_type()))
+        .collect::<ArrowResult<Vec<_>>>()?;
+
+    Ok(RecordBatch::try_new(output_schema.clone(), columns)?)
+}
+
 /// TODO: Make this a member function of [`GroupedHashAggregateStream`]
+#[allow(clippy::too_many_arguments)]
 fn group_aggregate_batch(
     mode: &AggregateMode,
     random_state: &RandomState,
@@ -477,6 +712,7 @@ fn group_aggregate_batch(
     batch: RecordBatch,
     accumulators: &mut Accumulators,
     aggregate_expressions: &[Vec<Arc<dyn PhysicalExpr>>],
+    load_factor: f64,
 ) -> Result<()> {
     // evaluate the grouping expressions
     let group_values = evaluate(group_expr, &batch)?;
@@ -493,58 +729,56 @@ fn group_aggregate_batch(
     // track which entries in `accumulators` have rows in this batch to aggregate
     let mut groups_with_rows = vec![];
 
-    // 1.1 Calculate the group keys for the group values
-    let mut batch_hashes = vec![0; batch.num_rows()];
-    create_hashes(&group_values, random_state, &mut batch_hashes)?;
-
-    for (row, hash) in batch_hashes.into_iter().enumerate() {
-        let Accumulators { map, group_states } = accumulators;
-
-        let entry = map.get_mut(hash, |(_hash, group_idx)| {
-            // verify that a group that we are inserting with hash is
-            // actually the same key value as the group in
-            // existing_idx  (aka group_values @ row)
-            let group_state = &group_states[*group_idx];
-            group_values
-                .iter()
-                .zip(group_state.group_by_values.iter())
-                .all(|(array, scalar)| scalar.eq_array(array, row))
-        });
-
-        match entry {
-            // Existing entry for this group value
-            Some((_hash, group_idx)) => {
-                let group_state = &mut group_states[*group_idx];
-                // 1.3
-                if group_state.indices.is_empty() {
-                    groups_with_rows.push(*group_idx);
+    let dictionary = match group_values.as_slice() {
+        [column] => take_dictionary_groups(
+            &mut accumulators.dictionary_groups,
+            column,
+            random_state,
+        )?,
+        _ => None,
+    };
+    match dictionary {
+        // the rows are grouped by their key, only the first row of each key
+        // looks up its group in the hash table
+        Some((mut dictionary, keys)) => {
+            for (row, key) in keys.into_iter().enumerate() {
+                let group_idx = match dictionary.group(key)? {
+                    Some(group_idx) => group_idx,
+                    None => {
+                        let hash = dictionary.hash(key);
+                        let group_idx = find_or_create_group(
+                            accumulators,
+                            &group_values,
+                            row,
+                            hash,
+                            aggr_expr,
+                            load_factor,
+                        )?;
+                        dictionary.set_group(key, group_idx);
+                        group_idx
+                    }
                 };
-                group_state.indices.push(row as u32); // remember this row
+                push_group_row(accumulators, group_idx, row, &mut groups_with_rows);
             }
-            //  1.2 Need to create new entry
-            None => {
-                let accumulator_set = create_accumulators(aggr_expr)?;
-
-                // Copy group values out of arrays into `ScalarValue`s
-                let group_by_values = group_values
-                    .iter()
-                    .map(|col| ScalarValue::try_from_array(col, row))
-                    .collect::<Result<Vec<_>>>()?;
-
-                // Add new entry to group_states and save newly created index
-                let group_state = GroupState {
-                    group_by_values: group_by_values.into_boxed_slice(),
-                    accumulator_set,
-                    indices: vec![row as u32], // 1.3
-                };
-                let group_idx = group_states.len();
-                group_states.push(group_state);
-                groups_with_rows.push(group_idx);
-
-                // for hasher function, use precomputed hash value
-                map.insert(hash, (hash, group_idx), |(hash, _group_idx)| *hash);
+            accumulators.dictionary_groups = Some(dictionary);
+        }
+        None => {
+            // 1.1 Calculate the group keys for the group values
+            let mut batch_hashes = vec![0; batch.num_rows()];
+            create_hashes(&group_values, random_state, &mut batch_hashes)?;
+
+            for (row, hash) in batch_hashes.into_iter().enumerate() {
+                let group_idx = find_or_create_group(
+                    accumulators,
+                    &group_values,
+                    row,
+                    hash,
+                    aggr_expr,
+                    load_factor,
+                )?;
+                push_group_row(accumulators, group_idx, row, &mut groups_with_rows);
             }
-        };
+        }
     }
 
     // Collect all indices + offsets based on keys in this vec
@@ -583,11 +817,16 @@ fn group_aggregate_batch(
     // 2.3 `slice` from each of its arrays the keys' values
     // 2.4 update / merge the accumulator with the values
     // 2.5 clear indices
+    let Accumulators {
+        group_states,
+        accumulators_size,
+        ..
+    } = accumulators;
     groups_with_rows
         .iter()
         .zip(offsets.windows(2))
         .try_for_each(|(group_idx, offsets)| {
-            let group_state = &mut accumulators.group_states[*group_idx];
+            let group_state = &mut group_states[*group_idx];
             // 2.2
             group_state
                 .accumulator_set
@@ -605,12 +844,17 @@ fn group_aggregate_batch(
                             .collect::<Vec<ArrayRef>>(),
                     )
                 })
-                .try_for_each(|(accumulator, values)| match mode {
-                    AggregateMode::Partial => accumulator.update_batch(&values),
-                    AggregateMode::FinalPartitioned | AggregateMode::Final => {
-                        // note: the aggregation here is over states, not values, thus the merge
-                        accumulator.merge_batch(&values)
-                    }
+                .try_for_each(|(accumulator, values)| {
+                    let size = accumulator.size();
+                    match mode {
+                        AggregateMode::Partial => accumulator.update_batch(&values),
+                        AggregateMode::FinalPartitioned | AggregateMode::Final => {
+                            // note: the aggregation here is over states, not values, thus the merge
+                            accumulator.merge_batch(&values)
+                        }
+                    }?;
+                    *accumulators_size = *accumulators_size + accumulator.size() - size;
+                    Ok(())
                 })
                 // 2.5
                 .and({
@@ -622,6 +866,244 @@ fn group_aggregate_batch(
     Ok(())
 }
 
+/// Returns the index of the group of the row `row` of `group_values`, whose
+/// hash is `hash`, creating the group if it does not exist
+fn find_or_create_group(
+    accumulators: &mut Accumulators,
+    group_values: &[ArrayRef],
+    row: usize,
+    hash: u64,
+    aggr_expr: &[Arc<dyn AggregateExpr>],
+    load_factor: f64,
+) -> Result<usize> {
+    let Accumulators {
+        map,
+        group_states,
+        group_values_size,
+        accumulators_size,
+        ..
+    } = accumulators;
+
+    let entry = map.get_mut(hash, |(_hash, group_idx)| {
+        // verify that a group that we are inserting with hash is
+        // actually the same key value as the group in
+        // existing_idx  (aka group_values @ row)
+        let group_state = &group_states[*group_idx];
+        group_values
+            .iter()
+            .zip(group_state.group_by_values.iter())
+            .all(|(array, scalar)| scalar.eq_array(array, row))
+    });
+
+    match entry {
+        // Existing entry for this group value
+        Some((_hash, group_idx)) => Ok(*group_idx),
+        //  1.2 Need to create new entry
+        None => {
+            let accumulator_set = create_accumulators(aggr_expr)?;
+
+            // Copy group values out of arrays into `ScalarValue`s
+            let group_by_values = group_values
+                .iter()
+                .map(|col| ScalarValue::try_from_array(col, row))
+                .collect::<Result<Vec<_>>>()?;
+            *group_values_size += ScalarValue::size_of_slice(&group_by_values);
+            *accumulators_size += accumulator_set
+                .iter()
+                .map(|accumulator| accumulator.size())
+                .sum::<usize>();
+
+            // Add new entry to group_states and save newly created index
+            let group_state = GroupState {
+                group_by_values: group_by_values.into_boxed_slice(),
+                accumulator_set,
+                indices: vec![],
+            };
+            let group_idx = group_states.len();
+            group_states.push(group_state);
+
+            // grow the table before exceeding the load factor, hashbrown
+            // itself only grows once 7/8 of the buckets are full
+            let buckets = ((map.len() + 1) as f64 / load_factor).ceil() as usize;
+            if buckets > map.buckets() {
+                let capacity = buckets * 7 / 8;
+                map.reserve(capacity.saturating_sub(map.len()), |(hash, _)| *hash);
+            }
+
+            // for hasher function, use precomputed hash value
+            map.insert(hash, (hash, group_idx), |(hash, _group_idx)| *hash);
+            Ok(group_idx)
+        }
+    }
+}
+
+/// Adds the row `row` to the rows of the group `group_idx` to aggregate,
+/// tracking the groups with rows in `groups_with_rows`
+fn push_group_row(
+    accumulators: &mut Accumulators,
+    group_idx: usize,
+    row: usize,
+    groups_with_rows: &mut Vec<usize>,
+) {
+    let indices = &mut accumulators.group_states[group_idx].indices;
+    // 1.3
+    if indices.is_empty() {
+        groups_with_rows.push(group_idx);
+    }
+    indices.push(row as u32); // remember this row
+}
+
+/// Upper bound of the number of values of the dictionary of a group column
+/// for its rows to be grouped by dictionary key
+const MAX_DICTIONARY_GROUPS: usize = 1 << 12;
+
+/// The groups of the keys of a dictionary-encoded group column, when it is
+/// the only group column, so that the rows are grouped by indexing an array
+/// with their key rather than by hashing their value.
+///
+/// The groups are also in the hash table of [`Accumulators`], so batches
+/// with a different dictionary than the first one fall back to it.
+#[derive(Debug)]
+struct DictionaryGroups {
+    /// The values of the dictionary
+    values: ArrayRef,
+    /// The hash of each value of the dictionary, as `create_hashes` hashes
+    /// the rows with its key
+    hashes: Vec<u64>,
+    /// The group of each key of the dictionary, once known
+    groups: Vec<Option<usize>>,
+    /// The group of the rows with a null key, once known
+    null_group: Option<usize>,
+}
+
+impl DictionaryGroups {
+    fn try_new(values: ArrayRef, random_state: &RandomState) -> Result<Self> {
+        let mut hashes = vec![0; values.len()];
+        create_hashes(&[values.clone()], random_state, &mut hashes)?;
+        Ok(Self {
+            groups: vec![None; values.len()],
+            values,
+            hashes,
+            null_group: None,
+        })
+    }
+
+    /// Whether the rows of a batch with the dictionary `values` are grouped
+    /// by their key
+    fn has_values(&self, values: &ArrayRef) -> bool {
+        self.values.data() == values.data()
+    }
+
+    /// Returns the known group of the rows with the key `key`
+    fn group(&self, key: Option<usize>) -> Result<Option<usize>> {
+        match key {
+            Some(key) => self.groups.get(key).copied().ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "Dictionary key {} out of bounds of {} values",
+                    key,
+                    self.groups.len()
+                ))
+            }),
+            None => Ok(self.null_group),
+        }
+    }
+
+    /// Sets the group of the rows with the key `key`
+    fn set_group(&mut self, key: Option<usize>, group_idx: usize) {
+        match key {
+            Some(key) => self.groups[key] = Some(group_idx),
+            None => self.null_group = Some(group_idx),
+        }
+    }
+
+    /// Returns the hash of the rows with the key `key`, as `create_hashes`
+    /// computes it, a null key or value leaving the hash to zero
+    fn hash(&self, key: Option<usize>) -> u64 {
+        match key {
+            Some(key) if self.values.is_valid(key) => self.hashes[key],
+            _ => 0,
+        }
+    }
+
+    /// Returns the number of bytes allocated by the groups
+    fn allocated_size(&self) -> usize {
+        self.hashes.capacity() * std::mem::size_of::<u64>()
+            + self.groups.capacity() * std::mem::size_of::<Option<usize>>()
+    }
+}
+
+/// Returns the dictionary values and the keys of `array` if it is a
+/// dictionary array
+fn dictionary_keys(array: &ArrayRef) -> Result<Option<(ArrayRef, Vec<Option<usize>>)>> {
+    fn keys<K: ArrowDictionaryKeyType>(
+        array: &ArrayRef,
+    ) -> Result<(ArrayRef, Vec<Option<usize>>)> {
+        let array = array.as_any().downcast_ref::<DictionaryArray<K>>().unwrap();
+        let keys = array
+            .keys()
+            .iter()
+            .map(|key| {
+                key.map(|key| {
+                    key.to_usize().ok_or_else(|| {
+                        DataFusionError::Internal(format!(
+                            "Can not convert key value {:?} to usize in dictionary of type {:?}",
+                            key,
+                            array.data_type()
+                        ))
+                    })
+                })
+                .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((array.values().clone(), keys))
+    }
+
+    let keys = match array.data_type() {
+        DataType::Dictionary(key_type, _) => match key_type.as_ref() {
+            DataType::Int8 => keys::<Int8Type>(array)?,
+            DataType::Int16 => keys::<Int16Type>(array)?,
+            DataType::Int32 => keys::<Int32Type>(array)?,
+            DataType::Int64 => keys::<Int64Type>(array)?,
+            DataType::UInt8 => keys::<UInt8Type>(array)?,
+            DataType::UInt16 => keys::<UInt16Type>(array)?,
+            DataType::UInt32 => keys::<UInt32Type>(array)?,
+            DataType::UInt64 => keys::<UInt64Type>(array)?,
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(keys))
+}
+
+/// Takes the groups of the dictionary of the group column `column` out of
+/// `dictionary_groups`, with the keys of its rows, if its rows can be grouped
+/// by their key: the first dictionary seen is small enough, and the dictionary
+/// of `column` is the same.
+fn take_dictionary_groups(
+    dictionary_groups: &mut Option<DictionaryGroups>,
+    column: &ArrayRef,
+    random_state: &RandomState,
+) -> Result<Option<(DictionaryGroups, Vec<Option<usize>>)>> {
+    let (values, keys) = match dictionary_keys(column)? {
+        Some(keys) => keys,
+        None => return Ok(None),
+    };
+    match dictionary_groups.take() {
+        Some(dictionary) if dictionary.has_values(&values) => {
+            Ok(Some((dictionary, keys)))
+        }
+        Some(dictionary) => {
+            *dictionary_groups = Some(dictionary);
+            Ok(None)
+        }
+        None if values.len() <= MAX_DICTIONARY_GROUPS => Ok(Some((
+            DictionaryGroups::try_new(values, random_state)?,
+            keys,
+        ))),
+        None => Ok(None),
+    }
+}
+
 type AccumulatorItem = Box<dyn Accumulator>;
 
 /// The state that is built for each output group.
@@ -652,6 +1134,43 @@ struct Accumulators {
 
     /// State for each group
     group_states: Vec<GroupState>,
+
+    /// Bytes allocated by the group by values of `group_states`
+    group_values_size: usize,
+
+    /// Bytes allocated by the accumulators of `group_states`
+    accumulators_size: usize,
+
+    /// The groups of the keys of the dictionary of the group column, if
+    /// its rows are grouped by dictionary key
+    dictionary_groups: Option<DictionaryGroups>,
+}
+
+impl Accumulators {
+    /// Creates the state for `capacity` groups, without reallocation
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: RawTable::with_capacity(capacity),
+            group_states: Vec::with_capacity(capacity),
+            group_values_size: 0,
+            accumulators_size: 0,
+            dictionary_groups: None,
+        }
+    }
+
+    /// Returns the number of bytes allocated by the hash table and the group
+    /// states, including the state of the accumulators
+    fn allocated_size(&self) -> usize {
+        // each bucket of the table has an entry and a control byte
+        self.map.buckets() * (std::mem::size_of::<(u64, usize)>() + 1)
+            + self.group_states.capacity() * std::mem::size_of::<GroupState>()
+            + self.group_values_size
+            + self.accumulators_size
+            + self
+                .dictionary_groups
+                .as_ref()
+                .map_or(0, |dictionary| dictionary.allocated_size())
+    }
 }
 
 impl std::fmt::Debug for Accumulators {
@@ -870,21 +1389,7 @@ fn create_batch_from_map(
     if accumulators.group_states.is_empty() {
         return Ok(RecordBatch::new_empty(Arc::new(output_schema.to_owned())));
     }
-    let accs = &accumulators.group_states[0].accumulator_set;
-    let mut acc_data_types: Vec<usize> = vec![];
-
-    // Calculate number/shape of state arrays
-    match mode {
-        AggregateMode::Partial => {
-            for acc in accs.iter() {
-                let state = acc.state()?;
-                acc_data_types.push(state.len());
-            }
-        }
-        AggregateMode::Final | AggregateMode::FinalPartitioned => {
-            acc_data_types = vec![1; accs.len()];
-        }
-    }
+    let num_accumulators = accumulators.group_states[0].accumulator_set.len();
 
     let mut columns = (0..num_group_expr)
         .map(|i| {
@@ -898,27 +1403,25 @@ fn create_batch_from_map(
         .collect::<Result<Vec<_>>>()?;
 
     // add state / evaluated arrays
-    for (x, &state_len) in acc_data_types.iter().enumerate() {
-        for y in 0..state_len {
-            match mode {
-                AggregateMode::Partial => {
-                    let res = ScalarValue::iter_to_array(
-                        accumulators.group_states.iter().map(|group_state| {
-                            let x = group_state.accumulator_set[x].state().unwrap();
-                            x[y].clone()
-                        }),
-                    )?;
-
-                    columns.push(res);
-                }
-                AggregateMode::Final | AggregateMode::FinalPartitioned => {
-                    let res = ScalarValue::iter_to_array(
-                        accumulators.group_states.iter().map(|group_state| {
-                            group_state.accumulator_set[x].evaluate().unwrap()
-                        }),
-                    )?;
-                    columns.push(res);
-                }
+    for x in 0..num_accumulators {
+        match mode {
+            AggregateMode::Partial => {
+                columns.extend(state_arrays(
+                    accumulators
+                        .group_states
+                        .iter()
+                        .map(|group_state| group_state.accumulator_set[x].as_ref()),
+                )?);
+            }
+            AggregateMode::Final | AggregateMode::FinalPartitioned => {
+                let res = ScalarValue::iter_to_array(
+                    accumulators
+                        .group_states
+                        .iter()
+                        .map(|group_state| group_state.accumulator_set[x].evaluate())
+                        .collect::<Result<Vec<_>>>()?,
+                )?;
+                columns.push(res);
             }
         }
     }
@@ -944,6 +1447,44 @@ fn create_accumulators(
         .collect::<Result<Vec<_>>>()
 }
 
+/// Returns the states of `accumulators`, instances of the same aggregate
+/// (e.g. for different groups), as one array per state field with one row
+/// per accumulator.
+///
+/// The states are appended to arrays by the accumulators supporting it, see
+/// [`Accumulator::state_builders`]. Otherwise the state of each accumulator
+/// is computed once, and its values are moved into the arrays.
+fn state_arrays<'a>(
+    accumulators: impl Iterator<Item = &'a dyn Accumulator>,
+) -> Result<Vec<ArrayRef>> {
+    let mut accumulators = accumulators.peekable();
+    let capacity = accumulators.size_hint().0;
+    if let Some(mut builders) = accumulators
+        .peek()
+        .and_then(|accumulator| accumulator.state_builders(capacity))
+    {
+        for accumulator in accumulators {
+            accumulator.append_state(&mut builders)?;
+        }
+        return Ok(builders
+            .iter_mut()
+            .map(|builder| builder.finish())
+            .collect());
+    }
+
+    let mut fields: Vec<Vec<ScalarValue>> = vec![];
+    for accumulator in accumulators {
+        let state = accumulator.state()?;
+        if fields.is_empty() {
+            fields = state.iter().map(|_| vec![]).collect();
+        }
+        for (field, value) in fields.iter_mut().zip(state) {
+            field.push(value);
+        }
+    }
+    fields.into_iter().map(ScalarValue::iter_to_array).collect()
+}
+
 /// returns a vector of ArrayRefs, where each entry corresponds to either the
 /// final value (mode = Final) or states (mode = Partial)
 fn finalize_aggregation(
@@ -955,14 +1496,9 @@ fn finalize_aggregation(
             // build the vector of states
             let a = accumulators
                 .iter()
-                .map(|accumulator| accumulator.state())
-                .map(|value| {
-                    value.map(|e| {
-                        e.iter().map(|v| v.to_array()).collect::<Vec<ArrayRef>>()
-                    })
-                })
+                .map(|accumulator| state_arrays(std::iter::once(accumulator.as_ref())))
                 .collect::<Result<Vec<_>>>()?;
-            Ok(a.iter().flatten().cloned().collect::<Vec<_>>())
+            Ok(a.into_iter().flatten().collect::<Vec<_>>())
         }
         AggregateMode::Final | AggregateMode::FinalPartitioned => {
             // merge the state to the final value
@@ -981,15 +1517,20 @@ mod tests {
     use crate::from_slice::FromSlice;
     use crate::physical_plan::expressions::{col, Avg};
     use crate::test::assert_is_pending;
-    use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
+    use crate::test::exec::{
+        assert_strong_count_converges_to_zero, BlockingExec, StatisticsExec,
+    };
     use crate::{assert_batches_sorted_eq, physical_plan::common};
-    use arrow::array::{Float64Array, UInt32Array};
+    use arrow::array::{Float64Array, Int8Array, StringArray, UInt32Array, UInt64Array};
     use arrow::datatypes::DataType;
     use datafusion_common::DataFusionError;
     use futures::FutureExt;
 
+    use crate::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
     use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
-    use crate::prelude::SessionContext;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::ColumnStatistics;
+    use crate::prelude::{SessionConfig, SessionContext};
 
     /// some mock data to aggregates
     fn some_data() -> (Arc<Schema>, Vec<RecordBatch>) {
@@ -1062,6 +1603,10 @@ mod tests {
         ];
         assert_batches_sorted_eq!(expected, &result);
 
+        // the hash table and the group values were accounted for
+        let metrics = partial_aggregate.metrics().unwrap();
+        assert!(metrics.sum_by_name("mem_used").unwrap().as_usize() > 0);
+
         let merge = Arc::new(CoalescePartitionsExec::new(partial_aggregate));
 
         let final_group: Vec<Arc<dyn PhysicalExpr>> = (0..groups.len())
@@ -1296,4 +1841,288 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn estimated_group_count() -> Result<()> {
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::UInt32, false),
+            Field::new("c", DataType::Float64, false),
+        ]);
+        let column_statistics = |distinct_count| ColumnStatistics {
+            distinct_count,
+            ..Default::default()
+        };
+        let input = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Some(1000),
+                column_statistics: Some(vec![
+                    column_statistics(Some(10)),
+                    column_statistics(Some(200)),
+                    column_statistics(None),
+                ]),
+                ..Default::default()
+            },
+            schema.clone(),
+        ));
+        let aggregate = |groups: &[&str]| {
+            let groups = groups
+                .iter()
+                .map(|name| Ok((col(name, &schema)?, name.to_string())))
+                .collect::<Result<_>>()?;
+            HashAggregateExec::try_new(
+                AggregateMode::Partial,
+                groups,
+                vec![],
+                input.clone(),
+                input.schema(),
+            )
+        };
+
+        assert_eq!(aggregate(&["a"])?.estimated_group_count(), Some(10));
+        // bounded by the number of rows
+        assert_eq!(aggregate(&["a", "b"])?.estimated_group_count(), Some(1000));
+        // unknown distinct count
+        assert_eq!(aggregate(&["a", "c"])?.estimated_group_count(), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn aggregate_with_load_factor() -> Result<()> {
+        let (schema, batches) = some_data();
+        let input: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+        let aggregate = HashAggregateExec::try_new(
+            AggregateMode::Partial,
+            vec![(col("a", &schema)?, "a".to_string())],
+            vec![],
+            input,
+            schema,
+        )?;
+
+        let session_ctx = SessionContext::with_config(
+            SessionConfig::new().with_aggregate_load_factor(0.5),
+        );
+        let result =
+            common::collect(aggregate.execute(0, session_ctx.task_ctx()).await?).await?;
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 2 |", "| 3 |", "| 4 |", "+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &result);
+
+        // the load factor is clamped rather than rejected
+        let config = SessionConfig::new().with_aggregate_load_factor(2.0);
+        assert_eq!(config.aggregate_load_factor(), 0.875);
+        let config = SessionConfig::new().with_aggregate_load_factor(0.0);
+        assert_eq!(config.aggregate_load_factor(), f64::MIN_POSITIVE);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn skip_partial_aggregation() -> Result<()> {
+        let (schema, batches) = some_data();
+        let input: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+        let groups = vec![(col("a", &schema)?, "a".to_string())];
+        let aggregates: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Avg::new(
+            col("b", &schema)?,
+            "AVG(b)".to_string(),
+            DataType::Float64,
+        ))];
+        let partial_aggregate = Arc::new(HashAggregateExec::try_new(
+            AggregateMode::Partial,
+            groups.clone(),
+            aggregates.clone(),
+            input,
+            schema.clone(),
+        )?);
+
+        // the first batch has 3 groups for 4 rows, above the ratio
+        let session_ctx = SessionContext::with_config(
+            SessionConfig::new().with_skip_partial_aggregation(1, 0.5),
+        );
+        let task_ctx = session_ctx.task_ctx();
+        let result =
+            common::collect(partial_aggregate.execute(0, task_ctx.clone()).await?)
+                .await?;
+        let expected = vec![
+            "+---+---------------+-------------+",
+            "| a | AVG(b)[count] | AVG(b)[sum] |",
+            "+---+---------------+-------------+",
+            "| 2 | 1             | 1           |",
+            "| 2 | 1             | 1           |",
+            "| 3 | 1             | 2           |",
+            "| 3 | 1             | 2           |",
+            "| 3 | 1             | 3           |",
+            "| 4 | 1             | 4           |",
+            "| 4 | 2             | 7           |",
+            "+---+---------------+-------------+",
+        ];
+        assert_batches_sorted_eq!(expected, &result);
+        let metrics = partial_aggregate.metrics().unwrap();
+        assert_eq!(
+            metrics
+                .sum_by_name("skipped_aggregation_rows")
+                .unwrap()
+                .as_usize(),
+            4
+        );
+        assert_eq!(metrics.output_rows().unwrap(), 7);
+
+        let final_aggregate = HashAggregateExec::try_new(
+            AggregateMode::Final,
+            vec![(col("a", &schema)?, "a".to_string())],
+            aggregates,
+            partial_aggregate,
+            schema,
+        )?;
+        let result = common::collect(final_aggregate.execute(0, task_ctx).await?).await?;
+        let expected = vec![
+            "+---+--------------------+",
+            "| a | AVG(b)             |",
+            "+---+--------------------+",
+            "| 2 | 1                  |",
+            "| 3 | 2.3333333333333335 |",
+            "| 4 | 3.6666666666666665 |",
+            "+---+--------------------+",
+        ];
+        assert_batches_sorted_eq!(expected, &result);
+
+        // the ratio is clamped rather than rejected
+        let config = SessionConfig::new().with_skip_partial_aggregation(1, 2.0);
+        assert_eq!(config.skip_partial_aggregation(), Some((1, 1.0)));
+        let config = SessionConfig::new().with_skip_partial_aggregation(1, f64::NAN);
+        assert_eq!(
+            config.skip_partial_aggregation(),
+            Some((1, f64::MIN_POSITIVE))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn aggregate_exceeding_memory_pool() -> Result<()> {
+        let (schema, batches) = some_data();
+        let input: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+        let aggregate = HashAggregateExec::try_new(
+            AggregateMode::Partial,
+            vec![(col("a", &schema)?, "a".to_string())],
+            vec![Arc::new(Avg::new(
+                col("b", &schema)?,
+                "AVG(b)".to_string(),
+                DataType::Float64,
+            ))],
+            input,
+            schema,
+        )?;
+
+        let runtime = RuntimeEnv::new(RuntimeConfig::new().with_memory_limit(100, 1.0))?;
+        let session_ctx =
+            SessionContext::with_config_rt(SessionConfig::new(), Arc::new(runtime));
+        let err = common::collect(aggregate.execute(0, session_ctx.task_ctx()).await?)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Resources exhausted"), "{}", err);
+        assert!(err.contains("for HashAggregateExec(Partial)["), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn states_as_arrays() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("b", DataType::Float64, false)]);
+        let avg = Avg::new(col("b", &schema)?, "AVG(b)".to_string(), DataType::Float64);
+
+        let mut accumulators = vec![avg.create_accumulator()?, avg.create_accumulator()?];
+        accumulators[0]
+            .update_batch(&[Arc::new(Float64Array::from_slice(&[1.0, 2.0, 3.0]))])?;
+        accumulators[1].update_batch(&[Arc::new(Float64Array::from_slice(&[4.0]))])?;
+
+        let states = state_arrays(accumulators.iter().map(|a| a.as_ref()))?;
+        assert_eq!(states.len(), 2);
+        assert_eq!(
+            states[0].as_any().downcast_ref::<UInt64Array>().unwrap(),
+            &UInt64Array::from_slice(&[3, 1])
+        );
+        assert_eq!(
+            states[1].as_any().downcast_ref::<Float64Array>().unwrap(),
+            &Float64Array::from_slice(&[6.0, 4.0])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn group_by_dictionary_keys() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "a",
+                DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+                true,
+            ),
+            Field::new("b", DataType::Float64, false),
+        ]));
+        let batch = |keys: Vec<Option<i8>>, values: &ArrayRef, b: &[f64]| {
+            let keys = Int8Array::from(keys);
+            let a = DictionaryArray::<Int8Type>::try_new(&keys, values)?;
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(a), Arc::new(Float64Array::from_slice(b))],
+            )
+        };
+        let values: ArrayRef = Arc::new(StringArray::from(vec!["x", "y"]));
+        let other_values: ArrayRef = Arc::new(StringArray::from(vec!["y", "z"]));
+        let batches = vec![
+            batch(
+                vec![Some(0), Some(1), Some(0), None],
+                &values,
+                &[1.0, 2.0, 3.0, 4.0],
+            )?,
+            batch(vec![Some(1), Some(1)], &values, &[5.0, 6.0])?,
+            // a different dictionary falls back to the hash table
+            batch(vec![Some(1), Some(0)], &other_values, &[7.0, 8.0])?,
+        ];
+
+        let group_expr = vec![(col("a", &schema)?, "a".to_string())];
+        let aggr_expr: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Avg::new(
+            col("b", &schema)?,
+            "AVG(b)".to_string(),
+            DataType::Float64,
+        ))];
+        let mode = AggregateMode::Partial;
+        let output_schema = create_schema(&schema, &group_expr, &aggr_expr, mode)?;
+        let group_expr = vec![group_expr[0].0.clone()];
+        let aggregate_expressions = aggregate_expressions(&aggr_expr, &mode, 1)?;
+
+        let mut accumulators = Accumulators::default();
+        for batch in batches {
+            group_aggregate_batch(
+                &mode,
+                &RandomState::new(),
+                &group_expr,
+                &aggr_expr,
+                batch,
+                &mut accumulators,
+                &aggregate_expressions,
+                0.5,
+            )?;
+        }
+        let dictionary = accumulators.dictionary_groups.as_ref().unwrap();
+        assert!(dictionary.has_values(&values));
+        assert_eq!(dictionary.groups, vec![Some(0), Some(1)]);
+        assert_eq!(dictionary.null_group, Some(2));
+
+        let result = create_batch_from_map(&mode, &accumulators, 1, &output_schema)?;
+        let expected = vec![
+            "+---+---------------+-------------+",
+            "| a | AVG(b)[count] | AVG(b)[sum] |",
+            "+---+---------------+-------------+",
+            "|   | 1             | 4           |",
+            "| x | 2             | 4           |",
+            "| y | 4             | 21          |",
+            "| z | 1             | 7           |",
+            "+---+---------------+-------------+",
+        ];
+        assert_batches_sorted_eq!(expected, &[result]);
+        Ok(())
+    }
 }
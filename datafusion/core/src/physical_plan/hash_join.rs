@@ -22,14 +22,15 @@ use ahash::RandomState;
 
 use arrow::{
     array::{
-        ArrayData, ArrayRef, BooleanArray, LargeStringArray, PrimitiveArray,
+        ArrayRef, BooleanArray, DurationMicrosecondArray, DurationMillisecondArray,
+        DurationNanosecondArray, DurationSecondArray, LargeStringArray, PrimitiveArray,
         TimestampMicrosecondArray, TimestampMillisecondArray, TimestampSecondArray,
-        UInt32BufferBuilder, UInt32Builder, UInt64BufferBuilder, UInt64Builder,
+        UInt32Builder, UInt64Builder,
     },
     compute,
-    datatypes::{UInt32Type, UInt64Type},
+    datatypes::ArrowPrimitiveType,
 };
-use smallvec::{smallvec, SmallVec};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::{any::Any, usize};
 use std::{time::Instant, vec};
@@ -59,7 +60,9 @@ use super::{
 };
 use super::{
     expressions::Column,
-    metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
+    metrics::{
+        self, ExecutionPlanMetricsSet, MemTrackingMetrics, MetricBuilder, MetricsSet,
+    },
 };
 use super::{hash_utils::create_hashes, Statistics};
 use crate::error::{DataFusionError, Result};
@@ -72,24 +75,63 @@ use super::{
 use crate::arrow::array::BooleanBufferBuilder;
 use crate::arrow::datatypes::TimeUnit;
 use crate::execution::context::TaskContext;
+use crate::physical_optimizer::hash_build_probe_order::{supports_swap, swap_join_type};
 use crate::physical_plan::coalesce_batches::concat_batches;
+use crate::physical_plan::common::batch_byte_size;
 use crate::physical_plan::PhysicalExpr;
 use log::debug;
 use std::fmt;
 
-// Maps a `u64` hash value based on the left ["on" values] to a list of indices with this key's value.
+// Maps a `u64` hash value based on the left ["on" values] to the chain of the indices with this
+// key's value.
 //
-// Note that the `u64` keys are not stored in the hashmap (hence the `()` as key), but are only used
-// to put the indices in a certain bucket.
-// By allocating a `HashMap` with capacity for *at least* the number of rows for entries at the left side,
-// we make sure that we don't have to re-hash the hashmap, which needs access to the key (the hash in this case) value.
-// E.g. 1 -> [3, 6, 8] indicates that the column values map to rows 3, 6 and 8 for hash value 1
+// The chains are stored in a single `next` vector rather than in a list per hash value, to avoid
+// an allocation per distinct hash value: `map` holds the first index of the chain of each hash
+// value, and `next[i]` the index following `i` in its chain. The indices are stored plus one, so
+// that 0 marks the end of a chain.
+// E.g. 1 -> 4, next[3] = 7, next[6] = 9, next[8] = 0 indicates that the column values map to rows
+// 3, 6 and 8 for hash value 1
+// By allocating the map with capacity for *at least* the number of rows for entries at the left
+// side, we make sure that we don't have to re-hash the map, which needs access to the key (the
+// hash in this case) value.
 // As the key is a hash value, we need to check possible hash collisions in the probe stage
 // During this stage it might be the case that a row is contained the same hashmap value,
 // but the values don't match. Those are checked in the [equal_rows] macro
-// TODO: speed up collission check and move away from using a hashbrown HashMap
-// https://github.com/apache/arrow-datafusion/issues/50
-struct JoinHashMap(RawTable<(u64, SmallVec<[u64; 1]>)>);
+struct JoinHashMap {
+    map: RawTable<(u64, u64)>,
+    next: Vec<u64>,
+}
+
+impl JoinHashMap {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            map: RawTable::with_capacity(capacity),
+            next: vec![0; capacity],
+        }
+    }
+
+    /// Inserts `row` at the front of the chain of the rows with the hash `hash`
+    fn insert(&mut self, hash: u64, row: usize) {
+        let index = row as u64 + 1;
+        match self.map.get_mut(hash, |(h, _)| *h == hash) {
+            Some((_, head)) => {
+                self.next[row] = *head;
+                *head = index;
+            }
+            None => {
+                self.map.insert(hash, (hash, index), |(h, _)| *h);
+            }
+        }
+    }
+
+    /// Returns the first index, plus one, of the chain of the rows with the
+    /// hash `hash`, or 0 if there is no such row
+    fn head(&self, hash: u64) -> u64 {
+        self.map
+            .get(hash, |(h, _)| *h == hash)
+            .map_or(0, |(_, head)| *head)
+    }
+}
 
 impl fmt::Debug for JoinHashMap {
     fn fmt(&self, _f: &mut fmt::Formatter) -> fmt::Result {
@@ -101,6 +143,13 @@ type JoinLeftData = Arc<(JoinHashMap, RecordBatch)>;
 
 /// join execution plan executes partitions in parallel and combines them into a set of
 /// partitions.
+///
+/// In the [`PartitionMode::Partitioned`] mode, the right input of a partition
+/// may be built instead of the left one, when sampling the inputs at
+/// execution time shows that it is smaller (see
+/// [`SessionConfig::with_adaptive_join`]).
+///
+/// [`SessionConfig::with_adaptive_join`]: crate::execution::context::SessionConfig::with_adaptive_join
 #[derive(Debug)]
 pub struct HashJoinExec {
     /// left (build) side which gets hashed
@@ -115,8 +164,6 @@ pub struct HashJoinExec {
     schema: SchemaRef,
     /// Build-side
     build_side: Arc<Mutex<Option<JoinLeftData>>>,
-    /// Shares the `RandomState` for the hashing algorithm
-    random_state: RandomState,
     /// Partitioning mode to use
     mode: PartitionMode,
     /// Execution metrics
@@ -140,6 +187,9 @@ struct HashJoinMetrics {
     output_batches: metrics::Count,
     /// Number of rows produced by this operator
     output_rows: metrics::Count,
+    /// Number of partitions built from the right input, chosen at execution
+    /// time by sampling the inputs
+    swapped_build_sides: metrics::Count,
 }
 
 impl HashJoinMetrics {
@@ -156,12 +206,16 @@ impl HashJoinMetrics {
 
         let output_rows = MetricBuilder::new(metrics).output_rows(partition);
 
+        let swapped_build_sides =
+            MetricBuilder::new(metrics).counter("swapped_build_sides", partition);
+
         Self {
             join_time,
             input_batches,
             input_rows,
             output_batches,
             output_rows,
+            swapped_build_sides,
         }
     }
 }
@@ -200,8 +254,6 @@ impl HashJoinExec {
         let (schema, column_indices) =
             build_join_schema(&left_schema, &right_schema, join_type);
 
-        let random_state = RandomState::with_seeds(0, 0, 0, 0);
-
         Ok(HashJoinExec {
             left,
             right,
@@ -209,7 +261,6 @@ impl HashJoinExec {
             join_type: *join_type,
             schema: Arc::new(schema),
             build_side: Arc::new(Mutex::new(None)),
-            random_state,
             mode: partition_mode,
             metrics: ExecutionPlanMetricsSet::new(),
             column_indices,
@@ -294,10 +345,13 @@ impl ExecutionPlan for HashJoinExec {
         context: Arc<TaskContext>,
     ) -> Result<SendableRecordBatchStream> {
         let on_left = self.on.iter().map(|on| on.0.clone()).collect::<Vec<_>>();
+        let on_right = self.on.iter().map(|on| on.1.clone()).collect::<Vec<_>>();
+        let random_state = context.session_config().hash_random_state();
+        let join_metrics = HashJoinMetrics::new(partition, &self.metrics);
         // we only want to compute the build side once for PartitionMode::CollectLeft
-        let left_data = {
-            match self.mode {
-                PartitionMode::CollectLeft => {
+        let (left_data, right_stream, swapped) = match self.mode {
+            PartitionMode::CollectLeft => {
+                let left_data = {
                     let mut build_side = self.build_side.lock().await;
 
                     match build_side.as_ref() {
@@ -309,113 +363,134 @@ impl ExecutionPlan for HashJoinExec {
                             let merge = CoalescePartitionsExec::new(self.left.clone());
                             let stream = merge.execute(0, context.clone()).await?;
 
-                            // This operation performs 2 steps at once:
-                            // 1. creates a [JoinHashMap] of all batches from the stream
-                            // 2. stores the batches in a vector.
-                            let initial = (0, Vec::new());
-                            let (num_rows, batches) = stream
-                                .try_fold(initial, |mut acc, batch| async {
-                                    acc.0 += batch.num_rows();
-                                    acc.1.push(batch);
-                                    Ok(acc)
-                                })
-                                .await?;
-                            let mut hashmap =
-                                JoinHashMap(RawTable::with_capacity(num_rows));
-                            let mut hashes_buffer = Vec::new();
-                            let mut offset = 0;
-                            for batch in batches.iter() {
-                                hashes_buffer.clear();
-                                hashes_buffer.resize(batch.num_rows(), 0);
-                                update_hash(
-                                    &on_left,
-                                    batch,
-                                    &mut hashmap,
-                                    offset,
-                                    &self.random_state,
-                                    &mut hashes_buffer,
-                                )?;
-                                offset += batch.num_rows();
-                            }
-                            // Merge all batches into a single batch, so we
-                            // can directly index into the arrays
-                            let single_batch =
-                                concat_batches(&self.left.schema(), &batches, num_rows)?;
-
-                            let left_side = Arc::new((hashmap, single_batch));
+                            let left_side = collect_build_side(
+                                stream,
+                                &on_left,
+                                &self.left.schema(),
+                                &random_state,
+                            )
+                            .await?;
 
                             *build_side = Some(left_side.clone());
 
                             debug!(
                                 "Built build-side of hash join containing {} rows in {} ms",
-                                num_rows,
+                                left_side.1.num_rows(),
                                 start.elapsed().as_millis()
                             );
 
                             left_side
                         }
                     }
+                };
+                let right_stream = self.right.execute(partition, context.clone()).await?;
+                (left_data, right_stream, false)
+            }
+            PartitionMode::Partitioned => {
+                let start = Instant::now();
+
+                let mut left_stream =
+                    self.left.execute(partition, context.clone()).await?;
+                let mut right_stream =
+                    self.right.execute(partition, context.clone()).await?;
+
+                // sample both sides of the partition to build the one that
+                // turns out to be the smallest
+                let mut swapped = false;
+                if let Some((sample_batches, sample_bytes)) = context
+                    .session_config()
+                    .adaptive_join()
+                    .filter(|_| supports_swap(self.join_type))
+                {
+                    let tracking_metrics = || {
+                        MemTrackingMetrics::new_with_rt(
+                            &ExecutionPlanMetricsSet::new(),
+                            partition,
+                            context.runtime_env(),
+                        )
+                        .with_name("HashJoinExec(sample)")
+                    };
+                    let left_sample = JoinInputSample::read(
+                        &mut left_stream,
+                        sample_batches,
+                        sample_bytes,
+                        tracking_metrics(),
+                    )
+                    .await?;
+                    let right_sample = JoinInputSample::read(
+                        &mut right_stream,
+                        sample_batches,
+                        sample_bytes,
+                        tracking_metrics(),
+                    )
+                    .await?;
+                    swapped = should_swap_build_side(&left_sample, &right_sample);
+                    left_stream = left_sample.into_stream(left_stream);
+                    right_stream = right_sample.into_stream(right_stream);
                 }
-                PartitionMode::Partitioned => {
-                    let start = Instant::now();
-
-                    // Load 1 partition of left side in memory
-                    let stream = self.left.execute(partition, context.clone()).await?;
-
-                    // This operation performs 2 steps at once:
-                    // 1. creates a [JoinHashMap] of all batches from the stream
-                    // 2. stores the batches in a vector.
-                    let initial = (0, Vec::new());
-                    let (num_rows, batches) = stream
-                        .try_fold(initial, |mut acc, batch| async {
-                            acc.0 += batch.num_rows();
-                            acc.1.push(batch);
-                            Ok(acc)
-                        })
-                        .await?;
-                    let mut hashmap = JoinHashMap(RawTable::with_capacity(num_rows));
-                    let mut hashes_buffer = Vec::new();
-                    let mut offset = 0;
-                    for batch in batches.iter() {
-                        hashes_buffer.clear();
-                        hashes_buffer.resize(batch.num_rows(), 0);
-                        update_hash(
-                            &on_left,
-                            batch,
-                            &mut hashmap,
-                            offset,
-                            &self.random_state,
-                            &mut hashes_buffer,
-                        )?;
-                        offset += batch.num_rows();
-                    }
-                    // Merge all batches into a single batch, so we
-                    // can directly index into the arrays
-                    let single_batch =
-                        concat_batches(&self.left.schema(), &batches, num_rows)?;
-
-                    let left_side = Arc::new((hashmap, single_batch));
-
-                    debug!(
-                        "Built build-side {} of hash join containing {} rows in {} ms",
-                        partition,
-                        num_rows,
-                        start.elapsed().as_millis()
-                    );
 
-                    left_side
-                }
+                // Load 1 partition of the build side in memory
+                let (build_stream, probe_stream, build_on, build_schema) = if swapped {
+                    join_metrics.swapped_build_sides.add(1);
+                    (right_stream, left_stream, &on_right, self.right.schema())
+                } else {
+                    (left_stream, right_stream, &on_left, self.left.schema())
+                };
+                let build_data = collect_build_side(
+                    build_stream,
+                    build_on,
+                    &build_schema,
+                    &random_state,
+                )
+                .await?;
+
+                debug!(
+                    "Built build-side {} of hash join containing {} rows in {} ms{}",
+                    partition,
+                    build_data.1.num_rows(),
+                    start.elapsed().as_millis(),
+                    if swapped { " from the right input" } else { "" }
+                );
+
+                (build_data, probe_stream, swapped)
             }
         };
 
+        // When the sides are swapped, the right input is the build side of
+        // the join stream, with the keys, the join type and the sides of the
+        // output columns swapped accordingly
+        let (on_left, on_right, join_type, column_indices) = if swapped {
+            let column_indices = self
+                .column_indices
+                .iter()
+                .map(|column_index| ColumnIndex {
+                    index: column_index.index,
+                    side: match column_index.side {
+                        JoinSide::Left => JoinSide::Right,
+                        JoinSide::Right => JoinSide::Left,
+                    },
+                })
+                .collect();
+            (
+                on_right,
+                on_left,
+                swap_join_type(self.join_type),
+                column_indices,
+            )
+        } else {
+            (
+                on_left,
+                on_right,
+                self.join_type,
+                self.column_indices.clone(),
+            )
+        };
+
         // we have the batches and the hash map with their keys. We can how create a stream
         // over the right that uses this information to issue new batches.
 
-        let right_stream = self.right.execute(partition, context.clone()).await?;
-        let on_right = self.on.iter().map(|on| on.1.clone()).collect::<Vec<_>>();
-
         let num_rows = left_data.1.num_rows();
-        let visited_left_side = match self.join_type {
+        let visited_left_side = match join_type {
             JoinType::Left | JoinType::Full | JoinType::Semi | JoinType::Anti => {
                 let mut buffer = BooleanBufferBuilder::new(num_rows);
 
@@ -429,13 +504,13 @@ impl ExecutionPlan for HashJoinExec {
             self.schema.clone(),
             on_left,
             on_right,
-            self.join_type,
+            join_type,
             left_data,
             right_stream,
-            self.column_indices.clone(),
-            self.random_state.clone(),
+            column_indices,
+            random_state,
             visited_left_side,
-            HashJoinMetrics::new(partition, &self.metrics),
+            join_metrics,
             self.null_equals_null,
         )))
     }
@@ -469,7 +544,8 @@ impl ExecutionPlan for HashJoinExec {
 }
 
 /// Updates `hash` with new entries from [RecordBatch] evaluated against the expressions `on`,
-/// assuming that the [RecordBatch] corresponds to the `index`th
+/// assuming that the [RecordBatch] starts at the row `offset` of the build side, and that the
+/// rows after this batch have already been inserted
 fn update_hash(
     on: &[Column],
     batch: &RecordBatch,
@@ -487,24 +563,163 @@ fn update_hash(
     // calculate the hash values
     let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
 
-    // insert hashes to key of the hashmap
-    for (row, hash_value) in hash_values.iter().enumerate() {
-        let item = hash_map
-            .0
-            .get_mut(*hash_value, |(hash, _)| *hash_value == *hash);
-        if let Some((_, indices)) = item {
-            indices.push((row + offset) as u64);
-        } else {
-            hash_map.0.insert(
-                *hash_value,
-                (*hash_value, smallvec![(row + offset) as u64]),
-                |(hash, _)| *hash,
-            );
-        }
+    // insert the rows in reverse order at the front of the chains of their hashes
+    for (row, hash_value) in hash_values.iter().enumerate().rev() {
+        hash_map.insert(*hash_value, row + offset);
     }
     Ok(())
 }
 
+/// Collects the build side of a join from `stream`, as a single batch of all
+/// its rows of `schema` and a [JoinHashMap] of their keys `on`
+async fn collect_build_side(
+    stream: SendableRecordBatchStream,
+    on: &[Column],
+    schema: &SchemaRef,
+    random_state: &RandomState,
+) -> Result<JoinLeftData> {
+    // This operation performs 2 steps at once:
+    // 1. creates a [JoinHashMap] of all batches from the stream
+    // 2. stores the batches in a vector.
+    let initial = (0, Vec::new());
+    let (num_rows, batches) = stream
+        .try_fold(initial, |mut acc, batch| async {
+            acc.0 += batch.num_rows();
+            acc.1.push(batch);
+            Ok(acc)
+        })
+        .await?;
+    let mut hashmap = JoinHashMap::with_capacity(num_rows);
+    let mut hashes_buffer = Vec::new();
+    // the rows are inserted in reverse order, so that the chains
+    // list the rows in order
+    let mut offset = num_rows;
+    for batch in batches.iter().rev() {
+        offset -= batch.num_rows();
+        hashes_buffer.clear();
+        hashes_buffer.resize(batch.num_rows(), 0);
+        update_hash(
+            on,
+            batch,
+            &mut hashmap,
+            offset,
+            random_state,
+            &mut hashes_buffer,
+        )?;
+    }
+    // Merge all batches into a single batch, so we
+    // can directly index into the arrays
+    let single_batch = concat_batches(schema, &batches, num_rows)?;
+
+    Ok(Arc::new((hashmap, single_batch)))
+}
+
+/// The first batches of an input of a hash join, read to compare the sizes
+/// of the inputs before choosing the build side
+struct JoinInputSample {
+    batches: Vec<RecordBatch>,
+    num_bytes: usize,
+    /// True if the sample holds the whole input
+    complete: bool,
+    /// The registration of the sampled batches with the memory manager
+    tracking_metrics: MemTrackingMetrics,
+}
+
+impl JoinInputSample {
+    /// Reads the batches of `stream` until it ends, or `max_batches` batches
+    /// or `max_bytes` bytes are read, or the memory pool can't hold the
+    /// sampled batches, which are registered with `tracking_metrics`
+    async fn read(
+        stream: &mut SendableRecordBatchStream,
+        max_batches: usize,
+        max_bytes: usize,
+        tracking_metrics: MemTrackingMetrics,
+    ) -> Result<Self> {
+        let mut sample = Self {
+            batches: vec![],
+            num_bytes: 0,
+            complete: false,
+            tracking_metrics,
+        };
+        while sample.batches.len() < max_batches && sample.num_bytes < max_bytes {
+            match stream.next().await {
+                Some(batch) => {
+                    let batch = batch?;
+                    sample.num_bytes += batch_byte_size(&batch);
+                    sample.batches.push(batch);
+                    // the batch is already read, so it is kept in the
+                    // sample, but no more batches are sampled
+                    let tracking_metrics = &sample.tracking_metrics;
+                    if tracking_metrics.try_set_mem_used(sample.num_bytes).is_err() {
+                        tracking_metrics.set_mem_used(sample.num_bytes);
+                        break;
+                    }
+                }
+                None => {
+                    sample.complete = true;
+                    break;
+                }
+            }
+        }
+        Ok(sample)
+    }
+
+    /// Returns the stream of the sampled batches followed by the rest of
+    /// their input `stream`
+    fn into_stream(self, stream: SendableRecordBatchStream) -> SendableRecordBatchStream {
+        Box::pin(SampledStream {
+            sample: self.batches.into(),
+            num_bytes: self.num_bytes,
+            tracking_metrics: self.tracking_metrics,
+            input: stream,
+        })
+    }
+}
+
+/// Returns true if the right input of a hash join should be the build side
+/// rather than the left input, given their samples: when the right input
+/// ends within its sample while the left input does not, or when both do and
+/// the right input is smaller. Otherwise the inputs are too big to compare,
+/// and the build side chosen by the planner is kept.
+fn should_swap_build_side(left: &JoinInputSample, right: &JoinInputSample) -> bool {
+    right.complete && (!left.complete || right.num_bytes < left.num_bytes)
+}
+
+/// A stream of the batches sampled from an input, followed by the rest of
+/// the input. The sampled batches are unregistered from the memory manager
+/// as they are consumed.
+struct SampledStream {
+    sample: VecDeque<RecordBatch>,
+    /// The size of the batches left in `sample`
+    num_bytes: usize,
+    tracking_metrics: MemTrackingMetrics,
+    input: SendableRecordBatchStream,
+}
+
+impl Stream for SampledStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        match self.sample.pop_front() {
+            Some(batch) => {
+                self.num_bytes -= batch_byte_size(&batch);
+                self.tracking_metrics.set_mem_used(self.num_bytes);
+                std::task::Poll::Ready(Some(Ok(batch)))
+            }
+            None => self.input.poll_next_unpin(cx),
+        }
+    }
+}
+
+impl RecordBatchStream for SampledStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
 /// A stream that issues [RecordBatch]es as they arrive from the right  of the join.
 struct HashJoinStream {
     /// Input schema
@@ -586,38 +801,108 @@ fn build_batch_from_indices(
 ) -> ArrowResult<(RecordBatch, UInt64Array)> {
     // build the columns of the new [RecordBatch]:
     // 1. pick whether the column is from the left or right
-    // 2. based on the pick, `take` items from the different RecordBatches
+    // 2. based on the pick, gather the rows from the different RecordBatches,
+    //    in the way chosen once per side from the indices
+    let left_gather = Gather::new(&left_indices);
+    let right_gather = Gather::new(&right_indices);
     let mut columns: Vec<Arc<dyn Array>> = Vec::with_capacity(schema.fields().len());
 
     for column_index in column_indices {
         let array = match column_index.side {
-            JoinSide::Left => {
-                let array = left.column(column_index.index);
-                if array.is_empty() || left_indices.null_count() == left_indices.len() {
-                    // Outer join would generate a null index when finding no match at our side.
-                    // Therefore, it's possible we are empty but need to populate an n-length null array,
-                    // where n is the length of the index array.
-                    assert_eq!(left_indices.null_count(), left_indices.len());
-                    new_null_array(array.data_type(), left_indices.len())
-                } else {
-                    compute::take(array.as_ref(), &left_indices, None)?
-                }
-            }
-            JoinSide::Right => {
-                let array = right.column(column_index.index);
-                if array.is_empty() || right_indices.null_count() == right_indices.len() {
-                    assert_eq!(right_indices.null_count(), right_indices.len());
-                    new_null_array(array.data_type(), right_indices.len())
-                } else {
-                    compute::take(array.as_ref(), &right_indices, None)?
-                }
-            }
+            JoinSide::Left => left_gather
+                .apply(left.column(column_index.index), |array| {
+                    compute::take(array, &left_indices, None)
+                })?,
+            JoinSide::Right => right_gather
+                .apply(right.column(column_index.index), |array| {
+                    compute::take(array, &right_indices, None)
+                })?,
         };
         columns.push(array);
     }
     RecordBatch::try_new(Arc::new(schema.clone()), columns).map(|x| (x, left_indices))
 }
 
+/// Minimum average length of the runs of consecutive indices, for the rows
+/// of a join side to be gathered by concatenating slices rather than `take`n
+const MIN_AVG_RUN_LEN: usize = 8;
+
+/// How the rows of one side of a join are gathered into an output batch.
+///
+/// The indices are inspected once per output batch, and the same gather is
+/// applied to every column of the side. Runs of consecutive rows (e.g. when
+/// the keys of both sides are unique and sorted) are sliced without copying
+/// when there is a single one, and concatenated otherwise, which preserves
+/// dictionary encoded columns like `take` does.
+#[derive(Debug, PartialEq)]
+enum Gather {
+    /// All the indices are null: the side has no matching row.
+    ///
+    /// Outer joins generate a null index when finding no match at one side,
+    /// even if this side is empty, so the output is an n-length null array,
+    /// where n is the length of the index array.
+    Nulls(usize),
+    /// The indices are runs of consecutive rows, as `(offset, len)` pairs
+    Runs(Vec<(usize, usize)>),
+    /// The indices are too scattered, or partially null
+    Take,
+}
+
+impl Gather {
+    fn new<T>(indices: &PrimitiveArray<T>) -> Self
+    where
+        T: ArrowPrimitiveType,
+        T::Native: Into<u64>,
+    {
+        let len = indices.len();
+        if indices.null_count() == len {
+            return Self::Nulls(len);
+        }
+        if indices.null_count() > 0 {
+            return Self::Take;
+        }
+
+        let max_runs = (len / MIN_AVG_RUN_LEN).max(1);
+        let mut runs: Vec<(usize, usize)> = vec![];
+        for index in indices.values() {
+            let index = match usize::try_from((*index).into()) {
+                Ok(index) => index,
+                Err(_) => return Self::Take,
+            };
+            match runs.last_mut() {
+                Some((offset, run_len)) if *offset + *run_len == index => *run_len += 1,
+                _ if runs.len() == max_runs => return Self::Take,
+                _ => runs.push((index, 1)),
+            }
+        }
+        Self::Runs(runs)
+    }
+
+    /// Gathers the rows of `array`, with `take` if they are not in runs
+    fn apply(
+        &self,
+        array: &ArrayRef,
+        take: impl FnOnce(&dyn Array) -> ArrowResult<ArrayRef>,
+    ) -> ArrowResult<ArrayRef> {
+        match self {
+            Self::Nulls(len) => Ok(new_null_array(array.data_type(), *len)),
+            Self::Runs(runs) if runs.len() == 1 => {
+                let (offset, len) = runs[0];
+                Ok(array.slice(offset, len))
+            }
+            Self::Runs(runs) => {
+                let slices = runs
+                    .iter()
+                    .map(|(offset, len)| array.slice(*offset, *len))
+                    .collect::<Vec<_>>();
+                let slices = slices.iter().map(|a| a.as_ref()).collect::<Vec<_>>();
+                compute::concat(&slices)
+            }
+            Self::Take => take(array.as_ref()),
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn build_batch(
     batch: &RecordBatch,
@@ -706,115 +991,68 @@ fn build_join_indexes(
     let hash_values = create_hashes(&keys_values, random_state, hashes_buffer)?;
     let left = &left_data.0;
 
-    match join_type {
-        JoinType::Inner | JoinType::Semi | JoinType::Anti => {
-            // Using a buffer builder to avoid slower normal builder
-            let mut left_indices = UInt64BufferBuilder::new(0);
-            let mut right_indices = UInt32BufferBuilder::new(0);
-
-            // Visit all of the right rows
-            for (row, hash_value) in hash_values.iter().enumerate() {
-                // Get the hash and find it in the build index
-
-                // For every item on the left and right we check if it matches
-                // This possibly contains rows with hash collisions,
-                // So we have to check here whether rows are equal or not
-                if let Some((_, indices)) =
-                    left.0.get(*hash_value, |(hash, _)| *hash_value == *hash)
-                {
-                    for &i in indices {
-                        // Check hash collisions
-                        if equal_rows(
-                            i as usize,
-                            row,
-                            &left_join_values,
-                            &keys_values,
-                            *null_equals_null,
-                        )? {
-                            left_indices.append(i);
-                            right_indices.append(row as u32);
-                        }
-                    }
-                }
-            }
-            let left = ArrayData::builder(DataType::UInt64)
-                .len(left_indices.len())
-                .add_buffer(left_indices.finish())
-                .build()
-                .unwrap();
-            let right = ArrayData::builder(DataType::UInt32)
-                .len(right_indices.len())
-                .add_buffer(right_indices.finish())
-                .build()
-                .unwrap();
-
-            Ok((
-                PrimitiveArray::<UInt64Type>::from(left),
-                PrimitiveArray::<UInt32Type>::from(right),
-            ))
+    // Probe the hash map with all the rows at once, finding the chains of the
+    // left rows that may match each right row
+    let heads = hash_values
+        .iter()
+        .map(|hash_value| left.head(*hash_value))
+        .collect::<Vec<_>>();
+
+    // Follow the chains, emitting all the candidate pairs of rows
+    let mut candidates_left = Vec::with_capacity(heads.len());
+    let mut candidates_right = Vec::with_capacity(heads.len());
+    for (row, head) in heads.into_iter().enumerate() {
+        let mut index = head;
+        while index != 0 {
+            candidates_left.push(index - 1);
+            candidates_right.push(row as u32);
+            index = left.next[(index - 1) as usize];
         }
-        JoinType::Left => {
-            let mut left_indices = UInt64Builder::new(0);
-            let mut right_indices = UInt32Builder::new(0);
-
-            // First visit all of the rows
-            for (row, hash_value) in hash_values.iter().enumerate() {
-                if let Some((_, indices)) =
-                    left.0.get(*hash_value, |(hash, _)| *hash_value == *hash)
-                {
-                    for &i in indices {
-                        // Collision check
-                        if equal_rows(
-                            i as usize,
-                            row,
-                            &left_join_values,
-                            &keys_values,
-                            *null_equals_null,
-                        )? {
-                            left_indices.append_value(i)?;
-                            right_indices.append_value(row as u32)?;
-                        }
-                    }
-                };
-            }
-            Ok((left_indices.finish(), right_indices.finish()))
+    }
+
+    // The chains possibly contain rows with hash collisions,
+    // so we have to check here whether rows are equal or not
+    let mut left_indices = Vec::with_capacity(candidates_left.len());
+    let mut right_indices = Vec::with_capacity(candidates_right.len());
+    for (i, row) in candidates_left.into_iter().zip(candidates_right) {
+        if equal_rows(
+            i as usize,
+            row as usize,
+            &left_join_values,
+            &keys_values,
+            *null_equals_null,
+        )? {
+            left_indices.push(i);
+            right_indices.push(row);
         }
+    }
+
+    match join_type {
+        JoinType::Inner | JoinType::Left | JoinType::Semi | JoinType::Anti => Ok((
+            UInt64Array::from(left_indices),
+            UInt32Array::from(right_indices),
+        )),
         JoinType::Right | JoinType::Full => {
-            let mut left_indices = UInt64Builder::new(0);
-            let mut right_indices = UInt32Builder::new(0);
-
-            for (row, hash_value) in hash_values.iter().enumerate() {
-                match left.0.get(*hash_value, |(hash, _)| *hash_value == *hash) {
-                    Some((_, indices)) => {
-                        let mut no_match = true;
-                        for &i in indices {
-                            if equal_rows(
-                                i as usize,
-                                row,
-                                &left_join_values,
-                                &keys_values,
-                                *null_equals_null,
-                            )? {
-                                left_indices.append_value(i)?;
-                                right_indices.append_value(row as u32)?;
-                                no_match = false;
-                            }
-                        }
-                        // If no rows matched left, still must keep the right
-                        // with all nulls for left
-                        if no_match {
-                            left_indices.append_null()?;
-                            right_indices.append_value(row as u32)?;
-                        }
-                    }
-                    None => {
-                        // when no match, add the row with None for the left side
-                        left_indices.append_null()?;
-                        right_indices.append_value(row as u32)?;
-                    }
+            let mut left_builder = UInt64Builder::new(left_indices.len());
+            let mut right_builder = UInt32Builder::new(right_indices.len());
+
+            // The matches are ordered by right row
+            let mut matches = left_indices.into_iter().zip(right_indices).peekable();
+            for row in 0..right.num_rows() as u32 {
+                let mut no_match = true;
+                while let Some((i, _)) = matches.next_if(|(_, r)| *r == row) {
+                    left_builder.append_value(i)?;
+                    right_builder.append_value(row)?;
+                    no_match = false;
+                }
+                // If no rows matched left, still must keep the right
+                // with all nulls for left
+                if no_match {
+                    left_builder.append_null()?;
+                    right_builder.append_value(row)?;
                 }
             }
-            Ok((left_indices.finish(), right_indices.finish()))
+            Ok((left_builder.finish(), right_builder.finish()))
         }
     }
 }
@@ -921,6 +1159,48 @@ fn equal_rows(
                     )
                 }
             },
+            DataType::Duration(time_unit) => match time_unit {
+                TimeUnit::Second => {
+                    equal_rows_elem!(
+                        DurationSecondArray,
+                        l,
+                        r,
+                        left,
+                        right,
+                        null_equals_null
+                    )
+                }
+                TimeUnit::Millisecond => {
+                    equal_rows_elem!(
+                        DurationMillisecondArray,
+                        l,
+                        r,
+                        left,
+                        right,
+                        null_equals_null
+                    )
+                }
+                TimeUnit::Microsecond => {
+                    equal_rows_elem!(
+                        DurationMicrosecondArray,
+                        l,
+                        r,
+                        left,
+                        right,
+                        null_equals_null
+                    )
+                }
+                TimeUnit::Nanosecond => {
+                    equal_rows_elem!(
+                        DurationNanosecondArray,
+                        l,
+                        r,
+                        left,
+                        right,
+                        null_equals_null
+                    )
+                }
+            },
             DataType::Utf8 => {
                 equal_rows_elem!(StringArray, l, r, left, right, null_equals_null)
             }
@@ -1077,7 +1357,10 @@ mod tests {
     };
 
     use super::*;
-    use crate::prelude::SessionContext;
+    use crate::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use crate::prelude::{SessionConfig, SessionContext};
+    use arrow::array::DictionaryArray;
+    use arrow::datatypes::{Field, Int32Type};
     use std::sync::Arc;
 
     fn build_table(
@@ -1734,6 +2017,141 @@ mod tests {
         Ok(())
     }
 
+    /// Joins two batches at the left with one batch at the right in a single
+    /// partition, returning the output and whether the sides were swapped
+    async fn adaptive_join_collect(
+        join_type: &JoinType,
+        config: SessionConfig,
+    ) -> Result<(Vec<RecordBatch>, bool)> {
+        let runtime = Arc::new(RuntimeEnv::new(RuntimeConfig::new())?);
+        adaptive_join_collect_with_runtime(join_type, config, runtime).await
+    }
+
+    async fn adaptive_join_collect_with_runtime(
+        join_type: &JoinType,
+        config: SessionConfig,
+        runtime: Arc<RuntimeEnv>,
+    ) -> Result<(Vec<RecordBatch>, bool)> {
+        let session_ctx = SessionContext::with_config_rt(config, runtime);
+        let left = build_table_two_batches(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 7]), // 7 does not exist on the right
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30]),
+            ("b1", &vec![4, 5, 6]), // 6 does not exist on the left
+            ("c2", &vec![70, 80, 90]),
+        );
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b1", &right.schema())?,
+        )];
+        let join = HashJoinExec::try_new(
+            left,
+            right,
+            on,
+            join_type,
+            PartitionMode::Partitioned,
+            &false,
+        )?;
+        let stream = join.execute(0, session_ctx.task_ctx()).await?;
+        let batches = common::collect(stream).await?;
+        let swapped = join
+            .metrics()
+            .unwrap()
+            .sum_by_name("swapped_build_sides")
+            .unwrap()
+            .as_usize();
+        Ok((batches, swapped == 1))
+    }
+
+    #[tokio::test]
+    async fn adaptive_join_swaps_build_side() -> Result<()> {
+        // the right input ends within its sample, unlike the left input
+        let config = SessionConfig::new().with_adaptive_join(2, usize::MAX);
+
+        let (batches, swapped) =
+            adaptive_join_collect(&JoinType::Left, config.clone()).await?;
+        assert!(swapped);
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let (batches, swapped) =
+            adaptive_join_collect(&JoinType::Full, config.clone()).await?;
+        assert!(swapped);
+        let expected = vec![
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "|    |    |    | 30 | 6  | 90 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 1  | 4  | 7  | 10 | 4  | 70 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 2  | 5  | 8  | 20 | 5  | 80 |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "| 3  | 7  | 9  |    |    |    |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // semi joins can't be swapped
+        let (batches, swapped) = adaptive_join_collect(&JoinType::Semi, config).await?;
+        assert!(!swapped);
+        let expected = vec![
+            "+----+----+----+",
+            "| a1 | b1 | c1 |",
+            "+----+----+----+",
+            "| 1  | 4  | 7  |",
+            "| 1  | 4  | 7  |",
+            "| 2  | 5  | 8  |",
+            "| 2  | 5  | 8  |",
+            "+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // the inputs are not sampled at all, so they can't be compared
+        let config = SessionConfig::new().with_adaptive_join(1, 0);
+        let (batches, swapped) = adaptive_join_collect(&JoinType::Inner, config).await?;
+        assert!(!swapped);
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 4);
+
+        // nor when the memory pool is full
+        let runtime = Arc::new(RuntimeEnv::new(
+            RuntimeConfig::new().with_memory_limit(1, 1.0),
+        )?);
+        let config = SessionConfig::new().with_adaptive_join(2, usize::MAX);
+        let (batches, swapped) =
+            adaptive_join_collect_with_runtime(&JoinType::Inner, config, runtime.clone())
+                .await?;
+        assert!(!swapped);
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 4);
+        // the sampled batches are released once consumed
+        assert!(runtime.memory_manager.top_consumers(1).is_empty());
+
+        // at least one batch is sampled
+        let config = SessionConfig::new().with_adaptive_join(0, usize::MAX);
+        assert_eq!(config.adaptive_join(), Some((1, usize::MAX)));
+
+        // disabled by default
+        let (_, swapped) =
+            adaptive_join_collect(&JoinType::Inner, SessionConfig::new()).await?;
+        assert!(!swapped);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_semi() -> Result<()> {
         let session_ctx = SessionContext::new();
@@ -1937,7 +2355,7 @@ mod tests {
 
     #[test]
     fn join_with_hash_collision() -> Result<()> {
-        let mut hashmap_left = RawTable::with_capacity(2);
+        let mut hashmap_left = JoinHashMap::with_capacity(2);
         let left = build_table_i32(
             ("a", &vec![10, 20]),
             ("x", &vec![100, 200]),
@@ -1949,9 +2367,15 @@ mod tests {
         let hashes =
             create_hashes(&[left.columns()[0].clone()], &random_state, hashes_buff)?;
 
-        // Create hash collisions (same hashes)
-        hashmap_left.insert(hashes[0], (hashes[0], smallvec![0, 1]), |(h, _)| *h);
-        hashmap_left.insert(hashes[1], (hashes[1], smallvec![0, 1]), |(h, _)| *h);
+        // Create hash collisions (same hashes): both hashes lead to the chain
+        // of the rows 0 and 1
+        hashmap_left
+            .map
+            .insert(hashes[0], (hashes[0], 1), |(h, _)| *h);
+        hashmap_left
+            .map
+            .insert(hashes[1], (hashes[1], 1), |(h, _)| *h);
+        hashmap_left.next[0] = 2;
 
         let right = build_table_i32(
             ("a", &vec![10, 20]),
@@ -1959,7 +2383,7 @@ mod tests {
             ("c", &vec![30, 40]),
         );
 
-        let left_data = JoinLeftData::new((JoinHashMap(hashmap_left), left));
+        let left_data = JoinLeftData::new((hashmap_left, left));
         let (l, r) = build_join_indexes(
             &left_data,
             &right,
@@ -1985,6 +2409,51 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn join_hash_map_chains() -> Result<()> {
+        let left = build_table_i32(
+            ("a", &vec![1, 2, 1, 1]),
+            ("x", &vec![0, 0, 0, 0]),
+            ("y", &vec![0, 0, 0, 0]),
+        );
+        let right = build_table_i32(
+            ("a", &vec![1, 3, 2]),
+            ("b", &vec![0, 0, 0]),
+            ("c", &vec![0, 0, 0]),
+        );
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let on = [Column::new("a", 0)];
+        let mut hashmap_left = JoinHashMap::with_capacity(left.num_rows());
+        update_hash(
+            &on,
+            &left,
+            &mut hashmap_left,
+            0,
+            &random_state,
+            &mut vec![0; left.num_rows()],
+        )?;
+
+        let left_data = JoinLeftData::new((hashmap_left, left));
+        let (l, r) = build_join_indexes(
+            &left_data,
+            &right,
+            JoinType::Full,
+            &on,
+            &on,
+            &random_state,
+            &false,
+        )?;
+
+        // the matches of each right row are listed in the order of the left rows
+        assert_eq!(
+            l,
+            UInt64Array::from(vec![Some(0), Some(2), Some(3), None, Some(1)])
+        );
+        assert_eq!(r, UInt32Array::from(vec![0, 0, 0, 1, 2]));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn join_with_duplicated_column_names() -> Result<()> {
         let session_ctx = SessionContext::new();
@@ -2025,4 +2494,67 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn gather_runs_of_indices() {
+        let contiguous = UInt32Array::from((3..40).collect::<Vec<u32>>());
+        assert_eq!(Gather::new(&contiguous), Gather::Runs(vec![(3, 37)]));
+
+        let runs = (0..16).chain(32..48).collect::<Vec<u64>>();
+        let runs = UInt64Array::from(runs);
+        assert_eq!(Gather::new(&runs), Gather::Runs(vec![(0, 16), (32, 16)]));
+
+        let scattered = UInt64Array::from(vec![4, 2, 8, 1, 0, 9, 3, 7, 5, 6]);
+        assert_eq!(Gather::new(&scattered), Gather::Take);
+
+        let nulls = UInt64Array::from(vec![None, None]);
+        assert_eq!(Gather::new(&nulls), Gather::Nulls(2));
+
+        let some_nulls = UInt32Array::from(vec![Some(0), None, Some(1)]);
+        assert_eq!(Gather::new(&some_nulls), Gather::Take);
+    }
+
+    #[tokio::test]
+    async fn join_preserves_dictionaries() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let left = build_table(
+            ("a1", &(0..20).collect()),
+            ("b1", &(0..20).collect()),
+            ("c1", &(0..20).collect()),
+        );
+
+        let keys = Int32Array::from((0..20).collect::<Vec<i32>>());
+        let values: DictionaryArray<Int32Type> =
+            (0..20).map(|i| ["x", "y"][i % 2]).collect();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("b2", DataType::Int32, false),
+            Field::new("d2", values.data_type().clone(), false),
+        ]));
+        let batch =
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(keys), Arc::new(values)])?;
+        // the right side is probed with two runs of consecutive keys
+        let right = Arc::new(MemoryExec::try_new(
+            &[vec![batch.slice(0, 10), batch.slice(12, 8)]],
+            schema,
+            None,
+        )?);
+
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b2", &right.schema())?,
+        )];
+        let (_, batches) =
+            join_collect(left, right, on, &JoinType::Inner, false, task_ctx).await?;
+
+        let rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(rows, 18);
+        for batch in &batches {
+            assert!(matches!(
+                batch.column(4).data_type(),
+                DataType::Dictionary(_, _)
+            ));
+        }
+        Ok(())
+    }
 }
@@ -21,10 +21,11 @@ use crate::error::{DataFusionError, Result};
 use ahash::{CallHasher, RandomState};
 use arrow::array::{
     Array, ArrayRef, BooleanArray, Date32Array, Date64Array, DecimalArray,
-    DictionaryArray, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
-    Int8Array, LargeStringArray, StringArray, TimestampMicrosecondArray,
-    TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray,
-    UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+    DictionaryArray, DurationMicrosecondArray, DurationMillisecondArray,
+    DurationNanosecondArray, DurationSecondArray, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, Int8Array, LargeStringArray, StringArray,
+    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+    TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
 };
 use arrow::datatypes::{
     ArrowDictionaryKeyType, ArrowNativeType, DataType, Int16Type, Int32Type, Int64Type,
@@ -217,7 +218,7 @@ fn create_hashes_dictionary<K: ArrowDictionaryKeyType>(
     // redundant hashing for large dictionary elements (e.g. strings)
     let dict_values = Arc::clone(dict_array.values());
     let mut dict_hashes = vec![0; dict_values.len()];
-    create_hashes(&[dict_values], random_state, &mut dict_hashes)?;
+    create_hashes(&[Arc::clone(&dict_values)], random_state, &mut dict_hashes)?;
 
     // combine hash for each index in values
     if multi_col {
@@ -231,7 +232,9 @@ fn create_hashes_dictionary<K: ArrowDictionaryKeyType>(
                             key, dict_array.data_type()
                         ))
                     })?;
-                *hash = combine_hashes(dict_hashes[idx], *hash)
+                if dict_values.is_valid(idx) {
+                    *hash = combine_hashes(dict_hashes[idx], *hash)
+                }
             } // no update for Null, consistent with other hashes
         }
     } else {
@@ -245,7 +248,9 @@ fn create_hashes_dictionary<K: ArrowDictionaryKeyType>(
                             key, dict_array.data_type()
                         ))
                     })?;
-                *hash = dict_hashes[idx]
+                if dict_values.is_valid(idx) {
+                    *hash = dict_hashes[idx]
+                }
             } // no update for Null, consistent with other hashes
         }
     }
@@ -427,6 +432,46 @@ pub fn create_hashes<'a>(
                     multi_col
                 );
             }
+            DataType::Duration(TimeUnit::Second) => {
+                hash_array_primitive!(
+                    DurationSecondArray,
+                    col,
+                    i64,
+                    hashes_buffer,
+                    random_state,
+                    multi_col
+                );
+            }
+            DataType::Duration(TimeUnit::Millisecond) => {
+                hash_array_primitive!(
+                    DurationMillisecondArray,
+                    col,
+                    i64,
+                    hashes_buffer,
+                    random_state,
+                    multi_col
+                );
+            }
+            DataType::Duration(TimeUnit::Microsecond) => {
+                hash_array_primitive!(
+                    DurationMicrosecondArray,
+                    col,
+                    i64,
+                    hashes_buffer,
+                    random_state,
+                    multi_col
+                );
+            }
+            DataType::Duration(TimeUnit::Nanosecond) => {
+                hash_array_primitive!(
+                    DurationNanosecondArray,
+                    col,
+                    i64,
+                    hashes_buffer,
+                    random_state,
+                    multi_col
+                );
+            }
             DataType::Date32 => {
                 hash_array_primitive!(
                     Date32Array,
@@ -678,4 +723,30 @@ mod tests {
 
         assert_ne!(one_col_hashes, two_col_hashes);
     }
+
+    #[test]
+    // Tests actual values of hashes, which are different if forcing collisions
+    #[cfg(not(feature = "force_hash_collisions"))]
+    fn create_multi_column_hash_for_dict_arrays_with_null_values() {
+        // the second key is null, the third one references a null value
+        let keys = Int8Array::from(vec![Some(0), None, Some(1)]);
+        let values = StringArray::from(vec![Some("foo"), None]);
+        let dict_array: ArrayRef =
+            Arc::new(DictionaryArray::<Int8Type>::try_new(&keys, &values).unwrap());
+        let int_array: ArrayRef = Arc::new(Int32Array::from_slice(&[1, 1, 1]));
+
+        let random_state = RandomState::with_seeds(0, 0, 0, 0);
+
+        let mut one_col_hashes = vec![0; 3];
+        create_hashes(&[dict_array.clone()], &random_state, &mut one_col_hashes).unwrap();
+        assert_eq!(one_col_hashes[1], 0);
+        assert_eq!(one_col_hashes[2], 0);
+
+        // both nulls hash as the nulls of the other arrays
+        let mut two_col_hashes = vec![0; 3];
+        create_hashes(&[int_array, dict_array], &random_state, &mut two_col_hashes)
+            .unwrap();
+        assert_ne!(two_col_hashes[0], two_col_hashes[1]);
+        assert_eq!(two_col_hashes[1], two_col_hashes[2]);
+    }
 }
@@ -0,0 +1,313 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Inspection of execution plans: finding their nodes by type, listing the
+//! files they scan and summarizing them, without chains of downcasts.
+//!
+//! The nodes are walked depth first, a node before its children, as
+//! [`accept`](super::accept) visits them.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::file_format::{AvroExec, CsvExec, FileScanConfig, NdJsonExec, ParquetExec};
+use super::{displayable, ExecutionPlan};
+
+/// Returns `plan` as a node of type `T`, if it is one
+pub fn downcast_plan<T: ExecutionPlan + 'static>(plan: &dyn ExecutionPlan) -> Option<&T> {
+    plan.as_any().downcast_ref::<T>()
+}
+
+/// Calls `f` on each node of type `T` of `plan`
+pub fn for_each_plan<T, F>(plan: &dyn ExecutionPlan, f: &mut F)
+where
+    T: ExecutionPlan + 'static,
+    F: FnMut(&T),
+{
+    if let Some(node) = downcast_plan::<T>(plan) {
+        f(node);
+    }
+    for child in plan.children() {
+        for_each_plan(child.as_ref(), f);
+    }
+}
+
+/// Returns the nodes of type `T` of `plan`, which can be downcast with
+/// [`downcast_plan`]
+pub fn find_plans<T: ExecutionPlan + 'static>(
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Vec<Arc<dyn ExecutionPlan>> {
+    let mut plans = vec![];
+    find_plans_internal::<T>(plan, &mut plans);
+    plans
+}
+
+fn find_plans_internal<T: ExecutionPlan + 'static>(
+    plan: &Arc<dyn ExecutionPlan>,
+    plans: &mut Vec<Arc<dyn ExecutionPlan>>,
+) {
+    if downcast_plan::<T>(plan.as_ref()).is_some() {
+        plans.push(plan.clone());
+    }
+    for child in plan.children() {
+        find_plans_internal::<T>(&child, plans);
+    }
+}
+
+/// Whether `plan` has a node of type `T`
+pub fn contains_plan<T: ExecutionPlan + 'static>(plan: &dyn ExecutionPlan) -> bool {
+    downcast_plan::<T>(plan).is_some()
+        || plan
+            .children()
+            .iter()
+            .any(|child| contains_plan::<T>(child.as_ref()))
+}
+
+/// Returns the configuration of `plan` if it scans files, i.e. if it is a
+/// [`ParquetExec`], a [`CsvExec`], an [`NdJsonExec`] or an [`AvroExec`]
+pub fn file_scan_config(plan: &dyn ExecutionPlan) -> Option<&FileScanConfig> {
+    if let Some(exec) = downcast_plan::<ParquetExec>(plan) {
+        Some(exec.base_config())
+    } else if let Some(exec) = downcast_plan::<CsvExec>(plan) {
+        Some(exec.base_config())
+    } else if let Some(exec) = downcast_plan::<NdJsonExec>(plan) {
+        Some(exec.base_config())
+    } else {
+        downcast_plan::<AvroExec>(plan).map(|exec| exec.base_config())
+    }
+}
+
+/// Returns the nodes of `plan` scanning files, see [`file_scan_config`]
+pub fn find_file_scans(plan: &Arc<dyn ExecutionPlan>) -> Vec<Arc<dyn ExecutionPlan>> {
+    let mut scans = vec![];
+    find_file_scans_internal(plan, &mut scans);
+    scans
+}
+
+fn find_file_scans_internal(
+    plan: &Arc<dyn ExecutionPlan>,
+    scans: &mut Vec<Arc<dyn ExecutionPlan>>,
+) {
+    if file_scan_config(plan.as_ref()).is_some() {
+        scans.push(plan.clone());
+    }
+    for child in plan.children() {
+        find_file_scans_internal(&child, scans);
+    }
+}
+
+/// Returns the paths of the files scanned by `plan`, in the order of its
+/// scans and of their partitions, each file once
+pub fn scanned_files(plan: &dyn ExecutionPlan) -> Vec<String> {
+    let mut files: Vec<String> = vec![];
+    scanned_files_internal(plan, &mut files);
+    files
+}
+
+fn scanned_files_internal(plan: &dyn ExecutionPlan, files: &mut Vec<String>) {
+    if let Some(config) = file_scan_config(plan) {
+        for file in config.file_groups.iter().flatten() {
+            let path = file.file_meta.path();
+            if !files.iter().any(|f| f == path) {
+                files.push(path.to_string());
+            }
+        }
+    }
+    for child in plan.children() {
+        scanned_files_internal(child.as_ref(), files);
+    }
+}
+
+/// A summary of an execution plan, see [`summarize_plan`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanSummary {
+    /// The number of nodes of each operator, by the name of the operator in
+    /// the display of the plan, e.g. `ProjectionExec`
+    pub operators: BTreeMap<String, usize>,
+    /// The number of nodes of the plan
+    pub num_nodes: usize,
+    /// The depth of the plan, 1 for a single node
+    pub depth: usize,
+    /// The number of leaf nodes, e.g. the scans of the plan
+    pub num_leaves: usize,
+    /// The number of files scanned, see [`scanned_files`]
+    pub num_files: usize,
+    /// The estimated number of bytes read by the leaves, from their
+    /// statistics, if known for all of them
+    pub estimated_bytes: Option<usize>,
+    /// The estimated number of rows read by the leaves, from their
+    /// statistics, if known for all of them
+    pub estimated_rows: Option<usize>,
+}
+
+/// Returns the [`PlanSummary`] of `plan`
+pub fn summarize_plan(plan: &dyn ExecutionPlan) -> PlanSummary {
+    let mut summary = PlanSummary {
+        estimated_bytes: Some(0),
+        estimated_rows: Some(0),
+        num_files: scanned_files(plan).len(),
+        ..Default::default()
+    };
+    summarize_plan_internal(plan, 1, &mut summary);
+    summary
+}
+
+fn summarize_plan_internal(
+    plan: &dyn ExecutionPlan,
+    depth: usize,
+    summary: &mut PlanSummary,
+) {
+    *summary.operators.entry(operator_name(plan)).or_default() += 1;
+    summary.num_nodes += 1;
+    summary.depth = summary.depth.max(depth);
+
+    let children = plan.children();
+    if children.is_empty() {
+        summary.num_leaves += 1;
+        let statistics = plan.statistics();
+        summary.estimated_bytes = summary
+            .estimated_bytes
+            .zip(statistics.total_byte_size)
+            .map(|(total, bytes)| total + bytes);
+        summary.estimated_rows = summary
+            .estimated_rows
+            .zip(statistics.num_rows)
+            .map(|(total, rows)| total + rows);
+    }
+    for child in children {
+        summarize_plan_internal(child.as_ref(), depth + 1, summary);
+    }
+}
+
+/// Returns the name of the operator of `plan` in its display, e.g.
+/// `ProjectionExec` for `ProjectionExec: expr=[a@0 as a]`
+pub fn operator_name(plan: &dyn ExecutionPlan) -> String {
+    let line = displayable(plan).one_line().to_string();
+    match line.find(':') {
+        Some(end) => line[..end].trim().to_string(),
+        None => line.trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::listing::PartitionedFile;
+    use crate::error::Result;
+    use crate::logical_plan::JoinType;
+    use crate::physical_plan::expressions::lit;
+    use crate::physical_plan::filter::FilterExec;
+    use crate::physical_plan::hash_join::{HashJoinExec, PartitionMode};
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::{expressions::Column, Statistics};
+    use crate::scalar::ScalarValue;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_data_access::object_store::local::LocalFileSystem;
+
+    /// A scan of `files` of 10 rows and 100 bytes each, if `statistics`
+    fn csv_scan(
+        schema: &Arc<Schema>,
+        files: &[&str],
+        statistics: bool,
+    ) -> Arc<dyn ExecutionPlan> {
+        let statistics = if statistics {
+            Statistics {
+                num_rows: Some(10 * files.len()),
+                total_byte_size: Some(100 * files.len()),
+                column_statistics: None,
+                is_exact: false,
+            }
+        } else {
+            Statistics::default()
+        };
+        Arc::new(CsvExec::new(
+            FileScanConfig {
+                object_store: Arc::new(LocalFileSystem {}),
+                file_schema: schema.clone(),
+                file_groups: files
+                    .iter()
+                    .map(|file| vec![PartitionedFile::new(file.to_string(), 100)])
+                    .collect(),
+                statistics,
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: None,
+            },
+            true,
+            b',',
+        ))
+    }
+
+    #[test]
+    fn inspect_plan() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let left = csv_scan(&schema, &["a.csv", "b.csv"], true);
+        let right: Arc<dyn ExecutionPlan> = Arc::new(FilterExec::try_new(
+            lit(ScalarValue::Boolean(Some(true))),
+            csv_scan(&schema, &["b.csv"], true),
+        )?);
+        let join: Arc<dyn ExecutionPlan> = Arc::new(HashJoinExec::try_new(
+            left,
+            right,
+            vec![(Column::new("a", 0), Column::new("a", 0))],
+            &JoinType::Inner,
+            PartitionMode::CollectLeft,
+            &false,
+        )?);
+
+        assert!(contains_plan::<FilterExec>(join.as_ref()));
+        assert!(!contains_plan::<MemoryExec>(join.as_ref()));
+        let filters = find_plans::<FilterExec>(&join);
+        assert_eq!(filters.len(), 1);
+        assert!(downcast_plan::<FilterExec>(filters[0].as_ref()).is_some());
+        let mut joins = 0;
+        for_each_plan(join.as_ref(), &mut |exec: &HashJoinExec| {
+            assert_eq!(exec.join_type(), &JoinType::Inner);
+            joins += 1;
+        });
+        assert_eq!(joins, 1);
+
+        assert_eq!(find_file_scans(&join).len(), 2);
+        assert_eq!(scanned_files(join.as_ref()), vec!["a.csv", "b.csv"]);
+
+        let summary = summarize_plan(join.as_ref());
+        let operators = summary
+            .operators
+            .iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operators,
+            vec![("CsvExec", 2), ("FilterExec", 1), ("HashJoinExec", 1)]
+        );
+        assert_eq!(summary.num_nodes, 4);
+        assert_eq!(summary.depth, 3);
+        assert_eq!(summary.num_leaves, 2);
+        assert_eq!(summary.num_files, 2);
+        assert_eq!(summary.estimated_bytes, Some(300));
+        assert_eq!(summary.estimated_rows, Some(30));
+
+        // the size of the scans is unknown without their statistics
+        let filter = FilterExec::try_new(
+            lit(ScalarValue::Boolean(Some(true))),
+            csv_scan(&schema, &["a.csv"], false),
+        )?;
+        assert_eq!(summarize_plan(&filter).estimated_bytes, None);
+        Ok(())
+    }
+}
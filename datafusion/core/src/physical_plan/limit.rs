@@ -39,7 +39,7 @@ use arrow::record_batch::RecordBatch;
 use super::expressions::PhysicalSortExpr;
 use super::{
     metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
-    RecordBatchStream, SendableRecordBatchStream, Statistics,
+    RecordBatchStream, SendableRecordBatchStream, Statistics, StatisticsCache,
 };
 
 use crate::execution::context::TaskContext;
@@ -54,6 +54,8 @@ pub struct GlobalLimitExec {
     limit: usize,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
+    /// The statistics, computed on the first request
+    statistics: StatisticsCache,
 }
 
 impl GlobalLimitExec {
@@ -63,6 +65,7 @@ impl GlobalLimitExec {
             input,
             limit,
             metrics: ExecutionPlanMetricsSet::new(),
+            statistics: StatisticsCache::default(),
         }
     }
 
@@ -177,24 +180,26 @@ impl ExecutionPlan for GlobalLimitExec {
     }
 
     fn statistics(&self) -> Statistics {
-        let input_stats = self.input.statistics();
-        match input_stats {
-            // if the input does not reach the limit globally, return input stats
-            Statistics {
-                num_rows: Some(nr), ..
-            } if nr <= self.limit => input_stats,
-            // if the input is greater than the limit, the num_row will be the limit
-            // but we won't be able to predict the other statistics
-            Statistics {
-                num_rows: Some(nr), ..
-            } if nr > self.limit => Statistics {
-                num_rows: Some(self.limit),
-                is_exact: input_stats.is_exact,
-                ..Default::default()
-            },
-            // if we don't know the input size, we can't predict the limit's behaviour
-            _ => Statistics::default(),
-        }
+        self.statistics.get_or_compute(|| {
+            let input_stats = self.input.statistics();
+            match input_stats {
+                // if the input does not reach the limit globally, return input stats
+                Statistics {
+                    num_rows: Some(nr), ..
+                } if nr <= self.limit => input_stats,
+                // if the input is greater than the limit, the num_row will be the limit
+                // but we won't be able to predict the other statistics
+                Statistics {
+                    num_rows: Some(nr), ..
+                } if nr > self.limit => Statistics {
+                    num_rows: Some(self.limit),
+                    is_exact: input_stats.is_exact,
+                    ..Default::default()
+                },
+                // if we don't know the input size, we can't predict the limit's behaviour
+                _ => Statistics::default(),
+            }
+        })
     }
 }
 
@@ -207,6 +212,8 @@ pub struct LocalLimitExec {
     limit: usize,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
+    /// The statistics, computed on the first request
+    statistics: StatisticsCache,
 }
 
 impl LocalLimitExec {
@@ -216,6 +223,7 @@ impl LocalLimitExec {
             input,
             limit,
             metrics: ExecutionPlanMetricsSet::new(),
+            statistics: StatisticsCache::default(),
         }
     }
 
@@ -314,27 +322,29 @@ impl ExecutionPlan for LocalLimitExec {
     }
 
     fn statistics(&self) -> Statistics {
-        let input_stats = self.input.statistics();
-        match input_stats {
-            // if the input does not reach the limit globally, return input stats
-            Statistics {
-                num_rows: Some(nr), ..
-            } if nr <= self.limit => input_stats,
-            // if the input is greater than the limit, the num_row will be greater
-            // than the limit because the partitions will be limited separatly
-            // the statistic
-            Statistics {
-                num_rows: Some(nr), ..
-            } if nr > self.limit => Statistics {
-                num_rows: Some(self.limit),
-                // this is not actually exact, but will be when GlobalLimit is applied
-                // TODO stats: find a more explicit way to vehiculate this information
-                is_exact: input_stats.is_exact,
-                ..Default::default()
-            },
-            // if we don't know the input size, we can't predict the limit's behaviour
-            _ => Statistics::default(),
-        }
+        self.statistics.get_or_compute(|| {
+            let input_stats = self.input.statistics();
+            match input_stats {
+                // if the input does not reach the limit globally, return input stats
+                Statistics {
+                    num_rows: Some(nr), ..
+                } if nr <= self.limit => input_stats,
+                // if the input is greater than the limit, the num_row will be greater
+                // than the limit because the partitions will be limited separatly
+                // the statistic
+                Statistics {
+                    num_rows: Some(nr), ..
+                } if nr > self.limit => Statistics {
+                    num_rows: Some(self.limit),
+                    // this is not actually exact, but will be when GlobalLimit is applied
+                    // TODO stats: find a more explicit way to vehiculate this information
+                    is_exact: input_stats.is_exact,
+                    ..Default::default()
+                },
+                // if we don't know the input size, we can't predict the limit's behaviour
+                _ => Statistics::default(),
+            }
+        })
     }
 }
 
@@ -455,6 +465,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -0,0 +1,283 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! MapBatchesExec replaces each batch of its input by the batch returned by a
+//! Rust function.
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::expressions::PhysicalSortExpr;
+use super::{RecordBatchStream, SendableRecordBatchStream, Statistics};
+use crate::error::{DataFusionError, Result};
+use crate::logical_plan::BatchFunction;
+use crate::physical_plan::{
+    metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+    DisplayFormatType, ExecutionPlan, Partitioning,
+};
+use arrow::datatypes::SchemaRef;
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+
+use async_trait::async_trait;
+
+use crate::execution::context::TaskContext;
+use futures::stream::{Stream, StreamExt};
+
+/// MapBatchesExec transforms each batch of its input with a function, which
+/// must return a batch of the schema of this operator. As the function can
+/// reorder the rows and drop the columns, the partitioning and the ordering
+/// of the input are not preserved.
+pub struct MapBatchesExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// The schema of the batches returned by the function
+    schema: SchemaRef,
+    /// The function transforming the batches
+    fun: BatchFunction,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl MapBatchesExec {
+    /// Create a MapBatchesExec transforming the batches of `input` with `fun`
+    /// into batches of the given `schema`
+    pub fn new(
+        input: Arc<dyn ExecutionPlan>,
+        schema: SchemaRef,
+        fun: BatchFunction,
+    ) -> Self {
+        Self {
+            input,
+            schema,
+            fun,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The function transforming the batches
+    pub fn fun(&self) -> &BatchFunction {
+        &self.fun
+    }
+}
+
+impl std::fmt::Debug for MapBatchesExec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapBatchesExec")
+            .field("input", &self.input)
+            .field("schema", &self.schema)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for MapBatchesExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(
+            self.input.output_partitioning().partition_count(),
+        )
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(MapBatchesExec::new(
+            children[0].clone(),
+            self.schema.clone(),
+            self.fun.clone(),
+        )))
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        Ok(Box::pin(MapBatchesStream {
+            schema: self.schema.clone(),
+            fun: self.fun.clone(),
+            input: self.input.execute(partition, context).await?,
+            baseline_metrics,
+        }))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                let columns = self
+                    .schema
+                    .fields()
+                    .iter()
+                    .map(|field| field.name().as_str())
+                    .collect::<Vec<_>>();
+                write!(f, "MapBatchesExec: output=[{}]", columns.join(", "))
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    /// The function can change the rows and columns arbitrarily
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Transforms `batch` with `fun`, checking the schema of the result
+fn map_batch(
+    batch: RecordBatch,
+    fun: &BatchFunction,
+    schema: &SchemaRef,
+) -> Result<RecordBatch> {
+    let result = fun(batch)?;
+    if result.schema().fields() != schema.fields() {
+        return Err(DataFusionError::Execution(format!(
+            "MapBatches function returned a batch of schema {:?}, expected {:?}",
+            result.schema(),
+            schema
+        )));
+    }
+    Ok(result)
+}
+
+/// Transforms the batches of the input partition with the function
+struct MapBatchesStream {
+    /// Output schema
+    schema: SchemaRef,
+    /// The function transforming the batches
+    fun: BatchFunction,
+    /// The input partition to transform
+    input: SendableRecordBatchStream,
+    /// runtime metrics recording
+    baseline_metrics: BaselineMetrics,
+}
+
+impl Stream for MapBatchesStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx).map(|x| match x {
+            Some(Ok(batch)) => {
+                let timer = self.baseline_metrics.elapsed_compute().timer();
+                let result = map_batch(batch, &self.fun, &self.schema)
+                    .map_err(DataFusionError::into);
+                timer.done();
+                Some(result)
+            }
+            other => other,
+        });
+        self.baseline_metrics.record_poll(poll)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // same number of record batches
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for MapBatchesStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::prelude::SessionContext;
+    use crate::test::make_partition;
+    use arrow::array::{Array, ArrayRef, Int32Array};
+
+    #[tokio::test]
+    async fn map_batches() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        // make_partition(5) produces the values 0..5 in column "i"
+        let batch = make_partition(5);
+        let schema = batch.schema();
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None)?);
+
+        let fun: BatchFunction = Arc::new(|batch: RecordBatch| -> Result<RecordBatch> {
+            let column = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<Int32Array>()
+                .unwrap();
+            let doubled: ArrayRef = Arc::new(
+                column
+                    .iter()
+                    .map(|v| v.map(|v| v * 2))
+                    .collect::<Int32Array>(),
+            );
+            Ok(RecordBatch::try_new(batch.schema(), vec![doubled])?)
+        });
+        let exec = Arc::new(MapBatchesExec::new(input.clone(), schema, fun));
+        let batches = collect(exec, session_ctx.task_ctx()).await?;
+        let expected: ArrayRef = Arc::new(Int32Array::from(vec![0, 2, 4, 6, 8]));
+        assert_eq!(batches[0].column(0), &expected);
+
+        // the batches must have the declared schema
+        let fun: BatchFunction = Arc::new(Ok::<RecordBatch, DataFusionError>);
+        let other_schema = Arc::new(arrow::datatypes::Schema::empty());
+        let exec = Arc::new(MapBatchesExec::new(input, other_schema, fun));
+        let err = collect(exec, session_ctx.task_ctx()).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("MapBatches function returned a batch of schema"),
+            "{}",
+            err
+        );
+        Ok(())
+    }
+}
@@ -154,6 +154,16 @@ impl MemoryExec {
             projection,
         })
     }
+
+    /// The partitions to query, before the projection
+    pub fn partitions(&self) -> &[Vec<RecordBatch>] {
+        &self.partitions
+    }
+
+    /// The projection applied to the batches of the partitions
+    pub fn projection(&self) -> Option<&Vec<usize>> {
+        self.projection.as_ref()
+    }
 }
 
 /// Iterator over batches
@@ -217,6 +217,13 @@ impl MetricsSet {
             .map(|v| v.as_usize())
     }
 
+    /// convenience: return the sum of the values of all metrics with
+    /// the given name, aggregated across partitions or None if no
+    /// metric is present
+    pub fn sum_by_name(&self, metric_name: &str) -> Option<MetricValue> {
+        self.sum(|metric| metric.value().name() == metric_name)
+    }
+
     /// Sums the values for metrics for which `f(metric)` returns
     /// true, and returns the value. Returns None if no metrics match
     /// the predicate.
@@ -17,6 +17,7 @@
 
 //! Metrics with memory usage tracking capability
 
+use crate::error::Result;
 use crate::execution::runtime_env::RuntimeEnv;
 use crate::execution::MemoryConsumerId;
 use crate::physical_plan::metrics::{
@@ -35,6 +36,7 @@ use arrow::{error::ArrowError, record_batch::RecordBatch};
 #[derive(Debug)]
 pub struct MemTrackingMetrics {
     id: MemoryConsumerId,
+    name: String,
     runtime: Option<Arc<RuntimeEnv>>,
     metrics: BaselineMetrics,
 }
@@ -47,6 +49,7 @@ impl MemTrackingMetrics {
         let id = MemoryConsumerId::new(partition);
         Self {
             id,
+            name: "MemTrackingMetrics".to_owned(),
             runtime: None,
             metrics: BaselineMetrics::new(metrics, partition),
         }
@@ -61,11 +64,19 @@ impl MemTrackingMetrics {
         let id = MemoryConsumerId::new(partition);
         Self {
             id,
+            name: "MemTrackingMetrics".to_owned(),
             runtime: Some(runtime),
             metrics: BaselineMetrics::new(metrics, partition),
         }
     }
 
+    /// Names the consumer in the memory manager, e.g. in the list of top
+    /// consumers of its errors
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
     /// return the metric for cpu time spend in this operator
     pub fn elapsed_compute(&self) -> &Time {
         self.metrics.elapsed_compute()
@@ -78,12 +89,31 @@ impl MemTrackingMetrics {
 
     /// setup initial memory usage and register it with memory manager
     pub fn init_mem_used(&self, size: usize) {
+        self.set_mem_used(size)
+    }
+
+    /// update the memory usage to `size`, growing or shrinking its
+    /// registration with the memory manager accordingly
+    pub fn set_mem_used(&self, size: usize) {
         self.metrics.mem_used().set(size);
         if let Some(rt) = self.runtime.as_ref() {
-            rt.memory_manager.grow_tracker_usage(size);
+            rt.memory_manager
+                .set_tracker_usage(&self.id, &self.name, size);
         }
     }
 
+    /// update the memory usage to `size` like [`Self::set_mem_used`], unless
+    /// growing it exceeds the memory pool of the memory manager, which fails
+    /// with a `ResourcesExhausted` error listing the top memory consumers
+    pub fn try_set_mem_used(&self, size: usize) -> Result<()> {
+        if let Some(rt) = self.runtime.as_ref() {
+            rt.memory_manager
+                .try_set_tracker_usage(&self.id, &self.name, size)?;
+        }
+        self.metrics.mem_used().set(size);
+        Ok(())
+    }
+
     /// return the metric for the total number of output rows produced
     pub fn output_rows(&self) -> &Count {
         self.metrics.output_rows()
@@ -34,6 +34,7 @@ pub use datafusion_expr::Accumulator;
 pub use datafusion_expr::ColumnarValue;
 pub use display::DisplayFormatType;
 use futures::stream::Stream;
+use parking_lot::Mutex;
 use std::fmt;
 use std::fmt::Debug;
 
@@ -118,6 +119,36 @@ pub struct ColumnStatistics {
     pub distinct_count: Option<usize>,
 }
 
+/// The [`Statistics`] of a plan node, computed lazily the first time they are
+/// requested and then cached on the node, for the plans deriving their
+/// statistics from the statistics of their inputs, which would otherwise be
+/// recomputed down the whole plan on every request.
+#[derive(Debug, Default)]
+pub struct StatisticsCache {
+    statistics: Mutex<Option<Statistics>>,
+}
+
+impl StatisticsCache {
+    /// Returns the cached statistics, computing them with `compute` if they
+    /// are not known yet
+    pub fn get_or_compute(&self, compute: impl FnOnce() -> Statistics) -> Statistics {
+        self.statistics.lock().get_or_insert_with(compute).clone()
+    }
+
+    /// Returns the cached statistics, if they were computed
+    pub fn get(&self) -> Option<Statistics> {
+        self.statistics.lock().clone()
+    }
+}
+
+impl Clone for StatisticsCache {
+    fn clone(&self) -> Self {
+        Self {
+            statistics: Mutex::new(self.get()),
+        }
+    }
+}
+
 /// `ExecutionPlan` represent nodes in the DataFusion Physical Plan.
 ///
 /// Each `ExecutionPlan` is Partition-aware and is responsible for
@@ -255,7 +286,32 @@ pub trait ExecutionPlan: Debug + Send + Sync {
     }
 
     /// Returns the global output statistics for this `ExecutionPlan` node.
+    ///
+    /// The statistics are requested during the planning, e.g. by the cost
+    /// model, so they must not perform any IO: the statistics depending on
+    /// metadata that must be fetched are only known once fetched by
+    /// [`ExecutionPlan::statistics_async`], which the physical planner calls
+    /// before optimizing the plan when an optimizer rule requires them, see
+    /// [`PhysicalOptimizerRule::requires_statistics`].
+    ///
+    /// [`PhysicalOptimizerRule::requires_statistics`]: crate::physical_optimizer::optimizer::PhysicalOptimizerRule::requires_statistics
     fn statistics(&self) -> Statistics;
+
+    /// Returns the global output statistics for this `ExecutionPlan` node,
+    /// fetching the metadata they depend on if needed, e.g. the footers of
+    /// the scanned files when their statistics were not collected when the
+    /// table was scanned. The fetched statistics are cached on the nodes and
+    /// then returned by [`ExecutionPlan::statistics`].
+    ///
+    /// The default implementation fetches the statistics of the children
+    /// before computing the statistics of the node. The nodes caching their
+    /// statistics keep the ones computed before the metadata was fetched.
+    async fn statistics_async(&self) -> Result<Statistics> {
+        for child in self.children() {
+            child.statistics_async().await?;
+        }
+        Ok(self.statistics())
+    }
 }
 
 /// Returns a copy of this plan if we change any child according to the pointer comparison.
@@ -415,6 +471,11 @@ pub async fn collect(
     plan: Arc<dyn ExecutionPlan>,
     context: Arc<TaskContext>,
 ) -> Result<Vec<RecordBatch>> {
+    if let Some(max_rows) = context.session_config().push_execution_max_rows() {
+        if push::is_push_executable(plan.as_ref(), max_rows) {
+            return push::push_collect(&plan);
+        }
+    }
     let stream = execute_stream(plan, context).await?;
     common::collect(stream).await
 }
@@ -542,10 +603,12 @@ pub fn project_schema(
 
 pub mod aggregates;
 pub mod analyze;
+pub mod async_udf;
 pub mod coalesce_batches;
 pub mod coalesce_partitions;
 pub mod common;
 pub mod cross_join;
+pub mod dedup;
 pub mod display;
 pub mod empty;
 pub mod explain;
@@ -555,17 +618,24 @@ pub use datafusion_physical_expr::expressions;
 pub mod aggregate_rule;
 pub mod file_format;
 pub mod filter;
+pub mod filter_project;
+pub mod fragment;
 pub mod functions;
 pub mod hash_aggregate;
 pub mod hash_join;
 pub mod hash_utils;
+pub mod inspect;
 pub mod join_utils;
 pub mod limit;
+pub mod map_batches;
 pub mod memory;
 pub mod metrics;
 pub mod planner;
 pub mod projection;
+pub mod push;
 pub mod repartition;
+pub mod replay;
+pub mod shuffle;
 pub mod sorts;
 pub mod stream;
 pub mod type_coercion;
@@ -573,4 +643,5 @@ pub mod udaf;
 pub mod udf;
 pub mod union;
 pub mod values;
+pub mod verify;
 pub mod windows;
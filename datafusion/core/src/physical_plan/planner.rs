@@ -23,48 +23,67 @@ use super::{
     hash_join::PartitionMode, udaf, union::UnionExec, values::ValuesExec, windows,
 };
 use crate::execution::context::{ExecutionProps, SessionState};
+use crate::logical_expr::binary_rule::binary_operator_data_type;
+use crate::logical_expr::BuiltinScalarFunction;
 use crate::logical_plan::plan::{
     source_as_provider, Aggregate, EmptyRelation, Filter, Join, Projection, Sort,
     SubqueryAlias, TableScan, Window,
 };
 use crate::logical_plan::{
-    unalias, unnormalize_cols, CrossJoin, DFSchema, Expr, LogicalPlan, Operator,
-    Partitioning as LogicalPartitioning, PlanType, Repartition, ToStringifiedPlan, Union,
-    UserDefinedLogicalNode,
+    unalias, unnormalize_col, unnormalize_cols, CreateMemoryTable, CrossJoin, DFField,
+    DFSchema, DFSchemaRef, DedupNode, DedupStrategy, Expr, LogicalPlan, MapBatchesNode,
+    Operator, Partitioning as LogicalPartitioning, PlanType, Repartition,
+    ToStringifiedPlan, Union, UserDefinedLogicalNode, VerifyNode,
 };
 use crate::logical_plan::{Limit, Values};
 use crate::physical_optimizer::optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::async_udf::{AsyncFuncExec, AsyncFuncExpr};
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use crate::physical_plan::cross_join::CrossJoinExec;
+use crate::physical_plan::dedup::DedupExec;
 use crate::physical_plan::explain::ExplainExec;
 use crate::physical_plan::expressions;
 use crate::physical_plan::expressions::{
     CaseExpr, Column, GetIndexedFieldExpr, Literal, PhysicalSortExpr,
 };
+use crate::physical_plan::file_format::ParquetExec;
 use crate::physical_plan::filter::FilterExec;
-use crate::physical_plan::hash_aggregate::{AggregateMode, HashAggregateExec};
+use crate::physical_plan::functions::ScalarFunctionExpr;
+use crate::physical_plan::hash_aggregate::{
+    AggregateMode, HashAggregateExec, NullGroupKeys,
+};
 use crate::physical_plan::hash_join::HashJoinExec;
 use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use crate::physical_plan::map_batches::MapBatchesExec;
 use crate::physical_plan::projection::ProjectionExec;
 use crate::physical_plan::repartition::RepartitionExec;
 use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
 use crate::physical_plan::udf;
+use crate::physical_plan::verify::VerifyExec;
 use crate::physical_plan::windows::WindowAggExec;
 use crate::physical_plan::{join_utils, Partitioning};
 use crate::physical_plan::{AggregateExpr, ExecutionPlan, PhysicalExpr, WindowExpr};
 use crate::scalar::ScalarValue;
-use crate::sql::utils::{generate_sort_key, window_expr_common_partition_keys};
+use crate::sql::utils::{
+    find_exprs_in_exprs, generate_sort_key, rebase_expr,
+    window_expr_common_partition_keys,
+};
 use crate::variable::VarType;
 use crate::{
     error::{DataFusionError, Result},
     physical_plan::displayable,
 };
+use arrow::array::ArrayRef;
 use arrow::compute::SortOptions;
 use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
 use arrow::{compute::can_cast_types, datatypes::DataType};
 use async_trait::async_trait;
+use datafusion_physical_expr::datetime_expressions;
 use futures::future::BoxFuture;
 use futures::{FutureExt, StreamExt, TryStreamExt};
-use log::{debug, trace};
+use log::{debug, trace, warn};
 use std::sync::Arc;
 
 fn create_function_physical_name(
@@ -284,6 +303,7 @@ impl PhysicalPlanner for DefaultPhysicalPlanner {
                 let plan = self
                     .create_initial_plan(logical_plan, session_state)
                     .await?;
+                self.fetch_statistics(&plan, session_state).await;
                 self.optimize_internal(plan, session_state, |_, _| {})
             }
         }
@@ -345,7 +365,16 @@ impl DefaultPhysicalPlanner {
                     // referred to in the query
                     let filters = unnormalize_cols(filters.iter().cloned());
                     let unaliased: Vec<Expr> = filters.into_iter().map(unalias).collect();
-                    source.scan(projection, &unaliased, *limit).await
+                    let plan = source.scan(projection, &unaliased, *limit).await?;
+                    // prune the files with the kernels of the session
+                    match plan.as_any().downcast_ref::<ParquetExec>() {
+                        Some(parquet) => Ok(Arc::new(
+                            parquet
+                                .clone()
+                                .with_execution_props(&session_state.execution_props),
+                        )),
+                        None => Ok(plan),
+                    }
                 }
                 LogicalPlan::Values(Values {
                     values,
@@ -492,15 +521,26 @@ impl DefaultPhysicalPlanner {
                 }) => {
                     // Initially need to perform the aggregate and then merge the partitions
                     let input_exec = self.create_initial_plan(input, session_state).await?;
+                    // the calls of the async functions are evaluated below the aggregate
+                    let exprs = group_expr
+                        .iter()
+                        .chain(aggr_expr)
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    let (async_exprs, input_exec, logical_input_schema) = self
+                        .plan_async_functions(&exprs, input, input_exec, session_state)?;
+                    let (async_group_expr, async_aggr_expr) =
+                        async_exprs.split_at(group_expr.len());
                     let physical_input_schema = input_exec.schema();
-                    let logical_input_schema = input.as_ref().schema();
+                    let logical_input_schema = logical_input_schema.as_ref();
 
                     let groups = group_expr
                         .iter()
-                        .map(|e| {
+                        .zip(async_group_expr)
+                        .map(|(e, async_e)| {
                             tuple_err((
                                 self.create_physical_expr(
-                                    e,
+                                    async_e,
                                     logical_input_schema,
                                     &physical_input_schema,
                                     session_state,
@@ -511,9 +551,19 @@ impl DefaultPhysicalPlanner {
                         .collect::<Result<Vec<_>>>()?;
                     let aggregates = aggr_expr
                         .iter()
-                        .map(|e| {
-                            create_aggregate_expr(
-                                e,
+                        .zip(async_aggr_expr)
+                        .map(|(e, async_e)| {
+                            // the aggregates are named after the calls rather
+                            // than after their results
+                            let (name, async_e) = match (e, async_e) {
+                                (Expr::Alias(_, alias), Expr::Alias(async_e, _)) => {
+                                    (alias.clone(), async_e.as_ref())
+                                }
+                                _ => (physical_name(e)?, async_e),
+                            };
+                            create_aggregate_expr_with_name(
+                                async_e,
+                                name,
                                 logical_input_schema,
                                 &physical_input_schema,
                                 &session_state.execution_props,
@@ -521,6 +571,28 @@ impl DefaultPhysicalPlanner {
                         })
                         .collect::<Result<Vec<_>>>()?;
 
+                    // drop the rows with a null group key before aggregating them,
+                    // unless the aggregate only groups them, e.g. for DISTINCT
+                    let input_exec: Arc<dyn ExecutionPlan> =
+                        match session_state.config.null_group_keys {
+                        NullGroupKeys::Drop
+                            if !groups.is_empty() && !aggregates.is_empty() =>
+                        {
+                            let mut predicate =
+                                expressions::is_not_null(groups[0].0.clone())?;
+                            for (expr, _) in &groups[1..] {
+                                predicate = binary(
+                                    predicate,
+                                    Operator::And,
+                                    expressions::is_not_null(expr.clone())?,
+                                    &physical_input_schema,
+                                )?;
+                            }
+                            Arc::new(FilterExec::try_new(predicate, input_exec)?)
+                        }
+                        _ => input_exec,
+                    };
+
                     let initial_aggr = Arc::new(HashAggregateExec::try_new(
                         AggregateMode::Partial,
                         groups.clone(),
@@ -578,10 +650,14 @@ impl DefaultPhysicalPlanner {
                 LogicalPlan::Projection(Projection { input, expr, .. }) => {
                     let input_exec = self.create_initial_plan(input, session_state).await?;
                     let input_schema = input.as_ref().schema();
+                    // the calls of the async functions are evaluated below the projection
+                    let (async_expr, input_exec, async_schema) = self
+                        .plan_async_functions(expr, input, input_exec, session_state)?;
 
                     let physical_exprs = expr
                         .iter()
-                        .map(|e| {
+                        .zip(async_expr.iter())
+                        .map(|(e, async_e)| {
                             // For projections, SQL planner and logical plan builder may convert user
                             // provided expressions into logical Column expressions if their results
                             // are already provided from the input plans. Because we work with
@@ -612,8 +688,8 @@ impl DefaultPhysicalPlanner {
 
                             tuple_err((
                                 self.create_physical_expr(
-                                    e,
-                                    input_schema,
+                                    async_e,
+                                    &async_schema,
                                     &input_exec.schema(),
                                     session_state,
                                 ),
@@ -631,16 +707,25 @@ impl DefaultPhysicalPlanner {
                     input, predicate, ..
                 }) => {
                     let physical_input = self.create_initial_plan(input, session_state).await?;
+                    let output_schema = physical_input.schema();
+                    // the calls of the async functions are evaluated below the filter
+                    let (predicate, physical_input, input_dfschema) = self
+                        .plan_async_functions(
+                            std::slice::from_ref(predicate),
+                            input,
+                            physical_input,
+                            session_state,
+                        )?;
                     let input_schema = physical_input.as_ref().schema();
-                    let input_dfschema = input.as_ref().schema();
 
                     let runtime_expr = self.create_physical_expr(
-                        predicate,
-                        input_dfschema,
+                        &predicate[0],
+                        &input_dfschema,
                         &input_schema,
                         session_state,
                     )?;
-                    Ok(Arc::new(FilterExec::try_new(runtime_expr, physical_input)?) )
+                    let filter = Arc::new(FilterExec::try_new(runtime_expr, physical_input)?);
+                    drop_async_results(filter, &output_schema)
                 }
                 LogicalPlan::Union(Union { inputs, .. }) => {
                     let physical_plans = futures::stream::iter(inputs)
@@ -682,8 +767,12 @@ impl DefaultPhysicalPlanner {
                 }
                 LogicalPlan::Sort(Sort { expr, input, .. }) => {
                     let physical_input = self.create_initial_plan(input, session_state).await?;
+                    let output_schema = physical_input.schema();
+                    // the calls of the async functions are evaluated below the sort
+                    let (expr, physical_input, input_dfschema) = self
+                        .plan_async_functions(expr, input, physical_input, session_state)?;
                     let input_schema = physical_input.as_ref().schema();
-                    let input_dfschema = input.as_ref().schema();
+                    let input_dfschema = input_dfschema.as_ref();
                     let sort_expr = expr
                         .iter()
                         .map(|e| match e {
@@ -706,7 +795,17 @@ impl DefaultPhysicalPlanner {
                             )),
                         })
                         .collect::<Result<Vec<_>>>()?;
-                    Ok(Arc::new(SortExec::try_new(sort_expr, physical_input)?) )
+                    let sort: Arc<dyn ExecutionPlan> =
+                        if !ordering_satisfies(physical_input.output_ordering(), &sort_expr) {
+                            Arc::new(SortExec::try_new(sort_expr, physical_input)?)
+                        } else if physical_input.output_partitioning().partition_count() == 1 {
+                            // the input is already sorted
+                            physical_input
+                        } else {
+                            // the partitions of the input are already sorted
+                            Arc::new(SortPreservingMergeExec::new(sort_expr, physical_input))
+                        };
+                    drop_async_results(sort, &output_schema)
                 }
                 LogicalPlan::Join(Join {
                     left,
@@ -812,35 +911,25 @@ impl DefaultPhysicalPlanner {
 
                     Ok(Arc::new(GlobalLimitExec::new(input, limit)))
                 }
-                LogicalPlan::CreateExternalTable(_) => {
-                    // There is no default plan for "CREATE EXTERNAL
-                    // TABLE" -- it must be handled at a higher level (so
-                    // that the appropriate table can be registered with
-                    // the context)
-                    Err(DataFusionError::Internal(
-                        "Unsupported logical plan: CreateExternalTable".to_string(),
-                    ))
+                LogicalPlan::CreateMemoryTable(CreateMemoryTable { input, .. }) => {
+                    // The table itself is registered by the context, this
+                    // plan computes the rows written to it (e.g. when the
+                    // statement is explained)
+                    self.create_initial_plan(input, session_state).await
                 }
-                LogicalPlan::CreateCatalogSchema(_) => {
-                    // There is no default plan for "CREATE SCHEMA".
-                    // It must be handled at a higher level (so
-                    // that the schema can be registered with
-                    // the context)
-                    Err(DataFusionError::Internal(
-                        "Unsupported logical plan: CreateCatalogSchema".to_string(),
-                    ))
-                }
-                LogicalPlan::CreateCatalog(_) => {
-                    // There is no default plan for "CREATE DATABASE".
-                    // It must be handled at a higher level (so
-                    // that the schema can be registered with
-                    // the context)
-                    Err(DataFusionError::Internal(
-                        "Unsupported logical plan: CreateCatalog".to_string(),
-                    ))
-                }
-                | LogicalPlan::CreateMemoryTable(_) | LogicalPlan::DropTable (_) => {
-                    // Create a dummy exec.
+                LogicalPlan::CreateExternalTable(_)
+                | LogicalPlan::CreateCatalogSchema(_)
+                | LogicalPlan::CreateCatalog(_)
+                | LogicalPlan::DropTable(_)
+                | LogicalPlan::SetVariable(_)
+                | LogicalPlan::Transaction(_)
+                | LogicalPlan::OptimizeTable(_)
+                | LogicalPlan::Delete(_)
+                | LogicalPlan::Update(_) => {
+                    // There is no default plan for the DDL statements, they
+                    // must be handled at a higher level (so that the
+                    // catalog of the context can be updated). Create a dummy
+                    // exec so that they can still be explained.
                     Ok(Arc::new(EmptyExec::new(
                         false,
                         SchemaRef::new(Schema::empty()),
@@ -860,22 +949,43 @@ impl DefaultPhysicalPlanner {
                         .try_collect::<Vec<_>>()
                         .await?;
 
-                    let maybe_plan = self.extension_planners.iter().try_fold(
-                        None,
-                        |maybe_plan, planner| {
-                            if let Some(plan) = maybe_plan {
-                                Ok(Some(plan))
-                            } else {
-                                planner.plan_extension(
-                                    self,
-                                    e.node.as_ref(),
-                                    &e.node.inputs(),
-                                    &physical_inputs,
-                                    session_state,
-                                )
-                            }
-                        },
-                    )?;
+                    let maybe_plan = if let Some(verify) = e.node.as_any().downcast_ref::<VerifyNode>() {
+                        // built-in extension node
+                        let physical_input = physical_inputs[0].clone();
+                        let runtime_expr = self.create_physical_expr(
+                            verify.predicate(),
+                            verify.input().schema(),
+                            &physical_input.schema(),
+                            session_state,
+                        )?;
+                        let plan: Arc<dyn ExecutionPlan> = Arc::new(VerifyExec::try_new(runtime_expr, physical_input)?);
+                        Some(plan)
+                    } else if let Some(map) = e.node.as_any().downcast_ref::<MapBatchesNode>() {
+                        // built-in extension node
+                        let schema = SchemaRef::new(map.schema().as_ref().into());
+                        let plan: Arc<dyn ExecutionPlan> = Arc::new(MapBatchesExec::new(physical_inputs[0].clone(), schema, map.fun().clone()));
+                        Some(plan)
+                    } else if let Some(dedup) = e.node.as_any().downcast_ref::<DedupNode>() {
+                        // built-in extension node
+                        Some(self.create_dedup_plan(dedup, physical_inputs[0].clone(), session_state)?)
+                    } else {
+                        self.extension_planners.iter().try_fold(
+                            None,
+                            |maybe_plan, planner| {
+                                if let Some(plan) = maybe_plan {
+                                    Ok(Some(plan))
+                                } else {
+                                    planner.plan_extension(
+                                        self,
+                                        e.node.as_ref(),
+                                        &e.node.inputs(),
+                                        &physical_inputs,
+                                        session_state,
+                                    )
+                                }
+                            },
+                        )?
+                    };
                     let plan = maybe_plan.ok_or_else(|| DataFusionError::Plan(format!(
                         "No installed planner was able to convert the custom node to an execution plan: {:?}", e.node
                     )))?;
@@ -954,7 +1064,27 @@ pub fn create_physical_expr(
                 input_schema,
                 execution_props,
             )?;
-            binary(lhs, *op, rhs, input_schema)
+            let lhs_type = lhs.data_type(input_schema)?;
+            let rhs_type = rhs.data_type(input_schema)?;
+            match execution_props.kernels.operator(*op, &lhs_type, &rhs_type) {
+                Some(kernel) => {
+                    let return_type =
+                        binary_operator_data_type(&lhs_type, op, &rhs_type)?;
+                    Ok(Arc::new(ScalarFunctionExpr::new(
+                        &format!("{}", op),
+                        kernel,
+                        vec![lhs, rhs],
+                        &return_type,
+                    )))
+                }
+                None => expressions::binary_with_settings(
+                    lhs,
+                    *op,
+                    rhs,
+                    input_schema,
+                    &execution_props.temporal_settings,
+                ),
+            }
         }
         Expr::Case {
             expr,
@@ -1016,11 +1146,43 @@ pub fn create_physical_expr(
                 else_expr,
             )?))
         }
-        Expr::Cast { expr, data_type } => expressions::cast(
-            create_physical_expr(expr, input_dfschema, input_schema, execution_props)?,
-            input_schema,
-            data_type.clone(),
-        ),
+        Expr::Cast { expr, data_type } => {
+            let expr = create_physical_expr(
+                expr,
+                input_dfschema,
+                input_schema,
+                execution_props,
+            )?;
+            match (expr.data_type(input_schema)?, data_type) {
+                // strings without an explicit offset are in the session time zone
+                (DataType::Utf8, DataType::Timestamp(_, None))
+                    if !execution_props.temporal_settings.is_utc() =>
+                {
+                    let to_timestamp = functions::create_physical_expr(
+                        &BuiltinScalarFunction::ToTimestamp,
+                        &[expr],
+                        input_schema,
+                        execution_props,
+                    )?;
+                    expressions::cast(to_timestamp, input_schema, data_type.clone())
+                }
+                // timestamps are rendered in the session time zone
+                (DataType::Timestamp(_, None), DataType::Utf8 | DataType::LargeUtf8)
+                    if !execution_props.temporal_settings.is_utc() =>
+                {
+                    let to_string = Arc::new(ScalarFunctionExpr::new(
+                        "timestamp_to_string",
+                        Arc::new(datetime_expressions::make_timestamp_to_string(
+                            execution_props.temporal_settings,
+                        )),
+                        vec![expr],
+                        &DataType::Utf8,
+                    ));
+                    expressions::cast(to_string, input_schema, data_type.clone())
+                }
+                _ => expressions::cast(expr, input_schema, data_type.clone()),
+            }
+        }
         Expr::TryCast { expr, data_type } => expressions::try_cast(
             create_physical_expr(expr, input_dfschema, input_schema, execution_props)?,
             input_schema,
@@ -1102,10 +1264,23 @@ pub fn create_physical_expr(
             )?;
 
             // rewrite the between into the two binary operators
+            let settings = &execution_props.temporal_settings;
             let binary_expr = binary(
-                binary(value_expr.clone(), Operator::GtEq, low_expr, input_schema)?,
+                expressions::binary_with_settings(
+                    value_expr.clone(),
+                    Operator::GtEq,
+                    low_expr,
+                    input_schema,
+                    settings,
+                )?,
                 Operator::And,
-                binary(value_expr.clone(), Operator::LtEq, high_expr, input_schema)?,
+                expressions::binary_with_settings(
+                    value_expr.clone(),
+                    Operator::LtEq,
+                    high_expr,
+                    input_schema,
+                    settings,
+                )?,
                 input_schema,
             );
 
@@ -1182,6 +1357,53 @@ pub fn create_physical_expr(
     }
 }
 
+/// Evaluates the logical expression `expr` on the rows of `batch`, without
+/// planning a query, and returns one value per row.
+///
+/// The columns of `expr` are looked up by name in the schema of `batch`,
+/// ignoring their qualifiers, and its operands and function arguments are
+/// coerced like in the queries. Aggregate and window functions are not
+/// supported. This function is part of the stable API of DataFusion.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use datafusion::arrow::array::{ArrayRef, Int64Array};
+/// # use datafusion::arrow::record_batch::RecordBatch;
+/// # use datafusion::error::Result;
+/// # use datafusion::physical_plan::planner::evaluate_expr;
+/// # use datafusion::prelude::*;
+/// # fn main() -> Result<()> {
+/// let a: ArrayRef = Arc::new(Int64Array::from(vec![1, 2, 3]));
+/// let batch = RecordBatch::try_from_iter(vec![("a", a)])?;
+///
+/// let result = evaluate_expr(&(col("a") + lit(1)), &batch)?;
+/// let expected: ArrayRef = Arc::new(Int64Array::from(vec![2, 3, 4]));
+/// assert_eq!(&result, &expected);
+/// # Ok(())
+/// # }
+/// ```
+pub fn evaluate_expr(expr: &Expr, batch: &RecordBatch) -> Result<ArrayRef> {
+    evaluate_expr_with_props(expr, batch, &ExecutionProps::new())
+}
+
+/// Evaluates `expr` on the rows of `batch` like [`evaluate_expr`], with the
+/// time zone, variables and kernels of `execution_props`, see
+/// [`SessionContext::evaluate_expr`] for the ones of a session
+///
+/// [`SessionContext::evaluate_expr`]: crate::execution::context::SessionContext::evaluate_expr
+pub fn evaluate_expr_with_props(
+    expr: &Expr,
+    batch: &RecordBatch,
+    execution_props: &ExecutionProps,
+) -> Result<ArrayRef> {
+    let schema = batch.schema();
+    let df_schema = DFSchema::try_from(schema.as_ref().clone())?;
+    let expr = unnormalize_col(expr.clone());
+    let physical_expr =
+        create_physical_expr(&expr, &df_schema, &schema, execution_props)?;
+    Ok(physical_expr.evaluate(batch)?.into_array(batch.num_rows()))
+}
+
 /// Create a window expression with a name from a logical expression
 pub fn create_window_expr_with_name(
     e: &Expr,
@@ -1379,7 +1601,171 @@ pub fn create_physical_sort_expr(
     })
 }
 
+/// Projects the columns of `schema`, the schema of the input of the node
+/// `plan`, dropping the results of the async functions evaluated below it
+fn drop_async_results(
+    plan: Arc<dyn ExecutionPlan>,
+    schema: &SchemaRef,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    if plan.schema().fields().len() == schema.fields().len() {
+        return Ok(plan);
+    }
+    let exprs = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let column: Arc<dyn PhysicalExpr> =
+                Arc::new(expressions::Column::new(field.name(), index));
+            (column, field.name().clone())
+        })
+        .collect();
+    Ok(Arc::new(ProjectionExec::try_new(exprs, plan)?))
+}
+
 impl DefaultPhysicalPlanner {
+    /// Plans a [`DedupExec`] keeping the latest row of each key of `input`.
+    ///
+    /// The input is hash partitioned by the keys, or coalesced into a single
+    /// partition, and sorted within each partition for the sort strategy.
+    fn create_dedup_plan(
+        &self,
+        dedup: &DedupNode,
+        input: Arc<dyn ExecutionPlan>,
+        session_state: &SessionState,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let input_dfschema = dedup.input().schema();
+        let input_schema = input.schema();
+        let keys = dedup
+            .keys()
+            .iter()
+            .map(|key| {
+                self.create_physical_expr(
+                    key,
+                    input_dfschema,
+                    &input_schema,
+                    session_state,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let sequence = self.create_physical_expr(
+            dedup.sequence(),
+            input_dfschema,
+            &input_schema,
+            session_state,
+        )?;
+
+        // TODO: dictionary type not yet supported in Hash Repartition
+        let contains_dict = keys
+            .iter()
+            .flat_map(|key| key.data_type(&input_schema))
+            .any(|data_type| matches!(data_type, DataType::Dictionary(_, _)));
+        let can_repartition = session_state.config.target_partitions > 1
+            && session_state.config.repartition_aggregations
+            && !contains_dict;
+        let input: Arc<dyn ExecutionPlan> = if can_repartition {
+            Arc::new(RepartitionExec::try_new(
+                input,
+                Partitioning::Hash(keys.clone(), session_state.config.target_partitions),
+            )?)
+        } else if input.output_partitioning().partition_count() > 1 {
+            Arc::new(CoalescePartitionsExec::new(input))
+        } else {
+            input
+        };
+
+        let input: Arc<dyn ExecutionPlan> = match dedup.strategy() {
+            DedupStrategy::Hash => input,
+            DedupStrategy::Sort => {
+                // the rows of each key from the latest, the NULL sequences last
+                let mut sort_exprs = keys
+                    .iter()
+                    .map(|key| PhysicalSortExpr {
+                        expr: key.clone(),
+                        options: SortOptions::default(),
+                    })
+                    .collect::<Vec<_>>();
+                sort_exprs.push(PhysicalSortExpr {
+                    expr: sequence.clone(),
+                    options: SortOptions {
+                        descending: true,
+                        nulls_first: false,
+                    },
+                });
+                Arc::new(SortExec::new_with_partitioning(sort_exprs, input, true))
+            }
+        };
+        Ok(Arc::new(DedupExec::try_new(
+            input,
+            keys,
+            sequence,
+            dedup.strategy(),
+        )?))
+    }
+
+    /// Plans an [`AsyncFuncExec`] evaluating the calls of the async functions
+    /// of the session in the expressions `exprs` of a projection, a filter, a
+    /// sort or an aggregate of `input`.
+    ///
+    /// Returns the expressions referring to the results of the calls instead,
+    /// with the new input of the node and its logical schema.
+    fn plan_async_functions(
+        &self,
+        exprs: &[Expr],
+        input: &LogicalPlan,
+        input_exec: Arc<dyn ExecutionPlan>,
+        session_state: &SessionState,
+    ) -> Result<(Vec<Expr>, Arc<dyn ExecutionPlan>, DFSchemaRef)> {
+        let async_functions = &session_state.async_scalar_functions;
+        let is_async = |e: &Expr| matches!(e, Expr::ScalarUDF { fun, .. } if async_functions.contains_key(&fun.name));
+        let calls = find_exprs_in_exprs(exprs, &is_async);
+        if calls.is_empty() {
+            return Ok((exprs.to_vec(), input_exec, input.schema().clone()));
+        }
+
+        let input_dfschema = input.schema();
+        let input_schema = input_exec.schema();
+        let mut fields = Vec::with_capacity(calls.len());
+        let mut async_exprs = Vec::with_capacity(calls.len());
+        for call in &calls {
+            if let Expr::ScalarUDF { fun, args } = call {
+                if let Some(nested) = find_exprs_in_exprs(args, &is_async).first() {
+                    return Err(DataFusionError::NotImplemented(format!(
+                        "Nested calls of async functions are not supported: {}",
+                        nested
+                    )));
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| {
+                        self.create_physical_expr(
+                            arg,
+                            input_dfschema,
+                            &input_schema,
+                            session_state,
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let async_expr = AsyncFuncExpr::try_new(
+                    call.name(input_dfschema)?,
+                    async_functions[&fun.name].clone(),
+                    &args,
+                    &input_schema,
+                )?;
+                fields.push(DFField::from(async_expr.field()));
+                async_exprs.push(async_expr);
+            }
+        }
+
+        let schema = input_dfschema.join(&DFSchema::new(fields)?)?;
+        let exprs = exprs
+            .iter()
+            .map(|e| rebase_expr(e, &calls, input))
+            .collect::<Result<Vec<_>>>()?;
+        let input_exec = Arc::new(AsyncFuncExec::try_new(async_exprs, input_exec)?);
+        Ok((exprs, input_exec, Arc::new(schema)))
+    }
+
     /// Handles capturing the various plans for EXPLAIN queries
     ///
     /// Returns
@@ -1403,6 +1789,7 @@ impl DefaultPhysicalPlanner {
             stringified_plans
                 .push(displayable(input.as_ref()).to_stringified(InitialPhysicalPlan));
 
+            self.fetch_statistics(&input, session_state).await;
             let input =
                 self.optimize_internal(input, session_state, |plan, optimizer| {
                     let optimizer_name = optimizer.name().to_string();
@@ -1423,6 +1810,25 @@ impl DefaultPhysicalPlanner {
         }
     }
 
+    /// Fetches the statistics of `plan` depending on metadata, e.g. the
+    /// footers of the scanned files, if an optimizer rule requires them.
+    /// The plan is still optimized when they cannot be fetched, with the
+    /// statistics known without fetching any metadata.
+    async fn fetch_statistics(
+        &self,
+        plan: &Arc<dyn ExecutionPlan>,
+        session_state: &SessionState,
+    ) {
+        let required = session_state.physical_optimizers.iter().any(|optimizer| {
+            optimizer.requires_statistics(&**plan, &session_state.config)
+        });
+        if required {
+            if let Err(e) = plan.statistics_async().await {
+                warn!("Failed to fetch the statistics of the plan: {}", e);
+            }
+        }
+    }
+
     /// Optimize a physical plan by applying each physical optimizer,
     /// calling observer(plan, optimizer after each one)
     fn optimize_internal<F>(
@@ -1455,6 +1861,26 @@ impl DefaultPhysicalPlanner {
     }
 }
 
+/// Whether rows sorted by `provided` are sorted by `required`, i.e. the
+/// columns of `required` are a prefix of the ones of `provided`, sorted the
+/// same way
+fn ordering_satisfies(
+    provided: Option<&[PhysicalSortExpr]>,
+    required: &[PhysicalSortExpr],
+) -> bool {
+    let as_column = |sort_expr: &PhysicalSortExpr| {
+        sort_expr.expr.as_any().downcast_ref::<Column>().cloned()
+    };
+    provided.map_or(false, |provided| {
+        provided.len() >= required.len()
+            && required.iter().zip(provided).all(|(required, provided)| {
+                required.options == provided.options
+                    && as_column(required).is_some()
+                    && as_column(required) == as_column(provided)
+            })
+    })
+}
+
 fn tuple_err<T, R>(value: (Result<T>, Result<R>)) -> Result<(T, R)> {
     match value {
         (Ok(e), Ok(e1)) => Ok((e, e1)),
@@ -1504,6 +1930,38 @@ mod tests {
             .await
     }
 
+    #[test]
+    fn evaluate_expr_on_batch() -> Result<()> {
+        use arrow::array::{Float64Array, Int32Array, StringArray};
+
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "a",
+                Arc::new(Int32Array::from(vec![Some(1), None, Some(3)])) as ArrayRef,
+            ),
+            (
+                "b",
+                Arc::new(StringArray::from(vec!["x", "y", "z"])) as ArrayRef,
+            ),
+        ])?;
+
+        // the qualifiers are ignored and the operands coerced
+        let expr = col("t.a") + lit(0.5f64);
+        let result = evaluate_expr(&expr, &batch)?;
+        let expected: ArrayRef =
+            Arc::new(Float64Array::from(vec![Some(1.5), None, Some(3.5)]));
+        assert_eq!(&result, &expected);
+
+        // the scalars are expanded to one value per row
+        let result = evaluate_expr(&lit("c"), &batch)?;
+        let expected: ArrayRef = Arc::new(StringArray::from(vec!["c", "c", "c"]));
+        assert_eq!(&result, &expected);
+
+        let err = evaluate_expr(&col("c"), &batch).unwrap_err();
+        assert!(err.to_string().contains("No field named 'c'"), "{}", err);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_all_operators() -> Result<()> {
         let testdata = crate::test_util::arrow_test_data();
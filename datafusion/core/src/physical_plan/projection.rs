@@ -37,7 +37,7 @@ use log::debug;
 
 use super::expressions::{Column, PhysicalSortExpr};
 use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
-use super::{RecordBatchStream, SendableRecordBatchStream, Statistics};
+use super::{RecordBatchStream, SendableRecordBatchStream, Statistics, StatisticsCache};
 use crate::execution::context::TaskContext;
 use async_trait::async_trait;
 use futures::stream::Stream;
@@ -52,8 +52,12 @@ pub struct ProjectionExec {
     schema: SchemaRef,
     /// The input plan
     input: Arc<dyn ExecutionPlan>,
+    /// The sort order of the input on the projected columns, if any
+    output_ordering: Option<Vec<PhysicalSortExpr>>,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
+    /// The statistics, computed on the first request
+    statistics: StatisticsCache,
 }
 
 impl ProjectionExec {
@@ -83,11 +87,31 @@ impl ProjectionExec {
             input_schema.metadata().clone(),
         ));
 
+        // the input order, up to its first column that is not projected
+        let output_ordering = input.output_ordering().and_then(|ordering| {
+            let projected_ordering = ordering
+                .iter()
+                .map_while(|sort_expr| {
+                    let column = sort_expr.expr.as_any().downcast_ref::<Column>()?;
+                    let position = expr.iter().position(|(e, _)| {
+                        e.as_any().downcast_ref::<Column>() == Some(column)
+                    })?;
+                    Some(PhysicalSortExpr {
+                        expr: Arc::new(Column::new(&expr[position].1, position)),
+                        options: sort_expr.options,
+                    })
+                })
+                .collect::<Vec<_>>();
+            (!projected_ordering.is_empty()).then(|| projected_ordering)
+        });
+
         Ok(Self {
             expr,
             schema,
             input: input.clone(),
+            output_ordering,
             metrics: ExecutionPlanMetricsSet::new(),
+            statistics: StatisticsCache::default(),
         })
     }
 
@@ -124,7 +148,9 @@ impl ExecutionPlan for ProjectionExec {
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
-        self.input.output_ordering()
+        // the sort expressions of the input refer to its own columns, so only
+        // the ordering remapped to the projected columns is reported
+        self.output_ordering.as_deref()
     }
 
     fn maintains_input_order(&self) -> bool {
@@ -190,10 +216,12 @@ impl ExecutionPlan for ProjectionExec {
     }
 
     fn statistics(&self) -> Statistics {
-        stats_projection(
-            self.input.statistics(),
-            self.expr.iter().map(|(e, _)| Arc::clone(e)),
-        )
+        self.statistics.get_or_compute(|| {
+            stats_projection(
+                self.input.statistics(),
+                self.expr.iter().map(|(e, _)| Arc::clone(e)),
+            )
+        })
     }
 }
 
@@ -325,6 +353,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -362,6 +391,56 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn project_output_ordering() -> Result<()> {
+        let schema = test_util::aggr_test_schema();
+        let (_, files) = test::create_partitioned_csv("aggregate_test_100.csv", 4)?;
+        let sort_expr = |name: &str| -> Result<PhysicalSortExpr> {
+            Ok(PhysicalSortExpr {
+                expr: col(name, &schema)?,
+                options: Default::default(),
+            })
+        };
+        let csv: Arc<dyn ExecutionPlan> = Arc::new(CsvExec::new(
+            FileScanConfig {
+                object_store: Arc::new(LocalFileSystem {}),
+                file_schema: Arc::clone(&schema),
+                file_groups: files,
+                statistics: Statistics::default(),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: Some(vec![sort_expr("c1")?, sort_expr("c2")?]),
+            },
+            true,
+            b',',
+        ));
+
+        // the ordering refers to the projected columns
+        let projection = ProjectionExec::try_new(
+            vec![
+                (col("c3", &schema)?, "c3".to_string()),
+                (col("c1", &schema)?, "x".to_string()),
+            ],
+            csv.clone(),
+        )?;
+        let ordering = projection.output_ordering().unwrap();
+        assert_eq!(ordering.len(), 1);
+        assert_eq!(
+            ordering[0]
+                .expr
+                .as_any()
+                .downcast_ref::<expressions::Column>(),
+            Some(&expressions::Column::new("x", 1))
+        );
+
+        // no ordering is reported without the first sort column
+        let projection =
+            ProjectionExec::try_new(vec![(col("c2", &schema)?, "c2".to_string())], csv)?;
+        assert!(projection.output_ordering().is_none());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_stats_projection_columns_only() {
         let source = Statistics {
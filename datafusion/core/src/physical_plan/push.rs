@@ -0,0 +1,369 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Synchronous, push-based execution of small plans over in-memory data.
+//!
+//! Executing a plan through [`ExecutionPlan::execute`] polls a stream per
+//! operator for every batch, which dominates the run time of queries that
+//! only process a few rows. Plans made of the operators supported here can
+//! instead be executed synchronously: the sources push their batches through
+//! the operators above them, with no stream, task or runtime involved.
+//!
+//! Push execution does not record the metrics of the operators.
+
+use std::sync::Arc;
+
+use arrow::compute::{lexsort_to_indices, take, SortColumn};
+use arrow::record_batch::RecordBatch;
+
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::coalesce_batches::{concat_batches, CoalesceBatchesExec};
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::empty::EmptyExec;
+use crate::physical_plan::filter::{batch_filter, FilterExec};
+use crate::physical_plan::limit::{truncate_batch, GlobalLimitExec, LocalLimitExec};
+use crate::physical_plan::memory::MemoryExec;
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::values::ValuesExec;
+use crate::physical_plan::{displayable, ExecutionPlan, Partitioning};
+
+/// Whether a push-based operator wants more input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    Continue,
+    Stop,
+}
+
+type Sink<'a> = &'a mut dyn FnMut(RecordBatch) -> Result<Flow>;
+
+/// Returns true if `plan` only uses operators supported by push execution,
+/// and its sources hold at most `max_rows` rows in memory
+pub fn is_push_executable(plan: &dyn ExecutionPlan, max_rows: usize) -> bool {
+    fn source_rows(plan: &dyn ExecutionPlan) -> Option<usize> {
+        let any = plan.as_any();
+        if let Some(exec) = any.downcast_ref::<MemoryExec>() {
+            return Some(
+                exec.partitions()
+                    .iter()
+                    .flatten()
+                    .map(|batch| batch.num_rows())
+                    .sum(),
+            );
+        }
+        if any.is::<EmptyExec>() {
+            return Some(1);
+        }
+        if let Some(exec) = any.downcast_ref::<ValuesExec>() {
+            return Some(exec.data().iter().map(|batch| batch.num_rows()).sum());
+        }
+        if let Some(exec) = any.downcast_ref::<RepartitionExec>() {
+            if !matches!(exec.partitioning(), Partitioning::RoundRobinBatch(_)) {
+                return None;
+            }
+        } else if !(any.is::<ProjectionExec>()
+            || any.is::<FilterExec>()
+            || any.is::<CoalesceBatchesExec>()
+            || any.is::<CoalescePartitionsExec>()
+            || any.is::<GlobalLimitExec>()
+            || any.is::<LocalLimitExec>()
+            || any.is::<SortExec>())
+        {
+            return None;
+        }
+        plan.children()
+            .iter()
+            .map(|child| source_rows(child.as_ref()))
+            .sum()
+    }
+
+    matches!(source_rows(plan), Some(rows) if rows <= max_rows)
+}
+
+/// Executes `plan` synchronously and collects the results of all its
+/// partitions in memory. Returns an error if `plan` uses an operator that
+/// push execution does not support, see [`is_push_executable`].
+pub fn push_collect(plan: &Arc<dyn ExecutionPlan>) -> Result<Vec<RecordBatch>> {
+    let mut batches = vec![];
+    for partition in 0..plan.output_partitioning().partition_count() {
+        push_partition(plan, partition, &mut |batch| {
+            batches.push(batch);
+            Ok(Flow::Continue)
+        })?;
+    }
+    Ok(batches)
+}
+
+/// Pushes the batches of the partition `partition` of `plan` to `sink`,
+/// until `sink` stops asking for more
+fn push_partition(
+    plan: &Arc<dyn ExecutionPlan>,
+    partition: usize,
+    sink: Sink,
+) -> Result<Flow> {
+    let any = plan.as_any();
+    if let Some(exec) = any.downcast_ref::<MemoryExec>() {
+        for batch in &exec.partitions()[partition] {
+            let batch = match exec.projection() {
+                Some(columns) => batch.project(columns)?,
+                None => batch.clone(),
+            };
+            if sink(batch)? == Flow::Stop {
+                return Ok(Flow::Stop);
+            }
+        }
+        Ok(Flow::Continue)
+    } else if let Some(exec) = any.downcast_ref::<EmptyExec>() {
+        push_batches(exec.data()?, sink)
+    } else if let Some(exec) = any.downcast_ref::<ValuesExec>() {
+        push_batches(exec.data(), sink)
+    } else if let Some(exec) = any.downcast_ref::<ProjectionExec>() {
+        let schema = exec.schema();
+        push_partition(exec.input(), partition, &mut |batch| {
+            let columns = exec
+                .expr()
+                .iter()
+                .map(|(expr, _)| {
+                    expr.evaluate(&batch)
+                        .map(|v| v.into_array(batch.num_rows()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            sink(RecordBatch::try_new(schema.clone(), columns)?)
+        })
+    } else if let Some(exec) = any.downcast_ref::<FilterExec>() {
+        push_partition(exec.input(), partition, &mut |batch| {
+            let batch = batch_filter(&batch, exec.predicate())?;
+            if batch.num_rows() == 0 {
+                return Ok(Flow::Continue);
+            }
+            sink(batch)
+        })
+    } else if let Some(exec) = any.downcast_ref::<CoalesceBatchesExec>() {
+        // the batches of small plans are not worth coalescing
+        push_partition(exec.input(), partition, sink)
+    } else if let Some(exec) = any.downcast_ref::<CoalescePartitionsExec>() {
+        push_all_partitions(exec.input(), sink)
+    } else if let Some(exec) = any.downcast_ref::<RepartitionExec>() {
+        match exec.partitioning() {
+            // a round robin distribution of the batches of small plans is
+            // not worth it: the first partition takes them all
+            Partitioning::RoundRobinBatch(_) if partition == 0 => {
+                push_all_partitions(exec.input(), sink)
+            }
+            Partitioning::RoundRobinBatch(_) => Ok(Flow::Continue),
+            _ => Err(push_not_supported(plan)),
+        }
+    } else if let Some(exec) = any.downcast_ref::<GlobalLimitExec>() {
+        push_limit(exec.input(), partition, exec.limit(), sink)
+    } else if let Some(exec) = any.downcast_ref::<LocalLimitExec>() {
+        push_limit(exec.input(), partition, exec.limit(), sink)
+    } else if let Some(exec) = any.downcast_ref::<SortExec>() {
+        let mut batches = vec![];
+        push_partition(exec.input(), partition, &mut |batch| {
+            batches.push(batch);
+            Ok(Flow::Continue)
+        })?;
+        if batches.is_empty() {
+            return Ok(Flow::Continue);
+        }
+        let row_count = batches.iter().map(|batch| batch.num_rows()).sum();
+        let batch = concat_batches(&exec.schema(), &batches, row_count)?;
+        let sort_columns = exec
+            .expr()
+            .iter()
+            .map(|expr| expr.evaluate_to_sort_column(&batch))
+            .collect::<Result<Vec<SortColumn>>>()?;
+        let indices = lexsort_to_indices(&sort_columns, None)?;
+        let columns = batch
+            .columns()
+            .iter()
+            .map(|column| take(column.as_ref(), &indices, None))
+            .collect::<arrow::error::Result<Vec<_>>>()?;
+        sink(RecordBatch::try_new(exec.schema(), columns)?)
+    } else {
+        Err(push_not_supported(plan))
+    }
+}
+
+/// Pushes all the partitions of `input` to `sink`
+fn push_all_partitions(input: &Arc<dyn ExecutionPlan>, sink: Sink) -> Result<Flow> {
+    for partition in 0..input.output_partitioning().partition_count() {
+        if push_partition(input, partition, sink)? == Flow::Stop {
+            return Ok(Flow::Stop);
+        }
+    }
+    Ok(Flow::Continue)
+}
+
+fn push_not_supported(plan: &Arc<dyn ExecutionPlan>) -> DataFusionError {
+    DataFusionError::NotImplemented(format!(
+        "Push execution does not support {}",
+        displayable(plan.as_ref()).one_line()
+    ))
+}
+
+fn push_batches(batches: Vec<RecordBatch>, sink: Sink) -> Result<Flow> {
+    for batch in batches {
+        if sink(batch)? == Flow::Stop {
+            return Ok(Flow::Stop);
+        }
+    }
+    Ok(Flow::Continue)
+}
+
+/// Pushes the first `limit` rows of the partition `partition` of `input` to
+/// `sink`, without its empty batches. The input is not pulled any further
+/// once the limit is reached or `sink` stops asking for more.
+fn push_limit(
+    input: &Arc<dyn ExecutionPlan>,
+    partition: usize,
+    limit: usize,
+    sink: Sink,
+) -> Result<Flow> {
+    if limit == 0 {
+        return Ok(Flow::Continue);
+    }
+    let mut remaining = limit;
+    let mut flow = Flow::Continue;
+    push_partition(input, partition, &mut |batch| {
+        if batch.num_rows() == 0 {
+            return Ok(Flow::Continue);
+        }
+        let batch = if batch.num_rows() < remaining {
+            remaining -= batch.num_rows();
+            batch
+        } else {
+            let batch = truncate_batch(&batch, remaining);
+            remaining = 0;
+            batch
+        };
+        flow = sink(batch)?;
+        if remaining == 0 {
+            Ok(Flow::Stop)
+        } else {
+            Ok(flow)
+        }
+    })?;
+    Ok(flow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::datasource::MemTable;
+    use crate::physical_plan::collect;
+    use crate::prelude::{SessionConfig, SessionContext};
+    use arrow::array::Int32Array;
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    fn create_context() -> Result<SessionContext> {
+        let ctx = SessionContext::with_config(
+            SessionConfig::new().with_push_execution_max_rows(100),
+        );
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batches = (0..3)
+            .map(|i| {
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(Int32Array::from(vec![
+                        3 * i,
+                        3 * i + 2,
+                        3 * i + 1,
+                    ]))],
+                )
+            })
+            .collect::<arrow::error::Result<Vec<_>>>()?;
+        let table = MemTable::try_new(
+            schema,
+            vec![batches[..2].to_vec(), batches[2..].to_vec()],
+        )?;
+        ctx.register_table("t", Arc::new(table))?;
+        Ok(ctx)
+    }
+
+    #[tokio::test]
+    async fn push_execution() -> Result<()> {
+        let ctx = create_context()?;
+        let plan = ctx.create_logical_plan(
+            "SELECT a * 2 AS b FROM t WHERE a % 2 = 0 ORDER BY b DESC LIMIT 3",
+        )?;
+        let plan = ctx.create_physical_plan(&ctx.optimize(&plan)?).await?;
+        assert!(is_push_executable(plan.as_ref(), 100));
+        assert!(!is_push_executable(plan.as_ref(), 8));
+
+        let expected = vec![
+            "+----+", "| b  |", "+----+", "| 16 |", "| 12 |", "| 8  |", "+----+",
+        ];
+        assert_batches_eq!(expected, &push_collect(&plan)?);
+        assert_batches_eq!(expected, &collect(plan, ctx.task_ctx()).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn push_execution_stops_at_limit() -> Result<()> {
+        let ctx = create_context()?;
+        let plan = ctx.create_logical_plan("SELECT a FROM t LIMIT 4")?;
+        let plan = ctx.create_physical_plan(&ctx.optimize(&plan)?).await?;
+        let batches = push_collect(&plan)?;
+        let rows: usize = batches.iter().map(|batch| batch.num_rows()).sum();
+        assert_eq!(rows, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn limit_stops_with_sink() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = |values: Vec<i32>| {
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(Int32Array::from(values))])
+        };
+        let batches = vec![batch(vec![])?, batch(vec![1, 2])?, batch(vec![3, 4])?];
+        let input: Arc<dyn ExecutionPlan> =
+            Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+        let limit: Arc<dyn ExecutionPlan> = Arc::new(GlobalLimitExec::new(input, 3));
+
+        // the empty batches are not pushed
+        let mut rows = vec![];
+        let flow = push_partition(&limit, 0, &mut |batch| {
+            rows.push(batch.num_rows());
+            Ok(Flow::Continue)
+        })?;
+        assert_eq!(flow, Flow::Continue);
+        assert_eq!(rows, vec![2, 1]);
+
+        // the limit stops pulling its input when its sink stops
+        let mut rows = vec![];
+        let flow = push_partition(&limit, 0, &mut |batch| {
+            rows.push(batch.num_rows());
+            Ok(Flow::Stop)
+        })?;
+        assert_eq!(flow, Flow::Stop);
+        assert_eq!(rows, vec![2]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unsupported_operator() -> Result<()> {
+        let ctx = create_context()?;
+        let plan = ctx.create_logical_plan("SELECT a, count(*) FROM t GROUP BY a")?;
+        let plan = ctx.create_physical_plan(&ctx.optimize(&plan)?).await?;
+        assert!(!is_push_executable(plan.as_ref(), 100));
+        assert!(push_collect(&plan).is_err());
+        Ok(())
+    }
+}
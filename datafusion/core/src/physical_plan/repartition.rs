@@ -18,6 +18,7 @@
 //! The repartition operator maps N input partitions to M output partitions based on a
 //! partitioning scheme.
 
+use std::collections::VecDeque;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -33,13 +34,13 @@ use arrow::record_batch::RecordBatch;
 use log::debug;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
-use super::common::{AbortOnDropMany, AbortOnDropSingle};
 use super::expressions::PhysicalSortExpr;
 use super::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
 use super::{RecordBatchStream, SendableRecordBatchStream};
 use async_trait::async_trait;
 
 use crate::execution::context::TaskContext;
+use crate::execution::executor::{self, SpawnedTask};
 use datafusion_physical_expr::PhysicalExpr;
 use futures::stream::Stream;
 use futures::StreamExt;
@@ -48,9 +49,9 @@ use tokio::sync::{
     mpsc::{self, UnboundedReceiver, UnboundedSender},
     Mutex,
 };
-use tokio::task::JoinHandle;
 
-type MaybeBatch = Option<ArrowResult<RecordBatch>>;
+/// A batch of an input partition, `None` once the input partition is done
+type MaybeBatch = (usize, Option<ArrowResult<RecordBatch>>);
 
 /// Inner state of [`RepartitionExec`].
 #[derive(Debug)]
@@ -61,7 +62,7 @@ struct RepartitionExecState {
         HashMap<usize, (UnboundedSender<MaybeBatch>, UnboundedReceiver<MaybeBatch>)>,
 
     /// Helper that ensures that that background job is killed once it is no longer needed.
-    abort_helper: Arc<AbortOnDropMany<()>>,
+    abort_helper: Arc<Vec<SpawnedTask<()>>>,
 }
 
 /// A utility that can be used to partition batches based on [`Partitioning`]
@@ -98,7 +99,7 @@ impl BatchPartitioner {
             Partitioning::Hash(exprs, num_partitions) => BatchPartitionerState::Hash {
                 exprs,
                 num_partitions,
-                // Use fixed random hash
+                // Use fixed random hash, unless replaced by `with_random_state`
                 random_state: ahash::RandomState::with_seeds(0, 0, 0, 0),
                 hash_buffer: vec![],
             },
@@ -113,6 +114,21 @@ impl BatchPartitioner {
         Ok(Self { state, timer })
     }
 
+    /// Hash the rows with `random_state`, e.g. the one of the session
+    /// configuration, see [`SessionConfig::with_hash_seeds`]
+    ///
+    /// [`SessionConfig::with_hash_seeds`]: crate::execution::context::SessionConfig::with_hash_seeds
+    pub fn with_random_state(mut self, random_state: ahash::RandomState) -> Self {
+        if let BatchPartitionerState::Hash {
+            random_state: state,
+            ..
+        } = &mut self.state
+        {
+            *state = random_state;
+        }
+        self
+    }
+
     /// Partition the provided [`RecordBatch`] into one or more partitioned [`RecordBatch`]
     /// based on the [`Partitioning`] specified on construction
     ///
@@ -192,7 +208,11 @@ impl BatchPartitioner {
 }
 
 /// The repartition operator maps N input partitions to M output partitions based on a
-/// partitioning scheme. No guarantees are made about the order of the resulting partitions.
+/// partitioning scheme. No guarantees are made about the order of the resulting partitions,
+/// unless the output of the session is [deterministic], in which case each output partition
+/// contains the batches of the input partitions in the order of the input partitions.
+///
+/// [deterministic]: crate::execution::context::SessionConfig::with_deterministic_output
 #[derive(Debug)]
 pub struct RepartitionExec {
     /// Input execution plan
@@ -324,12 +344,13 @@ impl ExecutionPlan for RepartitionExec {
                 // being read yet. This may cause high memory usage if the next operator is
                 // reading output partitions in order rather than concurrently. One workaround
                 // for this would be to add spill-to-disk capabilities.
-                let (sender, receiver) =
-                    mpsc::unbounded_channel::<Option<ArrowResult<RecordBatch>>>();
+                let (sender, receiver) = mpsc::unbounded_channel::<MaybeBatch>();
                 state.channels.insert(partition, (sender, receiver));
             }
 
             // launch one async task per *input* partition
+            let runtime = context.runtime_env();
+            let executor = runtime.executor.as_ref();
             let mut join_handles = Vec::with_capacity(num_input_partitions);
             for i in 0..num_input_partitions {
                 let txs: HashMap<_, _> = state
@@ -340,26 +361,26 @@ impl ExecutionPlan for RepartitionExec {
 
                 let r_metrics = RepartitionMetrics::new(i, partition, &self.metrics);
 
-                let input_task: JoinHandle<Result<()>> =
-                    tokio::spawn(Self::pull_from_input(
+                let input_task: SpawnedTask<Result<()>> = executor::spawn(
+                    executor,
+                    Self::pull_from_input(
                         self.input.clone(),
                         i,
                         txs.clone(),
                         self.partitioning.clone(),
                         r_metrics,
                         context.clone(),
-                    ));
+                    ),
+                );
 
                 // In a separate task, wait for each input to be done
                 // (and pass along any errors, including panic!s)
-                let join_handle = tokio::spawn(Self::wait_for_task(
-                    AbortOnDropSingle::new(input_task),
-                    txs,
-                ));
+                let join_handle =
+                    executor::spawn(executor, Self::wait_for_task(input_task, i, txs));
                 join_handles.push(join_handle);
             }
 
-            state.abort_helper = Arc::new(AbortOnDropMany(join_handles))
+            state.abort_helper = Arc::new(join_handles)
         }
 
         debug!(
@@ -367,11 +388,23 @@ impl ExecutionPlan for RepartitionExec {
             partition
         );
 
+        // the batches of the input partitions produced ahead of their turn
+        let ordered_inputs =
+            context
+                .session_config()
+                .deterministic_output()
+                .then(|| OrderedInputs {
+                    next_input: 0,
+                    buffered: vec![VecDeque::new(); num_input_partitions],
+                    finished: vec![false; num_input_partitions],
+                });
+
         // now return stream for the specified *output* partition which will
         // read from the channel
         Ok(Box::pin(RepartitionStream {
             num_input_partitions,
             num_input_partitions_processed: 0,
+            ordered_inputs,
             schema: self.input.schema(),
             input: UnboundedReceiverStream::new(
                 state.channels.remove(&partition).unwrap().1,
@@ -412,7 +445,7 @@ impl RepartitionExec {
             partitioning,
             state: Arc::new(Mutex::new(RepartitionExecState {
                 channels: HashMap::new(),
-                abort_helper: Arc::new(AbortOnDropMany::<()>(vec![])),
+                abort_helper: Arc::new(vec![]),
             })),
             metrics: ExecutionPlanMetricsSet::new(),
         })
@@ -427,13 +460,14 @@ impl RepartitionExec {
     async fn pull_from_input(
         input: Arc<dyn ExecutionPlan>,
         i: usize,
-        mut txs: HashMap<usize, UnboundedSender<Option<ArrowResult<RecordBatch>>>>,
+        mut txs: HashMap<usize, UnboundedSender<MaybeBatch>>,
         partitioning: Partitioning,
         r_metrics: RepartitionMetrics,
         context: Arc<TaskContext>,
     ) -> Result<()> {
         let mut partitioner =
-            BatchPartitioner::try_new(partitioning, r_metrics.repart_time.clone())?;
+            BatchPartitioner::try_new(partitioning, r_metrics.repart_time.clone())?
+                .with_random_state(context.session_config().hash_random_state());
 
         // execute the child operator
         let timer = r_metrics.fetch_time.timer();
@@ -458,7 +492,7 @@ impl RepartitionExec {
                 let timer = r_metrics.send_time.timer();
                 // if there is still a receiver, send to it
                 if let Some(tx) = txs.get_mut(&partition) {
-                    if tx.send(Some(Ok(partitioned))).is_err() {
+                    if tx.send((i, Some(Ok(partitioned)))).is_err() {
                         // If the other end has hung up, it was an early shutdown (e.g. LIMIT)
                         txs.remove(&partition);
                     }
@@ -477,8 +511,9 @@ impl RepartitionExec {
     /// complete. Upon error, propagates the errors to all output tx
     /// channels.
     async fn wait_for_task(
-        input_task: AbortOnDropSingle<Result<()>>,
-        txs: HashMap<usize, UnboundedSender<Option<ArrowResult<RecordBatch>>>>,
+        input_task: SpawnedTask<Result<()>>,
+        i: usize,
+        txs: HashMap<usize, UnboundedSender<MaybeBatch>>,
     ) {
         // wait for completion, and propagate error
         // note we ignore errors on send (.ok) as that means the receiver has already shutdown.
@@ -488,7 +523,7 @@ impl RepartitionExec {
                 for (_, tx) in txs {
                     let err = DataFusionError::Execution(format!("Join Error: {}", e));
                     let err = Err(err.into());
-                    tx.send(Some(err)).ok();
+                    tx.send((i, Some(err))).ok();
                 }
             }
             // Error from running input task
@@ -497,14 +532,14 @@ impl RepartitionExec {
                     // wrap it because need to send error to all output partitions
                     let err = DataFusionError::Execution(e.to_string());
                     let err = Err(err.into());
-                    tx.send(Some(err)).ok();
+                    tx.send((i, Some(err))).ok();
                 }
             }
             // Input task completed successfully
             Ok(Ok(())) => {
                 // notify each output partition that this input partition has no more data
                 for (_, tx) in txs {
-                    tx.send(None).ok();
+                    tx.send((i, None)).ok();
                 }
             }
         }
@@ -518,15 +553,19 @@ struct RepartitionStream {
     /// Number of input partitions that have finished sending batches to this output channel
     num_input_partitions_processed: usize,
 
+    /// State of the output of the batches in the order of the input partitions,
+    /// if the output is deterministic
+    ordered_inputs: Option<OrderedInputs>,
+
     /// Schema wrapped by Arc
     schema: SchemaRef,
 
     /// channel containing the repartitioned batches
-    input: UnboundedReceiverStream<Option<ArrowResult<RecordBatch>>>,
+    input: UnboundedReceiverStream<MaybeBatch>,
 
     /// Handle to ensure background tasks are killed when no longer needed.
     #[allow(dead_code)]
-    drop_helper: Arc<AbortOnDropMany<()>>,
+    drop_helper: Arc<Vec<SpawnedTask<()>>>,
 }
 
 impl Stream for RepartitionStream {
@@ -536,9 +575,12 @@ impl Stream for RepartitionStream {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
+        if self.ordered_inputs.is_some() {
+            return self.poll_next_ordered(cx);
+        }
         match self.input.poll_next_unpin(cx) {
-            Poll::Ready(Some(Some(v))) => Poll::Ready(Some(v)),
-            Poll::Ready(Some(None)) => {
+            Poll::Ready(Some((_, Some(v)))) => Poll::Ready(Some(v)),
+            Poll::Ready(Some((_, None))) => {
                 self.num_input_partitions_processed += 1;
                 if self.num_input_partitions == self.num_input_partitions_processed {
                     // all input partitions have finished sending batches
@@ -554,6 +596,60 @@ impl Stream for RepartitionStream {
     }
 }
 
+impl RepartitionStream {
+    /// Polls the next batch of the current input partition, buffering the
+    /// batches received from the following input partitions. The errors are
+    /// returned as soon as they are received.
+    fn poll_next_ordered(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<ArrowResult<RecordBatch>>> {
+        loop {
+            let ordered = self.ordered_inputs.as_mut().unwrap();
+            while ordered.next_input < self.num_input_partitions {
+                let next_input = ordered.next_input;
+                if let Some(batch) = ordered.buffered[next_input].pop_front() {
+                    return Poll::Ready(Some(Ok(batch)));
+                }
+                if !ordered.finished[next_input] {
+                    break;
+                }
+                ordered.next_input += 1;
+            }
+            if ordered.next_input == self.num_input_partitions {
+                return Poll::Ready(None);
+            }
+
+            match self.input.poll_next_unpin(cx) {
+                Poll::Ready(Some((_, Some(Err(e))))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(Some((input, Some(Ok(batch))))) => {
+                    let ordered = self.ordered_inputs.as_mut().unwrap();
+                    if input == ordered.next_input {
+                        return Poll::Ready(Some(Ok(batch)));
+                    }
+                    ordered.buffered[input].push_back(batch);
+                }
+                Poll::Ready(Some((input, None))) => {
+                    self.ordered_inputs.as_mut().unwrap().finished[input] = true;
+                }
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// The batches received by an output partition ahead of the turn of their
+/// input partition
+struct OrderedInputs {
+    /// The input partition whose batches are output
+    next_input: usize,
+    /// The batches received from each input partition, not output yet
+    buffered: Vec<VecDeque<RecordBatch>>,
+    /// Whether each input partition has finished sending batches
+    finished: Vec<bool>,
+}
+
 impl RecordBatchStream for RepartitionStream {
     /// Get the schema
     fn schema(&self) -> SchemaRef {
@@ -565,7 +661,7 @@ impl RecordBatchStream for RepartitionStream {
 mod tests {
     use super::*;
     use crate::from_slice::FromSlice;
-    use crate::prelude::SessionContext;
+    use crate::prelude::{SessionConfig, SessionContext};
     use crate::test::create_vec_batches;
     use crate::{
         assert_batches_sorted_eq,
@@ -581,11 +677,12 @@ mod tests {
     use arrow::datatypes::{DataType, Field, Schema};
     use arrow::record_batch::RecordBatch;
     use arrow::{
-        array::{ArrayRef, StringArray},
+        array::{Array, ArrayRef, StringArray, UInt32Array},
         error::ArrowError,
     };
     use futures::FutureExt;
     use std::collections::HashSet;
+    use tokio::task::JoinHandle;
 
     #[tokio::test]
     async fn one_to_many_round_robin() -> Result<()> {
@@ -670,6 +767,103 @@ mod tests {
         Ok(())
     }
 
+    /// The partition of each row of `batch` hashed with `config`
+    fn hash_partitions(
+        config: &SessionConfig,
+        batch: &RecordBatch,
+    ) -> Result<Vec<usize>> {
+        let partitioning = Partitioning::Hash(vec![col("c0", &batch.schema())?], 8);
+        let mut partitioner =
+            BatchPartitioner::try_new(partitioning, metrics::Time::new())?
+                .with_random_state(config.hash_random_state());
+        let mut partitions = vec![0; batch.num_rows()];
+        partitioner.partition(batch.clone(), |partition, batch| {
+            let rows = batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<UInt32Array>()
+                .unwrap();
+            rows.values()
+                .iter()
+                .for_each(|row| partitions[*row as usize] = partition);
+            Ok(())
+        })?;
+        Ok(partitions)
+    }
+
+    #[test]
+    fn hash_partition_with_seeds() -> Result<()> {
+        let schema = test_schema();
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![Arc::new(UInt32Array::from_iter_values(0..100))],
+        )?;
+
+        let config = SessionConfig::new();
+        assert_eq!(config.hash_seeds(), [0; 4]);
+        let partitions = hash_partitions(&config, &batch)?;
+        assert_eq!(partitions, hash_partitions(&SessionConfig::new(), &batch)?);
+
+        let config = SessionConfig::new().with_hash_seeds([1, 2, 3, 4]);
+        let seeded_partitions = hash_partitions(&config, &batch)?;
+        assert_eq!(seeded_partitions, hash_partitions(&config, &batch)?);
+        assert_ne!(partitions, seeded_partitions);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn many_to_many_round_robin_deterministic() -> Result<()> {
+        let session_ctx = SessionContext::with_config(
+            SessionConfig::new().with_deterministic_output(true),
+        );
+        let task_ctx = session_ctx.task_ctx();
+        let schema = test_schema();
+
+        // 4 input partitions of 5 batches of a single row
+        let partitions = (0..4u32)
+            .map(|partition| {
+                (0..5u32)
+                    .map(|i| {
+                        RecordBatch::try_new(
+                            schema.clone(),
+                            vec![Arc::new(UInt32Array::from(vec![partition * 100 + i]))],
+                        )
+                    })
+                    .collect::<ArrowResult<Vec<_>>>()
+            })
+            .collect::<ArrowResult<Vec<_>>>()?;
+        let exec = MemoryExec::try_new(&partitions, schema, None)?;
+        let exec =
+            RepartitionExec::try_new(Arc::new(exec), Partitioning::RoundRobinBatch(2))?;
+
+        let mut values = vec![];
+        for i in 0..2 {
+            let stream = exec.execute(i, task_ctx.clone()).await?;
+            let batches = crate::physical_plan::common::collect(stream).await?;
+            values.push(
+                batches
+                    .iter()
+                    .map(|batch| {
+                        let array =
+                            batch.column(0).as_any().downcast_ref::<UInt32Array>();
+                        array.unwrap().value(0)
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        // the batches of each input partition are dealt in turn
+        assert_eq!(
+            values,
+            vec![
+                vec![0, 2, 4, 100, 102, 104, 200, 202, 204, 300, 302, 304],
+                vec![1, 3, 101, 103, 201, 203, 301, 303],
+            ]
+        );
+
+        Ok(())
+    }
+
     fn test_schema() -> Arc<Schema> {
         Arc::new(Schema::new(vec![Field::new("c0", DataType::UInt32, false)]))
     }
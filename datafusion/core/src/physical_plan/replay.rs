@@ -0,0 +1,454 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Debugging facility to record the batches flowing through a plan edge and
+//! replay them later.
+//!
+//! [`RecordExec`] is inserted above the operator whose output should be
+//! captured: it passes batches through unchanged and writes every partition
+//! to its own Arrow IPC file. [`ReplayExec`] is a leaf that reads these files
+//! back, reproducing the same partitions and batch boundaries, so the
+//! operators above the recorded edge can be debugged in isolation from the
+//! original data source.
+
+use std::any::Any;
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::datatypes::SchemaRef;
+use arrow::error::Result as ArrowResult;
+use arrow::ipc::reader::FileReader;
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::StreamExt;
+use log::error;
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::task::{self, JoinHandle};
+
+use super::common::IPCWriter;
+use super::expressions::PhysicalSortExpr;
+use super::metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet};
+use super::stream::RecordBatchReceiverStream;
+use super::{
+    DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream, Statistics,
+};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+
+/// Returns the path of the file holding the recorded batches of `partition`
+fn partition_file(dir: &Path, partition: usize) -> PathBuf {
+    dir.join(format!("part-{}.arrow", partition))
+}
+
+/// Passes the batches of its input through unchanged, writing the batches of
+/// each partition to `<dir>/part-<partition>.arrow` in the Arrow IPC format.
+///
+/// The directory is only created, and the files of the partitions recorded
+/// by a previous run beyond the partitions of the input removed, once the
+/// plan is executed. The files are written on blocking threads.
+#[derive(Debug)]
+pub struct RecordExec {
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Directory the batches are recorded to
+    dir: PathBuf,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl RecordExec {
+    /// Create a new RecordExec that records the output of `input` to `dir`
+    pub fn new(input: Arc<dyn ExecutionPlan>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            input,
+            dir: dir.into(),
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Directory the batches are recorded to
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for RecordExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn maintains_input_order(&self) -> bool {
+        true
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(RecordExec::new(
+            children[0].clone(),
+            self.dir.clone(),
+        )))
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let schema = self.input.schema();
+        let input = self.input.execute(partition, context).await?;
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+
+        let (batch_sender, batch_receiver) = tokio::sync::mpsc::channel(2);
+        let dir = self.dir.clone();
+        let partition_count = self.output_partitioning().partition_count();
+        let writer_schema = schema.clone();
+        let writer = task::spawn_blocking(move || {
+            write_recording(
+                batch_receiver,
+                &dir,
+                partition,
+                partition_count,
+                writer_schema,
+            )
+        });
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let join_handle = task::spawn(async move {
+            let result =
+                record_stream(input, batch_sender, writer, &sender, baseline_metrics)
+                    .await;
+            if let Err(e) = result {
+                // forward the error to the stream consumer
+                sender.send(Err(e.into())).await.ok();
+            }
+        });
+        Ok(RecordBatchReceiverStream::create(
+            &schema,
+            receiver,
+            join_handle,
+        ))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(f, "RecordExec: dir={}", self.dir.display())
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+/// Sends the batches of `input` to `sender`, and to the `writer` recording
+/// them through `batch_sender`, failing if the recording failed
+async fn record_stream(
+    mut input: SendableRecordBatchStream,
+    batch_sender: Sender<RecordBatch>,
+    writer: JoinHandle<Result<()>>,
+    sender: &Sender<ArrowResult<RecordBatch>>,
+    baseline_metrics: BaselineMetrics,
+) -> Result<()> {
+    while let Some(batch) = input.next().await {
+        let batch = batch?;
+        // the writer only stops receiving the batches once it failed
+        if batch_sender.send(batch.clone()).await.is_err() {
+            break;
+        }
+        baseline_metrics.record_output(batch.num_rows());
+        if sender.send(Ok(batch)).await.is_err() {
+            // the consumer is gone, the recording is finished as is
+            return Ok(());
+        }
+    }
+    drop(batch_sender);
+    let result = writer.await.map_err(|e| {
+        DataFusionError::Execution(format!("Failed to record the batches: {}", e))
+    })?;
+    baseline_metrics.done();
+    result
+}
+
+/// Writes the batches received from `receiver` to the file of `partition`,
+/// once the recording directory `dir` is prepared
+fn write_recording(
+    mut receiver: Receiver<RecordBatch>,
+    dir: &Path,
+    partition: usize,
+    partition_count: usize,
+    schema: SchemaRef,
+) -> Result<()> {
+    prepare_recording_dir(dir, partition_count)?;
+    let mut writer = IPCWriter::new(&partition_file(dir, partition), schema.as_ref())?;
+    while let Some(batch) = receiver.blocking_recv() {
+        writer.write(&batch)?;
+    }
+    writer.finish()
+}
+
+/// Creates the recording directory `dir` if it doesn't exist, and removes
+/// the files of the partitions from `partition_count` on, recorded by a
+/// previous run and not overwritten by this one
+fn prepare_recording_dir(dir: &Path, partition_count: usize) -> Result<()> {
+    fs::create_dir_all(dir).map_err(|e| {
+        DataFusionError::Execution(format!(
+            "Could not create recording directory {:?}: {}",
+            dir, e
+        ))
+    })?;
+    let mut partition = partition_count;
+    loop {
+        // the partitions executed concurrently may remove the same files
+        match fs::remove_file(partition_file(dir, partition)) {
+            Ok(()) => partition += 1,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Leaf plan that replays the batches recorded by a [`RecordExec`], with
+/// one output partition per recorded partition.
+#[derive(Debug)]
+pub struct ReplayExec {
+    /// Directory the batches were recorded to
+    dir: PathBuf,
+    /// Schema of the recorded batches
+    schema: SchemaRef,
+    /// Number of recorded partitions
+    partition_count: usize,
+}
+
+impl ReplayExec {
+    /// Create a new ReplayExec reading the recording stored in `dir`
+    pub fn try_new(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+
+        let mut partition_count = 0;
+        while partition_file(&dir, partition_count).exists() {
+            partition_count += 1;
+        }
+        if partition_count == 0 {
+            return Err(DataFusionError::Execution(format!(
+                "No recorded partitions found in {:?}",
+                dir
+            )));
+        }
+
+        let file = File::open(partition_file(&dir, 0))?;
+        let schema = FileReader::try_new(BufReader::new(file), None)?.schema();
+
+        Ok(Self {
+            dir,
+            schema,
+            partition_count,
+        })
+    }
+
+    /// Directory the batches were recorded to
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for ReplayExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.partition_count)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        // this is a leaf node and has no children
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition >= self.partition_count {
+            return Err(DataFusionError::Internal(format!(
+                "ReplayExec invalid partition {} (expected less than {})",
+                partition, self.partition_count
+            )));
+        }
+
+        let path = partition_file(&self.dir, partition);
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let join_handle = task::spawn_blocking(move || {
+            if let Err(e) = read_recording(&sender, &path) {
+                error!("Failure while replaying recording {:?}: {}", path, e);
+                // forward the error to the stream consumer
+                sender.blocking_send(Err(e.into())).ok();
+            }
+        });
+        Ok(RecordBatchReceiverStream::create(
+            &self.schema,
+            receiver,
+            join_handle,
+        ))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "ReplayExec: dir={}, partitions={}",
+                    self.dir.display(),
+                    self.partition_count
+                )
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+fn read_recording(sender: &Sender<ArrowResult<RecordBatch>>, path: &Path) -> Result<()> {
+    let file = BufReader::new(File::open(path)?);
+    let reader = FileReader::try_new(file, None)?;
+    for batch in reader {
+        sender
+            .blocking_send(batch)
+            .map_err(|e| DataFusionError::Execution(format!("{}", e)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::collect_partitioned;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::prelude::SessionContext;
+    use crate::test::make_partition;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn record_and_replay() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let task_ctx = session_ctx.task_ctx();
+        let tmp_dir = TempDir::new()?;
+
+        let partitions = vec![
+            vec![make_partition(3), make_partition(2)],
+            vec![make_partition(5)],
+        ];
+        let schema = partitions[0][0].schema();
+        let input = Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None)?);
+
+        // the directory is only created when executing, and the partitions
+        // of a previous recording beyond the input partitions are removed
+        let dir = tmp_dir.path().join("recording");
+        let record = Arc::new(RecordExec::new(input, &dir));
+        assert!(!dir.exists());
+        fs::create_dir(&dir)?;
+        File::create(partition_file(&dir, 2))?;
+        File::create(partition_file(&dir, 3))?;
+        let recorded = collect_partitioned(record, task_ctx.clone()).await?;
+        assert!(!partition_file(&dir, 2).exists());
+
+        let replay = Arc::new(ReplayExec::try_new(&dir)?);
+        assert_eq!(replay.schema(), schema);
+        assert_eq!(replay.output_partitioning().partition_count(), 2);
+        let replayed = collect_partitioned(replay, task_ctx).await?;
+
+        // same partitions and same batch boundaries
+        assert_eq!(recorded, partitions);
+        assert_eq!(replayed, partitions);
+
+        Ok(())
+    }
+
+    #[test]
+    fn replay_missing_recording() {
+        let tmp_dir = TempDir::new().unwrap();
+        let err = ReplayExec::try_new(tmp_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("No recorded partitions found"));
+    }
+}
@@ -0,0 +1,903 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Shuffle operators exchanging the batches between the stages of a
+//! distributed query.
+//!
+//! [`ShuffleWriterExec`] repartitions each partition of the output of a stage
+//! and writes the batches of every output partition through a
+//! [`ShuffleTransport`]. [`ShuffleReaderExec`] reads them back in the next
+//! stage, each of its partitions reading the batches written to it by all the
+//! partitions of the previous stage. [`LocalShuffleTransport`] exchanges the
+//! batches through Arrow IPC files in a local (or shared) directory and
+//! [`MemoryShuffleTransport`] in memory; the distributed schedulers can plug
+//! their own transport, e.g. streaming the batches over the network.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::array::{ArrayRef, UInt32Array, UInt64Array};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use arrow::error::{ArrowError, Result as ArrowResult};
+use arrow::record_batch::RecordBatch;
+use async_trait::async_trait;
+use futures::stream::{self, BoxStream};
+use futures::{Stream, StreamExt};
+use log::error;
+use parking_lot::Mutex;
+use tokio::sync::mpsc::Sender;
+use tokio::task;
+
+use super::common::{batch_byte_size, read_spill_file, SpillWriter};
+use super::expressions::PhysicalSortExpr;
+use super::memory::MemoryStream;
+use super::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
+use super::repartition::BatchPartitioner;
+use super::stream::RecordBatchReceiverStream;
+use super::{
+    DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
+    SendableRecordBatchStream, Statistics,
+};
+use crate::error::{DataFusionError, Result};
+use crate::execution::context::TaskContext;
+use crate::execution::disk_manager::SpillCompression;
+
+/// Exchanges the batches of the shuffles between the [`ShuffleWriterExec`]s
+/// and the [`ShuffleReaderExec`]s. The batches are identified by the stage
+/// writing them, the partition of the stage writing them and the output
+/// partition they are written to.
+#[async_trait]
+pub trait ShuffleTransport: Debug + Send + Sync {
+    /// Creates the writer of the batches written by `input_partition` of the
+    /// stage `stage_id` to its `output_partition`
+    async fn writer(
+        &self,
+        stage_id: &str,
+        input_partition: usize,
+        output_partition: usize,
+        schema: SchemaRef,
+    ) -> Result<Box<dyn ShuffleWriter>>;
+
+    /// Returns the stream of the batches written by `input_partition` of the
+    /// stage `stage_id` to its `output_partition`, if any was written
+    async fn reader(
+        &self,
+        stage_id: &str,
+        input_partition: usize,
+        output_partition: usize,
+        schema: SchemaRef,
+    ) -> Result<Option<SendableRecordBatchStream>>;
+}
+
+/// Writes the batches of an output partition of a shuffle
+#[async_trait]
+pub trait ShuffleWriter: Send {
+    /// Writes a batch of the output partition
+    async fn write(&mut self, batch: RecordBatch) -> Result<()>;
+
+    /// Completes the output partition, making its batches readable
+    async fn finish(self: Box<Self>) -> Result<()>;
+}
+
+/// Exchanges the batches of the shuffles through Arrow IPC stream files,
+/// written to `<dir>/<stage_id>/<output_partition>/data-<input_partition>.arrow`
+/// and compressed like the spill files
+#[derive(Debug, Clone)]
+pub struct LocalShuffleTransport {
+    dir: PathBuf,
+    compression: SpillCompression,
+}
+
+impl LocalShuffleTransport {
+    /// Create a new transport writing uncompressed files under `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            compression: SpillCompression::default(),
+        }
+    }
+
+    /// Compress the files with `compression`, e.g. with the
+    /// [`spill_compression`](crate::execution::runtime_env::RuntimeEnv::spill_compression)
+    /// of the runtime. The readers and the writers of a shuffle must use the
+    /// same compression.
+    pub fn with_compression(mut self, compression: SpillCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// The directory the files are written under
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(
+        &self,
+        stage_id: &str,
+        input_partition: usize,
+        output_partition: usize,
+    ) -> PathBuf {
+        self.dir
+            .join(stage_id)
+            .join(output_partition.to_string())
+            .join(format!("data-{}.arrow", input_partition))
+    }
+}
+
+#[async_trait]
+impl ShuffleTransport for LocalShuffleTransport {
+    async fn writer(
+        &self,
+        stage_id: &str,
+        input_partition: usize,
+        output_partition: usize,
+        schema: SchemaRef,
+    ) -> Result<Box<dyn ShuffleWriter>> {
+        let path = self.path(stage_id, input_partition, output_partition);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // the readers only see the file once completely written
+        let tmp_path = path.with_extension("arrow.tmp");
+        let writer = SpillWriter::try_new(&tmp_path, schema.as_ref(), self.compression)?;
+        Ok(Box::new(LocalShuffleWriter {
+            writer,
+            tmp_path,
+            path,
+        }))
+    }
+
+    async fn reader(
+        &self,
+        stage_id: &str,
+        input_partition: usize,
+        output_partition: usize,
+        schema: SchemaRef,
+    ) -> Result<Option<SendableRecordBatchStream>> {
+        let path = self.path(stage_id, input_partition, output_partition);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let compression = self.compression;
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let join_handle = task::spawn_blocking(move || {
+            if let Err(e) = read_shuffle_file(&sender, &path, compression) {
+                error!("Failure while reading shuffle file {:?}: {}", path, e);
+                // forward the error to the stream consumer
+                sender.blocking_send(Err(e.into())).ok();
+            }
+        });
+        Ok(Some(RecordBatchReceiverStream::create(
+            &schema,
+            receiver,
+            join_handle,
+        )))
+    }
+}
+
+fn read_shuffle_file(
+    sender: &Sender<ArrowResult<RecordBatch>>,
+    path: &Path,
+    compression: SpillCompression,
+) -> Result<()> {
+    for batch in read_spill_file(path, compression)? {
+        sender
+            .blocking_send(batch)
+            .map_err(|e| DataFusionError::Execution(format!("{}", e)))?;
+    }
+    Ok(())
+}
+
+/// Writes an output partition of a [`LocalShuffleTransport`] to a temporary
+/// file, renamed to the file read by the readers when finished
+struct LocalShuffleWriter {
+    writer: SpillWriter,
+    tmp_path: PathBuf,
+    path: PathBuf,
+}
+
+#[async_trait]
+impl ShuffleWriter for LocalShuffleWriter {
+    async fn write(&mut self, batch: RecordBatch) -> Result<()> {
+        self.writer.write(&batch)
+    }
+
+    async fn finish(self: Box<Self>) -> Result<()> {
+        let Self {
+            writer,
+            tmp_path,
+            path,
+        } = *self;
+        writer.finish()?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+}
+
+/// The batches of the shuffles of a [`MemoryShuffleTransport`], by stage,
+/// input partition and output partition
+type ShuffleBatches = HashMap<(String, usize, usize), Vec<RecordBatch>>;
+
+/// Exchanges the batches of the shuffles in memory, between the stages of
+/// the same process
+#[derive(Debug, Default, Clone)]
+pub struct MemoryShuffleTransport {
+    batches: Arc<Mutex<ShuffleBatches>>,
+}
+
+impl MemoryShuffleTransport {
+    /// Create a new empty transport
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drops the batches written by the stage `stage_id`
+    pub fn remove_stage(&self, stage_id: &str) {
+        self.batches
+            .lock()
+            .retain(|(stage, _, _), _| stage != stage_id);
+    }
+}
+
+#[async_trait]
+impl ShuffleTransport for MemoryShuffleTransport {
+    async fn writer(
+        &self,
+        stage_id: &str,
+        input_partition: usize,
+        output_partition: usize,
+        _schema: SchemaRef,
+    ) -> Result<Box<dyn ShuffleWriter>> {
+        Ok(Box::new(MemoryShuffleWriter {
+            key: (stage_id.to_owned(), input_partition, output_partition),
+            batches: vec![],
+            transport: self.batches.clone(),
+        }))
+    }
+
+    async fn reader(
+        &self,
+        stage_id: &str,
+        input_partition: usize,
+        output_partition: usize,
+        schema: SchemaRef,
+    ) -> Result<Option<SendableRecordBatchStream>> {
+        let key = (stage_id.to_owned(), input_partition, output_partition);
+        match self.batches.lock().get(&key) {
+            Some(batches) => Ok(Some(Box::pin(MemoryStream::try_new(
+                batches.clone(),
+                schema,
+                None,
+            )?))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Buffers the batches of an output partition until it is finished
+struct MemoryShuffleWriter {
+    key: (String, usize, usize),
+    batches: Vec<RecordBatch>,
+    transport: Arc<Mutex<ShuffleBatches>>,
+}
+
+#[async_trait]
+impl ShuffleWriter for MemoryShuffleWriter {
+    async fn write(&mut self, batch: RecordBatch) -> Result<()> {
+        self.batches.push(batch);
+        Ok(())
+    }
+
+    async fn finish(self: Box<Self>) -> Result<()> {
+        self.transport.lock().insert(self.key, self.batches);
+        Ok(())
+    }
+}
+
+/// The schema of the output of a [`ShuffleWriterExec`]: a row per output
+/// partition written by the partition of the stage, with the numbers of
+/// batches, rows and in-memory bytes written to it
+pub fn shuffle_write_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("output_partition", DataType::UInt32, false),
+        Field::new("num_batches", DataType::UInt64, false),
+        Field::new("num_rows", DataType::UInt64, false),
+        Field::new("num_bytes", DataType::UInt64, false),
+    ]))
+}
+
+#[derive(Debug, Clone)]
+struct ShuffleWriteMetrics {
+    /// Time spent writing the batches
+    write_time: metrics::Time,
+    /// Time spent repartitioning the batches
+    repart_time: metrics::Time,
+    input_rows: metrics::Count,
+    output_rows: metrics::Count,
+}
+
+impl ShuffleWriteMetrics {
+    fn new(partition: usize, metrics: &ExecutionPlanMetricsSet) -> Self {
+        Self {
+            write_time: MetricBuilder::new(metrics).subset_time("write_time", partition),
+            repart_time: MetricBuilder::new(metrics)
+                .subset_time("repart_time", partition),
+            input_rows: MetricBuilder::new(metrics).counter("input_rows", partition),
+            output_rows: MetricBuilder::new(metrics).output_rows(partition),
+        }
+    }
+}
+
+/// Repartitions each partition of its input, the output of a stage, with a
+/// hash or round robin partitioning and writes the batches of every output
+/// partition through a [`ShuffleTransport`]. Each partition of this plan
+/// outputs the statistics of the output partitions written by the same
+/// partition of its input, with the schema [`shuffle_write_schema`].
+#[derive(Debug)]
+pub struct ShuffleWriterExec {
+    /// The output of the stage
+    input: Arc<dyn ExecutionPlan>,
+    /// Identifier of the stage, unique among the stages of the shuffles
+    /// exchanged through `transport`
+    stage_id: String,
+    /// The partitioning of the output partitions
+    partitioning: Partitioning,
+    /// The transport the output partitions are written through
+    transport: Arc<dyn ShuffleTransport>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl ShuffleWriterExec {
+    /// Create a new ShuffleWriterExec, erroring if `partitioning` is neither
+    /// a hash nor a round robin partitioning
+    pub fn try_new(
+        input: Arc<dyn ExecutionPlan>,
+        stage_id: impl Into<String>,
+        partitioning: Partitioning,
+        transport: Arc<dyn ShuffleTransport>,
+    ) -> Result<Self> {
+        match partitioning {
+            Partitioning::Hash(_, _) | Partitioning::RoundRobinBatch(_) => Ok(Self {
+                input,
+                stage_id: stage_id.into(),
+                partitioning,
+                transport,
+                metrics: ExecutionPlanMetricsSet::new(),
+            }),
+            other => Err(DataFusionError::Plan(format!(
+                "Unsupported shuffle partitioning scheme {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// The output of the stage
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Identifier of the stage
+    pub fn stage_id(&self) -> &str {
+        &self.stage_id
+    }
+
+    /// The partitioning of the output partitions
+    pub fn partitioning(&self) -> &Partitioning {
+        &self.partitioning
+    }
+
+    /// The transport the output partitions are written through
+    pub fn transport(&self) -> &Arc<dyn ShuffleTransport> {
+        &self.transport
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for ShuffleWriterExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        shuffle_write_schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(
+            self.input.output_partitioning().partition_count(),
+        )
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(ShuffleWriterExec::try_new(
+            children[0].clone(),
+            self.stage_id.clone(),
+            self.partitioning.clone(),
+            self.transport.clone(),
+        )?))
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let random_state = context.session_config().hash_random_state();
+        let input = self.input.execute(partition, context).await?;
+        let write = ShuffleWrite {
+            stage_id: self.stage_id.clone(),
+            partition,
+            partitioning: self.partitioning.clone(),
+            random_state,
+            transport: self.transport.clone(),
+            metrics: ShuffleWriteMetrics::new(partition, &self.metrics),
+        };
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let join_handle = task::spawn(async move {
+            let result = write
+                .write(input)
+                .await
+                .map_err(|e| ArrowError::ExternalError(Box::new(e)));
+            sender.send(result).await.ok();
+        });
+        Ok(RecordBatchReceiverStream::create(
+            &shuffle_write_schema(),
+            receiver,
+            join_handle,
+        ))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "ShuffleWriterExec: stage_id={}, partitioning={:?}",
+                    self.stage_id, self.partitioning
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// Writes a partition of the output of a stage
+struct ShuffleWrite {
+    stage_id: String,
+    partition: usize,
+    partitioning: Partitioning,
+    /// The state of the hashes of the hash partitioning
+    random_state: ahash::RandomState,
+    transport: Arc<dyn ShuffleTransport>,
+    metrics: ShuffleWriteMetrics,
+}
+
+/// The writer of an output partition and the numbers of batches, rows and
+/// bytes written to it
+type OutputPartitionWriter = (Box<dyn ShuffleWriter>, [u64; 3]);
+
+impl ShuffleWrite {
+    async fn write(self, mut input: SendableRecordBatchStream) -> Result<RecordBatch> {
+        let num_partitions = self.partitioning.partition_count();
+        let mut partitioner = BatchPartitioner::try_new(
+            self.partitioning.clone(),
+            self.metrics.repart_time.clone(),
+        )?
+        .with_random_state(self.random_state.clone());
+        // the writers are created on demand, as some output partitions
+        // may get no rows
+        let mut writers: Vec<Option<OutputPartitionWriter>> =
+            (0..num_partitions).map(|_| None).collect();
+
+        while let Some(batch) = input.next().await {
+            let batch = batch?;
+            self.metrics.input_rows.add(batch.num_rows());
+
+            let mut output_batches = vec![];
+            partitioner.partition(batch, |output_partition, output_batch| {
+                output_batches.push((output_partition, output_batch));
+                Ok(())
+            })?;
+
+            for (output_partition, output_batch) in output_batches {
+                let timer = self.metrics.write_time.timer();
+                if writers[output_partition].is_none() {
+                    let writer = self
+                        .transport
+                        .writer(
+                            &self.stage_id,
+                            self.partition,
+                            output_partition,
+                            input.schema(),
+                        )
+                        .await?;
+                    writers[output_partition] = Some((writer, [0; 3]));
+                }
+                let (writer, stats) = writers[output_partition].as_mut().unwrap();
+                stats[0] += 1;
+                stats[1] += output_batch.num_rows() as u64;
+                stats[2] += batch_byte_size(&output_batch) as u64;
+                self.metrics.output_rows.add(output_batch.num_rows());
+                writer.write(output_batch).await?;
+                timer.done();
+            }
+        }
+
+        let mut output_partitions = vec![];
+        let mut stats = [vec![], vec![], vec![]];
+        for (output_partition, writer) in writers.into_iter().enumerate() {
+            if let Some((writer, writer_stats)) = writer {
+                let timer = self.metrics.write_time.timer();
+                writer.finish().await?;
+                timer.done();
+                output_partitions.push(output_partition as u32);
+                for (stat, value) in stats.iter_mut().zip(writer_stats) {
+                    stat.push(value);
+                }
+            }
+        }
+
+        let [num_batches, num_rows, num_bytes] = stats;
+        Ok(RecordBatch::try_new(
+            shuffle_write_schema(),
+            vec![
+                Arc::new(UInt32Array::from(output_partitions)) as ArrayRef,
+                Arc::new(UInt64Array::from(num_batches)),
+                Arc::new(UInt64Array::from(num_rows)),
+                Arc::new(UInt64Array::from(num_bytes)),
+            ],
+        )?)
+    }
+}
+
+/// Reads the output partitions of a stage written by a [`ShuffleWriterExec`]
+/// through a [`ShuffleTransport`]. Each partition of this plan outputs the
+/// batches written to the same output partition by all the partitions of the
+/// stage, in the order of these partitions.
+#[derive(Debug)]
+pub struct ShuffleReaderExec {
+    /// Identifier of the stage
+    stage_id: String,
+    /// The schema of the output of the stage
+    schema: SchemaRef,
+    /// Number of partitions of the stage writing the output partitions
+    input_partitions: usize,
+    /// Number of output partitions
+    output_partitions: usize,
+    /// The transport the output partitions are read through
+    transport: Arc<dyn ShuffleTransport>,
+}
+
+impl ShuffleReaderExec {
+    /// Create a new ShuffleReaderExec reading the `output_partitions` written
+    /// by the `input_partitions` of the stage `stage_id`
+    pub fn new(
+        stage_id: impl Into<String>,
+        schema: SchemaRef,
+        input_partitions: usize,
+        output_partitions: usize,
+        transport: Arc<dyn ShuffleTransport>,
+    ) -> Self {
+        Self {
+            stage_id: stage_id.into(),
+            schema,
+            input_partitions,
+            output_partitions,
+            transport,
+        }
+    }
+
+    /// Identifier of the stage
+    pub fn stage_id(&self) -> &str {
+        &self.stage_id
+    }
+
+    /// Number of partitions of the stage writing the output partitions
+    pub fn input_partitions(&self) -> usize {
+        self.input_partitions
+    }
+
+    /// The transport the output partitions are read through
+    pub fn transport(&self) -> &Arc<dyn ShuffleTransport> {
+        &self.transport
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for ShuffleReaderExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(self.output_partitions)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        _: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(self)
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        _context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition >= self.output_partitions {
+            return Err(DataFusionError::Internal(format!(
+                "ShuffleReaderExec invalid partition {} (expected less than {})",
+                partition, self.output_partitions
+            )));
+        }
+
+        let mut readers = Vec::with_capacity(self.input_partitions);
+        for input_partition in 0..self.input_partitions {
+            let reader = self
+                .transport
+                .reader(
+                    &self.stage_id,
+                    input_partition,
+                    partition,
+                    self.schema.clone(),
+                )
+                .await?;
+            readers.extend(reader);
+        }
+        Ok(Box::pin(ShuffleReaderStream {
+            schema: self.schema.clone(),
+            input: stream::iter(readers).flatten().boxed(),
+        }))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "ShuffleReaderExec: stage_id={}, input_partitions={}, output_partitions={}",
+                    self.stage_id, self.input_partitions, self.output_partitions
+                )
+            }
+        }
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+/// The batches of an output partition, read from the partitions of the stage
+/// one after the other
+struct ShuffleReaderStream {
+    schema: SchemaRef,
+    input: BoxStream<'static, ArrowResult<RecordBatch>>,
+}
+
+impl Stream for ShuffleReaderStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.input.poll_next_unpin(cx)
+    }
+}
+
+impl RecordBatchStream for ShuffleReaderStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_sorted_eq;
+    use crate::from_slice::FromSlice;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::{collect, collect_partitioned};
+    use crate::prelude::SessionContext;
+    use arrow::array::Int32Array;
+    use tempfile::TempDir;
+
+    fn batch(schema: &SchemaRef, values: &[i32]) -> RecordBatch {
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from_slice(values))],
+        )
+        .unwrap()
+    }
+
+    async fn shuffle(transport: Arc<dyn ShuffleTransport>) -> Result<()> {
+        let task_ctx = SessionContext::new().task_ctx();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let partitions = vec![
+            vec![batch(&schema, &[1, 2, 3]), batch(&schema, &[4, 1])],
+            vec![batch(&schema, &[2, 5])],
+        ];
+        let input = Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None)?);
+        let partitioning = Partitioning::Hash(vec![col("a", &schema)?], 3);
+        let writer = Arc::new(ShuffleWriterExec::try_new(
+            input,
+            "stage_1",
+            partitioning,
+            transport.clone(),
+        )?);
+
+        let written = collect_partitioned(writer, task_ctx.clone()).await?;
+        assert_eq!(written.len(), 2);
+        let written_rows: u64 = written
+            .iter()
+            .flatten()
+            .map(|batch| {
+                let num_rows = batch
+                    .column(2)
+                    .as_any()
+                    .downcast_ref::<UInt64Array>()
+                    .unwrap();
+                num_rows.iter().flatten().sum::<u64>()
+            })
+            .sum();
+        assert_eq!(written_rows, 7);
+
+        let reader = Arc::new(ShuffleReaderExec::new(
+            "stage_1",
+            schema.clone(),
+            2,
+            3,
+            transport,
+        ));
+        let read = collect_partitioned(reader.clone(), task_ctx.clone()).await?;
+        assert_eq!(read.len(), 3);
+        // the rows with the same key are read by the same partition
+        for value in 1..=5 {
+            let partitions_with_value = read
+                .iter()
+                .filter(|batches| {
+                    batches.iter().any(|batch| {
+                        let a = batch.column(0).as_any().downcast_ref::<Int32Array>();
+                        a.unwrap().iter().any(|a| a == Some(value))
+                    })
+                })
+                .count();
+            assert_eq!(partitions_with_value, 1);
+        }
+
+        let batches = collect(reader, task_ctx).await?;
+        let expected = vec![
+            "+---+", "| a |", "+---+", "| 1 |", "| 1 |", "| 2 |", "| 2 |", "| 3 |",
+            "| 4 |", "| 5 |", "+---+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn local_shuffle() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        shuffle(Arc::new(LocalShuffleTransport::new(tmp_dir.path()))).await
+    }
+
+    #[tokio::test]
+    async fn local_shuffle_unfinished_file() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let transport = LocalShuffleTransport::new(tmp_dir.path());
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(vec![1, 2]))],
+        )?;
+
+        let mut writer = transport.writer("stage_1", 0, 0, schema.clone()).await?;
+        writer.write(batch).await?;
+        // the partially written file is not read
+        let reader = transport.reader("stage_1", 0, 0, schema.clone()).await?;
+        assert!(reader.is_none());
+
+        writer.finish().await?;
+        let reader = transport.reader("stage_1", 0, 0, schema).await?.unwrap();
+        let batches = reader.collect::<Vec<_>>().await;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].as_ref().unwrap().num_rows(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn compressed_local_shuffle() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let transport = LocalShuffleTransport::new(tmp_dir.path())
+            .with_compression(SpillCompression::Zstd);
+        shuffle(Arc::new(transport)).await
+    }
+
+    #[tokio::test]
+    async fn memory_shuffle() -> Result<()> {
+        let transport = MemoryShuffleTransport::new();
+        shuffle(Arc::new(transport.clone())).await?;
+        transport.remove_stage("stage_1");
+        assert!(transport.batches.lock().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn shuffle_unsupported_partitioning() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let input = Arc::new(MemoryExec::try_new(&[], schema, None)?);
+        let err = ShuffleWriterExec::try_new(
+            input,
+            "stage_1",
+            Partitioning::UnknownPartitioning(2),
+            Arc::new(MemoryShuffleTransport::new()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Unsupported shuffle partitioning scheme UnknownPartitioning(2)"
+        );
+        Ok(())
+    }
+}
@@ -21,11 +21,14 @@
 
 use crate::error::{DataFusionError, Result};
 use crate::execution::context::TaskContext;
+use crate::execution::disk_manager::SpillCompression;
 use crate::execution::memory_manager::{
     human_readable_size, ConsumerType, MemoryConsumer, MemoryConsumerId, MemoryManager,
 };
 use crate::execution::runtime_env::RuntimeEnv;
-use crate::physical_plan::common::{batch_byte_size, IPCWriter, SizedRecordBatchStream};
+use crate::physical_plan::common::{
+    batch_byte_size, read_spill_file, write_spill_file, SizedRecordBatchStream,
+};
 use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::metrics::{
     BaselineMetrics, CompositeMetricsSet, MemTrackingMetrics, MetricsSet,
@@ -43,7 +46,6 @@ pub use arrow::compute::SortOptions;
 use arrow::compute::{concat, lexsort_to_indices, take, SortColumn, TakeOptions};
 use arrow::datatypes::SchemaRef;
 use arrow::error::Result as ArrowResult;
-use arrow::ipc::reader::FileReader;
 use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
 use futures::lock::Mutex;
@@ -53,8 +55,6 @@ use std::any::Any;
 use std::cmp::min;
 use std::fmt;
 use std::fmt::{Debug, Formatter};
-use std::fs::File;
-use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -157,7 +157,11 @@ impl ExternalSorter {
             let mut spills = self.spills.lock().await;
 
             for spill in spills.drain(..) {
-                let stream = read_spill_as_stream(spill, self.schema.clone())?;
+                let stream = read_spill_as_stream(
+                    spill,
+                    self.schema.clone(),
+                    self.runtime.spill_compression,
+                )?;
                 streams.push(SortedStream::new(stream, 0));
             }
             let tracking_metrics = self
@@ -272,8 +276,13 @@ impl MemoryConsumer for ExternalSorter {
             tracking_metrics,
         );
 
-        spill_partial_sorted_stream(&mut stream?, spillfile.path(), self.schema.clone())
-            .await?;
+        spill_partial_sorted_stream(
+            &mut stream?,
+            spillfile.path(),
+            self.schema.clone(),
+            self.runtime.spill_compression,
+        )
+        .await?;
         let mut spills = self.spills.lock().await;
         let used = self.metrics.mem_used().set(0);
         self.metrics.record_spill(used);
@@ -514,6 +523,7 @@ impl SortedSizedRecordBatchStream {
     ) -> Self {
         let size = batches.iter().map(batch_byte_size).sum::<usize>()
             + sorted_iter.memory_size();
+        let metrics = metrics.with_name("SortedSizedRecordBatchStream");
         metrics.init_mem_used(size);
         let num_cols = batches[0].num_columns();
         SortedSizedRecordBatchStream {
@@ -579,10 +589,12 @@ async fn spill_partial_sorted_stream(
     in_mem_stream: &mut SendableRecordBatchStream,
     path: &Path,
     schema: SchemaRef,
+    compression: SpillCompression,
 ) -> Result<()> {
     let (sender, receiver) = tokio::sync::mpsc::channel(2);
     let path: PathBuf = path.into();
-    let handle = task::spawn_blocking(move || write_sorted(receiver, path, schema));
+    let handle =
+        task::spawn_blocking(move || write_sorted(receiver, path, schema, compression));
     while let Some(item) = in_mem_stream.next().await {
         sender.send(item).await.ok();
     }
@@ -599,13 +611,14 @@ async fn spill_partial_sorted_stream(
 fn read_spill_as_stream(
     path: NamedTempFile,
     schema: SchemaRef,
+    compression: SpillCompression,
 ) -> Result<SendableRecordBatchStream> {
     let (sender, receiver): (
         Sender<ArrowResult<RecordBatch>>,
         Receiver<ArrowResult<RecordBatch>>,
     ) = tokio::sync::mpsc::channel(2);
     let join_handle = task::spawn_blocking(move || {
-        if let Err(e) = read_spill(sender, path.path()) {
+        if let Err(e) = read_spill(sender, path.path(), compression) {
             error!("Failure while reading spill file: {:?}. Error: {}", path, e);
         }
     });
@@ -620,24 +633,29 @@ fn write_sorted(
     mut receiver: Receiver<ArrowResult<RecordBatch>>,
     path: PathBuf,
     schema: SchemaRef,
+    compression: SpillCompression,
 ) -> Result<()> {
-    let mut writer = IPCWriter::new(path.as_ref(), schema.as_ref())?;
-    while let Some(batch) = receiver.blocking_recv() {
-        writer.write(&batch?)?;
-    }
-    writer.finish()?;
+    let stats = write_spill_file(
+        path.as_ref(),
+        schema.as_ref(),
+        compression,
+        std::iter::from_fn(|| receiver.blocking_recv()),
+    )?;
     debug!(
         "Spilled {} batches of total {} rows to disk, memory released {}",
-        writer.num_batches,
-        writer.num_rows,
-        human_readable_size(writer.num_bytes as usize),
+        stats.num_batches,
+        stats.num_rows,
+        human_readable_size(stats.num_bytes as usize),
     );
     Ok(())
 }
 
-fn read_spill(sender: Sender<ArrowResult<RecordBatch>>, path: &Path) -> Result<()> {
-    let file = BufReader::new(File::open(&path)?);
-    let reader = FileReader::try_new(file, None)?;
+fn read_spill(
+    sender: Sender<ArrowResult<RecordBatch>>,
+    path: &Path,
+    compression: SpillCompression,
+) -> Result<()> {
+    let reader = read_spill_file(path, compression)?;
     for batch in reader {
         sender
             .blocking_send(batch)
@@ -952,6 +970,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -1010,8 +1029,20 @@ mod tests {
 
     #[tokio::test]
     async fn test_sort_spill() -> Result<()> {
+        sort_spill(SpillCompression::Uncompressed).await
+    }
+
+    #[tokio::test]
+    async fn test_sort_spill_compressed() -> Result<()> {
+        sort_spill(SpillCompression::Zstd).await?;
+        sort_spill(SpillCompression::Lz4).await
+    }
+
+    async fn sort_spill(compression: SpillCompression) -> Result<()> {
         // trigger spill there will be 4 batches with 5.5KB for each
-        let config = RuntimeConfig::new().with_memory_limit(12288, 1.0);
+        let config = RuntimeConfig::new()
+            .with_memory_limit(12288, 1.0)
+            .with_spill_compression(compression);
         let runtime = Arc::new(RuntimeEnv::new(config)?);
         let session_ctx = SessionContext::with_config_rt(SessionConfig::new(), runtime);
 
@@ -1029,6 +1060,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -191,7 +191,7 @@ impl ExecutionPlan for SortPreservingMergeExec {
                     .into_iter()
                     .map(|part_i| {
                         let (sender, receiver) = mpsc::channel(1);
-                        let join_handle = spawn_execution(
+                        let task = spawn_execution(
                             self.input.clone(),
                             sender,
                             part_i,
@@ -199,10 +199,8 @@ impl ExecutionPlan for SortPreservingMergeExec {
                         );
 
                         SortedStream::new(
-                            RecordBatchReceiverStream::create(
-                                &schema,
-                                receiver,
-                                join_handle,
+                            RecordBatchReceiverStream::create_with_task(
+                                &schema, receiver, task,
                             ),
                             0,
                         )
@@ -903,6 +901,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -990,6 +989,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -26,6 +26,14 @@ use tokio_stream::wrappers::ReceiverStream;
 
 use super::common::AbortOnDropSingle;
 use super::{RecordBatchStream, SendableRecordBatchStream};
+use crate::execution::executor::SpawnedTask;
+
+/// Cancels the task producing the batches of a stream when it is dropped
+#[derive(Debug)]
+enum DropHelper {
+    JoinHandle(AbortOnDropSingle<()>),
+    Task(SpawnedTask<()>),
+}
 
 /// Adapter for a tokio [`ReceiverStream`] that implements the
 /// [`SendableRecordBatchStream`]
@@ -36,7 +44,7 @@ pub struct RecordBatchReceiverStream {
     inner: ReceiverStream<ArrowResult<RecordBatch>>,
 
     #[allow(dead_code)]
-    drop_helper: AbortOnDropSingle<()>,
+    drop_helper: DropHelper,
 }
 
 impl RecordBatchReceiverStream {
@@ -52,7 +60,23 @@ impl RecordBatchReceiverStream {
         Box::pin(Self {
             schema,
             inner,
-            drop_helper: AbortOnDropSingle::new(join_handle),
+            drop_helper: DropHelper::JoinHandle(AbortOnDropSingle::new(join_handle)),
+        })
+    }
+
+    /// Construct a new [`RecordBatchReceiverStream`] which will send
+    /// batches of the specfied schema from `inner`, produced by `task`
+    pub fn create_with_task(
+        schema: &SchemaRef,
+        rx: tokio::sync::mpsc::Receiver<ArrowResult<RecordBatch>>,
+        task: SpawnedTask<()>,
+    ) -> SendableRecordBatchStream {
+        let schema = schema.clone();
+        let inner = ReceiverStream::new(rx);
+        Box::pin(Self {
+            schema,
+            inner,
+            drop_helper: DropHelper::Task(task),
         })
     }
 }
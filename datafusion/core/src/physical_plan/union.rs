@@ -31,7 +31,7 @@ use super::{
     expressions::PhysicalSortExpr,
     metrics::{ExecutionPlanMetricsSet, MetricsSet},
     ColumnStatistics, DisplayFormatType, ExecutionPlan, Partitioning, RecordBatchStream,
-    SendableRecordBatchStream, Statistics,
+    SendableRecordBatchStream, Statistics, StatisticsCache,
 };
 use crate::execution::context::TaskContext;
 use crate::{
@@ -47,6 +47,8 @@ pub struct UnionExec {
     inputs: Vec<Arc<dyn ExecutionPlan>>,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
+    /// The statistics, computed on the first request
+    statistics: StatisticsCache,
 }
 
 impl UnionExec {
@@ -55,6 +57,7 @@ impl UnionExec {
         UnionExec {
             inputs,
             metrics: ExecutionPlanMetricsSet::new(),
+            statistics: StatisticsCache::default(),
         }
     }
 
@@ -156,11 +159,13 @@ impl ExecutionPlan for UnionExec {
     }
 
     fn statistics(&self) -> Statistics {
-        self.inputs
-            .iter()
-            .map(|ep| ep.statistics())
-            .reduce(stats_union)
-            .unwrap_or_default()
+        self.statistics.get_or_compute(|| {
+            self.inputs
+                .iter()
+                .map(|ep| ep.statistics())
+                .reduce(stats_union)
+                .unwrap_or_default()
+        })
     }
 
     fn benefits_from_input_partitioning(&self) -> bool {
@@ -279,6 +284,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
@@ -293,6 +299,7 @@ mod tests {
                 projection: None,
                 limit: None,
                 table_partition_cols: vec![],
+                output_ordering: None,
             },
             true,
             b',',
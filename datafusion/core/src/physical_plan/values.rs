@@ -91,7 +91,7 @@ impl ValuesExec {
     }
 
     /// provides the data
-    fn data(&self) -> Vec<RecordBatch> {
+    pub(crate) fn data(&self) -> Vec<RecordBatch> {
         self.data.clone()
     }
 }
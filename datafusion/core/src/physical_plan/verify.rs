@@ -0,0 +1,336 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! VerifyExec checks that a boolean predicate holds for every row of its input, passing the
+//! batches through unchanged and failing the query with a sample of the offending rows otherwise.
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use super::expressions::PhysicalSortExpr;
+use super::{RecordBatchStream, SendableRecordBatchStream, Statistics};
+use crate::error::{DataFusionError, Result};
+use crate::physical_plan::{
+    metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+    DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr,
+};
+use arrow::array::{Array, BooleanArray};
+use arrow::compute::filter_record_batch;
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::error::Result as ArrowResult;
+use arrow::record_batch::RecordBatch;
+use arrow::util::pretty::pretty_format_batches;
+
+use async_trait::async_trait;
+use log::debug;
+
+use crate::execution::context::TaskContext;
+use futures::stream::{Stream, StreamExt};
+
+/// The default number of offending rows reported when the verification fails
+pub const DEFAULT_VERIFY_MAX_SAMPLES: usize = 5;
+
+/// VerifyExec evaluates a boolean predicate against all input batches and fails with a sample
+/// of the offending rows as soon as a row does not satisfy it. A row satisfies the predicate
+/// only if it evaluates to `true`: `false` and `NULL` are both reported.
+#[derive(Debug)]
+pub struct VerifyExec {
+    /// The expression to verify. This expression must evaluate to a boolean value.
+    predicate: Arc<dyn PhysicalExpr>,
+    /// The input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Maximum number of offending rows included in the error
+    max_samples: usize,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl VerifyExec {
+    /// Create a VerifyExec on an input
+    pub fn try_new(
+        predicate: Arc<dyn PhysicalExpr>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Result<Self> {
+        match predicate.data_type(input.schema().as_ref())? {
+            DataType::Boolean => Ok(Self {
+                predicate,
+                input,
+                max_samples: DEFAULT_VERIFY_MAX_SAMPLES,
+                metrics: ExecutionPlanMetricsSet::new(),
+            }),
+            other => Err(DataFusionError::Plan(format!(
+                "Verify predicate must return boolean values, not {:?}",
+                other
+            ))),
+        }
+    }
+
+    /// Set the maximum number of offending rows included in the error
+    /// - defaults to `DEFAULT_VERIFY_MAX_SAMPLES`
+    pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples;
+        self
+    }
+
+    /// The expression to verify
+    pub fn predicate(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.predicate
+    }
+
+    /// The input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Maximum number of offending rows included in the error
+    pub fn max_samples(&self) -> usize {
+        self.max_samples
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for VerifyExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    /// Get the schema for this execution plan
+    fn schema(&self) -> SchemaRef {
+        // The verify operator does not make any changes to the schema of its input
+        self.input.schema()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> bool {
+        // tell optimizer this operator doesn't reorder its input
+        true
+    }
+
+    fn relies_on_input_order(&self) -> bool {
+        false
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(
+            VerifyExec::try_new(self.predicate.clone(), children[0].clone())?
+                .with_max_samples(self.max_samples),
+        ))
+    }
+
+    async fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        debug!("Start VerifyExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        Ok(Box::pin(VerifyExecStream {
+            schema: self.input.schema(),
+            predicate: self.predicate.clone(),
+            max_samples: self.max_samples,
+            input: self.input.execute(partition, context).await?,
+            baseline_metrics,
+        }))
+    }
+
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(f, "VerifyExec: {}", self.predicate)
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    /// Verification doesn't change the statistics of its input
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+/// Checks that all the rows of `batch` satisfy `predicate`, returning an error
+/// with up to `max_samples` offending rows otherwise
+fn batch_verify(
+    batch: &RecordBatch,
+    predicate: &Arc<dyn PhysicalExpr>,
+    max_samples: usize,
+) -> Result<()> {
+    let array = predicate.evaluate(batch)?.into_array(batch.num_rows());
+    let result = array
+        .as_any()
+        .downcast_ref::<BooleanArray>()
+        .ok_or_else(|| {
+            DataFusionError::Internal(
+                "Verify predicate evaluated to non-boolean value".to_string(),
+            )
+        })?;
+
+    // NULL doesn't satisfy the predicate
+    let violations: BooleanArray = (0..result.len())
+        .map(|i| Some(!(result.is_valid(i) && result.value(i))))
+        .collect();
+    let violation_count = violations.iter().filter(|v| *v == Some(true)).count();
+    if violation_count == 0 {
+        return Ok(());
+    }
+
+    let offending = filter_record_batch(batch, &violations)?;
+    let sample = offending.slice(0, max_samples.min(violation_count));
+    Err(DataFusionError::Execution(format!(
+        "Verification of {} failed for {} row(s) of a batch, sample:\n{}",
+        predicate,
+        violation_count,
+        pretty_format_batches(&[sample])?
+    )))
+}
+
+/// The VerifyExec streams wraps the input iterator and checks that all rows of its batches
+/// satisfy the predicate expression
+struct VerifyExecStream {
+    /// Output schema, which is the same as the input schema for this operator
+    schema: SchemaRef,
+    /// The expression to verify. This expression must evaluate to a boolean value.
+    predicate: Arc<dyn PhysicalExpr>,
+    /// Maximum number of offending rows included in the error
+    max_samples: usize,
+    /// The input partition to verify.
+    input: SendableRecordBatchStream,
+    /// runtime metrics recording
+    baseline_metrics: BaselineMetrics,
+}
+
+impl Stream for VerifyExecStream {
+    type Item = ArrowResult<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.input.poll_next_unpin(cx).map(|x| match x {
+            Some(Ok(batch)) => {
+                let timer = self.baseline_metrics.elapsed_compute().timer();
+                let result = batch_verify(&batch, &self.predicate, self.max_samples)
+                    .map(|_| batch)
+                    .map_err(DataFusionError::into);
+                timer.done();
+                Some(result)
+            }
+            other => other,
+        });
+        self.baseline_metrics.record_poll(poll)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // same number of record batches
+        self.input.size_hint()
+    }
+}
+
+impl RecordBatchStream for VerifyExecStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::collect;
+    use crate::physical_plan::expressions::{binary, col, lit};
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::prelude::SessionContext;
+    use crate::scalar::ScalarValue;
+    use crate::test::make_partition;
+    use datafusion_expr::Operator;
+
+    fn verify_exec(threshold: i32) -> Result<Arc<dyn ExecutionPlan>> {
+        // make_partition(5) produces the values 0..5 in column "i"
+        let batch = make_partition(5);
+        let schema = batch.schema();
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None)?);
+        let predicate = binary(
+            col("i", &schema)?,
+            Operator::Lt,
+            lit(ScalarValue::Int32(Some(threshold))),
+            &schema,
+        )?;
+        Ok(Arc::new(
+            VerifyExec::try_new(predicate, input)?.with_max_samples(1),
+        ))
+    }
+
+    #[tokio::test]
+    async fn verify_passes() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let batches = collect(verify_exec(5)?, session_ctx.task_ctx()).await?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 5);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn verify_fails_with_samples() -> Result<()> {
+        let session_ctx = SessionContext::new();
+        let err = collect(verify_exec(3)?, session_ctx.task_ctx())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("failed for 2 row(s)"), "{}", err);
+        // only the first offending row is sampled
+        assert!(err.contains("| 3 |"), "{}", err);
+        assert!(!err.contains("| 4 |"), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn verify_requires_boolean_predicate() -> Result<()> {
+        let batch = make_partition(1);
+        let schema = batch.schema();
+        let input = Arc::new(MemoryExec::try_new(&[vec![batch]], schema.clone(), None)?);
+        let err = VerifyExec::try_new(col("i", &schema)?, input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Verify predicate must return boolean values, not Int32"
+        );
+        Ok(())
+    }
+}
@@ -25,7 +25,8 @@ use crate::physical_plan::metrics::{
 };
 use crate::physical_plan::{
     common, ColumnStatistics, DisplayFormatType, Distribution, ExecutionPlan,
-    Partitioning, RecordBatchStream, SendableRecordBatchStream, Statistics, WindowExpr,
+    Partitioning, RecordBatchStream, SendableRecordBatchStream, Statistics,
+    StatisticsCache, WindowExpr,
 };
 use arrow::{
     array::ArrayRef,
@@ -54,6 +55,8 @@ pub struct WindowAggExec {
     input_schema: SchemaRef,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
+    /// The statistics, computed on the first request
+    statistics: StatisticsCache,
 }
 
 impl WindowAggExec {
@@ -71,6 +74,7 @@ impl WindowAggExec {
             schema,
             input_schema,
             metrics: ExecutionPlanMetricsSet::new(),
+            statistics: StatisticsCache::default(),
         })
     }
 
@@ -187,23 +191,25 @@ impl ExecutionPlan for WindowAggExec {
     }
 
     fn statistics(&self) -> Statistics {
-        let input_stat = self.input.statistics();
-        let win_cols = self.window_expr.len();
-        let input_cols = self.input_schema.fields().len();
-        // TODO stats: some windowing function will maintain invariants such as min, max...
-        let mut column_statistics = vec![ColumnStatistics::default(); win_cols];
-        if let Some(input_col_stats) = input_stat.column_statistics {
-            column_statistics.extend(input_col_stats);
-        } else {
-            column_statistics.extend(vec![ColumnStatistics::default(); input_cols]);
-        }
-        Statistics {
-            is_exact: input_stat.is_exact,
-            num_rows: input_stat.num_rows,
-            column_statistics: Some(column_statistics),
-            // TODO stats: knowing the type of the new columns we can guess the output size
-            total_byte_size: None,
-        }
+        self.statistics.get_or_compute(|| {
+            let input_stat = self.input.statistics();
+            let win_cols = self.window_expr.len();
+            let input_cols = self.input_schema.fields().len();
+            // TODO stats: some windowing function will maintain invariants such as min, max...
+            let mut column_statistics = vec![ColumnStatistics::default(); win_cols];
+            if let Some(input_col_stats) = input_stat.column_statistics {
+                column_statistics.extend(input_col_stats);
+            } else {
+                column_statistics.extend(vec![ColumnStatistics::default(); input_cols]);
+            }
+            Statistics {
+                is_exact: input_stat.is_exact,
+                num_rows: input_stat.num_rows,
+                column_statistics: Some(column_statistics),
+                // TODO stats: knowing the type of the new columns we can guess the output size
+                total_byte_size: None,
+            }
+        })
     }
 }
 
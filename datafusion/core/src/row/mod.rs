@@ -482,6 +482,7 @@ mod tests {
                     projection: projection.clone(),
                     limit,
                     table_partition_cols: vec![],
+                    output_ordering: None,
                 },
                 &[],
             )
@@ -27,6 +27,29 @@ mod tests {
     use std::cmp::Ordering;
     use std::sync::Arc;
 
+    #[test]
+    fn scalar_size() {
+        let base = std::mem::size_of::<ScalarValue>();
+        assert_eq!(ScalarValue::Int32(Some(1)).size(), base);
+        assert_eq!(ScalarValue::Utf8(None).size(), base);
+
+        let value = ScalarValue::Utf8(Some(String::with_capacity(10)));
+        assert_eq!(value.size(), base + 10);
+
+        let list = ScalarValue::List(
+            Some(Box::new(vec![value.clone(), ScalarValue::Utf8(None)])),
+            Box::new(DataType::Utf8),
+        );
+        assert_eq!(
+            list.size(),
+            base + std::mem::size_of::<DataType>() + 2 * base + 10
+        );
+        assert_eq!(
+            ScalarValue::size_of_slice(&[value, list.clone()]),
+            base + 10 + list.size()
+        );
+    }
+
     #[test]
     fn scalar_decimal_test() {
         let decimal_value = ScalarValue::Decimal128(Some(123), 10, 1);
@@ -545,6 +568,10 @@ mod tests {
             ),
             make_test_case!(i32_vals, IntervalYearMonthArray, IntervalYearMonth),
             make_test_case!(i64_vals, IntervalDayTimeArray, IntervalDayTime),
+            make_test_case!(i64_vals, DurationSecondArray, DurationSecond),
+            make_test_case!(i64_vals, DurationMillisecondArray, DurationMillisecond),
+            make_test_case!(i64_vals, DurationMicrosecondArray, DurationMicrosecond),
+            make_test_case!(i64_vals, DurationNanosecondArray, DurationNanosecond),
             make_str_dict_test_case!(str_vals, Int8Type, Utf8),
             make_str_dict_test_case!(str_vals, Int16Type, Utf8),
             make_str_dict_test_case!(str_vals, Int32Type, Utf8),
@@ -1172,6 +1199,24 @@ mod tests {
         assert_eq!(array, &expected);
     }
 
+    #[test]
+    fn test_empty_nested_lists() {
+        let item_type =
+            DataType::List(Box::new(Field::new("item", DataType::Int32, true)));
+        let empty =
+            ScalarValue::List(Some(Box::new(vec![])), Box::new(item_type.clone()));
+        let null = ScalarValue::List(None, Box::new(item_type.clone()));
+
+        let array = ScalarValue::iter_to_array(vec![empty.clone(), null, empty]).unwrap();
+        let array = array.as_any().downcast_ref::<ListArray>().unwrap();
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.values().len(), 0);
+        assert_eq!(array.values().data_type(), &item_type);
+        assert!(array.is_valid(0));
+        assert!(array.is_null(1));
+        assert_eq!(array.value_length(2), 0);
+    }
+
     #[test]
     fn scalar_timestamp_ns_utc_timezone() {
         let scalar = ScalarValue::TimestampNanosecond(
@@ -0,0 +1,396 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Parsing of the SQL interval literals, e.g. `INTERVAL '1 day 2 hours'` or
+//! `INTERVAL 'P1DT2H'`
+
+use std::iter;
+
+use sqlparser::parser::ParserError::ParserError;
+
+use crate::error::{DataFusionError, Result};
+use crate::scalar::ScalarValue;
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const NANOS_PER_DAY: i128 = 24 * 3_600 * NANOS_PER_SECOND;
+const NANOS_PER_MILLI: i128 = 1_000_000;
+/// The number of days of the fractions of months, as in PostgreSQL
+const DAYS_PER_MONTH: i128 = 30;
+/// The maximum number of digits of the fractional part of the values, the
+/// next ones being below a nanosecond
+const MAX_FRACTION_DIGITS: usize = 18;
+
+/// A unit of the values of an interval literal, with its length
+#[derive(Debug, Clone, Copy)]
+enum Unit {
+    Months(i128),
+    Days(i128),
+    Nanos(i128),
+}
+
+impl Unit {
+    fn parse(unit: &str) -> Option<Self> {
+        Some(match unit.to_lowercase().as_str() {
+            "year" | "years" => Self::Months(12),
+            "month" | "months" | "mon" | "mons" => Self::Months(1),
+            "week" | "weeks" => Self::Days(7),
+            "day" | "days" => Self::Days(1),
+            "hour" | "hours" => Self::Nanos(3_600 * NANOS_PER_SECOND),
+            "minute" | "minutes" | "min" | "mins" => Self::Nanos(60 * NANOS_PER_SECOND),
+            "second" | "seconds" | "sec" | "secs" => Self::Nanos(NANOS_PER_SECOND),
+            "millisecond" | "milliseconds" => Self::Nanos(NANOS_PER_MILLI),
+            "microsecond" | "microseconds" => Self::Nanos(1_000),
+            "nanosecond" | "nanoseconds" => Self::Nanos(1),
+            _ => return None,
+        })
+    }
+}
+
+/// The months, days and nanoseconds of an interval. As the three are stored
+/// separately, the fractions of months are carried to the days, and the
+/// fractions of days to the nanoseconds: `0.5 month` is 15 days, and `1.5
+/// day` is 1 day and 12 hours.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct IntervalParts {
+    months: i128,
+    days: i128,
+    nanos: i128,
+}
+
+impl IntervalParts {
+    /// The parts of `numerator / denominator` `unit`s, if they do not overflow
+    fn new(numerator: i128, denominator: i128, unit: Unit) -> Option<Self> {
+        let (months, remainder) = match unit {
+            Unit::Months(months) => {
+                let months = numerator.checked_mul(months)?;
+                (months / denominator, months % denominator * DAYS_PER_MONTH)
+            }
+            Unit::Days(days) => (0, numerator.checked_mul(days)?),
+            Unit::Nanos(nanos) => {
+                return Some(Self {
+                    nanos: numerator.checked_mul(nanos)? / denominator,
+                    ..Self::default()
+                })
+            }
+        };
+        Some(Self {
+            months,
+            days: remainder / denominator,
+            nanos: remainder % denominator * NANOS_PER_DAY / denominator,
+        })
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Self {
+            months: self.months.checked_add(other.months)?,
+            days: self.days.checked_add(other.days)?,
+            nanos: self.nanos.checked_add(other.nanos)?,
+        })
+    }
+
+    /// Returns the interval as the narrowest Arrow interval type holding it:
+    /// [`ScalarValue::IntervalYearMonth`] for months only,
+    /// [`ScalarValue::IntervalDayTime`] for days and milliseconds, and
+    /// [`ScalarValue::IntervalMonthDayNano`] otherwise
+    fn to_scalar(self) -> Option<ScalarValue> {
+        let months = i32::try_from(self.months).ok()?;
+        let days = i32::try_from(self.days).ok()?;
+        let nanos = i64::try_from(self.nanos).ok()?;
+        if months != 0 && days == 0 && nanos == 0 {
+            return Some(ScalarValue::IntervalYearMonth(Some(months)));
+        }
+        if months == 0 && self.nanos % NANOS_PER_MILLI == 0 {
+            if let Ok(millis) = i32::try_from(self.nanos / NANOS_PER_MILLI) {
+                return Some(ScalarValue::IntervalDayTime(Some(
+                    ((days as i64) << 32) | (millis as u32 as i64),
+                )));
+            }
+        }
+        Some(ScalarValue::IntervalMonthDayNano(Some(
+            (((months as u32 as u128) << 96)
+                | ((days as u32 as u128) << 64)
+                | (nanos as u64 as u128)) as i128,
+        )))
+    }
+}
+
+/// Parses the value of an interval literal, either a list of values
+/// followed by their unit (e.g. `1 day 2.5 hours`), the values without unit
+/// being in `leading_field` (e.g. `INTERVAL '1' DAY`), or an ISO 8601
+/// duration (e.g. `P1DT2.5H`)
+pub(crate) fn parse_interval(value: &str, leading_field: &str) -> Result<ScalarValue> {
+    let trimmed = value.trim();
+    let parts = if trimmed.starts_with('P') || trimmed.starts_with('p') {
+        parse_iso8601_duration(value, &trimmed[1..])?
+    } else {
+        let mut parts = IntervalParts::default();
+        let mut tokens = trimmed.split_whitespace();
+        while let Some(number) = tokens.next() {
+            let unit = tokens.next().unwrap_or(leading_field);
+            let unit = Unit::parse(unit).ok_or_else(|| {
+                DataFusionError::NotImplemented(format!(
+                    "Invalid input syntax for type interval: {:?}",
+                    value
+                ))
+            })?;
+            parts = add_part(value, parts, number, unit)?;
+        }
+        parts
+    };
+    parts.to_scalar().ok_or_else(|| out_of_range(value))
+}
+
+/// Parses the ISO 8601 `duration` following the `P`, e.g. `1Y2M3DT4H5M6.7S`
+fn parse_iso8601_duration(value: &str, duration: &str) -> Result<IntervalParts> {
+    let invalid = || {
+        DataFusionError::SQL(ParserError(format!(
+            "Invalid ISO 8601 duration for type interval: {:?}",
+            value
+        )))
+    };
+    let (date, time) = match duration.find(|c: char| c == 'T' || c == 't') {
+        Some(position) => (&duration[..position], Some(&duration[position + 1..])),
+        None => (duration, None),
+    };
+    // the time designator must be followed by a time component
+    if (date.is_empty() && time.is_none()) || time.map_or(false, str::is_empty) {
+        return Err(invalid());
+    }
+
+    let mut parts = IntervalParts::default();
+    for (components, is_time) in iter::once((date, false)).chain(time.map(|t| (t, true)))
+    {
+        let mut rest = components;
+        while !rest.is_empty() {
+            let end = rest
+                .find(|c: char| c.is_ascii_alphabetic())
+                .ok_or_else(invalid)?;
+            let (number, designator) = (&rest[..end], &rest[end..end + 1]);
+            let unit = match (designator.to_ascii_uppercase().as_str(), is_time) {
+                ("Y", false) => "year",
+                ("M", false) => "month",
+                ("W", false) => "week",
+                ("D", false) => "day",
+                ("H", true) => "hour",
+                ("M", true) => "minute",
+                ("S", true) => "second",
+                _ => return Err(invalid()),
+            };
+            // ISO 8601 allows a comma as decimal separator
+            let number = number.replace(',', ".");
+            parts = add_part(value, parts, &number, Unit::parse(unit).unwrap())?;
+            rest = &rest[end + 1..];
+        }
+    }
+    Ok(parts)
+}
+
+/// Adds `number` `unit`s to the `parts` of the interval literal `value`
+fn add_part(
+    value: &str,
+    parts: IntervalParts,
+    number: &str,
+    unit: Unit,
+) -> Result<IntervalParts> {
+    let (numerator, denominator) = parse_decimal(value, number)?;
+    IntervalParts::new(numerator, denominator, unit)
+        .and_then(|part| parts.checked_add(part))
+        .ok_or_else(|| out_of_range(value))
+}
+
+/// Parses the decimal `number` of the interval literal `value` exactly, as
+/// a numerator and a power of ten denominator
+fn parse_decimal(value: &str, number: &str) -> Result<(i128, i128)> {
+    let invalid = || {
+        DataFusionError::SQL(ParserError(format!(
+            "Unsupported Interval Expression with value {:?}",
+            value
+        )))
+    };
+    let (negative, digits) = match number.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, number.strip_prefix('+').unwrap_or(number)),
+    };
+    let (integer, fraction) = match digits.split_once('.') {
+        Some((integer, fraction)) => (integer, fraction),
+        None => (digits, ""),
+    };
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let mut numerator: i128 = 0;
+    let mut denominator: i128 = 1;
+    let fraction = &fraction[..fraction.len().min(MAX_FRACTION_DIGITS)];
+    for (digit, is_fraction) in integer
+        .bytes()
+        .map(|digit| (digit, false))
+        .chain(fraction.bytes().map(|digit| (digit, true)))
+    {
+        numerator = numerator
+            .checked_mul(10)
+            .and_then(|n| n.checked_add((digit - b'0') as i128))
+            .ok_or_else(|| out_of_range(value))?;
+        if is_fraction {
+            denominator *= 10;
+        }
+    }
+    Ok((if negative { -numerator } else { numerator }, denominator))
+}
+
+fn out_of_range(value: &str) -> DataFusionError {
+    DataFusionError::NotImplemented(format!(
+        "Interval field value out of range: {:?}",
+        value
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn month_day_nano(months: i32, days: i32, nanos: i64) -> ScalarValue {
+        IntervalParts {
+            months: months as i128,
+            days: days as i128,
+            nanos: nanos as i128,
+        }
+        .to_scalar()
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_compound() -> Result<()> {
+        assert_eq!(
+            parse_interval("1 day 2 hours", "second")?,
+            ScalarValue::IntervalDayTime(Some((1 << 32) | 7_200_000))
+        );
+        assert_eq!(
+            parse_interval("2 weeks", "second")?,
+            ScalarValue::IntervalDayTime(Some(14 << 32))
+        );
+        assert_eq!(
+            parse_interval("1 YEARS 2 mons", "second")?,
+            ScalarValue::IntervalYearMonth(Some(14))
+        );
+        assert_eq!(
+            parse_interval("3", "month")?,
+            ScalarValue::IntervalYearMonth(Some(3))
+        );
+        // a negative time in a positive day
+        assert_eq!(
+            parse_interval("1 day -1 hour", "second")?,
+            ScalarValue::IntervalDayTime(Some(
+                (1 << 32) | (-3_600_000_i32 as u32 as i64)
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_fractional() -> Result<()> {
+        // the fractions are exact
+        assert_eq!(
+            parse_interval("0.499 day", "second")?,
+            ScalarValue::IntervalDayTime(Some(43_113_600))
+        );
+        // the fractions of months and days are carried
+        assert_eq!(
+            parse_interval("1.5 month", "second")?,
+            month_day_nano(1, 15, 0)
+        );
+        assert_eq!(
+            parse_interval("0.05 month", "second")?,
+            ScalarValue::IntervalDayTime(Some((1 << 32) | 43_200_000))
+        );
+        // below the millisecond
+        assert_eq!(
+            parse_interval("1.5 microseconds", "second")?,
+            month_day_nano(0, 0, 1_500)
+        );
+        assert_eq!(
+            parse_interval("0.000000001", "second")?,
+            month_day_nano(0, 0, 1)
+        );
+        // beyond the milliseconds of IntervalDayTime
+        assert_eq!(
+            parse_interval("1000 hours", "second")?,
+            month_day_nano(0, 0, 3_600_000 * 1_000_000_000)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_iso8601() -> Result<()> {
+        assert_eq!(
+            parse_interval("P1DT2H", "second")?,
+            parse_interval("1 day 2 hours", "second")?
+        );
+        assert_eq!(
+            parse_interval("P1Y2M3W4DT5H6M7.5S", "second")?,
+            month_day_nano(
+                14,
+                25,
+                ((5 * 60 + 6) * 60 + 7) * 1_000_000_000 + 500_000_000
+            )
+        );
+        assert_eq!(
+            parse_interval("PT0,5S", "second")?,
+            ScalarValue::IntervalDayTime(Some(500))
+        );
+        assert_eq!(
+            parse_interval("P2M", "second")?,
+            ScalarValue::IntervalYearMonth(Some(2))
+        );
+        for invalid in ["P", "PT", "P1", "P1H", "PT1D", "P1DT", "P1.2.3D", "PxD"] {
+            assert!(
+                matches!(
+                    parse_interval(invalid, "second"),
+                    Err(DataFusionError::SQL(_))
+                ),
+                "{}",
+                invalid
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(matches!(
+            parse_interval("1 fortnight", "second"),
+            Err(DataFusionError::NotImplemented(_))
+        ));
+        assert!(matches!(
+            parse_interval("1.2.3 day", "second"),
+            Err(DataFusionError::SQL(_))
+        ));
+        assert!(matches!(
+            parse_interval("3000000000 months", "second"),
+            Err(DataFusionError::NotImplemented(_))
+        ));
+        assert!(matches!(
+            parse_interval(&"9".repeat(50), "second"),
+            Err(DataFusionError::NotImplemented(_))
+        ));
+    }
+}
@@ -21,13 +21,19 @@
 
 use crate::logical_plan::FileType;
 use sqlparser::{
-    ast::{ColumnDef, ColumnOptionDef, Statement as SQLStatement, TableConstraint},
+    ast::{
+        ColumnDef, ColumnOptionDef, Expr as SQLExpr, Ident, OrderByExpr, Query,
+        Statement as SQLStatement, TableConstraint,
+    },
     dialect::{keywords::Keyword, Dialect, GenericDialect},
     parser::{Parser, ParserError},
     tokenizer::{Token, Tokenizer},
 };
+use std::any::Any;
 use std::collections::VecDeque;
+use std::fmt;
 use std::str::FromStr;
+use std::sync::Arc;
 
 // Use `Parser::expected` instead, if possible
 macro_rules! parser_err {
@@ -60,6 +66,8 @@ pub struct CreateExternalTable {
     pub name: String,
     /// Optional schema
     pub columns: Vec<ColumnDef>,
+    /// Table constraints, e.g. `PRIMARY KEY (c1)`
+    pub constraints: Vec<TableConstraint>,
     /// File type (Parquet, NDJSON, CSV)
     pub file_type: FileType,
     /// CSV Header row?
@@ -70,10 +78,84 @@ pub struct CreateExternalTable {
     pub location: String,
     /// Partition Columns
     pub table_partition_cols: Vec<String>,
+    /// The order of the rows of the files, e.g. `WITH ORDER (c1 ASC, c2 DESC)`
+    pub order_exprs: Vec<OrderByExpr>,
     /// Option to not error if table already exists
     pub if_not_exists: bool,
 }
 
+/// DataFusion extension of `EXPLAIN`, that can also explain the DataFusion
+/// specific statements
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainStatement {
+    /// `EXPLAIN ANALYZE ..`
+    pub analyze: bool,
+    /// `EXPLAIN .. VERBOSE ..`
+    pub verbose: bool,
+    /// The statement to explain
+    pub statement: Box<Statement>,
+}
+
+/// DataFusion extension `SELECT .. INTO [TEMP | TEMPORARY] [TABLE] name ..`,
+/// materializing the result of the query as a table of the session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectIntoStatement {
+    /// Name of the table created
+    pub name: String,
+    /// The query, without its `INTO` clause
+    pub query: Box<Query>,
+}
+
+/// DataFusion extension `OPTIMIZE TABLE name [ORDER BY ..] [TARGET_FILE_SIZE n]`,
+/// rewriting the files of a table into fewer, larger Parquet files
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizeTableStatement {
+    /// Name of the table
+    pub name: String,
+    /// The order of the rows of the rewritten files, if any
+    pub order_exprs: Vec<OrderByExpr>,
+    /// The target size of the rewritten files in bytes, if not the default
+    pub target_file_size: Option<u64>,
+}
+
+/// DataFusion extension `DELETE FROM name [WHERE predicate]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteStatement {
+    /// Name of the table
+    pub table_name: String,
+    /// The rows deleted, or all
+    pub selection: Option<SQLExpr>,
+}
+
+/// DataFusion extension `UPDATE name SET column = value, .. [WHERE predicate]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdateStatement {
+    /// Name of the table
+    pub table_name: String,
+    /// The columns updated, and their new values
+    pub assignments: Vec<(Ident, SQLExpr)>,
+    /// The rows updated, or all
+    pub selection: Option<SQLExpr>,
+}
+
+/// A statement of a kind DataFusion doesn't parse, e.g. custom DDL, produced
+/// by the parser of a project extending the SQL grammar and planned by one of
+/// the [`StatementPlanner`]s of the session.
+///
+/// The statements are compared by their SQL text.
+///
+/// [`StatementPlanner`]: crate::sql::planner::StatementPlanner
+pub trait UserDefinedStatement: fmt::Debug + fmt::Display + Send + Sync {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl PartialEq for dyn UserDefinedStatement {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
 /// DataFusion Statement representations.
 ///
 /// Tokens parsed by `DFParser` are converted into these values.
@@ -83,11 +165,30 @@ pub enum Statement {
     Statement(Box<SQLStatement>),
     /// Extension: `CREATE EXTERNAL TABLE`
     CreateExternalTable(CreateExternalTable),
+    /// Extension: `EXPLAIN <statement>`
+    Explain(ExplainStatement),
+    /// Extension: `SELECT .. INTO <table> ..`
+    SelectInto(SelectIntoStatement),
+    /// Extension: `OPTIMIZE TABLE <table>`
+    OptimizeTable(OptimizeTableStatement),
+    /// Extension: `DELETE FROM <table>`
+    Delete(DeleteStatement),
+    /// Extension: `UPDATE <table> SET ..`
+    Update(UpdateStatement),
+    /// A statement of a custom kind, parsed outside of DataFusion
+    Extension(Arc<dyn UserDefinedStatement>),
+}
+
+impl From<SQLStatement> for Statement {
+    fn from(statement: SQLStatement) -> Self {
+        Statement::Statement(Box::new(statement))
+    }
 }
 
 /// SQL Parser
 pub struct DFParser<'a> {
     parser: Parser<'a>,
+    dialect: &'a dyn Dialect,
 }
 
 impl<'a> DFParser<'a> {
@@ -107,6 +208,7 @@ impl<'a> DFParser<'a> {
 
         Ok(DFParser {
             parser: Parser::new(tokens, dialect),
+            dialect,
         })
     }
 
@@ -160,6 +262,27 @@ impl<'a> DFParser<'a> {
                         // use custom parsing
                         self.parse_create()
                     }
+                    Keyword::EXPLAIN => {
+                        // move one token forward
+                        self.parser.next_token();
+                        self.parse_explain()
+                    }
+                    Keyword::SELECT | Keyword::WITH => self.parse_query(),
+                    Keyword::DELETE => {
+                        // move one token forward
+                        self.parser.next_token();
+                        self.parse_delete()
+                    }
+                    Keyword::UPDATE => {
+                        // move one token forward
+                        self.parser.next_token();
+                        self.parse_update()
+                    }
+                    _ if w.value.eq_ignore_ascii_case("OPTIMIZE") => {
+                        // move one token forward
+                        self.parser.next_token();
+                        self.parse_optimize()
+                    }
                     _ => {
                         // use the native parser
                         Ok(Statement::Statement(Box::from(
@@ -186,6 +309,138 @@ impl<'a> DFParser<'a> {
         }
     }
 
+    /// Parse a SQL EXPLAIN statement, explaining any statement supported by
+    /// this parser
+    pub fn parse_explain(&mut self) -> Result<Statement, ParserError> {
+        let analyze = self.parser.parse_keyword(Keyword::ANALYZE);
+        let verbose = self.parser.parse_keyword(Keyword::VERBOSE);
+        let statement = self.parse_statement()?;
+
+        Ok(Statement::Explain(ExplainStatement {
+            analyze,
+            verbose,
+            statement: Box::new(statement),
+        }))
+    }
+
+    /// Parse a SQL OPTIMIZE TABLE statement
+    pub fn parse_optimize(&mut self) -> Result<Statement, ParserError> {
+        self.parser.expect_keyword(Keyword::TABLE)?;
+        let name = self.parser.parse_object_name()?;
+        let order_exprs = if self.parser.parse_keywords(&[Keyword::ORDER, Keyword::BY]) {
+            self.parser
+                .parse_comma_separated(Parser::parse_order_by_expr)?
+        } else {
+            vec![]
+        };
+        let target_file_size =
+            if self.consume_token(&Token::make_keyword("TARGET_FILE_SIZE")) {
+                Some(self.parser.parse_literal_uint()?)
+            } else {
+                None
+            };
+
+        Ok(Statement::OptimizeTable(OptimizeTableStatement {
+            name: name.to_string(),
+            order_exprs,
+            target_file_size,
+        }))
+    }
+
+    /// Parse a SQL DELETE statement
+    pub fn parse_delete(&mut self) -> Result<Statement, ParserError> {
+        self.parser.expect_keyword(Keyword::FROM)?;
+        let table_name = self.parser.parse_object_name()?;
+        let selection = self.parse_selection()?;
+
+        Ok(Statement::Delete(DeleteStatement {
+            table_name: table_name.to_string(),
+            selection,
+        }))
+    }
+
+    /// Parse a SQL UPDATE statement
+    pub fn parse_update(&mut self) -> Result<Statement, ParserError> {
+        let table_name = self.parser.parse_object_name()?;
+        self.parser.expect_keyword(Keyword::SET)?;
+        let assignments = self.parser.parse_comma_separated(|parser| {
+            let column = parser.parse_identifier()?;
+            parser.expect_token(&Token::Eq)?;
+            Ok((column, parser.parse_expr()?))
+        })?;
+        let selection = self.parse_selection()?;
+
+        Ok(Statement::Update(UpdateStatement {
+            table_name: table_name.to_string(),
+            assignments,
+            selection,
+        }))
+    }
+
+    /// Parses the optional `WHERE` clause of a statement
+    fn parse_selection(&mut self) -> Result<Option<SQLExpr>, ParserError> {
+        if self.parser.parse_keyword(Keyword::WHERE) {
+            Ok(Some(self.parser.parse_expr()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parse a SQL query, which may have the `INTO` clause of
+    /// `SELECT .. INTO [TEMP | TEMPORARY] [TABLE] name ..`
+    pub fn parse_query(&mut self) -> Result<Statement, ParserError> {
+        // extract the INTO clause from the tokens of the statement, outside
+        // of any parentheses, and parse the remaining ones as a query
+        let mut tokens = vec![];
+        let mut into = None;
+        let mut depth = 0usize;
+        loop {
+            match self.parser.peek_token() {
+                Token::EOF => break,
+                Token::SemiColon if depth == 0 => break,
+                Token::LParen => depth += 1,
+                Token::RParen => depth = depth.saturating_sub(1),
+                // not the INTO of `WITH .. INSERT INTO ..`
+                Token::Word(w)
+                    if w.keyword == Keyword::INTO
+                        && depth == 0
+                        && !matches!(
+                            tokens.last(),
+                            Some(Token::Word(w)) if w.keyword == Keyword::INSERT
+                        ) =>
+                {
+                    if into.is_some() {
+                        return parser_err!("SELECT has more than one INTO clause");
+                    }
+                    self.parser.next_token();
+                    self.parser
+                        .parse_one_of_keywords(&[Keyword::TEMP, Keyword::TEMPORARY]);
+                    self.parser.parse_keyword(Keyword::TABLE);
+                    into = Some(self.parser.parse_object_name()?.to_string());
+                    continue;
+                }
+                _ => {}
+            }
+            tokens.push(self.parser.next_token());
+        }
+
+        let mut parser = Parser::new(tokens, self.dialect);
+        let statement = parser.parse_statement()?;
+        if parser.peek_token() != Token::EOF {
+            return self.expected("end of statement", parser.peek_token());
+        }
+        match (into, statement) {
+            (Some(name), SQLStatement::Query(query)) => {
+                Ok(Statement::SelectInto(SelectIntoStatement { name, query }))
+            }
+            (Some(_), statement) => parser_err!(format!(
+                "INTO is only supported by queries, found: {}",
+                statement
+            )),
+            (None, statement) => Ok(Statement::Statement(Box::new(statement))),
+        }
+    }
+
     fn parse_partitions(&mut self) -> Result<Vec<String>, ParserError> {
         let mut partitions: Vec<String> = vec![];
         if !self.parser.consume_token(&Token::LParen)
@@ -294,7 +549,7 @@ impl<'a> DFParser<'a> {
             self.parser
                 .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
         let table_name = self.parser.parse_object_name()?;
-        let (columns, _) = self.parse_columns()?;
+        let (columns, constraints) = self.parse_columns()?;
         self.parser
             .expect_keywords(&[Keyword::STORED, Keyword::AS])?;
 
@@ -315,17 +570,25 @@ impl<'a> DFParser<'a> {
             vec![]
         };
 
+        let order_exprs = if self.parse_has_order() {
+            self.parse_order_exprs()?
+        } else {
+            vec![]
+        };
+
         self.parser.expect_keyword(Keyword::LOCATION)?;
         let location = self.parser.parse_literal_string()?;
 
         let create = CreateExternalTable {
             name: table_name.to_string(),
             columns,
+            constraints,
             file_type,
             has_header,
             delimiter,
             location,
             table_partition_cols,
+            order_exprs,
             if_not_exists,
         };
         Ok(Statement::CreateExternalTable(create))
@@ -351,9 +614,14 @@ impl<'a> DFParser<'a> {
     }
 
     fn parse_csv_has_header(&mut self) -> bool {
-        self.consume_token(&Token::make_keyword("WITH"))
-            & self.consume_token(&Token::make_keyword("HEADER"))
-            & self.consume_token(&Token::make_keyword("ROW"))
+        // `WITH` may also start `WITH ORDER`
+        if self.consume_token(&Token::make_keyword("WITH")) {
+            if self.consume_token(&Token::make_keyword("HEADER")) {
+                return self.consume_token(&Token::make_keyword("ROW"));
+            }
+            self.parser.prev_token();
+        }
+        false
     }
 
     fn parse_has_delimiter(&mut self) -> bool {
@@ -374,12 +642,27 @@ impl<'a> DFParser<'a> {
         self.consume_token(&Token::make_keyword("PARTITIONED"))
             & self.consume_token(&Token::make_keyword("BY"))
     }
+
+    fn parse_has_order(&mut self) -> bool {
+        self.consume_token(&Token::make_keyword("WITH"))
+            & self.consume_token(&Token::make_keyword("ORDER"))
+    }
+
+    /// Parses the order of the rows of the files, e.g. `(c1 ASC, c2 DESC)`
+    fn parse_order_exprs(&mut self) -> Result<Vec<OrderByExpr>, ParserError> {
+        self.parser.expect_token(&Token::LParen)?;
+        let order_exprs = self
+            .parser
+            .parse_comma_separated(Parser::parse_order_by_expr)?;
+        self.parser.expect_token(&Token::RParen)?;
+        Ok(order_exprs)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sqlparser::ast::{DataType, Ident};
+    use sqlparser::ast::{ColumnOption, DataType, Ident};
 
     fn expect_parse_ok(sql: &str, expected: Statement) -> Result<(), ParserError> {
         let statements = DFParser::parse_sql(sql)?;
@@ -433,11 +716,13 @@ mod tests {
         let expected = Statement::CreateExternalTable(CreateExternalTable {
             name: "t".into(),
             columns: vec![make_column_def("c1", DataType::Int(display))],
+            constraints: vec![],
             file_type: FileType::CSV,
             has_header: false,
             delimiter: ',',
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -448,11 +733,13 @@ mod tests {
         let expected = Statement::CreateExternalTable(CreateExternalTable {
             name: "t".into(),
             columns: vec![make_column_def("c1", DataType::Int(display))],
+            constraints: vec![],
             file_type: FileType::CSV,
             has_header: false,
             delimiter: '|',
             location: "foo.csv".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -463,11 +750,47 @@ mod tests {
         let expected = Statement::CreateExternalTable(CreateExternalTable {
             name: "t".into(),
             columns: vec![make_column_def("c1", DataType::Int(display))],
+            constraints: vec![],
             file_type: FileType::CSV,
             has_header: false,
             delimiter: ',',
             location: "foo.csv".into(),
             table_partition_cols: vec!["p1".to_string(), "p2".to_string()],
+            order_exprs: vec![],
+            if_not_exists: false,
+        });
+        expect_parse_ok(sql, expected)?;
+
+        // positive case: constraints
+        let sql = "CREATE EXTERNAL TABLE t(c1 int PRIMARY KEY, c2 int NOT NULL, UNIQUE (c2)) STORED AS CSV LOCATION 'foo.csv'";
+        let display = None;
+        let mut c1 = make_column_def("c1", DataType::Int(display));
+        c1.options.push(ColumnOptionDef {
+            name: None,
+            option: ColumnOption::Unique { is_primary: true },
+        });
+        let mut c2 = make_column_def("c2", DataType::Int(display));
+        c2.options.push(ColumnOptionDef {
+            name: None,
+            option: ColumnOption::NotNull,
+        });
+        let expected = Statement::CreateExternalTable(CreateExternalTable {
+            name: "t".into(),
+            columns: vec![c1, c2],
+            constraints: vec![TableConstraint::Unique {
+                name: None,
+                columns: vec![Ident {
+                    value: "c2".into(),
+                    quote_style: None,
+                }],
+                is_primary: false,
+            }],
+            file_type: FileType::CSV,
+            has_header: false,
+            delimiter: ',',
+            location: "foo.csv".into(),
+            table_partition_cols: vec![],
+            order_exprs: vec![],
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -481,11 +804,13 @@ mod tests {
             let expected = Statement::CreateExternalTable(CreateExternalTable {
                 name: "t".into(),
                 columns: vec![make_column_def("c1", DataType::Int(display))],
+                constraints: vec![],
                 file_type: FileType::CSV,
                 has_header: true,
                 delimiter: ',',
                 location: "foo.csv".into(),
                 table_partition_cols: vec![],
+                order_exprs: vec![],
                 if_not_exists: false,
             });
             expect_parse_ok(sql, expected)?;
@@ -496,11 +821,13 @@ mod tests {
         let expected = Statement::CreateExternalTable(CreateExternalTable {
             name: "t".into(),
             columns: vec![],
+            constraints: vec![],
             file_type: FileType::Parquet,
             has_header: false,
             delimiter: ',',
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -510,11 +837,13 @@ mod tests {
         let expected = Statement::CreateExternalTable(CreateExternalTable {
             name: "t".into(),
             columns: vec![],
+            constraints: vec![],
             file_type: FileType::Parquet,
             has_header: false,
             delimiter: ',',
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -524,11 +853,13 @@ mod tests {
         let expected = Statement::CreateExternalTable(CreateExternalTable {
             name: "t".into(),
             columns: vec![],
+            constraints: vec![],
             file_type: FileType::Avro,
             has_header: false,
             delimiter: ',',
             location: "foo.avro".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
             if_not_exists: false,
         });
         expect_parse_ok(sql, expected)?;
@@ -539,15 +870,58 @@ mod tests {
         let expected = Statement::CreateExternalTable(CreateExternalTable {
             name: "t".into(),
             columns: vec![],
+            constraints: vec![],
             file_type: FileType::Parquet,
             has_header: false,
             delimiter: ',',
             location: "foo.parquet".into(),
             table_partition_cols: vec![],
+            order_exprs: vec![],
             if_not_exists: true,
         });
         expect_parse_ok(sql, expected)?;
 
+        // positive case: order of the files
+        let sql = "CREATE EXTERNAL TABLE t(c1 int, c2 int) STORED AS CSV WITH HEADER ROW WITH ORDER (c1 ASC, c2 DESC NULLS FIRST) LOCATION 'foo.csv'";
+        let order_expr = |name: &str, asc, nulls_first| OrderByExpr {
+            expr: sqlparser::ast::Expr::Identifier(Ident {
+                value: name.into(),
+                quote_style: None,
+            }),
+            asc,
+            nulls_first,
+        };
+        let expected = Statement::CreateExternalTable(CreateExternalTable {
+            name: "t".into(),
+            columns: vec![
+                make_column_def("c1", DataType::Int(display)),
+                make_column_def("c2", DataType::Int(display)),
+            ],
+            constraints: vec![],
+            file_type: FileType::CSV,
+            has_header: true,
+            delimiter: ',',
+            location: "foo.csv".into(),
+            table_partition_cols: vec![],
+            order_exprs: vec![
+                order_expr("c1", Some(true), None),
+                order_expr("c2", Some(false), Some(true)),
+            ],
+            if_not_exists: false,
+        });
+        expect_parse_ok(sql, expected)?;
+
+        // positive case: `WITH ORDER` without a header row
+        let sql = "CREATE EXTERNAL TABLE t STORED AS PARQUET WITH ORDER (c1) LOCATION 'foo.parquet'";
+        let statements = DFParser::parse_sql(sql)?;
+        match &statements[0] {
+            Statement::CreateExternalTable(create) => {
+                assert!(!create.has_header);
+                assert_eq!(create.order_exprs, vec![order_expr("c1", None, None)]);
+            }
+            other => panic!("Expected CREATE EXTERNAL TABLE, found {:?}", other),
+        }
+
         // Error cases: Invalid type
         let sql =
             "CREATE EXTERNAL TABLE t(c1 int) STORED AS UNKNOWN_TYPE LOCATION 'foo.csv'";
@@ -560,4 +934,153 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn explain_statement() -> Result<(), ParserError> {
+        let sql = "EXPLAIN ANALYZE VERBOSE CREATE EXTERNAL TABLE t STORED AS PARQUET LOCATION 'foo.parquet'";
+        let expected = Statement::Explain(ExplainStatement {
+            analyze: true,
+            verbose: true,
+            statement: Box::new(Statement::CreateExternalTable(CreateExternalTable {
+                name: "t".into(),
+                columns: vec![],
+                constraints: vec![],
+                file_type: FileType::Parquet,
+                has_header: false,
+                delimiter: ',',
+                location: "foo.parquet".into(),
+                table_partition_cols: vec![],
+                order_exprs: vec![],
+                if_not_exists: false,
+            })),
+        });
+        expect_parse_ok(sql, expected)?;
+
+        let sql = "EXPLAIN SELECT 1";
+        let statements = DFParser::parse_sql(sql)?;
+        match &statements[0] {
+            Statement::Explain(ExplainStatement {
+                analyze: false,
+                verbose: false,
+                statement,
+            }) => assert!(matches!(statement.as_ref(), Statement::Statement(_))),
+            other => panic!("Expected an EXPLAIN statement, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn select_into_statement() -> Result<(), ParserError> {
+        let sql =
+            "SELECT a, b INTO TEMP TABLE s.t FROM (SELECT a, b FROM u) AS v WHERE a > 1";
+        let statements = DFParser::parse_sql(sql)?;
+        assert_eq!(statements.len(), 1);
+        match &statements[0] {
+            Statement::SelectInto(SelectIntoStatement { name, query }) => {
+                assert_eq!(name, "s.t");
+                assert_eq!(
+                    query.to_string(),
+                    "SELECT a, b FROM (SELECT a, b FROM u) AS v WHERE a > 1"
+                );
+            }
+            other => panic!("Expected a SELECT INTO statement, got {:?}", other),
+        }
+
+        let sql = "WITH v AS (SELECT 1 AS a) SELECT a INTO t FROM v; SELECT * FROM t";
+        let statements = DFParser::parse_sql(sql)?;
+        assert_eq!(statements.len(), 2);
+        assert!(matches!(statements[0], Statement::SelectInto(_)));
+        assert!(matches!(statements[1], Statement::Statement(_)));
+
+        expect_parse_error(
+            "SELECT a INTO t INTO u FROM v",
+            "SELECT has more than one INTO clause",
+        );
+        expect_parse_error(
+            "SELECT a INTO t FROM v w x",
+            "Expected end of statement, found: x",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn optimize_table_statement() -> Result<(), ParserError> {
+        let sql = "OPTIMIZE TABLE s.t";
+        let expected = Statement::OptimizeTable(OptimizeTableStatement {
+            name: "s.t".into(),
+            order_exprs: vec![],
+            target_file_size: None,
+        });
+        expect_parse_ok(sql, expected)?;
+
+        let sql = "optimize table t ORDER BY a DESC, b TARGET_FILE_SIZE 1048576";
+        let statements = DFParser::parse_sql(sql)?;
+        match &statements[0] {
+            Statement::OptimizeTable(OptimizeTableStatement {
+                name,
+                order_exprs,
+                target_file_size,
+            }) => {
+                assert_eq!(name, "t");
+                let order_exprs = order_exprs
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>();
+                assert_eq!(order_exprs, vec!["a DESC", "b"]);
+                assert_eq!(*target_file_size, Some(1048576));
+            }
+            other => panic!("Expected an OPTIMIZE TABLE statement, got {:?}", other),
+        }
+
+        expect_parse_error("OPTIMIZE t", "Expected TABLE, found: t");
+        expect_parse_error(
+            "OPTIMIZE TABLE t TARGET_FILE_SIZE x",
+            "Expected literal int, found: x",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn delete_and_update_statements() -> Result<(), ParserError> {
+        let statements =
+            DFParser::parse_sql("DELETE FROM s.t WHERE a > 1; DELETE FROM t")?;
+        match (&statements[0], &statements[1]) {
+            (
+                Statement::Delete(DeleteStatement {
+                    table_name,
+                    selection: Some(selection),
+                }),
+                Statement::Delete(DeleteStatement {
+                    selection: None, ..
+                }),
+            ) => {
+                assert_eq!(table_name, "s.t");
+                assert_eq!(selection.to_string(), "a > 1");
+            }
+            other => panic!("Expected DELETE statements, got {:?}", other),
+        }
+
+        let sql = "UPDATE t SET a = a + 1, \"B\" = 'x' WHERE c IS NULL";
+        let statements = DFParser::parse_sql(sql)?;
+        match &statements[0] {
+            Statement::Update(UpdateStatement {
+                table_name,
+                assignments,
+                selection,
+            }) => {
+                assert_eq!(table_name, "t");
+                let assignments = assignments
+                    .iter()
+                    .map(|(column, value)| format!("{} = {}", column, value))
+                    .collect::<Vec<_>>();
+                assert_eq!(assignments, vec!["a = a + 1", "\"B\" = 'x'"]);
+                assert_eq!(selection.as_ref().unwrap().to_string(), "c IS NULL");
+            }
+            other => panic!("Expected an UPDATE statement, got {:?}", other),
+        }
+
+        expect_parse_error("DELETE t", "Expected FROM, found: t");
+        expect_parse_error("UPDATE t SET a 1", "Expected =, found: 1");
+        Ok(())
+    }
 }
@@ -24,63 +24,200 @@ use std::sync::Arc;
 use std::{convert::TryInto, vec};
 
 use crate::catalog::TableReference;
+use crate::datasource::generate_series::{GenerateSeriesTable, GENERATE_SERIES};
+use crate::datasource::parquet_metadata::{ParquetMetadataTable, PARQUET_METADATA};
 use crate::datasource::TableProvider;
 use crate::logical_plan::window_frames::{WindowFrame, WindowFrameUnits};
 use crate::logical_plan::Expr::Alias;
 use crate::logical_plan::{
-    and, builder::expand_qualified_wildcard, builder::expand_wildcard, col, lit,
-    normalize_col, union_with_alias, Column, CreateCatalog, CreateCatalogSchema,
-    CreateExternalTable as PlanCreateExternalTable, CreateMemoryTable, DFSchema,
-    DFSchemaRef, DropTable, Expr, FileType, LogicalPlan, LogicalPlanBuilder, Operator,
-    PlanType, ToDFSchema, ToStringifiedPlan,
+    and, builder::expand_qualified_wildcard, builder::expand_wildcard,
+    coerce_to_table_schema, collate, lit, normalize_col, union_by_name_with_alias,
+    union_with_alias, unnormalize_col, Column, Constraint, Constraints, CreateCatalog,
+    CreateCatalogSchema, CreateExternalTable as PlanCreateExternalTable,
+    CreateMemoryTable, DFSchema, DFSchemaRef, DedupNode, DedupStrategy, Delete,
+    DropTable, Expr, ExprSchemable, FileType, LogicalPlan, LogicalPlanBuilder, Operator,
+    OptimizeTable, PlanType, SetVariable, ToDFSchema, ToStringifiedPlan, Transaction,
+    TransactionStatement, Update,
 };
 use crate::optimizer::utils::exprlist_to_columns;
 use crate::prelude::JoinType;
 use crate::scalar::ScalarValue;
+use crate::sql::interval::parse_interval;
 use crate::sql::utils::{make_decimal_type, normalize_ident};
 use crate::{
     error::{DataFusionError, Result},
     physical_plan::aggregates,
     physical_plan::udaf::AggregateUDF,
     physical_plan::udf::ScalarUDF,
-    sql::parser::{CreateExternalTable, Statement as DFStatement},
+    sql::parser::{
+        CreateExternalTable, DeleteStatement, ExplainStatement, OptimizeTableStatement,
+        SelectIntoStatement, Statement as DFStatement, UpdateStatement,
+        UserDefinedStatement,
+    },
 };
+use arrow::compute::cast;
 use arrow::datatypes::*;
+use datafusion_expr::binary_rule::coerce_types;
 use datafusion_expr::{window_function::WindowFunction, BuiltinScalarFunction};
 use hashbrown::HashMap;
 
 use sqlparser::ast::{
     BinaryOperator, DataType as SQLDataType, DateTimeField, Expr as SQLExpr, FunctionArg,
     FunctionArgExpr, Ident, Join, JoinConstraint, JoinOperator, ObjectName, Query,
-    Select, SelectItem, SetExpr, SetOperator, ShowStatementFilter, TableFactor,
-    TableWithJoins, TrimWhereField, UnaryOperator, Value, Values as SQLValues,
+    Select, SelectItem, SetExpr, SetOperator, ShowStatementFilter, TableAlias,
+    TableFactor, TableWithJoins, TrimWhereField, UnaryOperator, Value,
+    Values as SQLValues,
 };
 use sqlparser::ast::{ColumnDef as SQLColumnDef, ColumnOption};
-use sqlparser::ast::{ObjectType, OrderByExpr, Statement};
+use sqlparser::ast::{ObjectType, OrderByExpr, Statement, TableConstraint};
 use sqlparser::parser::ParserError::ParserError;
 
 use super::{
     parser::DFParser,
     utils::{
+        add_functionally_dependent_columns, alias_duplicate_names,
         can_columns_satisfy_exprs, expr_as_column_expr, extract_aliases,
-        find_aggregate_exprs, find_column_exprs, find_window_exprs, rebase_expr,
-        resolve_aliases_to_exprs, resolve_positions_to_exprs,
+        find_aggregate_exprs, find_column_exprs, find_nested_aggregate_exprs,
+        find_window_exprs, rebase_expr, replace_exprs, resolve_aliases_to_exprs,
+        resolve_positions_to_exprs,
     },
 };
 use crate::logical_plan::builder::project_with_alias;
-use crate::logical_plan::plan::{Analyze, Explain};
+use crate::logical_plan::plan::{Analyze, Explain, Extension};
+
+/// The name of the table function keeping the latest row of each key of a
+/// table, see [`DedupNode`]
+pub const DEDUP: &str = "dedup";
 
 /// The ContextProvider trait allows the query planner to obtain meta-data about tables and
 /// functions referenced in SQL statements
 pub trait ContextProvider {
     /// Getter for a datasource
     fn get_table_provider(&self, name: TableReference) -> Option<Arc<dyn TableProvider>>;
+    /// Checks that the table `name` may be accessed, returning an error if
+    /// denied
+    fn authorize_table(&self, _name: TableReference) -> Result<()> {
+        Ok(())
+    }
+    /// Checks that the metadata of the files at `path` may be read,
+    /// returning an error if denied
+    fn authorize_file_metadata(&self, _path: &str) -> Result<()> {
+        Ok(())
+    }
+    /// Plans the row filters and column masks of the security policies of
+    /// the table `name` above its `scan`
+    fn apply_security_policies(
+        &self,
+        _name: TableReference,
+        scan: LogicalPlan,
+    ) -> Result<LogicalPlan> {
+        Ok(scan)
+    }
     /// Getter for a UDF description
     fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>>;
     /// Getter for a UDAF description
     fn get_aggregate_meta(&self, name: &str) -> Option<Arc<AggregateUDF>>;
     /// Getter for system/user-defined variable type
     fn get_variable_type(&self, variable_names: &[String]) -> Option<DataType>;
+    /// Whether `UNION` matches the columns of its inputs by name rather than by position
+    fn union_by_name(&self) -> bool {
+        false
+    }
+    /// Whether the `SELECT` expressions with the same name as a previous one are
+    /// aliased to a unique name rather than failing the query
+    fn alias_duplicate_names(&self) -> bool {
+        false
+    }
+    /// Whether the indexes of the elements of the lists in `list[index]` start
+    /// at 1 rather than 0
+    fn one_based_list_index(&self) -> bool {
+        false
+    }
+    /// The ordering of the nulls of the `ORDER BY` expressions that don't
+    /// specify `NULLS FIRST` or `NULLS LAST`
+    fn default_null_ordering(&self) -> NullOrdering {
+        NullOrdering::default()
+    }
+    /// The planners of the [`UserDefinedStatement`]s, tried in order
+    fn statement_planners(&self) -> Vec<Arc<dyn StatementPlanner>> {
+        vec![]
+    }
+}
+
+/// Plans the [`UserDefinedStatement`]s of a project extending the SQL
+/// grammar, e.g. into a [`LogicalPlan::Extension`], reusing the SQL planner
+/// for their standard parts
+pub trait StatementPlanner: Send + Sync {
+    /// Plans `statement`, or returns `None` if this planner doesn't support
+    /// its kind. `plan_statement` plans the statements of the standard parts
+    /// of `statement`, e.g. the query of `CREATE MATERIALIZED VIEW v AS ..`.
+    fn plan_statement(
+        &self,
+        statement: &dyn UserDefinedStatement,
+        plan_statement: &dyn Fn(DFStatement) -> Result<LogicalPlan>,
+    ) -> Result<Option<LogicalPlan>>;
+}
+
+/// The default ordering of the nulls of the SQL `ORDER BY` expressions that
+/// don't specify `NULLS FIRST` or `NULLS LAST`, emulating the defaults of
+/// other engines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NullOrdering {
+    /// The nulls sort as if they were larger than any other value: last in
+    /// ascending order and first in descending order, as in PostgreSQL and
+    /// Oracle. This is the default.
+    NullsMax,
+    /// The nulls sort as if they were smaller than any other value: first in
+    /// ascending order and last in descending order, as in Spark, MySQL,
+    /// SQLite and SQL Server
+    NullsMin,
+    /// The nulls sort first in both orders
+    NullsFirst,
+    /// The nulls sort last in both orders, as in Presto and Trino
+    NullsLast,
+}
+
+impl NullOrdering {
+    /// Whether the nulls sort first in ascending (`asc`) or descending order
+    pub fn nulls_first(&self, asc: bool) -> bool {
+        match self {
+            NullOrdering::NullsMax => !asc,
+            NullOrdering::NullsMin => asc,
+            NullOrdering::NullsFirst => true,
+            NullOrdering::NullsLast => false,
+        }
+    }
+}
+
+impl Default for NullOrdering {
+    fn default() -> Self {
+        NullOrdering::NullsMax
+    }
+}
+
+impl FromStr for NullOrdering {
+    type Err = DataFusionError;
+
+    /// Parses the name of a null ordering, e.g. `nulls_min`, or of an engine
+    /// whose default it emulates, e.g. `spark`
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "nulls_max" | "postgres" | "postgresql" | "oracle" => {
+                Ok(NullOrdering::NullsMax)
+            }
+            "nulls_min" | "spark" | "mysql" | "sqlite" | "mssql" => {
+                Ok(NullOrdering::NullsMin)
+            }
+            "nulls_first" => Ok(NullOrdering::NullsFirst),
+            "nulls_last" | "presto" | "trino" => Ok(NullOrdering::NullsLast),
+            _ => Err(DataFusionError::Plan(format!(
+                "Unknown null ordering '{}', expected one of nulls_max, nulls_min, \
+                 nulls_first, nulls_last or the name of an engine such as postgres \
+                 or spark",
+                s
+            ))),
+        }
+    }
 }
 
 /// SQL query planner
@@ -91,7 +228,9 @@ pub struct SqlToRel<'a, S: ContextProvider> {
 fn plan_key(key: SQLExpr) -> Result<ScalarValue> {
     let scalar = match key {
         SQLExpr::Value(Value::Number(s, _)) => {
-            ScalarValue::Int64(Some(s.parse().unwrap()))
+            ScalarValue::Int64(Some(s.parse().map_err(|_| {
+                DataFusionError::Plan(format!("Invalid index key {}", s))
+            })?))
         }
         SQLExpr::Value(Value::SingleQuotedString(s)) => ScalarValue::Utf8(Some(s)),
         _ => {
@@ -105,25 +244,6 @@ fn plan_key(key: SQLExpr) -> Result<ScalarValue> {
     Ok(scalar)
 }
 
-fn plan_indexed(expr: Expr, mut keys: Vec<SQLExpr>) -> Result<Expr> {
-    let key = keys.pop().ok_or_else(|| {
-        DataFusionError::SQL(ParserError(
-            "Internal error: Missing index key expression".to_string(),
-        ))
-    })?;
-
-    let expr = if !keys.is_empty() {
-        plan_indexed(expr, keys)?
-    } else {
-        expr
-    };
-
-    Ok(Expr::GetIndexedField {
-        expr: Box::new(expr),
-        key: plan_key(key)?,
-    })
-}
-
 impl<'a, S: ContextProvider> SqlToRel<'a, S> {
     /// Create a new query planner
     pub fn new(schema_provider: &'a S) -> Self {
@@ -135,7 +255,167 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         match statement {
             DFStatement::CreateExternalTable(s) => self.external_table_to_plan(s),
             DFStatement::Statement(s) => self.sql_statement_to_plan(*s),
+            DFStatement::Explain(ExplainStatement {
+                verbose,
+                analyze,
+                statement,
+            }) => self.explain_statement_to_plan(verbose, analyze, *statement),
+            DFStatement::SelectInto(SelectIntoStatement { name, query }) => {
+                let plan = self.query_to_plan(*query)?;
+                Ok(LogicalPlan::CreateMemoryTable(CreateMemoryTable {
+                    name,
+                    input: Arc::new(plan),
+                    if_not_exists: false,
+                }))
+            }
+            DFStatement::OptimizeTable(statement) => {
+                self.optimize_table_to_plan(statement)
+            }
+            DFStatement::Delete(statement) => self.delete_to_plan(statement),
+            DFStatement::Update(statement) => self.update_to_plan(statement),
+            DFStatement::Extension(statement) => {
+                self.extension_statement_to_plan(statement.as_ref())
+            }
+        }
+    }
+
+    /// Generate a logical plan from an OPTIMIZE TABLE statement
+    fn optimize_table_to_plan(
+        &self,
+        statement: OptimizeTableStatement,
+    ) -> Result<LogicalPlan> {
+        let OptimizeTableStatement {
+            name,
+            order_exprs,
+            target_file_size,
+        } = statement;
+        let schema = self.modified_table_schema(&name)?;
+        let sort_exprs = order_exprs
+            .into_iter()
+            .map(|e| self.order_by_to_sort_expr(e, &schema))
+            .collect::<Result<Vec<_>>>()?;
+        let target_file_size = target_file_size.map(|size| size as usize);
+        if target_file_size == Some(0) {
+            return Err(DataFusionError::Plan(
+                "The target file size of OPTIMIZE TABLE must be positive".to_string(),
+            ));
+        }
+
+        Ok(LogicalPlan::OptimizeTable(OptimizeTable {
+            name,
+            sort_exprs,
+            target_file_size,
+            schema: Arc::new(LogicalPlan::optimize_table_schema().to_dfschema()?),
+        }))
+    }
+
+    /// Generate a logical plan from a DELETE statement
+    fn delete_to_plan(&self, statement: DeleteStatement) -> Result<LogicalPlan> {
+        let DeleteStatement {
+            table_name,
+            selection,
+        } = statement;
+        let schema = self.modified_table_schema(&table_name)?;
+        let predicate = selection
+            .map(|selection| self.dml_predicate(selection, &schema))
+            .transpose()?;
+
+        Ok(LogicalPlan::Delete(Delete {
+            table_name,
+            predicate,
+            schema: Arc::new(LogicalPlan::dml_schema().to_dfschema()?),
+        }))
+    }
+
+    /// Generate a logical plan from an UPDATE statement
+    fn update_to_plan(&self, statement: UpdateStatement) -> Result<LogicalPlan> {
+        let UpdateStatement {
+            table_name,
+            assignments,
+            selection,
+        } = statement;
+        let schema = self.modified_table_schema(&table_name)?;
+        let predicate = selection
+            .map(|selection| self.dml_predicate(selection, &schema))
+            .transpose()?;
+
+        let mut columns = HashSet::new();
+        let assignments = assignments
+            .into_iter()
+            .map(|(column, value)| {
+                let column = normalize_ident(column);
+                if schema.field_with_unqualified_name(&column).is_err() {
+                    return Err(DataFusionError::Plan(format!(
+                        "Table '{}' has no column named '{}'",
+                        table_name, column
+                    )));
+                }
+                if !columns.insert(column.clone()) {
+                    return Err(DataFusionError::Plan(format!(
+                        "Column '{}' is updated more than once",
+                        column
+                    )));
+                }
+                let value = unnormalize_col(self.sql_to_rex(value, &schema)?);
+                Ok((column, value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(LogicalPlan::Update(Update {
+            table_name,
+            assignments,
+            predicate,
+            schema: Arc::new(LogicalPlan::dml_schema().to_dfschema()?),
+        }))
+    }
+
+    /// Returns the schema of the table `name` modified by a statement,
+    /// qualified by its name, after checking that it may be accessed
+    fn modified_table_schema(&self, name: &str) -> Result<DFSchema> {
+        let table_ref = TableReference::from(name);
+        let provider = self
+            .schema_provider
+            .get_table_provider(table_ref)
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "Table or CTE with name '{}' not found",
+                    name
+                ))
+            })?;
+        self.schema_provider.authorize_table(table_ref)?;
+        DFSchema::try_from_qualified_schema(table_ref.table(), &provider.schema())
+    }
+
+    /// Returns the predicate of the rows deleted or updated, over the
+    /// unqualified columns of the table of `schema`
+    fn dml_predicate(&self, selection: SQLExpr, schema: &DFSchema) -> Result<Expr> {
+        let predicate = self.sql_to_rex(selection, schema)?;
+        let data_type = predicate.get_type(schema)?;
+        if data_type != DataType::Boolean {
+            return Err(DataFusionError::Plan(format!(
+                "The WHERE clause must be a boolean expression, not {:?}",
+                data_type
+            )));
         }
+        Ok(unnormalize_col(predicate))
+    }
+
+    /// Generate a logical plan from a statement of a custom kind, with the
+    /// first [`StatementPlanner`] supporting it
+    fn extension_statement_to_plan(
+        &self,
+        statement: &dyn UserDefinedStatement,
+    ) -> Result<LogicalPlan> {
+        let plan_statement = |statement: DFStatement| self.statement_to_plan(statement);
+        for planner in self.schema_provider.statement_planners() {
+            if let Some(plan) = planner.plan_statement(statement, &plan_statement)? {
+                return Ok(plan);
+            }
+        }
+        Err(DataFusionError::NotImplemented(format!(
+            "No statement planner supports the statement: {}",
+            statement
+        )))
     }
 
     /// Generate a logical plan from an SQL statement
@@ -146,7 +426,11 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 statement,
                 analyze,
                 describe_alias: _,
-            } => self.explain_statement_to_plan(verbose, analyze, *statement),
+            } => self.explain_statement_to_plan(
+                verbose,
+                analyze,
+                DFStatement::Statement(statement),
+            ),
             Statement::Query(query) => self.query_to_plan(*query),
             Statement::ShowVariable { variable } => self.show_variable_to_plan(&variable),
             Statement::CreateTable {
@@ -158,12 +442,15 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 with_options,
                 if_not_exists,
                 ..
-            } if columns.is_empty()
-                && constraints.is_empty()
+            } if constraints.is_empty()
                 && table_properties.is_empty()
                 && with_options.is_empty() =>
             {
-                let plan = self.query_to_plan(*query)?;
+                let mut plan = self.query_to_plan(*query)?;
+                // the rows of the query are written to the declared columns
+                if !columns.is_empty() {
+                    plan = coerce_to_table_schema(plan, &self.build_schema(columns)?)?;
+                }
 
                 Ok(LogicalPlan::CreateMemoryTable(CreateMemoryTable {
                     name: name.to_string(),
@@ -172,7 +459,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 }))
             }
             Statement::CreateTable { .. } => Err(DataFusionError::NotImplemented(
-                "Only `CREATE TABLE table_name AS SELECT ...` statement is supported"
+                "Only `CREATE TABLE table_name [(column_def, ...)] AS SELECT ...` statement is supported"
                     .to_string(),
             )),
             Statement::CreateSchema {
@@ -208,6 +495,32 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 }))
             }
 
+            Statement::SetVariable {
+                variable, value, ..
+            } => Ok(LogicalPlan::SetVariable(SetVariable {
+                variable: variable.to_string(),
+                values: value
+                    .iter()
+                    .map(|v| v.to_string().trim_matches('\'').to_string())
+                    .collect(),
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            })),
+
+            Statement::StartTransaction { .. } => {
+                Ok(LogicalPlan::Transaction(Transaction {
+                    statement: TransactionStatement::Begin,
+                    schema: DFSchemaRef::new(DFSchema::empty()),
+                }))
+            }
+            Statement::Commit { .. } => Ok(LogicalPlan::Transaction(Transaction {
+                statement: TransactionStatement::Commit,
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            })),
+            Statement::Rollback { .. } => Ok(LogicalPlan::Transaction(Transaction {
+                statement: TransactionStatement::Rollback,
+                schema: DFSchemaRef::new(DFSchema::empty()),
+            })),
+
             Statement::ShowColumns {
                 extended,
                 full,
@@ -252,6 +565,8 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                     Some(cte.alias.name.value.clone()),
                     &mut ctes.clone(),
                 )?;
+                let logical_plan =
+                    self.apply_table_alias_columns(logical_plan, cte.alias.clone())?;
                 ctes.insert(cte.alias.name.value, logical_plan);
             }
         }
@@ -279,12 +594,17 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             } => {
                 let left_plan = self.set_expr_to_plan(*left, None, ctes)?;
                 let right_plan = self.set_expr_to_plan(*right, None, ctes)?;
-                match (op, all) {
-                    (SetOperator::Union, true) => {
+                let union = |left_plan, right_plan, alias| {
+                    if self.schema_provider.union_by_name() {
+                        union_by_name_with_alias(left_plan, right_plan, alias)
+                    } else {
                         union_with_alias(left_plan, right_plan, alias)
                     }
+                };
+                match (op, all) {
+                    (SetOperator::Union, true) => union(left_plan, right_plan, alias),
                     (SetOperator::Union, false) => {
-                        let union_plan = union_with_alias(left_plan, right_plan, alias)?;
+                        let union_plan = union(left_plan, right_plan, alias)?;
                         LogicalPlanBuilder::from(union_plan).distinct()?.build()
                     }
                     (SetOperator::Intersect, true) => {
@@ -316,11 +636,13 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         let CreateExternalTable {
             name,
             columns,
+            constraints,
             file_type,
             has_header,
             delimiter,
             location,
             table_partition_cols,
+            order_exprs,
             if_not_exists,
         } = statement;
 
@@ -339,16 +661,23 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             FileType::Avro => {}
         };
 
-        let schema = self.build_schema(columns)?;
+        let constraints = self.build_constraints(&columns, constraints)?;
+        let schema = self.build_schema(columns)?.to_dfschema()?;
+        let order_exprs = order_exprs
+            .into_iter()
+            .map(|e| self.order_by_to_sort_expr(e, &schema))
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(LogicalPlan::CreateExternalTable(PlanCreateExternalTable {
-            schema: schema.to_dfschema_ref()?,
+            schema: Arc::new(schema),
+            constraints,
             name,
             location,
             file_type,
             has_header,
             delimiter,
             table_partition_cols,
+            order_exprs,
             if_not_exists,
         }))
     }
@@ -359,9 +688,9 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         &self,
         verbose: bool,
         analyze: bool,
-        statement: Statement,
+        statement: DFStatement,
     ) -> Result<LogicalPlan> {
-        let plan = self.sql_statement_to_plan(statement)?;
+        let plan = self.statement_to_plan(statement)?;
         let plan = Arc::new(plan);
         let schema = LogicalPlan::explain_schema();
         let schema = schema.to_dfschema_ref()?;
@@ -399,6 +728,91 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         Ok(Schema::new(fields))
     }
 
+    /// Returns the constraints declared on the `columns`, either as column
+    /// options (e.g. `c1 INT PRIMARY KEY`) or as `table_constraints`
+    fn build_constraints(
+        &self,
+        columns: &[SQLColumnDef],
+        table_constraints: Vec<TableConstraint>,
+    ) -> Result<Constraints> {
+        let make_constraint = |is_primary: bool, indices: Vec<usize>| {
+            if is_primary {
+                Constraint::PrimaryKey(indices)
+            } else {
+                Constraint::Unique(indices)
+            }
+        };
+
+        let mut constraints = vec![];
+        for (index, column) in columns.iter().enumerate() {
+            for option in &column.options {
+                if let ColumnOption::Unique { is_primary } = option.option {
+                    constraints.push(make_constraint(is_primary, vec![index]));
+                }
+            }
+        }
+        for table_constraint in table_constraints {
+            match table_constraint {
+                TableConstraint::Unique {
+                    columns: names,
+                    is_primary,
+                    ..
+                } => {
+                    let indices = names
+                        .iter()
+                        .map(|name| {
+                            columns
+                                .iter()
+                                .position(|column| column.name.value == name.value)
+                                .ok_or_else(|| {
+                                    DataFusionError::Plan(format!(
+                                        "Constraint column {} is not a column of the table",
+                                        name
+                                    ))
+                                })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    constraints.push(make_constraint(is_primary, indices));
+                }
+                other => {
+                    return Err(DataFusionError::NotImplemented(format!(
+                        "Unsupported constraint {}, only PRIMARY KEY and UNIQUE are supported",
+                        other
+                    )))
+                }
+            }
+        }
+
+        let primary_keys = constraints
+            .iter()
+            .filter_map(|constraint| match constraint {
+                Constraint::PrimaryKey(indices) => Some(indices),
+                Constraint::Unique(_) => None,
+            })
+            .collect::<Vec<_>>();
+        if primary_keys.len() > 1 {
+            return Err(DataFusionError::Plan(
+                "A table can not have more than one primary key".to_owned(),
+            ));
+        }
+        // the columns of the primary key are not null
+        for index in primary_keys.into_iter().flatten() {
+            let column = &columns[*index];
+            if column
+                .options
+                .iter()
+                .any(|option| option.option == ColumnOption::Null)
+            {
+                return Err(DataFusionError::Plan(format!(
+                    "Primary key column {} can not be NULL",
+                    column.name
+                )));
+            }
+        }
+
+        Ok(Constraints::new(constraints))
+    }
+
     /// Maps the SQL type to the corresponding Arrow `DataType`
     fn make_data_type(&self, sql_type: &SQLDataType) -> Result<DataType> {
         match sql_type {
@@ -634,6 +1048,21 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         ctes: &mut HashMap<String, LogicalPlan>,
     ) -> Result<LogicalPlan> {
         let (plan, alias) = match relation {
+            TableFactor::Table {
+                ref name,
+                alias,
+                ref args,
+                ..
+            } if !args.is_empty() => {
+                let plan = self.table_function_to_plan(name, args.clone())?;
+                let plan = match alias {
+                    Some(ref alias) => LogicalPlanBuilder::from(plan)
+                        .alias(alias.name.value.as_str())?
+                        .build()?,
+                    None => plan,
+                };
+                (plan, alias)
+            }
             TableFactor::Table {
                 ref name, alias, ..
             } => {
@@ -646,11 +1075,17 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                     ) {
                         (Some(cte_plan), _) => Ok(cte_plan.clone()),
                         (_, Some(provider)) => {
+                            self.schema_provider.authorize_table(name.try_into()?)?;
                             let scan =
-                                LogicalPlanBuilder::scan(&table_name, provider, None);
+                                LogicalPlanBuilder::scan(&table_name, provider, None)?
+                                    .build()?;
+                            let scan = LogicalPlanBuilder::from(
+                                self.schema_provider
+                                    .apply_security_policies(name.try_into()?, scan)?,
+                            );
                             let scan = match alias {
-                                Some(ref name) => scan?.alias(name.name.value.as_str()),
-                                _ => scan,
+                                Some(ref name) => scan.alias(name.name.value.as_str()),
+                                _ => Ok(scan),
                             };
                             scan?.build()
                         }
@@ -683,7 +1118,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                             .schema()
                             .fields()
                             .iter()
-                            .map(|field| col(field.name())),
+                            .map(|field| Expr::Column(field.qualified_column())),
                         alias.as_ref().map(|a| a.name.value.to_string()),
                     )?,
                     alias,
@@ -692,7 +1127,6 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             TableFactor::NestedJoin(table_with_joins) => {
                 (self.plan_table_with_joins(*table_with_joins, ctes)?, None)
             }
-            // @todo Support TableFactory::TableFunction?
             _ => {
                 return Err(DataFusionError::NotImplemented(format!(
                     "Unsupported ast node {:?} in create_relation",
@@ -700,34 +1134,46 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 )));
             }
         };
-        if let Some(alias) = alias {
-            let columns_alias = alias.clone().columns;
-            if columns_alias.is_empty() {
-                // sqlparser-rs encodes AS t as an empty list of column alias
-                Ok(plan)
-            } else if columns_alias.len() != plan.schema().fields().len() {
-                return Err(DataFusionError::Plan(format!(
-                    "Source table contains {} columns but only {} names given as column alias",
-                    plan.schema().fields().len(),
-                    columns_alias.len(),
-                )));
-            } else {
-                Ok(LogicalPlanBuilder::from(plan.clone())
-                    .project_with_alias(
-                        plan.schema()
-                            .fields()
-                            .iter()
-                            .zip(columns_alias.iter())
-                            .map(|(field, ident)| col(field.name()).alias(&ident.value)),
-                        Some(alias.name.value),
-                    )?
-                    .build()?)
-            }
-        } else {
-            Ok(plan)
+        match alias {
+            Some(alias) => self.apply_table_alias_columns(plan, alias),
+            None => Ok(plan),
         }
     }
 
+    /// Renames the columns of `plan`, in order, to the column list of the
+    /// table `alias`, e.g. `t (a, b)`, the renamed columns being qualified by
+    /// the name of the alias
+    fn apply_table_alias_columns(
+        &self,
+        plan: LogicalPlan,
+        alias: TableAlias,
+    ) -> Result<LogicalPlan> {
+        if alias.columns.is_empty() {
+            // sqlparser-rs encodes AS t as an empty list of column alias
+            return Ok(plan);
+        }
+        let fields = plan.schema().fields();
+        if alias.columns.len() != fields.len() {
+            return Err(DataFusionError::Plan(format!(
+                "Source table contains {} columns but only {} names given as column alias",
+                fields.len(),
+                alias.columns.len(),
+            )));
+        }
+        // the columns are referenced by their qualified columns rather than
+        // parsed from their names, which may contain dots
+        let exprs = fields
+            .iter()
+            .zip(alias.columns)
+            .map(|(field, ident)| {
+                Expr::Column(field.qualified_column()).alias(&normalize_ident(ident))
+            })
+            .collect::<Vec<_>>();
+        LogicalPlanBuilder::from(plan)
+            .project_with_alias(exprs, Some(alias.name.value))?
+            .build()
+    }
+
     /// Generate a logic plan from selection clause, the function contain optimization for cross join to inner join
     /// Related PR: <https://github.com/apache/arrow-datafusion/pull/1566>
     fn plan_selection(
@@ -857,6 +1303,156 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         plan
     }
 
+    /// Generate a logical plan from a table function call in a FROM clause
+    fn table_function_to_plan(
+        &self,
+        name: &ObjectName,
+        args: Vec<FunctionArg>,
+    ) -> Result<LogicalPlan> {
+        if name.0.len() == 1 && normalize_ident(name.0[0].clone()) == DEDUP {
+            return self.dedup_to_plan(args);
+        }
+        if name.0.len() == 1 && normalize_ident(name.0[0].clone()) == PARQUET_METADATA {
+            return self.parquet_metadata_to_plan(args);
+        }
+        if name.0.len() != 1 || normalize_ident(name.0[0].clone()) != GENERATE_SERIES {
+            return Err(DataFusionError::Plan(format!(
+                "Table function '{}' not found",
+                name
+            )));
+        }
+
+        let args = self
+            .function_args_to_expr(args, &DFSchema::empty())?
+            .iter()
+            .map(|arg| {
+                int_literal_value(arg).ok_or_else(|| {
+                    DataFusionError::Plan(format!(
+                        "Arguments of {} must be integer literals, got {:?}",
+                        GENERATE_SERIES, arg
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let table = match args[..] {
+            [start, stop] => GenerateSeriesTable::try_new(start, stop, 1)?,
+            [start, stop, step] => GenerateSeriesTable::try_new(start, stop, step)?,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} expects 2 or 3 arguments, got {}",
+                    GENERATE_SERIES,
+                    args.len()
+                )))
+            }
+        };
+        LogicalPlanBuilder::scan(GENERATE_SERIES, Arc::new(table), None)?.build()
+    }
+
+    /// Generate a logical plan from a call of the table function
+    /// `parquet_metadata(path)`, scanning the metadata of the column chunks
+    /// of the Parquet files at `path`
+    fn parquet_metadata_to_plan(&self, args: Vec<FunctionArg>) -> Result<LogicalPlan> {
+        let args = self.function_args_to_expr(args, &DFSchema::empty())?;
+        let path = match &args[..] {
+            [Expr::Literal(ScalarValue::Utf8(Some(path)))] => path,
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} expects a single string literal argument, the path of the files",
+                    PARQUET_METADATA
+                )))
+            }
+        };
+        self.schema_provider.authorize_file_metadata(path)?;
+        let table = ParquetMetadataTable::new(path.as_str());
+        LogicalPlanBuilder::scan(PARQUET_METADATA, Arc::new(table), None)?.build()
+    }
+
+    /// Generate a logical plan from a call of the table function
+    /// `dedup(table, sequence [, key, ..])`, keeping the latest row of each
+    /// key of the table. The key defaults to the primary key of the table.
+    fn dedup_to_plan(&self, args: Vec<FunctionArg>) -> Result<LogicalPlan> {
+        let mut args = args
+            .into_iter()
+            .map(|arg| match arg {
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(SQLExpr::Identifier(id))) => {
+                    Ok(vec![id])
+                }
+                FunctionArg::Unnamed(FunctionArgExpr::Expr(
+                    SQLExpr::CompoundIdentifier(ids),
+                )) => Ok(ids),
+                arg => Err(DataFusionError::Plan(format!(
+                    "Arguments of {} must be identifiers, got {}",
+                    DEDUP, arg
+                ))),
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+        let (table_name, sequence) = match (args.next(), args.next()) {
+            (Some(table_name), Some(sequence)) if sequence.len() == 1 => {
+                (ObjectName(table_name), sequence)
+            }
+            _ => {
+                return Err(DataFusionError::Plan(format!(
+                    "{} expects a table, a sequence column and optional key columns",
+                    DEDUP
+                )))
+            }
+        };
+
+        let table_ref: TableReference = (&table_name).try_into()?;
+        let provider = self
+            .schema_provider
+            .get_table_provider(table_ref)
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "Table or CTE with name '{}' not found",
+                    table_name
+                ))
+            })?;
+        self.schema_provider.authorize_table(table_ref)?;
+        let scan =
+            LogicalPlanBuilder::scan(&table_name.to_string(), provider.clone(), None)?
+                .build()?;
+        let scan = self
+            .schema_provider
+            .apply_security_policies(table_ref, scan)?;
+
+        let column = |ids: Vec<Ident>| {
+            let name = ids.into_iter().map(normalize_ident).collect::<Vec<_>>();
+            let column = Column::from_qualified_name(&name.join("."));
+            normalize_col(Expr::Column(column), &scan)
+        };
+        let sequence = column(sequence)?;
+        let mut keys = args.map(column).collect::<Result<Vec<_>>>()?;
+        if keys.is_empty() {
+            let schema = provider.schema();
+            let primary_key = provider.constraints().and_then(|constraints| {
+                constraints.iter().find_map(|constraint| match constraint {
+                    Constraint::PrimaryKey(indices) => Some(indices.clone()),
+                    Constraint::Unique(_) => None,
+                })
+            });
+            keys = primary_key
+                .ok_or_else(|| {
+                    DataFusionError::Plan(format!(
+                        "The table {} has no primary key, the keys of {} must be given",
+                        table_name, DEDUP
+                    ))
+                })?
+                .into_iter()
+                .map(|index| {
+                    let name = schema.field(index).name();
+                    normalize_col(Expr::Column(Column::from_name(name)), &scan)
+                })
+                .collect::<Result<Vec<_>>>()?;
+        }
+
+        let node = DedupNode::try_new(scan, keys, sequence, DedupStrategy::default())?;
+        Ok(LogicalPlan::Extension(Extension {
+            node: Arc::new(node),
+        }))
+    }
+
     /// Generate a logic plan from an SQL select
     fn select_to_plan(
         &self,
@@ -874,6 +1470,11 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         // process the SELECT expressions, with wildcards expanded.
         let select_exprs =
             self.prepare_select_exprs(&plan, select.projection, empty_from)?;
+        let select_exprs = if self.schema_provider.alias_duplicate_names() {
+            alias_duplicate_names(select_exprs, plan.schema())?
+        } else {
+            select_exprs
+        };
 
         // having and group by clause may reference aliases defined in select projection
         let projected_plan = self.project(plan.clone(), select_exprs.clone())?;
@@ -939,11 +1540,27 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             })
             .collect::<Result<Vec<Expr>>>()?;
 
+        // the window functions in the aggregate functions are computed before
+        // the aggregation, as in a subquery
+        let (plan, select_exprs, having_expr_opt, aggr_exprs) =
+            if find_window_exprs(&aggr_exprs).is_empty() {
+                (plan, select_exprs, having_expr_opt, aggr_exprs)
+            } else {
+                self.window_before_aggregate(
+                    plan,
+                    select_exprs,
+                    having_expr_opt,
+                    aggr_exprs,
+                )?
+            };
+
         // process group by, aggregation or having
         let (plan, select_exprs_post_aggr, having_expr_post_aggr_opt) = if !group_by_exprs
             .is_empty()
-            || !aggr_exprs.is_empty()
+            && !find_nested_aggregate_exprs(&aggr_exprs).is_empty()
         {
+            self.nested_aggregate(plan, &select_exprs, &having_expr_opt, group_by_exprs)?
+        } else if !group_by_exprs.is_empty() || !aggr_exprs.is_empty() {
             self.aggregate(
                 plan,
                 &select_exprs,
@@ -1036,6 +1653,20 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         group_by_exprs: Vec<Expr>,
         aggr_exprs: Vec<Expr>,
     ) -> Result<(LogicalPlan, Vec<Expr>, Option<Expr>)> {
+        // the columns determined by the grouping columns, e.g. by a primary
+        // key, do not need to be grouped by explicitly
+        let referenced_exprs = select_exprs
+            .iter()
+            .chain(having_expr_opt.iter())
+            .cloned()
+            .collect::<Vec<_>>();
+        let group_by_exprs = add_functionally_dependent_columns(
+            group_by_exprs,
+            &aggr_exprs,
+            &referenced_exprs,
+            &input,
+        )?;
+
         let aggr_projection_exprs = group_by_exprs
             .iter()
             .chain(aggr_exprs.iter())
@@ -1089,6 +1720,164 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         Ok((plan, select_exprs_post_aggr, having_expr_post_aggr_opt))
     }
 
+    /// Wrap a plan in the two aggregations of the nested aggregate functions
+    /// of the SELECT expressions: the nested aggregate functions aggregate the
+    /// groups, which are filtered by the HAVING expression, and the outer ones
+    /// aggregate the results into a single row.
+    ///
+    /// For example:
+    ///
+    ///   SELECT MAX(COUNT(a)) FROM t GROUP BY b;
+    ///
+    /// is planned like:
+    ///
+    ///   SELECT MAX(c) FROM (SELECT COUNT(a) AS c FROM t GROUP BY b);
+    ///
+    fn nested_aggregate(
+        &self,
+        input: LogicalPlan,
+        select_exprs: &[Expr],
+        having_expr_opt: &Option<Expr>,
+        group_by_exprs: Vec<Expr>,
+    ) -> Result<(LogicalPlan, Vec<Expr>, Option<Expr>)> {
+        let having_exprs = having_expr_opt.iter().cloned().collect::<Vec<_>>();
+        let having_aggr_exprs = find_aggregate_exprs(&having_exprs);
+        if let Some(nested) = find_nested_aggregate_exprs(&having_aggr_exprs).first() {
+            return Err(DataFusionError::Plan(format!(
+                "Nested aggregate function {:?} is not allowed in HAVING, \
+                 which filters the groups before the outer aggregation",
+                nested
+            )));
+        }
+        let outer_aggr_exprs = find_aggregate_exprs(select_exprs);
+        if let Some(expr) = outer_aggr_exprs
+            .iter()
+            .find(|expr| find_nested_aggregate_exprs(&[(*expr).clone()]).is_empty())
+        {
+            return Err(DataFusionError::Plan(format!(
+                "Aggregate function {:?} must contain a nested aggregate function, \
+                 like the other aggregate functions of the SELECT list",
+                expr
+            )));
+        }
+        let mut inner_aggr_exprs = find_nested_aggregate_exprs(&outer_aggr_exprs);
+        if let Some(nested) = find_nested_aggregate_exprs(&inner_aggr_exprs).first() {
+            return Err(DataFusionError::Plan(format!(
+                "Aggregate functions cannot be nested more than two levels deep, \
+                 as the aggregate function {:?}",
+                nested
+            )));
+        }
+        for expr in having_aggr_exprs {
+            if !inner_aggr_exprs.contains(&expr) {
+                inner_aggr_exprs.push(expr);
+            }
+        }
+
+        let (plan, select_exprs_post_inner, having_expr_post_aggr_opt) = self.aggregate(
+            input,
+            select_exprs,
+            having_expr_opt,
+            group_by_exprs,
+            inner_aggr_exprs,
+        )?;
+        let plan = if let Some(having_expr_post_aggr) = having_expr_post_aggr_opt {
+            LogicalPlanBuilder::from(plan)
+                .filter(having_expr_post_aggr)?
+                .build()?
+        } else {
+            plan
+        };
+
+        // the groups are aggregated into a single row, the values outside of
+        // the outer aggregate functions are not defined
+        let outer_aggr_exprs = find_aggregate_exprs(&select_exprs_post_inner);
+        let outer_aggr_columns = outer_aggr_exprs
+            .iter()
+            .map(|expr| expr_as_column_expr(expr, &plan))
+            .collect::<Result<Vec<_>>>()?;
+        for expr in &select_exprs_post_inner {
+            let expr = rebase_expr(expr, &outer_aggr_exprs, &plan)?;
+            if let Some(column) = find_column_exprs(&[expr])
+                .into_iter()
+                .find(|column| !outer_aggr_columns.contains(column))
+            {
+                return Err(DataFusionError::Plan(format!(
+                    "Column {:?} must appear in an aggregate function, \
+                     as the nested aggregate functions aggregate all the groups",
+                    column
+                )));
+            }
+        }
+
+        let (plan, select_exprs_post_aggr, _) = self.aggregate(
+            plan,
+            &select_exprs_post_inner,
+            &None,
+            vec![],
+            outer_aggr_exprs,
+        )?;
+        Ok((plan, select_exprs_post_aggr, None))
+    }
+
+    /// Computes the window functions in the arguments of the aggregate
+    /// functions `aggr_exprs` before the aggregation, which is planned as if
+    /// it aggregated a subquery computing them. For example
+    ///
+    ///   SELECT state, MAX(ROW_NUMBER() OVER (ORDER BY age)) FROM person GROUP BY state
+    ///
+    /// is planned as
+    ///
+    ///   SELECT state, MAX(rn) FROM
+    ///     (SELECT state, ROW_NUMBER() OVER (ORDER BY age) AS rn FROM person)
+    ///   GROUP BY state
+    ///
+    /// Returns the input with the window functions, and the SELECT, HAVING and
+    /// aggregate expressions referencing their results.
+    fn window_before_aggregate(
+        &self,
+        input: LogicalPlan,
+        select_exprs: Vec<Expr>,
+        having_expr_opt: Option<Expr>,
+        aggr_exprs: Vec<Expr>,
+    ) -> Result<(LogicalPlan, Vec<Expr>, Option<Expr>, Vec<Expr>)> {
+        let window_exprs = find_window_exprs(&aggr_exprs);
+        for expr in &window_exprs {
+            if let Some(aggr_expr) = find_aggregate_exprs(&[expr.clone()]).first() {
+                return Err(DataFusionError::Plan(format!(
+                    "Window function {:?} in an aggregate function cannot contain \
+                     the aggregate function {:?}, as it is computed before the \
+                     aggregation",
+                    expr, aggr_expr
+                )));
+            }
+        }
+        let input_schema = input.schema().clone();
+        let plan = LogicalPlanBuilder::window_plan(input, window_exprs.clone())?;
+        let rebased_aggr_exprs = aggr_exprs
+            .iter()
+            .map(|expr| rebase_expr(expr, &window_exprs, &plan))
+            .collect::<Result<Vec<_>>>()?;
+
+        // the SELECT expressions keep their names
+        let select_exprs = select_exprs
+            .iter()
+            .map(|expr| {
+                let rebased = replace_exprs(expr, &aggr_exprs, &rebased_aggr_exprs)?;
+                let name = expr.name(&input_schema)?;
+                if rebased.name(plan.schema())? == name {
+                    Ok(rebased)
+                } else {
+                    Ok(rebased.alias(&name))
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let having_expr_opt = having_expr_opt
+            .map(|expr| replace_exprs(&expr, &aggr_exprs, &rebased_aggr_exprs))
+            .transpose()?;
+        Ok((plan, select_exprs, having_expr_opt, rebased_aggr_exprs))
+    }
+
     /// Wrap a plan in a limit
     fn limit(&self, input: LogicalPlan, limit: Option<SQLExpr>) -> Result<LogicalPlan> {
         match limit {
@@ -1160,9 +1949,13 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             Expr::Sort {
                 expr: Box::new(expr),
                 asc,
-                // when asc is true, by default nulls last to be consistent with postgres
+                // by default nulls last when asc is true to be consistent with postgres
                 // postgres rule: https://www.postgresql.org/docs/current/queries-order.html
-                nulls_first: nulls_first.unwrap_or(!asc),
+                nulls_first: nulls_first.unwrap_or_else(|| {
+                    self.schema_provider
+                        .default_null_ordering()
+                        .nulls_first(asc)
+                }),
             }
         })
     }
@@ -1306,6 +2099,18 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         right: SQLExpr,
         schema: &DFSchema,
     ) -> Result<Expr> {
+        let (left, right) = match (row_fields(left), row_fields(right)) {
+            (Ok(left), Ok(right)) => {
+                return self.parse_row_comparison(left, op, right, schema)
+            }
+            (Err(left), Err(right)) => (left, right),
+            _ => {
+                return Err(DataFusionError::Plan(
+                    "Row values can only be compared to row values".to_string(),
+                ))
+            }
+        };
+
         let operator = match op {
             BinaryOperator::Gt => Ok(Operator::Gt),
             BinaryOperator::GtEq => Ok(Operator::GtEq),
@@ -1335,13 +2140,93 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             ))),
         }?;
 
+        let left = self.sql_expr_to_logical_expr(left, schema)?;
+        let right = self.sql_expr_to_logical_expr(right, schema)?;
+        let (left, right) = match operator {
+            Operator::Eq
+            | Operator::NotEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Gt
+            | Operator::GtEq => collate_operands(left, right),
+            _ => (left, right),
+        };
         Ok(Expr::BinaryExpr {
-            left: Box::new(self.sql_expr_to_logical_expr(left, schema)?),
+            left: Box::new(left),
             op: operator,
-            right: Box::new(self.sql_expr_to_logical_expr(right, schema)?),
+            right: Box::new(right),
         })
     }
 
+    /// Lowers the comparison of the row values of `left` and `right` fields,
+    /// e.g. `(a, b) < (1, 2)`, to the comparisons of their fields: the rows
+    /// are equal when all their fields are equal and are ordered by their
+    /// first unequal fields
+    fn parse_row_comparison(
+        &self,
+        left: Vec<SQLExpr>,
+        op: BinaryOperator,
+        right: Vec<SQLExpr>,
+        schema: &DFSchema,
+    ) -> Result<Expr> {
+        if left.len() != right.len() {
+            return Err(DataFusionError::Plan(format!(
+                "Row values of {} and {} fields cannot be compared",
+                left.len(),
+                right.len()
+            )));
+        }
+        let fields = left.into_iter().zip(right);
+        match op {
+            BinaryOperator::Eq => fields
+                .map(|(l, r)| self.parse_sql_binary_op(l, BinaryOperator::Eq, r, schema))
+                .reduce(|acc, expr| Ok(acc?.and(expr?)))
+                .unwrap_or_else(|| Ok(lit(true))),
+            BinaryOperator::NotEq => fields
+                .map(|(l, r)| {
+                    self.parse_sql_binary_op(l, BinaryOperator::NotEq, r, schema)
+                })
+                .reduce(|acc, expr| Ok(acc?.or(expr?)))
+                .unwrap_or_else(|| Ok(lit(false))),
+            BinaryOperator::Lt
+            | BinaryOperator::LtEq
+            | BinaryOperator::Gt
+            | BinaryOperator::GtEq => {
+                let strict = match op {
+                    BinaryOperator::Lt | BinaryOperator::LtEq => BinaryOperator::Lt,
+                    _ => BinaryOperator::Gt,
+                };
+                // the last fields are compared with the operator, the others
+                // strictly, each comparison applying when the previous fields
+                // are equal
+                let mut fields = fields.rev();
+                let (l, r) = fields.next().ok_or_else(|| {
+                    DataFusionError::Plan(
+                        "Empty row values cannot be ordered".to_string(),
+                    )
+                })?;
+                fields.try_fold(
+                    self.parse_sql_binary_op(l, op, r, schema)?,
+                    |acc, (l, r)| {
+                        let strict = self.parse_sql_binary_op(
+                            l.clone(),
+                            strict.clone(),
+                            r.clone(),
+                            schema,
+                        )?;
+                        let equal =
+                            self.parse_sql_binary_op(l, BinaryOperator::Eq, r, schema)?;
+                        Ok(strict.or(equal.and(acc)))
+                    },
+                )
+            }
+            _ => Err(DataFusionError::NotImplemented(format!(
+                "Unsupported SQL binary operator {:?} on row values",
+                op
+            ))),
+        }
+    }
+
     fn parse_sql_unary_op(
         &self,
         op: UnaryOperator,
@@ -1466,17 +2351,17 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 }
             }
 
-            SQLExpr::MapAccess { ref column, keys } => {
-                if let SQLExpr::Identifier(ref id) = column.as_ref() {
-                    plan_indexed(col(&id.value), keys)
-                } else {
-                    Err(DataFusionError::NotImplemented(format!(
-                        "map access requires an identifier, found column {} instead",
-                        column
-                    )))
-                }
-            }
-
+            SQLExpr::MapAccess { column, keys } => match *column {
+                column @ (SQLExpr::Identifier(_) | SQLExpr::CompoundIdentifier(_)) => {
+                    let expr = self.sql_expr_to_logical_expr(column, schema)?;
+                    self.plan_indexed(expr, keys, schema)
+                }
+                column => Err(DataFusionError::NotImplemented(format!(
+                    "map access requires an identifier, found column {} instead",
+                    column
+                ))),
+            },
+
             SQLExpr::CompoundIdentifier(ids) => {
                 let mut var_names: Vec<_> = ids.into_iter().map(normalize_ident).collect();
 
@@ -1492,19 +2377,7 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                         })?;
                     Ok(Expr::ScalarVariable(ty, var_names))
                 } else {
-                    match (var_names.pop(), var_names.pop()) {
-                        (Some(name), Some(relation)) if var_names.is_empty() => {
-                            // table.column identifier
-                            Ok(Expr::Column(Column {
-                                relation: Some(relation),
-                                name,
-                            }))
-                        }
-                        _ => Err(DataFusionError::NotImplemented(format!(
-                            "Unsupported compound identifier '{:?}'",
-                            var_names,
-                        ))),
-                    }
+                    self.plan_compound_identifier(var_names, schema)
                 }
             }
 
@@ -1604,6 +2477,31 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 high: Box::new(self.sql_expr_to_logical_expr(*high, schema)?),
             }),
 
+            SQLExpr::InList {
+                expr,
+                list,
+                negated,
+            } if matches!(row_fields(*expr.clone()), Ok(_)) => {
+                // a row value is in a list when it is equal to one of its rows
+                let in_list = list
+                    .into_iter()
+                    .map(|e| {
+                        self.parse_sql_binary_op(
+                            *expr.clone(),
+                            BinaryOperator::Eq,
+                            e,
+                            schema,
+                        )
+                    })
+                    .reduce(|acc, expr| Ok(acc?.or(expr?)))
+                    .unwrap_or_else(|| Ok(lit(false)))?;
+                Ok(if negated {
+                    Expr::Not(Box::new(in_list))
+                } else {
+                    in_list
+                })
+            }
+
             SQLExpr::InList {
                 expr,
                 list,
@@ -1820,6 +2718,23 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
 
             SQLExpr::Nested(e) => self.sql_expr_to_logical_expr(*e, schema),
 
+            SQLExpr::Tuple(_) => Err(DataFusionError::Plan(
+                "Row values are only supported in comparisons and IN lists".to_string(),
+            )),
+
+            SQLExpr::Collate { expr, collation } => {
+                let collation = collation
+                    .0
+                    .iter()
+                    .map(|ident| ident.value.as_str())
+                    .collect::<Vec<_>>()
+                    .join(".");
+                Ok(collate(
+                    self.sql_expr_to_logical_expr(*expr, schema)?,
+                    lit(collation),
+                ))
+            }
+
             _ => Err(DataFusionError::NotImplemented(format!(
                 "Unsupported ast node {:?} in sqltorel",
                 sql
@@ -1889,146 +2804,10 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             )));
         }
 
-        const SECONDS_PER_HOUR: f32 = 3_600_f32;
-        const MILLIS_PER_SECOND: f32 = 1_000_f32;
-
-        // We are storing parts as integers, it's why we need to align parts fractional
-        // INTERVAL '0.5 MONTH' = 15 days, INTERVAL '1.5 MONTH' = 1 month 15 days
-        // INTERVAL '0.5 DAY' = 12 hours, INTERVAL '1.5 DAY' = 1 day 12 hours
-        let align_interval_parts = |month_part: f32,
-                                    mut day_part: f32,
-                                    mut milles_part: f32|
-         -> (i32, i32, f32) {
-            // Convert fractional month to days, It's not supported by Arrow types, but anyway
-            day_part += (month_part - (month_part as i32) as f32) * 30_f32;
-
-            // Convert fractional days to hours
-            milles_part += (day_part - ((day_part as i32) as f32))
-                * 24_f32
-                * SECONDS_PER_HOUR
-                * MILLIS_PER_SECOND;
-
-            (month_part as i32, day_part as i32, milles_part)
-        };
-
-        let calculate_from_part = |interval_period_str: &str,
-                                   interval_type: &str|
-         -> Result<(i32, i32, f32)> {
-            // @todo It's better to use Decimal in order to protect rounding errors
-            // Wait https://github.com/apache/arrow/pull/9232
-            let interval_period = match f32::from_str(interval_period_str) {
-                Ok(n) => n,
-                Err(_) => {
-                    return Err(DataFusionError::SQL(ParserError(format!(
-                        "Unsupported Interval Expression with value {:?}",
-                        value
-                    ))));
-                }
-            };
-
-            if interval_period > (i32::MAX as f32) {
-                return Err(DataFusionError::NotImplemented(format!(
-                    "Interval field value out of range: {:?}",
-                    value
-                )));
-            }
-
-            match interval_type.to_lowercase().as_str() {
-                "year" => Ok(align_interval_parts(interval_period * 12_f32, 0.0, 0.0)),
-                "month" => Ok(align_interval_parts(interval_period, 0.0, 0.0)),
-                "day" | "days" => Ok(align_interval_parts(0.0, interval_period, 0.0)),
-                "hour" | "hours" => {
-                    Ok((0, 0, interval_period * SECONDS_PER_HOUR * MILLIS_PER_SECOND))
-                }
-                "minutes" | "minute" => {
-                    Ok((0, 0, interval_period * 60_f32 * MILLIS_PER_SECOND))
-                }
-                "seconds" | "second" => Ok((0, 0, interval_period * MILLIS_PER_SECOND)),
-                "milliseconds" | "millisecond" => Ok((0, 0, interval_period)),
-                _ => Err(DataFusionError::NotImplemented(format!(
-                    "Invalid input syntax for type interval: {:?}",
-                    value
-                ))),
-            }
-        };
-
-        let mut result_month: i64 = 0;
-        let mut result_days: i64 = 0;
-        let mut result_millis: i64 = 0;
-
-        let mut parts = value.split_whitespace();
-
-        loop {
-            let interval_period_str = parts.next();
-            if interval_period_str.is_none() {
-                break;
-            }
-
-            let leading_field = leading_field
-                .as_ref()
-                .map(|dt| dt.to_string())
-                .unwrap_or_else(|| "second".to_string());
-
-            let unit = parts
-                .next()
-                .map(|part| part.to_string())
-                .unwrap_or(leading_field);
-
-            let (diff_month, diff_days, diff_millis) =
-                calculate_from_part(interval_period_str.unwrap(), &unit)?;
-
-            result_month += diff_month as i64;
-
-            if result_month > (i32::MAX as i64) {
-                return Err(DataFusionError::NotImplemented(format!(
-                    "Interval field value out of range: {:?}",
-                    value
-                )));
-            }
-
-            result_days += diff_days as i64;
-
-            if result_days > (i32::MAX as i64) {
-                return Err(DataFusionError::NotImplemented(format!(
-                    "Interval field value out of range: {:?}",
-                    value
-                )));
-            }
-
-            result_millis += diff_millis as i64;
-
-            if result_millis > (i32::MAX as i64) {
-                return Err(DataFusionError::NotImplemented(format!(
-                    "Interval field value out of range: {:?}",
-                    value
-                )));
-            }
-        }
-
-        // Interval is tricky thing
-        // 1 day is not 24 hours because timezones, 1 year != 365/364! 30 days != 1 month
-        // The true way to store and calculate intervals is to store it as it defined
-        // It's why we there are 3 different interval types in Arrow
-        if result_month != 0 && (result_days != 0 || result_millis != 0) {
-            let result: i128 = ((result_month as i128) << 96)
-                | ((result_days as i128) << 64)
-                // IntervalMonthDayNano uses nanos, but IntervalDayTime uses milles
-                | ((result_millis * 1_000_000_i64) as i128);
-
-            return Ok(Expr::Literal(ScalarValue::IntervalMonthDayNano(Some(
-                result,
-            ))));
-        }
-
-        // Month interval
-        if result_month != 0 {
-            return Ok(Expr::Literal(ScalarValue::IntervalYearMonth(Some(
-                result_month as i32,
-            ))));
-        }
-
-        let result: i64 = (result_days << 32) | result_millis;
-        Ok(Expr::Literal(ScalarValue::IntervalDayTime(Some(result))))
+        let leading_field = leading_field
+            .map(|field| field.to_string())
+            .unwrap_or_else(|| "second".to_string());
+        Ok(Expr::Literal(parse_interval(&value, &leading_field)?))
     }
 
     fn show_variable_to_plan(&self, variable: &[Ident]) -> Result<LogicalPlan> {
@@ -2084,6 +2863,8 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 table_name
             )));
         }
+        self.schema_provider
+            .authorize_table(table_name.try_into()?)?;
 
         // Figure out the where clause
         let columns = vec!["table_name", "table_schema", "table_catalog"].into_iter();
@@ -2121,6 +2902,75 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             .is_some()
     }
 
+    /// Plans a compound identifier as the column `relation.column`, followed by
+    /// the fields of its structs, or as the column `column` followed by the
+    /// fields of its structs when there is no such qualified column
+    fn plan_compound_identifier(
+        &self,
+        mut ids: Vec<String>,
+        schema: &DFSchema,
+    ) -> Result<Expr> {
+        let qualified =
+            ids.len() > 1 && schema.field_with_qualified_name(&ids[0], &ids[1]).is_ok();
+        let unqualified = !schema.fields_with_unqualified_name(&ids[0]).is_empty();
+        let (column, fields) = if qualified || (ids.len() == 2 && !unqualified) {
+            // table.column identifier
+            let fields = ids.split_off(2);
+            let name = ids.pop().unwrap();
+            let column = Column {
+                relation: ids.pop(),
+                name,
+            };
+            (column, fields)
+        } else if unqualified {
+            let fields = ids.split_off(1);
+            (Column::from_name(ids.pop().unwrap()), fields)
+        } else {
+            return Err(DataFusionError::NotImplemented(format!(
+                "Unsupported compound identifier '{:?}'",
+                ids,
+            )));
+        };
+
+        Ok(fields
+            .into_iter()
+            .fold(Expr::Column(column), |expr, field| Expr::GetIndexedField {
+                expr: Box::new(expr),
+                key: ScalarValue::Utf8(Some(field)),
+            }))
+    }
+
+    /// Plans the access `expr[key]..` of the elements of a list by index, of the
+    /// fields of a struct by name or of the values of a map by key
+    fn plan_indexed(
+        &self,
+        expr: Expr,
+        keys: Vec<SQLExpr>,
+        schema: &DFSchema,
+    ) -> Result<Expr> {
+        keys.into_iter().try_fold(expr, |expr, key| {
+            let key = match plan_key(key)? {
+                ScalarValue::Int64(Some(index))
+                    if self.schema_provider.one_based_list_index()
+                        && matches!(expr.get_type(schema)?, DataType::List(_)) =>
+                {
+                    if index < 1 {
+                        return Err(DataFusionError::Plan(format!(
+                            "List indexes start at 1, was {}",
+                            index
+                        )));
+                    }
+                    ScalarValue::Int64(Some(index - 1))
+                }
+                key => key,
+            };
+            Ok(Expr::GetIndexedField {
+                expr: Box::new(expr),
+                key,
+            })
+        })
+    }
+
     fn sql_array_literal(
         &self,
         elements: Vec<SQLExpr>,
@@ -2143,27 +2993,52 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             }
         }
 
-        let data_types: HashSet<DataType> =
-            values.iter().map(|e| e.get_datatype()).collect();
-
-        if data_types.is_empty() {
-            Ok(Expr::Literal(ScalarValue::List(
+        if values.is_empty() {
+            return Ok(Expr::Literal(ScalarValue::List(
                 None,
                 Box::new(DataType::Utf8),
-            )))
-        } else if data_types.len() > 1 {
-            Err(DataFusionError::NotImplemented(format!(
-                "Arrays with different types are not supported: {:?}",
-                data_types,
-            )))
-        } else {
-            let data_type = values[0].get_datatype();
-
-            Ok(Expr::Literal(ScalarValue::List(
-                Some(Box::new(values)),
-                Box::new(data_type),
-            )))
+            )));
         }
+
+        // the elements are converted to the common type of the non null ones
+        let data_type = values
+            .iter()
+            .filter(|value| !value.is_null())
+            .try_fold(None, |common: Option<DataType>, value| {
+                let data_type = value.get_datatype();
+                match common {
+                    None => Ok(Some(data_type)),
+                    Some(common) if common == data_type => Ok(Some(common)),
+                    Some(common) => coerce_types(&common, &Operator::Eq, &data_type)
+                        .map(Some)
+                        .map_err(|_| {
+                            DataFusionError::NotImplemented(format!(
+                                "Arrays with different types are not supported: {:?} and {:?}",
+                                common, data_type
+                            ))
+                        }),
+                }
+            })?
+            .unwrap_or_else(|| values[0].get_datatype());
+
+        let values = values
+            .into_iter()
+            .map(|value| {
+                if value.get_datatype() == data_type {
+                    Ok(value)
+                } else if value.is_null() {
+                    ScalarValue::try_from(&data_type)
+                } else {
+                    let array = cast(&value.to_array(), &data_type)?;
+                    ScalarValue::try_from_array(&array, 0)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Expr::Literal(ScalarValue::List(
+            Some(Box::new(values)),
+            Box::new(data_type),
+        )))
     }
 }
 
@@ -2296,6 +3171,69 @@ pub fn convert_data_type(sql_type: &SQLDataType) -> Result<DataType> {
     }
 }
 
+/// Returns the fields of the row value constructor `expr`, e.g. `(a, b)` or
+/// `ROW(a, b)`, or `expr` itself if it is not a row value
+fn row_fields(expr: SQLExpr) -> std::result::Result<Vec<SQLExpr>, SQLExpr> {
+    match expr {
+        SQLExpr::Tuple(fields) => Ok(fields),
+        SQLExpr::Nested(inner) => match row_fields(*inner) {
+            Ok(fields) => Ok(fields),
+            Err(inner) => Err(SQLExpr::Nested(Box::new(inner))),
+        },
+        SQLExpr::Function(function)
+            if function.over.is_none()
+                && !function.distinct
+                && function.name.0.len() == 1
+                && function.name.0[0].value.eq_ignore_ascii_case("row")
+                && function.name.0[0].quote_style.is_none()
+                && function.args.iter().all(|arg| {
+                    matches!(arg, FunctionArg::Unnamed(FunctionArgExpr::Expr(_)))
+                }) =>
+        {
+            Ok(function
+                .args
+                .into_iter()
+                .map(|arg| match arg {
+                    FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => expr,
+                    _ => unreachable!(),
+                })
+                .collect())
+        }
+        expr => Err(expr),
+    }
+}
+
+/// Compares both operands of a comparison under the collation of the
+/// collated one, e.g. `name COLLATE nocase = 'Foo'` compares `'Foo'` without
+/// case too.
+fn collate_operands(left: Expr, right: Expr) -> (Expr, Expr) {
+    fn collation(expr: &Expr) -> Option<Expr> {
+        match expr {
+            Expr::ScalarFunction {
+                fun: BuiltinScalarFunction::Collate,
+                args,
+            } => args.get(1).cloned(),
+            _ => None,
+        }
+    }
+
+    match (collation(&left), collation(&right)) {
+        (Some(collation), None) => (left, collate(right, collation)),
+        (None, Some(collation)) => (collate(left, collation), right),
+        _ => (left, right),
+    }
+}
+
+/// Returns the value of an integer literal, possibly negated
+fn int_literal_value(expr: &Expr) -> Option<i64> {
+    match expr {
+        Expr::Literal(ScalarValue::Int64(Some(v))) => Some(*v),
+        Expr::Literal(ScalarValue::Int32(Some(v))) => Some(*v as i64),
+        Expr::Negative(expr) => int_literal_value(expr).and_then(i64::checked_neg),
+        _ => None,
+    }
+}
+
 // Parse number in sql string, convert to Expr::Literal
 fn parse_sql_number(n: &str) -> Result<Expr> {
     match n.parse::<i64>() {
@@ -2307,7 +3245,11 @@ fn parse_sql_number(n: &str) -> Result<Expr> {
 #[cfg(test)]
 mod tests {
     use crate::datasource::empty::EmptyTable;
-    use crate::{assert_contains, logical_plan::create_udf, sql::parser::DFParser};
+    use crate::{
+        assert_contains,
+        logical_plan::{col, create_udf},
+        sql::parser::DFParser,
+    };
     use datafusion_expr::{ScalarFunctionImplementation, Volatility};
 
     use super::*;
@@ -2543,6 +3485,16 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn cte_with_column_alias() {
+        let sql = "WITH t(a, b) AS (SELECT first_name, age FROM person) SELECT a FROM t";
+        let expected = "Projection: #t.a\
+                        \n  Projection: #t.first_name AS a, #t.age AS b, alias=t\
+                        \n    Projection: #person.first_name, #person.age, alias=t\
+                        \n      TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
     #[test]
     fn table_with_column_alias_number_cols() {
         let sql = "SELECT a, b, c
@@ -2554,6 +3506,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn select_where_row_comparison() {
+        let sql = "SELECT id FROM person WHERE (age, state) = (21, 'CO')";
+        let expected = "Projection: #person.id\
+                        \n  Filter: #person.age = Int64(21) AND #person.state = Utf8(\"CO\")\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        let sql = "SELECT id FROM person WHERE ROW(age, state) <> ROW(21, 'CO')";
+        let expected = "Projection: #person.id\
+                        \n  Filter: #person.age != Int64(21) OR #person.state != Utf8(\"CO\")\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+
+        let sql = "SELECT id FROM person WHERE (age, id) <= (21, 5)";
+        let expected = "Projection: #person.id\
+                        \n  Filter: #person.age < Int64(21) OR #person.age = Int64(21) AND #person.id <= Int64(5)\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_where_row_in_list() {
+        let sql =
+            "SELECT id FROM person WHERE (age, state) NOT IN ((21, 'CO'), (22, 'CA'))";
+        let expected = "Projection: #person.id\
+                        \n  Filter: NOT #person.age = Int64(21) AND #person.state = Utf8(\"CO\") OR #person.age = Int64(22) AND #person.state = Utf8(\"CA\")\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_where_row_comparison_errors() {
+        let sql = "SELECT id FROM person WHERE (age, state) = (21, 'CO', 1)";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Row values of 2 and 3 fields cannot be compared\")",
+            format!("{:?}", err)
+        );
+
+        let sql = "SELECT id FROM person WHERE (age, state) = 21";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Row values can only be compared to row values\")",
+            format!("{:?}", err)
+        );
+
+        let sql = "SELECT (age, state) FROM person";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Row values are only supported in comparisons and IN lists\")",
+            format!("{:?}", err)
+        );
+    }
+
     #[test]
     fn select_with_having() {
         let sql = "SELECT id, age
@@ -2955,6 +3962,71 @@ mod tests {
         );
     }
 
+    #[test]
+    fn select_nested_aggregate_with_groupby() {
+        quick_test(
+            "SELECT MAX(COUNT(*)), AVG(SUM(age)) + 1 FROM person \
+             GROUP BY state HAVING COUNT(*) > 1",
+            "Projection: #MAX(COUNT(UInt8(1))), #AVG(SUM(person.age)) + Int64(1)\
+            \n  Aggregate: groupBy=[[]], aggr=[[MAX(#COUNT(UInt8(1))), AVG(#SUM(person.age))]]\
+            \n    Filter: #COUNT(UInt8(1)) > Int64(1)\
+            \n      Aggregate: groupBy=[[#person.state]], aggr=[[COUNT(UInt8(1)), SUM(#person.age)]]\
+            \n        TableScan: person projection=None",
+        );
+    }
+
+    #[test]
+    fn select_nested_aggregate_errors() {
+        let cases = [
+            (
+                "SELECT MAX(COUNT(*)) FROM person",
+                "Aggregate function MAX(COUNT(UInt8(1))) cannot contain the aggregate function COUNT(UInt8(1))",
+            ),
+            (
+                "SELECT MAX(COUNT(*)), COUNT(*) FROM person GROUP BY state",
+                "Aggregate function COUNT(UInt8(1)) must contain a nested aggregate function",
+            ),
+            (
+                "SELECT MAX(MIN(COUNT(*))) FROM person GROUP BY state",
+                "Aggregate functions cannot be nested more than two levels deep",
+            ),
+            (
+                "SELECT COUNT(*) FROM person GROUP BY state HAVING MAX(COUNT(*)) > 1",
+                "Nested aggregate function COUNT(UInt8(1)) is not allowed in HAVING",
+            ),
+            (
+                "SELECT state, MAX(COUNT(*)) FROM person GROUP BY state",
+                "must appear in an aggregate function, as the nested aggregate functions aggregate all the groups",
+            ),
+        ];
+        for (sql, expected) in cases {
+            let err = logical_plan(sql).expect_err("query should have failed");
+            assert_contains!(err.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn select_aggregate_of_window_function() {
+        quick_test(
+            "SELECT state, MAX(ROW_NUMBER() OVER (ORDER BY age)) AS m FROM person \
+             GROUP BY state HAVING MAX(ROW_NUMBER() OVER (ORDER BY age)) > 1",
+            "Projection: #person.state, #MAX(ROW_NUMBER() ORDER BY [#person.age ASC NULLS LAST]) AS m\
+             \n  Filter: #MAX(ROW_NUMBER() ORDER BY [#person.age ASC NULLS LAST]) > Int64(1)\
+             \n    Aggregate: groupBy=[[#person.state]], aggr=[[MAX(#ROW_NUMBER() ORDER BY [#person.age ASC NULLS LAST])]]\
+             \n      WindowAggr: windowExpr=[[ROW_NUMBER() ORDER BY [#person.age ASC NULLS LAST]]]\
+             \n        TableScan: person projection=None",
+        );
+
+        let err = logical_plan(
+            "SELECT MAX(RANK() OVER (ORDER BY COUNT(*))) FROM person GROUP BY state",
+        )
+        .expect_err("query should have failed");
+        assert_contains!(
+            err.to_string(),
+            "cannot contain the aggregate function COUNT(UInt8(1))"
+        );
+    }
+
     #[test]
     fn select_simple_aggregate_with_groupby_with_aliases() {
         quick_test(
@@ -3019,11 +4091,18 @@ mod tests {
     fn select_array_no_common_type() {
         let sql = "SELECT [1, true, null]";
         let err = logical_plan(sql).expect_err("query should have failed");
-
-        // HashSet doesn't guarantee order
         assert_contains!(
             err.to_string(),
-            r#"Arrays with different types are not supported: "#
+            "Arrays with different types are not supported: Int64 and Boolean"
+        );
+    }
+
+    #[test]
+    fn select_array_common_type() {
+        quick_test(
+            "SELECT [1, 2.5, null], [null, 'a'], [null]",
+            "Projection: List([1,2.5,NULL]), List([NULL,a]), List([NULL])\
+             \n  EmptyRelation",
         );
     }
 
@@ -3358,6 +4437,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn select_order_by_collate() {
+        let sql = "SELECT id, first_name FROM person ORDER BY first_name COLLATE nocase";
+        let expected =
+            "Sort: collate(#person.first_name, Utf8(\"nocase\")) ASC NULLS LAST\
+                        \n  Projection: #person.id, #person.first_name\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
+    #[test]
+    fn select_where_collate() {
+        let sql = "SELECT id FROM person WHERE first_name COLLATE nocase = 'Ann'";
+        let expected = "Projection: #person.id\
+                        \n  Filter: collate(#person.first_name, Utf8(\"nocase\")) = collate(Utf8(\"Ann\"), Utf8(\"nocase\"))\
+                        \n    TableScan: person projection=None";
+        quick_test(sql, expected);
+    }
+
     #[test]
     fn select_group_by() {
         let sql = "SELECT state FROM person GROUP BY state";
@@ -3443,6 +4541,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn create_external_table_with_constraints() {
+        let sql = "CREATE EXTERNAL TABLE t(c1 int PRIMARY KEY, c2 int, UNIQUE (c2)) STORED AS CSV LOCATION 'foo.csv'";
+        match logical_plan(sql).unwrap() {
+            LogicalPlan::CreateExternalTable(PlanCreateExternalTable {
+                constraints,
+                ..
+            }) => assert_eq!(
+                constraints,
+                Constraints::new(vec![
+                    Constraint::PrimaryKey(vec![0]),
+                    Constraint::Unique(vec![1]),
+                ])
+            ),
+            plan => panic!("unexpected plan {:?}", plan),
+        }
+
+        let sql = "CREATE EXTERNAL TABLE t(c1 int, PRIMARY KEY (c3)) STORED AS CSV LOCATION 'foo.csv'";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Constraint column c3 is not a column of the table\")",
+            format!("{:?}", err)
+        );
+
+        let sql = "CREATE EXTERNAL TABLE t(c1 int NULL PRIMARY KEY) STORED AS CSV LOCATION 'foo.csv'";
+        let err = logical_plan(sql).expect_err("query should have failed");
+        assert_eq!(
+            "Plan(\"Primary key column c1 can not be NULL\")",
+            format!("{:?}", err)
+        );
+    }
+
     #[test]
     fn create_external_table_parquet_no_schema() {
         let sql = "CREATE EXTERNAL TABLE t STORED AS PARQUET LOCATION 'foo.parquet'";
@@ -3450,6 +4580,21 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn create_external_table_with_order() {
+        let sql = "CREATE EXTERNAL TABLE t(c1 int, c2 int) STORED AS CSV WITH ORDER (c1, 2 DESC) LOCATION 'foo.csv'";
+        match logical_plan(sql).unwrap() {
+            LogicalPlan::CreateExternalTable(PlanCreateExternalTable {
+                order_exprs,
+                ..
+            }) => assert_eq!(
+                order_exprs,
+                vec![col("c1").sort(true, false), col("c2").sort(false, true)]
+            ),
+            plan => panic!("unexpected plan {:?}", plan),
+        }
+    }
+
     #[test]
     fn equijoin_explicit_syntax() {
         let sql = "SELECT id, order_id \
@@ -4011,6 +5156,14 @@ mod tests {
         quick_test(sql, expected);
     }
 
+    #[test]
+    fn transaction_statements() {
+        quick_test("BEGIN", "Transaction: Begin");
+        quick_test("START TRANSACTION", "Transaction: Begin");
+        quick_test("COMMIT", "Transaction: Commit");
+        quick_test("ROLLBACK", "Transaction: Rollback");
+    }
+
     fn logical_plan(sql: &str) -> Result<LogicalPlan> {
         let planner = SqlToRel::new(&MockContextProvider {});
         let result = DFParser::parse_sql(sql);
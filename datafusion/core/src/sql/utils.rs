@@ -21,13 +21,13 @@ use arrow::datatypes::{DataType, DECIMAL_MAX_PRECISION};
 use sqlparser::ast::Ident;
 
 use crate::logical_plan::ExprVisitable;
-use crate::logical_plan::{Expr, LogicalPlan};
+use crate::logical_plan::{DFSchema, Expr, LogicalPlan};
 use crate::scalar::ScalarValue;
 use crate::{
     error::{DataFusionError, Result},
     logical_plan::{Column, ExpressionVisitor, Recursion},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Collect all deeply nested `Expr::AggregateFunction` and
 /// `Expr::AggregateUDF`. They are returned in order of occurrence (depth
@@ -41,6 +41,22 @@ pub(crate) fn find_aggregate_exprs(exprs: &[Expr]) -> Vec<Expr> {
     })
 }
 
+/// Collect the aggregate expressions nested in the arguments of the aggregate
+/// expressions `aggr_exprs`, e.g. `COUNT(#a)` in `MAX(COUNT(#a))`. They are
+/// returned in order of occurrence (depth first), with duplicates omitted.
+pub(crate) fn find_nested_aggregate_exprs(aggr_exprs: &[Expr]) -> Vec<Expr> {
+    let args = aggr_exprs
+        .iter()
+        .flat_map(|expr| match expr {
+            Expr::AggregateFunction { args, .. } | Expr::AggregateUDF { args, .. } => {
+                args.clone()
+            }
+            _ => vec![],
+        })
+        .collect::<Vec<_>>();
+    find_aggregate_exprs(&args)
+}
+
 /// Collect all deeply nested `Expr::Sort`. They are returned in order of occurrence
 /// (depth first), with duplicates omitted.
 pub(crate) fn find_sort_exprs(exprs: &[Expr]) -> Vec<Expr> {
@@ -66,7 +82,7 @@ pub(crate) fn find_column_exprs(exprs: &[Expr]) -> Vec<Expr> {
 /// Search the provided `Expr`'s, and all of their nested `Expr`, for any that
 /// pass the provided test. The returned `Expr`'s are deduplicated and returned
 /// in order of appearance (depth first).
-fn find_exprs_in_exprs<F>(exprs: &[Expr], test_fn: &F) -> Vec<Expr>
+pub(crate) fn find_exprs_in_exprs<F>(exprs: &[Expr], test_fn: &F) -> Vec<Expr>
 where
     F: Fn(&Expr) -> bool,
 {
@@ -170,6 +186,66 @@ pub(crate) fn rebase_expr(
     })
 }
 
+/// Replaces the occurrences of the expressions `exprs` in `expr` by the
+/// `replacements` at the same positions.
+pub(crate) fn replace_exprs(
+    expr: &Expr,
+    exprs: &[Expr],
+    replacements: &[Expr],
+) -> Result<Expr> {
+    clone_with_replacement(expr, &|nested_expr| {
+        Ok(exprs
+            .iter()
+            .position(|expr| expr == nested_expr)
+            .map(|index| replacements[index].clone()))
+    })
+}
+
+/// Returns the grouping expressions, followed by the columns of the input
+/// referenced by `exprs` outside of the aggregate expressions `aggr_exprs`
+/// that are functionally dependent on the grouping columns.
+///
+/// For example, in `SELECT id, name FROM t GROUP BY id` where `id` is the
+/// primary key of `t`, `name` is determined by `id` and can be grouped by
+/// as well without changing the groups.
+pub(crate) fn add_functionally_dependent_columns(
+    mut group_by_exprs: Vec<Expr>,
+    aggr_exprs: &[Expr],
+    exprs: &[Expr],
+    plan: &LogicalPlan,
+) -> Result<Vec<Expr>> {
+    let schema = plan.schema();
+    let functional_dependencies = schema.functional_dependencies();
+    if functional_dependencies.is_empty() {
+        return Ok(group_by_exprs);
+    }
+
+    let mut group_indices = group_by_exprs
+        .iter()
+        .filter_map(|expr| match expr {
+            Expr::Column(column) => schema.index_of_column(column).ok(),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let determined = functional_dependencies.determined_by(&group_indices);
+
+    let exprs = exprs
+        .iter()
+        .map(|expr| rebase_expr(expr, aggr_exprs, plan))
+        .collect::<Result<Vec<_>>>()?;
+    for expr in find_column_exprs(&exprs) {
+        if let Expr::Column(column) = &expr {
+            if let Ok(index) = schema.index_of_column(column) {
+                if determined.contains(&index) && !group_indices.contains(&index) {
+                    group_indices.push(index);
+                    group_by_exprs.push(expr);
+                }
+            }
+        }
+    }
+    Ok(group_by_exprs)
+}
+
 /// Determines if the set of `Expr`'s are a valid projection on the input
 /// `Expr::Column`'s.
 pub(crate) fn can_columns_satisfy_exprs(
@@ -395,6 +471,61 @@ pub(crate) fn extract_aliases(exprs: &[Expr]) -> HashMap<String, Expr> {
         .collect::<HashMap<String, Expr>>()
 }
 
+/// Aliases the expressions whose names are equal to the name of a previous
+/// expression, so that they can be projected together: the expression is
+/// aliased `<name>_<n>`, with the smallest `n` that makes its name unique, and
+/// `<name>` the name of the column for columns.
+///
+/// For example, `SELECT age, age, MIN(age), MIN(age)` is planned as
+/// `SELECT age, age AS age_1, MIN(age), MIN(age) AS "MIN(person.age)_1"`.
+pub(crate) fn alias_duplicate_names(
+    exprs: Vec<Expr>,
+    input_schema: &DFSchema,
+) -> Result<Vec<Expr>> {
+    let names = exprs
+        .iter()
+        .map(|expr| expr.name(input_schema))
+        .collect::<Result<Vec<_>>>()?;
+    // the names of the columns are also taken, so that no alias is equal to
+    // the unqualified name of a qualified column
+    let mut taken = names
+        .iter()
+        .cloned()
+        .chain(exprs.iter().filter_map(|expr| match expr {
+            Expr::Column(column) => Some(column.name.clone()),
+            _ => None,
+        }))
+        .collect::<HashSet<_>>();
+    let mut seen = HashSet::new();
+    exprs
+        .into_iter()
+        .zip(names)
+        .map(|(expr, name)| {
+            if seen.insert(name) {
+                return Ok(expr);
+            }
+            let (expr, base) = match expr {
+                Expr::Alias(expr, alias) => (*expr, alias),
+                Expr::Column(column) => {
+                    let base = column.name.clone();
+                    (Expr::Column(column), base)
+                }
+                expr => {
+                    let base = expr.name(input_schema)?;
+                    (expr, base)
+                }
+            };
+            let alias = (1..)
+                .map(|n| format!("{}_{}", base, n))
+                .find(|alias| !taken.contains(alias))
+                .unwrap();
+            taken.insert(alias.clone());
+            seen.insert(alias.clone());
+            Ok(expr.alias(&alias))
+        })
+        .collect()
+}
+
 /// Given an expression that's literal int encoding position, lookup the corresponding expression
 /// in the select_exprs list, if the index is within the bounds and it is indeed a position literal;
 /// Otherwise, return None
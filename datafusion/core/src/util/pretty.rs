@@ -0,0 +1,468 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Rendering of [`RecordBatch`]es as ASCII tables, Markdown tables or HTML
+//! tables, with a limited number of rows and width of the values.
+//!
+//! ```
+//! # use datafusion::arrow::array::Int32Array;
+//! # use datafusion::arrow::datatypes::{DataType, Field, Schema};
+//! # use datafusion::arrow::record_batch::RecordBatch;
+//! # use datafusion::error::Result;
+//! # use datafusion::util::pretty::{pretty_format_batches, DisplayFormat, DisplayOptions};
+//! # use std::sync::Arc;
+//! # fn main() -> Result<()> {
+//! let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+//! let batch = RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1, 2, 3]))])?;
+//!
+//! let options = DisplayOptions::new()
+//!     .with_format(DisplayFormat::Markdown)
+//!     .with_max_rows(2);
+//! assert_eq!(
+//!     pretty_format_batches(&[batch], &options)?,
+//!     "| a |\n| --- |\n| 1 |\n| 2 |\n| ... |\n"
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::Write;
+
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+
+use crate::error::Result;
+
+/// The value shown in place of the omitted rows, and at the end of the
+/// truncated values
+const ELLIPSIS: &str = "...";
+
+/// The output format of [`pretty_format_batches`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayFormat {
+    /// An ASCII table, as printed by [`arrow::util::pretty`]
+    Table,
+    /// A Markdown (GitHub flavored) table
+    Markdown,
+    /// An HTML `<table>`
+    Html,
+}
+
+/// Which rows are shown when there are more than the maximum number of rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TruncatePolicy {
+    /// The first rows
+    Head,
+    /// The first and the last rows, half of each
+    HeadAndTail,
+}
+
+/// Options of [`pretty_format_batches`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayOptions {
+    format: DisplayFormat,
+    max_rows: Option<usize>,
+    max_width: Option<usize>,
+    truncate: TruncatePolicy,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            format: DisplayFormat::Table,
+            max_rows: None,
+            max_width: None,
+            truncate: TruncatePolicy::Head,
+        }
+    }
+}
+
+impl DisplayOptions {
+    /// All the rows and values in full, as an ASCII table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the output format
+    pub fn with_format(mut self, format: DisplayFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Sets the maximum number of rows shown, the omitted rows being
+    /// replaced by a row of `...`
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+
+    /// Sets the maximum number of characters of the values, the longer
+    /// values being cut and ended by `...`
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Sets which rows are shown when there are more than the maximum
+    pub fn with_truncate(mut self, truncate: TruncatePolicy) -> Self {
+        self.truncate = truncate;
+        self
+    }
+
+    /// Returns the output format
+    pub fn format(&self) -> DisplayFormat {
+        self.format
+    }
+
+    /// Returns the maximum number of rows shown, if any
+    pub fn max_rows(&self) -> Option<usize> {
+        self.max_rows
+    }
+
+    /// Returns the maximum number of characters of the values, if any
+    pub fn max_width(&self) -> Option<usize> {
+        self.max_width
+    }
+
+    /// Returns which rows are shown when there are more than the maximum
+    pub fn truncate(&self) -> TruncatePolicy {
+        self.truncate
+    }
+}
+
+/// Renders the `batches` with the given `options`. The column names are
+/// the ones of the first batch, and nothing is rendered without batches.
+pub fn pretty_format_batches(
+    batches: &[RecordBatch],
+    options: &DisplayOptions,
+) -> Result<String> {
+    let schema = match batches.first() {
+        Some(batch) => batch.schema(),
+        None => return Ok(String::new()),
+    };
+    let header = schema
+        .fields()
+        .iter()
+        .map(|field| truncate_value(field.name().clone(), options.max_width))
+        .collect::<Vec<_>>();
+
+    // the shown rows, `None` standing for the omitted ones
+    let num_rows = batches.iter().map(|batch| batch.num_rows()).sum::<usize>();
+    let (head, tail) = match options.max_rows {
+        Some(max_rows) if max_rows < num_rows => match options.truncate {
+            TruncatePolicy::Head => (max_rows, 0),
+            TruncatePolicy::HeadAndTail => (max_rows - max_rows / 2, max_rows / 2),
+        },
+        _ => (num_rows, 0),
+    };
+    let mut rows = Vec::with_capacity(head + tail + 1);
+    let mut offset = 0;
+    for batch in batches {
+        for row in 0..batch.num_rows() {
+            let index = offset + row;
+            if index == head && tail > 0 {
+                rows.push(None);
+            }
+            if index < head || index >= num_rows - tail {
+                let values = batch
+                    .columns()
+                    .iter()
+                    .map(|column| {
+                        Ok(truncate_value(
+                            array_value_to_string(column, row)?,
+                            options.max_width,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                rows.push(Some(values));
+            }
+        }
+        offset += batch.num_rows();
+    }
+    if head < num_rows && tail == 0 {
+        rows.push(None);
+    }
+
+    Ok(match options.format {
+        DisplayFormat::Table => format_table(&header, &rows),
+        DisplayFormat::Markdown => format_markdown(&header, &rows),
+        DisplayFormat::Html => format_html(&header, &rows),
+    })
+}
+
+/// Renders the `batches` with the given `options` to stdout
+pub fn print_batches(batches: &[RecordBatch], options: &DisplayOptions) -> Result<()> {
+    print!("{}", pretty_format_batches(batches, options)?);
+    Ok(())
+}
+
+/// Cuts `value` to `max_width` characters, ending it by `...`
+fn truncate_value(value: String, max_width: Option<usize>) -> String {
+    match max_width {
+        Some(max_width) if value.chars().count() > max_width => {
+            let kept = max_width.saturating_sub(ELLIPSIS.len());
+            let mut truncated = value.chars().take(kept).collect::<String>();
+            truncated.push_str(&ELLIPSIS[..max_width.min(ELLIPSIS.len())]);
+            truncated
+        }
+        _ => value,
+    }
+}
+
+fn format_table(header: &[String], rows: &[Option<Vec<String>>]) -> String {
+    let mut widths = header
+        .iter()
+        .map(|name| name.chars().count())
+        .collect::<Vec<_>>();
+    for values in rows.iter().flatten() {
+        for (width, value) in widths.iter_mut().zip(values) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+    let omitted = vec![ELLIPSIS; widths.len()];
+    if rows.iter().any(|row| row.is_none()) {
+        for width in widths.iter_mut() {
+            *width = (*width).max(ELLIPSIS.len());
+        }
+    }
+
+    let mut separator = String::from("+");
+    for width in &widths {
+        separator.push_str(&"-".repeat(width + 2));
+        separator.push('+');
+    }
+    separator.push('\n');
+    let format_row = |output: &mut String, values: &[&str]| {
+        output.push('|');
+        for (value, width) in values.iter().zip(&widths) {
+            // `{:width$}` pads by characters
+            write!(output, " {:width$} |", value, width = width).unwrap();
+        }
+        output.push('\n');
+    };
+
+    let mut output = separator.clone();
+    format_row(
+        &mut output,
+        &header.iter().map(|name| name.as_str()).collect::<Vec<_>>(),
+    );
+    output.push_str(&separator);
+    for row in rows {
+        match row {
+            Some(values) => format_row(
+                &mut output,
+                &values
+                    .iter()
+                    .map(|value| value.as_str())
+                    .collect::<Vec<_>>(),
+            ),
+            None => format_row(&mut output, &omitted),
+        }
+    }
+    output.push_str(&separator);
+    output
+}
+
+fn format_markdown(header: &[String], rows: &[Option<Vec<String>>]) -> String {
+    let escape = |value: &str| value.replace('|', "\\|").replace('\n', "<br>");
+    let format_row = |output: &mut String, values: Vec<String>| {
+        output.push_str("| ");
+        output.push_str(&values.join(" | "));
+        output.push_str(" |\n");
+    };
+
+    let mut output = String::new();
+    format_row(
+        &mut output,
+        header.iter().map(|name| escape(name)).collect(),
+    );
+    format_row(&mut output, vec!["---".to_owned(); header.len()]);
+    for row in rows {
+        match row {
+            Some(values) => {
+                format_row(&mut output, values.iter().map(|v| escape(v)).collect())
+            }
+            None => format_row(&mut output, vec![ELLIPSIS.to_owned(); header.len()]),
+        }
+    }
+    output
+}
+
+fn format_html(header: &[String], rows: &[Option<Vec<String>>]) -> String {
+    let escape = |value: &str| {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    };
+    let format_row = |output: &mut String, tag: &str, values: Vec<String>| {
+        output.push_str("<tr>");
+        for value in values {
+            write!(output, "<{}>{}</{}>", tag, value, tag).unwrap();
+        }
+        output.push_str("</tr>\n");
+    };
+
+    let mut output = String::from("<table>\n<thead>\n");
+    format_row(
+        &mut output,
+        "th",
+        header.iter().map(|name| escape(name)).collect(),
+    );
+    output.push_str("</thead>\n<tbody>\n");
+    for row in rows {
+        match row {
+            Some(values) => format_row(
+                &mut output,
+                "td",
+                values.iter().map(|v| escape(v)).collect(),
+            ),
+            None => {
+                format_row(&mut output, "td", vec![ELLIPSIS.to_owned(); header.len()])
+            }
+        }
+    }
+    output.push_str("</tbody>\n</table>\n");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    /// Two batches of the rows (1, "one") to (5, "five")
+    fn batches() -> Result<Vec<RecordBatch>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = |ids: Vec<i32>, names: Vec<Option<&str>>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(Int32Array::from(ids)),
+                    Arc::new(StringArray::from(names)),
+                ],
+            )
+        };
+        Ok(vec![
+            batch(vec![1, 2], vec![Some("one"), Some("two")])?,
+            batch(
+                vec![3, 4, 5],
+                vec![Some("three"), None, Some("five <b>|</b>")],
+            )?,
+        ])
+    }
+
+    #[test]
+    fn format_table() -> Result<()> {
+        let batches = batches()?;
+        let formatted = pretty_format_batches(&batches, &DisplayOptions::new())?;
+        // the same output as arrow
+        assert_eq!(
+            formatted,
+            arrow::util::pretty::pretty_format_batches(&batches)?.to_string() + "\n"
+        );
+
+        let options = DisplayOptions::new()
+            .with_max_rows(3)
+            .with_max_width(6)
+            .with_truncate(TruncatePolicy::HeadAndTail);
+        let expected = vec![
+            "+-----+--------+",
+            "| id  | name   |",
+            "+-----+--------+",
+            "| 1   | one    |",
+            "| 2   | two    |",
+            "| ... | ...    |",
+            "| 5   | fiv... |",
+            "+-----+--------+",
+            "",
+        ];
+        assert_eq!(
+            pretty_format_batches(&batches, &options)?,
+            expected.join("\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn format_markdown() -> Result<()> {
+        let options = DisplayOptions::new()
+            .with_format(DisplayFormat::Markdown)
+            .with_max_rows(2);
+        let expected = vec![
+            "| id | name |",
+            "| --- | --- |",
+            "| 1 | one |",
+            "| 2 | two |",
+            "| ... | ... |",
+            "",
+        ];
+        assert_eq!(
+            pretty_format_batches(&batches()?, &options)?,
+            expected.join("\n")
+        );
+
+        let options = DisplayOptions::new()
+            .with_format(DisplayFormat::Markdown)
+            .with_max_rows(1)
+            .with_truncate(TruncatePolicy::HeadAndTail);
+        let expected = vec![
+            "| id | name |",
+            "| --- | --- |",
+            "| 1 | one |",
+            "| ... | ... |",
+            "",
+        ];
+        assert_eq!(
+            pretty_format_batches(&batches()?, &options)?,
+            expected.join("\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn format_html() -> Result<()> {
+        let options = DisplayOptions::new().with_format(DisplayFormat::Html);
+        let expected = vec![
+            "<table>",
+            "<thead>",
+            "<tr><th>id</th><th>name</th></tr>",
+            "</thead>",
+            "<tbody>",
+            "<tr><td>1</td><td>one</td></tr>",
+            "<tr><td>2</td><td>two</td></tr>",
+            "<tr><td>3</td><td>three</td></tr>",
+            "<tr><td>4</td><td></td></tr>",
+            "<tr><td>5</td><td>five &lt;b&gt;|&lt;/b&gt;</td></tr>",
+            "</tbody>",
+            "</table>",
+            "",
+        ];
+        assert_eq!(
+            pretty_format_batches(&batches()?, &options)?,
+            expected.join("\n")
+        );
+        assert_eq!(pretty_format_batches(&[], &options)?, "");
+        Ok(())
+    }
+}
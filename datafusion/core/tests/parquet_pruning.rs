@@ -53,9 +53,35 @@ async fn prune_timestamps_nanos() {
     // This should prune one metrics without error
     assert_eq!(output.predicate_evaluation_errors(), Some(0));
     assert_eq!(output.row_groups_pruned(), Some(1));
+    assert_eq!(output.row_groups_matched(), Some(3));
+    assert_eq!(output.rows_pruned(), Some(5));
+    assert_eq!(output.files_scanned(), Some(1));
+    assert_eq!(output.files_pruned(), Some(0));
+    assert_eq!(output.files_listed(), Some(1));
+    assert_eq!(output.files_pruned_by_listing(), Some(0));
+    // the rows of the row groups that were not pruned, before filtering
+    assert_eq!(output.output_rows(), Some(15));
     assert_eq!(output.result_rows, 10, "{}", output.description());
 }
 
+#[tokio::test]
+async fn prune_timestamps_whole_file() {
+    let output = ContextWithParquet::new(Scenario::Timestamps)
+        .await
+        .query("SELECT * FROM t where nanos > to_timestamp('2030-01-01T00:00:00Z')")
+        .await;
+    println!("{}", output.description());
+    // All the row groups, and thus the file, should be pruned
+    assert_eq!(output.predicate_evaluation_errors(), Some(0));
+    assert_eq!(output.row_groups_pruned(), Some(4));
+    assert_eq!(output.row_groups_matched(), Some(0));
+    assert_eq!(output.rows_pruned(), Some(20));
+    assert_eq!(output.files_scanned(), Some(0));
+    assert_eq!(output.files_pruned(), Some(1));
+    assert_eq!(output.output_rows(), Some(0));
+    assert_eq!(output.result_rows, 0, "{}", output.description());
+}
+
 #[tokio::test]
 async fn prune_timestamps_micros() {
     let output = ContextWithParquet::new(Scenario::Timestamps)
@@ -269,33 +295,33 @@ async fn prune_int32_scalar_fun() {
 
 #[tokio::test]
 async fn prune_int32_complex_expr() {
-    // resulrt of sql "SELECT * FROM t where i+1 = 1" is not supported
+    // resulrt of sql "SELECT * FROM t where i+1 = 1" is same as
+    // "SELECT * FROM t where i = 1 - 1"
     let output = ContextWithParquet::new(Scenario::Int32)
         .await
         .query("SELECT * FROM t where i+1 = 1")
         .await;
 
     println!("{}", output.description());
-    // This should prune out groups with error, because there is not col to
-    // prune the row groups.
-    assert_eq!(output.predicate_evaluation_errors(), Some(4));
-    assert_eq!(output.row_groups_pruned(), Some(0));
+    // This should prune out groups without error
+    assert_eq!(output.predicate_evaluation_errors(), Some(0));
+    assert_eq!(output.row_groups_pruned(), Some(2));
     assert_eq!(output.result_rows, 2, "{}", output.description());
 }
 
 #[tokio::test]
 async fn prune_int32_complex_expr_subtract() {
-    // resulrt of sql "SELECT * FROM t where 1-i > 1" is not supported
+    // resulrt of sql "SELECT * FROM t where 1-i > 1" is same as
+    // "SELECT * FROM t where i < 1 - 1"
     let output = ContextWithParquet::new(Scenario::Int32)
         .await
         .query("SELECT * FROM t where 1-i > 1")
         .await;
 
     println!("{}", output.description());
-    // This should prune out groups with error, because there is not col to
-    // prune the row groups.
-    assert_eq!(output.predicate_evaluation_errors(), Some(4));
-    assert_eq!(output.row_groups_pruned(), Some(0));
+    // This should prune out groups without error
+    assert_eq!(output.predicate_evaluation_errors(), Some(0));
+    assert_eq!(output.row_groups_pruned(), Some(2));
     assert_eq!(output.result_rows, 9, "{}", output.description());
 }
 
@@ -459,6 +485,41 @@ impl TestOutput {
         self.metric_value("row_groups_pruned")
     }
 
+    /// The number of row groups read
+    fn row_groups_matched(&self) -> Option<usize> {
+        self.metric_value("row_groups_matched")
+    }
+
+    /// The number of rows of the pruned row groups
+    fn rows_pruned(&self) -> Option<usize> {
+        self.metric_value("rows_pruned")
+    }
+
+    /// The number of files read
+    fn files_scanned(&self) -> Option<usize> {
+        self.metric_value("files_scanned")
+    }
+
+    /// The number of files whose row groups were all pruned
+    fn files_pruned(&self) -> Option<usize> {
+        self.metric_value("files_pruned")
+    }
+
+    /// The number of files listed by the table
+    fn files_listed(&self) -> Option<usize> {
+        self.metric_value("files_listed")
+    }
+
+    /// The number of listed files pruned before the scan
+    fn files_pruned_by_listing(&self) -> Option<usize> {
+        self.metric_value("files_pruned_by_listing")
+    }
+
+    /// The number of rows read from the row groups that were not pruned
+    fn output_rows(&self) -> Option<usize> {
+        self.metric_value("output_rows")
+    }
+
     fn description(&self) -> String {
         format!(
             "Input:\n{}\nQuery:\n{}\nOutput:\n{}\nMetrics:\n{}",
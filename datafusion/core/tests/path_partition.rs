@@ -31,7 +31,10 @@ use datafusion::{
     },
     datasource::{
         file_format::{csv::CsvFormat, parquet::ParquetFormat},
-        listing::{ListingOptions, ListingTable, ListingTableConfig},
+        listing::{
+            ListingOptions, ListingTable, ListingTableConfig, PartitionValueExtractor,
+            RegexPartitioning,
+        },
     },
     error::Result,
     physical_plan::ColumnStatistics,
@@ -277,6 +280,84 @@ async fn csv_grouping_by_partition() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn csv_grouping_by_regex_partition() -> Result<()> {
+    let ctx = SessionContext::new();
+
+    let partitioning = RegexPartitioning::try_new(
+        r"data-(?P<year>\d{4})-(?P<month>\d{2})-(?P<day>\d{2})\.csv$",
+    )?;
+    register_aggregate_csv_with_partitioning(
+        &ctx,
+        &[
+            "mytable/data-2021-10-26.csv",
+            "mytable/data-2021-10-27.csv",
+            "mytable/archive/data-2021-11-01.csv",
+            "mytable/README.csv",
+        ],
+        &["year", "month", "day"],
+        "mytable",
+        Arc::new(partitioning),
+    )?;
+
+    let result = ctx
+        .sql("SELECT month, count(*), count(distinct(day)) FROM t WHERE year = '2021' AND day <> '27' GROUP BY month")
+        .await?
+        .collect()
+        .await?;
+
+    let expected = vec![
+        "+-------+-----------------+-----------------------+",
+        "| month | COUNT(UInt8(1)) | COUNT(DISTINCT t.day) |",
+        "+-------+-----------------+-----------------------+",
+        "| 10    | 100             | 1                     |",
+        "| 11    | 100             | 1                     |",
+        "+-------+-----------------+-----------------------+",
+    ];
+    assert_batches_sorted_eq!(expected, &result);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn csv_projection_on_closure_partition() -> Result<()> {
+    let ctx = SessionContext::new();
+
+    // the date is the name of the file
+    let partitioning = |path: &str| {
+        path.strip_suffix(".csv")
+            .map(|date| vec![date.replace('_', "-")])
+    };
+    register_aggregate_csv_with_partitioning(
+        &ctx,
+        &["mytable/2021_10_26.csv", "mytable/2021_10_27.csv"],
+        &["date"],
+        "mytable",
+        Arc::new(partitioning),
+    )?;
+
+    let result = ctx
+        .sql("SELECT c1, date FROM t WHERE date='2021-10-27' LIMIT 5")
+        .await?
+        .collect()
+        .await?;
+
+    let expected = vec![
+        "+----+------------+",
+        "| c1 | date       |",
+        "+----+------------+",
+        "| a  | 2021-10-27 |",
+        "| b  | 2021-10-27 |",
+        "| b  | 2021-10-27 |",
+        "| c  | 2021-10-27 |",
+        "| d  | 2021-10-27 |",
+        "+----+------------+",
+    ];
+    assert_batches_sorted_eq!(expected, &result);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn parquet_multiple_partitions() -> Result<()> {
     let ctx = SessionContext::new();
@@ -428,6 +509,30 @@ fn register_partitioned_aggregate_csv(
         .expect("registering listing table failed");
 }
 
+fn register_aggregate_csv_with_partitioning(
+    ctx: &SessionContext,
+    store_paths: &[&str],
+    partition_cols: &[&str],
+    table_path: &str,
+    partitioning: Arc<dyn PartitionValueExtractor>,
+) -> Result<()> {
+    let testdata = arrow_test_data();
+    let csv_file_path = format!("{}/csv/aggregate_test_100.csv", testdata);
+    let file_schema = test_util::aggr_test_schema();
+    let object_store = MirroringObjectStore::new_arc(csv_file_path, store_paths);
+
+    let mut options = ListingOptions::new(Arc::new(CsvFormat::default()));
+    options.table_partition_cols = partition_cols.iter().map(|&s| s.to_owned()).collect();
+
+    let config = ListingTableConfig::new(object_store, table_path)
+        .with_listing_options(options)
+        .with_schema(file_schema);
+    let table = ListingTable::try_new(config)?.with_partitioning(partitioning)?;
+
+    ctx.register_table("t", Arc::new(table))?;
+    Ok(())
+}
+
 async fn register_partitioned_alltypes_parquet(
     ctx: &SessionContext,
     store_paths: &[&str],
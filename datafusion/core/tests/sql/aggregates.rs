@@ -51,6 +51,25 @@ async fn csv_query_avg() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn csv_query_min_max_boolean() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_aggregate_csv(&ctx).await?;
+    let sql = "SELECT min(c2 > 0) AS all_positive, max(c2 > 0) AS any_positive, \
+               min(c2 > 3) AS all_gt_3, max(c2 > 5) AS any_gt_5 \
+               FROM aggregate_test_100";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+--------------+--------------+----------+----------+",
+        "| all_positive | any_positive | all_gt_3 | any_gt_5 |",
+        "+--------------+--------------+----------+----------+",
+        "| true         | true         | false    | false    |",
+        "+--------------+--------------+----------+----------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn csv_query_covariance_1() -> Result<()> {
     let ctx = SessionContext::new();
@@ -387,6 +406,95 @@ async fn csv_query_approx_count() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn csv_query_hll_sketches() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_aggregate_csv(&ctx).await?;
+    // roll up pre-aggregated sketches, which must match approx_distinct(c9)
+    let sql = "SELECT hll_count(sketch) count_c9 \
+               FROM (SELECT c1, hll_sketch(c9) sketch FROM aggregate_test_100 GROUP BY c1) t";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----------+",
+        "| count_c9 |",
+        "+----------+",
+        "| 100      |",
+        "+----------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // unions of sketches are sketches too
+    let sql = "SELECT hll_count(sketch) count_c9 \
+               FROM (SELECT c1, hll_union(sketch) sketch \
+                     FROM (SELECT c1, c2, hll_sketch(c9) sketch FROM aggregate_test_100 GROUP BY c1, c2) t1 \
+                     GROUP BY c1) t2";
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_approx_top_k() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "SELECT approx_top_k(column1, 2) AS top \
+               FROM (VALUES ('a'), ('b'), ('a'), (NULL), ('c'), ('b'), ('a')) AS t";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let column = actual[0].column(0);
+    assert_eq!(column.len(), 1);
+
+    let fields = vec![
+        Field::new("value", DataType::Utf8, true),
+        Field::new("count", DataType::UInt64, true),
+    ];
+    let item = |value: &str, count: u64| {
+        ScalarValue::Struct(
+            Some(Box::new(vec![
+                ScalarValue::from(value),
+                ScalarValue::UInt64(Some(count)),
+            ])),
+            Box::new(fields.clone()),
+        )
+    };
+    let expected = ScalarValue::List(
+        Some(Box::new(vec![item("a", 3), item("b", 2)])),
+        Box::new(DataType::Struct(fields.clone())),
+    );
+    assert_eq!(ScalarValue::try_from_array(column, 0)?, expected);
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_histogram() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "SELECT histogram(column1, 2) AS hist \
+               FROM (VALUES (1), (2), (NULL), (2), (10), (11)) AS t";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let column = actual[0].column(0);
+    assert_eq!(column.len(), 1);
+
+    let fields = vec![
+        Field::new("lower", DataType::Float64, true),
+        Field::new("upper", DataType::Float64, true),
+        Field::new("count", DataType::UInt64, true),
+    ];
+    let bin = |lower: f64, upper: f64, count: u64| {
+        ScalarValue::Struct(
+            Some(Box::new(vec![
+                ScalarValue::from(lower),
+                ScalarValue::from(upper),
+                ScalarValue::UInt64(Some(count)),
+            ])),
+            Box::new(fields.clone()),
+        )
+    };
+    let expected = ScalarValue::List(
+        Some(Box::new(vec![bin(1.0, 2.0, 3), bin(10.0, 11.0, 2)])),
+        Box::new(DataType::Struct(fields.clone())),
+    );
+    assert_eq!(ScalarValue::try_from_array(column, 0)?, expected);
+    Ok(())
+}
+
 // This test executes the APPROX_PERCENTILE_CONT aggregation against the test
 // data, asserting the estimated quantiles are ±5% their actual values.
 //
@@ -804,3 +912,73 @@ async fn aggregate_timestamps_avg() -> Result<()> {
     assert_eq!(results.to_string(), "Error during planning: The function Avg does not support inputs of type Timestamp(Nanosecond, None).");
     Ok(())
 }
+
+#[tokio::test]
+async fn group_by_primary_key() -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("value", DataType::Int32, false),
+    ]));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            Arc::new(Int32Array::from_slice(&[1, 2, 3])),
+            Arc::new(StringArray::from_slice(&["a", "b", "a"])),
+            Arc::new(Int32Array::from_slice(&[10, 20, 30])),
+        ],
+    )?;
+    let table =
+        MemTable::try_new(schema, vec![vec![batch]])?.with_primary_key(vec![0])?;
+    let ctx = SessionContext::new();
+    ctx.register_table("t", Arc::new(table))?;
+
+    // name is determined by the primary key id
+    let sql = "SELECT id, name, sum(value) FROM t GROUP BY id HAVING name = 'a'";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+------+--------------+",
+        "| id | name | SUM(t.value) |",
+        "+----+------+--------------+",
+        "| 1  | a    | 10           |",
+        "| 3  | a    | 30           |",
+        "+----+------+--------------+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+
+    // but not by name
+    let sql = "SELECT id, name FROM t GROUP BY name";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: Projection references non-aggregate values"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn nested_aggregates() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "SELECT MAX(COUNT(*)) AS max_count, MIN(SUM(column1)) AS min_sum \
+               FROM (VALUES (1, 'a'), (2, 'a'), (5, 'b'), (7, 'c'), (8, 'c')) AS t \
+               GROUP BY column2 HAVING SUM(column1) > 4";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----------+---------+",
+        "| max_count | min_sum |",
+        "+-----------+---------+",
+        "| 2         | 5       |",
+        "+-----------+---------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the groups are aggregated into a single row
+    let sql = "SELECT column2, MAX(COUNT(*)) \
+               FROM (VALUES (1, 'a'), (2, 'b')) AS t GROUP BY column2";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "must appear in an aggregate function, as the nested aggregate functions aggregate all the groups"
+    );
+    Ok(())
+}
@@ -45,6 +45,81 @@ async fn create_table_as() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn create_table_as_with_columns() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "CREATE TABLE t (a BIGINT, b VARCHAR NULL, c TINYINT) AS \
+               SELECT column1, column2, 1 FROM (VALUES (1, 'x'), (2, NULL))";
+    ctx.sql(sql).await?;
+
+    // the columns of the query are cast to the declared columns
+    let table = ctx.table("t")?;
+    let types = table
+        .schema()
+        .fields()
+        .iter()
+        .map(|field| field.data_type().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(types, vec![DataType::Int64, DataType::Utf8, DataType::Int8]);
+    let actual = execute_to_batches(&ctx, "SELECT * FROM t ORDER BY a").await;
+    let expected = vec![
+        "+---+---+---+",
+        "| a | b | c |",
+        "+---+---+---+",
+        "| 1 | x | 1 |",
+        "| 2 |   | 1 |",
+        "+---+---+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the NULLs written to columns not declared NULL are rejected
+    let err = ctx
+        .sql("CREATE TABLE u (a VARCHAR) AS SELECT b FROM t")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Verification of"), "{}", err);
+
+    // the casts losing data must be explicit
+    let err = ctx
+        .sql("CREATE TABLE u (a SMALLINT) AS SELECT a FROM t")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: Cannot write column a of type Int64 to column a \
+         of type Int16 without losing data, cast it explicitly"
+    );
+    ctx.sql("CREATE TABLE u (a SMALLINT) AS SELECT CAST(a AS SMALLINT) FROM t")
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn select_into() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_aggregate_simple_csv(&ctx).await?;
+
+    let results = ctx
+        .sql_multi(
+            "SELECT c1, c3 INTO TEMP my_table FROM aggregate_simple WHERE c3; \
+             SELECT count(*) AS cnt FROM my_table",
+        )
+        .await?;
+    let expected = vec!["+-----+", "| cnt |", "+-----+", "| 9   |", "+-----+"];
+    assert_batches_eq!(expected, &results[1]);
+
+    // the table has the columns of the query
+    let table = ctx.table("my_table")?;
+    assert_eq!(table.schema().fields().len(), 2);
+
+    let err = ctx
+        .sql("SELECT c1 INTO my_table FROM aggregate_simple")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("already exists"), "{}", err);
+    Ok(())
+}
+
 #[tokio::test]
 async fn drop_table() -> Result<()> {
     let ctx = SessionContext::new();
@@ -65,6 +140,100 @@ async fn drop_table() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn delete_and_update_memory_table() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "CREATE TABLE t AS SELECT column1 AS a, column2 AS b \
+               FROM (VALUES (1, 'x'), (2, 'y'), (3, 'z'))";
+    ctx.sql(sql).await?;
+
+    let actual =
+        execute_to_batches(&ctx, "UPDATE t SET b = 'v', a = a * 10 WHERE a > 1").await;
+    let expected = vec![
+        "+-------+",
+        "| count |",
+        "+-------+",
+        "| 2     |",
+        "+-------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    let actual = execute_to_batches(&ctx, "DELETE FROM t WHERE b = 'x'").await;
+    let expected = vec![
+        "+-------+",
+        "| count |",
+        "+-------+",
+        "| 1     |",
+        "+-------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let actual = execute_to_batches(&ctx, "SELECT * FROM t ORDER BY a").await;
+    let expected = vec![
+        "+----+---+",
+        "| a  | b |",
+        "+----+---+",
+        "| 20 | v |",
+        "| 30 | v |",
+        "+----+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let err = ctx.sql("UPDATE t SET c = 1").await.unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Table 't' has no column named 'c'"),
+        "{}",
+        err
+    );
+    let err = ctx.sql("UPDATE t SET a = 1, a = 2").await.unwrap_err();
+    assert!(
+        err.to_string().contains("is updated more than once"),
+        "{}",
+        err
+    );
+    let err = ctx.sql("DELETE FROM t WHERE a").await.unwrap_err();
+    assert!(
+        err.to_string().contains("must be a boolean expression"),
+        "{}",
+        err
+    );
+    let err = ctx.sql("DELETE FROM missing").await.unwrap_err();
+    assert!(err.to_string().contains("'missing' not found"), "{}", err);
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_and_update_csv_table() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let mut file = std::fs::File::create(tmp_dir.path().join("data.csv"))?;
+    writeln!(file, "a|b\n1|x\n2|y\n3|z")?;
+
+    let ctx = SessionContext::new();
+    let sql = format!(
+        "CREATE EXTERNAL TABLE t STORED AS CSV WITH HEADER ROW DELIMITER '|' LOCATION '{}'",
+        tmp_dir.path().to_str().unwrap()
+    );
+    ctx.sql(&sql).await?;
+
+    ctx.sql("UPDATE t SET b = 'w' WHERE a = 3").await?;
+    ctx.sql("DELETE FROM t WHERE a < 2").await?;
+
+    // the file is rewritten with the options of the table
+    let content = std::fs::read_to_string(tmp_dir.path().join("data.csv"))?;
+    assert_eq!(content, "a|b\n2|y\n3|w\n");
+    let actual = execute_to_batches(&ctx, "SELECT * FROM t ORDER BY a").await;
+    let expected = vec![
+        "+---+---+",
+        "| a | b |",
+        "+---+---+",
+        "| 2 | y |",
+        "| 3 | w |",
+        "+---+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn csv_query_create_external_table() {
     let ctx = SessionContext::new();
@@ -214,3 +383,127 @@ async fn create_pipe_delimited_csv_table() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn create_external_table_with_constraints() -> Result<()> {
+    let ctx = SessionContext::new();
+
+    let data = "1,a,10\n2,b,20\n3,a,30";
+
+    let tmp_dir = TempDir::new().unwrap();
+    let file_path = tmp_dir.path().join("keyed.csv");
+
+    // scope to ensure the file is closed and written
+    {
+        std::fs::File::create(&file_path)
+            .expect("creating temp file")
+            .write_all(data.as_bytes())
+            .expect("writing data");
+    }
+
+    let sql = format!(
+        "CREATE EXTERNAL TABLE keyed (
+                  id INT PRIMARY KEY,
+                  name VARCHAR NOT NULL,
+                  value INT NOT NULL
+              )
+              STORED AS CSV
+              LOCATION '{}'
+              ",
+        file_path.to_str().expect("path is utf8")
+    );
+    plan_and_collect(&ctx, &sql).await?;
+
+    // the groups of the primary key are single rows, no aggregation is needed
+    let sql = "SELECT id, name, max(value) FROM keyed GROUP BY id";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    assert_not_contains!(format!("{:?}", plan), "Aggregate:");
+
+    let result = plan_and_collect(&ctx, sql).await?;
+    let expected = vec![
+        "+----+------+------------------+",
+        "| id | name | MAX(keyed.value) |",
+        "+----+------+------------------+",
+        "| 1  | a    | 10               |",
+        "| 2  | b    | 20               |",
+        "| 3  | a    | 30               |",
+        "+----+------+------------------+",
+    ];
+    assert_batches_sorted_eq!(expected, &result);
+
+    // the rows are already distinct
+    let sql = "SELECT DISTINCT id, name FROM keyed";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    assert_not_contains!(format!("{:?}", plan), "Aggregate:");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_external_table_with_order() -> Result<()> {
+    let ctx = SessionContext::with_config(SessionConfig::new().with_target_partitions(1));
+
+    let tmp_dir = TempDir::new().unwrap();
+    for (name, data) in [("1.csv", "1,a\n3,c\n5,e"), ("2.csv", "2,b\n4,d\n6,f")] {
+        std::fs::File::create(tmp_dir.path().join(name))
+            .expect("creating temp file")
+            .write_all(data.as_bytes())
+            .expect("writing data");
+    }
+
+    let sql = format!(
+        "CREATE EXTERNAL TABLE events (
+                  ts INT NOT NULL,
+                  event VARCHAR NOT NULL
+              )
+              STORED AS CSV
+              WITH ORDER (ts ASC)
+              LOCATION '{}'
+              ",
+        tmp_dir.path().to_str().expect("path is utf8")
+    );
+    plan_and_collect(&ctx, &sql).await?;
+
+    // the sorted files are merged rather than sorted
+    let sql = "SELECT ts, event FROM events ORDER BY ts";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let physical_plan = ctx.create_physical_plan(&plan).await?;
+    let formatted = displayable(physical_plan.as_ref()).indent().to_string();
+    assert_contains!(&formatted, "SortPreservingMergeExec");
+    assert_not_contains!(&formatted, "SortExec");
+
+    let result = collect(physical_plan, ctx.task_ctx()).await?;
+    let expected = vec![
+        "+----+-------+",
+        "| ts | event |",
+        "+----+-------+",
+        "| 1  | a     |",
+        "| 2  | b     |",
+        "| 3  | c     |",
+        "| 4  | d     |",
+        "| 5  | e     |",
+        "| 6  | f     |",
+        "+----+-------+",
+    ];
+    assert_batches_eq!(expected, &result);
+
+    // the files are not sorted in this order
+    let sql = "SELECT event FROM events ORDER BY ts DESC";
+    let plan = ctx.create_logical_plan(sql)?;
+    let plan = ctx.optimize(&plan)?;
+    let physical_plan = ctx.create_physical_plan(&plan).await?;
+    let formatted = displayable(physical_plan.as_ref()).indent().to_string();
+    assert_contains!(&formatted, "SortExec");
+
+    // the files would no longer be sorted
+    let err = ctx.sql("UPDATE events SET ts = 0").await.unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "The column 'ts' of the order of the files can't be updated"
+    );
+
+    Ok(())
+}
@@ -772,6 +772,37 @@ async fn csv_explain() {
     assert_eq!(expected, actual);
 }
 
+#[tokio::test]
+async fn csv_explain_ddl() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_aggregate_csv_by_sql(&ctx).await;
+
+    // the plan of the query creating the table is explained
+    let sql = "EXPLAIN CREATE TABLE t AS SELECT c1 FROM aggregate_test_100 WHERE c2 > 10";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)
+        .unwrap()
+        .to_string();
+    assert_contains!(&formatted, "CreateMemoryTable: \"t\"");
+    assert_contains!(&formatted, "FilterExec: CAST(c2@1 AS Int64) > 10");
+
+    let sql = format!(
+        "EXPLAIN CREATE EXTERNAL TABLE t2 STORED AS CSV WITH HEADER ROW \
+         LOCATION '{}/csv/aggregate_test_100.csv'",
+        datafusion::test_util::arrow_test_data()
+    );
+    let actual = execute_to_batches(&ctx, &sql).await;
+    let formatted = arrow::util::pretty::pretty_format_batches(&actual)
+        .unwrap()
+        .to_string();
+    assert_contains!(&formatted, "CreateExternalTable: \"t2\"");
+
+    // explaining the statements does not create the tables
+    assert!(ctx.table("t").is_err());
+    assert!(ctx.table("t2").is_err());
+    Ok(())
+}
+
 #[tokio::test]
 async fn csv_explain_analyze() {
     // This test uses the execute function to run an actual plan under EXPLAIN ANALYZE
@@ -510,6 +510,18 @@ async fn test_array_literals() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_array_contains() -> Result<()> {
+    test_expression!("array_contains([1, 2, 3], 2)", "true");
+    test_expression!("array_contains(ARRAY[1, 2, 3], 4)", "false");
+    test_expression!("array_contains([1, 2.5, null], 2.5)", "true");
+    test_expression!("array_contains([1, 2.5], 1)", "true");
+    test_expression!("array_contains(['a', null], 'a')", "true");
+    test_expression!("array_contains([1, 2], null)", "NULL");
+    test_expression!("array_contains([[1, 2], [3]], [3])", "true");
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_interval_expressions() -> Result<()> {
     // day nano intervals
@@ -575,11 +587,11 @@ async fn test_interval_expressions() -> Result<()> {
     );
     test_expression!(
         "interval '0.499 day'",
-        "0 years 0 mons 0 days 11 hours 58 mins 33.596 secs"
+        "0 years 0 mons 0 days 11 hours 58 mins 33.600 secs"
     );
     test_expression!(
         "interval '0.4999 day'",
-        "0 years 0 mons 0 days 11 hours 59 mins 51.364 secs"
+        "0 years 0 mons 0 days 11 hours 59 mins 51.360 secs"
     );
     test_expression!(
         "interval '0.49999 day'",
@@ -587,7 +599,7 @@ async fn test_interval_expressions() -> Result<()> {
     );
     test_expression!(
         "interval '0.49999999999 day'",
-        "0 years 0 mons 0 days 12 hours 0 mins 0.00 secs"
+        "0 years 0 mons 0 days 11 hours 59 mins 59.999999136 secs"
     );
     test_expression!(
         "interval '5 day'",
@@ -660,6 +672,23 @@ async fn test_interval_expressions() -> Result<()> {
         "interval '1 year 1 day 1 hour 1 minute 1 second'",
         "0 years 12 mons 1 days 1 hours 1 mins 1.00 secs"
     );
+    test_expression!(
+        "interval '2 weeks 1 day 2 hours'",
+        "0 years 0 mons 15 days 2 hours 0 mins 0.00 secs"
+    );
+    test_expression!(
+        "interval '1 years 2 mons'",
+        "1 years 2 mons 0 days 0 hours 0 mins 0.00 secs"
+    );
+    // ISO 8601
+    test_expression!(
+        "interval 'P1DT2H'",
+        "0 years 0 mons 1 days 2 hours 0 mins 0.00 secs"
+    );
+    test_expression!(
+        "interval 'P1Y2M3DT4H5M6.5S'",
+        "0 years 14 mons 3 days 4 hours 5 mins 6.500000000 secs"
+    );
 
     Ok(())
 }
@@ -814,6 +843,15 @@ async fn test_random_expression() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_width_bucket_expression() -> Result<()> {
+    test_expression!("width_bucket(5.35, 0.024, 10.06, 5)", "3");
+    test_expression!("width_bucket(-1, 0, 10, 5)", "0");
+    test_expression!("width_bucket(10, 0, 10, 5)", "6");
+    test_expression!("width_bucket(2.5, 10, 0, 5)", "4");
+    Ok(())
+}
+
 #[tokio::test]
 async fn case_with_bool_type_result() -> Result<()> {
     let ctx = SessionContext::new();
@@ -902,6 +940,49 @@ async fn test_extract_date_part() -> Result<()> {
         "date_part('second', to_timestamp('2020-09-08T12:00:12+00:00'))",
         "12"
     );
+    test_expression!(
+        "EXTRACT(second FROM to_timestamp('2020-09-08T12:00:12.5+00:00'))",
+        "12.5"
+    );
+    test_expression!(
+        "date_part('millisecond', to_timestamp('2020-09-08T12:00:12.345+00:00'))",
+        "12345"
+    );
+    test_expression!(
+        "date_part('microseconds', to_timestamp_micros('2020-09-08T12:00:12.345678+00:00'))",
+        "12345678"
+    );
+    test_expression!(
+        "date_part('nanosecond', to_timestamp_seconds('2020-09-08T12:00:12+00:00'))",
+        "12000000000"
+    );
+    test_expression!("date_part('millennium', CAST('2000-01-01' AS DATE))", "2");
+    test_expression!("date_part('century', CAST('2000-01-01' AS DATE))", "20");
+    test_expression!("date_part('century', CAST('2001-01-01' AS DATE))", "21");
+    test_expression!("date_part('decade', CAST('2009-01-01' AS DATE))", "200");
+    test_expression!("date_part('quarter', CAST('2000-01-01' AS DATE))", "1");
+    test_expression!(
+        "date_part('QUARTER', to_timestamp('2020-09-08T12:00:00+00:00'))",
+        "3"
+    );
+    test_expression!("date_part('doy', CAST('2000-12-31' AS DATE))", "366");
+    // 2021-01-01 is the Friday of the 53rd ISO week of 2020
+    test_expression!("date_part('isoyear', CAST('2021-01-01' AS DATE))", "2020");
+    test_expression!("date_part('week', CAST('2021-01-01' AS DATE))", "53");
+    test_expression!("date_part('isodow', CAST('2021-01-03' AS DATE))", "7");
+    test_expression!("date_part('dow', CAST('2021-01-03' AS DATE))", "6");
+    test_expression!(
+        "date_part('epoch', CAST('2000-01-02' AS DATE))",
+        "946771200"
+    );
+    test_expression!(
+        "date_part('epoch', to_timestamp_millis('2020-09-08T12:00:12.345+00:00'))",
+        "1599566412.345"
+    );
+    test_expression!(
+        "date_part('epoch', to_timestamp_seconds('1969-12-31T23:59:59+00:00'))",
+        "-1"
+    );
     Ok(())
 }
 
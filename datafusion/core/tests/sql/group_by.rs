@@ -16,6 +16,7 @@
 // under the License.
 
 use super::*;
+use datafusion::physical_plan::hash_aggregate::NullGroupKeys;
 
 #[tokio::test]
 async fn csv_query_group_by_int_min_max() -> Result<()> {
@@ -398,6 +399,140 @@ async fn query_group_on_null_multi_col() -> Result<()> {
     Ok(())
 }
 
+/// Registers the table `test` of an Int32 column and a dictionary column,
+/// whose nulls are either null keys or keys of a null value, in two
+/// partitions
+fn register_dictionary_groups(ctx: &SessionContext) -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("c1", DataType::Int32, true),
+        Field::new(
+            "c2",
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+            true,
+        ),
+    ]));
+    let values = StringArray::from(vec![Some("foo"), None, Some("bar")]);
+    let batch = |c1: Vec<Option<i32>>, keys: Vec<Option<i8>>| -> Result<RecordBatch> {
+        let keys = Int8Array::from(keys);
+        let c2 = DictionaryArray::<Int8Type>::try_new(&keys, &values)?;
+        Ok(RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(c1)), Arc::new(c2)],
+        )?)
+    };
+    let table = MemTable::try_new(
+        schema.clone(),
+        vec![
+            vec![batch(
+                vec![Some(0), Some(0), None, None, Some(3)],
+                vec![None, Some(1), Some(2), None, Some(0)],
+            )?],
+            vec![batch(
+                vec![Some(0), None, Some(3), None, None],
+                vec![Some(1), Some(1), Some(0), Some(2), None],
+            )?],
+        ],
+    )?;
+    ctx.register_table("test", Arc::new(table))?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_group_on_null_dictionary_multi_col() -> Result<()> {
+    for target_partitions in [1, 4] {
+        let ctx = SessionContext::with_config(
+            SessionConfig::new().with_target_partitions(target_partitions),
+        );
+        register_dictionary_groups(&ctx)?;
+        let sql = "SELECT COUNT(*), c1, c2 FROM test GROUP BY c1, c2";
+        let actual = execute_to_batches(&ctx, sql).await;
+        // null keys and keys of a null value are the same group
+        let expected = vec![
+            "+-----------------+----+-----+",
+            "| COUNT(UInt8(1)) | c1 | c2  |",
+            "+-----------------+----+-----+",
+            "| 3               |    |     |",
+            "| 2               |    | bar |",
+            "| 3               | 0  |     |",
+            "| 2               | 3  | foo |",
+            "+-----------------+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &actual);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_drop_null_group_keys() -> Result<()> {
+    let ctx = SessionContext::with_config(
+        SessionConfig::new().with_null_group_keys(NullGroupKeys::Drop),
+    );
+    register_dictionary_groups(&ctx)?;
+
+    // the rows with a null in any of the keys are dropped
+    let sql = "SELECT COUNT(*), c1, c2 FROM test GROUP BY c1, c2";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----------------+----+-----+",
+        "| COUNT(UInt8(1)) | c1 | c2  |",
+        "+-----------------+----+-----+",
+        "| 2               | 3  | foo |",
+        "+-----------------+----+-----+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+
+    let sql = "SELECT COUNT(*), c1 FROM test GROUP BY c1";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----------------+----+",
+        "| COUNT(UInt8(1)) | c1 |",
+        "+-----------------+----+",
+        "| 3               | 0  |",
+        "| 2               | 3  |",
+        "+-----------------+----+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+
+    // the aggregations without GROUP BY count all the rows
+    let sql = "SELECT COUNT(*) FROM test";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----------------+",
+        "| COUNT(UInt8(1)) |",
+        "+-----------------+",
+        "| 10              |",
+        "+-----------------+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+
+    // DISTINCT keeps the nulls
+    let sql = "SELECT DISTINCT c1 FROM test";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+", "| c1 |", "+----+", "|    |", "| 0  |", "| 3  |", "+----+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+
+    // and the session can group the nulls again
+    ctx.sql("SET null_group_keys = group")
+        .await?
+        .collect()
+        .await?;
+    let sql = "SELECT COUNT(*), c1 FROM test GROUP BY c1";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----------------+----+",
+        "| COUNT(UInt8(1)) | c1 |",
+        "+-----------------+----+",
+        "| 5               |    |",
+        "| 3               | 0  |",
+        "| 2               | 3  |",
+        "+-----------------+----+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn csv_group_by_date() -> Result<()> {
     let ctx = SessionContext::new();
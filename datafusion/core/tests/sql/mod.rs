@@ -30,6 +30,7 @@ use datafusion::assert_batches_sorted_eq;
 use datafusion::assert_contains;
 use datafusion::assert_not_contains;
 use datafusion::datasource::TableProvider;
+use datafusion::execution::context::default_target_partitions;
 use datafusion::from_slice::FromSlice;
 use datafusion::logical_plan::plan::{Aggregate, Projection};
 use datafusion::logical_plan::LogicalPlan;
@@ -708,7 +709,7 @@ fn normalize_for_explain(s: &str) -> String {
 
     // convert things like partitioning=RoundRobinBatch(16)
     // to partitioning=RoundRobinBatch(NUM_CORES)
-    let needle = format!("RoundRobinBatch({})", num_cpus::get());
+    let needle = format!("RoundRobinBatch({})", default_target_partitions());
     s.replace(&needle, "RoundRobinBatch(NUM_CORES)")
 }
 
@@ -16,6 +16,7 @@
 // under the License.
 
 use super::*;
+use datafusion::sql::planner::NullOrdering;
 use fuzz_utils::{batches_to_vec, partitions_to_sorted_vec};
 
 #[tokio::test]
@@ -72,6 +73,58 @@ async fn test_nulls_first_asc() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_order_by_collate() -> Result<()> {
+    let ctx = SessionContext::new();
+    let values = "(VALUES ('b'), ('B'), ('a'), ('C')) AS t (name)";
+
+    let sql = format!(
+        "SELECT name FROM {} ORDER BY name COLLATE nocase, name",
+        values
+    );
+    let actual = execute_to_batches(&ctx, &sql).await;
+    let expected = vec![
+        "+------+", "| name |", "+------+", "| a    |", "| B    |", "| b    |",
+        "| C    |", "+------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = format!(
+        "SELECT count(*) AS n FROM {} WHERE name COLLATE nocase = 'B'",
+        values
+    );
+    let actual = execute_to_batches(&ctx, &sql).await;
+    let expected = vec!["+---+", "| n |", "+---+", "| 2 |", "+---+"];
+    assert_batches_eq!(expected, &actual);
+
+    // the groups are the collation keys
+    let sql = format!(
+        "SELECT name COLLATE nocase AS key, count(*) AS n FROM {} \
+         GROUP BY name COLLATE nocase ORDER BY key",
+        values
+    );
+    let actual = execute_to_batches(&ctx, &sql).await;
+    let expected = vec![
+        "+-----+---+",
+        "| key | n |",
+        "+-----+---+",
+        "| a   | 1 |",
+        "| b   | 2 |",
+        "| c   | 1 |",
+        "+-----+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = format!("SELECT name FROM {} ORDER BY name COLLATE missing", values);
+    let err = plan_and_collect(&ctx, &sql).await.unwrap_err();
+    assert!(
+        err.to_string().contains("no collation named 'missing'"),
+        "{}",
+        err
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_nulls_first_desc() -> Result<()> {
     let ctx = SessionContext::new();
@@ -126,6 +179,77 @@ async fn test_specific_nulls_first_asc() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_default_null_ordering() -> Result<()> {
+    let ctx = SessionContext::with_config(
+        SessionConfig::new().with_default_null_ordering(NullOrdering::NullsMin),
+    );
+    let sql = "SELECT * FROM (VALUES (1, 'one'), (2, 'two'), (null, 'three')) AS t (num,letter) ORDER BY num ASC";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----+--------+",
+        "| num | letter |",
+        "+-----+--------+",
+        "|     | three  |",
+        "| 1   | one    |",
+        "| 2   | two    |",
+        "+-----+--------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // an explicit ordering of the nulls overrides the default
+    let sql = "SELECT * FROM (VALUES (1, 'one'), (2, 'two'), (null, 'three')) AS t (num,letter) ORDER BY num ASC NULLS LAST";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----+--------+",
+        "| num | letter |",
+        "+-----+--------+",
+        "| 1   | one    |",
+        "| 2   | two    |",
+        "|     | three  |",
+        "+-----+--------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    ctx.sql("SET default_null_ordering = trino")
+        .await?
+        .collect()
+        .await?;
+    let sql = "SELECT * FROM (VALUES (1, 'one'), (2, 'two'), (null, 'three')) AS t (num,letter) ORDER BY num DESC";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----+--------+",
+        "| num | letter |",
+        "+-----+--------+",
+        "| 2   | two    |",
+        "| 1   | one    |",
+        "|     | three  |",
+        "+-----+--------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_default_null_ordering_window() -> Result<()> {
+    let ctx = SessionContext::with_config(
+        SessionConfig::new().with_default_null_ordering(NullOrdering::NullsFirst),
+    );
+    let sql = "SELECT letter, ROW_NUMBER() OVER (ORDER BY num) AS rn FROM (VALUES (1, 'one'), (2, 'two'), (null, 'three')) AS t (num,letter) ORDER BY rn";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+--------+----+",
+        "| letter | rn |",
+        "+--------+----+",
+        "| three  | 1  |",
+        "| one    | 2  |",
+        "| two    | 3  |",
+        "+--------+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn sort() -> Result<()> {
     let results =
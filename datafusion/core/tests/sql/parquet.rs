@@ -229,3 +229,52 @@ async fn schema_merge_ignores_metadata() {
 
     assert_eq!(result[0].schema().metadata(), result[1].schema().metadata());
 }
+
+#[tokio::test]
+async fn optimize_table() -> Result<()> {
+    // write a table of 3 files of one row each
+    let tmp_dir = TempDir::new()?;
+    let table_path = tmp_dir.path().join("small_files");
+    fs::create_dir(&table_path)?;
+    let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+    for i in 0..3 {
+        let file = fs::File::create(table_path.join(format!("part-{}.parquet", i)))?;
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), None)?;
+        let ids = Arc::new(Int32Array::from_slice(&[2 - i]));
+        writer.write(&RecordBatch::try_new(schema.clone(), vec![ids])?)?;
+        writer.close()?;
+    }
+
+    let ctx = SessionContext::new();
+    let sql = format!(
+        "CREATE EXTERNAL TABLE t STORED AS PARQUET LOCATION '{}'",
+        table_path.to_str().unwrap()
+    );
+    ctx.sql(&sql).await?;
+
+    let actual = execute_to_batches(&ctx, "OPTIMIZE TABLE t ORDER BY id").await;
+    let expected = vec![
+        "+---------------+---------------+----------+",
+        "| files_removed | files_written | num_rows |",
+        "+---------------+---------------+----------+",
+        "| 3             | 1             | 3        |",
+        "+---------------+---------------+----------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    assert_eq!(fs::read_dir(&table_path)?.count(), 1);
+
+    let actual = execute_to_batches(&ctx, "SELECT id FROM t").await;
+    let expected = vec![
+        "+----+", "| id |", "+----+", "| 0  |", "| 1  |", "| 2  |", "+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let err = ctx
+        .sql("OPTIMIZE TABLE t TARGET_FILE_SIZE 0")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("must be positive"));
+    let err = ctx.sql("OPTIMIZE TABLE t ORDER BY x").await.unwrap_err();
+    assert!(err.to_string().contains("No field"));
+    Ok(())
+}
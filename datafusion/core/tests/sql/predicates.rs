@@ -238,6 +238,114 @@ async fn like_on_string_dictionaries() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn predicates_on_numeric_dictionaries() -> Result<()> {
+    let keys = Int32Array::from(vec![Some(0), Some(1), None, Some(2), Some(0)]);
+    let values = Int64Array::from(vec![10, 20, 30]);
+    let c1 = DictionaryArray::<Int32Type>::try_new(&keys, &values)?;
+    let c2 = vec![Some("foo"), Some("bar"), Some("baz"), None, Some("fazzz")]
+        .into_iter()
+        .collect::<DictionaryArray<UInt8Type>>();
+
+    let batch = RecordBatch::try_from_iter(vec![
+        ("c1", Arc::new(c1) as _),
+        ("c2", Arc::new(c2) as _),
+    ])
+    .unwrap();
+
+    let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+    let ctx = SessionContext::new();
+    ctx.register_table("test", Arc::new(table))?;
+
+    let sql = "SELECT c1, c2 FROM test WHERE c1 > 15 AND c2 NOT LIKE 'f%'";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+-----+",
+        "| c1 | c2  |",
+        "+----+-----+",
+        "| 20 | bar |",
+        "+----+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT c1, c2 FROM test WHERE 10.5 > c1 OR c2 = 'baz'";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+-------+",
+        "| c1 | c2    |",
+        "+----+-------+",
+        "| 10 | foo   |",
+        "|    | baz   |",
+        "| 10 | fazzz |",
+        "+----+-------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn predicates_on_unsigned_integers() -> Result<()> {
+    let c1 = UInt64Array::from(vec![0, 5, 9223372036854775808, u64::MAX]);
+    let c2 = UInt8Array::from(vec![0, 5, 128, u8::MAX]);
+    let c3 = Int64Array::from(vec![-1, 5, i64::MIN, 0]);
+    let batch = RecordBatch::try_from_iter(vec![
+        ("c1", Arc::new(c1) as _),
+        ("c2", Arc::new(c2) as _),
+        ("c3", Arc::new(c3) as _),
+    ])
+    .unwrap();
+
+    let table = MemTable::try_new(batch.schema(), vec![vec![batch]])?;
+    let ctx = SessionContext::new();
+    ctx.register_table("test", Arc::new(table))?;
+
+    // the literals are compared as UInt64 and UInt8 values
+    let sql = "SELECT c1, c2 FROM test WHERE c1 > 5 AND c2 <= 255";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----------------------+-----+",
+        "| c1                   | c2  |",
+        "+----------------------+-----+",
+        "| 9223372036854775808  | 128 |",
+        "| 18446744073709551615 | 255 |",
+        "+----------------------+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the literal is out of the range of UInt8
+    let sql = "SELECT c2 FROM test WHERE c2 < 256 AND c2 > -1 AND c2 <> 5";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-----+", "| c2  |", "+-----+", "| 0   |", "| 128 |", "| 255 |", "+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // UInt64 and Int64 columns are compared as floats
+    let sql = "SELECT c1, c3 FROM test WHERE c1 > c3";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----------------------+----------------------+",
+        "| c1                   | c3                   |",
+        "+----------------------+----------------------+",
+        "| 0                    | -1                   |",
+        "| 9223372036854775808  | -9223372036854775808 |",
+        "| 18446744073709551615 | 0                    |",
+        "+----------------------+----------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    let sql = "SELECT c1, c3 FROM test WHERE c1 = c3";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+----+",
+        "| c1 | c3 |",
+        "+----+----+",
+        "| 5  | 5  |",
+        "+----+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_regexp_is_match() -> Result<()> {
     let input = vec![Some("foo"), Some("Barrr"), Some("Bazzz"), Some("ZZZZZ")]
@@ -386,3 +494,23 @@ async fn csv_in_set_test() -> Result<()> {
     assert_batches_sorted_eq!(expected, &actual);
     Ok(())
 }
+
+#[tokio::test]
+async fn csv_query_projecting_filter_predicate() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_aggregate_csv(&ctx).await?;
+    let sql = "SELECT c1, c12 > 0.376 AS above, \
+               CASE WHEN c12 < 0.4 THEN 'low' ELSE 'high' END AS bucket \
+               FROM aggregate_test_100 WHERE c12 > 0.376 AND c12 < 0.4";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+-------+--------+",
+        "| c1 | above | bucket |",
+        "+----+-------+--------+",
+        "| e  | true  | low    |",
+        "| d  | true  | low    |",
+        "+----+-------+--------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
@@ -56,6 +56,37 @@ async fn projection_type_alias() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn projection_alias_duplicate_names() -> Result<()> {
+    let sql = "SELECT c3, c3, COUNT(*), COUNT(*) AS n, COUNT(*) AS n \
+        FROM aggregate_simple GROUP BY c3";
+
+    let ctx = SessionContext::new();
+    register_aggregate_simple_csv(&ctx).await?;
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert!(
+        err.to_string().contains("require unique expression names"),
+        "{}",
+        err
+    );
+
+    let ctx = SessionContext::with_config(
+        SessionConfig::new().with_alias_duplicate_names(true),
+    );
+    register_aggregate_simple_csv(&ctx).await?;
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-------+-------+-----------------+---+-----+",
+        "| c3    | c3_1  | COUNT(UInt8(1)) | n | n_1 |",
+        "+-------+-------+-----------------+---+-----+",
+        "| false | false | 6               | 6 | 6   |",
+        "| true  | true  | 9               | 9 | 9   |",
+        "+-------+-------+-----------------+---+-----+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn csv_query_group_by_avg_with_projection() -> Result<()> {
     let ctx = SessionContext::new();
@@ -16,8 +16,9 @@
 // under the License.
 
 use super::*;
+use arrow::buffer::Buffer;
 use datafusion::{
-    datasource::empty::EmptyTable, from_slice::FromSlice,
+    datasource::empty::EmptyTable, from_slice::FromSlice, logical_plan::DedupStrategy,
     physical_plan::collect_partitioned,
 };
 use tempfile::TempDir;
@@ -624,6 +625,134 @@ async fn query_nested_get_indexed_field_on_struct() -> Result<()> {
     Ok(())
 }
 
+fn structs_table() -> Result<Arc<MemTable>> {
+    let nested_dt = DataType::List(Box::new(Field::new("item", DataType::Int64, true)));
+    // Nested schema of { "some_struct": { "bar": [i64] } }
+    let struct_fields = vec![Field::new("bar", nested_dt, true)];
+    let schema = Arc::new(Schema::new(vec![Field::new(
+        "some_struct",
+        DataType::Struct(struct_fields.clone()),
+        false,
+    )]));
+
+    let builder = PrimitiveBuilder::<Int64Type>::new(3);
+    let nested_lb = ListBuilder::new(builder);
+    let mut sb = StructBuilder::new(struct_fields, vec![Box::new(nested_lb)]);
+    for int_vec in vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]] {
+        let lb = sb.field_builder::<ListBuilder<Int64Builder>>(0).unwrap();
+        for int in int_vec {
+            lb.values().append_value(int).unwrap();
+        }
+        lb.append(true).unwrap();
+        sb.append(true).unwrap();
+    }
+    let data = RecordBatch::try_new(schema.clone(), vec![Arc::new(sb.finish())])?;
+    Ok(Arc::new(MemTable::try_new(schema, vec![vec![data]])?))
+}
+
+#[tokio::test]
+async fn query_struct_field_access() -> Result<()> {
+    let ctx = SessionContext::new();
+    ctx.register_table("structs", structs_table()?)?;
+
+    let sql = "SELECT some_struct.bar[0] AS i0, structs.some_struct.bar[1] AS i1, \
+               some_struct['bar'][7] AS i7 FROM structs";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+----+----+",
+        "| i0 | i1 | i7 |",
+        "+----+----+----+",
+        "| 0  | 1  |    |",
+        "| 4  | 5  |    |",
+        "| 8  | 9  |    |",
+        "+----+----+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT some_struct.baz FROM structs";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: Field baz not found in struct"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_one_based_list_index() -> Result<()> {
+    let ctx =
+        SessionContext::with_config(SessionConfig::new().with_one_based_list_index(true));
+    ctx.register_table("structs", structs_table()?)?;
+
+    let sql = "SELECT some_struct.bar[1] AS i1, some_struct.bar[4] AS i4 FROM structs";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+----+",
+        "| i1 | i4 |",
+        "+----+----+",
+        "| 0  | 3  |",
+        "| 4  | 7  |",
+        "| 8  | 11 |",
+        "+----+----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT some_struct.bar[0] FROM structs";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: List indexes start at 1, was 0"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_map_key_access() -> Result<()> {
+    let ctx = SessionContext::new();
+    let keys = StringArray::from(vec!["a", "b", "a"]);
+    let values = Int64Array::from(vec![Some(1), None, Some(3)]);
+    let entries = StructArray::from(vec![
+        (
+            Field::new("keys", DataType::Utf8, false),
+            Arc::new(keys) as ArrayRef,
+        ),
+        (
+            Field::new("values", DataType::Int64, true),
+            Arc::new(values) as ArrayRef,
+        ),
+    ]);
+    let map_type = DataType::Map(
+        Box::new(Field::new("entries", entries.data_type().clone(), false)),
+        false,
+    );
+    // maps {a: 1, b: null}, {a: 3} and {}
+    let map = MapArray::from(
+        ArrayData::builder(map_type.clone())
+            .len(3)
+            .add_buffer(Buffer::from_slice_ref(&[0i32, 2, 3, 3]))
+            .add_child_data(entries.data().clone())
+            .build()?,
+    );
+    let schema = Arc::new(Schema::new(vec![Field::new("m", map_type, true)]));
+    let data = RecordBatch::try_new(schema.clone(), vec![Arc::new(map)])?;
+    let table = MemTable::try_new(schema, vec![vec![data]])?;
+    ctx.register_table("maps", Arc::new(table))?;
+
+    let sql = "SELECT m['a'] AS a, m['b'] AS b, m['c'] AS c FROM maps";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+---+---+",
+        "| a | b | c |",
+        "+---+---+---+",
+        "| 1 |   |   |",
+        "| 3 |   |   |",
+        "|   |   |   |",
+        "+---+---+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
 #[tokio::test]
 async fn query_on_string_dictionary() -> Result<()> {
     // Test to ensure DataFusion can operate on dictionary types
@@ -870,6 +999,82 @@ async fn query_cte() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn query_row_comparisons() -> Result<()> {
+    let ctx = SessionContext::new();
+    let expected = vec![
+        "+----+----+",
+        "| c1 | c2 |",
+        "+----+----+",
+        "| 1  | b  |",
+        "| 2  | a  |",
+        "+----+----+",
+    ];
+
+    let sql = "SELECT c1, c2 FROM (VALUES (1, 'a'), (1, 'b'), (2, 'a')) AS t(c1, c2) \
+        WHERE (c1, c2) > (1, 'a')";
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT c1, c2 FROM (VALUES (1, 'a'), (1, 'b'), (2, 'a')) AS t(c1, c2) \
+        WHERE (c1, c2) IN ((1, 'b'), (2, 'a'), (3, 'c'))";
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "SELECT c1, c2 FROM (VALUES (1, 'a'), (1, 'b'), (2, 'a')) AS t(c1, c2) \
+        WHERE ROW(c1, c2) <> ROW(1, 'a')";
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn query_table_alias_with_columns() -> Result<()> {
+    let ctx = SessionContext::new();
+
+    // with column names
+    let sql = "WITH t(a, b) AS (SELECT 1, 2) SELECT b, a FROM t";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+---+",
+        "| b | a |",
+        "+---+---+",
+        "| 2 | 1 |",
+        "+---+---+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // derived table of columns whose names contain dots
+    let sql = "SELECT x.total FROM \
+        (SELECT t.c1 + t.c2 FROM (VALUES (1, 2), (3, 4)) AS t(c1, c2)) AS x(total)";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-------+",
+        "| total |",
+        "+-------+",
+        "| 3     |",
+        "| 7     |",
+        "+-------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // the column names are normalized like the other identifiers
+    let sql = "SELECT s.n FROM (VALUES (1)) AS s(N)";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec!["+---+", "| n |", "+---+", "| 1 |", "+---+"];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "WITH t(a) AS (SELECT 1, 2) SELECT * FROM t";
+    let err = ctx.create_logical_plan(sql).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Error during planning: Source table contains 2 columns but only 1 names given as column alias"
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn csv_select_nested() -> Result<()> {
     let ctx = SessionContext::new();
@@ -1008,3 +1213,205 @@ async fn query_empty_table() {
     let expected = vec!["++", "++"];
     assert_batches_sorted_eq!(expected, &result);
 }
+
+#[tokio::test]
+async fn select_from_generate_series() -> Result<()> {
+    let ctx = SessionContext::new();
+    {
+        let sql = "SELECT * FROM generate_series(1, 3)";
+        let actual = execute_to_batches(&ctx, sql).await;
+        let expected = vec![
+            "+-----------------+",
+            "| generate_series |",
+            "+-----------------+",
+            "| 1               |",
+            "| 2               |",
+            "| 3               |",
+            "+-----------------+",
+        ];
+        assert_batches_eq!(expected, &actual);
+    }
+    {
+        let sql = "SELECT n * 2 AS doubled FROM generate_series(10, 1, -4) AS s(n)";
+        let actual = execute_to_batches(&ctx, sql).await;
+        let expected = vec![
+            "+---------+",
+            "| doubled |",
+            "+---------+",
+            "| 20      |",
+            "| 12      |",
+            "| 4       |",
+            "+---------+",
+        ];
+        assert_batches_eq!(expected, &actual);
+    }
+    {
+        let sql = "SELECT count(*), sum(generate_series) FROM generate_series(1, 100)";
+        let actual = execute_to_batches(&ctx, sql).await;
+        let expected = vec![
+            "+-----------------+--------------------------------------+",
+            "| COUNT(UInt8(1)) | SUM(generate_series.generate_series) |",
+            "+-----------------+--------------------------------------+",
+            "| 100             | 5050                                 |",
+            "+-----------------+--------------------------------------+",
+        ];
+        assert_batches_eq!(expected, &actual);
+    }
+    Ok(())
+}
+
+/// Registers the table `versions` of several versions of the rows of each
+/// `id`, in two partitions
+fn register_versions(ctx: &SessionContext) -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int32, false),
+        Field::new("seq", DataType::Int64, true),
+        Field::new("v", DataType::Utf8, false),
+    ]));
+    let batch = |ids: Vec<i32>, seqs: Vec<Option<i64>>, values: Vec<&str>| {
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(ids)),
+                Arc::new(Int64Array::from(seqs)),
+                Arc::new(StringArray::from(values)),
+            ],
+        )
+    };
+    let table = MemTable::try_new(
+        schema.clone(),
+        vec![
+            vec![batch(
+                vec![1, 2, 1],
+                vec![Some(1), Some(5), Some(3)],
+                vec!["a", "b", "c"],
+            )?],
+            vec![batch(
+                vec![2, 3, 1],
+                vec![None, None, Some(2)],
+                vec!["d", "e", "f"],
+            )?],
+        ],
+    )?
+    .with_primary_key(vec![0])?;
+    ctx.register_table("versions", Arc::new(table))?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn select_from_dedup() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_versions(&ctx)?;
+    let expected = vec![
+        "+----+-----+---+",
+        "| id | seq | v |",
+        "+----+-----+---+",
+        "| 1  | 3   | c |",
+        "| 2  | 5   | b |",
+        "| 3  |     | e |",
+        "+----+-----+---+",
+    ];
+    // keyed by the primary key of the table
+    let sql = "SELECT * FROM dedup(versions, seq) ORDER BY id";
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+    let sql = "SELECT d.* FROM dedup(versions, seq, versions.id) AS d ORDER BY d.id";
+    let actual = execute_to_batches(&ctx, sql).await;
+    assert_batches_eq!(expected, &actual);
+
+    // filters are applied to the latest rows
+    let sql = "SELECT v FROM dedup(versions, seq) WHERE v IN ('a', 'b', 'f')";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec!["+---+", "| v |", "+---+", "| b |", "+---+"];
+    assert_batches_eq!(expected, &actual);
+
+    let sql = "EXPLAIN SELECT * FROM dedup(versions, seq)";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let plan = arrow::util::pretty::pretty_format_batches(&actual)?.to_string();
+    assert_contains!(plan, "Dedup: keys=[#versions.id], sequence=#versions.seq");
+    assert_contains!(
+        plan,
+        "DedupExec: keys=[id@0], sequence=seq@1, strategy=Hash"
+    );
+
+    let err = ctx.sql("SELECT * FROM dedup(versions)").await.unwrap_err();
+    assert_contains!(err.to_string(), "dedup expects a table, a sequence column");
+    let err = ctx
+        .sql("SELECT * FROM dedup(versions, 1)")
+        .await
+        .unwrap_err();
+    assert_contains!(err.to_string(), "Arguments of dedup must be identifiers");
+    Ok(())
+}
+
+#[tokio::test]
+async fn dataframe_dedup_strategies() -> Result<()> {
+    let expected = vec![
+        "+----+---+",
+        "| id | v |",
+        "+----+---+",
+        "| 1  | c |",
+        "| 2  | b |",
+        "| 3  | e |",
+        "+----+---+",
+    ];
+    for target_partitions in [1, 4] {
+        let config = SessionConfig::new().with_target_partitions(target_partitions);
+        let ctx = SessionContext::with_config(config);
+        register_versions(&ctx)?;
+        for strategy in [DedupStrategy::Hash, DedupStrategy::Sort] {
+            let df = ctx
+                .table("versions")?
+                .dedup_with_strategy(vec![col("id")], col("seq"), strategy)?
+                .select_columns(&["id", "v"])?
+                .sort(vec![col("id").sort(true, false)])?;
+            assert_batches_eq!(expected, &df.collect().await?);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn select_without_from_with_multiple_rows() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "SELECT 1 AS value UNION ALL SELECT * FROM generate_series(2, 3) \
+               UNION ALL SELECT column1 FROM (VALUES (4), (5)) AS t";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+-------+",
+        "| value |",
+        "+-------+",
+        "| 1     |",
+        "| 2     |",
+        "| 3     |",
+        "| 4     |",
+        "| 5     |",
+        "+-------+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn select_from_invalid_table_function() -> Result<()> {
+    let ctx = SessionContext::new();
+    let err = ctx
+        .sql("SELECT * FROM generate_series(1, 5, 0)")
+        .await
+        .unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "step size of generate_series cannot equal zero"
+    );
+    let err = ctx.sql("SELECT * FROM unknown(1, 5)").await.unwrap_err();
+    assert_contains!(err.to_string(), "Table function 'unknown' not found");
+    let err = ctx
+        .sql("SELECT * FROM generate_series(1, 'a')")
+        .await
+        .unwrap_err();
+    assert_contains!(
+        err.to_string(),
+        "Arguments of generate_series must be integer literals"
+    );
+    Ok(())
+}
@@ -814,3 +814,128 @@ async fn group_by_timestamp_millis() -> Result<()> {
     assert_batches_eq!(expected, &actual);
     Ok(())
 }
+
+#[tokio::test]
+async fn session_time_zone_and_first_day_of_week() -> Result<()> {
+    let ctx = SessionContext::with_config(
+        SessionConfig::new()
+            .with_time_zone(FixedOffset::east(5 * 3600 + 30 * 60))
+            .with_first_day_of_week(Weekday::Sun),
+    );
+
+    // 2022-05-04 is a Wednesday; results are rendered in UTC
+    let sql = "SELECT \
+        CAST('2022-05-04 10:00:00' AS TIMESTAMP) AS ts, \
+        to_timestamp('2022-05-04T10:00:00+00:00') AS explicit, \
+        date_trunc('week', to_timestamp('2022-05-04T10:00:00')) AS week, \
+        date_part('hour', to_timestamp('2022-05-04T10:00:00')) AS hour, \
+        date_part('dow', to_timestamp('2022-05-04T10:00:00')) AS dow";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---------------------+---------------------+---------------------+------+-----+",
+        "| ts                  | explicit            | week                | hour | dow |",
+        "+---------------------+---------------------+---------------------+------+-----+",
+        "| 2022-05-04 04:30:00 | 2022-05-04 10:00:00 | 2022-04-30 18:30:00 | 10   | 3   |",
+        "+---------------------+---------------------+---------------------+------+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    // casts to strings render the timestamps in the session time zone
+    let sql = "SELECT CAST(to_timestamp('2022-05-04T10:00:00') AS VARCHAR) AS rendered";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---------------------------+",
+        "| rendered                  |",
+        "+---------------------------+",
+        "| 2022-05-04 10:00:00+05:30 |",
+        "+---------------------------+",
+    ];
+    assert_batches_eq!(expected, &actual);
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_time_zone_and_first_day_of_week() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "SELECT \
+        date_part('hour', to_timestamp('2022-05-04T10:00:00Z')) AS hour, \
+        date_trunc('week', to_timestamp('2022-05-04T10:00:00Z')) AS week, \
+        date_part('dow', to_timestamp('2022-05-04T10:00:00Z')) AS dow";
+
+    // the settings changed by SET are used by the following statements
+    ctx.sql("SET time_zone = '+02:00'").await?.collect().await?;
+    ctx.sql("SET first_day_of_week = 'sunday'")
+        .await?
+        .collect()
+        .await?;
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+------+---------------------+-----+",
+        "| hour | week                | dow |",
+        "+------+---------------------+-----+",
+        "| 12   | 2022-04-30 22:00:00 | 3   |",
+        "+------+---------------------+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    ctx.sql("SET time_zone = DEFAULT").await?.collect().await?;
+    ctx.sql("SET first_day_of_week = DEFAULT")
+        .await?
+        .collect()
+        .await?;
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+------+---------------------+-----+",
+        "| hour | week                | dow |",
+        "+------+---------------------+-----+",
+        "| 10   | 2022-05-02 00:00:00 | 3   |",
+        "+------+---------------------+-----+",
+    ];
+    assert_batches_eq!(expected, &actual);
+
+    let err = ctx.sql("SET time_zone = 'Europe/Paris'").await.unwrap_err();
+    assert!(err.to_string().contains("Invalid time zone"), "{}", err);
+    Ok(())
+}
+
+#[tokio::test]
+async fn timestamp_string_comparison() -> Result<()> {
+    let ctx = SessionContext::new();
+    ctx.register_table(
+        "ts_millis",
+        make_timestamp_table::<TimestampMillisecondType>()?,
+    )?;
+    ctx.register_table("ts_secs", make_timestamp_table::<TimestampSecondType>()?)?;
+
+    // with an offset: 12:00:00 UTC
+    let sql = "SELECT value FROM ts_millis \
+        WHERE ts > '2020-09-08T14:00:00.000+02:00' ORDER BY value";
+    assert_eq!(execute(&ctx, sql).await, vec![vec!["1"], vec!["2"]]);
+
+    let sql = "SELECT value FROM ts_millis WHERE ts = '2020-09-08T12:42:29.190Z'";
+    assert_eq!(execute(&ctx, sql).await, vec![vec!["2"]]);
+    let sql = "SELECT value FROM ts_millis WHERE '2020-09-08T12:42:29.190Z' = ts";
+    assert_eq!(execute(&ctx, sql).await, vec![vec!["2"]]);
+
+    // below the unit of the timestamps
+    let sql = "SELECT value FROM ts_millis WHERE ts >= '2020-09-08T12:42:29.1905Z'";
+    assert_eq!(execute(&ctx, sql).await, vec![vec!["1"]]);
+    let sql = "SELECT value FROM ts_secs \
+        WHERE ts < '2020-09-08 12:42:29.5' ORDER BY value";
+    assert_eq!(execute(&ctx, sql).await, vec![vec!["2"], vec!["3"]]);
+
+    let sql = "SELECT value FROM ts_secs WHERE ts = 'not a timestamp'";
+    let err = ctx.sql(sql).await?.collect().await;
+    assert!(err.is_err());
+
+    // without an offset: in the session time zone
+    ctx.sql("SET time_zone = '+02:00'").await?.collect().await?;
+    let sql = "SELECT value FROM ts_millis \
+        WHERE ts > '2020-09-08T14:00:00.000' ORDER BY value";
+    assert_eq!(execute(&ctx, sql).await, vec![vec!["1"], vec!["2"]]);
+    let sql = "SELECT value FROM ts_millis WHERE ts = '2020-09-08 14:42:29.190'";
+    assert_eq!(execute(&ctx, sql).await, vec![vec!["2"]]);
+    let sql = "SELECT value FROM ts_millis WHERE ts = '2020-09-08T12:42:29.190Z'";
+    assert_eq!(execute(&ctx, sql).await, vec![vec!["2"]]);
+    Ok(())
+}
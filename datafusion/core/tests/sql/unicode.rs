@@ -60,6 +60,13 @@ async fn test_unicode_expressions() -> Result<()> {
     test_expression!("lpad('xyxhi', 3)", "xyx");
     test_expression!("lpad(NULL, 0)", "NULL");
     test_expression!("lpad(NULL, 5, 'xy')", "NULL");
+    test_expression!("nfc('Cre\u{300}me')", "Crème");
+    test_expression!("octet_length(nfc('Cre\u{300}me'))", "6");
+    test_expression!("octet_length(nfd('Crème'))", "7");
+    test_expression!("nfc(nfd('Crème')) = 'Crème'", "true");
+    test_expression!("nfd('Crème') = 'Crème'", "false");
+    test_expression!("nfd('Crème') COLLATE nfc = 'Crème'", "true");
+    test_expression!("nfd(NULL)", "NULL");
     test_expression!("reverse('abcde')", "edcba");
     test_expression!("reverse('loẅks')", "skẅol");
     test_expression!("reverse(NULL)", "NULL");
@@ -101,6 +108,9 @@ async fn test_unicode_expressions() -> Result<()> {
     test_expression!("translate(NULL, '143', 'ax')", "NULL");
     test_expression!("translate('12345', NULL, 'ax')", "NULL");
     test_expression!("translate('12345', '143', NULL)", "NULL");
+    test_expression!("unaccent('Crème Brûlée')", "Creme Brulee");
+    test_expression!("unaccent('Ångström')", "Angstrom");
+    test_expression!("unaccent(NULL)", "NULL");
     Ok(())
 }
 
@@ -48,6 +48,39 @@ async fn union_distinct() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn nested_union_distinct() -> Result<()> {
+    let ctx = SessionContext::new();
+    let sql = "SELECT 1 AS x, 'a' AS y \
+        UNION SELECT CAST(1 AS INT), 'a' \
+        UNION ALL SELECT 2, 'b' \
+        UNION SELECT 2, 'b' \
+        UNION SELECT DISTINCT x, y FROM (SELECT 3 AS x, 'c' AS y) AS t";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+---+",
+        "| x | y |",
+        "+---+---+",
+        "| 1 | a |",
+        "| 2 | b |",
+        "| 3 | c |",
+        "+---+---+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+
+    // the rows of all the inputs are de-duplicated by a single aggregate
+    let sql = format!("EXPLAIN {}", sql);
+    let actual = execute(&ctx, &sql).await;
+    let logical_plan = &actual[0][1];
+    assert_eq!(
+        logical_plan.matches("Aggregate").count(),
+        1,
+        "{}",
+        logical_plan
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn union_all_with_aggregate() -> Result<()> {
     let ctx = SessionContext::new();
@@ -64,3 +97,23 @@ async fn union_all_with_aggregate() -> Result<()> {
     assert_batches_eq!(expected, &actual);
     Ok(())
 }
+
+#[tokio::test]
+async fn union_all_by_name() -> Result<()> {
+    let ctx = SessionContext::with_config(SessionConfig::new().with_union_by_name(true));
+    let sql = "SELECT 1 AS a, 'x' AS b \
+        UNION ALL SELECT 'y' AS b, 2 AS a \
+        UNION ALL SELECT 3 AS a, 'z' AS c";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+---+---+---+",
+        "| a | b | c |",
+        "+---+---+---+",
+        "| 1 | x |   |",
+        "| 2 | y |   |",
+        "| 3 |   | z |",
+        "+---+---+---+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+    Ok(())
+}
@@ -142,3 +142,26 @@ async fn csv_query_window_with_partition_by_order_by() -> Result<()> {
     assert_batches_eq!(expected, &actual);
     Ok(())
 }
+
+#[tokio::test]
+async fn csv_query_aggregate_of_window_function() -> Result<()> {
+    let ctx = SessionContext::new();
+    register_aggregate_csv(&ctx).await?;
+    // the window function is computed before the aggregation
+    let sql = "SELECT c1, MAX(ROW_NUMBER() OVER (PARTITION BY c1 ORDER BY c9)) AS n \
+               FROM aggregate_test_100 GROUP BY c1";
+    let actual = execute_to_batches(&ctx, sql).await;
+    let expected = vec![
+        "+----+----+",
+        "| c1 | n  |",
+        "+----+----+",
+        "| a  | 21 |",
+        "| b  | 19 |",
+        "| c  | 21 |",
+        "| d  | 18 |",
+        "| e  | 21 |",
+        "+----+----+",
+    ];
+    assert_batches_sorted_eq!(expected, &actual);
+    Ok(())
+}
@@ -17,8 +17,8 @@
 
 //! Accumulator module contains the trait definition for aggregation function's accumulators.
 
-use arrow::array::ArrayRef;
-use datafusion_common::{Result, ScalarValue};
+use arrow::array::{ArrayBuilder, ArrayRef};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
 use std::fmt::Debug;
 
 /// An accumulator represents a stateful object that lives throughout the evaluation of multiple rows and
@@ -26,7 +26,8 @@ use std::fmt::Debug;
 ///
 /// An accumulator knows how to:
 /// * update its state from inputs via `update_batch`
-/// * convert its internal state to a vector of scalar values
+/// * convert its internal state to a vector of scalar values, or append it to
+///   arrays via `state_builders` and `append_state`
 /// * update its state from multiple accumulators' states via `merge_batch`
 /// * compute the final value from its internal state via `evaluate`
 pub trait Accumulator: Send + Sync + Debug {
@@ -35,6 +36,26 @@ pub trait Accumulator: Send + Sync + Debug {
     // of two values, sum and n.
     fn state(&self) -> Result<Vec<ScalarValue>>;
 
+    /// Creates the builders of the arrays of the states of instances of this
+    /// accumulator, e.g. for different groups, with one builder per state
+    /// field and room for `capacity` states. The states are then appended by
+    /// [`Accumulator::append_state`] instead of being built from the scalar
+    /// values returned by [`Accumulator::state`].
+    ///
+    /// Returns `None` by default, if the accumulator can't build its state
+    /// as arrays.
+    fn state_builders(&self, _capacity: usize) -> Option<Vec<Box<dyn ArrayBuilder>>> {
+        None
+    }
+
+    /// Appends the state of the accumulator to `builders`, created by the
+    /// [`Accumulator::state_builders`] of an instance of the same aggregate.
+    fn append_state(&self, _builders: &mut [Box<dyn ArrayBuilder>]) -> Result<()> {
+        Err(DataFusionError::NotImplemented(
+            "The accumulator can't append its state to arrays".to_string(),
+        ))
+    }
+
     /// updates the accumulator's state from a vector of arrays.
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()>;
 
@@ -43,4 +64,12 @@ pub trait Accumulator: Send + Sync + Debug {
 
     /// returns its value based on its current state.
     fn evaluate(&self) -> Result<ScalarValue>;
+
+    /// Returns the number of bytes allocated by the accumulator, including
+    /// its heap allocated state. Accumulators whose state grows with their
+    /// input (e.g. the values of a distinct count) should override it, so
+    /// that the memory of the aggregations is accounted for.
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
 }
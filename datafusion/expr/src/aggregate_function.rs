@@ -49,6 +49,13 @@ pub static TIMESTAMPS: &[DataType] = &[
 
 pub static DATES: &[DataType] = &[DataType::Date32, DataType::Date64];
 
+pub static DURATIONS: &[DataType] = &[
+    DataType::Duration(TimeUnit::Second),
+    DataType::Duration(TimeUnit::Millisecond),
+    DataType::Duration(TimeUnit::Microsecond),
+    DataType::Duration(TimeUnit::Nanosecond),
+];
+
 /// Enum of all built-in aggregate functions
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
 pub enum AggregateFunction {
@@ -86,6 +93,16 @@ pub enum AggregateFunction {
     ApproxPercentileContWithWeight,
     /// ApproxMedian
     ApproxMedian,
+    /// HyperLogLog sketch of the distinct input values
+    HllSketch,
+    /// Union of HyperLogLog sketches
+    HllUnion,
+    /// Approximate number of distinct values of a union of HyperLogLog sketches
+    HllCount,
+    /// Approximate most frequent values
+    ApproxTopK,
+    /// Histogram of numeric values
+    Histogram,
 }
 
 impl fmt::Display for AggregateFunction {
@@ -121,6 +138,11 @@ impl FromStr for AggregateFunction {
                 AggregateFunction::ApproxPercentileContWithWeight
             }
             "approx_median" => AggregateFunction::ApproxMedian,
+            "hll_sketch" => AggregateFunction::HllSketch,
+            "hll_union" => AggregateFunction::HllUnion,
+            "hll_count" => AggregateFunction::HllCount,
+            "approx_top_k" => AggregateFunction::ApproxTopK,
+            "histogram" => AggregateFunction::Histogram,
             _ => {
                 return Err(DataFusionError::Plan(format!(
                     "There is no built-in function named {}",
@@ -144,9 +166,9 @@ pub fn return_type(
 
     match fun {
         // TODO If the datafusion is compatible with PostgreSQL, the returned data type should be INT64.
-        AggregateFunction::Count | AggregateFunction::ApproxDistinct => {
-            Ok(DataType::UInt64)
-        }
+        AggregateFunction::Count
+        | AggregateFunction::ApproxDistinct
+        | AggregateFunction::HllCount => Ok(DataType::UInt64),
         AggregateFunction::Max | AggregateFunction::Min => {
             // For min and max agg function, the returned type is same as input type.
             // The coerced_data_types is same with input_types.
@@ -173,6 +195,13 @@ pub fn return_type(
             Ok(coerced_data_types[0].clone())
         }
         AggregateFunction::ApproxMedian => Ok(coerced_data_types[0].clone()),
+        AggregateFunction::HllSketch | AggregateFunction::HllUnion => {
+            Ok(DataType::Binary)
+        }
+        AggregateFunction::ApproxTopK => {
+            Ok(approx_top_k_return_type(&coerced_data_types[0]))
+        }
+        AggregateFunction::Histogram => Ok(histogram_return_type()),
     }
 }
 
@@ -187,9 +216,9 @@ pub fn coerce_types(
     check_arg_count(agg_fun, input_types, &signature.type_signature)?;
 
     match agg_fun {
-        AggregateFunction::Count | AggregateFunction::ApproxDistinct => {
-            Ok(input_types.to_vec())
-        }
+        AggregateFunction::Count
+        | AggregateFunction::ApproxDistinct
+        | AggregateFunction::HllSketch => Ok(input_types.to_vec()),
         AggregateFunction::ArrayAgg => Ok(input_types.to_vec()),
         AggregateFunction::Min | AggregateFunction::Max => {
             // min and max support the dictionary data type
@@ -326,6 +355,40 @@ pub fn coerce_types(
             }
             Ok(input_types.to_vec())
         }
+        AggregateFunction::ApproxTopK => {
+            if !is_integer_arg_type(&input_types[1]) {
+                return Err(DataFusionError::Plan(format!(
+                    "The k argument for {:?} must be an integer, not {:?}.",
+                    agg_fun, input_types[1]
+                )));
+            }
+            Ok(input_types.to_vec())
+        }
+        AggregateFunction::Histogram => {
+            if !is_approx_percentile_cont_supported_arg_type(&input_types[0]) {
+                return Err(DataFusionError::Plan(format!(
+                    "The function {:?} does not support inputs of type {:?}.",
+                    agg_fun, input_types[0]
+                )));
+            }
+            if !is_integer_arg_type(&input_types[1]) {
+                return Err(DataFusionError::Plan(format!(
+                    "The bins argument for {:?} must be an integer, not {:?}.",
+                    agg_fun, input_types[1]
+                )));
+            }
+            // the boundaries of the bins are computed as Float64
+            Ok(vec![DataType::Float64, input_types[1].clone()])
+        }
+        AggregateFunction::HllUnion | AggregateFunction::HllCount => {
+            if !matches!(input_types[0], DataType::Binary) {
+                return Err(DataFusionError::Plan(format!(
+                    "The function {:?} expects a Binary sketch, not {:?}.",
+                    agg_fun, input_types[0]
+                )));
+            }
+            Ok(input_types.to_vec())
+        }
     }
 }
 
@@ -335,13 +398,22 @@ pub fn signature(fun: &AggregateFunction) -> Signature {
     match fun {
         AggregateFunction::Count
         | AggregateFunction::ApproxDistinct
-        | AggregateFunction::ArrayAgg => Signature::any(1, Volatility::Immutable),
+        | AggregateFunction::ArrayAgg
+        | AggregateFunction::HllSketch => Signature::any(1, Volatility::Immutable),
+        AggregateFunction::HllUnion | AggregateFunction::HllCount => {
+            Signature::exact(vec![DataType::Binary], Volatility::Immutable)
+        }
+        AggregateFunction::ApproxTopK | AggregateFunction::Histogram => {
+            Signature::any(2, Volatility::Immutable)
+        }
         AggregateFunction::Min | AggregateFunction::Max => {
             let valid = STRINGS
                 .iter()
                 .chain(NUMERICS.iter())
                 .chain(TIMESTAMPS.iter())
                 .chain(DATES.iter())
+                .chain(DURATIONS.iter())
+                .chain(std::iter::once(&DataType::Boolean))
                 .cloned()
                 .collect::<Vec<_>>();
             Signature::uniform(1, valid, Volatility::Immutable)
@@ -400,6 +472,7 @@ pub fn sum_return_type(arg_type: &DataType) -> Result<DataType> {
             let new_precision = DECIMAL_MAX_PRECISION.min(*precision + 10);
             Ok(DataType::Decimal(new_precision, *scale))
         }
+        DataType::Duration(unit) => Ok(DataType::Duration(unit.clone())),
         other => Err(DataFusionError::Plan(format!(
             "SUM does not support type \"{:?}\"",
             other
@@ -407,6 +480,33 @@ pub fn sum_return_type(arg_type: &DataType) -> Result<DataType> {
     }
 }
 
+/// function return type of approx_top_k: a list of the most frequent values
+/// of type `arg_type` with their (approximate) number of occurrences
+pub fn approx_top_k_return_type(arg_type: &DataType) -> DataType {
+    DataType::List(Box::new(Field::new(
+        "item",
+        DataType::Struct(vec![
+            Field::new("value", arg_type.clone(), true),
+            Field::new("count", DataType::UInt64, true),
+        ]),
+        true,
+    )))
+}
+
+/// function return type of histogram: a list of bins, ordered by their
+/// boundaries, with the number of values they contain
+pub fn histogram_return_type() -> DataType {
+    DataType::List(Box::new(Field::new(
+        "item",
+        DataType::Struct(vec![
+            Field::new("lower", DataType::Float64, true),
+            Field::new("upper", DataType::Float64, true),
+            Field::new("count", DataType::UInt64, true),
+        ]),
+        true,
+    )))
+}
+
 /// function return type of variance
 pub fn variance_return_type(arg_type: &DataType) -> Result<DataType> {
     match arg_type {
@@ -507,6 +607,8 @@ pub fn avg_return_type(arg_type: &DataType) -> Result<DataType> {
         | DataType::UInt64
         | DataType::Float32
         | DataType::Float64 => Ok(DataType::Float64),
+        // the average of durations is a duration, as for intervals in postgres
+        DataType::Duration(unit) => Ok(DataType::Duration(unit.clone())),
         other => Err(DataFusionError::Plan(format!(
             "AVG does not support {:?}",
             other
@@ -597,6 +699,7 @@ pub fn is_sum_support_arg_type(arg_type: &DataType) -> bool {
             | DataType::Float32
             | DataType::Float64
             | DataType::Decimal(_, _)
+            | DataType::Duration(_)
     )
 }
 
@@ -614,6 +717,7 @@ pub fn is_avg_support_arg_type(arg_type: &DataType) -> bool {
             | DataType::Float32
             | DataType::Float64
             | DataType::Decimal(_, _)
+            | DataType::Duration(_)
     )
 }
 
@@ -681,6 +785,22 @@ pub fn is_correlation_support_arg_type(arg_type: &DataType) -> bool {
     )
 }
 
+/// Return `true` if `arg_type` is an integer type, as required by the
+/// size arguments of `approx_top_k` and `histogram`
+pub fn is_integer_arg_type(arg_type: &DataType) -> bool {
+    matches!(
+        arg_type,
+        DataType::UInt8
+            | DataType::UInt16
+            | DataType::UInt32
+            | DataType::UInt64
+            | DataType::Int8
+            | DataType::Int16
+            | DataType::Int32
+            | DataType::Int64
+    )
+}
+
 /// Return `true` if `arg_type` is of a [`DataType`] that the
 /// [`ApproxPercentileCont`] aggregation can operate on.
 pub fn is_approx_percentile_cont_supported_arg_type(arg_type: &DataType) -> bool {
@@ -737,6 +857,7 @@ mod tests {
             AggregateFunction::Count,
             AggregateFunction::ArrayAgg,
             AggregateFunction::ApproxDistinct,
+            AggregateFunction::HllSketch,
             AggregateFunction::Min,
             AggregateFunction::Max,
         ];
@@ -758,6 +879,7 @@ mod tests {
             vec![DataType::Int32],
             vec![DataType::Float32],
             vec![DataType::Decimal(20, 3)],
+            vec![DataType::Duration(TimeUnit::Millisecond)],
         ];
         for fun in funs {
             for input_type in &input_types {
@@ -790,6 +912,18 @@ mod tests {
             );
             assert_eq!(*input_type, result.unwrap());
         }
+
+        // Histogram values are coerced to Float64
+        let fun = AggregateFunction::Histogram;
+        let signature = aggregate_function::signature(&fun);
+        let result = coerce_types(&fun, &[DataType::Int32, DataType::Int64], &signature);
+        assert_eq!(vec![DataType::Float64, DataType::Int64], result.unwrap());
+        let result =
+            coerce_types(&fun, &[DataType::Int32, DataType::Float64], &signature);
+        assert_eq!(
+            "Error during planning: The bins argument for Histogram must be an integer, not Float64.",
+            result.unwrap_err().to_string()
+        );
     }
 
     #[test]
@@ -101,6 +101,13 @@ pub fn coerce_types(
                 _ => None,
             }
         }
+        // duration +/- duration of the same unit returns duration
+        Operator::Plus | Operator::Minus if matches!(lhs_type, DataType::Duration(_)) => {
+            match rhs_type {
+                DataType::Duration(_) if lhs_type == rhs_type => Some(lhs_type.clone()),
+                _ => None,
+            }
+        }
         // for math expressions, the final value of the coercion is also the return type
         // because coercion favours higher information types
         Operator::Plus
@@ -202,6 +209,16 @@ fn comparison_binary_numeric_coercion(
         (_, Decimal(_, _)) => get_comparison_common_decimal_type(rhs_type, lhs_type),
         (Float64, _) | (_, Float64) => Some(Float64),
         (_, Float32) | (Float32, _) => Some(Float32),
+        // a signed and an unsigned integer are compared in the narrowest
+        // type holding all the values of both. No integer type holds the
+        // values of UInt64 and Int64, they are compared as floats as with the
+        // other floats
+        (UInt64, Int64 | Int32 | Int16 | Int8)
+        | (Int64 | Int32 | Int16 | Int8, UInt64) => Some(Float64),
+        (UInt32, Int64 | Int32 | Int16 | Int8)
+        | (Int64 | Int32 | Int16 | Int8, UInt32) => Some(Int64),
+        (UInt16, Int32 | Int16 | Int8) | (Int32 | Int16 | Int8, UInt16) => Some(Int32),
+        (UInt8, Int16 | Int8) | (Int16 | Int8, UInt8) => Some(Int16),
         (Int64, _) | (_, Int64) => Some(Int64),
         (Int32, _) | (_, Int32) => Some(Int32),
         (Int16, _) | (_, Int16) => Some(Int16),
@@ -225,6 +242,10 @@ fn get_comparison_common_decimal_type(
         DataType::Int16 => DataType::Decimal(5, 0),
         DataType::Int32 => DataType::Decimal(10, 0),
         DataType::Int64 => DataType::Decimal(20, 0),
+        DataType::UInt8 => DataType::Decimal(3, 0),
+        DataType::UInt16 => DataType::Decimal(5, 0),
+        DataType::UInt32 => DataType::Decimal(10, 0),
+        DataType::UInt64 => DataType::Decimal(20, 0),
         DataType::Float32 => DataType::Decimal(14, 7),
         DataType::Float64 => DataType::Decimal(30, 15),
         _ => {
@@ -410,22 +431,24 @@ pub fn is_numeric(dt: &DataType) -> bool {
         }
 }
 
-/// Coercion rules for dictionary values (aka the type of the  dictionary itself)
+/// Coercion rules for dictionary values (aka the type of the  dictionary itself).
+/// The numeric values follow the comparison rules, so that e.g. decimal values
+/// can be compared to integers.
 fn dictionary_value_coercion(
     lhs_type: &DataType,
     rhs_type: &DataType,
 ) -> Option<DataType> {
-    numerical_coercion(lhs_type, rhs_type).or_else(|| string_coercion(lhs_type, rhs_type))
+    comparison_binary_numeric_coercion(lhs_type, rhs_type)
+        .or_else(|| string_coercion(lhs_type, rhs_type))
 }
 
 /// Coercion rules for Dictionaries: the type that both lhs and rhs
 /// can be casted to for the purpose of a computation.
 ///
-/// It would likely be preferable to cast primitive values to
-/// dictionaries, and thus avoid unpacking dictionary as well as doing
-/// faster comparisons. However, the arrow compute kernels (e.g. eq)
-/// don't have DictionaryArray support yet, so fall back to unpacking
-/// the dictionaries
+/// The dictionaries compared to literals are not unpacked by the physical
+/// expressions: the operator is applied to the values of the dictionary
+/// (cast to the returned type), see `BinaryExpr`. The dictionaries compared
+/// to arrays are unpacked.
 fn dictionary_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<DataType> {
     match (lhs_type, rhs_type) {
         (
@@ -500,6 +523,11 @@ fn temporal_coercion(lhs_type: &DataType, rhs_type: &DataType) -> Option<DataTyp
         (Date32, Utf8) => Some(Date32),
         (Utf8, Date64) => Some(Date64),
         (Date64, Utf8) => Some(Date64),
+        // string literals are parsed in the unit of the timestamps
+        (Utf8 | LargeUtf8, Timestamp(unit, tz))
+        | (Timestamp(unit, tz), Utf8 | LargeUtf8) => {
+            Some(Timestamp(unit.clone(), tz.clone()))
+        }
         (Timestamp(lhs_unit, lhs_tz), Timestamp(rhs_unit, rhs_tz)) => {
             let tz = match (lhs_tz, rhs_tz) {
                 // can't cast across timezones
@@ -615,6 +643,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_signed_unsigned_comparison_coercion() -> Result<()> {
+        use DataType::*;
+        let cases = [
+            (UInt8, Int8, Int16),
+            (UInt8, Int16, Int16),
+            (UInt8, Int64, Int64),
+            (UInt16, Int8, Int32),
+            (UInt16, Int32, Int32),
+            (UInt32, Int16, Int64),
+            (UInt32, Int64, Int64),
+            (UInt64, Int8, Float64),
+            (UInt64, Int64, Float64),
+            (UInt64, UInt8, UInt64),
+            (UInt32, Float32, Float32),
+            (UInt16, Decimal(10, 2), Decimal(10, 2)),
+            (UInt64, Decimal(10, 2), Decimal(22, 2)),
+        ];
+        for (unsigned, other, expected) in cases {
+            for op in [Operator::Eq, Operator::Lt] {
+                assert_eq!(coerce_types(&unsigned, &op, &other)?, expected);
+                assert_eq!(coerce_types(&other, &op, &unsigned)?, expected);
+            }
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_decimal_binary_comparison_coercion() -> Result<()> {
         let input_decimal = DataType::Decimal(20, 3);
@@ -745,5 +800,55 @@ mod tests {
         let lhs_type = Utf8;
         let rhs_type = Dictionary(Box::new(Int8), Box::new(Utf8));
         assert_eq!(dictionary_coercion(&lhs_type, &rhs_type), Some(Utf8));
+
+        let lhs_type = Dictionary(Box::new(Int32), Box::new(Decimal(10, 2)));
+        let rhs_type = Int64;
+        assert_eq!(
+            dictionary_coercion(&lhs_type, &rhs_type),
+            Some(Decimal(22, 2))
+        );
+
+        let lhs_type = Dictionary(Box::new(UInt16), Box::new(LargeUtf8));
+        assert_eq!(
+            coerce_types(&lhs_type, &Operator::Like, &Utf8).unwrap(),
+            LargeUtf8
+        );
+
+        let rhs_type = Dictionary(Box::new(UInt16), Box::new(UInt32));
+        assert_eq!(
+            coerce_types(&Int64, &Operator::LtEq, &rhs_type).unwrap(),
+            Int64
+        );
+    }
+
+    #[test]
+    fn test_duration_coercion() -> Result<()> {
+        use arrow::datatypes::TimeUnit;
+
+        let seconds = DataType::Duration(TimeUnit::Second);
+        let millis = DataType::Duration(TimeUnit::Millisecond);
+        for op in [Operator::Eq, Operator::Lt, Operator::Plus, Operator::Minus] {
+            assert_eq!(coerce_types(&seconds, &op, &seconds)?, seconds);
+        }
+        assert_eq!(
+            binary_operator_data_type(&millis, &Operator::GtEq, &millis)?,
+            DataType::Boolean
+        );
+        assert!(coerce_types(&seconds, &Operator::Plus, &millis).is_err());
+        assert!(coerce_types(&seconds, &Operator::Plus, &DataType::Int64).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_timestamp_string_coercion() -> Result<()> {
+        use arrow::datatypes::TimeUnit;
+
+        let millis = DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".to_owned()));
+        for op in [Operator::Eq, Operator::NotEq, Operator::Lt, Operator::GtEq] {
+            assert_eq!(coerce_types(&millis, &op, &DataType::Utf8)?, millis);
+            assert_eq!(coerce_types(&DataType::LargeUtf8, &op, &millis)?, millis);
+        }
+        assert!(coerce_types(&millis, &Operator::Plus, &DataType::Utf8).is_err());
+        Ok(())
     }
 }
@@ -66,10 +66,14 @@ pub enum BuiltinScalarFunction {
     Tan,
     /// trunc
     Trunc,
+    /// width_bucket
+    WidthBucket,
 
     // string functions
     /// construct an array from columns
     Array,
+    /// array_contains
+    ArrayContains,
     /// ascii
     Ascii,
     /// bit_length
@@ -80,6 +84,8 @@ pub enum BuiltinScalarFunction {
     CharacterLength,
     /// chr
     Chr,
+    /// collate
+    Collate,
     /// concat
     Concat,
     /// concat_ws
@@ -100,6 +106,10 @@ pub enum BuiltinScalarFunction {
     Ltrim,
     /// md5
     MD5,
+    /// nfc
+    Nfc,
+    /// nfd
+    Nfd,
     /// nullif
     NullIf,
     /// octet_length
@@ -152,6 +162,8 @@ pub enum BuiltinScalarFunction {
     Translate,
     /// trim
     Trim,
+    /// unaccent
+    Unaccent,
     /// upper
     Upper,
     /// regexp_match
@@ -190,11 +202,14 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::Sqrt => Volatility::Immutable,
             BuiltinScalarFunction::Tan => Volatility::Immutable,
             BuiltinScalarFunction::Trunc => Volatility::Immutable,
+            BuiltinScalarFunction::WidthBucket => Volatility::Immutable,
             BuiltinScalarFunction::Array => Volatility::Immutable,
+            BuiltinScalarFunction::ArrayContains => Volatility::Immutable,
             BuiltinScalarFunction::Ascii => Volatility::Immutable,
             BuiltinScalarFunction::BitLength => Volatility::Immutable,
             BuiltinScalarFunction::Btrim => Volatility::Immutable,
             BuiltinScalarFunction::CharacterLength => Volatility::Immutable,
+            BuiltinScalarFunction::Collate => Volatility::Immutable,
             BuiltinScalarFunction::Chr => Volatility::Immutable,
             BuiltinScalarFunction::Concat => Volatility::Immutable,
             BuiltinScalarFunction::ConcatWithSeparator => Volatility::Immutable,
@@ -206,6 +221,8 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::Lower => Volatility::Immutable,
             BuiltinScalarFunction::Ltrim => Volatility::Immutable,
             BuiltinScalarFunction::MD5 => Volatility::Immutable,
+            BuiltinScalarFunction::Nfc => Volatility::Immutable,
+            BuiltinScalarFunction::Nfd => Volatility::Immutable,
             BuiltinScalarFunction::NullIf => Volatility::Immutable,
             BuiltinScalarFunction::OctetLength => Volatility::Immutable,
             BuiltinScalarFunction::RegexpReplace => Volatility::Immutable,
@@ -231,6 +248,7 @@ impl BuiltinScalarFunction {
             BuiltinScalarFunction::ToTimestampSeconds => Volatility::Immutable,
             BuiltinScalarFunction::Translate => Volatility::Immutable,
             BuiltinScalarFunction::Trim => Volatility::Immutable,
+            BuiltinScalarFunction::Unaccent => Volatility::Immutable,
             BuiltinScalarFunction::Upper => Volatility::Immutable,
             BuiltinScalarFunction::RegexpMatch => Volatility::Immutable,
 
@@ -273,12 +291,14 @@ impl FromStr for BuiltinScalarFunction {
             "sqrt" => BuiltinScalarFunction::Sqrt,
             "tan" => BuiltinScalarFunction::Tan,
             "trunc" => BuiltinScalarFunction::Trunc,
+            "width_bucket" => BuiltinScalarFunction::WidthBucket,
 
             // conditional functions
             "coalesce" => BuiltinScalarFunction::Coalesce,
 
             // string functions
             "array" => BuiltinScalarFunction::Array,
+            "array_contains" => BuiltinScalarFunction::ArrayContains,
             "ascii" => BuiltinScalarFunction::Ascii,
             "bit_length" => BuiltinScalarFunction::BitLength,
             "btrim" => BuiltinScalarFunction::Btrim,
@@ -287,6 +307,7 @@ impl FromStr for BuiltinScalarFunction {
             "concat" => BuiltinScalarFunction::Concat,
             "concat_ws" => BuiltinScalarFunction::ConcatWithSeparator,
             "chr" => BuiltinScalarFunction::Chr,
+            "collate" => BuiltinScalarFunction::Collate,
             "date_part" | "datepart" => BuiltinScalarFunction::DatePart,
             "date_trunc" | "datetrunc" => BuiltinScalarFunction::DateTrunc,
             "initcap" => BuiltinScalarFunction::InitCap,
@@ -296,6 +317,8 @@ impl FromStr for BuiltinScalarFunction {
             "lpad" => BuiltinScalarFunction::Lpad,
             "ltrim" => BuiltinScalarFunction::Ltrim,
             "md5" => BuiltinScalarFunction::MD5,
+            "nfc" => BuiltinScalarFunction::Nfc,
+            "nfd" => BuiltinScalarFunction::Nfd,
             "nullif" => BuiltinScalarFunction::NullIf,
             "octet_length" => BuiltinScalarFunction::OctetLength,
             "random" => BuiltinScalarFunction::Random,
@@ -323,6 +346,7 @@ impl FromStr for BuiltinScalarFunction {
             "now" => BuiltinScalarFunction::Now,
             "translate" => BuiltinScalarFunction::Translate,
             "trim" => BuiltinScalarFunction::Trim,
+            "unaccent" => BuiltinScalarFunction::Unaccent,
             "upper" => BuiltinScalarFunction::Upper,
             "regexp_match" => BuiltinScalarFunction::RegexpMatch,
             _ => {
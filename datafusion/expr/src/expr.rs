@@ -106,7 +106,8 @@ pub enum Expr {
     IsNull(Box<Expr>),
     /// arithmetic negation of an expression, the operand must be of a signed numeric data type
     Negative(Box<Expr>),
-    /// Returns the field of a [`arrow::array::ListArray`] or [`arrow::array::StructArray`] by key
+    /// Returns the field of a [`arrow::array::ListArray`], [`arrow::array::StructArray`] or
+    /// [`arrow::array::MapArray`] by key
     GetIndexedField {
         /// the expression to take the field from
         expr: Box<Expr>,
@@ -157,6 +157,56 @@ pub fn approx_distinct(expr: Expr) -> Expr {
     }
 }
 
+/// Returns the `k` most frequent values of `expr` with their approximate
+/// number of occurrences, as a list of `{value, count}` structs.
+pub fn approx_top_k(expr: Expr, k: Expr) -> Expr {
+    Expr::AggregateFunction {
+        fun: aggregate_function::AggregateFunction::ApproxTopK,
+        distinct: false,
+        args: vec![expr, k],
+    }
+}
+
+/// Returns a histogram of `expr` with at most `bins` bins, as a list of
+/// `{lower, upper, count}` structs ordered by their boundaries.
+pub fn histogram(expr: Expr, bins: Expr) -> Expr {
+    Expr::AggregateFunction {
+        fun: aggregate_function::AggregateFunction::Histogram,
+        distinct: false,
+        args: vec![expr, bins],
+    }
+}
+
+/// Returns a HyperLogLog sketch of the distinct input values, as a binary value.
+/// Sketches can be stored and later combined with [`hll_union`] or [`hll_count`],
+/// e.g. to roll up pre-aggregated tables.
+pub fn hll_sketch(expr: Expr) -> Expr {
+    Expr::AggregateFunction {
+        fun: aggregate_function::AggregateFunction::HllSketch,
+        distinct: false,
+        args: vec![expr],
+    }
+}
+
+/// Returns the union of the HyperLogLog sketches produced by [`hll_sketch`].
+pub fn hll_union(sketch: Expr) -> Expr {
+    Expr::AggregateFunction {
+        fun: aggregate_function::AggregateFunction::HllUnion,
+        distinct: false,
+        args: vec![sketch],
+    }
+}
+
+/// Returns the approximate number of distinct values of the union of the
+/// HyperLogLog sketches produced by [`hll_sketch`].
+pub fn hll_count(sketch: Expr) -> Expr {
+    Expr::AggregateFunction {
+        fun: aggregate_function::AggregateFunction::HllCount,
+        distinct: false,
+        args: vec![sketch],
+    }
+}
+
 /// Calculate an approximation of the specified `percentile` for `expr`.
 pub fn approx_percentile_cont(expr: Expr, percentile: Expr) -> Expr {
     Expr::AggregateFunction {
@@ -239,6 +289,7 @@ unary_scalar_expr!(Exp, exp);
 unary_scalar_expr!(Log2, log2);
 unary_scalar_expr!(Log10, log10);
 unary_scalar_expr!(Ln, ln);
+scalar_expr!(WidthBucket, width_bucket, expr, low, high, count);
 unary_scalar_expr!(NullIf, nullif);
 
 // string functions
@@ -247,12 +298,15 @@ scalar_expr!(BitLength, bit_length, string);
 scalar_expr!(CharacterLength, character_length, string);
 scalar_expr!(CharacterLength, length, string);
 scalar_expr!(Chr, chr, string);
+scalar_expr!(Collate, collate, string, collation);
 scalar_expr!(Digest, digest, string, algorithm);
 scalar_expr!(InitCap, initcap, string);
 scalar_expr!(Left, left, string, count);
 scalar_expr!(Lower, lower, string);
 scalar_expr!(Ltrim, ltrim, string);
 scalar_expr!(MD5, md5, string);
+scalar_expr!(Nfc, nfc, string);
+scalar_expr!(Nfd, nfd, string);
 scalar_expr!(OctetLength, octet_length, string);
 scalar_expr!(Replace, replace, string, from, to);
 scalar_expr!(Repeat, repeat, string, count);
@@ -270,6 +324,7 @@ scalar_expr!(Substr, substr, string, position);
 scalar_expr!(ToHex, to_hex, string);
 scalar_expr!(Translate, translate, string, from, to);
 scalar_expr!(Trim, trim, string);
+scalar_expr!(Unaccent, unaccent, string);
 scalar_expr!(Upper, upper, string);
 //use vec as parameter
 nary_scalar_expr!(Lpad, lpad);
@@ -297,6 +352,14 @@ pub fn array(args: Vec<Expr>) -> Expr {
     }
 }
 
+/// Returns whether the list `array` contains `element`.
+pub fn array_contains(array: Expr, element: Expr) -> Expr {
+    Expr::ScalarFunction {
+        fun: built_in_function::BuiltinScalarFunction::ArrayContains,
+        args: vec![array, element],
+    }
+}
+
 /// Returns `coalesce(args...)`, which evaluates to the value of the first [Expr]
 /// which is not NULL
 pub fn coalesce(args: Vec<Expr>) -> Expr {
@@ -391,6 +454,7 @@ mod test {
         test_unary_scalar_expr!(Log2, log2);
         test_unary_scalar_expr!(Log10, log10);
         test_unary_scalar_expr!(Ln, ln);
+        test_scalar_expr!(WidthBucket, width_bucket, expr, low, high, count);
 
         test_scalar_expr!(Ascii, ascii, input);
         test_scalar_expr!(BitLength, bit_length, string);
@@ -399,6 +463,7 @@ mod test {
         test_scalar_expr!(CharacterLength, character_length, string);
         test_scalar_expr!(CharacterLength, length, string);
         test_scalar_expr!(Chr, chr, string);
+        test_scalar_expr!(Collate, collate, string, collation);
         test_scalar_expr!(Digest, digest, string, algorithm);
         test_scalar_expr!(InitCap, initcap, string);
         test_scalar_expr!(Left, left, string, count);
@@ -407,6 +472,8 @@ mod test {
         test_nary_scalar_expr!(Lpad, lpad, string, count, characters);
         test_scalar_expr!(Ltrim, ltrim, string);
         test_scalar_expr!(MD5, md5, string);
+        test_scalar_expr!(Nfc, nfc, string);
+        test_scalar_expr!(Nfd, nfd, string);
         test_scalar_expr!(OctetLength, octet_length, string);
         test_nary_scalar_expr!(RegexpMatch, regexp_match, string, pattern);
         test_nary_scalar_expr!(RegexpMatch, regexp_match, string, pattern, flags);
@@ -443,6 +510,7 @@ mod test {
         test_scalar_expr!(ToHex, to_hex, string);
         test_scalar_expr!(Translate, translate, string, from, to);
         test_scalar_expr!(Trim, trim, string);
+        test_scalar_expr!(Unaccent, unaccent, string);
         test_scalar_expr!(Upper, upper, string);
 
         test_scalar_expr!(DatePart, date_part, part, date);
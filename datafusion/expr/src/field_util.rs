@@ -17,13 +17,16 @@
 
 //! Utility functions for complex field access
 
+use arrow::compute::can_cast_types;
 use arrow::datatypes::{DataType, Field};
 use datafusion_common::{DataFusionError, Result, ScalarValue};
 
-/// Returns the field access indexed by `key` from a [`DataType::List`] or [`DataType::Struct`]
+/// Returns the field access indexed by `key` from a [`DataType::List`], [`DataType::Struct`]
+/// or [`DataType::Map`]: the 0-based index of an element of a list, the name of a field of a
+/// struct or the key of a map
 /// # Error
 /// Errors if
-/// * the `data_type` is not a Struct or,
+/// * the `data_type` is not a List, Struct or Map or,
 /// * there is no field key is not of the required index type
 pub fn get_indexed_field(data_type: &DataType, key: &ScalarValue) -> Result<Field> {
     match (data_type, key) {
@@ -34,7 +37,8 @@ pub fn get_indexed_field(data_type: &DataType, key: &ScalarValue) -> Result<Fiel
                     i
                 )))
             } else {
-                Ok(Field::new(&i.to_string(), lt.data_type().clone(), false))
+                // the elements past the end of the lists are null
+                Ok(Field::new(&i.to_string(), lt.data_type().clone(), true))
             }
         }
         (DataType::Struct(fields), ScalarValue::Utf8(Some(s))) => {
@@ -59,8 +63,36 @@ pub fn get_indexed_field(data_type: &DataType, key: &ScalarValue) -> Result<Fiel
         (DataType::List(_), _) => Err(DataFusionError::Plan(
             "Only ints are valid as an indexed field in a list".to_string(),
         )),
+        (DataType::Map(entries, _), _) => match entries.data_type() {
+            DataType::Struct(fields) if fields.len() == 2 => {
+                let key_type = key.get_datatype();
+                if key.is_null() {
+                    Err(DataFusionError::Plan(
+                        "Map based indexed access requires a non null key".to_string(),
+                    ))
+                } else if !can_cast_types(&key_type, fields[0].data_type()) {
+                    Err(DataFusionError::Plan(format!(
+                        "Map based indexed access requires a key of type {:?}, was {:?}",
+                        fields[0].data_type(),
+                        key_type
+                    )))
+                } else {
+                    // the keys missing from the maps are null
+                    Ok(Field::new(
+                        &key.to_string(),
+                        fields[1].data_type().clone(),
+                        true,
+                    ))
+                }
+            }
+            other => Err(DataFusionError::Internal(format!(
+                "The entries of a map must be a struct of a key and a value, was {:?}",
+                other
+            ))),
+        },
         _ => Err(DataFusionError::Plan(
-            "The expression to get an indexed field is only valid for `List` types"
+            "The expression to get an indexed field is only valid for `List`, `Struct` \
+             and `Map` types"
                 .to_string(),
         )),
     }
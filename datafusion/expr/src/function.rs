@@ -99,6 +99,7 @@ pub fn return_type(
             Box::new(Field::new("item", input_expr_types[0].clone(), true)),
             input_expr_types.len() as i32,
         )),
+        BuiltinScalarFunction::ArrayContains => Ok(DataType::Boolean),
         BuiltinScalarFunction::Ascii => Ok(DataType::Int32),
         BuiltinScalarFunction::BitLength => {
             utf8_to_int_type(&input_expr_types[0], "bit_length")
@@ -113,9 +114,12 @@ pub fn return_type(
             let coerced_types = data_types(input_expr_types, &signature(fun));
             coerced_types.map(|types| types[0].clone())
         }
+        BuiltinScalarFunction::Collate => {
+            utf8_to_str_type(&input_expr_types[0], "collate")
+        }
         BuiltinScalarFunction::Concat => Ok(DataType::Utf8),
         BuiltinScalarFunction::ConcatWithSeparator => Ok(DataType::Utf8),
-        BuiltinScalarFunction::DatePart => Ok(DataType::Int32),
+        BuiltinScalarFunction::DatePart => Ok(DataType::Float64),
         BuiltinScalarFunction::DateTrunc => {
             Ok(DataType::Timestamp(TimeUnit::Nanosecond, None))
         }
@@ -136,9 +140,12 @@ pub fn return_type(
             utf8_to_int_type(&input_expr_types[0], "octet_length")
         }
         BuiltinScalarFunction::Random => Ok(DataType::Float64),
+        BuiltinScalarFunction::WidthBucket => Ok(DataType::Int64),
         BuiltinScalarFunction::RegexpReplace => {
             utf8_to_str_type(&input_expr_types[0], "regex_replace")
         }
+        BuiltinScalarFunction::Nfc => utf8_to_str_type(&input_expr_types[0], "nfc"),
+        BuiltinScalarFunction::Nfd => utf8_to_str_type(&input_expr_types[0], "nfd"),
         BuiltinScalarFunction::Repeat => utf8_to_str_type(&input_expr_types[0], "repeat"),
         BuiltinScalarFunction::Replace => {
             utf8_to_str_type(&input_expr_types[0], "replace")
@@ -147,6 +154,9 @@ pub fn return_type(
             utf8_to_str_type(&input_expr_types[0], "reverse")
         }
         BuiltinScalarFunction::Right => utf8_to_str_type(&input_expr_types[0], "right"),
+        BuiltinScalarFunction::Unaccent => {
+            utf8_to_str_type(&input_expr_types[0], "unaccent")
+        }
         BuiltinScalarFunction::Rpad => utf8_to_str_type(&input_expr_types[0], "rpad"),
         BuiltinScalarFunction::Rtrim => utf8_to_str_type(&input_expr_types[0], "rtrimp"),
         BuiltinScalarFunction::SHA224 => {
@@ -251,6 +261,7 @@ pub fn signature(fun: &BuiltinScalarFunction) -> Signature {
             array_expressions::SUPPORTED_ARRAY_TYPES.to_vec(),
             fun.volatility(),
         ),
+        BuiltinScalarFunction::ArrayContains => Signature::any(2, fun.volatility()),
         BuiltinScalarFunction::Concat | BuiltinScalarFunction::ConcatWithSeparator => {
             Signature::variadic(vec![DataType::Utf8], fun.volatility())
         }
@@ -264,6 +275,8 @@ pub fn signature(fun: &BuiltinScalarFunction) -> Signature {
         | BuiltinScalarFunction::InitCap
         | BuiltinScalarFunction::Lower
         | BuiltinScalarFunction::MD5
+        | BuiltinScalarFunction::Nfc
+        | BuiltinScalarFunction::Nfd
         | BuiltinScalarFunction::OctetLength
         | BuiltinScalarFunction::Reverse
         | BuiltinScalarFunction::SHA224
@@ -271,6 +284,7 @@ pub fn signature(fun: &BuiltinScalarFunction) -> Signature {
         | BuiltinScalarFunction::SHA384
         | BuiltinScalarFunction::SHA512
         | BuiltinScalarFunction::Trim
+        | BuiltinScalarFunction::Unaccent
         | BuiltinScalarFunction::Upper => Signature::uniform(
             1,
             vec![DataType::Utf8, DataType::LargeUtf8],
@@ -288,6 +302,13 @@ pub fn signature(fun: &BuiltinScalarFunction) -> Signature {
         BuiltinScalarFunction::Chr | BuiltinScalarFunction::ToHex => {
             Signature::uniform(1, vec![DataType::Int64], fun.volatility())
         }
+        BuiltinScalarFunction::Collate => Signature::one_of(
+            vec![
+                TypeSignature::Exact(vec![DataType::Utf8, DataType::Utf8]),
+                TypeSignature::Exact(vec![DataType::LargeUtf8, DataType::Utf8]),
+            ],
+            fun.volatility(),
+        ),
         BuiltinScalarFunction::Lpad | BuiltinScalarFunction::Rpad => Signature::one_of(
             vec![
                 TypeSignature::Exact(vec![DataType::Utf8, DataType::Int64]),
@@ -505,6 +526,15 @@ pub fn signature(fun: &BuiltinScalarFunction) -> Signature {
             fun.volatility(),
         ),
         BuiltinScalarFunction::Random => Signature::exact(vec![], fun.volatility()),
+        BuiltinScalarFunction::WidthBucket => Signature::exact(
+            vec![
+                DataType::Float64,
+                DataType::Float64,
+                DataType::Float64,
+                DataType::Int64,
+            ],
+            fun.volatility(),
+        ),
         // math expressions expect 1 argument of type f64 or f32
         // priority is given to f64 because e.g. `sqrt(1i32)` is in IR (real numbers) and thus we
         // return the best approximation for it (in f64).
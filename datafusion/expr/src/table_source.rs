@@ -17,6 +17,7 @@
 
 use crate::Expr;
 use arrow::datatypes::SchemaRef;
+use datafusion_common::Constraints;
 use std::any::Any;
 
 ///! Table source
@@ -76,4 +77,9 @@ pub trait TableSource: Sync + Send {
     ) -> datafusion_common::Result<TableProviderFilterPushDown> {
         Ok(TableProviderFilterPushDown::Unsupported)
     }
+
+    /// Get the constraints declared on the columns of this table, if any
+    fn constraints(&self) -> Option<&Constraints> {
+        None
+    }
 }
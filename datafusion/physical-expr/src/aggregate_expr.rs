@@ -16,6 +16,7 @@
 // under the License.
 
 use crate::PhysicalExpr;
+use arrow::array::ArrayRef;
 use arrow::datatypes::Field;
 use datafusion_common::Result;
 use datafusion_expr::Accumulator;
@@ -54,4 +55,13 @@ pub trait AggregateExpr: Send + Sync + Debug {
     fn name(&self) -> &str {
         "AggregateExpr: default name"
     }
+
+    /// Returns the states of the aggregation of each row of `values`, the
+    /// evaluated `expressions`, on its own: one array per field of
+    /// `state_fields` with one row per input row. Returns `None` by default,
+    /// for the aggregations whose states can only be built by their
+    /// accumulators.
+    fn row_states(&self, _values: &[ArrayRef]) -> Result<Option<Vec<ArrayRef>>> {
+        Ok(None)
+    }
 }
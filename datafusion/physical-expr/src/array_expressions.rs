@@ -18,8 +18,9 @@
 //! Array expressions
 
 use arrow::array::*;
+use arrow::compute::cast;
 use arrow::datatypes::DataType;
-use datafusion_common::{DataFusionError, Result};
+use datafusion_common::{DataFusionError, Result, ScalarValue};
 use datafusion_expr::ColumnarValue;
 use std::sync::Arc;
 
@@ -105,3 +106,64 @@ pub fn array(values: &[ColumnarValue]) -> Result<ColumnarValue> {
 
     Ok(ColumnarValue::Array(array_array(&arrays)?))
 }
+
+/// Returns whether the lists of the first argument contain the values of the
+/// second one, or null when either of them is null.
+pub fn array_contains(args: &[ArrayRef]) -> Result<ArrayRef> {
+    if args.len() != 2 {
+        return Err(DataFusionError::Internal(format!(
+            "array_contains was called with {} arguments. It requires 2.",
+            args.len()
+        )));
+    }
+    let lists = &args[0];
+    let element_type = match lists.data_type() {
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::FixedSizeList(field, _) => field.data_type(),
+        data_type => {
+            return Err(DataFusionError::Plan(format!(
+                "array_contains requires a list as first argument, got {:?}",
+                data_type
+            )))
+        }
+    };
+    // the values are compared as scalars of the type of the elements
+    let elements =
+        if args[1].data_type() == element_type || element_type == &DataType::Null {
+            args[1].clone()
+        } else {
+            cast(&args[1], element_type)?
+        };
+
+    let contains = (0..lists.len())
+        .map(|row| {
+            if lists.is_null(row) || elements.is_null(row) {
+                return Ok(None);
+            }
+            let list = list_value(lists, row)?;
+            let element = ScalarValue::try_from_array(&elements, row)?;
+            for index in 0..list.len() {
+                if ScalarValue::try_from_array(&list, index)? == element {
+                    return Ok(Some(true));
+                }
+            }
+            Ok(Some(false))
+        })
+        .collect::<Result<BooleanArray>>()?;
+    Ok(Arc::new(contains))
+}
+
+/// The list at `row` of the list array `lists`
+fn list_value(lists: &ArrayRef, row: usize) -> Result<ArrayRef> {
+    let any = lists.as_any();
+    if let Some(lists) = any.downcast_ref::<ListArray>() {
+        Ok(lists.value(row))
+    } else if let Some(lists) = any.downcast_ref::<LargeListArray>() {
+        Ok(lists.value(row))
+    } else if let Some(lists) = any.downcast_ref::<FixedSizeListArray>() {
+        Ok(lists.value(row))
+    } else {
+        Err(DataFusionError::Internal("failed to downcast".to_string()))
+    }
+}
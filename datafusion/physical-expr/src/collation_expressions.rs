@@ -0,0 +1,268 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Collation expressions
+//!
+//! `collate(string, collation)` maps every string to a collation key: two
+//! strings compare, sort and group under the collation the same way as their
+//! keys do byte-wise, so the existing kernels honor the collation.
+//!
+//! The supported collations are:
+//! * `binary` (or `C`): byte-wise, the key is the string itself
+//! * `nocase`: case-insensitive, the key is the lowercase string
+//! * `nfc`: canonically equivalent strings (e.g. precomposed or decomposed
+//!   accents) are equal, the key is the string in Unicode Normalization Form
+//!   C. Requires the `unicode_expressions` feature
+//! * any ICU locale (e.g. `de_DE`), when compiled with the `icu` feature:
+//!   the key is the hex encoded ICU sort key of the string
+
+use arrow::array::{Array, ArrayRef, GenericStringArray, StringOffsetSizeTrait};
+use arrow::datatypes::DataType;
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+use datafusion_expr::ColumnarValue;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+/// The collation of the strings of a `collate` expression
+pub enum Collation {
+    /// Byte-wise comparison
+    Binary,
+    /// Case-insensitive comparison
+    NoCase,
+    /// Comparison of the canonical compositions
+    #[cfg(feature = "unicode_expressions")]
+    Nfc,
+    /// Locale-aware comparison
+    #[cfg(feature = "icu")]
+    Icu(rust_icu_ucol::UCollator),
+}
+
+impl Collation {
+    /// Creates the collation named `name`
+    pub fn try_new(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "binary" | "c" => Ok(Self::Binary),
+            "nocase" => Ok(Self::NoCase),
+            #[cfg(feature = "unicode_expressions")]
+            "nfc" => Ok(Self::Nfc),
+            _ => Self::try_new_icu(name),
+        }
+    }
+
+    #[cfg(feature = "icu")]
+    fn try_new_icu(name: &str) -> Result<Self> {
+        rust_icu_ucol::UCollator::try_from(name)
+            .map(Self::Icu)
+            .map_err(|e| {
+                DataFusionError::Plan(format!("Invalid collation '{}': {}", name, e))
+            })
+    }
+
+    #[cfg(not(feature = "icu"))]
+    fn try_new_icu(name: &str) -> Result<Self> {
+        Err(DataFusionError::Plan(format!(
+            "There is no collation named '{}', currently supported collations are: binary, nocase, nfc. \
+             Locale-aware collations require compilation with feature flag: icu.",
+            name
+        )))
+    }
+
+    /// Returns the collation key of `value`
+    pub fn key<'a>(&self, value: &'a str) -> Result<Cow<'a, str>> {
+        match self {
+            Self::Binary => Ok(Cow::Borrowed(value)),
+            Self::NoCase => Ok(Cow::Owned(value.to_lowercase())),
+            #[cfg(feature = "unicode_expressions")]
+            Self::Nfc => {
+                use unicode_normalization::{
+                    is_nfc_quick, IsNormalized, UnicodeNormalization,
+                };
+                match is_nfc_quick(value.chars()) {
+                    IsNormalized::Yes => Ok(Cow::Borrowed(value)),
+                    _ => Ok(Cow::Owned(value.nfc().collect())),
+                }
+            }
+            #[cfg(feature = "icu")]
+            Self::Icu(collator) => {
+                use std::fmt::Write;
+                let value = rust_icu_ustring::UChar::try_from(value).map_err(|e| {
+                    DataFusionError::Execution(format!(
+                        "Cannot compute the collation key of '{}': {}",
+                        value, e
+                    ))
+                })?;
+                // the hex encoding preserves the byte-wise order of the sort key
+                let sort_key = collator.get_sort_key(&value);
+                let mut key = String::with_capacity(2 * sort_key.len());
+                for byte in sort_key {
+                    write!(key, "{:02x}", byte).unwrap();
+                }
+                Ok(Cow::Owned(key))
+            }
+        }
+    }
+
+    fn keys<T: StringOffsetSizeTrait>(&self, array: &ArrayRef) -> Result<ArrayRef> {
+        let array = array
+            .as_any()
+            .downcast_ref::<GenericStringArray<T>>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "could not cast string to StringArray".to_string(),
+                )
+            })?;
+        let keys = array
+            .iter()
+            .map(|value| value.map(|value| self.key(value)).transpose())
+            .collect::<Result<GenericStringArray<T>>>()?;
+        Ok(Arc::new(keys))
+    }
+}
+
+/// Returns the collation key of the string, under the collation named by the
+/// second argument.
+/// collate('Abc', 'nocase') = 'abc'
+pub fn collate(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    if args.len() != 2 {
+        return Err(DataFusionError::Internal(format!(
+            "{:?} args were supplied but collate takes exactly two arguments",
+            args.len(),
+        )));
+    }
+    let collation = match &args[1] {
+        ColumnarValue::Scalar(
+            ScalarValue::Utf8(Some(name)) | ScalarValue::LargeUtf8(Some(name)),
+        ) => Collation::try_new(name),
+        ColumnarValue::Scalar(other) => Err(DataFusionError::Internal(format!(
+            "Unsupported data type {:?} for function collate",
+            other,
+        ))),
+        ColumnarValue::Array(_) => Err(DataFusionError::Internal(
+            "Collate using dynamically decided collation is not yet supported".into(),
+        )),
+    }?;
+    if let Collation::Binary = collation {
+        return Ok(args[0].clone());
+    }
+
+    match &args[0] {
+        ColumnarValue::Array(array) => match array.data_type() {
+            DataType::Utf8 => Ok(ColumnarValue::Array(collation.keys::<i32>(array)?)),
+            DataType::LargeUtf8 => {
+                Ok(ColumnarValue::Array(collation.keys::<i64>(array)?))
+            }
+            other => Err(DataFusionError::Internal(format!(
+                "Unsupported data type {:?} for function collate",
+                other,
+            ))),
+        },
+        ColumnarValue::Scalar(ScalarValue::Utf8(value)) => {
+            let key = value.as_deref().map(|v| collation.key(v)).transpose()?;
+            Ok(ColumnarValue::Scalar(ScalarValue::Utf8(
+                key.map(Cow::into_owned),
+            )))
+        }
+        ColumnarValue::Scalar(ScalarValue::LargeUtf8(value)) => {
+            let key = value.as_deref().map(|v| collation.key(v)).transpose()?;
+            Ok(ColumnarValue::Scalar(ScalarValue::LargeUtf8(
+                key.map(Cow::into_owned),
+            )))
+        }
+        ColumnarValue::Scalar(other) => Err(DataFusionError::Internal(format!(
+            "Unsupported data type {:?} for function collate",
+            other,
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{LargeStringArray, StringArray};
+
+    fn collate_array(array: ArrayRef, collation: &str) -> Result<ArrayRef> {
+        let args = [
+            ColumnarValue::Array(array),
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some(collation.to_owned()))),
+        ];
+        match collate(&args)? {
+            ColumnarValue::Array(array) => Ok(array),
+            ColumnarValue::Scalar(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn collate_nocase() -> Result<()> {
+        let array = Arc::new(StringArray::from(vec![Some("Abc"), None, Some("ÉTÉ")]));
+        let keys = collate_array(array.clone(), "NoCase")?;
+        let expected = StringArray::from(vec![Some("abc"), None, Some("été")]);
+        assert_eq!(keys.as_ref(), &expected as &dyn Array);
+
+        let keys = collate_array(array.clone(), "binary")?;
+        assert_eq!(keys.as_ref(), array.as_ref() as &dyn Array);
+
+        let array = Arc::new(LargeStringArray::from(vec![Some("aBC")]));
+        let keys = collate_array(array, "nocase")?;
+        let expected = LargeStringArray::from(vec![Some("abc")]);
+        assert_eq!(keys.as_ref(), &expected as &dyn Array);
+        Ok(())
+    }
+
+    #[test]
+    fn collate_scalar() -> Result<()> {
+        let args = [
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some("ABC".to_owned()))),
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some("nocase".to_owned()))),
+        ];
+        match collate(&args)? {
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some(key))) => assert_eq!(key, "abc"),
+            other => panic!("unexpected collation key {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "icu"))]
+    #[test]
+    fn unknown_collation() {
+        let array = Arc::new(StringArray::from(vec!["a"]));
+        let err = collate_array(array, "de_DE").unwrap_err().to_string();
+        assert!(err.contains("no collation named 'de_DE'"), "{}", err);
+    }
+
+    #[cfg(feature = "unicode_expressions")]
+    #[test]
+    fn collate_nfc() -> Result<()> {
+        let array = Arc::new(StringArray::from(vec!["Cre\u{300}me", "Crème"]));
+        let keys = collate_array(array, "nfc")?;
+        let expected = StringArray::from(vec!["Crème", "Crème"]);
+        assert_eq!(keys.as_ref(), &expected as &dyn Array);
+        Ok(())
+    }
+
+    #[cfg(feature = "icu")]
+    #[test]
+    fn collate_icu() -> Result<()> {
+        let array = Arc::new(StringArray::from(vec!["b", "Ä", "a", "z"]));
+        let keys = collate_array(array, "de_DE")?;
+        let keys = keys.as_any().downcast_ref::<StringArray>().unwrap();
+        // 'Ä' sorts between 'a' and 'b' in German, not after 'z'
+        assert!(keys.value(2) < keys.value(1));
+        assert!(keys.value(1) < keys.value(0));
+        assert!(keys.value(0) < keys.value(3));
+        Ok(())
+    }
+}
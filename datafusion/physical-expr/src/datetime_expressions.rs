@@ -18,7 +18,10 @@
 //! DateTime expressions
 
 use arrow::{
-    array::{Array, ArrayRef, GenericStringArray, PrimitiveArray, StringOffsetSizeTrait},
+    array::{
+        Array, ArrayRef, GenericStringArray, PrimitiveArray, StringArray,
+        StringOffsetSizeTrait,
+    },
     compute::kernels::cast_utils::string_to_timestamp_nanos,
     datatypes::{
         ArrowPrimitiveType, DataType, TimestampMicrosecondType, TimestampMillisecondType,
@@ -27,11 +30,11 @@ use arrow::{
 };
 use arrow::{
     array::{
-        Date32Array, Date64Array, TimestampMicrosecondArray, TimestampMillisecondArray,
-        TimestampNanosecondArray, TimestampSecondArray,
+        Date32Array, Date64Array, Float64Array, TimestampMicrosecondArray,
+        TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray,
     },
-    compute::kernels::temporal,
-    datatypes::TimeUnit,
+    compute::kernels::arity::unary,
+    datatypes::{ArrowNumericType, ArrowTemporalType, TimeUnit},
     temporal_conversions::timestamp_ns_to_datetime,
 };
 use chrono::prelude::*;
@@ -42,6 +45,79 @@ use datafusion_expr::ColumnarValue;
 use std::borrow::Borrow;
 use std::sync::Arc;
 
+/// Session settings used by the temporal functions.
+///
+/// Timestamps without a time zone are instants in UTC: the session time zone
+/// determines the local date and time they are truncated to, decomposed into
+/// and rendered as strings, and the instant strings without an explicit
+/// offset refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemporalSettings {
+    /// Time zone of the session, as a fixed offset from UTC
+    pub time_zone: FixedOffset,
+    /// First day of the week, for `date_trunc('week', ..)`
+    pub first_day_of_week: Weekday,
+}
+
+impl Default for TemporalSettings {
+    fn default() -> Self {
+        Self {
+            time_zone: FixedOffset::east(0),
+            first_day_of_week: Weekday::Mon,
+        }
+    }
+}
+
+impl TemporalSettings {
+    /// Whether the session time zone is UTC
+    pub fn is_utc(&self) -> bool {
+        self.time_zone.local_minus_utc() == 0
+    }
+
+    /// Offset of the session time zone from UTC, in nanoseconds
+    fn offset_nanos(&self) -> i64 {
+        self.time_zone.local_minus_utc() as i64 * 1_000_000_000
+    }
+}
+
+/// Parses a time zone setting: either `UTC`, `Z` or a fixed offset from UTC
+/// such as `+05:30`, `-0800` or `+01`. The named time zones, such as
+/// `Europe/Paris`, are not supported as their offsets depend on the date.
+pub fn parse_time_zone(time_zone: &str) -> Result<FixedOffset> {
+    let invalid = || {
+        DataFusionError::Plan(format!(
+            "Invalid time zone '{}', expected UTC or a fixed offset such as +05:30, \
+             the named time zones are not supported",
+            time_zone
+        ))
+    };
+    let trimmed = time_zone.trim();
+    if trimmed.eq_ignore_ascii_case("utc") || trimmed.eq_ignore_ascii_case("z") {
+        return Ok(FixedOffset::east(0));
+    }
+
+    let sign = match trimmed.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Err(invalid()),
+    };
+    let digits = trimmed[1..].replacen(':', "", 1);
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (&digits[..], "0"),
+        4 => (&digits[..2], &digits[2..]),
+        _ => return Err(invalid()),
+    };
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 23 || minutes > 59 {
+        return Err(invalid());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
 /// given a function `op` that maps a `&str` to a Result of an arrow native type,
 /// returns a `PrimitiveArray` after the application
 /// of the function to `args[0]`.
@@ -130,40 +206,83 @@ fn string_to_timestamp_nanos_shim(s: &str) -> Result<i64> {
     string_to_timestamp_nanos(s).map_err(|e| e.into())
 }
 
+/// Returns true if `s` is parsed by `string_to_timestamp_nanos` with an
+/// explicit offset, rather than as a local date and time
+fn has_explicit_offset(s: &str) -> bool {
+    DateTime::parse_from_rfc3339(s).is_ok()
+        || DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%:z").is_ok()
+}
+
+/// Parses `s` as a timestamp in nanoseconds, strings without an explicit
+/// offset being local to the session time zone
+pub(crate) fn string_to_timestamp_nanos_in(
+    s: &str,
+    settings: &TemporalSettings,
+) -> Result<i64> {
+    let nanos = string_to_timestamp_nanos_shim(s)?;
+    if settings.offset_nanos() == 0 || has_explicit_offset(s) {
+        Ok(nanos)
+    } else {
+        Ok(nanos - settings.offset_nanos())
+    }
+}
+
+/// Create an implementation of the `to_timestamp` SQL functions for strings,
+/// producing timestamps of the given unit and parsing strings without an
+/// explicit offset in the session time zone.
+pub fn make_to_timestamp(
+    unit: TimeUnit,
+    settings: TemporalSettings,
+) -> impl Fn(&[ColumnarValue]) -> Result<ColumnarValue> {
+    move |args| {
+        let parse = |s: &str| string_to_timestamp_nanos_in(s, &settings);
+        match unit {
+            TimeUnit::Nanosecond => handle::<
+                TimestampNanosecondType,
+                _,
+                TimestampNanosecondType,
+            >(args, parse, "to_timestamp"),
+            TimeUnit::Microsecond => {
+                handle::<TimestampMicrosecondType, _, TimestampMicrosecondType>(
+                    args,
+                    |s| parse(s).map(|n| n / 1_000),
+                    "to_timestamp_micros",
+                )
+            }
+            TimeUnit::Millisecond => {
+                handle::<TimestampMillisecondType, _, TimestampMillisecondType>(
+                    args,
+                    |s| parse(s).map(|n| n / 1_000_000),
+                    "to_timestamp_millis",
+                )
+            }
+            TimeUnit::Second => handle::<TimestampSecondType, _, TimestampSecondType>(
+                args,
+                |s| parse(s).map(|n| n / 1_000_000_000),
+                "to_timestamp_seconds",
+            ),
+        }
+    }
+}
+
 /// to_timestamp SQL function
 pub fn to_timestamp(args: &[ColumnarValue]) -> Result<ColumnarValue> {
-    handle::<TimestampNanosecondType, _, TimestampNanosecondType>(
-        args,
-        string_to_timestamp_nanos_shim,
-        "to_timestamp",
-    )
+    make_to_timestamp(TimeUnit::Nanosecond, TemporalSettings::default())(args)
 }
 
 /// to_timestamp_millis SQL function
 pub fn to_timestamp_millis(args: &[ColumnarValue]) -> Result<ColumnarValue> {
-    handle::<TimestampMillisecondType, _, TimestampMillisecondType>(
-        args,
-        |s| string_to_timestamp_nanos_shim(s).map(|n| n / 1_000_000),
-        "to_timestamp_millis",
-    )
+    make_to_timestamp(TimeUnit::Millisecond, TemporalSettings::default())(args)
 }
 
 /// to_timestamp_micros SQL function
 pub fn to_timestamp_micros(args: &[ColumnarValue]) -> Result<ColumnarValue> {
-    handle::<TimestampMicrosecondType, _, TimestampMicrosecondType>(
-        args,
-        |s| string_to_timestamp_nanos_shim(s).map(|n| n / 1_000),
-        "to_timestamp_micros",
-    )
+    make_to_timestamp(TimeUnit::Microsecond, TemporalSettings::default())(args)
 }
 
 /// to_timestamp_seconds SQL function
 pub fn to_timestamp_seconds(args: &[ColumnarValue]) -> Result<ColumnarValue> {
-    handle::<TimestampSecondType, _, TimestampSecondType>(
-        args,
-        |s| string_to_timestamp_nanos_shim(s).map(|n| n / 1_000_000_000),
-        "to_timestamp_seconds",
-    )
+    make_to_timestamp(TimeUnit::Second, TemporalSettings::default())(args)
 }
 
 /// Create an implementation of `now()` that always returns the
@@ -188,8 +307,14 @@ fn quarter_month(date: &NaiveDateTime) -> u32 {
     1 + 3 * ((date.month() - 1) / 3)
 }
 
-fn date_trunc_single(granularity: &str, value: i64) -> Result<i64> {
-    let value = timestamp_ns_to_datetime(value).with_nanosecond(0);
+fn date_trunc_single(
+    granularity: &str,
+    value: i64,
+    settings: &TemporalSettings,
+) -> Result<i64> {
+    // truncate the local date and time
+    let offset_nanos = settings.offset_nanos();
+    let value = timestamp_ns_to_datetime(value + offset_nanos).with_nanosecond(0);
     let value = match granularity {
         "second" => value,
         "minute" => value.and_then(|d| d.with_second(0)),
@@ -204,7 +329,7 @@ fn date_trunc_single(granularity: &str, value: i64) -> Result<i64> {
             .and_then(|d| d.with_second(0))
             .and_then(|d| d.with_minute(0))
             .and_then(|d| d.with_hour(0))
-            .map(|d| d - Duration::seconds(60 * 60 * 24 * d.weekday() as i64)),
+            .map(|d| d - Duration::days(days_since_week_start(&d, settings))),
         "month" => value
             .and_then(|d| d.with_second(0))
             .and_then(|d| d.with_minute(0))
@@ -230,11 +355,32 @@ fn date_trunc_single(granularity: &str, value: i64) -> Result<i64> {
         }
     };
     // `with_x(0)` are infalible because `0` are always a valid
-    Ok(value.unwrap().timestamp_nanos())
+    Ok(value.unwrap().timestamp_nanos() - offset_nanos)
+}
+
+/// Number of days between the first day of the week of `date` and `date`
+fn days_since_week_start(date: &NaiveDateTime, settings: &TemporalSettings) -> i64 {
+    let first_day = settings.first_day_of_week.num_days_from_monday();
+    ((date.weekday().num_days_from_monday() + 7 - first_day) % 7) as i64
+}
+
+/// Create an implementation of `date_trunc()` truncating timestamps in the
+/// session time zone, weeks starting on the session first day of the week.
+pub fn make_date_trunc(
+    settings: TemporalSettings,
+) -> impl Fn(&[ColumnarValue]) -> Result<ColumnarValue> {
+    move |args| date_trunc_with_settings(args, &settings)
 }
 
 /// date_trunc SQL function
 pub fn date_trunc(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    date_trunc_with_settings(args, &TemporalSettings::default())
+}
+
+fn date_trunc_with_settings(
+    args: &[ColumnarValue],
+    settings: &TemporalSettings,
+) -> Result<ColumnarValue> {
     let (granularity, array) = (&args[0], &args[1]);
 
     let granularity =
@@ -246,7 +392,10 @@ pub fn date_trunc(args: &[ColumnarValue]) -> Result<ColumnarValue> {
             ));
         };
 
-    let f = |x: Option<i64>| x.map(|x| date_trunc_single(granularity, x)).transpose();
+    let f = |x: Option<i64>| {
+        x.map(|x| date_trunc_single(granularity, x, settings))
+            .transpose()
+    };
 
     Ok(match array {
         ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(v, tz_opt)) => {
@@ -276,15 +425,15 @@ pub fn date_trunc(args: &[ColumnarValue]) -> Result<ColumnarValue> {
 }
 
 macro_rules! extract_date_part {
-    ($ARRAY: expr, $FN:expr) => {
+    ($ARRAY: expr, $FN:expr $(, $ARG:expr)*) => {
         match $ARRAY.data_type() {
             DataType::Date32 => {
                 let array = $ARRAY.as_any().downcast_ref::<Date32Array>().unwrap();
-                Ok($FN(array)?)
+                Ok($FN(array $(, $ARG)*)?)
             }
             DataType::Date64 => {
                 let array = $ARRAY.as_any().downcast_ref::<Date64Array>().unwrap();
-                Ok($FN(array)?)
+                Ok($FN(array $(, $ARG)*)?)
             }
             DataType::Timestamp(time_unit, None) => match time_unit {
                 TimeUnit::Second => {
@@ -292,28 +441,28 @@ macro_rules! extract_date_part {
                         .as_any()
                         .downcast_ref::<TimestampSecondArray>()
                         .unwrap();
-                    Ok($FN(array)?)
+                    Ok($FN(array $(, $ARG)*)?)
                 }
                 TimeUnit::Millisecond => {
                     let array = $ARRAY
                         .as_any()
                         .downcast_ref::<TimestampMillisecondArray>()
                         .unwrap();
-                    Ok($FN(array)?)
+                    Ok($FN(array $(, $ARG)*)?)
                 }
                 TimeUnit::Microsecond => {
                     let array = $ARRAY
                         .as_any()
                         .downcast_ref::<TimestampMicrosecondArray>()
                         .unwrap();
-                    Ok($FN(array)?)
+                    Ok($FN(array $(, $ARG)*)?)
                 }
                 TimeUnit::Nanosecond => {
                     let array = $ARRAY
                         .as_any()
                         .downcast_ref::<TimestampNanosecondArray>()
                         .unwrap();
-                    Ok($FN(array)?)
+                    Ok($FN(array $(, $ARG)*)?)
                 }
             },
             datatype => Err(DataFusionError::Internal(format!(
@@ -324,8 +473,143 @@ macro_rules! extract_date_part {
     };
 }
 
+/// The values of a date part of the values of `array`, computed from their
+/// date and time by `part`
+fn date_part_values<T>(
+    array: &PrimitiveArray<T>,
+    part: &dyn Fn(&NaiveDateTime) -> f64,
+) -> Result<Float64Array>
+where
+    T: ArrowTemporalType + ArrowNumericType,
+    i64: From<T::Native>,
+{
+    Ok((0..array.len())
+        .map(|i| {
+            if array.is_null(i) {
+                return None;
+            }
+            array.value_as_datetime(i).map(|d| part(&d))
+        })
+        .collect())
+}
+
+/// Nanoseconds of the minute of `d`, including the seconds
+fn nanos_of_minute(d: &NaiveDateTime) -> i64 {
+    d.second() as i64 * 1_000_000_000 + d.nanosecond() as i64
+}
+
+/// Returns the function computing the date part named `date_part` (e.g.
+/// `year`) of a date and time
+fn date_part_fn(date_part: &str) -> Result<Box<dyn Fn(&NaiveDateTime) -> f64>> {
+    Ok(match date_part.to_lowercase().as_str() {
+        "millennium" | "millennia" => Box::new(|d| {
+            let year = d.year();
+            if year > 0 {
+                ((year + 999) / 1000) as f64
+            } else {
+                -((999 - year) / 1000) as f64
+            }
+        }),
+        "century" | "centuries" => Box::new(|d| {
+            let year = d.year();
+            if year > 0 {
+                ((year + 99) / 100) as f64
+            } else {
+                -((99 - year) / 100) as f64
+            }
+        }),
+        "decade" | "decades" => Box::new(|d| d.year().div_euclid(10) as f64),
+        "year" | "years" => Box::new(|d| d.year() as f64),
+        // the year of the ISO 8601 week
+        "isoyear" => Box::new(|d| d.iso_week().year() as f64),
+        "quarter" | "quarters" => Box::new(|d| (d.month0() / 3 + 1) as f64),
+        "month" | "months" => Box::new(|d| d.month() as f64),
+        // the ISO 8601 week, starting on Monday
+        "week" | "weeks" => Box::new(|d| d.iso_week().week() as f64),
+        "day" | "days" => Box::new(|d| d.day() as f64),
+        "doy" => Box::new(|d| d.ordinal() as f64),
+        // from 0 for Sunday to 6 for Saturday, as in PostgreSQL
+        "dow" => Box::new(|d| d.weekday().num_days_from_sunday() as f64),
+        // from 1 for Monday to 7 for Sunday
+        "isodow" => Box::new(|d| d.weekday().number_from_monday() as f64),
+        "hour" | "hours" => Box::new(|d| d.hour() as f64),
+        "minute" | "minutes" => Box::new(|d| d.minute() as f64),
+        // the seconds, milliseconds, ... include the fractional seconds
+        "second" | "seconds" => Box::new(|d| nanos_of_minute(d) as f64 / 1e9),
+        "millisecond" | "milliseconds" => Box::new(|d| nanos_of_minute(d) as f64 / 1e6),
+        "microsecond" | "microseconds" => Box::new(|d| nanos_of_minute(d) as f64 / 1e3),
+        "nanosecond" | "nanoseconds" => Box::new(|d| nanos_of_minute(d) as f64),
+        // the seconds since 1970-01-01T00:00:00Z
+        "epoch" => {
+            Box::new(|d| d.timestamp() as f64 + d.timestamp_subsec_nanos() as f64 / 1e9)
+        }
+        _ => {
+            return Err(DataFusionError::Execution(format!(
+                "Date part '{}' not supported",
+                date_part
+            )))
+        }
+    })
+}
+
+/// Shifts the timestamps of `array` to the local date and time of the session
+/// time zone. Dates have no time zone and are returned unchanged.
+fn to_local_time(array: ArrayRef, settings: &TemporalSettings) -> ArrayRef {
+    let offset_seconds = settings.time_zone.local_minus_utc() as i64;
+    if offset_seconds == 0 {
+        return array;
+    }
+    macro_rules! shift {
+        ($ARRAY_TYPE:ident, $TYPE:ident, $UNITS_PER_SECOND:expr) => {{
+            let array = array.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+            Arc::new(unary::<_, _, $TYPE>(array, |v| {
+                v + offset_seconds * $UNITS_PER_SECOND
+            })) as ArrayRef
+        }};
+    }
+    let data_type = array.data_type().clone();
+    match data_type {
+        DataType::Timestamp(TimeUnit::Second, None) => {
+            shift!(TimestampSecondArray, TimestampSecondType, 1)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, None) => {
+            shift!(TimestampMillisecondArray, TimestampMillisecondType, 1_000)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, None) => {
+            shift!(
+                TimestampMicrosecondArray,
+                TimestampMicrosecondType,
+                1_000_000
+            )
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+            shift!(
+                TimestampNanosecondArray,
+                TimestampNanosecondType,
+                1_000_000_000
+            )
+        }
+        _ => array,
+    }
+}
+
+/// Create an implementation of `date_part()` decomposing timestamps in the
+/// session time zone.
+pub fn make_date_part(
+    settings: TemporalSettings,
+) -> impl Fn(&[ColumnarValue]) -> Result<ColumnarValue> {
+    move |args| date_part_with_settings(args, &settings)
+}
+
 /// DATE_PART SQL function
 pub fn date_part(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    date_part_with_settings(args, &TemporalSettings::default())
+}
+
+fn date_part_with_settings(
+    args: &[ColumnarValue],
+    settings: &TemporalSettings,
+) -> Result<ColumnarValue> {
     if args.len() != 2 {
         return Err(DataFusionError::Execution(
             "Expected two arguments in DATE_PART".to_string(),
@@ -347,20 +631,15 @@ pub fn date_part(args: &[ColumnarValue]) -> Result<ColumnarValue> {
         ColumnarValue::Array(array) => array.clone(),
         ColumnarValue::Scalar(scalar) => scalar.to_array(),
     };
+    // the epoch is the same in all time zones
+    let array = if date_part.eq_ignore_ascii_case("epoch") {
+        array
+    } else {
+        to_local_time(array, settings)
+    };
 
-    let arr = match date_part.to_lowercase().as_str() {
-        "year" => extract_date_part!(array, temporal::year),
-        "month" => extract_date_part!(array, temporal::month),
-        "week" => extract_date_part!(array, temporal::week),
-        "day" => extract_date_part!(array, temporal::day),
-        "hour" => extract_date_part!(array, temporal::hour),
-        "minute" => extract_date_part!(array, temporal::minute),
-        "second" => extract_date_part!(array, temporal::second),
-        _ => Err(DataFusionError::Execution(format!(
-            "Date part '{}' not supported",
-            date_part
-        ))),
-    }?;
+    let part = date_part_fn(date_part)?;
+    let arr = extract_date_part!(array, date_part_values, part.as_ref())?;
 
     Ok(if is_scalar {
         ColumnarValue::Scalar(ScalarValue::try_from_array(
@@ -372,6 +651,80 @@ pub fn date_part(args: &[ColumnarValue]) -> Result<ColumnarValue> {
     })
 }
 
+/// Create an implementation of the casts of timestamps to strings rendering
+/// them in the session time zone followed by its offset, e.g.
+/// `2020-09-07 21:00:00-05:00`
+pub fn make_timestamp_to_string(
+    settings: TemporalSettings,
+) -> impl Fn(&[ColumnarValue]) -> Result<ColumnarValue> {
+    move |args| match &args[0] {
+        ColumnarValue::Array(array) => Ok(ColumnarValue::Array(Arc::new(
+            timestamp_to_string(array.as_ref(), &settings)?,
+        ))),
+        ColumnarValue::Scalar(scalar) => {
+            let array = timestamp_to_string(scalar.to_array().as_ref(), &settings)?;
+            Ok(ColumnarValue::Scalar(ScalarValue::try_from_array(
+                &(Arc::new(array) as ArrayRef),
+                0,
+            )?))
+        }
+    }
+}
+
+fn timestamp_to_string(
+    array: &dyn Array,
+    settings: &TemporalSettings,
+) -> Result<StringArray> {
+    let offset_seconds = settings.time_zone.local_minus_utc() as i64;
+    let time_zone = settings.time_zone.to_string();
+    macro_rules! render {
+        ($ARRAY_TYPE:ident, $UNITS_PER_SECOND:expr) => {{
+            let array = array.as_any().downcast_ref::<$ARRAY_TYPE>().unwrap();
+            array
+                .iter()
+                .map(|v| {
+                    v.map(|v| {
+                        let seconds = v.div_euclid($UNITS_PER_SECOND);
+                        let nanos = v.rem_euclid($UNITS_PER_SECOND)
+                            * (1_000_000_000 / $UNITS_PER_SECOND);
+                        seconds
+                            .checked_add(offset_seconds)
+                            .and_then(|seconds| {
+                                NaiveDateTime::from_timestamp_opt(seconds, nanos as u32)
+                            })
+                            .map(|local| format!("{}{}", local, time_zone))
+                            .ok_or_else(|| {
+                                DataFusionError::Execution(format!(
+                                    "Timestamp {} out of range",
+                                    v
+                                ))
+                            })
+                    })
+                    .transpose()
+                })
+                .collect::<Result<StringArray>>()
+        }};
+    }
+    match array.data_type() {
+        DataType::Timestamp(TimeUnit::Second, None) => {
+            render!(TimestampSecondArray, 1)
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, None) => {
+            render!(TimestampMillisecondArray, 1_000)
+        }
+        DataType::Timestamp(TimeUnit::Microsecond, None) => {
+            render!(TimestampMicrosecondArray, 1_000_000)
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+            render!(TimestampNanosecondArray, 1_000_000_000)
+        }
+        other => Err(DataFusionError::Internal(format!(
+            "Cannot render {:?} as a timestamp string",
+            other
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -497,11 +850,157 @@ mod tests {
         cases.iter().for_each(|(original, granularity, expected)| {
             let left = string_to_timestamp_nanos(original).unwrap();
             let right = string_to_timestamp_nanos(expected).unwrap();
-            let result = date_trunc_single(granularity, left).unwrap();
+            let result =
+                date_trunc_single(granularity, left, &TemporalSettings::default())
+                    .unwrap();
+            assert_eq!(result, right, "{} = {}", original, expected);
+        });
+    }
+
+    #[test]
+    fn date_trunc_with_session_settings() {
+        let settings = TemporalSettings {
+            time_zone: FixedOffset::east(-5 * 3600),
+            first_day_of_week: Weekday::Sun,
+        };
+        let cases = vec![
+            // 02:00 UTC is still the previous day at -05:00
+            ("2020-09-08T02:42:29Z", "day", "2020-09-07T05:00:00Z"),
+            ("2020-09-08T02:42:29Z", "hour", "2020-09-08T02:00:00Z"),
+            // weeks start on Sunday 2020-09-06 at -05:00
+            ("2020-09-08T13:42:29Z", "week", "2020-09-06T05:00:00Z"),
+            ("2020-09-06T13:42:29Z", "week", "2020-09-06T05:00:00Z"),
+        ];
+        cases.iter().for_each(|(original, granularity, expected)| {
+            let left = string_to_timestamp_nanos(original).unwrap();
+            let right = string_to_timestamp_nanos(expected).unwrap();
+            let result = date_trunc_single(granularity, left, &settings).unwrap();
             assert_eq!(result, right, "{} = {}", original, expected);
         });
     }
 
+    #[test]
+    fn to_timestamp_in_session_time_zone() -> Result<()> {
+        let settings = TemporalSettings {
+            time_zone: parse_time_zone("+02:00")?,
+            ..Default::default()
+        };
+        let to_timestamp = make_to_timestamp(TimeUnit::Second, settings);
+        let parse = |s: &str| -> Result<ScalarValue> {
+            match to_timestamp(&[ColumnarValue::Scalar(ScalarValue::from(s))])? {
+                ColumnarValue::Scalar(scalar) => Ok(scalar),
+                _ => panic!("Expected a scalar"),
+            }
+        };
+        // local to the session time zone
+        assert_eq!(
+            parse("2020-09-08 02:00:00")?,
+            ScalarValue::TimestampSecond(Some(1599523200), None)
+        );
+        // explicit offsets are kept
+        assert_eq!(
+            parse("2020-09-08T02:00:00Z")?,
+            ScalarValue::TimestampSecond(Some(1599530400), None)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn date_part_in_session_time_zone() -> Result<()> {
+        let settings = TemporalSettings {
+            time_zone: parse_time_zone("-0500")?,
+            first_day_of_week: Weekday::Sun,
+        };
+        // 2020-09-08T02:00:00Z is Monday 2020-09-07 21:00 at -05:00
+        let timestamp = ColumnarValue::Scalar(ScalarValue::TimestampNanosecond(
+            Some(1599530400000000000),
+            None,
+        ));
+        let date_part = make_date_part(settings);
+        let extract = |part: &str| -> Result<ScalarValue> {
+            match date_part(&[
+                ColumnarValue::Scalar(ScalarValue::from(part)),
+                timestamp.clone(),
+            ])? {
+                ColumnarValue::Scalar(scalar) => Ok(scalar),
+                _ => panic!("Expected a scalar"),
+            }
+        };
+        assert_eq!(extract("hour")?, ScalarValue::Float64(Some(21.0)));
+        assert_eq!(extract("day")?, ScalarValue::Float64(Some(7.0)));
+        // Sunday is 0
+        assert_eq!(extract("dow")?, ScalarValue::Float64(Some(1.0)));
+        assert_eq!(extract("isodow")?, ScalarValue::Float64(Some(1.0)));
+        // the epoch is not shifted to the session time zone
+        assert_eq!(extract("epoch")?, ScalarValue::Float64(Some(1599530400.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn dow_counts_from_sunday() -> Result<()> {
+        // Sunday 2020-09-06 to Saturday 2020-09-12, whatever the first day of
+        // the week of the session
+        let timestamps = ColumnarValue::Array(Arc::new(TimestampSecondArray::from(
+            (0..7)
+                .map(|day| 1599350400 + day * 86400)
+                .collect::<Vec<i64>>(),
+        )));
+        for first_day_of_week in [Weekday::Mon, Weekday::Sun] {
+            let settings = TemporalSettings {
+                first_day_of_week,
+                ..Default::default()
+            };
+            let dow = match make_date_part(settings)(&[
+                ColumnarValue::Scalar(ScalarValue::from("dow")),
+                timestamps.clone(),
+            ])? {
+                ColumnarValue::Array(array) => array,
+                _ => panic!("Expected an array"),
+            };
+            let expected: ArrayRef =
+                Arc::new(Float64Array::from(vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]));
+            assert_eq!(dow.as_ref(), expected.as_ref());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn timestamp_to_string_in_session_time_zone() -> Result<()> {
+        let settings = TemporalSettings {
+            time_zone: parse_time_zone("-05:00")?,
+            ..Default::default()
+        };
+        let timestamps =
+            ColumnarValue::Array(Arc::new(TimestampMillisecondArray::from(vec![
+                Some(1599530400500),
+                None,
+            ])));
+        let rendered = match make_timestamp_to_string(settings)(&[timestamps])? {
+            ColumnarValue::Array(array) => array,
+            _ => panic!("Expected an array"),
+        };
+        let expected: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("2020-09-07 21:00:00.500-05:00"),
+            None,
+        ]));
+        assert_eq!(rendered.as_ref(), expected.as_ref());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_time_zones() -> Result<()> {
+        assert_eq!(parse_time_zone("UTC")?, FixedOffset::east(0));
+        assert_eq!(parse_time_zone("+05:30")?, FixedOffset::east(19800));
+        assert_eq!(parse_time_zone("-08")?, FixedOffset::east(-28800));
+        let err = parse_time_zone("Europe/Paris").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Invalid time zone 'Europe/Paris', expected UTC or a fixed offset \
+             such as +05:30, the named time zones are not supported"
+        );
+        Ok(())
+    }
+
     #[test]
     fn to_timestamp_invalid_input_type() -> Result<()> {
         // pass the wrong type of input array to to_timestamp and test
@@ -84,30 +84,7 @@ impl AggregateExpr for ApproxDistinct {
     }
 
     fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
-        let accumulator: Box<dyn Accumulator> = match &self.input_data_type {
-            // TODO u8, i8, u16, i16 shall really be done using bitmap, not HLL
-            // TODO support for boolean (trivial case)
-            // https://github.com/apache/arrow-datafusion/issues/1109
-            DataType::UInt8 => Box::new(NumericHLLAccumulator::<UInt8Type>::new()),
-            DataType::UInt16 => Box::new(NumericHLLAccumulator::<UInt16Type>::new()),
-            DataType::UInt32 => Box::new(NumericHLLAccumulator::<UInt32Type>::new()),
-            DataType::UInt64 => Box::new(NumericHLLAccumulator::<UInt64Type>::new()),
-            DataType::Int8 => Box::new(NumericHLLAccumulator::<Int8Type>::new()),
-            DataType::Int16 => Box::new(NumericHLLAccumulator::<Int16Type>::new()),
-            DataType::Int32 => Box::new(NumericHLLAccumulator::<Int32Type>::new()),
-            DataType::Int64 => Box::new(NumericHLLAccumulator::<Int64Type>::new()),
-            DataType::Utf8 => Box::new(StringHLLAccumulator::<i32>::new()),
-            DataType::LargeUtf8 => Box::new(StringHLLAccumulator::<i64>::new()),
-            DataType::Binary => Box::new(BinaryHLLAccumulator::<i32>::new()),
-            DataType::LargeBinary => Box::new(BinaryHLLAccumulator::<i64>::new()),
-            other => {
-                return Err(DataFusionError::NotImplemented(format!(
-                    "Support for 'approx_distinct' for data type {} is not implemented",
-                    other
-                )))
-            }
-        };
-        Ok(accumulator)
+        hll_accumulator(&self.input_data_type, "approx_distinct")
     }
 
     fn name(&self) -> &str {
@@ -115,6 +92,39 @@ impl AggregateExpr for ApproxDistinct {
     }
 }
 
+/// Creates an accumulator adding the values of type `data_type` to a
+/// HyperLogLog, whose state is the binary registers of the HyperLogLog and
+/// which evaluates to its count. `fn_name` is only used in error messages.
+pub(crate) fn hll_accumulator(
+    data_type: &DataType,
+    fn_name: &str,
+) -> Result<Box<dyn Accumulator>> {
+    let accumulator: Box<dyn Accumulator> = match data_type {
+        // TODO u8, i8, u16, i16 shall really be done using bitmap, not HLL
+        // TODO support for boolean (trivial case)
+        // https://github.com/apache/arrow-datafusion/issues/1109
+        DataType::UInt8 => Box::new(NumericHLLAccumulator::<UInt8Type>::new()),
+        DataType::UInt16 => Box::new(NumericHLLAccumulator::<UInt16Type>::new()),
+        DataType::UInt32 => Box::new(NumericHLLAccumulator::<UInt32Type>::new()),
+        DataType::UInt64 => Box::new(NumericHLLAccumulator::<UInt64Type>::new()),
+        DataType::Int8 => Box::new(NumericHLLAccumulator::<Int8Type>::new()),
+        DataType::Int16 => Box::new(NumericHLLAccumulator::<Int16Type>::new()),
+        DataType::Int32 => Box::new(NumericHLLAccumulator::<Int32Type>::new()),
+        DataType::Int64 => Box::new(NumericHLLAccumulator::<Int64Type>::new()),
+        DataType::Utf8 => Box::new(StringHLLAccumulator::<i32>::new()),
+        DataType::LargeUtf8 => Box::new(StringHLLAccumulator::<i64>::new()),
+        DataType::Binary => Box::new(BinaryHLLAccumulator::<i32>::new()),
+        DataType::LargeBinary => Box::new(BinaryHLLAccumulator::<i64>::new()),
+        other => {
+            return Err(DataFusionError::NotImplemented(format!(
+                "Support for '{}' for data type {} is not implemented",
+                fn_name, other
+            )))
+        }
+    };
+    Ok(accumulator)
+}
+
 #[derive(Debug)]
 struct BinaryHLLAccumulator<T>
 where
@@ -183,20 +193,18 @@ where
 
 impl<T: Hash> From<&HyperLogLog<T>> for ScalarValue {
     fn from(v: &HyperLogLog<T>) -> ScalarValue {
-        let values = v.as_ref().to_vec();
-        ScalarValue::Binary(Some(values))
+        ScalarValue::Binary(Some(v.to_bytes()))
     }
 }
 
 impl<T: Hash> TryFrom<&[u8]> for HyperLogLog<T> {
     type Error = DataFusionError;
     fn try_from(v: &[u8]) -> Result<HyperLogLog<T>> {
-        let arr: [u8; 16384] = v.try_into().map_err(|_| {
+        HyperLogLog::<T>::try_new_with_registers(v).ok_or_else(|| {
             DataFusionError::Internal(
                 "Impossibly got invalid binary array from states".into(),
             )
-        })?;
-        Ok(HyperLogLog::<T>::new_with_registers(arr))
+        })
     }
 }
 
@@ -0,0 +1,415 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines physical expressions that can evaluated at runtime during query execution
+
+use super::{format_state_name, Literal};
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::ArrayRef;
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::ScalarValue;
+use datafusion_common::{DataFusionError, Result};
+use datafusion_expr::aggregate_function::approx_top_k_return_type;
+use datafusion_expr::Accumulator;
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Number of counters kept for each of the `k` requested values. The more
+/// counters, the more accurate the counts of the least frequent values.
+const COUNTERS_PER_VALUE: usize = 10;
+
+/// APPROX_TOP_K aggregate expression: the `k` most frequent values with their
+/// approximate number of occurrences, computed with the Space-Saving algorithm.
+#[derive(Debug)]
+pub struct ApproxTopK {
+    name: String,
+    input_data_type: DataType,
+    expr: Vec<Arc<dyn PhysicalExpr>>,
+    k: usize,
+}
+
+impl ApproxTopK {
+    /// Create a new [`ApproxTopK`] aggregate function.
+    pub fn new(
+        expr: Vec<Arc<dyn PhysicalExpr>>,
+        name: impl Into<String>,
+        input_data_type: DataType,
+    ) -> Result<Self> {
+        // Arguments should be [ColumnExpr, KLiteral]
+        debug_assert_eq!(expr.len(), 2);
+
+        let lit = expr[1]
+            .as_any()
+            .downcast_ref::<Literal>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "k argument of approx_top_k must be an integer literal".to_string(),
+                )
+            })?
+            .value();
+        let k = match lit {
+            ScalarValue::Int8(Some(k)) => *k as i64,
+            ScalarValue::Int16(Some(k)) => *k as i64,
+            ScalarValue::Int32(Some(k)) => *k as i64,
+            ScalarValue::Int64(Some(k)) => *k,
+            ScalarValue::UInt8(Some(k)) => *k as i64,
+            ScalarValue::UInt16(Some(k)) => *k as i64,
+            ScalarValue::UInt32(Some(k)) => *k as i64,
+            ScalarValue::UInt64(Some(k)) => *k as i64,
+            got => {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "k value for 'APPROX_TOP_K' must be an integer literal (got data type {})",
+                    got
+                )))
+            }
+        };
+        if k <= 0 {
+            return Err(DataFusionError::Plan(format!(
+                "k value for 'APPROX_TOP_K' must be greater than 0, {} is invalid",
+                k
+            )));
+        }
+
+        Ok(Self {
+            name: name.into(),
+            input_data_type,
+            expr,
+            k: k as usize,
+        })
+    }
+}
+
+impl AggregateExpr for ApproxTopK {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(
+            &self.name,
+            approx_top_k_return_type(&self.input_data_type),
+            false,
+        ))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![
+            Field::new(
+                &format_state_name(&self.name, "values"),
+                DataType::List(Box::new(Field::new(
+                    "item",
+                    self.input_data_type.clone(),
+                    true,
+                ))),
+                false,
+            ),
+            Field::new(
+                &format_state_name(&self.name, "counts"),
+                DataType::List(Box::new(Field::new("item", DataType::UInt64, true))),
+                false,
+            ),
+        ])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        self.expr.clone()
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(ApproxTopKAccumulator::new(
+            self.k,
+            self.input_data_type.clone(),
+        )))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug)]
+struct ApproxTopKAccumulator {
+    k: usize,
+    /// maximum number of counters
+    capacity: usize,
+    data_type: DataType,
+    /// the count and the id of the counter of each value
+    counters: HashMap<ScalarValue, (u64, u64)>,
+    /// the values of the counters by count and id, so that the least
+    /// frequent value is found without scanning all the counters
+    by_count: BTreeMap<(u64, u64), ScalarValue>,
+    /// the id of the next counter, the oldest of the least frequent values
+    /// is evicted first
+    next_id: u64,
+}
+
+impl ApproxTopKAccumulator {
+    fn new(k: usize, data_type: DataType) -> Self {
+        Self {
+            k,
+            capacity: k.saturating_mul(COUNTERS_PER_VALUE),
+            data_type,
+            counters: HashMap::new(),
+            by_count: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Adds `count` occurrences of `value`. Once all the counters are in use,
+    /// the least frequent value is evicted and the new value inherits its
+    /// count, so that counts are overestimated by at most the evicted count.
+    fn add(&mut self, value: ScalarValue, count: u64) {
+        if let Some((current, id)) = self.counters.get_mut(&value) {
+            let value = self
+                .by_count
+                .remove(&(*current, *id))
+                .expect("counters are indexed by count");
+            *current += count;
+            self.by_count.insert((*current, *id), value);
+            return;
+        }
+
+        let mut new_count = count;
+        if self.counters.len() >= self.capacity {
+            let min_key = *self
+                .by_count
+                .keys()
+                .next()
+                .expect("capacity is greater than 0");
+            let min_value = self.by_count.remove(&min_key).unwrap();
+            self.counters.remove(&min_value);
+            new_count += min_key.0;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.counters.insert(value.clone(), (new_count, id));
+        self.by_count.insert((new_count, id), value);
+    }
+
+    /// The counters, most frequent first
+    fn sorted_counters(&self) -> Vec<(&ScalarValue, u64)> {
+        let mut counters = self
+            .counters
+            .iter()
+            .map(|(value, (count, _))| (value, *count))
+            .collect::<Vec<_>>();
+        counters.sort_by(|(v1, c1), (v2, c2)| {
+            c2.cmp(c1)
+                .then_with(|| v1.partial_cmp(v2).unwrap_or(Ordering::Equal))
+        });
+        counters
+    }
+}
+
+impl Accumulator for ApproxTopKAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        // values[1] is the k literal
+        let array = &values[0];
+        // count the values of the batch first, so that frequent values of the
+        // batch are not evicted by the infrequent ones
+        let mut batch_counts: HashMap<ScalarValue, u64> = HashMap::new();
+        for index in 0..array.len() {
+            if array.is_valid(index) {
+                let value = ScalarValue::try_from_array(array, index)?;
+                *batch_counts.entry(value).or_default() += 1;
+            }
+        }
+        let mut batch_counts = batch_counts.into_iter().collect::<Vec<_>>();
+        batch_counts.sort_by(|(_, c1), (_, c2)| c2.cmp(c1));
+        for (value, count) in batch_counts {
+            self.add(value, count);
+        }
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        for index in 0..states[0].len() {
+            let values = ScalarValue::try_from_array(&states[0], index)?;
+            let counts = ScalarValue::try_from_array(&states[1], index)?;
+            match (values, counts) {
+                (
+                    ScalarValue::List(Some(values), _),
+                    ScalarValue::List(Some(counts), _),
+                ) => {
+                    for (value, count) in values.into_iter().zip(counts.into_iter()) {
+                        match count {
+                            ScalarValue::UInt64(Some(count)) => self.add(value, count),
+                            other => {
+                                return Err(DataFusionError::Internal(format!(
+                                    "approx_top_k count state must be UInt64, got {:?}",
+                                    other
+                                )))
+                            }
+                        }
+                    }
+                }
+                _ => {
+                    return Err(DataFusionError::Internal(
+                        "approx_top_k states must be lists".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        let (values, counts): (Vec<_>, Vec<_>) = self
+            .counters
+            .iter()
+            .map(|(value, (count, _))| (value.clone(), ScalarValue::UInt64(Some(*count))))
+            .unzip();
+        Ok(vec![
+            ScalarValue::List(Some(Box::new(values)), Box::new(self.data_type.clone())),
+            ScalarValue::List(Some(Box::new(counts)), Box::new(DataType::UInt64)),
+        ])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        let fields = vec![
+            Field::new("value", self.data_type.clone(), true),
+            Field::new("count", DataType::UInt64, true),
+        ];
+        let items = self
+            .sorted_counters()
+            .into_iter()
+            .take(self.k)
+            .map(|(value, count)| {
+                ScalarValue::Struct(
+                    Some(Box::new(vec![
+                        value.clone(),
+                        ScalarValue::UInt64(Some(count)),
+                    ])),
+                    Box::new(fields.clone()),
+                )
+            })
+            .collect::<Vec<_>>();
+        Ok(ScalarValue::List(
+            Some(Box::new(items)),
+            Box::new(DataType::Struct(fields)),
+        ))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.counters.capacity() * std::mem::size_of::<(ScalarValue, (u64, u64))>()
+            + self.by_count.len() * std::mem::size_of::<((u64, u64), ScalarValue)>()
+            + 2 * self
+                .counters
+                .keys()
+                .map(|value| value.size() - std::mem::size_of_val(value))
+                .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expressions::{col, lit};
+    use arrow::array::StringArray;
+    use arrow::datatypes::Schema;
+
+    fn top_k_item(value: &str, count: u64) -> ScalarValue {
+        ScalarValue::Struct(
+            Some(Box::new(vec![
+                ScalarValue::from(value),
+                ScalarValue::UInt64(Some(count)),
+            ])),
+            Box::new(vec![
+                Field::new("value", DataType::Utf8, true),
+                Field::new("count", DataType::UInt64, true),
+            ]),
+        )
+    }
+
+    fn top_k(k: i64) -> Result<ApproxTopK> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        ApproxTopK::new(
+            vec![col("a", &schema)?, lit(ScalarValue::Int64(Some(k)))],
+            "top_k",
+            DataType::Utf8,
+        )
+    }
+
+    #[test]
+    fn approx_top_k_merge() -> Result<()> {
+        let agg = top_k(2)?;
+
+        let mut acc1 = agg.create_accumulator()?;
+        let a: ArrayRef = Arc::new(StringArray::from(vec![
+            Some("a"),
+            Some("b"),
+            Some("a"),
+            None,
+            Some("c"),
+        ]));
+        acc1.update_batch(&[a])?;
+
+        let mut acc2 = agg.create_accumulator()?;
+        let b: ArrayRef = Arc::new(StringArray::from(vec!["b", "b", "c"]));
+        acc2.update_batch(&[b])?;
+
+        // merge the partial states
+        let states = acc2
+            .state()?
+            .iter()
+            .map(|s| s.to_array())
+            .collect::<Vec<_>>();
+        acc1.merge_batch(&states)?;
+
+        let expected = ScalarValue::List(
+            Some(Box::new(vec![top_k_item("b", 3), top_k_item("a", 2)])),
+            Box::new(DataType::Struct(vec![
+                Field::new("value", DataType::Utf8, true),
+                Field::new("count", DataType::UInt64, true),
+            ])),
+        );
+        let result = acc1.evaluate()?;
+        assert_eq!(result, expected);
+        assert_eq!(&result.get_datatype(), agg.field()?.data_type());
+        Ok(())
+    }
+
+    #[test]
+    fn approx_top_k_eviction() -> Result<()> {
+        let mut acc = ApproxTopKAccumulator::new(1, DataType::Utf8);
+        for i in 0..COUNTERS_PER_VALUE {
+            acc.add(ScalarValue::from(format!("v{}", i).as_str()), 2);
+        }
+        acc.add(ScalarValue::from("v0"), 5);
+        // all counters are in use, a least frequent value is evicted
+        acc.add(ScalarValue::from("new"), 1);
+        assert_eq!(acc.counters.len(), COUNTERS_PER_VALUE);
+        assert_eq!(acc.counters[&ScalarValue::from("new")].0, 3);
+        // the oldest of the least frequent values
+        assert!(!acc.counters.contains_key(&ScalarValue::from("v1")));
+        assert_eq!(acc.by_count.len(), COUNTERS_PER_VALUE);
+        assert_eq!(acc.sorted_counters()[0], (&ScalarValue::from("v0"), 7));
+        Ok(())
+    }
+
+    #[test]
+    fn approx_top_k_invalid_k() {
+        let err = top_k(0).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: k value for 'APPROX_TOP_K' must be greater than 0, 0 is invalid"
+        );
+    }
+}
@@ -153,6 +153,13 @@ impl Accumulator for ArrayAggAccumulator {
             Box::new(self.datatype.clone()),
         ))
     }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + (self.values.capacity() - self.values.len())
+                * std::mem::size_of::<ScalarValue>()
+            + ScalarValue::size_of_slice(&self.values)
+    }
 }
 
 #[cfg(test)]
@@ -25,14 +25,16 @@ use crate::{AggregateExpr, PhysicalExpr};
 use arrow::compute;
 use arrow::datatypes::DataType;
 use arrow::{
-    array::{ArrayRef, UInt64Array},
+    array::{ArrayBuilder, ArrayRef, UInt64Array, UInt64Builder},
     datatypes::Field,
 };
 use datafusion_common::ScalarValue;
 use datafusion_common::{DataFusionError, Result};
 use datafusion_expr::Accumulator;
 
-use super::{format_state_name, sum};
+use super::{
+    append_scalar, count, format_state_name, row_state_values, scalar_builder, sum,
+};
 
 /// AVG aggregate expression
 #[derive(Debug)]
@@ -49,10 +51,10 @@ impl Avg {
         name: impl Into<String>,
         data_type: DataType,
     ) -> Self {
-        // the result of avg just support FLOAT64 and Decimal data type.
+        // the result of avg just support FLOAT64, Decimal and Duration data type.
         assert!(matches!(
             data_type,
-            DataType::Float64 | DataType::Decimal(_, _)
+            DataType::Float64 | DataType::Decimal(_, _) | DataType::Duration(_)
         ));
         Self {
             name: name.into(),
@@ -74,11 +76,16 @@ impl AggregateExpr for Avg {
 
     fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
         Ok(Box::new(AvgAccumulator::try_new(
-            // avg is f64 or decimal
+            // avg is f64, decimal or duration
             &self.data_type,
         )?))
     }
 
+    fn row_states(&self, values: &[ArrayRef]) -> Result<Option<Vec<ArrayRef>>> {
+        Ok(row_state_values(&values[0], &self.data_type)?
+            .map(|sums| vec![count::row_counts(&values[0]), sums]))
+    }
+
     fn state_fields(&self) -> Result<Vec<Field>> {
         Ok(vec![
             Field::new(
@@ -126,6 +133,16 @@ impl Accumulator for AvgAccumulator {
         Ok(vec![ScalarValue::from(self.count), self.sum.clone()])
     }
 
+    fn state_builders(&self, capacity: usize) -> Option<Vec<Box<dyn ArrayBuilder>>> {
+        let sum = scalar_builder(&self.sum.get_datatype(), capacity)?;
+        Some(vec![Box::new(UInt64Builder::new(capacity)), sum])
+    }
+
+    fn append_state(&self, builders: &mut [Box<dyn ArrayBuilder>]) -> Result<()> {
+        append_scalar(builders[0].as_mut(), &ScalarValue::from(self.count))?;
+        append_scalar(builders[1].as_mut(), &self.sum)
+    }
+
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let values = &values[0];
 
@@ -160,6 +177,19 @@ impl Accumulator for AvgAccumulator {
                     ),
                 })
             }
+            // durations are averaged with a truncating integer division
+            ScalarValue::DurationSecond(value) => Ok(ScalarValue::DurationSecond(
+                value.map(|v| v / self.count as i64),
+            )),
+            ScalarValue::DurationMillisecond(value) => Ok(
+                ScalarValue::DurationMillisecond(value.map(|v| v / self.count as i64)),
+            ),
+            ScalarValue::DurationMicrosecond(value) => Ok(
+                ScalarValue::DurationMicrosecond(value.map(|v| v / self.count as i64)),
+            ),
+            ScalarValue::DurationNanosecond(value) => Ok(
+                ScalarValue::DurationNanosecond(value.map(|v| v / self.count as i64)),
+            ),
             _ => Err(DataFusionError::Internal(
                 "Sum should be f64 on average".to_string(),
             )),
@@ -230,6 +260,23 @@ mod tests {
         )
     }
 
+    #[test]
+    fn avg_duration() -> Result<()> {
+        let a: ArrayRef = Arc::new(DurationSecondArray::from(vec![
+            Some(10),
+            None,
+            Some(20),
+            Some(35),
+        ]));
+        generic_test_op!(
+            a,
+            DataType::Duration(TimeUnit::Second),
+            Avg,
+            ScalarValue::DurationSecond(Some(21)),
+            DataType::Duration(TimeUnit::Second)
+        )
+    }
+
     #[test]
     fn avg_i32() -> Result<()> {
         let a: ArrayRef = Arc::new(Int32Array::from(vec![1, 2, 3, 4, 5]));
@@ -25,6 +25,7 @@ use arrow::compute::kernels::arithmetic::{
     multiply_scalar, subtract, subtract_scalar,
 };
 use arrow::compute::kernels::boolean::{and_kleene, not, or_kleene};
+use arrow::compute::kernels::cast::can_cast_types;
 use arrow::compute::kernels::comparison::{
     eq_bool_scalar, gt_bool_scalar, gt_eq_bool_scalar, lt_bool_scalar, lt_eq_bool_scalar,
     neq_bool_scalar,
@@ -50,11 +51,16 @@ use arrow::compute::kernels::comparison::{
     regexp_is_match_utf8_scalar,
 };
 use arrow::compute::kernels::comparison::{like_utf8, nlike_utf8, regexp_is_match_utf8};
-use arrow::datatypes::{ArrowNumericType, DataType, Schema, TimeUnit};
+use arrow::compute::kernels::take::take;
+use arrow::datatypes::{
+    ArrowDictionaryKeyType, ArrowNumericType, DataType, Int16Type, Int32Type, Int64Type,
+    Int8Type, Schema, TimeUnit, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
 use arrow::error::ArrowError::DivideByZero;
 use arrow::record_batch::RecordBatch;
 
-use crate::expressions::try_cast;
+use crate::datetime_expressions::{string_to_timestamp_nanos_in, TemporalSettings};
+use crate::expressions::{try_cast, Literal};
 use crate::PhysicalExpr;
 use datafusion_common::ScalarValue;
 use datafusion_common::{DataFusionError, Result};
@@ -823,6 +829,18 @@ macro_rules! binary_primitive_array_op {
             DataType::UInt64 => compute_op!($LEFT, $RIGHT, $OP, UInt64Array),
             DataType::Float32 => compute_op!($LEFT, $RIGHT, $OP, Float32Array),
             DataType::Float64 => compute_op!($LEFT, $RIGHT, $OP, Float64Array),
+            DataType::Duration(TimeUnit::Second) => {
+                compute_op!($LEFT, $RIGHT, $OP, DurationSecondArray)
+            }
+            DataType::Duration(TimeUnit::Millisecond) => {
+                compute_op!($LEFT, $RIGHT, $OP, DurationMillisecondArray)
+            }
+            DataType::Duration(TimeUnit::Microsecond) => {
+                compute_op!($LEFT, $RIGHT, $OP, DurationMicrosecondArray)
+            }
+            DataType::Duration(TimeUnit::Nanosecond) => {
+                compute_op!($LEFT, $RIGHT, $OP, DurationNanosecondArray)
+            }
             other => Err(DataFusionError::Internal(format!(
                 "Data type {:?} not supported for binary operation '{}' on primitive arrays",
                 other, stringify!($OP)
@@ -848,6 +866,18 @@ macro_rules! binary_primitive_array_op_scalar {
             DataType::UInt64 => compute_op_scalar!($LEFT, $RIGHT, $OP, UInt64Array),
             DataType::Float32 => compute_op_scalar!($LEFT, $RIGHT, $OP, Float32Array),
             DataType::Float64 => compute_op_scalar!($LEFT, $RIGHT, $OP, Float64Array),
+            DataType::Duration(TimeUnit::Second) => {
+                compute_op_scalar!($LEFT, $RIGHT, $OP, DurationSecondArray)
+            }
+            DataType::Duration(TimeUnit::Millisecond) => {
+                compute_op_scalar!($LEFT, $RIGHT, $OP, DurationMillisecondArray)
+            }
+            DataType::Duration(TimeUnit::Microsecond) => {
+                compute_op_scalar!($LEFT, $RIGHT, $OP, DurationMicrosecondArray)
+            }
+            DataType::Duration(TimeUnit::Nanosecond) => {
+                compute_op_scalar!($LEFT, $RIGHT, $OP, DurationNanosecondArray)
+            }
             other => Err(DataFusionError::Internal(format!(
                 "Data type {:?} not supported for scalar operation '{}' on primitive array",
                 other, stringify!($OP)
@@ -893,6 +923,18 @@ macro_rules! binary_array_op_scalar {
             DataType::Date64 => {
                 compute_op_scalar!($LEFT, $RIGHT, $OP, Date64Array)
             }
+            DataType::Duration(TimeUnit::Second) => {
+                compute_op_scalar!($LEFT, $RIGHT, $OP, DurationSecondArray)
+            }
+            DataType::Duration(TimeUnit::Millisecond) => {
+                compute_op_scalar!($LEFT, $RIGHT, $OP, DurationMillisecondArray)
+            }
+            DataType::Duration(TimeUnit::Microsecond) => {
+                compute_op_scalar!($LEFT, $RIGHT, $OP, DurationMicrosecondArray)
+            }
+            DataType::Duration(TimeUnit::Nanosecond) => {
+                compute_op_scalar!($LEFT, $RIGHT, $OP, DurationNanosecondArray)
+            }
             DataType::Boolean => compute_bool_op_scalar!($LEFT, $RIGHT, $OP, BooleanArray),
             other => Err(DataFusionError::Internal(format!(
                 "Data type {:?} not supported for scalar operation '{}' on dyn array",
@@ -939,6 +981,18 @@ macro_rules! binary_array_op {
             DataType::Date64 => {
                 compute_op!($LEFT, $RIGHT, $OP, Date64Array)
             }
+            DataType::Duration(TimeUnit::Second) => {
+                compute_op!($LEFT, $RIGHT, $OP, DurationSecondArray)
+            }
+            DataType::Duration(TimeUnit::Millisecond) => {
+                compute_op!($LEFT, $RIGHT, $OP, DurationMillisecondArray)
+            }
+            DataType::Duration(TimeUnit::Microsecond) => {
+                compute_op!($LEFT, $RIGHT, $OP, DurationMicrosecondArray)
+            }
+            DataType::Duration(TimeUnit::Nanosecond) => {
+                compute_op!($LEFT, $RIGHT, $OP, DurationNanosecondArray)
+            }
             DataType::Boolean => compute_bool_op!($LEFT, $RIGHT, $OP, BooleanArray),
             other => Err(DataFusionError::Internal(format!(
                 "Data type {:?} not supported for binary operation '{}' on dyn arrays",
@@ -1072,6 +1126,38 @@ impl PhysicalExpr for BinaryExpr {
         let left_data_type = left_value.data_type();
         let right_data_type = right_value.data_type();
 
+        // a dictionary compared to a scalar of the type of its values: the
+        // operator is applied to the values of the dictionary only
+        if supports_dictionary_values(&self.op) {
+            match (&left_value, &right_value) {
+                (ColumnarValue::Array(array), ColumnarValue::Scalar(_))
+                    if is_dictionary_of(&left_data_type, &right_data_type) =>
+                {
+                    return evaluate_dictionary_values(array, |values| {
+                        self.evaluate_values(
+                            ColumnarValue::Array(values.clone()),
+                            right_value.clone(),
+                            values.len(),
+                        )
+                    })
+                    .map(ColumnarValue::Array);
+                }
+                (ColumnarValue::Scalar(_), ColumnarValue::Array(array))
+                    if is_dictionary_of(&right_data_type, &left_data_type) =>
+                {
+                    return evaluate_dictionary_values(array, |values| {
+                        self.evaluate_values(
+                            left_value.clone(),
+                            ColumnarValue::Array(values.clone()),
+                            values.len(),
+                        )
+                    })
+                    .map(ColumnarValue::Array);
+                }
+                _ => {}
+            }
+        }
+
         if left_data_type != right_data_type {
             return Err(DataFusionError::Internal(format!(
                 "Cannot evaluate binary expression {:?} with types {:?} and {:?}",
@@ -1079,6 +1165,22 @@ impl PhysicalExpr for BinaryExpr {
             )));
         }
 
+        self.evaluate_values(left_value, right_value, batch.num_rows())
+            .map(ColumnarValue::Array)
+    }
+}
+
+impl BinaryExpr {
+    /// Evaluate the expression on values of the same type, of `num_rows` rows
+    fn evaluate_values(
+        &self,
+        left_value: ColumnarValue,
+        right_value: ColumnarValue,
+        num_rows: usize,
+    ) -> Result<ArrayRef> {
+        let left_data_type = left_value.data_type();
+        let right_data_type = right_value.data_type();
+
         // Attempt to use special kernels if one input is scalar and the other is an array
         let scalar_result = match (&left_value, &right_value) {
             (ColumnarValue::Array(array), ColumnarValue::Scalar(scalar)) => {
@@ -1093,19 +1195,69 @@ impl PhysicalExpr for BinaryExpr {
         };
 
         if let Some(result) = scalar_result {
-            return result.map(|a| ColumnarValue::Array(a));
+            return result;
         }
 
         // if both arrays or both literals - extract arrays and continue execution
         let (left, right) = (
-            left_value.into_array(batch.num_rows()),
-            right_value.into_array(batch.num_rows()),
+            left_value.into_array(num_rows),
+            right_value.into_array(num_rows),
         );
         self.evaluate_with_resolved_args(left, &left_data_type, right, &right_data_type)
-            .map(|a| ColumnarValue::Array(a))
     }
 }
 
+/// Whether the operator can be applied to the values of a dictionary rather
+/// than to each of its rows, its result only depending on the row's value
+fn supports_dictionary_values(op: &Operator) -> bool {
+    is_comparison_operator(op) || matches!(op, Operator::Like | Operator::NotLike)
+}
+
+/// Whether `data_type` is a dictionary of `value_type` values
+fn is_dictionary_of(data_type: &DataType, value_type: &DataType) -> bool {
+    matches!(data_type, DataType::Dictionary(_, values) if values.as_ref() == value_type)
+}
+
+/// Applies `f` to the values of the dictionary `array`, and returns the result
+/// of each row of the dictionary, i.e. the result of its key
+fn evaluate_dictionary_values(
+    array: &ArrayRef,
+    f: impl Fn(&ArrayRef) -> Result<ArrayRef>,
+) -> Result<ArrayRef> {
+    match array.data_type() {
+        DataType::Dictionary(key_type, _) => match key_type.as_ref() {
+            DataType::Int8 => take_dictionary_values::<Int8Type>(array, f),
+            DataType::Int16 => take_dictionary_values::<Int16Type>(array, f),
+            DataType::Int32 => take_dictionary_values::<Int32Type>(array, f),
+            DataType::Int64 => take_dictionary_values::<Int64Type>(array, f),
+            DataType::UInt8 => take_dictionary_values::<UInt8Type>(array, f),
+            DataType::UInt16 => take_dictionary_values::<UInt16Type>(array, f),
+            DataType::UInt32 => take_dictionary_values::<UInt32Type>(array, f),
+            DataType::UInt64 => take_dictionary_values::<UInt64Type>(array, f),
+            other => Err(DataFusionError::Internal(format!(
+                "Unsupported dictionary key type {:?}",
+                other
+            ))),
+        },
+        other => Err(DataFusionError::Internal(format!(
+            "Expected a dictionary array, got {:?}",
+            other
+        ))),
+    }
+}
+
+fn take_dictionary_values<K: ArrowDictionaryKeyType>(
+    array: &ArrayRef,
+    f: impl Fn(&ArrayRef) -> Result<ArrayRef>,
+) -> Result<ArrayRef> {
+    let dictionary = array
+        .as_any()
+        .downcast_ref::<DictionaryArray<K>>()
+        .expect("Unable to downcast to DictionaryArray");
+    let values = f(dictionary.values())?;
+    Ok(take(values.as_ref(), dictionary.keys(), None)?)
+}
+
 /// The binary_array_op_dyn_scalar macro includes types that extend beyond the primitive,
 /// such as Utf8 strings.
 #[macro_export]
@@ -1368,18 +1520,147 @@ fn binary_cast(
     op: &Operator,
     rhs: Arc<dyn PhysicalExpr>,
     input_schema: &Schema,
+    settings: &TemporalSettings,
 ) -> Result<(Arc<dyn PhysicalExpr>, Arc<dyn PhysicalExpr>)> {
     let lhs_type = &lhs.data_type(input_schema)?;
     let rhs_type = &rhs.data_type(input_schema)?;
 
+    if is_comparison_operator(op) {
+        if let DataType::Timestamp(unit, tz) = lhs_type {
+            if let Some(rhs) = timestamp_literal(&rhs, unit, tz, settings)? {
+                let rhs_type = rhs.data_type(input_schema)?;
+                return Ok((try_cast(lhs, input_schema, rhs_type)?, rhs));
+            }
+        }
+        if let DataType::Timestamp(unit, tz) = rhs_type {
+            if let Some(lhs) = timestamp_literal(&lhs, unit, tz, settings)? {
+                let lhs_type = lhs.data_type(input_schema)?;
+                return Ok((lhs, try_cast(rhs, input_schema, lhs_type)?));
+            }
+        }
+        if let Some(rhs) = integer_literal(&rhs, lhs_type) {
+            return Ok((lhs, rhs));
+        }
+        if let Some(lhs) = integer_literal(&lhs, rhs_type) {
+            return Ok((lhs, rhs));
+        }
+    }
+
     let result_type = coerce_types(lhs_type, op, rhs_type)?;
 
+    // a dictionary compared to a literal is not unpacked: its values are
+    // cast, and the operator is applied to them
+    if supports_dictionary_values(op) {
+        if let DataType::Dictionary(key_type, _) = lhs_type {
+            let dictionary_type =
+                DataType::Dictionary(key_type.clone(), Box::new(result_type.clone()));
+            if rhs.as_any().is::<Literal>() && can_cast_types(lhs_type, &dictionary_type)
+            {
+                return Ok((
+                    try_cast(lhs, input_schema, dictionary_type)?,
+                    try_cast(rhs, input_schema, result_type)?,
+                ));
+            }
+        }
+        if let DataType::Dictionary(key_type, _) = rhs_type {
+            let dictionary_type =
+                DataType::Dictionary(key_type.clone(), Box::new(result_type.clone()));
+            if lhs.as_any().is::<Literal>() && can_cast_types(rhs_type, &dictionary_type)
+            {
+                return Ok((
+                    try_cast(lhs, input_schema, result_type)?,
+                    try_cast(rhs, input_schema, dictionary_type)?,
+                ));
+            }
+        }
+    }
+
     Ok((
         try_cast(lhs, input_schema, result_type.clone())?,
         try_cast(rhs, input_schema, result_type)?,
     ))
 }
 
+fn is_comparison_operator(op: &Operator) -> bool {
+    matches!(
+        op,
+        Operator::Eq
+            | Operator::NotEq
+            | Operator::Lt
+            | Operator::LtEq
+            | Operator::Gt
+            | Operator::GtEq
+    )
+}
+
+/// Returns `expr` parsed as a timestamp literal if it is a string literal
+/// compared to timestamps of the given `unit` and time zone, e.g.
+/// `'2021-01-01T00:00:00.123+02:00'`, strings without an explicit offset being
+/// in the session time zone of `settings`. The timestamp is in `unit` if it
+/// can be represented exactly in it, so that the compared timestamps are not
+/// cast, and in nanoseconds otherwise.
+fn timestamp_literal(
+    expr: &Arc<dyn PhysicalExpr>,
+    unit: &TimeUnit,
+    tz: &Option<String>,
+    settings: &TemporalSettings,
+) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+    let value = match expr.as_any().downcast_ref::<Literal>().map(Literal::value) {
+        Some(ScalarValue::Utf8(Some(value)) | ScalarValue::LargeUtf8(Some(value))) => {
+            value
+        }
+        _ => return Ok(None),
+    };
+    let nanos = string_to_timestamp_nanos_in(value, settings)?;
+    let tz = tz.clone();
+    let timestamp = match unit {
+        TimeUnit::Second if nanos % 1_000_000_000 == 0 => {
+            ScalarValue::TimestampSecond(Some(nanos / 1_000_000_000), tz)
+        }
+        TimeUnit::Millisecond if nanos % 1_000_000 == 0 => {
+            ScalarValue::TimestampMillisecond(Some(nanos / 1_000_000), tz)
+        }
+        TimeUnit::Microsecond if nanos % 1_000 == 0 => {
+            ScalarValue::TimestampMicrosecond(Some(nanos / 1_000), tz)
+        }
+        _ => ScalarValue::TimestampNanosecond(Some(nanos), tz),
+    };
+    Ok(Some(Arc::new(Literal::new(timestamp))))
+}
+
+/// Returns `expr` as a literal of the integer type `data_type` if it is an
+/// integer literal whose value is in the range of `data_type`, e.g. `5` compared
+/// to a UInt64 column, so that the compared integers are not cast. The literals
+/// out of the range are coerced with the other integers.
+fn integer_literal(
+    expr: &Arc<dyn PhysicalExpr>,
+    data_type: &DataType,
+) -> Option<Arc<dyn PhysicalExpr>> {
+    let value: i128 = match expr.as_any().downcast_ref::<Literal>()?.value() {
+        ScalarValue::Int8(Some(v)) => (*v).into(),
+        ScalarValue::Int16(Some(v)) => (*v).into(),
+        ScalarValue::Int32(Some(v)) => (*v).into(),
+        ScalarValue::Int64(Some(v)) => (*v).into(),
+        ScalarValue::UInt8(Some(v)) => (*v).into(),
+        ScalarValue::UInt16(Some(v)) => (*v).into(),
+        ScalarValue::UInt32(Some(v)) => (*v).into(),
+        ScalarValue::UInt64(Some(v)) => (*v).into(),
+        _ => return None,
+    };
+    let value = match data_type {
+        DataType::Int8 => ScalarValue::Int8(Some(value.try_into().ok()?)),
+        DataType::Int16 => ScalarValue::Int16(Some(value.try_into().ok()?)),
+        DataType::Int32 => ScalarValue::Int32(Some(value.try_into().ok()?)),
+        DataType::Int64 => ScalarValue::Int64(Some(value.try_into().ok()?)),
+        DataType::UInt8 => ScalarValue::UInt8(Some(value.try_into().ok()?)),
+        DataType::UInt16 => ScalarValue::UInt16(Some(value.try_into().ok()?)),
+        DataType::UInt32 => ScalarValue::UInt32(Some(value.try_into().ok()?)),
+        DataType::UInt64 => ScalarValue::UInt64(Some(value.try_into().ok()?)),
+        _ => return None,
+    };
+    Some(Arc::new(Literal::new(value)))
+}
+
 /// Create a binary expression whose arguments are correctly coerced.
 /// This function errors if it is not possible to coerce the arguments
 /// to computational types supported by the operator.
@@ -1389,14 +1670,27 @@ pub fn binary(
     rhs: Arc<dyn PhysicalExpr>,
     input_schema: &Schema,
 ) -> Result<Arc<dyn PhysicalExpr>> {
-    let (l, r) = binary_cast(lhs, &op, rhs, input_schema)?;
+    binary_with_settings(lhs, op, rhs, input_schema, &TemporalSettings::default())
+}
+
+/// Create a binary expression like [`binary`], the strings compared to
+/// timestamps without an explicit offset being in the session time zone of
+/// `settings`.
+pub fn binary_with_settings(
+    lhs: Arc<dyn PhysicalExpr>,
+    op: Operator,
+    rhs: Arc<dyn PhysicalExpr>,
+    input_schema: &Schema,
+    settings: &TemporalSettings,
+) -> Result<Arc<dyn PhysicalExpr>> {
+    let (l, r) = binary_cast(lhs, &op, rhs, input_schema, settings)?;
     Ok(Arc::new(BinaryExpr::new(l, op, r)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::expressions::{col, lit};
+    use crate::expressions::{col, lit, Column};
     use arrow::datatypes::{ArrowNumericType, Field, Int32Type, SchemaRef};
     use arrow::util::display::array_value_to_string;
     use datafusion_common::Result;
@@ -1848,6 +2142,138 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_integer_literal_comparison() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("u8", DataType::UInt8, false),
+            Field::new("u64", DataType::UInt64, false),
+            Field::new("i8", DataType::Int8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(UInt8Array::from(vec![0, 5, u8::MAX])),
+                Arc::new(UInt64Array::from(vec![0, 5, u64::MAX])),
+                Arc::new(Int8Array::from(vec![i8::MIN, 0, i8::MAX])),
+            ],
+        )?;
+
+        // (column, operator, literal, whether the literal is narrowed, result)
+        let cases = vec![
+            ("u8", Operator::Eq, 255, true, "false\nfalse\ntrue"),
+            ("u8", Operator::Lt, 256, false, "true\ntrue\ntrue"),
+            ("u8", Operator::GtEq, -1, false, "true\ntrue\ntrue"),
+            ("u64", Operator::Gt, 4, true, "false\ntrue\ntrue"),
+            ("u64", Operator::NotEq, 0, true, "false\ntrue\ntrue"),
+            ("i8", Operator::Gt, -128, true, "false\ntrue\ntrue"),
+            ("i8", Operator::LtEq, 127, true, "true\ntrue\ntrue"),
+            ("i8", Operator::Lt, 128, false, "true\ntrue\ntrue"),
+        ];
+        for (column, op, value, narrowed, expected) in cases {
+            let expression = binary(
+                col(column, &schema)?,
+                op,
+                lit(ScalarValue::Int64(Some(value))),
+                &schema,
+            )?;
+            let binary = expression.as_any().downcast_ref::<BinaryExpr>().unwrap();
+            assert_eq!(
+                binary.left().as_any().is::<Column>(),
+                narrowed,
+                "{}",
+                expression
+            );
+            let result = expression.evaluate(&batch)?.into_array(batch.num_rows());
+            assert_eq!(expected, array_to_string(&result)?, "{}", expression);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_dictionary_scalar_comparison() -> Result<()> {
+        let keys_builder = PrimitiveBuilder::<Int8Type>::new(10);
+        let values_builder = arrow::array::StringBuilder::new(10);
+        let mut str_builder = StringDictionaryBuilder::new(keys_builder, values_builder);
+        str_builder.append("one")?;
+        str_builder.append_null()?;
+        str_builder.append("three")?;
+        str_builder.append("one")?;
+
+        let keys_builder = PrimitiveBuilder::<Int32Type>::new(10);
+        let values_builder = PrimitiveBuilder::<Int32Type>::new(10);
+        let mut int_builder =
+            PrimitiveDictionaryBuilder::new(keys_builder, values_builder);
+        int_builder.append(1)?;
+        int_builder.append_null()?;
+        int_builder.append(3)?;
+        int_builder.append(1)?;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "str",
+                DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8)),
+                true,
+            ),
+            Field::new(
+                "int",
+                DataType::Dictionary(
+                    Box::new(DataType::Int32),
+                    Box::new(DataType::Int32),
+                ),
+                true,
+            ),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(str_builder.finish()),
+                Arc::new(int_builder.finish()),
+            ],
+        )?;
+
+        let cases = vec![
+            (
+                col("str", &schema)?,
+                Operator::Like,
+                lit(ScalarValue::Utf8(Some("%e".to_owned()))),
+                "true\n\ntrue\ntrue",
+            ),
+            (
+                col("str", &schema)?,
+                Operator::NotLike,
+                lit(ScalarValue::Utf8(Some("t%".to_owned()))),
+                "true\n\nfalse\ntrue",
+            ),
+            (
+                lit(ScalarValue::Utf8(Some("one".to_owned()))),
+                Operator::Eq,
+                col("str", &schema)?,
+                "true\n\nfalse\ntrue",
+            ),
+            // the values of the dictionary are cast to Int64
+            (
+                col("int", &schema)?,
+                Operator::GtEq,
+                lit(ScalarValue::Int64(Some(2))),
+                "false\n\ntrue\nfalse",
+            ),
+            (
+                lit(ScalarValue::Int64(Some(2))),
+                Operator::Gt,
+                col("int", &schema)?,
+                "true\n\nfalse\ntrue",
+            ),
+        ];
+        for (left, op, right, expected) in cases {
+            let expression = binary(left, op, right, &schema)?;
+            assert_eq!(expression.data_type(&schema)?, DataType::Boolean);
+            let result = expression.evaluate(&batch)?.into_array(batch.num_rows());
+            assert_eq!(result.data_type(), &DataType::Boolean);
+            assert_eq!(expected, array_to_string(&result)?, "{}", expression);
+        }
+        Ok(())
+    }
+
     // Convert the array to a newline delimited string of pretty printed values
     fn array_to_string(array: &ArrayRef) -> Result<String> {
         let s = (0..array.len())
@@ -20,8 +20,10 @@ use std::{any::Any, sync::Arc};
 use crate::expressions::try_cast;
 use crate::PhysicalExpr;
 use arrow::array::*;
-use arrow::compute::kernels::zip::zip;
-use arrow::compute::{and, eq_dyn, is_null, not, or, or_kleene};
+use arrow::compute::{
+    and, and_kleene, eq_dyn, filter_record_batch, is_not_null, is_null, not, or,
+    SlicesIterator,
+};
 use arrow::datatypes::{DataType, Schema};
 use arrow::record_batch::RecordBatch;
 use datafusion_common::{DataFusionError, Result};
@@ -117,48 +119,32 @@ impl CaseExpr {
     ///     [ELSE result]
     /// END
     fn case_when_with_expr(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
-        let return_type = self.when_then_expr[0].1.data_type(&batch.schema())?;
         let expr = self.expr.as_ref().unwrap();
         let base_value = expr.evaluate(batch)?;
         let base_value = base_value.into_array(batch.num_rows());
         let base_nulls = is_null(base_value.as_ref())?;
 
-        // start with nulls as default output
-        let mut current_value = new_null_array(&return_type, batch.num_rows());
         // We only consider non-null values while comparing with whens
         let mut remainder = not(&base_nulls)?;
-        for i in 0..self.when_then_expr.len() {
-            let when_value = self.when_then_expr[i]
-                .0
-                .evaluate_selection(batch, &remainder)?;
+        let mut branches = Vec::with_capacity(self.when_then_expr.len() + 1);
+        for (when, then) in &self.when_then_expr {
+            if SlicesIterator::new(&remainder).next().is_none() {
+                break;
+            }
+            let when_value = when.evaluate_selection(batch, &remainder)?;
             let when_value = when_value.into_array(batch.num_rows());
             // build boolean array representing which rows match the "when" value
-            let when_match = eq_dyn(&when_value, base_value.as_ref())?;
-
-            let then_value = self.when_then_expr[i]
-                .1
-                .evaluate_selection(batch, &when_match)?;
-            let then_value = then_value.into_array(batch.num_rows());
-
-            current_value =
-                zip(&when_match, then_value.as_ref(), current_value.as_ref())?;
-
-            remainder = and(&remainder, &or_kleene(&not(&when_match)?, &base_nulls)?)?;
-        }
-
-        if let Some(e) = &self.else_expr {
-            // keep `else_expr`'s data type and return type consistent
-            let expr = try_cast(e.clone(), &*batch.schema(), return_type.clone())
-                .unwrap_or_else(|_| e.clone());
-            // null and unmatched tuples should be assigned else value
-            remainder = or(&base_nulls, &remainder)?;
-            let else_ = expr
-                .evaluate_selection(batch, &remainder)?
-                .into_array(batch.num_rows());
-            current_value = zip(&remainder, else_.as_ref(), current_value.as_ref())?;
+            let when_match = and(
+                &remainder,
+                &nulls_as_false(&eq_dyn(&when_value, base_value.as_ref())?)?,
+            )?;
+            remainder = and(&remainder, &not(&when_match)?)?;
+            branches.push((when_match, then.clone()));
         }
 
-        Ok(ColumnarValue::Array(current_value))
+        // null and unmatched tuples should be assigned else value
+        let else_rows = or(&base_nulls, &remainder)?;
+        self.select_branches(batch, branches, else_rows)
     }
 
     /// This function evaluates the form of CASE where each WHEN expression is a boolean
@@ -169,15 +155,13 @@ impl CaseExpr {
     ///      [ELSE result]
     /// END
     fn case_when_no_expr(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
-        let return_type = self.when_then_expr[0].1.data_type(&batch.schema())?;
-
-        // start with nulls as default output
-        let mut current_value = new_null_array(&return_type, batch.num_rows());
         let mut remainder = BooleanArray::from(vec![true; batch.num_rows()]);
-        for i in 0..self.when_then_expr.len() {
-            let when_value = self.when_then_expr[i]
-                .0
-                .evaluate_selection(batch, &remainder)?;
+        let mut branches = Vec::with_capacity(self.when_then_expr.len() + 1);
+        for (when, then) in &self.when_then_expr {
+            if SlicesIterator::new(&remainder).next().is_none() {
+                break;
+            }
+            let when_value = when.evaluate_selection(batch, &remainder)?;
             // Treat 'NULL' as false value
             let when_value = match when_value {
                 ColumnarValue::Scalar(value) if value.is_null() => {
@@ -192,35 +176,105 @@ impl CaseExpr {
                 .downcast_ref::<BooleanArray>()
                 .expect("WHEN expression did not return a BooleanArray");
 
-            let then_value = self.when_then_expr[i]
-                .1
-                .evaluate_selection(batch, when_value)?;
-            let then_value = then_value.into_array(batch.num_rows());
-
-            current_value = zip(when_value, then_value.as_ref(), current_value.as_ref())?;
-
             // Succeed tuples should be filtered out for short-circuit evaluation,
             // null values for the current when expr should be kept
-            remainder = and(
-                &remainder,
-                &or_kleene(&not(when_value)?, &is_null(when_value)?)?,
-            )?;
+            let when_match = and(&remainder, &nulls_as_false(when_value)?)?;
+            remainder = and(&remainder, &not(&when_match)?)?;
+            branches.push((when_match, then.clone()));
         }
 
+        self.select_branches(batch, branches, remainder)
+    }
+
+    /// Assembles the result from the `branches`, the THEN expressions with
+    /// the rows they are selected for, and from the ELSE expression selected
+    /// for the `else_rows`. The rows selected by no branch are NULL.
+    ///
+    /// Each expression is only evaluated on the rows it is selected for, and
+    /// its values are copied to the result slice by slice in a single pass,
+    /// rather than merging the whole result once per branch.
+    fn select_branches(
+        &self,
+        batch: &RecordBatch,
+        mut branches: Vec<(BooleanArray, Arc<dyn PhysicalExpr>)>,
+        else_rows: BooleanArray,
+    ) -> Result<ColumnarValue> {
+        let return_type = self.data_type(&batch.schema())?;
         if let Some(e) = &self.else_expr {
             // keep `else_expr`'s data type and return type consistent
             let expr = try_cast(e.clone(), &*batch.schema(), return_type.clone())
                 .unwrap_or_else(|_| e.clone());
-            let else_ = expr
-                .evaluate_selection(batch, &remainder)?
-                .into_array(batch.num_rows());
-            current_value = zip(&remainder, else_.as_ref(), current_value.as_ref())?;
+            branches.push((else_rows, expr));
         }
 
-        Ok(ColumnarValue::Array(current_value))
+        let mut values = Vec::with_capacity(branches.len());
+        let mut slices = vec![];
+        for (rows, expr) in &branches {
+            let rows_slices = SlicesIterator::new(rows).collect::<Vec<_>>();
+            let num_rows = rows_slices.iter().map(|(start, end)| end - start).sum();
+            if num_rows == 0 {
+                continue;
+            }
+            let selected = if num_rows == batch.num_rows() {
+                batch.clone()
+            } else {
+                filter_record_batch(batch, rows)?
+            };
+            let value = expr.evaluate(&selected)?.into_array(num_rows);
+            if value.data_type() != &return_type {
+                return Err(DataFusionError::Internal(format!(
+                    "CASE branch {} returned {:?} values rather than {:?}",
+                    expr,
+                    value.data_type(),
+                    return_type
+                )));
+            }
+            if num_rows == batch.num_rows() {
+                return Ok(ColumnarValue::Array(value));
+            }
+            let index = values.len();
+            slices.extend(
+                rows_slices
+                    .into_iter()
+                    .map(|(start, end)| (start, end, index)),
+            );
+            values.push(value);
+        }
+        if values.is_empty() {
+            return Ok(ColumnarValue::Array(new_null_array(
+                &return_type,
+                batch.num_rows(),
+            )));
+        }
+
+        // the rows of the branches are disjoint, each slice is copied from
+        // the next values of its branch
+        slices.sort_unstable_by_key(|(start, _, _)| *start);
+        let data = values.iter().map(|value| value.data()).collect();
+        let mut result = MutableArrayData::new(data, true, batch.num_rows());
+        let mut offsets = vec![0; values.len()];
+        let mut filled = 0;
+        for (start, end, index) in slices {
+            if start > filled {
+                result.extend_nulls(start - filled);
+            }
+            let len = end - start;
+            result.extend(index, offsets[index], offsets[index] + len);
+            offsets[index] += len;
+            filled = end;
+        }
+        if filled < batch.num_rows() {
+            result.extend_nulls(batch.num_rows() - filled);
+        }
+        Ok(ColumnarValue::Array(make_array(result.freeze())))
     }
 }
 
+/// Returns `mask` with its NULLs replaced by `false`
+fn nulls_as_false(mask: &BooleanArray) -> Result<BooleanArray> {
+    Ok(and_kleene(mask, &is_not_null(mask)?)?)
+}
+
 impl PhysicalExpr for CaseExpr {
     /// Return a reference to Any that can be used for down-casting
     fn as_any(&self) -> &dyn Any {
@@ -572,6 +626,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn case_ladder() -> Result<()> {
+        let batch = case_test_batch1()?;
+        let schema = batch.schema();
+        let a = col("a", &schema)?;
+        let a_is = |op: Operator, value: i32| {
+            binary(a.clone(), op, lit(ScalarValue::Int32(Some(value))), &schema)
+        };
+
+        // CASE WHEN a = 1 THEN 10 WHEN a < 0 THEN 20 WHEN a > 2 THEN a * 100 ELSE a END
+        let expr = case(
+            None,
+            &[
+                (a_is(Operator::Eq, 1)?, lit(ScalarValue::Int32(Some(10)))),
+                (a_is(Operator::Lt, 0)?, lit(ScalarValue::Int32(Some(20)))),
+                (a_is(Operator::Gt, 2)?, a_is(Operator::Multiply, 100)?),
+            ],
+            Some(a.clone()),
+        )?;
+        let result = expr.evaluate(&batch)?.into_array(batch.num_rows());
+        let result = result
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .expect("failed to downcast to Int32Array");
+        let expected = &Int32Array::from(vec![Some(10), Some(0), None, Some(500)]);
+        assert_eq!(expected, result);
+
+        // CASE a WHEN NULL THEN 1 ELSE 2 END, NULL matching no value
+        let expr = case(
+            Some(a),
+            &[(
+                lit(ScalarValue::Int32(None)),
+                lit(ScalarValue::Int32(Some(1))),
+            )],
+            Some(lit(ScalarValue::Int32(Some(2)))),
+        )?;
+        let result = expr.evaluate(&batch)?.into_array(batch.num_rows());
+        let result = result
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .expect("failed to downcast to Int32Array");
+        let expected = &Int32Array::from(vec![2, 2, 2, 2]);
+        assert_eq!(expected, result);
+
+        Ok(())
+    }
+
     fn case_test_batch() -> Result<RecordBatch> {
         let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
         let a = StringArray::from(vec![Some("foo"), Some("baz"), None, Some("bar")]);
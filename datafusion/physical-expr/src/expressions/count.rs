@@ -24,14 +24,14 @@ use crate::{AggregateExpr, PhysicalExpr};
 use arrow::compute;
 use arrow::datatypes::DataType;
 use arrow::{
-    array::{ArrayRef, UInt64Array},
+    array::{Array, ArrayBuilder, ArrayRef, UInt64Array, UInt64Builder},
     datatypes::Field,
 };
 use datafusion_common::Result;
 use datafusion_common::ScalarValue;
 use datafusion_expr::Accumulator;
 
-use super::format_state_name;
+use super::{append_scalar, format_state_name};
 
 /// COUNT aggregate expression
 /// Returns the amount of non-null values of the given expression.
@@ -89,11 +89,24 @@ impl AggregateExpr for Count {
         Ok(Box::new(CountAccumulator::new()))
     }
 
+    fn row_states(&self, values: &[ArrayRef]) -> Result<Option<Vec<ArrayRef>>> {
+        Ok(Some(vec![row_counts(&values[0])]))
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
 }
 
+/// Returns the count of each row of `values` on its own: 1 if it is not null
+pub(crate) fn row_counts(values: &ArrayRef) -> ArrayRef {
+    Arc::new(
+        (0..values.len())
+            .map(|row| Some(values.is_valid(row) as u64))
+            .collect::<UInt64Array>(),
+    )
+}
+
 #[derive(Debug)]
 struct CountAccumulator {
     count: u64,
@@ -126,6 +139,14 @@ impl Accumulator for CountAccumulator {
         Ok(vec![ScalarValue::UInt64(Some(self.count))])
     }
 
+    fn state_builders(&self, capacity: usize) -> Option<Vec<Box<dyn ArrayBuilder>>> {
+        Some(vec![Box::new(UInt64Builder::new(capacity))])
+    }
+
+    fn append_state(&self, builders: &mut [Box<dyn ArrayBuilder>]) -> Result<()> {
+        append_scalar(builders[0].as_mut(), &ScalarValue::UInt64(Some(self.count)))
+    }
+
     fn evaluate(&self) -> Result<ScalarValue> {
         Ok(ScalarValue::UInt64(Some(self.count)))
     }
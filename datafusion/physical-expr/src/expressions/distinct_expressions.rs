@@ -231,6 +231,17 @@ impl Accumulator for DistinctCountAccumulator {
             ))),
         }
     }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.values.capacity() * std::mem::size_of::<DistinctScalarValues>()
+            + self
+                .values
+                .iter()
+                .map(|values| ScalarValue::size_of_slice(&values.0))
+                .sum::<usize>()
+            + self.state_data_types.capacity() * std::mem::size_of::<DataType>()
+    }
 }
 
 /// Expression for a ARRAY_AGG(DISTINCT) aggregation.
@@ -358,6 +369,16 @@ impl Accumulator for DistinctArrayAggAccumulator {
             Box::new(self.datatype.clone()),
         ))
     }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.values.capacity() * std::mem::size_of::<ScalarValue>()
+            + self
+                .values
+                .iter()
+                .map(|value| value.size() - std::mem::size_of_val(value))
+                .sum::<usize>()
+    }
 }
 
 #[cfg(test)]
@@ -367,8 +388,8 @@ mod tests {
     use crate::expressions::tests::aggregate;
     use arrow::array::{
         ArrayRef, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array,
-        Int64Array, Int8Array, ListArray, UInt16Array, UInt32Array, UInt64Array,
-        UInt8Array,
+        Int64Array, Int8Array, ListArray, StringArray, UInt16Array, UInt32Array,
+        UInt64Array, UInt8Array,
     };
     use arrow::array::{Int32Builder, ListBuilder, UInt64Builder};
     use arrow::datatypes::{DataType, Schema};
@@ -459,6 +480,23 @@ mod tests {
         Ok((accum.state()?, accum.evaluate()?))
     }
 
+    #[test]
+    fn count_distinct_size() -> Result<()> {
+        let agg = DistinctCount::new(
+            vec![DataType::Utf8],
+            vec![],
+            String::from("__col_name__"),
+            DataType::UInt64,
+        );
+        let mut accum = agg.create_accumulator()?;
+        let empty_size = accum.size();
+
+        let array = Arc::new(StringArray::from(vec!["a", "b", "a", "a long string"]));
+        accum.update_batch(&[array])?;
+        assert!(accum.size() > empty_size + 3 * std::mem::size_of::<ScalarValue>());
+        Ok(())
+    }
+
     fn run_update(
         data_types: &[DataType],
         rows: &[Vec<ScalarValue>],
@@ -15,12 +15,12 @@
 // specific language governing permissions and limitations
 // under the License.
 
-//! get field of a `ListArray`
+//! get field of a `ListArray`, `StructArray` or `MapArray`
 
 use crate::PhysicalExpr;
-use arrow::array::Array;
-use arrow::array::{ListArray, StructArray};
-use arrow::compute::concat;
+use arrow::array::{Array, ArrayRef, UInt32Array};
+use arrow::array::{ListArray, MapArray, StructArray};
+use arrow::compute::{cast, take};
 use arrow::{
     datatypes::{DataType, Schema},
     record_batch::RecordBatch,
@@ -35,7 +35,8 @@ use std::convert::TryInto;
 use std::fmt::Debug;
 use std::{any::Any, sync::Arc};
 
-/// expression to get a field of a struct array.
+/// expression to get an element of a list array by its 0-based index, a field
+/// of a struct array by its name or a value of a map array by its key.
 #[derive(Debug)]
 pub struct GetIndexedFieldExpr {
     arg: Arc<dyn PhysicalExpr>,
@@ -52,6 +53,11 @@ impl GetIndexedFieldExpr {
     pub fn arg(&self) -> &Arc<dyn PhysicalExpr> {
         &self.arg
     }
+
+    /// Get the key of the field
+    pub fn key(&self) -> &ScalarValue {
+        &self.key
+    }
 }
 
 impl std::fmt::Display for GetIndexedFieldExpr {
@@ -72,7 +78,8 @@ impl PhysicalExpr for GetIndexedFieldExpr {
 
     fn nullable(&self, input_schema: &Schema) -> Result<bool> {
         let data_type = self.arg.data_type(input_schema)?;
-        get_data_type_field(&data_type, &self.key).map(|f| f.is_nullable())
+        Ok(get_data_type_field(&data_type, &self.key)?.is_nullable()
+            || self.arg.nullable(input_schema)?)
     }
 
     fn evaluate(&self, batch: &RecordBatch) -> Result<ColumnarValue> {
@@ -86,25 +93,19 @@ impl PhysicalExpr for GetIndexedFieldExpr {
                 (DataType::List(_), ScalarValue::Int64(Some(i))) => {
                     let as_list_array =
                         array.as_any().downcast_ref::<ListArray>().unwrap();
-                    if as_list_array.is_empty() {
-                        let scalar_null: ScalarValue = array.data_type().try_into()?;
-                        return Ok(ColumnarValue::Scalar(scalar_null))
-                    }
-                    let sliced_array: Vec<Arc<dyn Array>> = as_list_array
-                        .iter()
-                        .filter_map(|o| o.map(|list| list.slice(*i as usize, 1)))
-                        .collect();
-                    let vec = sliced_array.iter().map(|a| a.as_ref()).collect::<Vec<&dyn Array>>();
-                    let iter = concat(vec.as_slice()).unwrap();
-                    Ok(ColumnarValue::Array(iter))
+                    Ok(ColumnarValue::Array(list_element(as_list_array, *i)?))
                 }
                 (DataType::Struct(_), ScalarValue::Utf8(Some(k))) => {
                     let as_struct_array = array.as_any().downcast_ref::<StructArray>().unwrap();
                     match as_struct_array.column_by_name(k) {
                         None => Err(DataFusionError::Execution(format!("get indexed field {} not found in struct", k))),
-                        Some(col) => Ok(ColumnarValue::Array(col.clone()))
+                        Some(col) => Ok(ColumnarValue::Array(struct_field(as_struct_array, col)?))
                     }
                 }
+                (DataType::Map(_, _), key) if !key.is_null() => {
+                    let as_map_array = array.as_any().downcast_ref::<MapArray>().unwrap();
+                    Ok(ColumnarValue::Array(map_value(as_map_array, key)?))
+                }
                 (dt, key) => Err(DataFusionError::NotImplemented(format!("get indexed field is only possible on lists with int64 indexes. Tried {} with {} index", dt, key))),
             },
             ColumnarValue::Scalar(_) => Err(DataFusionError::NotImplemented(
@@ -114,14 +115,61 @@ impl PhysicalExpr for GetIndexedFieldExpr {
     }
 }
 
+/// The elements at the 0-based `index` of the lists of `array`, null for the
+/// null lists and the lists with no such element
+fn list_element(array: &ListArray, index: i64) -> Result<ArrayRef> {
+    let offsets = array.value_offsets();
+    let indices: UInt32Array = (0..array.len())
+        .map(|row| {
+            let (start, end) = (offsets[row] as i64, offsets[row + 1] as i64);
+            (array.is_valid(row) && index >= 0 && index < end - start)
+                .then(|| (start + index) as u32)
+        })
+        .collect();
+    Ok(take(array.values().as_ref(), &indices, None)?)
+}
+
+/// The field `column` of the structs of `array`, null for the null structs
+fn struct_field(array: &StructArray, column: &ArrayRef) -> Result<ArrayRef> {
+    if array.null_count() == 0 {
+        return Ok(column.clone());
+    }
+    let indices: UInt32Array = (0..array.len())
+        .map(|row| array.is_valid(row).then(|| row as u32))
+        .collect();
+    Ok(take(column.as_ref(), &indices, None)?)
+}
+
+/// The values of the entries of the maps of `array` with the given `key`,
+/// null for the null maps and the maps with no such key
+fn map_value(array: &MapArray, key: &ScalarValue) -> Result<ArrayRef> {
+    let keys = array.keys();
+    let key = ScalarValue::try_from_array(&cast(&key.to_array(), keys.data_type())?, 0)?;
+    let offsets = array.value_offsets();
+    let indices = (0..array.len())
+        .map(|row| {
+            if array.is_valid(row) {
+                for entry in offsets[row] as usize..offsets[row + 1] as usize {
+                    if ScalarValue::try_from_array(&keys, entry)? == key {
+                        return Ok(Some(entry as u32));
+                    }
+                }
+            }
+            Ok(None)
+        })
+        .collect::<Result<UInt32Array>>()?;
+    Ok(take(array.values().as_ref(), &indices, None)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::expressions::{col, lit};
-    use arrow::array::GenericListArray;
+    use arrow::array::{ArrayData, GenericListArray};
     use arrow::array::{
         Int64Array, Int64Builder, ListBuilder, StringBuilder, StructArray, StructBuilder,
     };
+    use arrow::buffer::Buffer;
     use arrow::{array::StringArray, datatypes::Field};
     use datafusion_common::Result;
 
@@ -205,6 +253,79 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn get_indexed_field_list_out_of_bounds() -> Result<()> {
+        let schema = list_schema("l");
+        let builder = StringBuilder::new(3);
+        let mut lb = ListBuilder::new(builder);
+        lb.values().append_value("a")?;
+        lb.append(true)?;
+        lb.append(true)?;
+        lb.append(false)?;
+        let expr = col("l", &schema).unwrap();
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(lb.finish())])?;
+
+        for (index, expected) in [(0, Some("a")), (1, None)] {
+            let key = ScalarValue::Int64(Some(index));
+            let expr = Arc::new(GetIndexedFieldExpr::new(expr.clone(), key));
+            let result = expr.evaluate(&batch)?.into_array(batch.num_rows());
+            let result = result
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .expect("failed to downcast to StringArray");
+            assert_eq!(&StringArray::from(vec![expected, None, None]), result);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn get_indexed_field_map() -> Result<()> {
+        let keys = StringArray::from(vec!["a", "b", "a"]);
+        let values = Int64Array::from(vec![Some(1), None, Some(3)]);
+        let entries = StructArray::from(vec![
+            (
+                Field::new("keys", DataType::Utf8, false),
+                Arc::new(keys) as ArrayRef,
+            ),
+            (
+                Field::new("values", DataType::Int64, true),
+                Arc::new(values) as ArrayRef,
+            ),
+        ]);
+        let map_type = DataType::Map(
+            Box::new(Field::new("entries", entries.data_type().clone(), false)),
+            false,
+        );
+        // maps {a: 1, b: null}, {a: 3} and {}
+        let map = MapArray::from(
+            ArrayData::builder(map_type.clone())
+                .len(3)
+                .add_buffer(Buffer::from_slice_ref(&[0i32, 2, 3, 3]))
+                .add_child_data(entries.data().clone())
+                .build()?,
+        );
+        let schema = Schema::new(vec![Field::new("m", map_type, true)]);
+        let expr = col("m", &schema).unwrap();
+        let batch = RecordBatch::try_new(Arc::new(schema), vec![Arc::new(map)])?;
+
+        for (key, expected) in [
+            ("a", vec![Some(1), Some(3), None]),
+            ("b", vec![None, None, None]),
+            ("c", vec![None, None, None]),
+        ] {
+            let key = ScalarValue::Utf8(Some(key.to_string()));
+            let expr = Arc::new(GetIndexedFieldExpr::new(expr.clone(), key));
+            assert_eq!(expr.data_type(batch.schema().as_ref())?, DataType::Int64);
+            let result = expr.evaluate(&batch)?.into_array(batch.num_rows());
+            let result = result
+                .as_any()
+                .downcast_ref::<Int64Array>()
+                .expect("failed to downcast to Int64Array");
+            assert_eq!(&Int64Array::from(expected), result);
+        }
+        Ok(())
+    }
+
     fn get_indexed_field_test_failure(
         schema: Schema,
         expr: Arc<dyn PhysicalExpr>,
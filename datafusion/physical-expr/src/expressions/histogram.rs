@@ -0,0 +1,440 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines physical expressions that can evaluated at runtime during query execution
+
+use super::{format_state_name, Literal};
+use crate::{AggregateExpr, PhysicalExpr};
+use arrow::array::{ArrayRef, Float64Array};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::ScalarValue;
+use datafusion_common::{DataFusionError, Result};
+use datafusion_expr::aggregate_function::histogram_return_type;
+use datafusion_expr::Accumulator;
+use std::any::Any;
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+/// HISTOGRAM aggregate expression: a histogram of the input values with at
+/// most `bins` bins.
+///
+/// The bins are built with the streaming algorithm of Ben-Haim and Tom-Tov:
+/// values are added as their own bin, and the two bins with the closest means
+/// are merged whenever there are too many of them. The histograms of different
+/// partitions are combined the same way, so the bins adapt to the distribution
+/// of the values without knowing their range in advance.
+#[derive(Debug)]
+pub struct Histogram {
+    name: String,
+    expr: Vec<Arc<dyn PhysicalExpr>>,
+    bins: usize,
+}
+
+impl Histogram {
+    /// Create a new [`Histogram`] aggregate function.
+    pub fn new(
+        expr: Vec<Arc<dyn PhysicalExpr>>,
+        name: impl Into<String>,
+    ) -> Result<Self> {
+        // Arguments should be [ColumnExpr, BinsLiteral]
+        debug_assert_eq!(expr.len(), 2);
+
+        let lit = expr[1]
+            .as_any()
+            .downcast_ref::<Literal>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "bins argument of histogram must be an integer literal".to_string(),
+                )
+            })?
+            .value();
+        let bins = match lit {
+            ScalarValue::Int8(Some(bins)) => *bins as i64,
+            ScalarValue::Int16(Some(bins)) => *bins as i64,
+            ScalarValue::Int32(Some(bins)) => *bins as i64,
+            ScalarValue::Int64(Some(bins)) => *bins,
+            ScalarValue::UInt8(Some(bins)) => *bins as i64,
+            ScalarValue::UInt16(Some(bins)) => *bins as i64,
+            ScalarValue::UInt32(Some(bins)) => *bins as i64,
+            ScalarValue::UInt64(Some(bins)) => *bins as i64,
+            got => {
+                return Err(DataFusionError::NotImplemented(format!(
+                    "bins value for 'HISTOGRAM' must be an integer literal (got data type {})",
+                    got
+                )))
+            }
+        };
+        if bins <= 0 {
+            return Err(DataFusionError::Plan(format!(
+                "bins value for 'HISTOGRAM' must be greater than 0, {} is invalid",
+                bins
+            )));
+        }
+
+        Ok(Self {
+            name: name.into(),
+            expr,
+            bins: bins as usize,
+        })
+    }
+}
+
+impl AggregateExpr for Histogram {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, histogram_return_type(), false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        let list_field = |name: &str, data_type: DataType| {
+            Field::new(
+                &format_state_name(&self.name, name),
+                DataType::List(Box::new(Field::new("item", data_type, true))),
+                false,
+            )
+        };
+        Ok(vec![
+            list_field("means", DataType::Float64),
+            list_field("counts", DataType::UInt64),
+            list_field("lowers", DataType::Float64),
+            list_field("uppers", DataType::Float64),
+        ])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        self.expr.clone()
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(HistogramAccumulator::new(self.bins)))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A bin of the histogram
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bin {
+    /// mean of the values of the bin
+    mean: f64,
+    /// number of values of the bin
+    count: u64,
+    /// minimum value of the bin
+    lower: f64,
+    /// maximum value of the bin
+    upper: f64,
+}
+
+impl Bin {
+    fn merge(&mut self, other: &Bin) {
+        let count = self.count + other.count;
+        self.mean = (self.mean * self.count as f64 + other.mean * other.count as f64)
+            / count as f64;
+        self.count = count;
+        self.lower = self.lower.min(other.lower);
+        self.upper = self.upper.max(other.upper);
+    }
+}
+
+#[derive(Debug)]
+struct HistogramAccumulator {
+    max_bins: usize,
+    /// the bins, ordered by mean
+    bins: Vec<Bin>,
+}
+
+impl HistogramAccumulator {
+    fn new(max_bins: usize) -> Self {
+        Self {
+            max_bins,
+            bins: vec![],
+        }
+    }
+
+    /// Adds `bins` to the histogram, merging the closest bins until there
+    /// are at most `max_bins` of them
+    fn add_bins(&mut self, mut bins: Vec<Bin>) {
+        bins.append(&mut self.bins);
+        bins.sort_by(|a, b| a.mean.partial_cmp(&b.mean).unwrap_or(Ordering::Equal));
+        self.bins = compress(bins, self.max_bins);
+    }
+}
+
+/// Merges the pairs of adjacent bins with the closest means until there are
+/// at most `max_bins` bins left
+fn compress(mut bins: Vec<Bin>, max_bins: usize) -> Vec<Bin> {
+    let len = bins.len();
+    if len <= max_bins {
+        return bins;
+    }
+
+    // doubly linked list of the remaining bins, where `len` is the end
+    let mut next = (1..=len).collect::<Vec<_>>();
+    let mut prev = (0..len).map(|i| i.checked_sub(1)).collect::<Vec<_>>();
+    let mut alive = vec![true; len];
+    // incremented when a bin absorbs its next bin, so that gaps computed with
+    // its previous mean are ignored
+    let mut versions = vec![0_u32; len];
+
+    // bins are ordered by mean, so gaps are positive and their bit patterns
+    // are ordered like their values
+    let gap = |bins: &[Bin], i: usize, j: usize| (bins[j].mean - bins[i].mean).to_bits();
+    let mut gaps = BinaryHeap::with_capacity(len);
+    for i in 0..len - 1 {
+        gaps.push(Reverse((gap(&bins, i, i + 1), i, 0_u32, 0_u32)));
+    }
+
+    let mut remaining = len;
+    while remaining > max_bins {
+        let Reverse((_, i, version_i, version_j)) = match gaps.pop() {
+            Some(gap) => gap,
+            None => break,
+        };
+        let j = next[i];
+        if !alive[i] || versions[i] != version_i || j == len || versions[j] != version_j {
+            continue;
+        }
+
+        let absorbed = bins[j];
+        bins[i].merge(&absorbed);
+        alive[j] = false;
+        versions[i] += 1;
+        next[i] = next[j];
+        if next[i] != len {
+            prev[next[i]] = Some(i);
+        }
+        remaining -= 1;
+
+        if next[i] != len {
+            let k = next[i];
+            gaps.push(Reverse((gap(&bins, i, k), i, versions[i], versions[k])));
+        }
+        if let Some(h) = prev[i] {
+            gaps.push(Reverse((gap(&bins, h, i), h, versions[h], versions[i])));
+        }
+    }
+
+    bins.into_iter()
+        .zip(alive)
+        .filter(|(_, alive)| *alive)
+        .map(|(bin, _)| bin)
+        .collect()
+}
+
+/// Extracts the values of a list state of type `T`
+macro_rules! list_state {
+    ($STATE:expr, $INDEX:expr, $SCALAR:ident) => {{
+        match ScalarValue::try_from_array($STATE, $INDEX)? {
+            ScalarValue::List(Some(values), _) => values
+                .into_iter()
+                .map(|value| match value {
+                    ScalarValue::$SCALAR(Some(value)) => Ok(value),
+                    other => Err(DataFusionError::Internal(format!(
+                        "Unexpected histogram state value {:?}",
+                        other
+                    ))),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            other => {
+                return Err(DataFusionError::Internal(format!(
+                    "Unexpected histogram state {:?}",
+                    other
+                )))
+            }
+        }
+    }};
+}
+
+impl Accumulator for HistogramAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        // values[1] is the bins literal
+        let array = values[0]
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "histogram expects Float64 input values".to_string(),
+                )
+            })?;
+
+        let mut batch_values = array
+            .iter()
+            .flatten()
+            .filter(|value| !value.is_nan())
+            .collect::<Vec<_>>();
+        batch_values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        // one bin per distinct value
+        let mut bins: Vec<Bin> = vec![];
+        for value in batch_values {
+            match bins.last_mut() {
+                Some(bin) if bin.mean == value => bin.count += 1,
+                _ => bins.push(Bin {
+                    mean: value,
+                    count: 1,
+                    lower: value,
+                    upper: value,
+                }),
+            }
+        }
+        self.add_bins(bins);
+        Ok(())
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        for index in 0..states[0].len() {
+            let means = list_state!(&states[0], index, Float64);
+            let counts = list_state!(&states[1], index, UInt64);
+            let lowers = list_state!(&states[2], index, Float64);
+            let uppers = list_state!(&states[3], index, Float64);
+            let bins = means
+                .into_iter()
+                .zip(counts)
+                .zip(lowers.into_iter().zip(uppers))
+                .map(|((mean, count), (lower, upper))| Bin {
+                    mean,
+                    count,
+                    lower,
+                    upper,
+                })
+                .collect();
+            self.add_bins(bins);
+        }
+        Ok(())
+    }
+
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        let list = |values: Vec<ScalarValue>, data_type: DataType| {
+            ScalarValue::List(Some(Box::new(values)), Box::new(data_type))
+        };
+        let float_list = |f: fn(&Bin) -> f64| {
+            list(
+                self.bins
+                    .iter()
+                    .map(|bin| ScalarValue::from(f(bin)))
+                    .collect(),
+                DataType::Float64,
+            )
+        };
+        Ok(vec![
+            float_list(|bin| bin.mean),
+            list(
+                self.bins
+                    .iter()
+                    .map(|bin| ScalarValue::UInt64(Some(bin.count)))
+                    .collect(),
+                DataType::UInt64,
+            ),
+            float_list(|bin| bin.lower),
+            float_list(|bin| bin.upper),
+        ])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        let fields = match histogram_return_type() {
+            DataType::List(field) => match field.data_type() {
+                DataType::Struct(fields) => fields.clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let items = self
+            .bins
+            .iter()
+            .map(|bin| {
+                ScalarValue::Struct(
+                    Some(Box::new(vec![
+                        ScalarValue::from(bin.lower),
+                        ScalarValue::from(bin.upper),
+                        ScalarValue::UInt64(Some(bin.count)),
+                    ])),
+                    Box::new(fields.clone()),
+                )
+            })
+            .collect::<Vec<_>>();
+        Ok(ScalarValue::List(
+            Some(Box::new(items)),
+            Box::new(DataType::Struct(fields)),
+        ))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.bins.capacity() * std::mem::size_of::<Bin>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds_and_counts(acc: &HistogramAccumulator) -> Vec<(f64, f64, u64)> {
+        acc.bins
+            .iter()
+            .map(|bin| (bin.lower, bin.upper, bin.count))
+            .collect()
+    }
+
+    #[test]
+    fn histogram_compress() -> Result<()> {
+        let mut acc = HistogramAccumulator::new(2);
+        let values: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(1.0),
+            Some(2.0),
+            None,
+            Some(2.0),
+            Some(10.0),
+            Some(11.0),
+        ]));
+        acc.update_batch(&[values])?;
+        assert_eq!(
+            bounds_and_counts(&acc),
+            vec![(1.0, 2.0, 3), (10.0, 11.0, 2)]
+        );
+        assert!((acc.bins[0].mean - 5.0 / 3.0).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn histogram_merge() -> Result<()> {
+        let mut acc1 = HistogramAccumulator::new(3);
+        acc1.update_batch(&[Arc::new(Float64Array::from(vec![1.0, 2.0, 100.0]))])?;
+
+        let mut acc2 = HistogramAccumulator::new(3);
+        acc2.update_batch(&[Arc::new(Float64Array::from(vec![50.0, 51.0, 101.0]))])?;
+
+        let states = acc2
+            .state()?
+            .iter()
+            .map(|s| s.to_array())
+            .collect::<Vec<_>>();
+        acc1.merge_batch(&states)?;
+        assert_eq!(
+            bounds_and_counts(&acc1),
+            vec![(1.0, 2.0, 2), (50.0, 51.0, 2), (100.0, 101.0, 2)]
+        );
+
+        let result = acc1.evaluate()?;
+        assert_eq!(result.get_datatype(), histogram_return_type());
+        Ok(())
+    }
+}
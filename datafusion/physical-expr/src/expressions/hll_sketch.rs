@@ -0,0 +1,333 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Aggregates exporting and combining the HyperLogLog sketches used by `approx_distinct`
+
+use super::approx_distinct::hll_accumulator;
+use super::format_state_name;
+use crate::{hyperloglog::HyperLogLog, AggregateExpr, PhysicalExpr};
+use arrow::array::{ArrayRef, BinaryArray};
+use arrow::datatypes::{DataType, Field};
+use datafusion_common::ScalarValue;
+use datafusion_common::{DataFusionError, Result};
+use datafusion_expr::Accumulator;
+use std::any::Any;
+use std::sync::Arc;
+
+/// HLL_SKETCH aggregate expression: the HyperLogLog registers of the distinct
+/// input values, as a binary value that can be combined with [`HllUnion`] or
+/// [`HllCount`].
+#[derive(Debug)]
+pub struct HllSketch {
+    name: String,
+    input_data_type: DataType,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl HllSketch {
+    /// Create a new HllSketch aggregate function.
+    pub fn new(
+        expr: Arc<dyn PhysicalExpr>,
+        name: impl Into<String>,
+        input_data_type: DataType,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            input_data_type,
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for HllSketch {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, DataType::Binary, false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "hll_registers"),
+            DataType::Binary,
+            false,
+        )])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(HllSketchAccumulator {
+            inner: hll_accumulator(&self.input_data_type, "hll_sketch")?,
+        }))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Wraps an `approx_distinct` accumulator to evaluate to its registers
+/// instead of its count
+#[derive(Debug)]
+struct HllSketchAccumulator {
+    inner: Box<dyn Accumulator>,
+}
+
+impl Accumulator for HllSketchAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        self.inner.state()
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        self.inner.update_batch(values)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.inner.merge_batch(states)
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        // the state is the registers
+        Ok(self.inner.state()?.remove(0))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.inner.size()
+    }
+}
+
+/// HLL_UNION aggregate expression: the union of binary sketches produced by
+/// [`HllSketch`]. Null sketches are ignored.
+#[derive(Debug)]
+pub struct HllUnion {
+    name: String,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl HllUnion {
+    /// Create a new HllUnion aggregate function.
+    pub fn new(expr: Arc<dyn PhysicalExpr>, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for HllUnion {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, DataType::Binary, false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "hll_registers"),
+            DataType::Binary,
+            false,
+        )])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(HllUnionAccumulator::new("hll_union", false)))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// HLL_COUNT aggregate expression: the approximate number of distinct values
+/// of the union of binary sketches produced by [`HllSketch`]. Null sketches
+/// are ignored.
+#[derive(Debug)]
+pub struct HllCount {
+    name: String,
+    expr: Arc<dyn PhysicalExpr>,
+}
+
+impl HllCount {
+    /// Create a new HllCount aggregate function.
+    pub fn new(expr: Arc<dyn PhysicalExpr>, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            expr,
+        }
+    }
+}
+
+impl AggregateExpr for HllCount {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn field(&self) -> Result<Field> {
+        Ok(Field::new(&self.name, DataType::UInt64, false))
+    }
+
+    fn state_fields(&self) -> Result<Vec<Field>> {
+        Ok(vec![Field::new(
+            &format_state_name(&self.name, "hll_registers"),
+            DataType::Binary,
+            false,
+        )])
+    }
+
+    fn expressions(&self) -> Vec<Arc<dyn PhysicalExpr>> {
+        vec![self.expr.clone()]
+    }
+
+    fn create_accumulator(&self) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(HllUnionAccumulator::new("hll_count", true)))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Merges binary sketches, evaluating to either the merged registers or
+/// their count
+#[derive(Debug)]
+struct HllUnionAccumulator {
+    hll: HyperLogLog<Vec<u8>>,
+    /// true to evaluate to the count instead of the registers
+    count: bool,
+    /// only used in error messages
+    fn_name: &'static str,
+}
+
+impl HllUnionAccumulator {
+    fn new(fn_name: &'static str, count: bool) -> Self {
+        Self {
+            hll: HyperLogLog::new(),
+            count,
+            fn_name,
+        }
+    }
+
+    fn merge_sketches(&mut self, sketches: &ArrayRef) -> Result<()> {
+        let sketches =
+            sketches
+                .as_any()
+                .downcast_ref::<BinaryArray>()
+                .ok_or_else(|| {
+                    DataFusionError::Internal(format!(
+                        "{} expects a binary array of sketches",
+                        self.fn_name
+                    ))
+                })?;
+        // null sketches are skipped
+        for sketch in sketches.iter().flatten() {
+            let other = HyperLogLog::try_new_with_registers(sketch).ok_or_else(|| {
+                DataFusionError::Execution(format!(
+                    "{} got an invalid HyperLogLog sketch of {} bytes, \
+                         sketches must be produced by hll_sketch",
+                    self.fn_name,
+                    sketch.len()
+                ))
+            })?;
+            self.hll.merge(&other);
+        }
+        Ok(())
+    }
+}
+
+impl Accumulator for HllUnionAccumulator {
+    fn state(&self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::from(&self.hll)])
+    }
+
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        self.merge_sketches(&values[0])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.merge_sketches(&states[0])
+    }
+
+    fn evaluate(&self) -> Result<ScalarValue> {
+        if self.count {
+            Ok(ScalarValue::UInt64(Some(self.hll.count() as u64)))
+        } else {
+            Ok(ScalarValue::from(&self.hll))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::Int64Array;
+
+    #[test]
+    fn union_of_sketches() -> Result<()> {
+        let sketch = |values: Vec<i64>| -> Result<ArrayRef> {
+            let mut acc = HllSketch::new(
+                Arc::new(crate::expressions::Column::new("a", 0)),
+                "sketch",
+                DataType::Int64,
+            )
+            .create_accumulator()?;
+            acc.update_batch(&[Arc::new(Int64Array::from(values))])?;
+            Ok(acc.evaluate()?.to_array())
+        };
+
+        let mut count = HllUnionAccumulator::new("hll_count", true);
+        count.update_batch(&[sketch(vec![1, 2, 3])?])?;
+        count.update_batch(&[sketch(vec![3, 4])?])?;
+        assert_eq!(count.evaluate()?, ScalarValue::UInt64(Some(4)));
+
+        // sketches of unions can be unioned again
+        let mut union = HllUnionAccumulator::new("hll_union", false);
+        union.merge_batch(&[count.state()?[0].to_array()])?;
+        union.update_batch(&[sketch(vec![5])?])?;
+        let mut count = HllUnionAccumulator::new("hll_count", true);
+        count.update_batch(&[union.evaluate()?.to_array()])?;
+        assert_eq!(count.evaluate()?, ScalarValue::UInt64(Some(5)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_sketch() {
+        let mut union = HllUnionAccumulator::new("hll_union", false);
+        let err = union
+            .update_batch(&[Arc::new(BinaryArray::from(vec![&b"abc"[..]]))])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Execution error: hll_union got an invalid HyperLogLog sketch of 3 bytes, \
+             sketches must be produced by hll_sketch"
+        );
+    }
+}
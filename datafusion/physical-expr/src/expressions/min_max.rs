@@ -26,10 +26,12 @@ use arrow::compute;
 use arrow::datatypes::{DataType, TimeUnit};
 use arrow::{
     array::{
-        ArrayRef, Date32Array, Date64Array, Float32Array, Float64Array, Int16Array,
-        Int32Array, Int64Array, Int8Array, LargeStringArray, StringArray,
-        TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
-        TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+        ArrayRef, BooleanArray, Date32Array, Date64Array, DurationMicrosecondArray,
+        DurationMillisecondArray, DurationNanosecondArray, DurationSecondArray,
+        Float32Array, Float64Array, Int16Array, Int32Array, Int64Array, Int8Array,
+        LargeStringArray, StringArray, TimestampMicrosecondArray,
+        TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray,
+        UInt16Array, UInt32Array, UInt64Array, UInt8Array,
     },
     datatypes::Field,
 };
@@ -37,9 +39,9 @@ use datafusion_common::ScalarValue;
 use datafusion_common::{DataFusionError, Result};
 use datafusion_expr::Accumulator;
 
-use super::format_state_name;
-use arrow::array::Array;
+use super::{append_scalar, format_state_name, row_state_values, scalar_builder};
 use arrow::array::DecimalArray;
+use arrow::array::{Array, ArrayBuilder};
 
 // Min/max aggregation can take Dictionary encode input but always produces unpacked
 // (aka non Dictionary) output. We need to adjust the output data type to reflect this.
@@ -108,6 +110,10 @@ impl AggregateExpr for Max {
         Ok(Box::new(MaxAccumulator::try_new(&self.data_type)?))
     }
 
+    fn row_states(&self, values: &[ArrayRef]) -> Result<Option<Vec<ArrayRef>>> {
+        Ok(row_state_values(&values[0], &self.data_type)?.map(|values| vec![values]))
+    }
+
     fn name(&self) -> &str {
         &self.name
     }
@@ -228,6 +234,27 @@ macro_rules! min_max_batch {
             ),
             DataType::Date32 => typed_min_max_batch!($VALUES, Date32Array, Date32, $OP),
             DataType::Date64 => typed_min_max_batch!($VALUES, Date64Array, Date64, $OP),
+            DataType::Duration(TimeUnit::Second) => {
+                typed_min_max_batch!($VALUES, DurationSecondArray, DurationSecond, $OP)
+            }
+            DataType::Duration(TimeUnit::Millisecond) => typed_min_max_batch!(
+                $VALUES,
+                DurationMillisecondArray,
+                DurationMillisecond,
+                $OP
+            ),
+            DataType::Duration(TimeUnit::Microsecond) => typed_min_max_batch!(
+                $VALUES,
+                DurationMicrosecondArray,
+                DurationMicrosecond,
+                $OP
+            ),
+            DataType::Duration(TimeUnit::Nanosecond) => typed_min_max_batch!(
+                $VALUES,
+                DurationNanosecondArray,
+                DurationNanosecond,
+                $OP
+            ),
             other => {
                 // This should have been handled before
                 return Err(DataFusionError::Internal(format!(
@@ -248,6 +275,10 @@ fn min_batch(values: &ArrayRef) -> Result<ScalarValue> {
         DataType::LargeUtf8 => {
             typed_min_max_batch_string!(values, LargeStringArray, LargeUtf8, min_string)
         }
+        // evaluated on the bitmap of the values, 64 values at a time
+        DataType::Boolean => {
+            typed_min_max_batch!(values, BooleanArray, Boolean, min_boolean)
+        }
         _ => min_max_batch!(values, min),
     })
 }
@@ -261,6 +292,9 @@ fn max_batch(values: &ArrayRef) -> Result<ScalarValue> {
         DataType::LargeUtf8 => {
             typed_min_max_batch_string!(values, LargeStringArray, LargeUtf8, max_string)
         }
+        DataType::Boolean => {
+            typed_min_max_batch!(values, BooleanArray, Boolean, max_boolean)
+        }
         _ => min_max_batch!(values, max),
     })
 }
@@ -359,6 +393,9 @@ macro_rules! min_max {
             (ScalarValue::Int8(lhs), ScalarValue::Int8(rhs)) => {
                 typed_min_max!(lhs, rhs, Int8, $OP)
             }
+            (ScalarValue::Boolean(lhs), ScalarValue::Boolean(rhs)) => {
+                typed_min_max!(lhs, rhs, Boolean, $OP)
+            }
             (ScalarValue::Utf8(lhs), ScalarValue::Utf8(rhs)) => {
                 typed_min_max_string!(lhs, rhs, Utf8, $OP)
             }
@@ -398,6 +435,30 @@ macro_rules! min_max {
             ) => {
                 typed_min_max!(lhs, rhs, Date64, $OP)
             }
+            (
+                ScalarValue::DurationSecond(lhs),
+                ScalarValue::DurationSecond(rhs),
+            ) => {
+                typed_min_max!(lhs, rhs, DurationSecond, $OP)
+            }
+            (
+                ScalarValue::DurationMillisecond(lhs),
+                ScalarValue::DurationMillisecond(rhs),
+            ) => {
+                typed_min_max!(lhs, rhs, DurationMillisecond, $OP)
+            }
+            (
+                ScalarValue::DurationMicrosecond(lhs),
+                ScalarValue::DurationMicrosecond(rhs),
+            ) => {
+                typed_min_max!(lhs, rhs, DurationMicrosecond, $OP)
+            }
+            (
+                ScalarValue::DurationNanosecond(lhs),
+                ScalarValue::DurationNanosecond(rhs),
+            ) => {
+                typed_min_max!(lhs, rhs, DurationNanosecond, $OP)
+            }
             e => {
                 return Err(DataFusionError::Internal(format!(
                     "MIN/MAX is not expected to receive scalars of incompatible types {:?}",
@@ -449,9 +510,21 @@ impl Accumulator for MaxAccumulator {
         Ok(vec![self.max.clone()])
     }
 
+    fn state_builders(&self, capacity: usize) -> Option<Vec<Box<dyn ArrayBuilder>>> {
+        scalar_builder(&self.max.get_datatype(), capacity).map(|builder| vec![builder])
+    }
+
+    fn append_state(&self, builders: &mut [Box<dyn ArrayBuilder>]) -> Result<()> {
+        append_scalar(builders[0].as_mut(), &self.max)
+    }
+
     fn evaluate(&self) -> Result<ScalarValue> {
         Ok(self.max.clone())
     }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.max) + self.max.size()
+    }
 }
 
 /// MIN aggregate expression
@@ -497,6 +570,10 @@ impl AggregateExpr for Min {
         Ok(Box::new(MinAccumulator::try_new(&self.data_type)?))
     }
 
+    fn row_states(&self, values: &[ArrayRef]) -> Result<Option<Vec<ArrayRef>>> {
+        Ok(row_state_values(&values[0], &self.data_type)?.map(|values| vec![values]))
+    }
+
     fn state_fields(&self) -> Result<Vec<Field>> {
         Ok(vec![Field::new(
             &format_state_name(&self.name, "min"),
@@ -534,6 +611,14 @@ impl Accumulator for MinAccumulator {
         Ok(vec![self.min.clone()])
     }
 
+    fn state_builders(&self, capacity: usize) -> Option<Vec<Box<dyn ArrayBuilder>>> {
+        scalar_builder(&self.min.get_datatype(), capacity).map(|builder| vec![builder])
+    }
+
+    fn append_state(&self, builders: &mut [Box<dyn ArrayBuilder>]) -> Result<()> {
+        append_scalar(builders[0].as_mut(), &self.min)
+    }
+
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let values = &values[0];
         let delta = &min_batch(values)?;
@@ -548,6 +633,10 @@ impl Accumulator for MinAccumulator {
     fn evaluate(&self) -> Result<ScalarValue> {
         Ok(self.min.clone())
     }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) - std::mem::size_of_val(&self.min) + self.min.size()
+    }
 }
 
 #[cfg(test)]
@@ -754,6 +843,45 @@ mod tests {
         )
     }
 
+    #[test]
+    fn max_boolean() -> Result<()> {
+        let a: ArrayRef = Arc::new(BooleanArray::from(vec![
+            Some(false),
+            None,
+            Some(true),
+            Some(false),
+        ]));
+        generic_test_op!(
+            a,
+            DataType::Boolean,
+            Max,
+            ScalarValue::from(true),
+            DataType::Boolean
+        )
+    }
+
+    #[test]
+    fn min_boolean() -> Result<()> {
+        let a: ArrayRef = Arc::new(BooleanArray::from(vec![
+            Some(true),
+            None,
+            Some(false),
+            Some(true),
+        ]));
+        generic_test_op!(
+            a,
+            DataType::Boolean,
+            Min,
+            ScalarValue::from(false),
+            DataType::Boolean
+        )?;
+
+        // the minimum of two booleans
+        let result = min(&ScalarValue::from(true), &ScalarValue::Boolean(None))?;
+        assert_eq!(result, ScalarValue::from(true));
+        Ok(())
+    }
+
     #[test]
     fn max_utf8() -> Result<()> {
         let a: ArrayRef = Arc::new(StringArray::from(vec!["d", "a", "c", "b"]));
@@ -987,4 +1115,30 @@ mod tests {
             DataType::Date64
         )
     }
+
+    #[test]
+    fn min_duration() -> Result<()> {
+        let a: ArrayRef =
+            Arc::new(DurationNanosecondArray::from(vec![Some(3), None, Some(-2)]));
+        generic_test_op!(
+            a,
+            DataType::Duration(TimeUnit::Nanosecond),
+            Min,
+            ScalarValue::DurationNanosecond(Some(-2)),
+            DataType::Duration(TimeUnit::Nanosecond)
+        )
+    }
+
+    #[test]
+    fn max_duration() -> Result<()> {
+        let a: ArrayRef =
+            Arc::new(DurationNanosecondArray::from(vec![Some(3), None, Some(-2)]));
+        generic_test_op!(
+            a,
+            DataType::Duration(TimeUnit::Nanosecond),
+            Max,
+            ScalarValue::DurationNanosecond(Some(3)),
+            DataType::Duration(TimeUnit::Nanosecond)
+        )
+    }
 }
@@ -17,9 +17,20 @@
 
 //! Defines physical expressions that can evaluated at runtime during query execution
 
+use arrow::array::{
+    make_builder, ArrayBuilder, ArrayRef, BooleanBuilder, Date32Builder, Date64Builder,
+    Float32Builder, Float64Builder, Int16Builder, Int32Builder, Int64Builder,
+    Int8Builder, StringBuilder, UInt16Builder, UInt32Builder, UInt64Builder,
+    UInt8Builder,
+};
+use arrow::compute::{can_cast_types, cast};
+use arrow::datatypes::DataType;
+use datafusion_common::{DataFusionError, Result, ScalarValue};
+
 mod approx_distinct;
 mod approx_percentile_cont;
 mod approx_percentile_cont_with_weight;
+mod approx_top_k;
 mod array_agg;
 mod average;
 #[macro_use]
@@ -30,6 +41,8 @@ mod column;
 mod count;
 mod cume_dist;
 mod get_indexed_field;
+mod histogram;
+mod hll_sketch;
 mod in_list;
 mod is_not_null;
 mod is_null;
@@ -62,9 +75,10 @@ pub use approx_distinct::ApproxDistinct;
 pub use approx_median::ApproxMedian;
 pub use approx_percentile_cont::ApproxPercentileCont;
 pub use approx_percentile_cont_with_weight::ApproxPercentileContWithWeight;
+pub use approx_top_k::ApproxTopK;
 pub use array_agg::ArrayAgg;
 pub use average::{Avg, AvgAccumulator};
-pub use binary::{binary, BinaryExpr};
+pub use binary::{binary, binary_with_settings, BinaryExpr};
 pub use case::{case, CaseExpr};
 pub use cast::{
     cast, cast_column, cast_with_options, CastExpr, DEFAULT_DATAFUSION_CAST_OPTIONS,
@@ -77,6 +91,8 @@ pub use cume_dist::cume_dist;
 
 pub use distinct_expressions::{DistinctArrayAgg, DistinctCount};
 pub use get_indexed_field::GetIndexedFieldExpr;
+pub use histogram::Histogram;
+pub use hll_sketch::{HllCount, HllSketch, HllUnion};
 pub use in_list::{in_list, InListExpr};
 pub use is_not_null::{is_not_null, IsNotNullExpr};
 pub use is_null::{is_null, IsNullExpr};
@@ -100,11 +116,84 @@ pub use variance::{Variance, VariancePop};
 pub fn format_state_name(name: &str, state_name: &str) -> String {
     format!("{}[{}]", name, state_name)
 }
+
+/// Casts the values of the rows aggregated on their own to the type of their
+/// state, if supported, for [`AggregateExpr::row_states`](crate::AggregateExpr::row_states)
+pub(crate) fn row_state_values(
+    values: &ArrayRef,
+    data_type: &DataType,
+) -> Result<Option<ArrayRef>> {
+    if !can_cast_types(values.data_type(), data_type) {
+        return Ok(None);
+    }
+    Ok(Some(cast(values, data_type)?))
+}
 pub use crate::PhysicalSortExpr;
 
+/// Creates the builder of an array of states of type `data_type`, if the
+/// states of this type can be appended by [`append_scalar`], see
+/// [`Accumulator::state_builders`](datafusion_expr::Accumulator::state_builders)
+pub(crate) fn scalar_builder(
+    data_type: &DataType,
+    capacity: usize,
+) -> Option<Box<dyn ArrayBuilder>> {
+    use DataType::*;
+    match data_type {
+        Boolean | Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | UInt64
+        | Float32 | Float64 | Date32 | Date64 | Utf8 => {
+            Some(make_builder(data_type, capacity))
+        }
+        _ => None,
+    }
+}
+
+/// Appends the state `value` to `builder`, created by [`scalar_builder`] for
+/// the type of `value`
+pub(crate) fn append_scalar(
+    builder: &mut dyn ArrayBuilder,
+    value: &ScalarValue,
+) -> Result<()> {
+    macro_rules! append {
+        ($BUILDER:ident, $VALUE:expr) => {{
+            let typed = builder.as_any_mut().downcast_mut::<$BUILDER>();
+            let builder = typed.ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "Expected a {} to append the state {:?}",
+                    stringify!($BUILDER),
+                    value
+                ))
+            })?;
+            Ok(builder.append_option($VALUE)?)
+        }};
+    }
+    match value {
+        ScalarValue::Boolean(v) => append!(BooleanBuilder, *v),
+        ScalarValue::Int8(v) => append!(Int8Builder, *v),
+        ScalarValue::Int16(v) => append!(Int16Builder, *v),
+        ScalarValue::Int32(v) => append!(Int32Builder, *v),
+        ScalarValue::Int64(v) => append!(Int64Builder, *v),
+        ScalarValue::UInt8(v) => append!(UInt8Builder, *v),
+        ScalarValue::UInt16(v) => append!(UInt16Builder, *v),
+        ScalarValue::UInt32(v) => append!(UInt32Builder, *v),
+        ScalarValue::UInt64(v) => append!(UInt64Builder, *v),
+        ScalarValue::Float32(v) => append!(Float32Builder, *v),
+        ScalarValue::Float64(v) => append!(Float64Builder, *v),
+        ScalarValue::Date32(v) => append!(Date32Builder, *v),
+        ScalarValue::Date64(v) => append!(Date64Builder, *v),
+        ScalarValue::Utf8(v) => append!(StringBuilder, v.as_deref()),
+        _ => Err(DataFusionError::NotImplemented(format!(
+            "Appending the state {:?} to an array",
+            value
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{col, Avg, Count, Max, Min, Sum};
     use crate::AggregateExpr;
+    use arrow::array::{Array, ArrayRef, Int64Array, StringArray};
+    use arrow::datatypes::{DataType, Field, Schema};
     use arrow::record_batch::RecordBatch;
     use datafusion_common::Result;
     use datafusion_common::ScalarValue;
@@ -172,4 +261,95 @@ mod tests {
         accum.update_batch(&values)?;
         accum.evaluate()
     }
+
+    /// Builds the states of accumulators of `agg`, one per batch of `values`,
+    /// as arrays, checking that they are the arrays of their scalar states
+    fn assert_state_arrays(
+        agg: Arc<dyn AggregateExpr>,
+        values: Vec<ArrayRef>,
+    ) -> Result<Vec<ArrayRef>> {
+        let mut accumulators = vec![];
+        for value in values {
+            let mut accumulator = agg.create_accumulator()?;
+            accumulator.update_batch(&[value])?;
+            accumulators.push(accumulator);
+        }
+        let mut builders = accumulators[0]
+            .state_builders(accumulators.len())
+            .expect("the states can be built as arrays");
+        for accumulator in &accumulators {
+            accumulator.append_state(&mut builders)?;
+        }
+        let arrays = builders
+            .iter_mut()
+            .map(|builder| builder.finish())
+            .collect::<Vec<_>>();
+
+        let states = accumulators
+            .iter()
+            .map(|accumulator| accumulator.state())
+            .collect::<Result<Vec<_>>>()?;
+        for (i, array) in arrays.iter().enumerate() {
+            let expected =
+                ScalarValue::iter_to_array(states.iter().map(|state| state[i].clone()))?;
+            assert_eq!(array.as_ref(), expected.as_ref());
+        }
+        Ok(arrays)
+    }
+
+    #[test]
+    fn state_arrays() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int64, true)]);
+        let values = || -> Vec<ArrayRef> {
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), Some(2)])),
+                Arc::new(Int64Array::from(vec![None])),
+                Arc::new(Int64Array::from(vec![Some(3), None, Some(4)])),
+            ]
+        };
+        let expr = col("a", &schema)?;
+
+        let arrays = assert_state_arrays(
+            Arc::new(Sum::new(expr.clone(), "sum", DataType::Int64)),
+            values(),
+        )?;
+        assert_eq!(arrays[0].null_count(), 1);
+        let arrays = assert_state_arrays(
+            Arc::new(Count::new(expr.clone(), "count", DataType::UInt64)),
+            values(),
+        )?;
+        assert_eq!(arrays[0].len(), 3);
+        assert_state_arrays(
+            Arc::new(Min::new(expr.clone(), "min", DataType::Int64)),
+            values(),
+        )?;
+        assert_state_arrays(
+            Arc::new(Max::new(expr.clone(), "max", DataType::Int64)),
+            values(),
+        )?;
+        let arrays = assert_state_arrays(
+            Arc::new(Avg::new(expr, "avg", DataType::Float64)),
+            values(),
+        )?;
+        assert_eq!(arrays.len(), 2);
+
+        let schema = Schema::new(vec![Field::new("a", DataType::Utf8, true)]);
+        assert_state_arrays(
+            Arc::new(Max::new(col("a", &schema)?, "max", DataType::Utf8)),
+            vec![
+                Arc::new(StringArray::from(vec![Some("a"), Some("c")])),
+                Arc::new(StringArray::from(vec![None::<&str>])),
+            ],
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn state_arrays_unsupported() -> Result<()> {
+        // the states of other types are built from their scalar values
+        let schema = Schema::new(vec![Field::new("a", DataType::Decimal(10, 2), true)]);
+        let sum = Sum::new(col("a", &schema)?, "sum", DataType::Decimal(20, 2));
+        assert!(sum.create_accumulator()?.state_builders(1).is_none());
+        Ok(())
+    }
 }
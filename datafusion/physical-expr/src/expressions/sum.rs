@@ -23,20 +23,22 @@ use std::sync::Arc;
 
 use crate::{AggregateExpr, PhysicalExpr};
 use arrow::compute;
-use arrow::datatypes::DataType;
+use arrow::datatypes::{DataType, TimeUnit};
 use arrow::{
     array::{
-        ArrayRef, Float32Array, Float64Array, Int16Array, Int32Array, Int64Array,
-        Int8Array, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+        ArrayRef, DurationMicrosecondArray, DurationMillisecondArray,
+        DurationNanosecondArray, DurationSecondArray, Float32Array, Float64Array,
+        Int16Array, Int32Array, Int64Array, Int8Array, UInt16Array, UInt32Array,
+        UInt64Array, UInt8Array,
     },
     datatypes::Field,
 };
 use datafusion_common::{DataFusionError, Result, ScalarValue};
 use datafusion_expr::Accumulator;
 
-use super::format_state_name;
-use arrow::array::Array;
+use super::{append_scalar, format_state_name, row_state_values, scalar_builder};
 use arrow::array::DecimalArray;
+use arrow::array::{Array, ArrayBuilder};
 
 /// SUM aggregate expression
 #[derive(Debug)]
@@ -81,6 +83,10 @@ impl AggregateExpr for Sum {
         Ok(Box::new(SumAccumulator::try_new(&self.data_type)?))
     }
 
+    fn row_states(&self, values: &[ArrayRef]) -> Result<Option<Vec<ArrayRef>>> {
+        Ok(row_state_values(&values[0], &self.data_type)?.map(|sums| vec![sums]))
+    }
+
     fn state_fields(&self) -> Result<Vec<Field>> {
         Ok(vec![Field::new(
             &format_state_name(&self.name, "sum"),
@@ -159,6 +165,18 @@ pub(super) fn sum_batch(values: &ArrayRef) -> Result<ScalarValue> {
         DataType::UInt32 => typed_sum_delta_batch!(values, UInt32Array, UInt32),
         DataType::UInt16 => typed_sum_delta_batch!(values, UInt16Array, UInt16),
         DataType::UInt8 => typed_sum_delta_batch!(values, UInt8Array, UInt8),
+        DataType::Duration(TimeUnit::Second) => {
+            typed_sum_delta_batch!(values, DurationSecondArray, DurationSecond)
+        }
+        DataType::Duration(TimeUnit::Millisecond) => {
+            typed_sum_delta_batch!(values, DurationMillisecondArray, DurationMillisecond)
+        }
+        DataType::Duration(TimeUnit::Microsecond) => {
+            typed_sum_delta_batch!(values, DurationMicrosecondArray, DurationMicrosecond)
+        }
+        DataType::Duration(TimeUnit::Nanosecond) => {
+            typed_sum_delta_batch!(values, DurationNanosecondArray, DurationNanosecond)
+        }
         e => {
             return Err(DataFusionError::Internal(format!(
                 "Sum is not expected to receive the type {:?}",
@@ -297,6 +315,21 @@ pub(super) fn sum(lhs: &ScalarValue, rhs: &ScalarValue) -> Result<ScalarValue> {
         (ScalarValue::Int64(lhs), ScalarValue::Int8(rhs)) => {
             typed_sum!(lhs, rhs, Int64, i64)
         }
+        // durations are only summed with durations of the same unit
+        (ScalarValue::DurationSecond(lhs), ScalarValue::DurationSecond(rhs)) => {
+            typed_sum!(lhs, rhs, DurationSecond, i64)
+        }
+        (
+            ScalarValue::DurationMillisecond(lhs),
+            ScalarValue::DurationMillisecond(rhs),
+        ) => typed_sum!(lhs, rhs, DurationMillisecond, i64),
+        (
+            ScalarValue::DurationMicrosecond(lhs),
+            ScalarValue::DurationMicrosecond(rhs),
+        ) => typed_sum!(lhs, rhs, DurationMicrosecond, i64),
+        (ScalarValue::DurationNanosecond(lhs), ScalarValue::DurationNanosecond(rhs)) => {
+            typed_sum!(lhs, rhs, DurationNanosecond, i64)
+        }
         e => {
             return Err(DataFusionError::Internal(format!(
                 "Sum is not expected to receive a scalar {:?}",
@@ -311,6 +344,14 @@ impl Accumulator for SumAccumulator {
         Ok(vec![self.sum.clone()])
     }
 
+    fn state_builders(&self, capacity: usize) -> Option<Vec<Box<dyn ArrayBuilder>>> {
+        scalar_builder(&self.sum.get_datatype(), capacity).map(|builder| vec![builder])
+    }
+
+    fn append_state(&self, builders: &mut [Box<dyn ArrayBuilder>]) -> Result<()> {
+        append_scalar(builders[0].as_mut(), &self.sum)
+    }
+
     fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
         let values = &values[0];
         self.sum = sum(&self.sum, &sum_batch(values)?)?;
@@ -532,6 +573,23 @@ mod tests {
         )
     }
 
+    #[test]
+    fn sum_duration() -> Result<()> {
+        let a: ArrayRef = Arc::new(DurationMillisecondArray::from(vec![
+            Some(1000),
+            None,
+            Some(-250),
+            Some(4000),
+        ]));
+        generic_test_op!(
+            a,
+            DataType::Duration(TimeUnit::Millisecond),
+            Sum,
+            ScalarValue::DurationMillisecond(Some(4750)),
+            DataType::Duration(TimeUnit::Millisecond)
+        )
+    }
+
     fn aggregate(
         batch: &RecordBatch,
         agg: Arc<dyn AggregateExpr>,
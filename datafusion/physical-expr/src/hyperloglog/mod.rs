@@ -34,9 +34,9 @@
 //!
 //! This module also borrows some code structure from [pdatastructs.rs](https://github.com/crepererum/pdatastructs.rs/blob/3997ed50f6b6871c9e53c4c5e0f48f431405fc63/src/hyperloglog.rs).
 
-use ahash::{AHasher, RandomState};
-use std::hash::{BuildHasher, Hash, Hasher};
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use twox_hash::XxHash64;
 
 /// The greater is P, the smaller the error.
 const HLL_P: usize = 14_usize;
@@ -57,16 +57,15 @@ where
 
 /// Fixed seed for the hashing so that values are consistent across runs
 ///
-/// Note that when we later move on to have serialized HLL register binaries
-/// shared across cluster, this SEED will have to be consistent across all
-/// parties otherwise we might have corruption. So ideally for later this seed
-/// shall be part of the serialized form (or stay unchanged across versions).
-const SEED: RandomState = RandomState::with_seeds(
-    0x885f6cab121d01a3_u64,
-    0x71e4379f2976ad8f_u64,
-    0xbf30173dd28a8816_u64,
-    0x0eaea5d736d733a4_u64,
-);
+/// Note that the registers are exported by `hll_sketch` and may be stored and
+/// merged by later queries, so the SEED and the hash function must stay
+/// unchanged as long as [`FORMAT_VERSION`] is.
+const SEED: u64 = 0x885f6cab121d01a3_u64;
+
+/// Version of the serialized registers, their first byte. It must change
+/// whenever the registers of a value change, e.g. with the hash function, so
+/// that the sketches of other versions are rejected instead of being merged.
+const FORMAT_VERSION: u8 = 1;
 
 impl<T> HyperLogLog<T>
 where
@@ -88,12 +87,35 @@ where
         }
     }
 
-    /// choice of hash function: ahash is already an dependency
-    /// and it fits the requirements of being a 64bit hash with
-    /// reasonable performance.
+    /// Creates a HyperLogLog from registers serialized by
+    /// [`HyperLogLog::to_bytes`] of unknown origin, e.g. sketches provided by
+    /// users, returning `None` if they aren't valid registers of this version.
+    pub(crate) fn try_new_with_registers(bytes: &[u8]) -> Option<Self> {
+        let (version, registers) = bytes.split_first()?;
+        if *version != FORMAT_VERSION {
+            return None;
+        }
+        let registers: [u8; NUM_REGISTERS] = registers.try_into().ok()?;
+        if registers.iter().any(|r| *r as usize > HLL_Q + 1) {
+            return None;
+        }
+        Some(Self::new_with_registers(registers))
+    }
+
+    /// Serializes the registers, prefixed with the version of their format
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(NUM_REGISTERS + 1);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&self.registers);
+        bytes
+    }
+
+    /// choice of hash function: xxHash64 is a 64bit hash with reasonable
+    /// performance, whose output is specified and thus stable across
+    /// versions and platforms.
     #[inline]
     fn hash_value(&self, obj: &T) -> u64 {
-        let mut hasher: AHasher = SEED.build_hasher();
+        let mut hasher = StableHasher(XxHash64::with_seed(SEED));
         obj.hash(&mut hasher);
         hasher.finish()
     }
@@ -193,12 +215,38 @@ fn hll_tau(x: f64) -> f64 {
     }
 }
 
-impl<T> AsRef<[u8]> for HyperLogLog<T>
-where
-    T: Hash + ?Sized,
-{
-    fn as_ref(&self) -> &[u8] {
-        &self.registers
+/// Hasher writing the integers in little endian, and the `usize` ones as 64
+/// bit integers, e.g. the lengths of the hashed slices, so that the hashes
+/// are the same on all platforms
+struct StableHasher(XxHash64);
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes)
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes())
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as u64).to_le_bytes())
     }
 }
 
@@ -226,7 +274,8 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{HyperLogLog, NUM_REGISTERS};
+    use super::{HyperLogLog, FORMAT_VERSION, NUM_REGISTERS};
+    use std::hash::Hash;
 
     fn compare_with_delta(got: usize, expected: usize) {
         let expected = expected as f64;
@@ -351,6 +400,47 @@ mod tests {
         compare_with_delta(hll.count(), 1000);
     }
 
+    /// The non-zero registers of `hll`, by index
+    fn non_zero_registers<T: Hash + ?Sized>(hll: &HyperLogLog<T>) -> Vec<(usize, u8)> {
+        let bytes = hll.to_bytes();
+        assert_eq!(bytes[0], FORMAT_VERSION);
+        bytes[1..]
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| **r != 0)
+            .map(|(i, r)| (i, *r))
+            .collect()
+    }
+
+    #[test]
+    fn test_stable_hash() {
+        // the registers of the values must not change within a version
+        let mut hll = HyperLogLog::<str>::new();
+        hll.add("datafusion");
+        assert_eq!(non_zero_registers(&hll), vec![(14446, 1)]);
+
+        let mut hll = HyperLogLog::<u64>::new();
+        hll.add(&1000);
+        assert_eq!(non_zero_registers(&hll), vec![(13976, 4)]);
+    }
+
+    #[test]
+    fn test_serialized_registers() {
+        let mut hll = HyperLogLog::<u64>::new();
+        hll.extend(0..1000);
+        let bytes = hll.to_bytes();
+        assert_eq!(bytes.len(), NUM_REGISTERS + 1);
+        let other = HyperLogLog::<u64>::try_new_with_registers(&bytes).unwrap();
+        assert_eq!(other.count(), hll.count());
+
+        // other versions, and the registers without version, are rejected
+        let mut other_version = bytes.clone();
+        other_version[0] = FORMAT_VERSION + 1;
+        assert!(HyperLogLog::<u64>::try_new_with_registers(&other_version).is_none());
+        assert!(HyperLogLog::<u64>::try_new_with_registers(&bytes[1..]).is_none());
+        assert!(HyperLogLog::<u64>::try_new_with_registers(&[]).is_none());
+    }
+
     #[test]
     fn test_repetition() {
         let mut hll = HyperLogLog::<u32>::new();
@@ -17,7 +17,7 @@
 
 //! Math expressions
 
-use arrow::array::{Float32Array, Float64Array};
+use arrow::array::{Array, ArrayRef, Float32Array, Float64Array, Int64Array};
 use arrow::datatypes::DataType;
 use datafusion_common::ScalarValue;
 use datafusion_common::{DataFusionError, Result};
@@ -120,6 +120,96 @@ pub fn random(args: &[ColumnarValue]) -> Result<ColumnarValue> {
     Ok(ColumnarValue::Array(Arc::new(array)))
 }
 
+/// width_bucket SQL function: the number of the bucket `operand` falls in,
+/// out of `count` buckets of equal width spanning `low` to `high`.
+///
+/// Like in PostgreSQL, operands below the range are in bucket `0` and
+/// operands above it in bucket `count + 1`, and `high` may be lower than
+/// `low` to number the buckets in decreasing order.
+pub fn width_bucket(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let downcast_f64 = |array: &ArrayRef| {
+        array
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(
+                    "width_bucket expects Float64 operand and bounds".to_string(),
+                )
+            })
+    };
+    let operand = downcast_f64(&args[0])?;
+    let low = downcast_f64(&args[1])?;
+    let high = downcast_f64(&args[2])?;
+    let count = args[3]
+        .as_any()
+        .downcast_ref::<Int64Array>()
+        .ok_or_else(|| {
+            DataFusionError::Internal(
+                "width_bucket expects an Int64 bucket count".to_string(),
+            )
+        })?;
+
+    let result = (0..operand.len())
+        .map(|i| {
+            if operand.is_null(i) || low.is_null(i) || high.is_null(i) || count.is_null(i)
+            {
+                return Ok(None);
+            }
+            bucket_of(
+                operand.value(i),
+                low.value(i),
+                high.value(i),
+                count.value(i),
+            )
+            .map(Some)
+        })
+        .collect::<Result<Int64Array>>()?;
+    Ok(Arc::new(result))
+}
+
+fn bucket_of(operand: f64, low: f64, high: f64, count: i64) -> Result<i64> {
+    if count <= 0 {
+        return Err(DataFusionError::Execution(format!(
+            "width_bucket count must be greater than 0, got {}",
+            count
+        )));
+    }
+    if !low.is_finite() || !high.is_finite() || low == high {
+        return Err(DataFusionError::Execution(format!(
+            "width_bucket bounds must be finite and different, got {} and {}",
+            low, high
+        )));
+    }
+    if operand.is_nan() {
+        return Err(DataFusionError::Execution(
+            "width_bucket operand must not be NaN".to_string(),
+        ));
+    }
+
+    let bucket = if low < high {
+        if operand < low {
+            0
+        } else if operand >= high {
+            count + 1
+        } else {
+            in_range_bucket((operand - low) / (high - low), count)
+        }
+    } else if operand > low {
+        0
+    } else if operand <= high {
+        count + 1
+    } else {
+        in_range_bucket((low - operand) / (low - high), count)
+    };
+    Ok(bucket)
+}
+
+/// The bucket of an operand at `fraction` of the range, in `[0, 1)`
+fn in_range_bucket(fraction: f64, count: i64) -> i64 {
+    // guard against rounding up to the next bucket
+    ((fraction * count as f64) as i64 + 1).min(count)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -135,4 +225,44 @@ mod tests {
         assert_eq!(floats.len(), 1);
         assert!(0.0 <= floats.value(0) && floats.value(0) < 1.0);
     }
+
+    #[test]
+    fn test_width_bucket() -> Result<()> {
+        let operand: ArrayRef = Arc::new(Float64Array::from(vec![
+            Some(-1.0),
+            Some(0.0),
+            Some(2.5),
+            Some(9.99),
+            Some(10.0),
+            None,
+        ]));
+        let low: ArrayRef = Arc::new(Float64Array::from(vec![0.0; 6]));
+        let high: ArrayRef = Arc::new(Float64Array::from(vec![10.0; 6]));
+        let count: ArrayRef = Arc::new(Int64Array::from(vec![5; 6]));
+        let result = width_bucket(&[operand.clone(), low.clone(), high.clone(), count])?;
+        let expected =
+            Int64Array::from(vec![Some(0), Some(1), Some(2), Some(5), Some(6), None]);
+        assert_eq!(
+            result.as_any().downcast_ref::<Int64Array>().unwrap(),
+            &expected
+        );
+
+        // buckets are numbered in decreasing order when high < low
+        let count: ArrayRef = Arc::new(Int64Array::from(vec![5; 6]));
+        let result = width_bucket(&[operand.clone(), high, low.clone(), count])?;
+        let expected =
+            Int64Array::from(vec![Some(6), Some(6), Some(4), Some(1), Some(1), None]);
+        assert_eq!(
+            result.as_any().downcast_ref::<Int64Array>().unwrap(),
+            &expected
+        );
+
+        let zero: ArrayRef = Arc::new(Int64Array::from(vec![0; 6]));
+        let err = width_bucket(&[operand, low.clone(), low, zero]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Execution error: width_bucket count must be greater than 0, got 0"
+        );
+        Ok(())
+    }
 }
@@ -32,6 +32,8 @@ use hashbrown::HashMap;
 use std::any::type_name;
 use std::cmp::Ordering;
 use std::sync::Arc;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
 use unicode_segmentation::UnicodeSegmentation;
 
 macro_rules! downcast_string_arg {
@@ -209,6 +211,32 @@ pub fn lpad<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
     }
 }
 
+/// Returns the string in Unicode Normalization Form C (canonical composition).
+/// nfc('e\u{301}') = 'é'
+pub fn nfc<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_string_arg!(args[0], "string", T);
+
+    let result = string_array
+        .iter()
+        .map(|string| string.map(|string: &str| string.nfc().collect::<String>()))
+        .collect::<GenericStringArray<T>>();
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
+/// Returns the string in Unicode Normalization Form D (canonical decomposition).
+/// nfd('é') = 'e\u{301}'
+pub fn nfd<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_string_arg!(args[0], "string", T);
+
+    let result = string_array
+        .iter()
+        .map(|string| string.map(|string: &str| string.nfd().collect::<String>()))
+        .collect::<GenericStringArray<T>>();
+
+    Ok(Arc::new(result) as ArrayRef)
+}
+
 /// Reverses the order of the characters in the string.
 /// reverse('abcde') = 'edcba'
 pub fn reverse<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
@@ -538,3 +566,24 @@ pub fn translate<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef
 
     Ok(Arc::new(result) as ArrayRef)
 }
+
+/// Removes the accents (diacritical marks) from the string.
+/// unaccent('Crème Brûlée') = 'Creme Brulee'
+pub fn unaccent<T: StringOffsetSizeTrait>(args: &[ArrayRef]) -> Result<ArrayRef> {
+    let string_array = downcast_string_arg!(args[0], "string", T);
+
+    let result = string_array
+        .iter()
+        .map(|string| {
+            string.map(|string: &str| {
+                string
+                    .nfd()
+                    .filter(|c| !is_combining_mark(*c))
+                    .nfc()
+                    .collect::<String>()
+            })
+        })
+        .collect::<GenericStringArray<T>>();
+
+    Ok(Arc::new(result) as ArrayRef)
+}
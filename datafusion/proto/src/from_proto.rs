@@ -29,11 +29,12 @@ use datafusion::{
     error::DataFusionError,
     logical_expr::{BuiltInWindowFunction, BuiltinScalarFunction},
     logical_plan::{
-        abs, acos, ascii, asin, atan, ceil, character_length, chr, concat_expr,
-        concat_ws_expr, cos, digest, exp, floor, left, ln, log10, log2, now_expr, nullif,
-        random, regexp_replace, repeat, replace, reverse, right, round, signum, sin,
-        split_part, sqrt, starts_with, strpos, substr, tan, to_hex, to_timestamp_micros,
-        to_timestamp_millis, to_timestamp_seconds, translate, trunc,
+        abs, acos, array_contains, ascii, asin, atan, ceil, character_length, chr,
+        collate, concat_expr, concat_ws_expr, cos, digest, exp, floor, left, ln, log10,
+        log2, nfc, nfd, now_expr, nullif, random, regexp_replace, repeat, replace,
+        reverse, right, round, signum, sin, split_part, sqrt, starts_with, strpos,
+        substr, tan, to_hex, to_timestamp_micros, to_timestamp_millis,
+        to_timestamp_seconds, translate, trunc, unaccent, width_bucket,
         window_frames::{WindowFrame, WindowFrameBound, WindowFrameUnits},
         Column, DFField, DFSchema, DFSchemaRef, Expr, Operator,
     },
@@ -239,6 +240,8 @@ impl From<protobuf::PrimitiveScalarType> for DataType {
             protobuf::PrimitiveScalarType::IntervalYearmonth => {
                 DataType::Interval(IntervalUnit::YearMonth)
             }
+            protobuf::PrimitiveScalarType::Binary => DataType::Binary,
+            protobuf::PrimitiveScalarType::LargeBinary => DataType::LargeBinary,
             protobuf::PrimitiveScalarType::IntervalDaytime => {
                 DataType::Interval(IntervalUnit::DayTime)
             }
@@ -417,6 +420,7 @@ impl From<&protobuf::ScalarFunction> for BuiltinScalarFunction {
             ScalarFunction::Ceil => Self::Ceil,
             ScalarFunction::Round => Self::Round,
             ScalarFunction::Trunc => Self::Trunc,
+            ScalarFunction::WidthBucket => Self::WidthBucket,
             ScalarFunction::Abs => Self::Abs,
             ScalarFunction::OctetLength => Self::OctetLength,
             ScalarFunction::Concat => Self::Concat,
@@ -466,6 +470,11 @@ impl From<&protobuf::ScalarFunction> for BuiltinScalarFunction {
             ScalarFunction::Translate => Self::Translate,
             ScalarFunction::RegexpMatch => Self::RegexpMatch,
             ScalarFunction::Coalesce => Self::Coalesce,
+            ScalarFunction::Collate => Self::Collate,
+            ScalarFunction::Nfc => Self::Nfc,
+            ScalarFunction::Nfd => Self::Nfd,
+            ScalarFunction::Unaccent => Self::Unaccent,
+            ScalarFunction::ArrayContains => Self::ArrayContains,
         }
     }
 }
@@ -494,6 +503,11 @@ impl From<protobuf::AggregateFunction> for AggregateFunction {
                 Self::ApproxPercentileContWithWeight
             }
             protobuf::AggregateFunction::ApproxMedian => Self::ApproxMedian,
+            protobuf::AggregateFunction::HllSketch => Self::HllSketch,
+            protobuf::AggregateFunction::HllUnion => Self::HllUnion,
+            protobuf::AggregateFunction::HllCount => Self::HllCount,
+            protobuf::AggregateFunction::ApproxTopK => Self::ApproxTopK,
+            protobuf::AggregateFunction::Histogram => Self::Histogram,
         }
     }
 }
@@ -614,6 +628,8 @@ impl TryFrom<&protobuf::scalar_value::Value> for ScalarValue {
             Value::Date64Value(v) => ScalarValue::Date64(Some(*v)),
             Value::IntervalYearmonthValue(v) => ScalarValue::IntervalYearMonth(Some(*v)),
             Value::IntervalDaytimeValue(v) => ScalarValue::IntervalDayTime(Some(*v)),
+            Value::BinaryValue(v) => ScalarValue::Binary(Some(v.clone())),
+            Value::LargeBinaryValue(v) => ScalarValue::LargeBinary(Some(v.clone())),
             Value::TimestampValue(v) => {
                 let ts_value =
                     v.value.as_ref().ok_or_else(|| Error::required("value"))?;
@@ -784,6 +800,8 @@ impl TryFrom<&protobuf::PrimitiveScalarType> for ScalarValue {
             }
             PrimitiveScalarType::IntervalYearmonth => Self::IntervalYearMonth(None),
             PrimitiveScalarType::IntervalDaytime => Self::IntervalDayTime(None),
+            PrimitiveScalarType::Binary => Self::Binary(None),
+            PrimitiveScalarType::LargeBinary => Self::LargeBinary(None),
         })
     }
 }
@@ -879,6 +897,8 @@ impl TryFrom<&protobuf::ScalarValue> for ScalarValue {
             Value::Date64Value(v) => Self::Date64(Some(*v)),
             Value::IntervalYearmonthValue(v) => Self::IntervalYearMonth(Some(*v)),
             Value::IntervalDaytimeValue(v) => Self::IntervalDayTime(Some(*v)),
+            Value::BinaryValue(v) => Self::Binary(Some(v.clone())),
+            Value::LargeBinaryValue(v) => Self::LargeBinary(Some(v.clone())),
             Value::TimestampValue(v) => {
                 let timezone = if v.timezone.is_empty() {
                     None
@@ -1101,6 +1121,12 @@ pub fn parse_expr(
                 ScalarFunction::Ceil => Ok(ceil(parse_expr(&args[0], registry)?)),
                 ScalarFunction::Round => Ok(round(parse_expr(&args[0], registry)?)),
                 ScalarFunction::Trunc => Ok(trunc(parse_expr(&args[0], registry)?)),
+                ScalarFunction::WidthBucket => Ok(width_bucket(
+                    parse_expr(&args[0], registry)?,
+                    parse_expr(&args[1], registry)?,
+                    parse_expr(&args[2], registry)?,
+                    parse_expr(&args[3], registry)?,
+                )),
                 ScalarFunction::Abs => Ok(abs(parse_expr(&args[0], registry)?)),
                 ScalarFunction::Signum => Ok(signum(parse_expr(&args[0], registry)?)),
                 ScalarFunction::OctetLength => {
@@ -1243,6 +1269,17 @@ pub fn parse_expr(
                         .map(|expr| parse_expr(expr, registry))
                         .collect::<Result<Vec<_>, _>>()?,
                 )),
+                ScalarFunction::Collate => Ok(collate(
+                    parse_expr(&args[0], registry)?,
+                    parse_expr(&args[1], registry)?,
+                )),
+                ScalarFunction::Nfc => Ok(nfc(parse_expr(&args[0], registry)?)),
+                ScalarFunction::Nfd => Ok(nfd(parse_expr(&args[0], registry)?)),
+                ScalarFunction::Unaccent => Ok(unaccent(parse_expr(&args[0], registry)?)),
+                ScalarFunction::ArrayContains => Ok(array_contains(
+                    parse_expr(&args[0], registry)?,
+                    parse_expr(&args[1], registry)?,
+                )),
                 _ => Err(proto_error(
                     "Protobuf deserialization error: Unsupported scalar function",
                 )),
@@ -1503,6 +1540,8 @@ fn typechecked_scalar_value_conversion(
                     PrimitiveScalarType::IntervalDaytime => {
                         ScalarValue::IntervalDayTime(None)
                     }
+                    PrimitiveScalarType::Binary => ScalarValue::Binary(None),
+                    PrimitiveScalarType::LargeBinary => ScalarValue::LargeBinary(None),
                 };
                 scalar_value
             } else {
@@ -1526,6 +1565,12 @@ fn typechecked_scalar_value_conversion(
         (Value::IntervalDaytimeValue(v), PrimitiveScalarType::IntervalDaytime) => {
             ScalarValue::IntervalDayTime(Some(*v))
         }
+        (Value::BinaryValue(v), PrimitiveScalarType::Binary) => {
+            ScalarValue::Binary(Some(v.clone()))
+        }
+        (Value::LargeBinaryValue(v), PrimitiveScalarType::LargeBinary) => {
+            ScalarValue::LargeBinary(Some(v.clone()))
+        }
         _ => return Err(proto_error("Could not convert to the proper type")),
     })
 }
@@ -187,6 +187,10 @@ mod roundtrip_tests {
             ScalarValue::UInt64(Some(0)),
             ScalarValue::Utf8(Some(String::from("Test string   "))),
             ScalarValue::LargeUtf8(Some(String::from("Test Large utf8"))),
+            ScalarValue::Binary(None),
+            ScalarValue::Binary(Some(vec![0, 1, 255])),
+            ScalarValue::LargeBinary(None),
+            ScalarValue::LargeBinary(Some(b"Test large binary".to_vec())),
             ScalarValue::Date32(Some(0)),
             ScalarValue::Date32(Some(i32::MAX)),
             ScalarValue::TimestampNanosecond(Some(0), None),
@@ -277,6 +281,8 @@ mod roundtrip_tests {
             DataType::Time64(TimeUnit::Nanosecond),
             DataType::Utf8,
             DataType::LargeUtf8,
+            DataType::Binary,
+            DataType::LargeBinary,
             // Recursive list tests
             DataType::List(new_box_field("Level1", DataType::Boolean, true)),
             DataType::List(new_box_field(
@@ -304,11 +310,9 @@ mod roundtrip_tests {
             DataType::Duration(TimeUnit::Nanosecond),
             DataType::Interval(IntervalUnit::YearMonth),
             DataType::Interval(IntervalUnit::DayTime),
-            DataType::Binary,
             DataType::FixedSizeBinary(0),
             DataType::FixedSizeBinary(1234),
             DataType::FixedSizeBinary(-432),
-            DataType::LargeBinary,
             DataType::Decimal(1345, 5431),
             // Recursive list tests
             DataType::List(new_box_field("Level1", DataType::Binary, true)),
@@ -356,6 +356,11 @@ impl From<&AggregateFunction> for protobuf::AggregateFunction {
                 Self::ApproxPercentileContWithWeight
             }
             AggregateFunction::ApproxMedian => Self::ApproxMedian,
+            AggregateFunction::HllSketch => Self::HllSketch,
+            AggregateFunction::HllUnion => Self::HllUnion,
+            AggregateFunction::HllCount => Self::HllCount,
+            AggregateFunction::ApproxTopK => Self::ApproxTopK,
+            AggregateFunction::Histogram => Self::Histogram,
         }
     }
 }
@@ -541,6 +546,17 @@ impl TryFrom<&Expr> for protobuf::LogicalExprNode {
                     AggregateFunction::ApproxMedian => {
                         protobuf::AggregateFunction::ApproxMedian
                     }
+                    AggregateFunction::HllSketch => {
+                        protobuf::AggregateFunction::HllSketch
+                    }
+                    AggregateFunction::HllUnion => protobuf::AggregateFunction::HllUnion,
+                    AggregateFunction::HllCount => protobuf::AggregateFunction::HllCount,
+                    AggregateFunction::ApproxTopK => {
+                        protobuf::AggregateFunction::ApproxTopK
+                    }
+                    AggregateFunction::Histogram => {
+                        protobuf::AggregateFunction::Histogram
+                    }
                 };
 
                 let aggregate_expr = protobuf::AggregateExprNode {
@@ -791,6 +807,16 @@ impl TryFrom<&ScalarValue> for protobuf::ScalarValue {
                     Value::LargeUtf8Value(s.to_owned())
                 })
             }
+            scalar::ScalarValue::Binary(val) => {
+                create_proto_scalar(val, PrimitiveScalarType::Binary, |s| {
+                    Value::BinaryValue(s.to_owned())
+                })
+            }
+            scalar::ScalarValue::LargeBinary(val) => {
+                create_proto_scalar(val, PrimitiveScalarType::LargeBinary, |s| {
+                    Value::LargeBinaryValue(s.to_owned())
+                })
+            }
             scalar::ScalarValue::List(value, datatype) => {
                 println!("Current datatype of list: {:?}", datatype);
                 match value {
@@ -1020,6 +1046,7 @@ impl TryFrom<&BuiltinScalarFunction> for protobuf::ScalarFunction {
             BuiltinScalarFunction::Ceil => Self::Ceil,
             BuiltinScalarFunction::Round => Self::Round,
             BuiltinScalarFunction::Trunc => Self::Trunc,
+            BuiltinScalarFunction::WidthBucket => Self::WidthBucket,
             BuiltinScalarFunction::Abs => Self::Abs,
             BuiltinScalarFunction::OctetLength => Self::OctetLength,
             BuiltinScalarFunction::Concat => Self::Concat,
@@ -1069,6 +1096,11 @@ impl TryFrom<&BuiltinScalarFunction> for protobuf::ScalarFunction {
             BuiltinScalarFunction::Translate => Self::Translate,
             BuiltinScalarFunction::RegexpMatch => Self::RegexpMatch,
             BuiltinScalarFunction::Coalesce => Self::Coalesce,
+            BuiltinScalarFunction::Collate => Self::Collate,
+            BuiltinScalarFunction::Nfc => Self::Nfc,
+            BuiltinScalarFunction::Nfd => Self::Nfd,
+            BuiltinScalarFunction::Unaccent => Self::Unaccent,
+            BuiltinScalarFunction::ArrayContains => Self::ArrayContains,
         };
 
         Ok(scalar_function)
@@ -1118,6 +1150,10 @@ impl TryFrom<&DataType> for protobuf::scalar_type::Datatype {
             },
             DataType::Utf8 => Self::Scalar(PrimitiveScalarType::Utf8 as i32),
             DataType::LargeUtf8 => Self::Scalar(PrimitiveScalarType::LargeUtf8 as i32),
+            DataType::Binary => Self::Scalar(PrimitiveScalarType::Binary as i32),
+            DataType::LargeBinary => {
+                Self::Scalar(PrimitiveScalarType::LargeBinary as i32)
+            }
             DataType::List(field_type) => {
                 let mut field_names: Vec<String> = Vec::new();
                 let mut curr_field = field_type.as_ref();
@@ -1176,9 +1212,7 @@ impl TryFrom<&DataType> for protobuf::scalar_type::Datatype {
             | DataType::Time32(_)
             | DataType::Duration(_)
             | DataType::Interval(_)
-            | DataType::Binary
             | DataType::FixedSizeBinary(_)
-            | DataType::LargeBinary
             | DataType::FixedSizeList(_, _)
             | DataType::LargeList(_)
             | DataType::Struct(_)